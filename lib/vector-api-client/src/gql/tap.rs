@@ -55,6 +55,7 @@ impl From<TapEncodingFormat>
 
 pub trait TapSubscriptionExt {
     /// Executes an output events subscription.
+    #[allow(clippy::too_many_arguments)]
     fn output_events_by_component_id_patterns_subscription(
         &self,
         outputs_patterns: Vec<String>,
@@ -62,11 +63,14 @@ pub trait TapSubscriptionExt {
         encoding: TapEncodingFormat,
         limit: i64,
         interval: i64,
+        filter: Option<String>,
+        cursor: Option<String>,
     ) -> crate::BoxedSubscription<OutputEventsByComponentIdPatternsSubscription>;
 }
 
 impl TapSubscriptionExt for crate::SubscriptionClient {
     /// Executes an output events subscription.
+    #[allow(clippy::too_many_arguments)]
     fn output_events_by_component_id_patterns_subscription(
         &self,
         outputs_patterns: Vec<String>,
@@ -74,6 +78,8 @@ impl TapSubscriptionExt for crate::SubscriptionClient {
         encoding: TapEncodingFormat,
         limit: i64,
         interval: i64,
+        filter: Option<String>,
+        cursor: Option<String>,
     ) -> BoxedSubscription<OutputEventsByComponentIdPatternsSubscription> {
         let request_body = OutputEventsByComponentIdPatternsSubscription::build_query(
             output_events_by_component_id_patterns_subscription::Variables {
@@ -82,6 +88,8 @@ impl TapSubscriptionExt for crate::SubscriptionClient {
                 limit,
                 interval,
                 encoding: encoding.into(),
+                filter,
+                cursor,
             },
         );
 
@@ -4,6 +4,14 @@ use metrics::{register_counter, Counter};
 pub const INTENTIONAL: bool = true;
 pub const UNINTENTIONAL: bool = false;
 
+/// The `tracing` target that drop reports are emitted under.
+///
+/// A drop report is emitted alongside the regular "Events dropped" log every time events are
+/// dropped. Unlike that log, drop reports are never rate limited and always carry the same set of
+/// fields (`count`, `intentional` and `reason`), so they can be relied upon to account for every
+/// dropped event.
+pub const DROP_REPORT_TARGET: &str = "vector::drop_report";
+
 #[derive(Debug)]
 pub struct ComponentEventsDropped<'a, const INTENTIONAL: bool> {
     pub count: usize,
@@ -69,6 +77,13 @@ impl<'a, const INTENDED: bool> InternalEventHandle for DroppedHandle<'a, INTENDE
                 internal_log_rate_limit = true,
             );
         }
+        trace!(
+            target: DROP_REPORT_TARGET,
+            message = "Component events dropped.",
+            count = data.0,
+            intentional = INTENDED,
+            reason = self.reason,
+        );
         self.discarded_events.increment(data.0 as u64);
     }
 }
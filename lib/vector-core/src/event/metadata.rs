@@ -1,6 +1,7 @@
 #![deny(missing_docs)]
 
 use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
@@ -14,9 +15,12 @@ use crate::{schema, ByteSizeOf};
 const DATADOG_API_KEY: &str = "datadog_api_key";
 const SPLUNK_HEC_TOKEN: &str = "splunk_hec_token";
 
+/// The next identifier assigned to an event.
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
 /// The top-level metadata structure contained by both `struct Metric`
 /// and `struct LogEvent` types.
-#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct EventMetadata {
     /// Arbitrary data stored with an event
     #[serde(default = "default_metadata_value", skip)]
@@ -35,6 +39,22 @@ pub struct EventMetadata {
     /// TODO(Jean): must not skip serialization to track schemas across restarts.
     #[serde(default = "default_schema_definition", skip)]
     schema_definition: Arc<schema::Definition>,
+
+    /// An identifier of the event within this process, assigned when it is sent by a source while
+    /// it may be tapped, and kept by the versions of the event output by transforms.
+    #[serde(default, skip)]
+    id: Option<u64>,
+}
+
+impl PartialEq for EventMetadata {
+    fn eq(&self, other: &Self) -> bool {
+        // The identifier is left out, as events are otherwise equal regardless of when they were
+        // sent.
+        self.value == other.value
+            && self.secrets == other.secrets
+            && self.finalizers == other.finalizers
+            && self.schema_definition == other.schema_definition
+    }
 }
 
 fn default_metadata_value() -> Value {
@@ -89,6 +109,18 @@ impl EventMetadata {
     pub fn set_splunk_hec_token(&mut self, secret: Arc<str>) {
         self.secrets.insert(SPLUNK_HEC_TOKEN, secret);
     }
+
+    /// Returns the identifier of the event, if it has been assigned one
+    pub fn id(&self) -> Option<u64> {
+        self.id
+    }
+
+    /// Assigns an identifier to the event, unless it already has one
+    pub fn assign_id(&mut self) {
+        if self.id.is_none() {
+            self.id = Some(NEXT_ID.fetch_add(1, Ordering::Relaxed));
+        }
+    }
 }
 
 impl Default for EventMetadata {
@@ -98,6 +130,7 @@ impl Default for EventMetadata {
             secrets: Secrets::new(),
             finalizers: Default::default(),
             schema_definition: default_schema_definition(),
+            id: None,
         }
     }
 }
@@ -249,4 +282,19 @@ mod test {
         assert_eq!(metadata.datadog_api_key().unwrap().as_ref(), SECRET);
         assert_eq!(metadata.splunk_hec_token().unwrap().as_ref(), SECRET2);
     }
+
+    #[test]
+    fn assigns_id_once() {
+        let mut metadata = EventMetadata::default();
+        assert_eq!(metadata.id(), None);
+
+        metadata.assign_id();
+        let id = metadata.id();
+        assert!(id.is_some());
+        metadata.assign_id();
+        assert_eq!(metadata.id(), id);
+
+        // Events are equal regardless of their identifier.
+        assert_eq!(metadata, EventMetadata::default());
+    }
 }
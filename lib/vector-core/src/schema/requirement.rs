@@ -13,6 +13,9 @@ use super::Definition;
 pub struct Requirement {
     /// Semantic meanings confingured for this requirement.
     meaning: BTreeMap<&'static str, SemanticMeaning>,
+
+    /// Event fields that must be able to exist, such as the fields referenced by templates.
+    fields: BTreeSet<LookupBuf>,
 }
 
 /// The semantic meaning of an event.
@@ -36,6 +39,7 @@ impl Requirement {
     pub fn empty() -> Self {
         Self {
             meaning: BTreeMap::default(),
+            fields: BTreeSet::default(),
         }
     }
 
@@ -44,8 +48,9 @@ impl Requirement {
     /// 1. There are no required fields defined.
     /// 2. The unknown fields are set to "any".
     /// 3. There are no required meanings defined.
+    /// 4. There are no required fields defined.
     pub fn is_empty(&self) -> bool {
-        self.meaning.is_empty()
+        self.meaning.is_empty() && self.fields.is_empty()
     }
 
     /// Add a restriction to the schema.
@@ -66,6 +71,16 @@ impl Requirement {
         self
     }
 
+    /// Add a field that must be able to exist in the event.
+    ///
+    /// This only fails validation if the [`Definition`] rules out the field, which a definition
+    /// allowing unknown fields never does.
+    #[must_use]
+    pub fn required_field(mut self, path: LookupBuf) -> Self {
+        self.fields.insert(path);
+        self
+    }
+
     fn insert_meaning(&mut self, identifier: &'static str, kind: Kind, optional: bool) {
         let meaning = SemanticMeaning { kind, optional };
         self.meaning.insert(identifier, meaning);
@@ -117,6 +132,12 @@ impl Requirement {
             }
         }
 
+        for path in &self.fields {
+            if definition.event_kind().at_path(path).is_undefined() {
+                errors.push(ValidationError::FieldMissing { path: path.clone() });
+            }
+        }
+
         if errors.is_empty() {
             Ok(())
         } else {
@@ -176,6 +197,9 @@ pub enum ValidationError {
         identifier: &'static str,
         paths: BTreeSet<LookupBuf>,
     },
+
+    /// A required field can't exist.
+    FieldMissing { path: LookupBuf },
 }
 
 impl ValidationError {
@@ -190,6 +214,10 @@ impl ValidationError {
     pub fn is_meaning_duplicate(&self) -> bool {
         matches!(self, Self::MeaningDuplicate { .. })
     }
+
+    pub fn is_field_missing(&self) -> bool {
+        matches!(self, Self::FieldMissing { .. })
+    }
 }
 
 impl std::fmt::Display for ValidationError {
@@ -217,6 +245,7 @@ impl std::fmt::Display for ValidationError {
                     .collect::<Vec<_>>()
                     .join(", ")
             ),
+            Self::FieldMissing { path } => write!(f, "missing field: {}", path),
         }
     }
 }
@@ -343,6 +372,28 @@ mod tests {
                     }],
                 },
             ),
+            (
+                "missing required field",
+                TestCase {
+                    requirement: Requirement::empty()
+                        .required_field("foo".into())
+                        .required_field("bar".into()),
+                    definition: Definition::empty_legacy_namespace().with_event_field(
+                        &owned_value_path!("foo"),
+                        Kind::bytes(),
+                        None,
+                    ),
+                    errors: vec![ValidationError::FieldMissing { path: "bar".into() }],
+                },
+            ),
+            (
+                "required field of unknown fields",
+                TestCase {
+                    requirement: Requirement::empty().required_field("foo".into()),
+                    definition: Definition::empty_legacy_namespace().unknown_fields(Kind::any()),
+                    errors: vec![],
+                },
+            ),
         ]) {
             let got = requirement.validate(&definition);
             let want = if errors.is_empty() {
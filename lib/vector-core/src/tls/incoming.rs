@@ -8,10 +8,11 @@ use std::{
 };
 
 use futures::{future::BoxFuture, stream, FutureExt, Stream};
-use openssl::ssl::{Ssl, SslAcceptor, SslMethod};
-use openssl::x509::X509;
+use openssl::ssl::{NameType, SniError, Ssl, SslAcceptor, SslContext, SslMethod};
+use openssl::x509::{X509Ref, X509};
+use regex::Regex;
 use snafu::ResultExt;
-use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::sync::{mpsc, OwnedSemaphorePermit, Semaphore};
 use tokio::{
     io::{self, AsyncRead, AsyncWrite, ReadBuf},
     net::{TcpListener, TcpStream},
@@ -20,8 +21,9 @@ use tokio_openssl::SslStream;
 use tonic::transport::{server::Connected, Certificate};
 
 use super::{
-    CreateAcceptorSnafu, HandshakeSnafu, IncomingListenerSnafu, MaybeTlsSettings, MaybeTlsStream,
-    SslBuildSnafu, TcpBindSnafu, TlsError, TlsSettings,
+    settings::SniIdentity, CreateAcceptorSnafu, HandshakeSnafu, IncomingListenerSnafu,
+    MaybeTlsSettings, MaybeTlsStream, SslBuildSnafu, TcpBindSnafu, TlsClientAllowlistConfig,
+    TlsError, TlsSettings,
 };
 use crate::tcp::{self, TcpKeepaliveConfig};
 
@@ -33,6 +35,32 @@ impl TlsSettings {
                 let mut acceptor = SslAcceptor::mozilla_intermediate(SslMethod::tls())
                     .context(CreateAcceptorSnafu)?;
                 self.apply_context(&mut acceptor)?;
+                if !self.sni_identities.is_empty() {
+                    // Each identity gets a context of its own, switched to once the client has
+                    // sent the server name it is connecting to.
+                    let contexts = self
+                        .sni_identities
+                        .iter()
+                        .map(|sni| {
+                            let acceptor = self.with_identity(sni.identity.clone()).acceptor()?;
+                            Ok((sni.clone(), acceptor.into_context()))
+                        })
+                        .collect::<crate::tls::Result<Vec<(SniIdentity, SslContext)>>>()?;
+                    acceptor.set_servername_callback(move |ssl, _alert| {
+                        let context = ssl.servername(NameType::HOST_NAME).and_then(|name| {
+                            contexts
+                                .iter()
+                                .find(|(sni, _)| sni.matches(name))
+                                .map(|(_, context)| context)
+                        });
+                        match context {
+                            Some(context) => ssl
+                                .set_ssl_context(context)
+                                .map_err(|_| SniError::ALERT_FATAL),
+                            None => Ok(()),
+                        }
+                    });
+                }
                 Ok(acceptor.build())
             }
         }
@@ -48,24 +76,46 @@ impl MaybeTlsSettings {
             Self::Raw(()) => None,
         };
 
-        Ok(MaybeTlsListener { listener, acceptor })
+        Ok(MaybeTlsListener {
+            listener,
+            acceptor,
+            handoff: None,
+        })
     }
 }
 
 pub struct MaybeTlsListener {
     listener: TcpListener,
     acceptor: Option<SslAcceptor>,
+    handoff: Option<mpsc::Receiver<TcpStream>>,
 }
 
 impl MaybeTlsListener {
+    /// Also accepts the connections received on `handoff`, which were established elsewhere and
+    /// handed over to this listener, as if they had been accepted by it.
+    #[must_use]
+    pub fn with_handoff(mut self, handoff: mpsc::Receiver<TcpStream>) -> Self {
+        self.handoff = Some(handoff);
+        self
+    }
+
     pub async fn accept(&mut self) -> crate::tls::Result<MaybeTlsIncomingStream<TcpStream>> {
-        self.listener
-            .accept()
-            .await
-            .map(|(stream, peer_addr)| {
-                MaybeTlsIncomingStream::new(stream, peer_addr, self.acceptor.clone())
-            })
-            .context(IncomingListenerSnafu)
+        let (stream, peer_addr) = match &mut self.handoff {
+            Some(handoff) => tokio::select! {
+                accepted = self.listener.accept() => accepted,
+                Some(stream) = handoff.recv() => {
+                    stream.peer_addr().map(|peer_addr| (stream, peer_addr))
+                }
+            },
+            None => self.listener.accept().await,
+        }
+        .context(IncomingListenerSnafu)?;
+
+        Ok(MaybeTlsIncomingStream::new(
+            stream,
+            peer_addr,
+            self.acceptor.clone(),
+        ))
     }
 
     async fn into_accept(
@@ -134,6 +184,7 @@ impl From<TcpListener> for MaybeTlsListener {
         Self {
             listener,
             acceptor: None,
+            handoff: None,
         }
     }
 }
@@ -391,6 +442,106 @@ impl From<X509> for CertificateMetadata {
     }
 }
 
+/// The identity a client certificate was allowed by.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ClientIdentity {
+    /// The hex encoded SHA-256 fingerprint of the certificate's `SubjectPublicKeyInfo`.
+    SpkiSha256(String),
+    /// A Subject Alternative Name of the certificate.
+    SubjectAltName(String),
+}
+
+impl ClientIdentity {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::SpkiSha256(fingerprint) => fingerprint,
+            Self::SubjectAltName(name) => name,
+        }
+    }
+}
+
+/// Restricts clients to those presenting a certificate with an allowed SPKI fingerprint or
+/// Subject Alternative Name.
+#[derive(Clone, Debug)]
+pub struct TlsClientAllowlist {
+    spki_sha256: Vec<String>,
+    san_patterns: Vec<Regex>,
+}
+
+impl TlsClientAllowlist {
+    pub fn new(config: &TlsClientAllowlistConfig) -> crate::tls::Result<Self> {
+        let spki_sha256 = config
+            .spki_sha256
+            .iter()
+            .map(|fingerprint| {
+                let normalized = fingerprint.replace(':', "").to_ascii_lowercase();
+                if normalized.len() == 64 && normalized.chars().all(|c| c.is_ascii_hexdigit()) {
+                    Ok(normalized)
+                } else {
+                    Err(TlsError::InvalidSpkiFingerprint {
+                        fingerprint: fingerprint.clone(),
+                    })
+                }
+            })
+            .collect::<crate::tls::Result<_>>()?;
+
+        let san_patterns = config
+            .san_patterns
+            .iter()
+            .map(|pattern| {
+                let pattern = format!("^{}$", regex::escape(pattern).replace(r"\*", "[^./]*"));
+                Regex::new(&pattern).expect("escaped pattern is a valid regex")
+            })
+            .collect();
+
+        Ok(Self {
+            spki_sha256,
+            san_patterns,
+        })
+    }
+
+    /// Returns the identity `cert` is allowed by, if any.
+    pub fn matched_identity(&self, cert: &X509Ref) -> Option<ClientIdentity> {
+        if !self.spki_sha256.is_empty() {
+            if let Some(fingerprint) = spki_sha256(cert) {
+                if self.spki_sha256.contains(&fingerprint) {
+                    return Some(ClientIdentity::SpkiSha256(fingerprint));
+                }
+            }
+        }
+
+        if !self.san_patterns.is_empty() {
+            let names = cert.subject_alt_names()?;
+            return names
+                .iter()
+                .filter_map(|name| {
+                    name.dnsname()
+                        .or_else(|| name.uri())
+                        .or_else(|| name.email())
+                })
+                .find(|name| {
+                    self.san_patterns
+                        .iter()
+                        .any(|pattern| pattern.is_match(name))
+                })
+                .map(|name| ClientIdentity::SubjectAltName(name.to_string()));
+        }
+
+        None
+    }
+}
+
+/// Computes the hex encoded SHA-256 fingerprint of the `SubjectPublicKeyInfo` of `cert`.
+fn spki_sha256(cert: &X509Ref) -> Option<String> {
+    let spki = cert.public_key().ok()?.public_key_to_der().ok()?;
+    Some(
+        openssl::sha::sha256(&spki)
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect(),
+    )
+}
+
 #[derive(Clone)]
 pub struct MaybeTlsConnectInfo {
     pub remote_addr: SocketAddr,
@@ -463,4 +614,93 @@ mod test {
         );
         assert_eq!(expected, example_meta.subject());
     }
+
+    fn client_cert() -> X509 {
+        use openssl::{
+            asn1::Asn1Time, bn::BigNum, hash::MessageDigest, pkey::PKey, rsa::Rsa,
+            x509::extension::SubjectAlternativeName,
+        };
+
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let mut builder = X509::builder().unwrap();
+        builder
+            .set_serial_number(&BigNum::from_u32(1).unwrap().to_asn1_integer().unwrap())
+            .unwrap();
+        builder.set_pubkey(&key).unwrap();
+        builder
+            .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        builder
+            .set_not_after(&Asn1Time::days_from_now(1).unwrap())
+            .unwrap();
+        let san = SubjectAlternativeName::new()
+            .dns("client.example.com")
+            .uri("spiffe://example.org/ns/prod/sa/api")
+            .build(&builder.x509v3_context(None, None))
+            .unwrap();
+        builder.append_extension(san).unwrap();
+        builder.sign(&key, MessageDigest::sha256()).unwrap();
+        builder.build()
+    }
+
+    #[test]
+    fn client_allowlist_matches_spki_fingerprint() {
+        let cert = client_cert();
+        let fingerprint = spki_sha256(&cert).unwrap();
+        let with_colons = fingerprint
+            .as_bytes()
+            .chunks(2)
+            .map(|byte| std::str::from_utf8(byte).unwrap().to_ascii_uppercase())
+            .collect::<Vec<_>>()
+            .join(":");
+
+        let allowlist = TlsClientAllowlist::new(&TlsClientAllowlistConfig {
+            spki_sha256: vec![with_colons],
+            san_patterns: vec![],
+        })
+        .unwrap();
+
+        assert_eq!(
+            allowlist.matched_identity(&cert),
+            Some(ClientIdentity::SpkiSha256(fingerprint))
+        );
+    }
+
+    #[test]
+    fn client_allowlist_matches_san_patterns() {
+        let cert = client_cert();
+
+        let allowlist = TlsClientAllowlist::new(&TlsClientAllowlistConfig {
+            spki_sha256: vec![],
+            san_patterns: vec!["spiffe://example.org/ns/prod/sa/*".into()],
+        })
+        .unwrap();
+        assert_eq!(
+            allowlist.matched_identity(&cert),
+            Some(ClientIdentity::SubjectAltName(
+                "spiffe://example.org/ns/prod/sa/api".into()
+            ))
+        );
+
+        let allowlist = TlsClientAllowlist::new(&TlsClientAllowlistConfig {
+            spki_sha256: vec!["00".repeat(32)],
+            san_patterns: vec![
+                "*.example.net".into(),
+                "*.com".into(),
+                "spiffe://example.org/ns/*".into(),
+            ],
+        })
+        .unwrap();
+        assert_eq!(allowlist.matched_identity(&cert), None);
+    }
+
+    #[test]
+    fn client_allowlist_rejects_invalid_fingerprint() {
+        let error = TlsClientAllowlist::new(&TlsClientAllowlistConfig {
+            spki_sha256: vec!["not a fingerprint".into()],
+            san_patterns: vec![],
+        })
+        .unwrap_err();
+        assert!(matches!(error, TlsError::InvalidSpkiFingerprint { .. }));
+    }
 }
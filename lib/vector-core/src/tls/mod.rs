@@ -16,14 +16,20 @@ use crate::tcp::{self, TcpKeepaliveConfig};
 mod incoming;
 mod maybe_tls;
 mod outgoing;
+mod policy;
 mod settings;
 
-pub use incoming::{CertificateMetadata, MaybeTlsIncomingStream, MaybeTlsListener};
+pub use incoming::{
+    CertificateMetadata, ClientIdentity, MaybeTlsIncomingStream, MaybeTlsListener,
+    TlsClientAllowlist,
+};
 pub use maybe_tls::MaybeTls;
+pub use policy::{TlsPolicy, TlsPolicyConfig, TlsVersion};
 pub use settings::{
-    MaybeTlsSettings, TlsConfig, TlsEnableableConfig, TlsSettings, TlsSourceConfig,
-    PEM_START_MARKER, TEST_PEM_CA_PATH, TEST_PEM_CLIENT_CRT_PATH, TEST_PEM_CLIENT_KEY_PATH,
-    TEST_PEM_CRT_PATH, TEST_PEM_INTERMEDIATE_CA_PATH, TEST_PEM_KEY_PATH,
+    MaybeTlsSettings, TlsClientAllowlistConfig, TlsConfig, TlsEnableableConfig, TlsSettings,
+    TlsSniCertificateConfig, TlsSourceConfig, PEM_START_MARKER, TEST_PEM_CA_PATH,
+    TEST_PEM_CLIENT_CRT_PATH, TEST_PEM_CLIENT_KEY_PATH, TEST_PEM_CRT_PATH,
+    TEST_PEM_INTERMEDIATE_CA_PATH, TEST_PEM_KEY_PATH,
 };
 
 pub type Result<T> = std::result::Result<T, TlsError>;
@@ -130,6 +136,24 @@ pub enum TlsError {
     NewCaStack { source: ErrorStack },
     #[snafu(display("Could not push intermediate certificate onto stack"))]
     CaStackPush { source: ErrorStack },
+    #[snafu(display("Invalid SPKI SHA-256 fingerprint {:?}", fingerprint))]
+    InvalidSpkiFingerprint { fingerprint: String },
+    #[snafu(display("TLS policy violation in `{}`: {}", option, reason))]
+    PolicyViolation {
+        option: &'static str,
+        reason: String,
+    },
+    #[snafu(display("SNI certificates are configured, but TLS is not enabled"))]
+    SniWithoutTls,
+    #[snafu(display("A client allowlist is configured, but TLS is not enabled"))]
+    ClientAllowlistWithoutTls,
+    #[snafu(display("SNI certificate {:?} has no server names", filename))]
+    MissingSniServerNames { filename: PathBuf },
+    #[snafu(display("Could not apply TLS policy option `{}`: {}", option, source))]
+    ApplyPolicy {
+        option: &'static str,
+        source: ErrorStack,
+    },
 }
 
 impl MaybeTlsStream<TcpStream> {
@@ -0,0 +1,543 @@
+use openssl::ssl::{SslContextBuilder, SslMethod, SslVerifyMode, SslVersion};
+use snafu::ResultExt;
+use vector_config::configurable_component;
+
+use super::{ApplyPolicySnafu, Result, SslBuildSnafu, TlsError};
+
+/// TLS 1.3 cipher suites approved for use in FIPS 140 mode.
+const FIPS_TLS13_CIPHER_SUITES: &[&str] = &["TLS_AES_128_GCM_SHA256", "TLS_AES_256_GCM_SHA384"];
+
+/// TLS 1.2 cipher suites approved for use in FIPS 140 mode.
+const FIPS_TLS12_CIPHER_SUITES: &[&str] = &[
+    "ECDHE-ECDSA-AES128-GCM-SHA256",
+    "ECDHE-ECDSA-AES256-GCM-SHA384",
+    "ECDHE-RSA-AES128-GCM-SHA256",
+    "ECDHE-RSA-AES256-GCM-SHA384",
+    "DHE-RSA-AES128-GCM-SHA256",
+    "DHE-RSA-AES256-GCM-SHA384",
+];
+
+/// Curves approved for use in FIPS 140 mode.
+const FIPS_CURVES: &[&str] = &["P-256", "P-384", "P-521"];
+
+/// TLS protocol version.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Ord)]
+pub enum TlsVersion {
+    /// TLS 1.0.
+    #[serde(rename = "tls1.0")]
+    Tls1_0,
+
+    /// TLS 1.1.
+    #[serde(rename = "tls1.1")]
+    Tls1_1,
+
+    /// TLS 1.2.
+    #[serde(rename = "tls1.2")]
+    Tls1_2,
+
+    /// TLS 1.3.
+    #[serde(rename = "tls1.3")]
+    Tls1_3,
+}
+
+impl TlsVersion {
+    const fn ssl_version(self) -> SslVersion {
+        match self {
+            Self::Tls1_0 => SslVersion::TLS1,
+            Self::Tls1_1 => SslVersion::TLS1_1,
+            Self::Tls1_2 => SslVersion::TLS1_2,
+            Self::Tls1_3 => SslVersion::TLS1_3,
+        }
+    }
+}
+
+/// Policy enforced on the TLS connections accepted by a source.
+///
+/// All options are validated when the source is built, and the option breaking the policy is
+/// named in the resulting error.
+#[configurable_component]
+#[derive(Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct TlsPolicyConfig {
+    /// Restricts connections to FIPS 140 approved protocol versions, cipher suites, and curves.
+    ///
+    /// Unless set explicitly, `min_version`, `cipher_suites`, and `curves` default to the approved
+    /// values, and setting them to anything else is an error.
+    #[serde(default)]
+    pub fips: bool,
+
+    /// The minimum TLS version accepted.
+    pub min_version: Option<TlsVersion>,
+
+    /// The cipher suites accepted.
+    ///
+    /// TLS 1.3 cipher suites use their IANA names, such as `TLS_AES_256_GCM_SHA384`, and earlier
+    /// ones their OpenSSL names, such as `ECDHE-RSA-AES256-GCM-SHA384`. Each entry must name a
+    /// single cipher suite: OpenSSL cipher strings such as `HIGH` are rejected.
+    ///
+    /// Cipher suites not listed are never accepted, whatever the protocol version. If only TLS 1.3
+    /// cipher suites are listed, connections are restricted to TLS 1.3, and if none are listed,
+    /// connections are restricted to TLS 1.2 and earlier.
+    pub cipher_suites: Option<Vec<String>>,
+
+    /// The elliptic curves accepted for key exchange, such as `P-256` or `X25519`.
+    pub curves: Option<Vec<String>>,
+
+    /// Requires clients to present a certificate valid for the configured CA.
+    #[serde(default)]
+    pub require_client_certificate: bool,
+}
+
+/// A validated TLS policy, ready to be applied to an SSL context.
+#[derive(Clone, Debug, Default)]
+pub struct TlsPolicy {
+    min_version: Option<SslVersion>,
+    max_version: Option<SslVersion>,
+    cipher_list: Option<String>,
+    ciphersuites: Option<String>,
+    groups: Option<String>,
+    require_client_certificate: bool,
+}
+
+impl TlsPolicy {
+    pub fn new(config: &TlsPolicyConfig) -> Result<Self> {
+        let mut scratch = SslContextBuilder::new(SslMethod::tls()).context(SslBuildSnafu)?;
+
+        let mut min_version = match (config.min_version, config.fips) {
+            (Some(version), true) if version < TlsVersion::Tls1_2 => {
+                return Err(violation(
+                    "min_version",
+                    "versions older than TLS 1.2 are not allowed in FIPS mode",
+                ))
+            }
+            (None, true) => Some(TlsVersion::Tls1_2),
+            (version, _) => version,
+        };
+        let mut max_version = None;
+
+        let cipher_suites = match (&config.cipher_suites, config.fips) {
+            (Some(suites), _) if suites.is_empty() => {
+                return Err(violation(
+                    "cipher_suites",
+                    "at least one cipher suite is required",
+                ))
+            }
+            (Some(suites), _) => suites.clone(),
+            (None, true) => FIPS_TLS13_CIPHER_SUITES
+                .iter()
+                .chain(FIPS_TLS12_CIPHER_SUITES)
+                .copied()
+                .map(String::from)
+                .collect(),
+            (None, false) => Vec::new(),
+        };
+        let (tls13, tls12): (Vec<_>, Vec<_>) = cipher_suites
+            .iter()
+            .partition(|suite| suite.starts_with("TLS_"));
+        for suite in &tls13 {
+            if !is_single_cipher_suite(suite, '_') {
+                return Err(not_a_cipher_suite("cipher_suites", suite));
+            }
+            if config.fips && !FIPS_TLS13_CIPHER_SUITES.contains(&suite.as_str()) {
+                return Err(not_fips_approved("cipher_suites", suite));
+            }
+            if scratch.set_ciphersuites(suite).is_err() {
+                return Err(unsupported("cipher_suites", suite));
+            }
+        }
+        for suite in &tls12 {
+            if !is_single_cipher_suite(suite, '-') {
+                return Err(not_a_cipher_suite("cipher_suites", suite));
+            }
+            if config.fips && !FIPS_TLS12_CIPHER_SUITES.contains(&suite.as_str()) {
+                return Err(not_fips_approved("cipher_suites", suite));
+            }
+            if scratch.set_cipher_list(suite).is_err() {
+                return Err(unsupported("cipher_suites", suite));
+            }
+        }
+
+        // The TLS 1.3 and the earlier cipher suites are configured separately, so leaving either
+        // list unset would keep the OpenSSL defaults for it. OpenSSL doesn't allow an empty list
+        // of TLS 1.2 cipher suites, so the protocol versions are restricted instead.
+        let mut tls13_suites = join(&tls13);
+        if !cipher_suites.is_empty() {
+            if tls12.is_empty() {
+                min_version = Some(TlsVersion::Tls1_3);
+            }
+            if tls13.is_empty() {
+                if config.min_version == Some(TlsVersion::Tls1_3) {
+                    return Err(violation(
+                        "cipher_suites",
+                        "no TLS 1.3 cipher suite is listed, but `min_version` is TLS 1.3",
+                    ));
+                }
+                max_version = Some(TlsVersion::Tls1_2);
+                tls13_suites = Some(String::new());
+            }
+        }
+
+        let curves = match (&config.curves, config.fips) {
+            (Some(curves), _) => curves.clone(),
+            (None, true) => FIPS_CURVES.iter().copied().map(String::from).collect(),
+            (None, false) => Vec::new(),
+        };
+        for curve in &curves {
+            if config.fips && !FIPS_CURVES.contains(&curve.as_str()) {
+                return Err(not_fips_approved("curves", curve));
+            }
+            if scratch.set_groups_list(curve).is_err() {
+                return Err(unsupported("curves", curve));
+            }
+        }
+
+        Ok(Self {
+            min_version: min_version.map(TlsVersion::ssl_version),
+            max_version: max_version.map(TlsVersion::ssl_version),
+            cipher_list: join(&tls12),
+            ciphersuites: tls13_suites,
+            groups: join(&curves.iter().collect::<Vec<_>>()),
+            require_client_certificate: config.require_client_certificate,
+        })
+    }
+
+    pub(super) const fn require_client_certificate(&self) -> bool {
+        self.require_client_certificate
+    }
+
+    pub(super) fn apply_context(&self, context: &mut SslContextBuilder) -> Result<()> {
+        if let Some(version) = self.min_version {
+            context
+                .set_min_proto_version(Some(version))
+                .context(ApplyPolicySnafu {
+                    option: "min_version",
+                })?;
+        }
+        if let Some(version) = self.max_version {
+            context
+                .set_max_proto_version(Some(version))
+                .context(ApplyPolicySnafu {
+                    option: "cipher_suites",
+                })?;
+        }
+        if let Some(cipher_list) = &self.cipher_list {
+            context
+                .set_cipher_list(cipher_list)
+                .context(ApplyPolicySnafu {
+                    option: "cipher_suites",
+                })?;
+        }
+        if let Some(ciphersuites) = &self.ciphersuites {
+            context
+                .set_ciphersuites(ciphersuites)
+                .context(ApplyPolicySnafu {
+                    option: "cipher_suites",
+                })?;
+        }
+        if let Some(groups) = &self.groups {
+            context
+                .set_groups_list(groups)
+                .context(ApplyPolicySnafu { option: "curves" })?;
+        }
+        if self.require_client_certificate {
+            context.set_verify(SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT);
+        }
+        Ok(())
+    }
+}
+
+fn join(values: &[&String]) -> Option<String> {
+    (!values.is_empty()).then(|| {
+        values
+            .iter()
+            .map(|value| value.as_str())
+            .collect::<Vec<_>>()
+            .join(":")
+    })
+}
+
+/// Whether `suite` names a single cipher suite, as opposed to an OpenSSL cipher string such as
+/// `HIGH` or `ECDHE+AESGCM:!SHA1`, which could select any number of them.
+fn is_single_cipher_suite(suite: &str, separator: char) -> bool {
+    let mut parts = suite.split(separator);
+    parts.clone().count() > 1
+        && parts.all(|part| {
+            !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+        })
+}
+
+fn violation(option: &'static str, reason: impl Into<String>) -> TlsError {
+    TlsError::PolicyViolation {
+        option,
+        reason: reason.into(),
+    }
+}
+
+fn not_fips_approved(option: &'static str, value: &str) -> TlsError {
+    violation(
+        option,
+        format!("{:?} is not approved for use in FIPS mode", value),
+    )
+}
+
+fn not_a_cipher_suite(option: &'static str, value: &str) -> TlsError {
+    violation(
+        option,
+        format!("{:?} is not the name of a cipher suite", value),
+    )
+}
+
+fn unsupported(option: &'static str, value: &str) -> TlsError {
+    violation(option, format!("{:?} is not supported", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::TcpListener, thread};
+
+    use openssl::{
+        pkey::PKey,
+        ssl::{SslAcceptor, SslConnector},
+        x509::X509,
+    };
+
+    use super::*;
+
+    const TEST_PEM_CRT_BYTES: &[u8] =
+        include_bytes!("../../../../tests/data/ca/intermediate_server/certs/localhost.cert.pem");
+    const TEST_PEM_KEY_BYTES: &[u8] =
+        include_bytes!("../../../../tests/data/ca/intermediate_server/private/localhost.key.pem");
+
+    fn policy_error(config: TlsPolicyConfig) -> String {
+        TlsPolicy::new(&config).unwrap_err().to_string()
+    }
+
+    /// Runs a handshake between a server enforcing `policy` and a client restricted to
+    /// `client_version` and `client_suite`, returning the cipher suite negotiated.
+    fn handshake(
+        policy: &TlsPolicyConfig,
+        client_version: SslVersion,
+        client_suite: &str,
+    ) -> Option<String> {
+        let policy = TlsPolicy::new(policy).unwrap();
+        let mut acceptor = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).unwrap();
+        acceptor
+            .set_certificate(&X509::from_pem(TEST_PEM_CRT_BYTES).unwrap())
+            .unwrap();
+        acceptor
+            .set_private_key(&PKey::private_key_from_pem(TEST_PEM_KEY_BYTES).unwrap())
+            .unwrap();
+        policy.apply_context(&mut acceptor).unwrap();
+        let acceptor = acceptor.build();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            acceptor.accept(stream).is_ok()
+        });
+
+        let mut connector = SslConnector::builder(SslMethod::tls()).unwrap();
+        connector.set_verify(SslVerifyMode::NONE);
+        connector
+            .set_min_proto_version(Some(client_version))
+            .unwrap();
+        connector
+            .set_max_proto_version(Some(client_version))
+            .unwrap();
+        if client_version == SslVersion::TLS1_3 {
+            connector.set_ciphersuites(client_suite).unwrap();
+        } else {
+            connector.set_cipher_list(client_suite).unwrap();
+        }
+        let stream = std::net::TcpStream::connect(addr).unwrap();
+        let negotiated = connector
+            .build()
+            .connect("localhost", stream)
+            .ok()
+            .and_then(|stream| {
+                stream
+                    .ssl()
+                    .current_cipher()
+                    .map(|cipher| cipher.name().to_string())
+            });
+
+        let accepted = server.join().unwrap();
+        assert_eq!(accepted, negotiated.is_some());
+        negotiated
+    }
+
+    #[test]
+    fn fips_defaults_to_approved_values() {
+        let policy = TlsPolicy::new(&TlsPolicyConfig {
+            fips: true,
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert_eq!(policy.min_version, Some(SslVersion::TLS1_2));
+        assert_eq!(
+            policy.ciphersuites.as_deref(),
+            Some("TLS_AES_128_GCM_SHA256:TLS_AES_256_GCM_SHA384")
+        );
+        assert_eq!(policy.groups.as_deref(), Some("P-256:P-384:P-521"));
+
+        let mut context = SslContextBuilder::new(SslMethod::tls()).unwrap();
+        policy.apply_context(&mut context).unwrap();
+    }
+
+    #[test]
+    fn fips_rejects_unapproved_values() {
+        let error = policy_error(TlsPolicyConfig {
+            fips: true,
+            min_version: Some(TlsVersion::Tls1_1),
+            ..Default::default()
+        });
+        assert!(error.contains("`min_version`"), "{}", error);
+
+        let error = policy_error(TlsPolicyConfig {
+            fips: true,
+            cipher_suites: Some(vec!["TLS_CHACHA20_POLY1305_SHA256".into()]),
+            ..Default::default()
+        });
+        assert!(error.contains("`cipher_suites`"), "{}", error);
+        assert!(error.contains("TLS_CHACHA20_POLY1305_SHA256"), "{}", error);
+
+        let error = policy_error(TlsPolicyConfig {
+            fips: true,
+            curves: Some(vec!["X25519".into()]),
+            ..Default::default()
+        });
+        assert!(error.contains("`curves`"), "{}", error);
+    }
+
+    #[test]
+    fn rejects_unknown_values() {
+        let error = policy_error(TlsPolicyConfig {
+            cipher_suites: Some(vec!["ECDHE-RSA-AES128-GCM-SHA256".into(), "BOGUS".into()]),
+            ..Default::default()
+        });
+        assert!(error.contains("`cipher_suites`"), "{}", error);
+        assert!(error.contains("BOGUS"), "{}", error);
+
+        let error = policy_error(TlsPolicyConfig {
+            curves: Some(vec!["P-255".into()]),
+            ..Default::default()
+        });
+        assert!(error.contains("`curves`"), "{}", error);
+    }
+
+    #[test]
+    fn rejects_cipher_strings() {
+        for cipher_string in [
+            "HIGH",
+            "ALL",
+            "ECDHE+AESGCM",
+            "!aNULL",
+            "ECDHE-RSA-AES128-GCM-SHA256:HIGH",
+            "TLS_AES_128_GCM_SHA256:TLS_AES_256_GCM_SHA384",
+        ] {
+            let error = policy_error(TlsPolicyConfig {
+                cipher_suites: Some(vec![cipher_string.into()]),
+                ..Default::default()
+            });
+            assert!(error.contains("`cipher_suites`"), "{}", error);
+            assert!(
+                error.contains("is not the name of a cipher suite"),
+                "{}",
+                error
+            );
+        }
+
+        let error = policy_error(TlsPolicyConfig {
+            cipher_suites: Some(Vec::new()),
+            ..Default::default()
+        });
+        assert!(error.contains("`cipher_suites`"), "{}", error);
+    }
+
+    #[test]
+    fn cipher_suites_restrict_protocol_versions() {
+        let policy = TlsPolicy::new(&TlsPolicyConfig {
+            cipher_suites: Some(vec!["TLS_AES_128_GCM_SHA256".into()]),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(policy.min_version, Some(SslVersion::TLS1_3));
+        assert_eq!(policy.max_version, None);
+
+        let policy = TlsPolicy::new(&TlsPolicyConfig {
+            cipher_suites: Some(vec!["ECDHE-RSA-AES128-GCM-SHA256".into()]),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(policy.max_version, Some(SslVersion::TLS1_2));
+        assert_eq!(policy.ciphersuites.as_deref(), Some(""));
+
+        let error = policy_error(TlsPolicyConfig {
+            min_version: Some(TlsVersion::Tls1_3),
+            cipher_suites: Some(vec!["ECDHE-RSA-AES128-GCM-SHA256".into()]),
+            ..Default::default()
+        });
+        assert!(error.contains("`cipher_suites`"), "{}", error);
+    }
+
+    #[test]
+    fn handshakes_only_with_listed_cipher_suites() {
+        let tls13_only = TlsPolicyConfig {
+            fips: true,
+            cipher_suites: Some(vec!["TLS_AES_128_GCM_SHA256".into()]),
+            ..Default::default()
+        };
+        assert_eq!(
+            handshake(&tls13_only, SslVersion::TLS1_3, "TLS_AES_128_GCM_SHA256").as_deref(),
+            Some("TLS_AES_128_GCM_SHA256")
+        );
+        assert_eq!(
+            handshake(&tls13_only, SslVersion::TLS1_3, "TLS_AES_256_GCM_SHA384"),
+            None
+        );
+        assert_eq!(
+            handshake(
+                &tls13_only,
+                SslVersion::TLS1_2,
+                "ECDHE-RSA-CHACHA20-POLY1305"
+            ),
+            None
+        );
+
+        let tls12_only = TlsPolicyConfig {
+            cipher_suites: Some(vec!["ECDHE-RSA-AES128-GCM-SHA256".into()]),
+            ..Default::default()
+        };
+        assert_eq!(
+            handshake(
+                &tls12_only,
+                SslVersion::TLS1_2,
+                "ECDHE-RSA-AES128-GCM-SHA256"
+            )
+            .as_deref(),
+            Some("ECDHE-RSA-AES128-GCM-SHA256")
+        );
+        assert_eq!(
+            handshake(
+                &tls12_only,
+                SslVersion::TLS1_2,
+                "ECDHE-RSA-CHACHA20-POLY1305"
+            ),
+            None
+        );
+        assert_eq!(
+            handshake(
+                &tls12_only,
+                SslVersion::TLS1_3,
+                "TLS_CHACHA20_POLY1305_SHA256"
+            ),
+            None
+        );
+    }
+}
@@ -19,8 +19,8 @@ use super::{
     AddCertToStoreSnafu, AddExtraChainCertSnafu, CaStackPushSnafu, DerExportSnafu,
     EncodeAlpnProtocolsSnafu, FileOpenFailedSnafu, FileReadFailedSnafu, MaybeTls, NewCaStackSnafu,
     NewStoreBuilderSnafu, ParsePkcs12Snafu, Pkcs12Snafu, PrivateKeyParseSnafu, Result,
-    SetAlpnProtocolsSnafu, SetCertificateSnafu, SetPrivateKeySnafu, SetVerifyCertSnafu, TlsError,
-    TlsIdentitySnafu, X509ParseSnafu,
+    SetAlpnProtocolsSnafu, SetCertificateSnafu, SetPrivateKeySnafu, SetVerifyCertSnafu,
+    TlsClientAllowlist, TlsError, TlsIdentitySnafu, TlsPolicy, TlsPolicyConfig, X509ParseSnafu,
 };
 
 pub const PEM_START_MARKER: &str = "-----BEGIN ";
@@ -73,10 +73,104 @@ pub struct TlsSourceConfig {
     /// Event field for client certificate metadata.
     pub client_metadata_key: Option<String>,
 
+    #[configurable(derived)]
+    pub client_allowlist: Option<TlsClientAllowlistConfig>,
+
+    #[configurable(derived)]
+    pub policy: Option<TlsPolicyConfig>,
+
+    /// Additional certificates, each presented to the clients requesting one of its server names
+    /// through SNI.
+    ///
+    /// Clients not using SNI, or requesting another server name, are presented the certificate set
+    /// by `crt_file`.
+    #[serde(default)]
+    pub sni_certificates: Vec<TlsSniCertificateConfig>,
+
     #[serde(flatten)]
     pub tls_config: TlsEnableableConfig,
 }
 
+impl TlsSourceConfig {
+    /// Builds the allowlist restricting which clients can connect, if one is configured.
+    pub fn client_allowlist(&self) -> Result<Option<TlsClientAllowlist>> {
+        self.client_allowlist
+            .as_ref()
+            .map(TlsClientAllowlist::new)
+            .transpose()
+    }
+}
+
+/// A certificate presented to the clients requesting one of its server names through SNI.
+#[configurable_component]
+#[derive(Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct TlsSniCertificateConfig {
+    /// The server names this certificate is presented for.
+    ///
+    /// A name starting with `*.` matches any single label in its place, i.e. `*.example.com`
+    /// matches `logs.example.com` but not `example.com`. Names are matched case-insensitively, in
+    /// the order the certificates are listed.
+    pub server_names: Vec<String>,
+
+    /// Absolute path to the certificate file, in the same formats as `crt_file`.
+    pub crt_file: PathBuf,
+
+    /// Absolute path to the private key file of the certificate, in the same formats as
+    /// `key_file`.
+    pub key_file: Option<PathBuf>,
+
+    /// Passphrase used to unlock the encrypted key file.
+    pub key_pass: Option<String>,
+}
+
+impl TlsSniCertificateConfig {
+    fn load(&self) -> Result<SniIdentity> {
+        if self.server_names.is_empty() {
+            return Err(TlsError::MissingSniServerNames {
+                filename: self.crt_file.clone(),
+            });
+        }
+        let identity = TlsConfig {
+            crt_file: Some(self.crt_file.clone()),
+            key_file: self.key_file.clone(),
+            key_pass: self.key_pass.clone(),
+            ..TlsConfig::default()
+        }
+        .load_identity()?
+        .ok_or(TlsError::MissingCertificate)?;
+        Ok(SniIdentity {
+            server_names: self.server_names.clone(),
+            identity,
+        })
+    }
+}
+
+/// Restricts which clients can connect, based on the certificate they present.
+///
+/// TLS must be enabled, and clients must still present a certificate valid for the configured CA.
+/// Their certificate must then match at least one of the listed SPKI fingerprints or Subject
+/// Alternative Name patterns, or the connection is closed. When `client_metadata_key` is set, the
+/// matched identity is added to the client certificate metadata as `matched_identity`.
+#[configurable_component]
+#[derive(Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct TlsClientAllowlistConfig {
+    /// SHA-256 fingerprints of the `SubjectPublicKeyInfo` of allowed client certificates.
+    ///
+    /// Fingerprints are hex encoded, and may contain colons between bytes.
+    #[serde(default)]
+    pub spki_sha256: Vec<String>,
+
+    /// Patterns matched against the DNS name, URI, and email Subject Alternative Names of client
+    /// certificates.
+    ///
+    /// `*` matches a single label, that is any sequence of characters other than `.` and `/`. For
+    /// example, `*.example.com` matches `logs.example.com` but not `eu.logs.example.com`.
+    #[serde(default)]
+    pub san_patterns: Vec<String>,
+}
+
 /// TLS configuration.
 #[configurable_component]
 #[derive(Clone, Debug, Default)]
@@ -156,11 +250,34 @@ pub struct TlsSettings {
     authorities: Vec<X509>,
     pub(super) identity: Option<IdentityStore>, // openssl::pkcs12::ParsedPkcs12 doesn't impl Clone yet
     alpn_protocols: Option<Vec<u8>>,
+    policy: Option<TlsPolicy>,
+    pub(super) sni_identities: Vec<SniIdentity>,
 }
 
 #[derive(Clone)]
 pub(super) struct IdentityStore(Vec<u8>, String);
 
+/// An identity presented to the clients requesting one of `server_names` through SNI.
+#[derive(Clone)]
+pub(super) struct SniIdentity {
+    pub(super) server_names: Vec<String>,
+    pub(super) identity: IdentityStore,
+}
+
+impl SniIdentity {
+    /// Whether this identity is presented for `server_name`.
+    pub(super) fn matches(&self, server_name: &str) -> bool {
+        self.server_names
+            .iter()
+            .any(|pattern| match pattern.strip_prefix("*.") {
+                Some(suffix) => server_name
+                    .split_once('.')
+                    .map_or(false, |(_, parent)| parent.eq_ignore_ascii_case(suffix)),
+                None => pattern.eq_ignore_ascii_case(server_name),
+            })
+    }
+}
+
 impl TlsSettings {
     /// Generate a filled out settings struct from the given optional
     /// option set, interpreted as client options. If `options` is
@@ -190,9 +307,20 @@ impl TlsSettings {
             authorities: options.load_authorities()?,
             identity: options.load_identity()?,
             alpn_protocols: options.parse_alpn_protocols()?,
+            policy: None,
+            sni_identities: Vec::new(),
         })
     }
 
+    /// Returns these settings with their identity replaced by `identity`.
+    pub(super) fn with_identity(&self, identity: IdentityStore) -> Self {
+        Self {
+            identity: Some(identity),
+            sni_identities: Vec::new(),
+            ..self.clone()
+        }
+    }
+
     fn identity(&self) -> Option<ParsedPkcs12> {
         // This data was test-built previously, so we can just use it
         // here and expect the results will not fail. This can all be
@@ -281,6 +409,10 @@ impl TlsSettings {
                 .context(SetAlpnProtocolsSnafu)?;
         }
 
+        if let Some(policy) = &self.policy {
+            policy.apply_context(context)?;
+        }
+
         Ok(())
     }
 
@@ -516,6 +648,56 @@ impl MaybeTlsSettings {
         }
     }
 
+    /// Generates the settings of a source from its TLS configuration, enforcing its TLS policy if
+    /// one is configured.
+    pub fn from_source_config(config: &Option<TlsSourceConfig>) -> Result<Self> {
+        let config = match config {
+            Some(config) => config,
+            None => return Ok(Self::Raw(())),
+        };
+        let mut settings = Self::from_config(&Some(config.tls_config.clone()), true)?;
+        if config.client_allowlist.is_some() && settings.is_raw() {
+            return Err(TlsError::ClientAllowlistWithoutTls);
+        }
+        if !config.sni_certificates.is_empty() {
+            match &mut settings {
+                Self::Raw(()) => return Err(TlsError::SniWithoutTls),
+                Self::Tls(tls) => {
+                    tls.sni_identities = config
+                        .sni_certificates
+                        .iter()
+                        .map(TlsSniCertificateConfig::load)
+                        .collect::<Result<_>>()?;
+                }
+            }
+        }
+        let policy = match &config.policy {
+            Some(policy) => TlsPolicy::new(policy)?,
+            None => return Ok(settings),
+        };
+
+        match settings {
+            Self::Raw(()) => Err(TlsError::PolicyViolation {
+                option: "enabled",
+                reason: "a TLS policy is configured, but TLS is not enabled".into(),
+            }),
+            Self::Tls(mut tls) => {
+                if policy.require_client_certificate()
+                    && config.tls_config.options.verify_certificate == Some(false)
+                {
+                    return Err(TlsError::PolicyViolation {
+                        option: "verify_certificate",
+                        reason:
+                            "client certificates are required, but their verification is disabled"
+                                .into(),
+                    });
+                }
+                tls.policy = Some(policy);
+                Ok(Self::Tls(tls))
+            }
+        }
+    }
+
     pub const fn http_protocol_name(&self) -> &'static str {
         match self {
             MaybeTls::Raw(_) => "http",
@@ -754,6 +936,67 @@ mod test {
         assert!(config.is_tls());
     }
 
+    #[test]
+    fn from_source_config_with_sni_certificates() {
+        let sni_certificate = TlsSniCertificateConfig {
+            server_names: vec!["logs.example.com".into(), "*.metrics.example.com".into()],
+            crt_file: TEST_PEM_CRT_PATH.into(),
+            key_file: Some(TEST_PEM_KEY_PATH.into()),
+            key_pass: None,
+        };
+        let mut config = TlsSourceConfig {
+            sni_certificates: vec![sni_certificate.clone()],
+            tls_config: make_config(Some(true), true, true),
+            ..Default::default()
+        };
+
+        let settings = MaybeTlsSettings::from_source_config(&Some(config.clone()))
+            .expect("Failed to load SNI certificate");
+        let sni = &settings
+            .tls()
+            .expect("Settings were not TLS")
+            .sni_identities[0];
+        assert!(sni.matches("LOGS.example.com"));
+        assert!(sni.matches("eu.metrics.example.com"));
+        assert!(!sni.matches("metrics.example.com"));
+        assert!(!sni.matches("a.eu.metrics.example.com"));
+        assert!(!sni.matches("example.com"));
+
+        config.sni_certificates[0].server_names.clear();
+        assert!(matches!(
+            MaybeTlsSettings::from_source_config(&Some(config.clone())),
+            Err(TlsError::MissingSniServerNames { .. })
+        ));
+
+        config.sni_certificates = vec![sni_certificate];
+        config.tls_config.enabled = Some(false);
+        assert!(matches!(
+            MaybeTlsSettings::from_source_config(&Some(config)),
+            Err(TlsError::SniWithoutTls)
+        ));
+    }
+
+    #[test]
+    fn from_source_config_with_client_allowlist() {
+        let mut config = TlsSourceConfig {
+            client_allowlist: Some(TlsClientAllowlistConfig {
+                spki_sha256: vec![],
+                san_patterns: vec!["*.example.com".into()],
+            }),
+            tls_config: make_config(Some(true), true, true),
+            ..Default::default()
+        };
+        assert!(MaybeTlsSettings::from_source_config(&Some(config.clone()))
+            .unwrap()
+            .is_tls());
+
+        config.tls_config.enabled = Some(false);
+        assert!(matches!(
+            MaybeTlsSettings::from_source_config(&Some(config)),
+            Err(TlsError::ClientAllowlistWithoutTls)
+        ));
+    }
+
     fn settings_from_config(
         enabled: Option<bool>,
         set_crt: bool,
@@ -0,0 +1,426 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+use async_graphql::{Context, Enum, InputObject, Object, SimpleObject};
+use tokio::time::{self, Duration, Instant};
+
+use super::components;
+use crate::{
+    api::ShutdownRx,
+    config::ComponentKey,
+    event::{Metric, MetricValue},
+    internal_events::{ApiAlertResolved, ApiAlertTriggered},
+    metrics::Controller,
+};
+
+const INVARIANT: &str = "Couldn't acquire lock on Vector alerts. Please report this.";
+
+/// How often alert rules are evaluated.
+const EVALUATION_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The maximum number of alert rules that can be registered at once.
+const MAX_ALERT_RULES: usize = 100;
+
+/// The metric an alert rule is evaluated against.
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Debug)]
+pub enum AlertMetric {
+    /// Errors per second, from `component_errors_total`. The alert fires when the rate goes
+    /// above the threshold.
+    ErrorRate,
+
+    /// Events sent per second, from `component_sent_events_total`. The alert fires when the rate
+    /// goes below the threshold.
+    Throughput,
+}
+
+impl AlertMetric {
+    const fn metric_name(self) -> &'static str {
+        match self {
+            Self::ErrorRate => "component_errors_total",
+            Self::Throughput => "component_sent_events_total",
+        }
+    }
+
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::ErrorRate => "error_rate",
+            Self::Throughput => "throughput",
+        }
+    }
+
+    fn is_violated(self, rate: f64, threshold: f64) -> bool {
+        match self {
+            Self::ErrorRate => rate > threshold,
+            Self::Throughput => rate < threshold,
+        }
+    }
+}
+
+#[derive(InputObject)]
+pub struct AlertInput {
+    /// The component to watch. If not set, the metric is summed over all components.
+    component_id: Option<String>,
+
+    /// The metric to watch.
+    metric: AlertMetric,
+
+    /// The rate, per second, at which the alert fires.
+    threshold: f64,
+
+    /// The length of the window the rate is computed over, in seconds.
+    #[graphql(default = 60, validator(minimum = 1, maximum = 86_400))]
+    window_secs: i32,
+}
+
+/// An alert rule, and whether it's currently firing.
+#[derive(SimpleObject, Clone, Debug, PartialEq)]
+pub struct Alert {
+    /// Alert rule id
+    id: i32,
+
+    /// The component watched, or `null` if the metric is summed over all components
+    component_id: Option<String>,
+
+    /// The metric watched
+    metric: AlertMetric,
+
+    /// The rate, per second, at which the alert fires
+    threshold: f64,
+
+    /// The length of the window the rate is computed over, in seconds
+    window_secs: i32,
+
+    /// The rate over the last window, or `null` until a full window has been observed
+    rate: Option<f64>,
+
+    /// Whether the alert is firing
+    firing: bool,
+}
+
+/// An alert rule along with the samples its rate is computed from.
+struct AlertState {
+    alert: Alert,
+    window: Duration,
+    samples: VecDeque<(Instant, f64)>,
+}
+
+impl AlertState {
+    fn new(alert: Alert) -> Self {
+        Self {
+            window: Duration::from_secs(alert.window_secs as u64),
+            alert,
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Returns the total of the watched metric in `metrics`.
+    fn total(&self, metrics: &[Metric]) -> f64 {
+        let name = self.alert.metric.metric_name();
+        metrics
+            .iter()
+            .filter(|metric| metric.name() == name)
+            .filter(|metric| match &self.alert.component_id {
+                Some(id) => metric.tag_matches("component_id", id),
+                None => true,
+            })
+            .map(|metric| match metric.value() {
+                MetricValue::Counter { value } => *value,
+                _ => 0.0,
+            })
+            .sum()
+    }
+
+    /// Records the total of the watched metric at `now`, and returns the rate over the last
+    /// window once a full window has been observed.
+    fn observe(&mut self, total: f64, now: Instant) -> Option<f64> {
+        // Counters reset when components are reloaded, which starts a new window.
+        if matches!(self.samples.back(), Some((_, last)) if *last > total) {
+            self.samples.clear();
+        }
+        self.samples.push_back((now, total));
+        while self.samples.len() > 1 && now.duration_since(self.samples[1].0) >= self.window {
+            self.samples.pop_front();
+        }
+
+        let (start, first) = *self.samples.front()?;
+        let elapsed = now.duration_since(start);
+        (elapsed >= self.window).then(|| (total - first) / elapsed.as_secs_f64())
+    }
+
+    fn evaluate(&mut self, metrics: &[Metric], now: Instant) {
+        let rate = self.observe(self.total(metrics), now);
+        self.alert.rate = rate;
+
+        let violated = rate
+            .map(|rate| self.alert.metric.is_violated(rate, self.alert.threshold))
+            .unwrap_or(false);
+        match (violated, self.alert.firing) {
+            (true, false) => emit!(ApiAlertTriggered {
+                id: self.alert.id,
+                component_id: self.alert.component_id.as_deref(),
+                metric: self.alert.metric.as_str(),
+                rate: rate.unwrap_or_default(),
+                threshold: self.alert.threshold,
+            }),
+            (false, true) => emit!(ApiAlertResolved {
+                id: self.alert.id,
+                component_id: self.alert.component_id.as_deref(),
+                metric: self.alert.metric.as_str(),
+            }),
+            _ => {}
+        }
+        self.alert.firing = violated;
+    }
+}
+
+#[derive(Default)]
+struct Alerts {
+    next_id: i32,
+    rules: BTreeMap<i32, AlertState>,
+}
+
+impl Alerts {
+    fn add(&mut self, input: AlertInput) -> Result<Alert, String> {
+        if self.rules.len() >= MAX_ALERT_RULES {
+            return Err(format!(
+                "Can't register more than {} alert rules",
+                MAX_ALERT_RULES
+            ));
+        }
+
+        self.next_id += 1;
+        let alert = Alert {
+            id: self.next_id,
+            component_id: input.component_id,
+            metric: input.metric,
+            threshold: input.threshold,
+            window_secs: input.window_secs,
+            rate: None,
+            firing: false,
+        };
+        self.rules.insert(alert.id, AlertState::new(alert.clone()));
+        Ok(alert)
+    }
+
+    fn remove(&mut self, id: i32) -> bool {
+        self.rules.remove(&id).is_some()
+    }
+
+    fn list(&self) -> Vec<Alert> {
+        self.rules
+            .values()
+            .map(|state| state.alert.clone())
+            .collect()
+    }
+
+    fn evaluate(&mut self, metrics: &[Metric], now: Instant) {
+        for state in self.rules.values_mut() {
+            state.evaluate(metrics, now);
+        }
+    }
+}
+
+/// Alert rules registered through the API, shared by all connections to the API server.
+#[derive(Clone, Default)]
+pub struct AlertRules(Arc<Mutex<Alerts>>);
+
+impl AlertRules {
+    fn lock(&self) -> std::sync::MutexGuard<'_, Alerts> {
+        self.0.lock().expect(INVARIANT)
+    }
+}
+
+/// Evaluates `rules` against the internal metrics, until `shutdown` resolves.
+pub async fn run(rules: AlertRules, mut shutdown: ShutdownRx) {
+    let controller = match Controller::get() {
+        Ok(controller) => controller,
+        Err(_) => return,
+    };
+
+    let mut interval = time::interval(EVALUATION_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            _ = interval.tick() => {
+                if rules.lock().rules.is_empty() {
+                    continue;
+                }
+                // Capturing metrics walks the whole registry, so it's done before taking the
+                // lock to avoid blocking the API resolvers meanwhile.
+                let metrics = controller.capture_metrics();
+                rules.lock().evaluate(&metrics, Instant::now());
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct AlertsQuery;
+
+#[Object]
+impl AlertsQuery {
+    /// Alert rules, and whether they're currently firing
+    async fn alerts(&self, ctx: &Context<'_>) -> Vec<Alert> {
+        ctx.data_unchecked::<AlertRules>().lock().list()
+    }
+}
+
+#[derive(Default)]
+pub struct AlertsMutation;
+
+#[Object]
+impl AlertsMutation {
+    /// Adds an alert rule, which emits a log when its metric goes past its threshold
+    async fn add_alert(
+        &self,
+        ctx: &Context<'_>,
+        input: AlertInput,
+    ) -> async_graphql::Result<Alert> {
+        if let Some(id) = &input.component_id {
+            if components::state::component_by_component_key(&ComponentKey::from(id.as_str()))
+                .is_none()
+            {
+                return Err(format!("Component {:?} doesn't exist", id).into());
+            }
+        }
+        Ok(ctx.data_unchecked::<AlertRules>().lock().add(input)?)
+    }
+
+    /// Removes the alert rule with the provided `id`, and returns whether it existed
+    async fn remove_alert(&self, ctx: &Context<'_>, id: i32) -> bool {
+        ctx.data_unchecked::<AlertRules>().lock().remove(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_core::{event::MetricKind, metric_tags};
+
+    use super::*;
+
+    fn counter(name: &str, component_id: &str, value: f64) -> Metric {
+        Metric::new(name, MetricKind::Absolute, MetricValue::Counter { value })
+            .with_tags(Some(metric_tags!("component_id" => component_id)))
+    }
+
+    fn input(component_id: Option<&str>, metric: AlertMetric, threshold: f64) -> AlertInput {
+        AlertInput {
+            component_id: component_id.map(Into::into),
+            metric,
+            threshold,
+            window_secs: 10,
+        }
+    }
+
+    #[test]
+    fn fires_on_error_rate_above_threshold() {
+        let mut alerts = Alerts::default();
+        let id = alerts
+            .add(input(Some("in"), AlertMetric::ErrorRate, 1.0))
+            .unwrap()
+            .id;
+        let start = Instant::now();
+
+        alerts.evaluate(&[counter("component_errors_total", "in", 0.0)], start);
+        assert_eq!(alerts.list()[0].rate, None);
+
+        // Errors of other components are ignored.
+        alerts.evaluate(
+            &[
+                counter("component_errors_total", "in", 5.0),
+                counter("component_errors_total", "out", 100.0),
+            ],
+            start + Duration::from_secs(10),
+        );
+        let alert = &alerts.list()[0];
+        assert_eq!(alert.rate, Some(0.5));
+        assert!(!alert.firing);
+
+        alerts.evaluate(
+            &[counter("component_errors_total", "in", 25.0)],
+            start + Duration::from_secs(20),
+        );
+        let alert = &alerts.list()[0];
+        assert_eq!(alert.rate, Some(2.0));
+        assert!(alert.firing);
+
+        assert!(alerts.remove(id));
+        assert!(alerts.list().is_empty());
+    }
+
+    #[test]
+    fn fires_on_aggregate_throughput_below_threshold() {
+        let mut alerts = Alerts::default();
+        alerts
+            .add(input(None, AlertMetric::Throughput, 10.0))
+            .unwrap();
+        let start = Instant::now();
+
+        alerts.evaluate(
+            &[
+                counter("component_sent_events_total", "a", 0.0),
+                counter("component_sent_events_total", "b", 0.0),
+            ],
+            start,
+        );
+        alerts.evaluate(
+            &[
+                counter("component_sent_events_total", "a", 100.0),
+                counter("component_sent_events_total", "b", 100.0),
+            ],
+            start + Duration::from_secs(10),
+        );
+        assert!(!alerts.list()[0].firing);
+
+        alerts.evaluate(
+            &[
+                counter("component_sent_events_total", "a", 110.0),
+                counter("component_sent_events_total", "b", 110.0),
+            ],
+            start + Duration::from_secs(20),
+        );
+        let alert = &alerts.list()[0];
+        assert_eq!(alert.rate, Some(2.0));
+        assert!(alert.firing);
+    }
+
+    #[test]
+    fn restarts_window_on_counter_reset() {
+        let mut alerts = Alerts::default();
+        alerts
+            .add(input(Some("in"), AlertMetric::ErrorRate, 1.0))
+            .unwrap();
+        let start = Instant::now();
+
+        alerts.evaluate(&[counter("component_errors_total", "in", 50.0)], start);
+        alerts.evaluate(
+            &[counter("component_errors_total", "in", 0.0)],
+            start + Duration::from_secs(10),
+        );
+
+        let alert = &alerts.list()[0];
+        assert_eq!(alert.rate, None);
+        assert!(!alert.firing);
+    }
+
+    #[test]
+    fn caps_number_of_rules() {
+        let mut alerts = Alerts::default();
+        for _ in 0..MAX_ALERT_RULES {
+            alerts
+                .add(input(None, AlertMetric::Throughput, 10.0))
+                .unwrap();
+        }
+        assert!(alerts
+            .add(input(None, AlertMetric::Throughput, 10.0))
+            .is_err());
+
+        // Removing a rule makes room for another one.
+        assert!(alerts.remove(1));
+        assert!(alerts
+            .add(input(None, AlertMetric::Throughput, 10.0))
+            .is_ok());
+    }
+}
@@ -0,0 +1,253 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use async_graphql::SimpleObject;
+use vector_core::ByteSizeOf;
+
+use super::{log::Log, output::OutputEventsPayload};
+use crate::event::{LogEvent, Value};
+
+/// Number of versions of log events held on to, to be diffed against the later versions of the
+/// same events.
+const MAX_VERSIONS: usize = 10_000;
+
+/// Total size, in bytes, of the versions of log events held on to. The oldest versions are let go
+/// of first, and versions larger than this aren't held on to at all.
+const MAX_VERSIONS_BYTES: usize = 8 * 1024 * 1024;
+
+#[derive(Debug, Clone, SimpleObject)]
+/// A field that differs between two versions of a log event
+pub struct FieldDiff {
+    /// Path of the field
+    path: String,
+    /// Value of the field in the version of the log event that was input to the component
+    before: Option<Value>,
+    /// Value of the field in the version of the log event that was output by the component
+    after: Option<Value>,
+}
+
+#[derive(Debug, Clone, SimpleObject)]
+/// Structural diff between the version of a log event that was input to a component and the
+/// version it output
+pub struct LogDiff {
+    /// Id of the component that output the version of the log event that was input to the component
+    input_component_id: String,
+    /// Fields only in the output version
+    added: Vec<FieldDiff>,
+    /// Fields only in the input version
+    removed: Vec<FieldDiff>,
+    /// Fields in both versions, with different values
+    changed: Vec<FieldDiff>,
+}
+
+impl LogDiff {
+    pub(super) fn new(input: &Log, output: &LogEvent) -> Self {
+        let before = input
+            .event()
+            .convert_to_fields()
+            .collect::<BTreeMap<_, _>>();
+        let after = output.convert_to_fields().collect::<BTreeMap<_, _>>();
+
+        let mut added = Vec::new();
+        let mut changed = Vec::new();
+        for (path, value) in &after {
+            match before.get(path) {
+                None => added.push(FieldDiff {
+                    path: path.clone(),
+                    before: None,
+                    after: Some((*value).clone()),
+                }),
+                Some(previous) if previous != value => changed.push(FieldDiff {
+                    path: path.clone(),
+                    before: Some((*previous).clone()),
+                    after: Some((*value).clone()),
+                }),
+                Some(_) => {}
+            }
+        }
+        let removed = before
+            .into_iter()
+            .filter(|(path, _)| !after.contains_key(path))
+            .map(|(path, value)| FieldDiff {
+                path,
+                before: Some(value.clone()),
+                after: None,
+            })
+            .collect();
+
+        Self {
+            input_component_id: input.output().output_id.component.id().to_string(),
+            added,
+            removed,
+            changed,
+        }
+    }
+}
+
+/// The most recently tapped versions of log events, by event identifier, used to pair each log
+/// event with the version of it that was input to the component that output it.
+#[derive(Default)]
+pub(super) struct Versions {
+    /// The latest version of each event, with its sequence number and size.
+    logs: HashMap<u64, (u64, usize, Log)>,
+    /// The identifiers and sequence numbers of the versions, from the oldest to the newest.
+    order: VecDeque<(u64, u64)>,
+    sequence: u64,
+    bytes: usize,
+}
+
+impl Versions {
+    /// Records the log event of `payload`, if any, attaching the latest version of the same event
+    /// that was output by another component.
+    pub(super) fn correlate(&mut self, mut payload: OutputEventsPayload) -> OutputEventsPayload {
+        if let OutputEventsPayload::Log(log) = &mut payload {
+            if let Some(id) = log.event().metadata().id() {
+                if let Some((_, _, input)) = self.logs.get(&id) {
+                    if input.output().output_id.component != log.output().output_id.component {
+                        log.set_input(input.clone());
+                    }
+                }
+                self.record(id, log.clone());
+            }
+        }
+        payload
+    }
+
+    fn record(&mut self, id: u64, mut log: Log) {
+        // The previous version of the event is no longer needed either way.
+        if let Some((_, size, _)) = self.logs.remove(&id) {
+            self.bytes -= size;
+        }
+
+        let size = log.event().size_of();
+        if size > MAX_VERSIONS_BYTES {
+            return;
+        }
+        while self.order.len() >= MAX_VERSIONS || self.bytes + size > MAX_VERSIONS_BYTES {
+            match self.order.pop_front() {
+                Some((id, sequence)) => self.evict(id, sequence),
+                None => break,
+            }
+        }
+
+        self.sequence += 1;
+        log.clear_input();
+        self.bytes += size;
+        self.logs.insert(id, (self.sequence, size, log));
+        self.order.push_back((id, self.sequence));
+    }
+
+    fn evict(&mut self, id: u64, sequence: u64) {
+        // The event may have been recorded again since, or let go of already.
+        if matches!(self.logs.get(&id), Some((latest, _, _)) if *latest == sequence) {
+            if let Some((_, size, _)) = self.logs.remove(&id) {
+                self.bytes -= size;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::OutputId, topology::TapOutput};
+
+    fn log(component: &str, event: &LogEvent) -> OutputEventsPayload {
+        let output = TapOutput {
+            output_id: OutputId::from(component),
+            component_kind: "transform",
+            component_type: "remap".to_string(),
+        };
+        OutputEventsPayload::Log(Log::new(output, event.clone()))
+    }
+
+    fn diff(payload: &OutputEventsPayload) -> Option<LogDiff> {
+        match payload {
+            OutputEventsPayload::Log(log) => {
+                log.input().map(|input| LogDiff::new(input, log.event()))
+            }
+            _ => panic!("Expected payload to be a Log"),
+        }
+    }
+
+    fn fields(fields: &[FieldDiff]) -> Vec<(&str, Option<&Value>, Option<&Value>)> {
+        fields
+            .iter()
+            .map(|field| {
+                (
+                    field.path.as_str(),
+                    field.before.as_ref(),
+                    field.after.as_ref(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn diffs_versions_of_the_same_event() {
+        let mut input = LogEvent::default();
+        input.insert("message", "hello");
+        input.insert("host", "localhost");
+        input.insert("nested.level", "info");
+        input.metadata_mut().assign_id();
+
+        let mut output = input.clone();
+        output.insert("message", "hello world");
+        output.remove("host");
+        output.insert("nested.code", 200);
+
+        let mut other = LogEvent::default();
+        other.metadata_mut().assign_id();
+
+        let mut versions = Versions::default();
+        assert!(diff(&versions.correlate(log("in", &input))).is_none());
+        assert!(diff(&versions.correlate(log("other", &other))).is_none());
+        let changes = diff(&versions.correlate(log("remap", &output))).unwrap();
+
+        assert_eq!(changes.input_component_id, "in");
+        assert_eq!(
+            fields(&changes.added),
+            vec![("nested.code", None, Some(&Value::from(200)))]
+        );
+        assert_eq!(
+            fields(&changes.removed),
+            vec![("host", Some(&Value::from("localhost")), None)]
+        );
+        assert_eq!(
+            fields(&changes.changed),
+            vec![(
+                "message",
+                Some(&Value::from("hello")),
+                Some(&Value::from("hello world"))
+            )]
+        );
+    }
+
+    #[test]
+    fn bounds_the_size_of_versions() {
+        let mut versions = Versions::default();
+        let mut first = LogEvent::from("x".repeat(MAX_VERSIONS_BYTES / 2));
+        first.metadata_mut().assign_id();
+        let mut second = LogEvent::from("x".repeat(MAX_VERSIONS_BYTES / 2));
+        second.metadata_mut().assign_id();
+        let mut third = LogEvent::from("x".repeat(MAX_VERSIONS_BYTES / 4));
+        third.metadata_mut().assign_id();
+
+        for event in [&first, &second, &third] {
+            versions.correlate(log("in", event));
+        }
+
+        // The first version is let go of to make room for the others.
+        assert!(versions.bytes <= MAX_VERSIONS_BYTES);
+        assert!(diff(&versions.correlate(log("remap", &first))).is_none());
+        assert!(diff(&versions.correlate(log("remap", &third))).is_some());
+    }
+
+    #[test]
+    fn ignores_events_without_id() {
+        let event = LogEvent::from("hello");
+
+        let mut versions = Versions::default();
+        versions.correlate(log("in", &event));
+        assert!(diff(&versions.correlate(log("remap", &event))).is_none());
+    }
+}
@@ -0,0 +1,363 @@
+use std::collections::HashSet;
+
+use async_graphql::{Context, Object};
+use indexmap::IndexMap;
+use tokio::{select, sync::mpsc, time};
+
+use super::TapPatterns;
+use crate::{
+    api::tap::{TapController, TapPayload},
+    config::{ComponentKey, OutputId},
+    event::{LogEvent, Value},
+    topology::WatchRx,
+};
+
+#[derive(Debug, Default)]
+pub struct EventsQuery;
+
+#[Object]
+impl EventsQuery {
+    /// Captures log events flowing into, and optionally out of, a transform, and returns them
+    /// as a unit test definition (TOML) that can be added to a configuration and run with
+    /// `vector test`. Captured output events are asserted with VRL conditions.
+    ///
+    /// Capturing stops once `limit` input events (and, if requested, `limit` output events) have
+    /// been seen, or after `timeout` milliseconds, whichever comes first. Since tapped inputs and
+    /// outputs are not correlated, the generated fixture should be reviewed before use.
+    ///
+    /// Unit test inputs can only hold strings, numbers and booleans, so timestamps and regular
+    /// expressions are inserted as strings, and null values and empty arrays or objects are left
+    /// out. Output events are asserted field by field, leaving out the fields holding such values.
+    async fn tap_test_fixture(
+        &self,
+        ctx: &Context<'_>,
+        component_id: String,
+        name: Option<String>,
+        #[graphql(default = true)] include_outputs: bool,
+        #[graphql(default = 10, validator(minimum = 1, maximum = 1_000))] limit: u32,
+        #[graphql(default = 10_000, validator(minimum = 1, maximum = 300_000))] timeout: u32,
+    ) -> async_graphql::Result<String> {
+        let watch_rx = ctx.data_unchecked::<WatchRx>().clone();
+        let component_key = ComponentKey::from(component_id.clone());
+
+        let is_transform = watch_rx.borrow().outputs.keys().any(|output| {
+            output.output_id.component == component_key && output.component_kind == "transform"
+        });
+        if !is_transform {
+            return Err(format!(
+                "Component \"{}\" is not a transform in the running topology.",
+                component_id
+            )
+            .into());
+        }
+
+        let (inputs, outputs) = capture_events(
+            watch_rx,
+            component_key,
+            include_outputs,
+            limit as usize,
+            time::Duration::from_millis(timeout as u64),
+        )
+        .await;
+
+        if inputs.is_empty() {
+            return Err(format!(
+                "No log events flowed into \"{}\" before the timeout elapsed.",
+                component_id
+            )
+            .into());
+        }
+
+        let name = name.unwrap_or_else(|| format!("{} tapped events", component_id));
+        Ok(build_test_fixture(&name, &component_id, &inputs, &outputs)?)
+    }
+}
+
+/// Taps the inputs and outputs of `component_key`, until enough events have been captured or
+/// `timeout` elapses.
+async fn capture_events(
+    watch_rx: WatchRx,
+    component_key: ComponentKey,
+    include_outputs: bool,
+    limit: usize,
+    timeout: time::Duration,
+) -> (Vec<LogEvent>, Vec<(OutputId, LogEvent)>) {
+    let (tap_tx, mut tap_rx) = mpsc::channel(limit);
+
+    let id = component_key.id().to_string();
+    let for_outputs = if include_outputs {
+        HashSet::from([id.clone()])
+    } else {
+        HashSet::new()
+    };
+    let patterns = TapPatterns::new(for_outputs, HashSet::from([id]));
+
+    // The tap is removed when the controller drops out of scope.
+    let _tap_controller = TapController::new(watch_rx, tap_tx, patterns);
+
+    let mut inputs = Vec::with_capacity(limit);
+    let mut outputs = Vec::with_capacity(limit);
+
+    let deadline = time::sleep(timeout);
+    tokio::pin!(deadline);
+
+    while inputs.len() < limit || (include_outputs && outputs.len() < limit) {
+        select! {
+            payload = tap_rx.recv() => match payload {
+                // Inputs are tapped at the outputs of upstream components, so anything that
+                // wasn't emitted by the component itself was sent to it.
+                Some(TapPayload::Log(output, logs)) => {
+                    if output.output_id.component == component_key {
+                        let remaining = limit - outputs.len();
+                        outputs.extend(
+                            logs.into_iter()
+                                .take(remaining)
+                                .map(|log| (output.output_id.clone(), log)),
+                        );
+                    } else {
+                        let remaining = limit - inputs.len();
+                        inputs.extend(logs.into_iter().take(remaining));
+                    }
+                }
+                Some(_) => {}
+                None => break,
+            },
+            _ = &mut deadline => break,
+        }
+    }
+
+    (inputs, outputs)
+}
+
+/// Builds a unit test definition which inserts `inputs` into `component_id`, and expects each of
+/// the events in `outputs` to be emitted from the output it was tapped from.
+fn build_test_fixture(
+    name: &str,
+    component_id: &str,
+    inputs: &[LogEvent],
+    outputs: &[(OutputId, LogEvent)],
+) -> Result<String, toml::ser::Error> {
+    let inputs = inputs
+        .iter()
+        .map(|log| {
+            let log_fields = log
+                .all_fields()
+                .into_iter()
+                .flatten()
+                .filter_map(|(path, value)| Some((path, test_input_value(value)?)))
+                .collect::<toml::value::Table>();
+
+            let mut input = toml::value::Table::new();
+            input.insert("insert_at".into(), component_id.into());
+            input.insert("type".into(), "log".into());
+            input.insert("log_fields".into(), log_fields.into());
+            toml::Value::Table(input)
+        })
+        .collect::<Vec<_>>();
+
+    let mut outputs_by_id = IndexMap::<String, Vec<&LogEvent>>::new();
+    for (output_id, log) in outputs {
+        outputs_by_id
+            .entry(output_id.to_string())
+            .or_default()
+            .push(log);
+    }
+
+    let outputs = outputs_by_id
+        .into_iter()
+        .map(|(extract_from, logs)| {
+            let conditions = logs
+                .into_iter()
+                .map(|log| {
+                    let mut condition = toml::value::Table::new();
+                    condition.insert("type".into(), "vrl".into());
+                    condition.insert("source".into(), test_output_condition(log).into());
+                    toml::Value::Table(condition)
+                })
+                .collect::<Vec<_>>();
+
+            let mut output = toml::value::Table::new();
+            output.insert("extract_from".into(), extract_from.into());
+            output.insert("conditions".into(), conditions.into());
+            toml::Value::Table(output)
+        })
+        .collect::<Vec<_>>();
+
+    let mut test = toml::value::Table::new();
+    test.insert("name".into(), name.into());
+    test.insert("inputs".into(), inputs.into());
+    if !outputs.is_empty() {
+        test.insert("outputs".into(), outputs.into());
+    }
+
+    let mut fixture = toml::value::Table::new();
+    fixture.insert("tests".into(), vec![toml::Value::Table(test)].into());
+    toml::to_string(&fixture)
+}
+
+/// Builds a VRL condition asserting the value of each field of `log` that a unit test input can
+/// hold as is. The other fields can't be reproduced by the inputs of the test, so they aren't
+/// asserted.
+fn test_output_condition(log: &LogEvent) -> String {
+    let assertions = log
+        .all_fields()
+        .into_iter()
+        .flatten()
+        .filter_map(|(path, value)| Some(format!(".{} == {}", path, vrl_literal(value)?)))
+        .collect::<Vec<_>>();
+
+    if assertions.is_empty() {
+        "true".to_string()
+    } else {
+        assertions.join(" &&\n")
+    }
+}
+
+/// Formats a value that a unit test input can hold as is as a VRL literal.
+fn vrl_literal(value: &Value) -> Option<String> {
+    match value {
+        Value::Bytes(_) | Value::Integer(_) | Value::Boolean(_) => Some(value.to_string()),
+        // Unlike `Display`, `Debug` keeps the decimal point of whole numbers, which VRL would
+        // otherwise parse as integers.
+        Value::Float(float) => Some(format!("{:?}", float.into_inner())),
+        Value::Timestamp(_)
+        | Value::Regex(_)
+        | Value::Null
+        | Value::Object(_)
+        | Value::Array(_) => None,
+    }
+}
+
+/// Converts a log field to a value that can be used in a unit test input. Unit test inputs only
+/// support scalar values, so timestamps and regular expressions are inserted as strings, and null
+/// values and empty arrays or objects are left out.
+fn test_input_value(value: &Value) -> Option<toml::Value> {
+    match value {
+        Value::Bytes(bytes) => Some(String::from_utf8_lossy(bytes).into_owned().into()),
+        Value::Integer(integer) => Some((*integer).into()),
+        Value::Float(float) => Some(float.into_inner().into()),
+        Value::Boolean(boolean) => Some((*boolean).into()),
+        Value::Timestamp(timestamp) => Some(
+            timestamp
+                .to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true)
+                .into(),
+        ),
+        Value::Regex(regex) => Some(regex.as_str().to_string().into()),
+        Value::Null | Value::Object(_) | Value::Array(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{unit_test::build_unit_tests, ConfigBuilder, TestDefinition};
+
+    #[test]
+    fn builds_loadable_test_fixture() {
+        let mut input = LogEvent::from("foo");
+        input.insert("nested.count", 2);
+
+        let mut output = input.clone();
+        output.insert("processed", true);
+
+        let fixture = build_test_fixture(
+            "remap test",
+            "remap",
+            &[input],
+            &[(OutputId::from(ComponentKey::from("remap")), output)],
+        )
+        .unwrap();
+
+        let builder: ConfigBuilder = toml::from_str(&fixture).unwrap();
+        let test: &TestDefinition<String> = &builder.tests[0];
+        assert_eq!(test.name, "remap test");
+        assert_eq!(test.inputs.len(), 1);
+        assert_eq!(test.inputs[0].insert_at, ComponentKey::from("remap"));
+        assert_eq!(test.inputs[0].type_str, "log");
+
+        let log_fields = test.inputs[0].log_fields.as_ref().unwrap();
+        assert!(log_fields.contains_key("message"));
+        assert!(log_fields.contains_key("nested.count"));
+
+        assert_eq!(test.outputs.len(), 1);
+        assert_eq!(test.outputs[0].conditions.as_ref().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn generated_fixture_passes_vector_test() {
+        let mut input = LogEvent::from("foo");
+        input.insert("nested.count", 2);
+        input.insert("nested.ratio", 1.0);
+        input.insert("tags", Value::Array(vec!["a".into(), "b".into()]));
+        input.insert("empty", Value::Array(Vec::new()));
+        input.insert("missing", Value::Null);
+        input.insert("quoted", "a \"quoted\"\nvalue");
+
+        // The remap transform below sets `processed`, and leaves the rest of the event as is.
+        let mut output = input.clone();
+        output.insert("processed", true);
+
+        let fixture = build_test_fixture(
+            "remap test",
+            "remap",
+            &[input],
+            &[(OutputId::from(ComponentKey::from("remap")), output)],
+        )
+        .unwrap();
+
+        let config = format!(
+            indoc::indoc! {r#"
+                [transforms.remap]
+                  inputs = ["in"]
+                  type = "remap"
+                  source = ".processed = true"
+
+                {}
+            "#},
+            fixture
+        );
+        let builder: ConfigBuilder = toml::from_str(&config).unwrap();
+        let mut tests = build_unit_tests(builder).await.unwrap();
+        let result = tests.remove(0).run().await;
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+    }
+
+    #[tokio::test]
+    async fn generated_fixture_fails_on_changed_outputs() {
+        let input = LogEvent::from("foo");
+        let mut output = input.clone();
+        output.insert("processed", false);
+
+        let fixture = build_test_fixture(
+            "remap test",
+            "remap",
+            &[input],
+            &[(OutputId::from(ComponentKey::from("remap")), output)],
+        )
+        .unwrap();
+
+        let config = format!(
+            indoc::indoc! {r#"
+                [transforms.remap]
+                  inputs = ["in"]
+                  type = "remap"
+                  source = ".processed = true"
+
+                {}
+            "#},
+            fixture
+        );
+        let builder: ConfigBuilder = toml::from_str(&config).unwrap();
+        let mut tests = build_unit_tests(builder).await.unwrap();
+        assert!(!tests.remove(0).run().await.errors.is_empty());
+    }
+
+    #[test]
+    fn leaves_out_outputs_when_none_were_captured() {
+        let fixture =
+            build_test_fixture("remap test", "remap", &[LogEvent::from("foo")], &[]).unwrap();
+
+        let builder: ConfigBuilder = toml::from_str(&fixture).unwrap();
+        assert!(builder.tests[0].outputs.is_empty());
+    }
+}
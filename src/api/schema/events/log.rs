@@ -4,7 +4,7 @@ use async_graphql::Object;
 use chrono::{DateTime, Utc};
 use vector_common::encode_logfmt;
 
-use super::EventEncodingType;
+use super::{diff::LogDiff, EventEncodingType};
 use crate::{
     event::{self, Value},
     topology::TapOutput,
@@ -14,11 +14,37 @@ use crate::{
 pub struct Log {
     output: TapOutput,
     event: event::LogEvent,
+    input: Option<Box<Log>>,
 }
 
 impl Log {
     pub const fn new(output: TapOutput, event: event::LogEvent) -> Self {
-        Self { output, event }
+        Self {
+            output,
+            event,
+            input: None,
+        }
+    }
+
+    pub(super) const fn output(&self) -> &TapOutput {
+        &self.output
+    }
+
+    pub(super) const fn event(&self) -> &event::LogEvent {
+        &self.event
+    }
+
+    /// Sets the version of this event output by the component it was input to.
+    pub(super) fn set_input(&mut self, input: Log) {
+        self.input = Some(Box::new(input));
+    }
+
+    pub(super) fn input(&self) -> Option<&Log> {
+        self.input.as_deref()
+    }
+
+    pub(super) fn clear_input(&mut self) {
+        self.input = None;
     }
 
     pub fn get_message(&self) -> Option<Cow<'_, str>> {
@@ -74,4 +100,11 @@ impl Log {
     async fn json(&self, field: String) -> Option<&Value> {
         self.event.get(field.as_str())
     }
+
+    /// Fields added, removed, or changed since the version of the log event that was input to the
+    /// component. Only available when the output of the component the event was input to was
+    /// tapped too, for example by tapping both the inputs and the outputs of a transform
+    async fn diff(&self) -> Option<LogDiff> {
+        self.input().map(|input| LogDiff::new(input, &self.event))
+    }
 }
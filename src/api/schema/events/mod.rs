@@ -1,21 +1,41 @@
+mod diff;
 mod encoding;
+mod fixture;
 pub mod log;
 pub mod metric;
 pub mod notification;
 pub mod output;
+mod sampling;
+mod session;
 pub mod trace;
 
-use std::collections::HashSet;
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use async_graphql::{Context, Subscription};
+use diff::Versions;
 use encoding::EventEncodingType;
-use futures::{stream, Stream, StreamExt};
+pub use fixture::EventsQuery;
+use futures::{stream, stream::BoxStream, Stream, StreamExt};
+use notification::{EventNotification, EventsDropped, Notification, ResumeFailed};
 use output::OutputEventsPayload;
 use rand::{rngs::SmallRng, Rng, SeedableRng};
+pub use sampling::{Sampler, TapSampling};
 use tokio::{select, sync::mpsc, time};
 use tokio_stream::wrappers::ReceiverStream;
 
-use crate::{api::tap::TapController, topology::WatchRx};
+use crate::{
+    api::tap::{TapController, TapPayload},
+    conditions::{AnyCondition, Condition},
+    config,
+    event::Event,
+    internal_events::ApiTapEventsDropped,
+    source_sender::enable_event_ids,
+    topology::WatchRx,
+};
 
 /// Patterns (glob) used by tap to match against components and access events
 /// flowing into (for_inputs) or out of (for_outputs) specified components
@@ -48,7 +68,19 @@ pub struct EventsSubscription;
 
 #[Subscription]
 impl EventsSubscription {
-    /// A stream of events emitted from matched component ID patterns
+    /// A stream of events emitted from matched component ID patterns, optionally narrowed down to
+    /// those matching a VRL `filter` condition.
+    ///
+    /// Passing the `cursor` of the last `CursorAdvanced` notification received resumes a tap that
+    /// was recently disconnected, with the options it was started with, instead of starting anew.
+    ///
+    /// Busy components can be sampled on the server by keeping only 1 in every `sampleRate`
+    /// events, or at most `maxEventsPerSec` events per second, across all matched components.
+    /// Events are sampled as soon as they are tapped, before `filter` is applied. The number of
+    /// events dropped this way is sent in an `EventsDropped` notification with each batch.
+    ///
+    /// When both the inputs and the outputs of a transform are tapped, the `diff` of each log
+    /// event it outputs holds the fields it changed in the event
     pub async fn output_events_by_component_id_patterns<'a>(
         &'a self,
         ctx: &'a Context<'a>,
@@ -56,42 +88,108 @@ impl EventsSubscription {
         inputs_patterns: Option<Vec<String>>,
         #[graphql(default = 500)] interval: u32,
         #[graphql(default = 100, validator(minimum = 1, maximum = 10_000))] limit: u32,
-    ) -> impl Stream<Item = Vec<OutputEventsPayload>> + 'a {
+        filter: Option<String>,
+        cursor: Option<String>,
+        #[graphql(validator(minimum = 1))] sample_rate: Option<u32>,
+        #[graphql(validator(minimum = 1))] max_events_per_sec: Option<u32>,
+    ) -> async_graphql::Result<BoxStream<'static, Vec<OutputEventsPayload>>> {
         let watch_rx = ctx.data_unchecked::<WatchRx>().clone();
+        let resume_timeout_secs = ctx
+            .data_opt::<config::api::Options>()
+            .map(|options| options.tap_resume_timeout_secs)
+            .unwrap_or_default();
+
+        let mut resume_failed = None;
+        if let Some(cursor) = cursor {
+            if let Some(events) = session::resume(cursor.parse()?).await {
+                return Ok(events.boxed());
+            }
+            resume_failed = Some(cursor);
+        }
 
         let patterns = TapPatterns {
             for_outputs: outputs_patterns.into_iter().collect(),
             for_inputs: inputs_patterns.unwrap_or_default().into_iter().collect(),
         };
+        let filter = filter
+            .map(|filter| AnyCondition::String(filter).build(&Default::default()))
+            .transpose()
+            .map_err(|error| format!("Invalid filter: {}", error))?;
         // Client input is confined to `u32` to provide sensible bounds.
-        create_events_stream(watch_rx, patterns, interval as u64, limit as usize)
+        let sampling = TapSampling {
+            rate: sample_rate,
+            max_events_per_sec,
+        };
+        let events = create_events_stream(
+            watch_rx,
+            patterns,
+            interval as u64,
+            limit as usize,
+            filter,
+            sampling,
+        );
+        let events = if resume_timeout_secs > 0 {
+            session::start(Box::pin(events), Duration::from_secs(resume_timeout_secs)).boxed()
+        } else {
+            events.boxed()
+        };
+
+        Ok(match resume_failed {
+            Some(cursor) => stream::once(async move {
+                vec![OutputEventsPayload::Notification(EventNotification {
+                    notification: Notification::ResumeFailed(ResumeFailed::new(cursor)),
+                })]
+            })
+            .chain(events)
+            .boxed(),
+            None => events,
+        })
     }
 }
 
 /// Creates an events stream based on component ids, and a provided interval. Will emit
 /// control messages that bubble up the application if the sink goes away. The stream contains
-/// all matching events; filtering should be done at the caller level.
+/// all matching events kept by `sampling`, which drops them as soon as they are tapped. Of those,
+/// only the events passing `filter`, if given, are encoded. Log events are paired with the
+/// version of them tapped from the component they were input to, if any.
 pub(crate) fn create_events_stream(
     watch_rx: WatchRx,
     patterns: TapPatterns,
     interval: u64,
     limit: usize,
+    filter: Option<Condition>,
+    sampling: TapSampling,
 ) -> impl Stream<Item = Vec<OutputEventsPayload>> {
     // Channel for receiving individual tap payloads. Since we can process at most `limit` per
     // interval, this is capped to the same value.
     let (tap_tx, tap_rx) = mpsc::channel(limit);
+    let mut versions = Versions::default();
     let mut tap_rx = ReceiverStream::new(tap_rx)
-        .flat_map(|payload| stream::iter(<Vec<OutputEventsPayload>>::from(payload)));
+        .map(move |payload| match &filter {
+            Some(filter) => filter_payload(filter, payload),
+            None => payload,
+        })
+        .flat_map(|payload| stream::iter(<Vec<OutputEventsPayload>>::from(payload)))
+        .map(move |payload| versions.correlate(payload));
 
     // The resulting vector of `Event` sent to the client. Only one result set will be streamed
     // back to the client at a time. This value is set higher than `1` to prevent blocking the event
     // pipeline on slower client connections, but low enough to apply a modest cap on mem usage.
     let (event_tx, event_rx) = mpsc::channel::<Vec<OutputEventsPayload>>(10);
 
+    // Drops events before they're sent by the tap sinks, as requested by the client.
+    let sampler = sampling
+        .is_enabled()
+        .then(|| Arc::new(Mutex::new(Sampler::new(sampling))));
+
     tokio::spawn(async move {
+        // Events are identified, so that their versions can be correlated, only while tapped.
+        let _event_ids = enable_event_ids();
+
         // Create a tap controller. When this drops out of scope, clean up will be performed on the
         // event handlers and topology observation that the tap controller provides.
-        let _tap_controller = TapController::new(watch_rx, tap_tx, patterns);
+        let _tap_controller =
+            TapController::with_sampler(watch_rx, tap_tx, patterns, sampler.clone());
 
         // A tick interval to represent when to 'cut' the results back to the client.
         let mut interval = time::interval(time::Duration::from_millis(interval));
@@ -150,6 +248,20 @@ pub(crate) fn create_events_stream(
                     }
                 }
                 _ = interval.tick() => {
+                    let dropped = sampler.as_ref().map_or(0, |sampler| {
+                        sampler.lock().expect("tap sampler lock poisoned").take_dropped()
+                    });
+                    if dropped > 0 {
+                        emit!(ApiTapEventsDropped { count: dropped });
+                        let notification = OutputEventsPayload::Notification(EventNotification {
+                            notification: Notification::EventsDropped(EventsDropped::new(dropped)),
+                        });
+                        if let Err(err) = event_tx.send(vec![notification]).await {
+                            debug!(message = "Couldn't send notification.", error = ?err);
+                            break;
+                        }
+                    }
+
                     // If there are any existing results after the interval tick, emit.
                     if !results.is_empty() {
                         // Reset the batch count, to adjust sampling probability for the next round.
@@ -176,3 +288,37 @@ pub(crate) fn create_events_stream(
 
     ReceiverStream::new(event_rx)
 }
+
+/// Drops the events of `payload` not matching `filter`. Notifications are kept as is.
+fn filter_payload(filter: &Condition, payload: TapPayload) -> TapPayload {
+    match payload {
+        TapPayload::Log(output, logs) => TapPayload::Log(
+            output,
+            filter_events(filter, logs, Event::Log, Event::into_log),
+        ),
+        TapPayload::Metric(output, metrics) => TapPayload::Metric(
+            output,
+            filter_events(filter, metrics, Event::Metric, Event::into_metric),
+        ),
+        TapPayload::Trace(output, traces) => TapPayload::Trace(
+            output,
+            filter_events(filter, traces, Event::Trace, Event::into_trace),
+        ),
+        TapPayload::Notification(notification) => TapPayload::Notification(notification),
+    }
+}
+
+fn filter_events<T>(
+    filter: &Condition,
+    events: Vec<T>,
+    into_event: impl Fn(T) -> Event,
+    from_event: impl Fn(Event) -> T,
+) -> Vec<T> {
+    events
+        .into_iter()
+        .filter_map(|event| match filter.check(into_event(event)) {
+            (true, event) => Some(from_event(event)),
+            (false, _) => None,
+        })
+        .collect()
+}
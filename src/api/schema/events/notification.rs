@@ -61,12 +61,74 @@ impl InvalidMatch {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, SimpleObject)]
+/// All events up to this point have been sent. Passing the cursor when subscribing again resumes
+/// the tap from here
+pub struct CursorAdvanced {
+    #[graphql(skip)]
+    message: String,
+    /// Cursor to resume the tap from
+    pub cursor: String,
+}
+
+impl CursorAdvanced {
+    pub fn new(cursor: String) -> Self {
+        Self {
+            message: format!("[tap] Events sent up to cursor '{}'.", cursor),
+            cursor,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, SimpleObject)]
+/// The tap couldn't be resumed from the provided cursor, as it is no longer running. A new tap
+/// was started instead, so events might have been missed
+pub struct ResumeFailed {
+    #[graphql(skip)]
+    message: String,
+    /// Cursor the tap failed to resume from
+    pub cursor: String,
+}
+
+impl ResumeFailed {
+    pub fn new(cursor: String) -> Self {
+        Self {
+            message: format!(
+                "[tap] Couldn't resume from cursor '{}': the tap has expired. Events may have been missed.",
+                cursor
+            ),
+            cursor,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, SimpleObject)]
+/// Events were dropped by server-side sampling since the previous batch
+pub struct EventsDropped {
+    #[graphql(skip)]
+    message: String,
+    /// Number of events dropped
+    pub count: u64,
+}
+
+impl EventsDropped {
+    pub fn new(count: u64) -> Self {
+        Self {
+            message: format!("[tap] {} events dropped by sampling.", count),
+            count,
+        }
+    }
+}
+
 #[derive(Union, Debug, Clone, PartialEq, Eq)]
 /// A specific kind of notification with additional details
 pub enum Notification {
     Matched(Matched),
     NotMatched(NotMatched),
     InvalidMatch(InvalidMatch),
+    CursorAdvanced(CursorAdvanced),
+    ResumeFailed(ResumeFailed),
+    EventsDropped(EventsDropped),
 }
 
 impl Notification {
@@ -75,6 +137,9 @@ impl Notification {
             Notification::Matched(n) => n.message.as_ref(),
             Notification::NotMatched(n) => n.message.as_ref(),
             Notification::InvalidMatch(n) => n.message.as_ref(),
+            Notification::CursorAdvanced(n) => n.message.as_ref(),
+            Notification::ResumeFailed(n) => n.message.as_ref(),
+            Notification::EventsDropped(n) => n.message.as_ref(),
         }
     }
 }
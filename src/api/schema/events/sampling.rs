@@ -0,0 +1,117 @@
+use tokio::time::{Duration, Instant};
+
+/// Server-side sampling of tapped events, which keeps busy components from overwhelming the
+/// subscription and the Vector process.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TapSampling {
+    /// Keep only 1 in every `rate` events.
+    pub rate: Option<u32>,
+
+    /// Keep at most this many events per second.
+    pub max_events_per_sec: Option<u32>,
+}
+
+impl TapSampling {
+    /// Whether any event is dropped at all.
+    pub const fn is_enabled(&self) -> bool {
+        self.rate.is_some() || self.max_events_per_sec.is_some()
+    }
+}
+
+/// Decides which events to keep according to a [`TapSampling`], counting those it drops.
+#[derive(Debug)]
+pub struct Sampler {
+    sampling: TapSampling,
+    seen: u64,
+    window_start: Instant,
+    window_kept: u32,
+    dropped: u64,
+}
+
+impl Sampler {
+    pub fn new(sampling: TapSampling) -> Self {
+        Self {
+            sampling,
+            seen: 0,
+            window_start: Instant::now(),
+            window_kept: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Returns whether an event seen at `now` is kept.
+    pub fn keep(&mut self, now: Instant) -> bool {
+        self.seen += 1;
+        if let Some(rate) = self.sampling.rate {
+            if (self.seen - 1) % u64::from(rate.max(1)) != 0 {
+                self.dropped += 1;
+                return false;
+            }
+        }
+
+        if let Some(max_events_per_sec) = self.sampling.max_events_per_sec {
+            if now.duration_since(self.window_start) >= Duration::from_secs(1) {
+                self.window_start = now;
+                self.window_kept = 0;
+            }
+            if self.window_kept >= max_events_per_sec {
+                self.dropped += 1;
+                return false;
+            }
+            self.window_kept += 1;
+        }
+
+        true
+    }
+
+    /// Returns the number of events dropped since the previous call.
+    pub fn take_dropped(&mut self) -> u64 {
+        std::mem::take(&mut self.dropped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kept(sampler: &mut Sampler, count: usize, now: Instant) -> usize {
+        (0..count).filter(|_| sampler.keep(now)).count()
+    }
+
+    #[test]
+    fn keeps_one_in_rate() {
+        let mut sampler = Sampler::new(TapSampling {
+            rate: Some(10),
+            max_events_per_sec: None,
+        });
+
+        assert_eq!(kept(&mut sampler, 95, Instant::now()), 10);
+        assert_eq!(sampler.take_dropped(), 85);
+        assert_eq!(sampler.take_dropped(), 0);
+    }
+
+    #[test]
+    fn keeps_at_most_max_events_per_sec() {
+        let mut sampler = Sampler::new(TapSampling {
+            rate: None,
+            max_events_per_sec: Some(100),
+        });
+        let start = Instant::now();
+
+        assert_eq!(kept(&mut sampler, 150, start), 100);
+        assert_eq!(
+            kept(&mut sampler, 10, start + Duration::from_millis(500)),
+            0
+        );
+        assert_eq!(kept(&mut sampler, 150, start + Duration::from_secs(1)), 100);
+        assert_eq!(sampler.take_dropped(), 110);
+    }
+
+    #[test]
+    fn keeps_everything_by_default() {
+        let mut sampler = Sampler::new(TapSampling::default());
+
+        assert_eq!(kept(&mut sampler, 1000, Instant::now()), 1000);
+        assert_eq!(sampler.take_dropped(), 0);
+    }
+}
@@ -0,0 +1,278 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    str::FromStr,
+    sync::Mutex,
+    time::Duration,
+};
+
+use futures::{Stream, StreamExt};
+use once_cell::sync::Lazy;
+use tokio::{
+    sync::mpsc,
+    time::{sleep_until, Instant},
+};
+use tokio_stream::wrappers::ReceiverStream;
+use uuid::Uuid;
+
+use super::{
+    notification::{CursorAdvanced, EventNotification, Notification},
+    output::OutputEventsPayload,
+};
+
+/// Number of most recently sent batches each session holds on to, to be sent again to clients
+/// resuming from before them.
+const MAX_BUFFERED_BATCHES: usize = 100;
+
+type Batch = Vec<OutputEventsPayload>;
+
+/// Running sessions, by ID. Each entry is used to hand a running session over to a new client.
+static SESSIONS: Lazy<Mutex<HashMap<Uuid, mpsc::Sender<Resume>>>> = Lazy::new(Default::default);
+
+/// Position of a client in a tap session: the session ID, and the sequence number of the last
+/// batch the client received.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cursor {
+    session: Uuid,
+    sequence: u64,
+}
+
+impl fmt::Display for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.session, self.sequence)
+    }
+}
+
+impl FromStr for Cursor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (session, sequence) = s
+            .split_once(':')
+            .ok_or_else(|| format!("Invalid cursor '{}'.", s))?;
+        Ok(Self {
+            session: session
+                .parse()
+                .map_err(|_| format!("Invalid cursor '{}'.", s))?,
+            sequence: sequence
+                .parse()
+                .map_err(|_| format!("Invalid cursor '{}'.", s))?,
+        })
+    }
+}
+
+/// Request for a running session to send its batches to a new client.
+struct Resume {
+    after_sequence: u64,
+    tx: mpsc::Sender<Batch>,
+}
+
+/// Starts a resumable session sending `batches` on the returned stream.
+///
+/// Each batch is followed by a cursor notification. If the returned stream is dropped, the session
+/// keeps running for `resume_timeout`, so that a client reconnecting with a cursor in time gets
+/// the batches it missed in the meantime.
+pub fn start<S>(batches: S, resume_timeout: Duration) -> impl Stream<Item = Batch>
+where
+    S: Stream<Item = Batch> + Send + Unpin + 'static,
+{
+    let session = Uuid::new_v4();
+    let (resume_tx, resume_rx) = mpsc::channel(1);
+    let (tx, rx) = mpsc::channel(10);
+
+    SESSIONS
+        .lock()
+        .expect("tap sessions lock poisoned")
+        .insert(session, resume_tx);
+    tokio::spawn(run(session, batches, tx, resume_rx, resume_timeout));
+
+    ReceiverStream::new(rx)
+}
+
+/// Resumes the session `cursor` belongs to, sending the batches after it on the returned stream.
+///
+/// Returns `None` if the session is no longer running.
+pub async fn resume(cursor: Cursor) -> Option<impl Stream<Item = Batch>> {
+    let resume_tx = SESSIONS
+        .lock()
+        .expect("tap sessions lock poisoned")
+        .get(&cursor.session)
+        .cloned()?;
+    let (tx, rx) = mpsc::channel(10);
+
+    resume_tx
+        .send(Resume {
+            after_sequence: cursor.sequence,
+            tx,
+        })
+        .await
+        .ok()?;
+
+    Some(ReceiverStream::new(rx))
+}
+
+async fn run<S>(
+    session: Uuid,
+    mut batches: S,
+    tx: mpsc::Sender<Batch>,
+    mut resume_rx: mpsc::Receiver<Resume>,
+    resume_timeout: Duration,
+) where
+    S: Stream<Item = Batch> + Unpin,
+{
+    let mut buffer = VecDeque::<(u64, Batch)>::with_capacity(MAX_BUFFERED_BATCHES);
+    let mut sequence = 0;
+    let mut client = Some(tx);
+    let mut expires_at = None;
+
+    loop {
+        tokio::select! {
+            batch = batches.next() => {
+                let mut batch = match batch {
+                    Some(batch) => batch,
+                    None => break,
+                };
+
+                sequence += 1;
+                let cursor = Cursor { session, sequence };
+                batch.push(OutputEventsPayload::Notification(EventNotification {
+                    notification: Notification::CursorAdvanced(CursorAdvanced::new(
+                        cursor.to_string(),
+                    )),
+                }));
+
+                if buffer.len() == MAX_BUFFERED_BATCHES {
+                    buffer.pop_front();
+                }
+                buffer.push_back((sequence, batch.clone()));
+
+                if let Some(tx) = &client {
+                    if tx.send(batch).await.is_err() {
+                        client = None;
+                        expires_at = Some(Instant::now() + resume_timeout);
+                    }
+                }
+            }
+            _ = client_gone(&client) => {
+                client = None;
+                expires_at = Some(Instant::now() + resume_timeout);
+            }
+            Some(resume) = resume_rx.recv() => {
+                debug!(message = "Resuming tap session.", %session, after_sequence = resume.after_sequence);
+
+                let missed = buffer
+                    .iter()
+                    .filter(|(sequence, _)| *sequence > resume.after_sequence);
+                for (_, batch) in missed {
+                    if resume.tx.send(batch.clone()).await.is_err() {
+                        break;
+                    }
+                }
+
+                client = Some(resume.tx);
+                expires_at = None;
+            }
+            _ = expired(expires_at) => {
+                debug!(message = "Tap session expired.", %session);
+                break;
+            }
+        }
+    }
+
+    SESSIONS
+        .lock()
+        .expect("tap sessions lock poisoned")
+        .remove(&session);
+}
+
+/// Resolves once the client of the session has gone away, or never if there is no client.
+async fn client_gone(client: &Option<mpsc::Sender<Batch>>) {
+    match client {
+        Some(tx) => tx.closed().await,
+        None => futures::future::pending().await,
+    }
+}
+
+/// Resolves once `deadline` has passed, or never if there is no deadline.
+async fn expired(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => sleep_until(deadline).await,
+        None => futures::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::stream;
+
+    use super::*;
+    use crate::api::schema::events::notification::Matched;
+
+    fn batch(pattern: &str) -> Batch {
+        vec![OutputEventsPayload::Notification(EventNotification {
+            notification: Notification::Matched(Matched::new(pattern.to_string())),
+        })]
+    }
+
+    fn notification(payload: &OutputEventsPayload) -> &Notification {
+        match payload {
+            OutputEventsPayload::Notification(EventNotification { notification }) => notification,
+            _ => panic!("Expected payload to be a Notification"),
+        }
+    }
+
+    fn cursor_of(batch: &Batch) -> Cursor {
+        match notification(batch.last().unwrap()) {
+            Notification::CursorAdvanced(cursor) => cursor.cursor.parse().unwrap(),
+            _ => panic!("Expected batch to end with a cursor"),
+        }
+    }
+
+    #[test]
+    fn cursor_round_trip() {
+        let cursor = Cursor {
+            session: Uuid::new_v4(),
+            sequence: 42,
+        };
+
+        assert_eq!(cursor.to_string().parse::<Cursor>(), Ok(cursor));
+        assert!("42".parse::<Cursor>().is_err());
+        assert!("not-a-session:42".parse::<Cursor>().is_err());
+    }
+
+    #[tokio::test]
+    async fn resumes_after_last_received_batch() {
+        let (batches_tx, batches_rx) = mpsc::channel(10);
+        let mut first = Box::pin(start(
+            ReceiverStream::new(batches_rx),
+            Duration::from_secs(60),
+        ));
+
+        batches_tx.send(batch("a")).await.unwrap();
+        let cursor = cursor_of(&first.next().await.unwrap());
+        drop(first);
+
+        batches_tx.send(batch("b")).await.unwrap();
+        batches_tx.send(batch("c")).await.unwrap();
+
+        let resumed = resume(cursor).await.expect("session should be running");
+        let resumed = resumed.take(2).collect::<Vec<_>>().await;
+
+        assert_eq!(notification(&resumed[0][0]), notification(&batch("b")[0]));
+        assert_eq!(notification(&resumed[1][0]), notification(&batch("c")[0]));
+        assert_eq!(cursor_of(&resumed[1]).sequence, cursor.sequence + 2);
+    }
+
+    #[tokio::test]
+    async fn sessions_expire() {
+        let batches = stream::iter(vec![batch("a")]).chain(stream::pending());
+        let mut first = Box::pin(start(batches, Duration::from_millis(10)));
+
+        let cursor = cursor_of(&first.next().await.unwrap());
+        drop(first);
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        assert!(resume(cursor).await.is_none());
+    }
+}
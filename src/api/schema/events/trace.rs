@@ -41,11 +41,11 @@ impl Trace {
     async fn string(&self, encoding: EventEncodingType) -> String {
         match encoding {
             EventEncodingType::Json => serde_json::to_string(&self.event)
-                .expect("JSON serialization of log event failed. Please report."),
+                .expect("JSON serialization of trace event failed. Please report."),
             EventEncodingType::Yaml => serde_yaml::to_string(&self.event)
-                .expect("YAML serialization of log event failed. Please report."),
+                .expect("YAML serialization of trace event failed. Please report."),
             EventEncodingType::Logfmt => encode_logfmt::encode_map(self.event.as_map())
-                .expect("logfmt serialization of log event failed. Please report."),
+                .expect("logfmt serialization of trace event failed. Please report."),
         }
     }
 
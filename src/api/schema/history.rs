@@ -0,0 +1,280 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::Mutex,
+};
+
+use async_graphql::{Object, SimpleObject};
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use tokio::time::{self, Duration, Instant};
+
+use crate::{
+    api::ShutdownRx,
+    event::{Metric, MetricValue},
+    metrics::Controller,
+};
+
+const INVARIANT: &str = "Couldn't acquire lock on Vector throughput history. Please report this.";
+
+/// How often throughput samples are taken.
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Throughput samples of each component, shared by all connections.
+static HISTORY: Lazy<Mutex<History>> = Lazy::new(|| Mutex::new(History::default()));
+
+/// The throughput of a component over one sampling interval.
+#[derive(SimpleObject, Clone, Debug, PartialEq)]
+pub struct ThroughputSample {
+    /// The time the sample was taken
+    timestamp: DateTime<Utc>,
+
+    /// Events received per second, from `component_received_events_total`
+    received_events_throughput: f64,
+
+    /// Events sent per second, from `component_sent_events_total`
+    sent_events_throughput: f64,
+}
+
+/// The throughput samples of a component, oldest first.
+#[derive(SimpleObject, Clone, Debug, PartialEq)]
+pub struct ComponentThroughputHistory {
+    /// Component id
+    component_id: String,
+
+    /// Throughput samples over the retention period, oldest first
+    samples: Vec<ThroughputSample>,
+}
+
+/// The totals a component's throughput is computed from.
+#[derive(Clone, Copy, Default)]
+struct Totals {
+    received_events: f64,
+    sent_events: f64,
+}
+
+impl Totals {
+    /// Adds the value of `metric` to the matching total, if any.
+    fn add(&mut self, metric: &Metric) {
+        let value = match metric.value() {
+            MetricValue::Counter { value } => *value,
+            _ => return,
+        };
+        match metric.name() {
+            "component_received_events_total" => self.received_events += value,
+            "component_sent_events_total" => self.sent_events += value,
+            _ => {}
+        }
+    }
+}
+
+#[derive(Default)]
+struct ComponentHistory {
+    last: Option<(Instant, Totals)>,
+    samples: VecDeque<(Instant, ThroughputSample)>,
+}
+
+impl ComponentHistory {
+    fn observe(&mut self, totals: Totals, now: Instant, timestamp: DateTime<Utc>) {
+        if let Some((last_at, last)) = self.last {
+            let elapsed = now.duration_since(last_at).as_secs_f64();
+            if elapsed > 0.0 {
+                // Counters reset when components are reloaded, in which case everything counted
+                // since was counted over the interval.
+                let rate = |total: f64, last: f64| {
+                    (if total < last { total } else { total - last }) / elapsed
+                };
+                self.samples.push_back((
+                    now,
+                    ThroughputSample {
+                        timestamp,
+                        received_events_throughput: rate(
+                            totals.received_events,
+                            last.received_events,
+                        ),
+                        sent_events_throughput: rate(totals.sent_events, last.sent_events),
+                    },
+                ));
+            }
+        }
+        self.last = Some((now, totals));
+    }
+
+    fn prune(&mut self, now: Instant, retention: Duration) {
+        while matches!(self.samples.front(), Some((at, _)) if now.duration_since(*at) >= retention)
+        {
+            self.samples.pop_front();
+        }
+    }
+}
+
+#[derive(Default)]
+struct History {
+    components: BTreeMap<String, ComponentHistory>,
+}
+
+impl History {
+    /// Records a sample for every component in `metrics`, and forgets the components no longer
+    /// running along with the samples older than `retention`.
+    fn record(
+        &mut self,
+        metrics: &[Metric],
+        now: Instant,
+        timestamp: DateTime<Utc>,
+        retention: Duration,
+    ) {
+        let mut totals = BTreeMap::<&str, Totals>::new();
+        for metric in metrics {
+            if let Some(component_id) = metric.tags().and_then(|tags| tags.get("component_id")) {
+                totals.entry(component_id).or_default().add(metric);
+            }
+        }
+
+        self.components
+            .retain(|component_id, _| totals.contains_key(component_id.as_str()));
+        for (component_id, totals) in totals {
+            let history = self.components.entry(component_id.to_owned()).or_default();
+            history.observe(totals, now, timestamp);
+            history.prune(now, retention);
+        }
+    }
+
+    fn list(&self, component_ids: Option<&[String]>) -> Vec<ComponentThroughputHistory> {
+        self.components
+            .iter()
+            .filter(|(component_id, _)| {
+                component_ids.map_or(true, |component_ids| component_ids.contains(component_id))
+            })
+            .map(|(component_id, history)| ComponentThroughputHistory {
+                component_id: component_id.clone(),
+                samples: history
+                    .samples
+                    .iter()
+                    .map(|(_, sample)| sample.clone())
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+/// Samples the throughput of each component, keeping the samples of the last `retention`, until
+/// `shutdown` resolves.
+pub async fn run(mut shutdown: ShutdownRx, retention: Duration) {
+    let controller = match Controller::get() {
+        Ok(controller) => controller,
+        Err(_) => return,
+    };
+
+    let mut interval = time::interval(SAMPLE_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            _ = interval.tick() => {
+                let metrics = controller.capture_metrics();
+                HISTORY.lock().expect(INVARIANT).record(
+                    &metrics,
+                    Instant::now(),
+                    Utc::now(),
+                    retention,
+                );
+            }
+        }
+    }
+
+    // Samples would go stale while the API isn't running.
+    *HISTORY.lock().expect(INVARIANT) = History::default();
+}
+
+#[derive(Default)]
+pub struct HistoryQuery;
+
+#[Object]
+impl HistoryQuery {
+    /// Throughput samples of components over the `api.throughput_history_retention_secs` period,
+    /// taken every second. Limited to the provided `component_ids`, if set
+    async fn component_throughput_history(
+        &self,
+        component_ids: Option<Vec<String>>,
+    ) -> Vec<ComponentThroughputHistory> {
+        HISTORY
+            .lock()
+            .expect(INVARIANT)
+            .list(component_ids.as_deref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_core::{event::MetricKind, metric_tags};
+
+    use super::*;
+
+    fn counter(name: &str, component_id: &str, value: f64) -> Metric {
+        Metric::new(name, MetricKind::Absolute, MetricValue::Counter { value })
+            .with_tags(Some(metric_tags!("component_id" => component_id)))
+    }
+
+    #[test]
+    fn records_throughput_within_retention() {
+        let mut history = History::default();
+        let retention = Duration::from_secs(2);
+        let start = Instant::now();
+        let timestamp = Utc::now();
+
+        for (secs, received, sent) in [
+            (0, 0.0, 0.0),
+            (1, 10.0, 5.0),
+            (2, 30.0, 5.0),
+            (3, 60.0, 8.0),
+        ] {
+            history.record(
+                &[
+                    counter("component_received_events_total", "in", received),
+                    counter("component_sent_events_total", "in", sent),
+                    counter("component_sent_events_total", "out", sent),
+                ],
+                start + Duration::from_secs(secs),
+                timestamp,
+                retention,
+            );
+        }
+
+        let list = history.list(Some(&["in".to_string()]));
+        assert_eq!(list.len(), 1);
+        let samples = &list[0].samples;
+        assert_eq!(
+            samples
+                .iter()
+                .map(|sample| (
+                    sample.received_events_throughput,
+                    sample.sent_events_throughput
+                ))
+                .collect::<Vec<_>>(),
+            vec![(20.0, 0.0), (30.0, 3.0)]
+        );
+        assert_eq!(history.list(None).len(), 2);
+    }
+
+    #[test]
+    fn forgets_removed_components() {
+        let mut history = History::default();
+        let retention = Duration::from_secs(60);
+        let start = Instant::now();
+
+        history.record(
+            &[counter("component_sent_events_total", "old", 1.0)],
+            start,
+            Utc::now(),
+            retention,
+        );
+        history.record(
+            &[counter("component_sent_events_total", "new", 1.0)],
+            start + Duration::from_secs(1),
+            Utc::now(),
+            retention,
+        );
+
+        let list = history.list(None);
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].component_id, "new");
+    }
+}
@@ -8,6 +8,7 @@ mod processed_bytes;
 mod processed_events;
 mod received_events;
 mod sent_events;
+mod series;
 mod sink;
 pub mod source;
 mod transform;
@@ -34,6 +35,9 @@ pub use received_events::{
     ComponentReceivedEventsThroughput, ComponentReceivedEventsTotal, ReceivedEventsTotal,
 };
 pub use sent_events::{ComponentSentEventsThroughput, ComponentSentEventsTotal, SentEventsTotal};
+pub use series::{
+    InternalMetricSample, InternalMetricTag, InternalMetricTagFilter, MetricAggregation,
+};
 pub use sink::{IntoSinkMetrics, SinkMetrics};
 pub use source::{IntoSourceMetrics, SourceMetrics};
 use tokio_stream::{Stream, StreamExt};
@@ -330,4 +334,17 @@ impl MetricsSubscription {
             _ => None,
         })
     }
+
+    /// Samples of the internal counter and gauge series named one of `names`, and having all
+    /// `tags`, taken every `interval` milliseconds. Values are reported as is, or as the change,
+    /// or rate of change per second, since the previous sample, as set by `aggregation`
+    async fn internal_metrics(
+        &self,
+        names: Vec<String>,
+        #[graphql(default)] tags: Vec<InternalMetricTagFilter>,
+        #[graphql(default_with = "MetricAggregation::Value")] aggregation: MetricAggregation,
+        #[graphql(default = 1000, validator(minimum = 10, maximum = 60_000))] interval: i32,
+    ) -> impl Stream<Item = Vec<InternalMetricSample>> {
+        series::internal_metric_samples(interval, names, tags, aggregation)
+    }
 }
@@ -0,0 +1,252 @@
+use std::collections::BTreeMap;
+
+use async_graphql::{Enum, InputObject, SimpleObject};
+use chrono::{DateTime, Utc};
+use tokio::time::Instant;
+use tokio_stream::{Stream, StreamExt};
+
+use super::get_all_metrics;
+use crate::event::{Metric, MetricValue};
+
+/// How the values of internal metric series are reported.
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Debug)]
+pub enum MetricAggregation {
+    /// The current value of the series.
+    Value,
+
+    /// The change of the series since the previous sample.
+    Delta,
+
+    /// The change of the series per second since the previous sample.
+    Rate,
+}
+
+/// A tag internal metric series must have to be streamed.
+#[derive(InputObject)]
+pub struct InternalMetricTagFilter {
+    /// The name of the tag.
+    key: String,
+
+    /// The value the tag must have. If not set, any value matches.
+    value: Option<String>,
+}
+
+impl InternalMetricTagFilter {
+    fn matches(&self, metric: &Metric) -> bool {
+        match &self.value {
+            Some(value) => metric.tag_matches(&self.key, value),
+            None => metric
+                .tags()
+                .map_or(false, |tags| tags.contains_key(&self.key)),
+        }
+    }
+}
+
+/// A tag of an internal metric series.
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct InternalMetricTag {
+    /// Tag name
+    key: String,
+
+    /// Tag value
+    value: String,
+}
+
+/// A sample of an internal metric series.
+#[derive(SimpleObject, Clone, Debug, PartialEq)]
+pub struct InternalMetricSample {
+    /// Metric name
+    name: String,
+
+    /// Metric tags, sorted by name
+    tags: Vec<InternalMetricTag>,
+
+    /// The time the sample was taken
+    timestamp: DateTime<Utc>,
+
+    /// The value of the series, aggregated as requested. For deltas and rates, `null` on the
+    /// first sample of the series
+    value: Option<f64>,
+}
+
+/// Identifies a series by its name and tags.
+type SeriesKey = (String, Vec<InternalMetricTag>);
+
+/// Computes the samples of the series matching the filters, keeping the previous value of each
+/// series to compute deltas and rates from.
+struct Series {
+    names: Vec<String>,
+    tags: Vec<InternalMetricTagFilter>,
+    aggregation: MetricAggregation,
+    last: BTreeMap<SeriesKey, (Instant, f64)>,
+}
+
+impl Series {
+    fn new(
+        names: Vec<String>,
+        tags: Vec<InternalMetricTagFilter>,
+        aggregation: MetricAggregation,
+    ) -> Self {
+        Self {
+            names,
+            tags,
+            aggregation,
+            last: BTreeMap::new(),
+        }
+    }
+
+    fn matches(&self, metric: &Metric) -> bool {
+        self.names.iter().any(|name| name == metric.name())
+            && self.tags.iter().all(|filter| filter.matches(metric))
+    }
+
+    /// Returns the samples of the matching series in `metrics`, and forgets the series no longer
+    /// reported.
+    fn observe(
+        &mut self,
+        metrics: &[Metric],
+        now: Instant,
+        timestamp: DateTime<Utc>,
+    ) -> Vec<InternalMetricSample> {
+        let mut last = BTreeMap::new();
+        let mut samples = Vec::new();
+
+        for metric in metrics.iter().filter(|metric| self.matches(metric)) {
+            // Only counters and gauges are single-valued.
+            let total = match metric.value() {
+                MetricValue::Counter { value } | MetricValue::Gauge { value } => *value,
+                _ => continue,
+            };
+            let tags = metric
+                .tags()
+                .map(|tags| {
+                    tags.iter_single()
+                        .map(|(key, value)| InternalMetricTag {
+                            key: key.to_owned(),
+                            value: value.to_owned(),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            let key = (metric.name().to_owned(), tags);
+
+            let previous = self.last.get(&key).copied();
+            let delta = previous.map(|(_, previous)| match metric.value() {
+                // Counters reset when components are reloaded, in which case everything counted
+                // since was counted over the interval.
+                MetricValue::Counter { .. } if total < previous => total,
+                _ => total - previous,
+            });
+            let value = match self.aggregation {
+                MetricAggregation::Value => Some(total),
+                MetricAggregation::Delta => delta,
+                MetricAggregation::Rate => previous.zip(delta).and_then(|((at, _), delta)| {
+                    let elapsed = now.duration_since(at).as_secs_f64();
+                    (elapsed > 0.0).then_some(delta / elapsed)
+                }),
+            };
+
+            samples.push(InternalMetricSample {
+                name: key.0.clone(),
+                tags: key.1.clone(),
+                timestamp,
+                value,
+            });
+            last.insert(key, (now, total));
+        }
+
+        self.last = last;
+        samples
+    }
+}
+
+/// Returns a stream of the samples of the internal metric series named one of `names` and
+/// matching all `tags` filters, taken every `interval` milliseconds.
+pub fn internal_metric_samples(
+    interval: i32,
+    names: Vec<String>,
+    tags: Vec<InternalMetricTagFilter>,
+    aggregation: MetricAggregation,
+) -> impl Stream<Item = Vec<InternalMetricSample>> {
+    let mut series = Series::new(names, tags, aggregation);
+    get_all_metrics(interval)
+        .map(move |metrics| series.observe(&metrics, Instant::now(), Utc::now()))
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::time::Duration;
+    use vector_core::{event::MetricKind, metric_tags};
+
+    use super::*;
+
+    fn counter(name: &str, component_id: &str, value: f64) -> Metric {
+        Metric::new(name, MetricKind::Absolute, MetricValue::Counter { value })
+            .with_tags(Some(metric_tags!("component_id" => component_id)))
+    }
+
+    fn values(samples: &[InternalMetricSample]) -> Vec<Option<f64>> {
+        samples.iter().map(|sample| sample.value).collect()
+    }
+
+    #[test]
+    fn filters_series_by_name_and_tags() {
+        let mut series = Series::new(
+            vec!["component_sent_events_total".to_string()],
+            vec![InternalMetricTagFilter {
+                key: "component_id".to_string(),
+                value: Some("out".to_string()),
+            }],
+            MetricAggregation::Value,
+        );
+
+        let samples = series.observe(
+            &[
+                counter("component_sent_events_total", "out", 3.0),
+                counter("component_sent_events_total", "in", 5.0),
+                counter("component_received_events_total", "out", 7.0),
+            ],
+            Instant::now(),
+            Utc::now(),
+        );
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].name, "component_sent_events_total");
+        assert_eq!(
+            samples[0].tags,
+            vec![InternalMetricTag {
+                key: "component_id".to_string(),
+                value: "out".to_string(),
+            }]
+        );
+        assert_eq!(samples[0].value, Some(3.0));
+    }
+
+    #[test]
+    fn computes_deltas_and_rates() {
+        let names = vec!["component_sent_events_total".to_string()];
+        let mut deltas = Series::new(names.clone(), Vec::new(), MetricAggregation::Delta);
+        let mut rates = Series::new(names, Vec::new(), MetricAggregation::Rate);
+        let start = Instant::now();
+        let timestamp = Utc::now();
+
+        let mut observed = Vec::new();
+        for (secs, total) in [(0, 10.0), (2, 30.0), (4, 4.0)] {
+            let metrics = [counter("component_sent_events_total", "out", total)];
+            let now = start + Duration::from_secs(secs);
+            observed.push((
+                values(&deltas.observe(&metrics, now, timestamp)),
+                values(&rates.observe(&metrics, now, timestamp)),
+            ));
+        }
+
+        // The counter was reset before the last sample.
+        assert_eq!(
+            observed,
+            vec![
+                (vec![None], vec![None]),
+                (vec![Some(20.0)], vec![Some(10.0)]),
+                (vec![Some(4.0)], vec![Some(2.0)]),
+            ]
+        );
+    }
+}
@@ -1,13 +1,15 @@
+pub mod alerts;
 pub mod components;
 pub mod events;
 pub mod filter;
 mod health;
+pub mod history;
 mod meta;
 mod metrics;
 mod relay;
 pub mod sort;
 
-use async_graphql::{EmptyMutation, MergedObject, MergedSubscription, Schema, SchemaBuilder};
+use async_graphql::{MergedObject, MergedSubscription, Schema, SchemaBuilder};
 
 #[derive(MergedObject, Default)]
 pub struct Query(
@@ -15,8 +17,14 @@ pub struct Query(
     components::ComponentsQuery,
     metrics::MetricsQuery,
     meta::MetaQuery,
+    events::EventsQuery,
+    alerts::AlertsQuery,
+    history::HistoryQuery,
 );
 
+#[derive(MergedObject, Default)]
+pub struct Mutation(alerts::AlertsMutation);
+
 #[derive(MergedSubscription, Default)]
 pub struct Subscription(
     health::HealthSubscription,
@@ -26,6 +34,10 @@ pub struct Subscription(
 );
 
 /// Build a new GraphQL schema, comprised of Query, Mutation and Subscription types
-pub fn build_schema() -> SchemaBuilder<Query, EmptyMutation, Subscription> {
-    Schema::build(Query::default(), EmptyMutation, Subscription::default())
+pub fn build_schema() -> SchemaBuilder<Query, Mutation, Subscription> {
+    Schema::build(
+        Query::default(),
+        Mutation::default(),
+        Subscription::default(),
+    )
 }
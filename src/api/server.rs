@@ -1,7 +1,8 @@
 use std::{
     convert::Infallible,
     net::SocketAddr,
-    sync::{atomic::AtomicBool, Arc},
+    sync::{atomic::AtomicBool, Arc, Mutex},
+    time::{Duration, Instant},
 };
 
 use async_graphql::{
@@ -9,8 +10,14 @@ use async_graphql::{
     Data, Request, Schema,
 };
 use async_graphql_warp::{graphql_protocol, GraphQLResponse, GraphQLWebSocket};
-use tokio::sync::oneshot;
-use warp::{filters::BoxedFilter, http::Response, ws::Ws, Filter, Reply};
+use futures::{channel::mpsc, FutureExt, Sink, SinkExt, Stream, StreamExt};
+use tokio::{sync::oneshot, time};
+use warp::{
+    filters::BoxedFilter,
+    http::Response,
+    ws::{Message, WebSocket, Ws},
+    Filter, Reply,
+};
 
 use super::{handler, schema, ShutdownTx};
 use crate::{
@@ -21,6 +28,8 @@ use crate::{
 
 pub struct Server {
     _shutdown: ShutdownTx,
+    _alerts_shutdown: ShutdownTx,
+    _history_shutdown: ShutdownTx,
     addr: SocketAddr,
 }
 
@@ -32,7 +41,8 @@ impl Server {
         watch_rx: topology::WatchRx,
         running: Arc<AtomicBool>,
     ) -> crate::Result<Self> {
-        let routes = make_routes(config.api.playground, watch_rx, running);
+        let alert_rules = schema::alerts::AlertRules::default();
+        let routes = make_routes(config.api, watch_rx, running, alert_rules.clone());
 
         let (_shutdown, rx) = oneshot::channel();
         let (addr, server) = warp::serve(routes)
@@ -56,7 +66,26 @@ impl Server {
         // Spawn the server in the background.
         tokio::spawn(server);
 
-        Ok(Self { _shutdown, addr })
+        // Evaluate the alert rules registered through the API for as long as the server runs.
+        let (_alerts_shutdown, alerts_rx) = oneshot::channel();
+        tokio::spawn(schema::alerts::run(alert_rules, alerts_rx));
+
+        // Sample the throughput of components, so that clients can show some history as soon as
+        // they connect.
+        let (_history_shutdown, history_rx) = oneshot::channel();
+        if config.api.throughput_history_retention_secs > 0 {
+            tokio::spawn(schema::history::run(
+                history_rx,
+                Duration::from_secs(config.api.throughput_history_retention_secs),
+            ));
+        }
+
+        Ok(Self {
+            _shutdown,
+            _alerts_shutdown,
+            _history_shutdown,
+            addr,
+        })
     }
 
     /// Returns a copy of the SocketAddr that the server was started on.
@@ -73,9 +102,10 @@ impl Server {
 }
 
 fn make_routes(
-    playground: bool,
+    options: config::api::Options,
     watch_tx: topology::WatchRx,
     running: Arc<AtomicBool>,
+    alert_rules: schema::alerts::AlertRules,
 ) -> BoxedFilter<(impl Reply,)> {
     // Routes...
 
@@ -87,6 +117,10 @@ fn make_routes(
     // 404.
     let not_found = warp::any().and_then(|| async { Err(warp::reject::not_found()) });
 
+    // Alert rules are kept by the server, and shared by every connection.
+    let graphql_schema = schema::build_schema().data(alert_rules).finish();
+    let subscription_schema = graphql_schema.clone();
+
     // GraphQL subscription handler. Creates a Warp WebSocket handler and for each connection,
     // parses the required headers for GraphQL and builds per-connection context based on the
     // provided `WatchTx` channel sender. This allows GraphQL resolvers to subscribe to
@@ -95,16 +129,27 @@ fn make_routes(
         warp::ws()
             .and(graphql_protocol())
             .map(move |ws: Ws, protocol: WebSocketProtocols| {
-                let schema = schema::build_schema().finish();
+                let schema = subscription_schema.clone();
                 let watch_tx = watch_tx.clone();
 
                 let reply = ws.on_upgrade(move |socket| {
                     let mut data = Data::default();
                     data.insert(watch_tx);
+                    data.insert(options);
 
-                    GraphQLWebSocket::new(socket, schema, protocol)
-                        .with_data(data)
-                        .serve()
+                    match options.keepalive {
+                        Some(keepalive) => {
+                            let (sink, stream) = with_keepalive(socket, keepalive);
+                            GraphQLWebSocket::new_with_pair(sink, stream, schema, protocol)
+                                .with_data(data)
+                                .serve()
+                                .boxed()
+                        }
+                        None => GraphQLWebSocket::new(socket, schema, protocol)
+                            .with_data(data)
+                            .serve()
+                            .boxed(),
+                    }
                 });
 
                 warp::reply::with_header(
@@ -118,7 +163,7 @@ fn make_routes(
     // a subscription and if so, an attempt will be made to upgrade the connection to WebSockets.
     // All other queries will fall back to the default HTTP handler.
     let graphql_handler = warp::path("graphql").and(graphql_subscription_handler.or(
-        async_graphql_warp::graphql(schema::build_schema().finish()).and_then(
+        async_graphql_warp::graphql(graphql_schema).and_then(
             |(schema, request): (Schema<_, _, _>, Request)| async move {
                 Ok::<_, Infallible>(GraphQLResponse::from(schema.execute(request).await))
             },
@@ -126,7 +171,7 @@ fn make_routes(
     ));
 
     // Provide a playground for executing GraphQL queries/mutations/subscriptions.
-    let graphql_playground = if playground {
+    let graphql_playground = if options.playground {
         warp::path("playground")
             .map(move || {
                 Response::builder()
@@ -174,3 +219,57 @@ fn with_shared(
 ) -> impl Filter<Extract = (Arc<AtomicBool>,), Error = Infallible> + Clone {
     warp::any().map(move || Arc::<AtomicBool>::clone(&shared))
 }
+
+/// Splits `socket` into a sink and a stream to serve GraphQL over, pinging the client every
+/// `interval_secs` in the meantime. Once the client hasn't sent anything, pongs included, for
+/// `timeout_secs`, the connection is closed and the stream ends, which also ends its subscriptions.
+fn with_keepalive(
+    socket: WebSocket,
+    keepalive: config::api::KeepaliveOptions,
+) -> (
+    impl Sink<Message> + Send + Unpin,
+    impl Stream<Item = Result<Message, warp::Error>> + Send + Unpin,
+) {
+    let (mut socket_tx, socket_rx) = socket.split();
+    let (tx, mut rx) = mpsc::channel::<Message>(16);
+    let (closed_tx, closed_rx) = oneshot::channel::<()>();
+
+    let last_received = Arc::new(Mutex::new(Instant::now()));
+    let last_received_clone = Arc::clone(&last_received);
+
+    tokio::spawn(async move {
+        let timeout = Duration::from_secs(keepalive.timeout_secs);
+        let mut interval = time::interval(Duration::from_secs(keepalive.interval_secs.max(1)));
+
+        loop {
+            tokio::select! {
+                message = rx.next() => match message {
+                    Some(message) => {
+                        if socket_tx.send(message).await.is_err() {
+                            break;
+                        }
+                    }
+                    None => break,
+                },
+                _ = interval.tick() => {
+                    if last_received_clone.lock().unwrap().elapsed() > timeout {
+                        debug!(message = "Closing unresponsive API WebSocket connection.");
+                        break;
+                    }
+                    if socket_tx.send(Message::ping(Vec::new())).await.is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+
+        let _ = socket_tx.close().await;
+        drop(closed_tx);
+    });
+
+    let socket_rx = socket_rx
+        .inspect(move |_| *last_received.lock().unwrap() = Instant::now())
+        .take_until(closed_rx);
+
+    (tx, socket_rx)
+}
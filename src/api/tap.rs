@@ -1,6 +1,7 @@
 use std::{
     collections::{HashMap, HashSet},
     num::NonZeroUsize,
+    sync::{Arc, Mutex},
 };
 
 use futures::{future::try_join_all, FutureExt};
@@ -15,13 +16,13 @@ use vector_buffers::{topology::builder::TopologyBuilder, WhenFull};
 use super::{
     schema::events::{
         notification::{InvalidMatch, Matched, NotMatched, Notification},
-        TapPatterns,
+        Sampler, TapPatterns,
     },
     ShutdownRx, ShutdownTx,
 };
 use crate::{
     config::ComponentKey,
-    event::{EventArray, LogArray, MetricArray, TraceArray},
+    event::{EventArray, EventContainer, LogArray, MetricArray, TraceArray},
     topology::{fanout, fanout::ControlChannel, TapOutput, TapResource, WatchRx},
 };
 
@@ -125,19 +126,42 @@ impl TapPayload {
     }
 }
 
+/// A sampler shared by all the sinks of a tap.
+pub type SharedSampler = Arc<Mutex<Sampler>>;
+
 /// A `TapTransformer` transforms raw events and ships them to the global tap receiver.
 #[derive(Clone)]
 pub struct TapTransformer {
     tap_tx: TapSender,
     output: TapOutput,
+    sampler: Option<SharedSampler>,
 }
 
 impl TapTransformer {
-    pub const fn new(tap_tx: TapSender, output: TapOutput) -> Self {
-        Self { tap_tx, output }
+    pub const fn new(tap_tx: TapSender, output: TapOutput, sampler: Option<SharedSampler>) -> Self {
+        Self {
+            tap_tx,
+            output,
+            sampler,
+        }
     }
 
-    pub fn try_send(&mut self, events: EventArray) {
+    pub fn try_send(&mut self, mut events: EventArray) {
+        // Events are sampled before being sent, so that those dropped don't take up room in the
+        // tap channel.
+        if let Some(sampler) = &self.sampler {
+            let mut sampler = sampler.lock().expect("tap sampler lock poisoned");
+            let now = tokio::time::Instant::now();
+            match &mut events {
+                EventArray::Logs(logs) => logs.retain(|_| sampler.keep(now)),
+                EventArray::Metrics(metrics) => metrics.retain(|_| sampler.keep(now)),
+                EventArray::Traces(traces) => traces.retain(|_| sampler.keep(now)),
+            }
+        }
+        if events.is_empty() {
+            return;
+        }
+
         let payload = match events {
             EventArray::Logs(logs) => TapPayload::Log(self.output.clone(), logs),
             EventArray::Metrics(metrics) => TapPayload::Metric(self.output.clone(), metrics),
@@ -166,9 +190,26 @@ impl TapController {
     /// and a separate inner handler for events. Uses a oneshot channel to trigger shutdown
     /// of handlers when the `TapSink` drops out of scope.
     pub fn new(watch_rx: WatchRx, tap_tx: TapSender, patterns: TapPatterns) -> Self {
+        Self::with_sampler(watch_rx, tap_tx, patterns, None)
+    }
+
+    /// Creates a new tap sink, as with `new`, which drops the events not kept by `sampler` before
+    /// sending them.
+    pub fn with_sampler(
+        watch_rx: WatchRx,
+        tap_tx: TapSender,
+        patterns: TapPatterns,
+        sampler: Option<SharedSampler>,
+    ) -> Self {
         let (_shutdown, shutdown_rx) = oneshot::channel();
 
-        tokio::spawn(tap_handler(patterns, tap_tx, watch_rx, shutdown_rx));
+        tokio::spawn(tap_handler(
+            patterns,
+            tap_tx,
+            sampler,
+            watch_rx,
+            shutdown_rx,
+        ));
 
         Self { _shutdown }
     }
@@ -238,6 +279,7 @@ async fn send_invalid_output_pattern_match(
 async fn tap_handler(
     patterns: TapPatterns,
     tx: TapSender,
+    sampler: Option<SharedSampler>,
     mut watch_rx: WatchRx,
     mut shutdown_rx: ShutdownRx,
 ) {
@@ -316,7 +358,7 @@ async fn tap_handler(
                             // wrap each event payload with the necessary metadata before forwarding
                             // it to our global tap receiver.
                             let (tap_buffer_tx, mut tap_buffer_rx) = TopologyBuilder::standalone_memory(TAP_BUFFER_SIZE, WhenFull::DropNewest).await;
-                            let mut tap_transformer = TapTransformer::new(tx.clone(), output.clone());
+                            let mut tap_transformer = TapTransformer::new(tx.clone(), output.clone(), sampler.clone());
 
                             tokio::spawn(async move {
                                 while let Some(events) = tap_buffer_rx.next().await {
@@ -423,9 +465,10 @@ mod tests {
 
     use super::*;
     use crate::api::schema::events::output::OutputEventsPayload;
-    use crate::api::schema::events::{create_events_stream, log, metric};
+    use crate::api::schema::events::{create_events_stream, log, metric, TapSampling};
+    use crate::conditions::AnyCondition;
     use crate::config::{Config, OutputId};
-    use crate::event::{LogEvent, Metric, MetricKind, MetricValue};
+    use crate::event::{LogEvent, Metric, MetricKind, MetricValue, TraceEvent};
     use crate::sinks::blackhole::BlackholeConfig;
     use crate::sources::demo_logs::{DemoLogsConfig, OutputFormat};
     use crate::test_util::{start_topology, trace_init};
@@ -448,6 +491,38 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    /// A tap transformer should drop the events not kept by its sampler before sending them.
+    async fn transformer_samples_before_sending() {
+        let (tap_tx, mut tap_rx) = tokio_mpsc::channel(10);
+        let sampler = Arc::new(Mutex::new(Sampler::new(TapSampling {
+            rate: Some(2),
+            max_events_per_sec: None,
+        })));
+        let mut transformer = TapTransformer::new(
+            tap_tx,
+            TapOutput {
+                output_id: OutputId::from(&ComponentKey::from("test")),
+                component_kind: "source",
+                component_type: "demo".to_string(),
+            },
+            Some(Arc::clone(&sampler)),
+        );
+
+        for count in [4, 1, 1] {
+            transformer.try_send(EventArray::Logs(vec![LogEvent::from("test log"); count]));
+        }
+
+        // The last event is dropped, so it isn't sent at all.
+        for expected in [2, 1] {
+            assert!(
+                matches!(tap_rx.recv().await, Some(TapPayload::Log(_, logs)) if logs.len() == expected)
+            );
+        }
+        assert!(tap_rx.try_recv().is_err());
+        assert_eq!(sampler.lock().unwrap().take_dropped(), 3);
+    }
+
     #[tokio::test]
     /// A tap sink should match a pattern, receive the correct notifications,
     /// and receive events
@@ -513,6 +588,7 @@ mod tests {
         // Send some events down the wire. Waiting until the first notifications are in
         // to ensure the event handler has been initialized.
         let log_event = LogEvent::default();
+        let trace_event = TraceEvent::default();
         let metric_event = Metric::new(
             id.to_string(),
             MetricKind::Incremental,
@@ -527,6 +603,10 @@ mod tests {
             .send(vec![log_event].into())
             .await
             .expect("should not fail");
+        fanout
+            .send(trace_event.into())
+            .await
+            .expect("should not fail");
 
         // 3rd payload should be the metric event
         assert!(matches!(
@@ -539,6 +619,12 @@ mod tests {
             sink_rx.recv().await,
             Some(TapPayload::Log(output, _)) if output.output_id == id
         ));
+
+        // 5th payload should be the trace event
+        assert!(matches!(
+            sink_rx.recv().await,
+            Some(TapPayload::Trace(output, _)) if output.output_id == id
+        ));
     }
 
     fn assert_notification(payload: OutputEventsPayload) -> Notification {
@@ -585,6 +671,7 @@ mod tests {
             BlackholeConfig {
                 print_interval_secs: 1,
                 rate: None,
+                latency_key: None,
                 acknowledgements: Default::default(),
             },
         );
@@ -596,6 +683,8 @@ mod tests {
             TapPatterns::new(HashSet::from(["in".to_string()]), HashSet::new()),
             500,
             100,
+            None,
+            TapSampling::default(),
         );
 
         let source_tap_events: Vec<_> = source_tap_stream.take(2).collect().await;
@@ -607,6 +696,60 @@ mod tests {
         let _log = assert_log(source_tap_events[1][0].clone());
     }
 
+    #[tokio::test]
+    async fn integration_test_source_log_filtered() {
+        trace_init();
+
+        let mut config = Config::builder();
+        config.add_source(
+            "in",
+            DemoLogsConfig {
+                interval: 0.01,
+                count: 200,
+                format: OutputFormat::Shuffle {
+                    sequence: false,
+                    lines: vec!["keep".to_string(), "drop".to_string()],
+                },
+                ..Default::default()
+            },
+        );
+        config.add_sink(
+            "out",
+            &["in"],
+            BlackholeConfig {
+                print_interval_secs: 1,
+                rate: None,
+                latency_key: None,
+                acknowledgements: Default::default(),
+            },
+        );
+
+        let (topology, _) = start_topology(config.build().unwrap(), false).await;
+
+        let filter = AnyCondition::String(r#".message == "keep""#.to_string())
+            .build(&Default::default())
+            .unwrap();
+        let source_tap_stream = create_events_stream(
+            topology.watch(),
+            TapPatterns::new(HashSet::from(["in".to_string()]), HashSet::new()),
+            500,
+            100,
+            Some(filter),
+            TapSampling::default(),
+        );
+
+        let source_tap_events: Vec<_> = source_tap_stream.take(3).collect().await;
+
+        assert_eq!(
+            assert_notification(source_tap_events[0][0].clone()),
+            Notification::Matched(Matched::new("in".to_string()))
+        );
+        for payload in source_tap_events[1..].iter().flatten() {
+            let log = assert_log(payload.clone());
+            assert_eq!(log.get_message().as_deref(), Some("keep"));
+        }
+    }
+
     #[tokio::test]
     async fn integration_test_source_metric() {
         trace_init();
@@ -643,6 +786,7 @@ mod tests {
             BlackholeConfig {
                 print_interval_secs: 1,
                 rate: None,
+                latency_key: None,
                 acknowledgements: Default::default(),
             },
         );
@@ -654,6 +798,8 @@ mod tests {
             TapPatterns::new(HashSet::from(["to_metric".to_string()]), HashSet::new()),
             500,
             100,
+            None,
+            TapSampling::default(),
         );
 
         let source_tap_events: Vec<_> = source_tap_stream.take(2).collect().await;
@@ -693,6 +839,7 @@ mod tests {
             BlackholeConfig {
                 print_interval_secs: 1,
                 rate: None,
+                latency_key: None,
                 acknowledgements: Default::default(),
             },
         );
@@ -704,6 +851,8 @@ mod tests {
             TapPatterns::new(HashSet::from(["transform".to_string()]), HashSet::new()),
             500,
             100,
+            None,
+            TapSampling::default(),
         );
 
         let transform_tap_events: Vec<_> = transform_tap_stream.take(2).collect().await;
@@ -746,6 +895,7 @@ mod tests {
             BlackholeConfig {
                 print_interval_secs: 1,
                 rate: None,
+                latency_key: None,
                 acknowledgements: Default::default(),
             },
         );
@@ -760,6 +910,8 @@ mod tests {
             ),
             500,
             100,
+            None,
+            TapSampling::default(),
         );
 
         let tap_events: Vec<_> = tap_stream.take(4).collect().await;
@@ -820,6 +972,7 @@ mod tests {
             BlackholeConfig {
                 print_interval_secs: 1,
                 rate: None,
+                latency_key: None,
                 acknowledgements: Default::default(),
             },
         );
@@ -831,6 +984,8 @@ mod tests {
             TapPatterns::new(HashSet::new(), HashSet::from(["out".to_string()])),
             500,
             100,
+            None,
+            TapSampling::default(),
         );
 
         let tap_events: Vec<_> = tap_stream.take(2).collect().await;
@@ -880,6 +1035,7 @@ mod tests {
             BlackholeConfig {
                 print_interval_secs: 1,
                 rate: None,
+                latency_key: None,
                 acknowledgements: Default::default(),
             },
         );
@@ -894,6 +1050,8 @@ mod tests {
             ),
             500,
             100,
+            None,
+            TapSampling::default(),
         );
 
         let transform_tap_events: Vec<_> =
@@ -956,6 +1114,7 @@ mod tests {
             BlackholeConfig {
                 print_interval_secs: 1,
                 rate: None,
+                latency_key: None,
                 acknowledgements: Default::default(),
             },
         );
@@ -967,6 +1126,8 @@ mod tests {
             TapPatterns::new(HashSet::from(["transform*".to_string()]), HashSet::new()),
             500,
             100,
+            None,
+            TapSampling::default(),
         );
 
         let transform_tap_notifications = transform_tap_all_outputs_stream.next().await.unwrap();
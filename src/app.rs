@@ -52,6 +52,8 @@ pub struct ApplicationConfig {
     pub enterprise: Option<EnterpriseReporter<BoxFuture<'static, ()>>>,
     pub signal_handler: signal::SignalHandler,
     pub signal_rx: signal::SignalRx,
+    /// Secrets resolved for the running configuration.
+    pub resolved_secrets: config::ResolvedSecrets,
 }
 
 pub struct Application {
@@ -206,12 +208,14 @@ impl Application {
                 #[cfg(not(feature = "enterprise-tests"))]
                 config::init_log_schema(&config_paths, true).map_err(handle_config_errors)?;
 
-                let mut config = config::load_from_paths_with_provider_and_secrets(
-                    &config_paths,
-                    &mut signal_handler,
-                )
-                .await
-                .map_err(handle_config_errors)?;
+                let (mut config, resolved_secrets) =
+                    config::load_from_paths_with_provider_and_secrets(
+                        &config_paths,
+                        &config::ResolvedSecrets::default(),
+                        &mut signal_handler,
+                    )
+                    .await
+                    .map_err(handle_config_errors)?;
 
                 if !config.healthchecks.enabled {
                     info!("Health checks are disabled.");
@@ -259,6 +263,7 @@ impl Application {
                     enterprise,
                     signal_handler,
                     signal_rx,
+                    resolved_secrets,
                 })
             })
         }?;
@@ -288,6 +293,7 @@ impl Application {
 
         let mut signal_handler = self.config.signal_handler;
         let mut signal_rx = self.config.signal_rx;
+        let mut resolved_secrets = self.config.resolved_secrets;
 
         // Any internal_logs sources will have grabbed a copy of the
         // early buffer by this point and set up a subscriber.
@@ -381,15 +387,19 @@ impl Application {
                                 }
                             }
                             Ok(SignalTo::ReloadFromDisk) => {
+                                signal::notify_reload_requested();
+
                                 // Reload paths
                                 config_paths = config::process_paths(&opts.config_paths_with_formats()).unwrap_or(config_paths);
 
-                                // Reload config
-                                let new_config = config::load_from_paths_with_provider_and_secrets(&config_paths, &mut signal_handler)
+                                // Reload config, reusing the secrets of the running one unless asked not to
+                                let no_secrets = config::ResolvedSecrets::default();
+                                let previous_secrets = if opts.refresh_secrets { &no_secrets } else { &resolved_secrets };
+                                let new_config = config::load_from_paths_with_provider_and_secrets(&config_paths, previous_secrets, &mut signal_handler)
                                     .await
                                     .map_err(handle_config_errors).ok();
 
-                                if let Some(mut new_config) = new_config {
+                                if let Some((mut new_config, new_secrets)) = new_config {
                                     new_config.healthchecks.set_require_healthy(opts.require_healthy);
 
                                     #[cfg(feature = "enterprise")]
@@ -412,6 +422,9 @@ impl Application {
                                         .await
                                     {
                                         Ok(true) => {
+                                            // Only keep the new secrets once their configuration is running.
+                                            resolved_secrets = new_secrets;
+
                                             #[cfg(feature = "api")]
                                             // Pass the new config to the API server.
                                             if let Some(ref api_server) = api_server {
@@ -42,6 +42,18 @@ pub enum AwsAuthentication {
         /// The ARN of the role to assume.
         assume_role: String,
 
+        /// The external ID to pass when assuming the role.
+        ///
+        /// Required by roles whose trust policy is shared with a third party, such as a role in
+        /// another AWS account.
+        external_id: Option<String>,
+
+        /// The name of the session, which identifies Vector in the CloudTrail logs of the account
+        /// the role belongs to.
+        ///
+        /// If not set, a name is generated.
+        session_name: Option<String>,
+
         /// Timeout for assuming the role, in seconds.
         load_timeout_secs: Option<u64>,
 
@@ -79,12 +91,21 @@ impl AwsAuthentication {
             }
             AwsAuthentication::Role {
                 assume_role,
+                external_id,
+                session_name,
                 load_timeout_secs,
                 region,
             } => {
                 let auth_region = region.clone().map(Region::new).unwrap_or(service_region);
-                let provider = AssumeRoleProviderBuilder::new(assume_role)
-                    .region(auth_region.clone())
+                let mut builder =
+                    AssumeRoleProviderBuilder::new(assume_role).region(auth_region.clone());
+                if let Some(external_id) = external_id {
+                    builder = builder.external_id(external_id);
+                }
+                if let Some(session_name) = session_name {
+                    builder = builder.session_name(session_name);
+                }
+                let provider = builder
                     .build(default_credentials_provider(auth_region, *load_timeout_secs).await);
 
                 Ok(SharedCredentialsProvider::new(provider))
@@ -201,6 +222,7 @@ mod tests {
                 assume_role,
                 load_timeout_secs,
                 region,
+                ..
             } => {
                 assert_eq!(&assume_role, "auth.root");
                 assert_eq!(load_timeout_secs, Some(10));
@@ -210,6 +232,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parsing_assume_role_with_external_id() {
+        let config = toml::from_str::<ComponentConfig>(
+            r#"
+            auth.assume_role = "arn:aws:iam::123456789012:role/vector"
+            auth.external_id = "id"
+            auth.session_name = "vector"
+        "#,
+        )
+        .unwrap();
+
+        match config.auth {
+            AwsAuthentication::Role {
+                external_id,
+                session_name,
+                ..
+            } => {
+                assert_eq!(external_id.as_deref(), Some("id"));
+                assert_eq!(session_name.as_deref(), Some("vector"));
+            }
+            _ => panic!(),
+        }
+    }
+
     #[test]
     fn parsing_static() {
         let config = toml::from_str::<ComponentConfig>(
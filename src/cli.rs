@@ -148,6 +148,11 @@ pub struct RootOpts {
     #[arg(short, long, env = "VECTOR_WATCH_CONFIG")]
     pub watch_config: bool,
 
+    /// Retrieve every secret again when reloading the configuration, instead of reusing those of
+    /// unchanged components.
+    #[arg(long, env = "VECTOR_REFRESH_SECRETS")]
+    pub refresh_secrets: bool,
+
     /// Set the internal log rate limit
     #[arg(
         short,
@@ -48,6 +48,11 @@ impl Decoder {
         self
     }
 
+    /// Returns the framer, deserializer, and log namespace making up this decoder.
+    pub fn into_parts(self) -> (Framer, Deserializer, LogNamespace) {
+        (self.framer, self.deserializer, self.log_namespace)
+    }
+
     /// Handles the framing result and parses it into a structured event, if
     /// possible.
     ///
@@ -0,0 +1,36 @@
+//! Monotonic timestamps embedded in events to measure their latency through a topology.
+//!
+//! The timestamps count the nanoseconds elapsed since a point fixed for the lifetime of the
+//! process, so they are only meaningful to components of the same Vector instance.
+
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+
+static EPOCH: Lazy<Instant> = Lazy::new(Instant::now);
+
+/// Returns the current monotonic timestamp, in nanoseconds.
+pub(crate) fn monotonic_nanos() -> i64 {
+    EPOCH.elapsed().as_nanos() as i64
+}
+
+/// Returns the time elapsed since the monotonic timestamp `nanos`, or `None` if `nanos` is in the
+/// future, i.e. it wasn't taken by this process.
+pub(crate) fn elapsed_since(nanos: i64) -> Option<Duration> {
+    let now = monotonic_nanos();
+    (nanos >= 0 && nanos <= now).then(|| Duration::from_nanos((now - nanos) as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_elapsed_time() {
+        let start = monotonic_nanos();
+        std::thread::sleep(Duration::from_millis(10));
+        assert!(elapsed_since(start).unwrap() >= Duration::from_millis(10));
+        assert_eq!(elapsed_since(monotonic_nanos() + 1_000_000_000), None);
+        assert_eq!(elapsed_since(-1), None);
+    }
+}
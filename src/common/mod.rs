@@ -16,5 +16,15 @@ pub(crate) mod datadog;
 ))]
 pub(crate) mod sqs;
 
-#[cfg(any(feature = "sources-aws_s3", feature = "sinks-aws_s3"))]
+#[cfg(any(
+    feature = "sources-aws_s3",
+    feature = "sources-aws_sqs",
+    feature = "sinks-aws_s3"
+))]
 pub(crate) mod s3;
+
+#[cfg(any(feature = "sources-prometheus", feature = "sinks-prometheus"))]
+pub(crate) mod prometheus;
+
+#[cfg(any(feature = "sources-demo_logs", feature = "sinks-blackhole"))]
+pub(crate) mod latency;
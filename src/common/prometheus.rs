@@ -17,11 +17,11 @@ fn utc_timestamp(timestamp: Option<i64>, default: DateTime<Utc>) -> DateTime<Utc
         .unwrap_or(default)
 }
 
-pub(super) fn parse_text(packet: &str) -> Result<Vec<Event>, ParserError> {
+pub(crate) fn parse_text(packet: &str) -> Result<Vec<Event>, ParserError> {
     prometheus_parser::parse_text(packet).map(reparse_groups)
 }
 
-pub(super) fn parse_request(request: proto::WriteRequest) -> Result<Vec<Event>, ParserError> {
+pub(crate) fn parse_request(request: proto::WriteRequest) -> Result<Vec<Event>, ParserError> {
     prometheus_parser::parse_request(request).map(reparse_groups)
 }
 
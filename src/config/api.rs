@@ -18,6 +18,26 @@ pub struct Options {
     /// Whether or not to expose the GraphQL playground on the API endpoint.
     #[serde(default = "default_playground")]
     pub playground: bool,
+
+    #[configurable(derived)]
+    pub keepalive: Option<KeepaliveOptions>,
+
+    /// The amount of time, in seconds, a tap subscription is kept running after its client
+    /// disconnected, so that the client can resume it from where it left off.
+    ///
+    /// By default, taps are stopped as soon as their client disconnects.
+    #[serde(default = "default_tap_resume_timeout_secs")]
+    pub tap_resume_timeout_secs: u64,
+
+    /// The amount of time, in seconds, the throughput history of each component is kept for.
+    ///
+    /// The throughput of each component is sampled every second, and the samples of this period
+    /// can be queried with the `componentThroughputHistory` field, so that clients can show some
+    /// history as soon as they connect.
+    ///
+    /// By default, no throughput history is kept.
+    #[serde(default = "default_throughput_history_retention_secs")]
+    pub throughput_history_retention_secs: u64,
 }
 
 impl Default for Options {
@@ -26,10 +46,48 @@ impl Default for Options {
             enabled: default_enabled(),
             playground: default_playground(),
             address: default_address(),
+            keepalive: None,
+            tap_resume_timeout_secs: default_tap_resume_timeout_secs(),
+            throughput_history_retention_secs: default_throughput_history_retention_secs(),
         }
     }
 }
 
+/// WebSocket keepalive options.
+///
+/// When set, the API pings each WebSocket client at a regular interval and closes connections
+/// that stopped responding, so that subscriptions of clients gone away are cleaned up.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct KeepaliveOptions {
+    /// The interval, in seconds, at which clients are pinged.
+    #[serde(default = "default_keepalive_interval_secs")]
+    pub interval_secs: u64,
+
+    /// The amount of time, in seconds, after which a client that hasn't sent anything, pongs
+    /// included, is disconnected.
+    #[serde(default = "default_keepalive_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl Default for KeepaliveOptions {
+    fn default() -> Self {
+        Self {
+            interval_secs: default_keepalive_interval_secs(),
+            timeout_secs: default_keepalive_timeout_secs(),
+        }
+    }
+}
+
+const fn default_keepalive_interval_secs() -> u64 {
+    30
+}
+
+const fn default_keepalive_timeout_secs() -> u64 {
+    90
+}
+
 const fn default_enabled() -> bool {
     false
 }
@@ -45,6 +103,14 @@ const fn default_playground() -> bool {
     true
 }
 
+const fn default_tap_resume_timeout_secs() -> u64 {
+    0
+}
+
+const fn default_throughput_history_retention_secs() -> u64 {
+    0
+}
+
 impl Options {
     pub fn merge(&mut self, other: Self) -> Result<(), String> {
         // Merge options
@@ -66,10 +132,24 @@ impl Options {
             }
         };
 
+        let keepalive = match (self.keepalive, other.keepalive) {
+            (Some(a), Some(b)) if a != b => {
+                return Err("Conflicting `api` keepalive options.".to_string())
+            }
+            (a, b) => a.or(b),
+        };
+
         let options = Options {
             address,
             enabled: self.enabled | other.enabled,
             playground: self.playground & other.playground,
+            keepalive,
+            tap_resume_timeout_secs: self
+                .tap_resume_timeout_secs
+                .max(other.tap_resume_timeout_secs),
+            throughput_history_retention_secs: self
+                .throughput_history_retention_secs
+                .max(other.throughput_history_retention_secs),
         };
 
         *self = options;
@@ -83,6 +163,7 @@ fn bool_merge() {
         enabled: true,
         address: None,
         playground: false,
+        ..Options::default()
     };
 
     a.merge(Options::default()).unwrap();
@@ -93,6 +174,7 @@ fn bool_merge() {
             enabled: true,
             address: default_address(),
             playground: false,
+            ..Options::default()
         }
     );
 }
@@ -104,6 +186,7 @@ fn bind_merge() {
         enabled: true,
         address: Some(address),
         playground: true,
+        ..Options::default()
     };
 
     a.merge(Options::default()).unwrap();
@@ -114,6 +197,7 @@ fn bind_merge() {
             enabled: true,
             address: Some(address),
             playground: true,
+            ..Options::default()
         }
     );
 }
@@ -132,3 +216,24 @@ fn bind_conflict() {
 
     assert!(a.merge(b).is_err());
 }
+
+#[test]
+fn keepalive_conflict() {
+    let mut a = Options {
+        keepalive: Some(KeepaliveOptions::default()),
+        ..Options::default()
+    };
+
+    a.merge(Options::default()).unwrap();
+    assert_eq!(a.keepalive, Some(KeepaliveOptions::default()));
+
+    let b = Options {
+        keepalive: Some(KeepaliveOptions {
+            interval_secs: 5,
+            ..KeepaliveOptions::default()
+        }),
+        ..Options::default()
+    };
+
+    assert!(a.merge(b).is_err());
+}
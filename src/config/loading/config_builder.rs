@@ -32,19 +32,24 @@ impl ConfigBuilderLoader {
 }
 
 impl Process for ConfigBuilderLoader {
-    /// Prepares input for a `ConfigBuilder` by interpolating environment variables.
+    /// Prepares input for a `ConfigBuilder` by interpolating environment variables, and the
+    /// secrets found outside of quoted strings.
     fn prepare<R: Read>(&mut self, input: R) -> Result<(String, Vec<String>), Vec<String>> {
         let (prepared_input, warnings) = prepare_input(input)?;
         let prepared_input = self
             .secrets
             .as_ref()
-            .map(|s| secret::interpolate(&prepared_input, s))
+            .map(|s| secret::interpolate_unquoted(&prepared_input, s))
             .unwrap_or(Ok(prepared_input))?;
         Ok((prepared_input, warnings))
     }
 
-    /// Merge a TOML `Table` with a `ConfigBuilder`. Component types extend specific keys.
-    fn merge(&mut self, table: Table, hint: Option<ComponentHint>) -> Result<(), Vec<String>> {
+    /// Merge a TOML `Table` with a `ConfigBuilder`, once the secrets left in its strings are
+    /// replaced. Component types extend specific keys.
+    fn merge(&mut self, mut table: Table, hint: Option<ComponentHint>) -> Result<(), Vec<String>> {
+        if let Some(secrets) = &self.secrets {
+            secret::interpolate_table(&mut table, secrets)?;
+        }
         match hint {
             Some(ComponentHint::Source) => {
                 self.builder.sources.extend(deserialize_table::<
@@ -132,24 +132,20 @@ pub fn load_from_paths(config_paths: &[ConfigPath]) -> Result<Config, Vec<String
 /// Loads a configuration from paths. Handle secret replacement and if a provider is present
 /// in the builder, the config is used as bootstrapping for a remote source. Otherwise,
 /// provider instantiation is skipped.
+///
+/// Secrets of unchanged components are reused from `previous_secrets`. The secrets resolved for
+/// the loaded configuration are returned alongside it, to be passed to the next reload once the
+/// configuration is running.
 pub async fn load_from_paths_with_provider_and_secrets(
     config_paths: &[ConfigPath],
+    previous_secrets: &ResolvedSecrets,
     signal_handler: &mut signal::SignalHandler,
-) -> Result<Config, Vec<String>> {
-    // Load secret backends first
-    let (mut secrets_backends_loader, secrets_warning) =
-        load_secret_backends_from_paths(config_paths)?;
-    // And then, if needed, retrieve secrets from configured backends
-    let (mut builder, load_warnings) = if secrets_backends_loader.has_secrets_to_retrieve() {
-        debug!(message = "Secret placeholders found, retrieving secrets from configured backends.");
-        let resolved_secrets = secrets_backends_loader
-            .retrieve(&mut signal_handler.subscribe())
-            .map_err(|e| vec![e])?;
-        load_builder_from_paths_with_secrets(config_paths, resolved_secrets)?
-    } else {
-        debug!(message = "No secret placeholder found, skipping secret resolution.");
-        load_builder_from_paths(config_paths)?
-    };
+) -> Result<(Config, ResolvedSecrets), Vec<String>> {
+    let (mut builder, resolved_secrets, load_warnings) = load_builder_from_paths_resolving_secrets(
+        config_paths,
+        previous_secrets,
+        &mut signal_handler.subscribe(),
+    )?;
 
     validation::check_provider(&builder)?;
     signal_handler.clear();
@@ -164,15 +160,11 @@ pub async fn load_from_paths_with_provider_and_secrets(
 
     validation::check_buffer_preconditions(&new_config).await?;
 
-    for warning in secrets_warning
-        .into_iter()
-        .chain(load_warnings)
-        .chain(build_warnings)
-    {
+    for warning in load_warnings.into_iter().chain(build_warnings) {
         warn!("{}", warning);
     }
 
-    Ok(new_config)
+    Ok((new_config, resolved_secrets))
 }
 
 /// Iterators over `ConfigPaths`, and processes a file/dir according to a provided `Loader`.
@@ -231,6 +223,47 @@ pub fn load_builder_from_paths_with_secrets(
     loader_from_paths(ConfigBuilderLoader::with_secrets(secrets), config_paths)
 }
 
+/// Uses `ConfigBuilderLoader` to process `ConfigPaths`, first retrieving the secrets they reference
+/// from the configured backends, if any, and reusing those of `previous` which are still valid.
+///
+/// Secrets are replaced in every string value their placeholders are found in, including in the
+/// paths of the files read by components, such as enrichment tables, TLS keys and VRL programs, so
+/// they are all resolved before any component is built. The values of the secrets are redacted
+/// from the errors and warnings returned.
+pub(crate) fn load_builder_from_paths_resolving_secrets(
+    config_paths: &[ConfigPath],
+    previous: &ResolvedSecrets,
+    signal_rx: &mut signal::SignalRx,
+) -> Result<(ConfigBuilder, ResolvedSecrets, Vec<String>), Vec<String>> {
+    // Load secret backends first
+    let (mut secrets_backends_loader, mut warnings) =
+        load_secret_backends_from_paths(config_paths)?;
+    // And then, if needed, retrieve secrets from configured backends
+    let (builder, resolved, load_warnings) = if secrets_backends_loader.has_secrets_to_retrieve() {
+        debug!(message = "Secret placeholders found, retrieving secrets from configured backends.");
+        let resolved = secrets_backends_loader
+            .retrieve(previous, signal_rx)
+            .map_err(|e| vec![e])?;
+        let redact_all = |messages: Vec<String>| {
+            messages
+                .iter()
+                .map(|message| secret::redact(message, resolved.secrets()))
+                .collect::<Vec<_>>()
+        };
+        let (builder, load_warnings) =
+            load_builder_from_paths_with_secrets(config_paths, resolved.secrets().clone())
+                .map_err(redact_all)?;
+        let load_warnings = redact_all(load_warnings);
+        (builder, resolved, load_warnings)
+    } else {
+        debug!(message = "No secret placeholder found, skipping secret resolution.");
+        let (builder, load_warnings) = load_builder_from_paths(config_paths)?;
+        (builder, ResolvedSecrets::default(), load_warnings)
+    };
+    warnings.extend(load_warnings);
+    Ok((builder, resolved, warnings))
+}
+
 /// Uses `SourceLoader` to process `ConfigPaths`, deserializing to a toml `SourceMap`.
 pub fn load_source_from_paths(
     config_paths: &[ConfigPath],
@@ -1,17 +1,24 @@
-use std::{collections::HashMap, io::Read};
+use std::{
+    collections::{HashMap, HashSet},
+    io::Read,
+    time::Instant,
+};
 
 use indexmap::IndexMap;
 use once_cell::sync::Lazy;
 use regex::{Captures, Regex};
 use serde::{Deserialize, Serialize};
-use toml::value::Table;
+use toml::value::{Table, Value};
 use vector_common::config::ComponentKey;
+use vector_config::NamedComponent;
 
 use crate::{
     config::{
         loading::{deserialize_table, prepare_input, process::Process, ComponentHint, Loader},
         SecretBackend,
     },
+    emit,
+    internal_events::{SecretRetrievalFailed, SecretsRetrieved, SecretsReused},
     secrets::SecretBackends,
     signal,
 };
@@ -21,10 +28,20 @@ use crate::{
 // - "SECRET[backend.secret_name]" will match and capture "backend" and "secret_name"
 // - "SECRET[backend.secret.name]" will match and catpure "backend" and "secret.name"
 // - "SECRET[backend..secret.name]" will match and catpure "backend" and ".secret.name"
+// - "SECRET[backend.my-secret/name]" will match and capture "backend" and "my-secret/name"
 // - "SECRET[secret_name]" will not match
 // - "SECRET[.secret.name]" wil not match
 static COLLECTOR: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"SECRET\[([[:word:]]+)\.([[:word:].]+)\]").unwrap());
+    Lazy::new(|| Regex::new(r"SECRET\[([[:word:]]+)\.([[:word:]./-]+)\]").unwrap());
+
+/// Top-level configuration fields holding components whose secrets are tracked individually.
+const COMPONENT_FIELDS: [&str; 4] = ["sources", "transforms", "sinks", "enrichment_tables"];
+
+/// What the values of secrets are replaced with by `redact`.
+const REDACTED: &str = "**REDACTED**";
+
+/// The length from which the values of secrets are redacted wherever they appear in a message.
+const MIN_REDACTED_LENGTH: usize = 4;
 
 /// Helper type for specifically deserializing secrets backends.
 #[derive(Debug, Default, Deserialize, Serialize)]
@@ -33,11 +50,42 @@ pub(crate) struct SecretBackendOuter {
     pub(crate) secret: IndexMap<ComponentKey, SecretBackends>,
 }
 
+/// Secrets referenced by a single component.
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct ComponentSecrets {
+    /// The raw configuration of the component, before secret interpolation.
+    config: String,
+    /// The secrets referenced by the component, as `backend.key`.
+    secrets: HashSet<String>,
+}
+
+/// Secrets resolved by a load, along with the configuration they were resolved for.
+///
+/// These are owned by the caller reloading the configuration, which should only keep them once
+/// the configuration they were resolved for is running, so that a reload only retrieves what
+/// changed since then.
+#[derive(Debug, Default)]
+pub struct ResolvedSecrets {
+    backends: HashMap<String, String>,
+    components: HashMap<String, String>,
+    secrets: HashMap<String, String>,
+}
+
+impl ResolvedSecrets {
+    /// The resolved secrets, keyed by `backend.key`.
+    pub(crate) fn secrets(&self) -> &HashMap<String, String> {
+        &self.secrets
+    }
+}
+
 /// Loader for secrets backends.
 #[derive(Debug, Default, Deserialize, Serialize)]
 pub struct SecretBackendLoader {
     backends: IndexMap<ComponentKey, SecretBackends>,
     pub(crate) secret_keys: HashMap<String, Vec<String>>,
+    backend_configs: HashMap<String, String>,
+    components: HashMap<String, ComponentSecrets>,
+    unowned_secrets: HashSet<String>,
 }
 
 impl SecretBackendLoader {
@@ -45,40 +93,159 @@ impl SecretBackendLoader {
         Self {
             backends: IndexMap::new(),
             secret_keys: HashMap::new(),
+            backend_configs: HashMap::new(),
+            components: HashMap::new(),
+            unowned_secrets: HashSet::new(),
         }
     }
 
+    /// Retrieves the secrets referenced in the configuration.
+    ///
+    /// Secrets found in `previous` are reused as long as their backend is reusable, and every
+    /// component referencing them, as well as the backend holding them, is unchanged since they
+    /// were resolved. Secrets referenced outside of components are always retrieved again.
+    /// `previous` is left untouched: the secrets resolved for this configuration are returned
+    /// instead.
     pub(crate) fn retrieve(
         &mut self,
+        previous: &ResolvedSecrets,
         signal_rx: &mut signal::SignalRx,
-    ) -> Result<HashMap<String, String>, String> {
-        let secrets = self.secret_keys.iter().flat_map(|(backend_name, keys)| {
-            match self.backends.get_mut(&ComponentKey::from(backend_name.clone())) {
-                None => {
-                    vec![Err(format!("Backend \"{}\" is required for secret retrieval but was not found in config.", backend_name))]
-                },
-                Some(backend) => {
-                    debug!(message = "Retrieving secret from a backend.", backend = ?backend_name);
-                    match backend.retrieve(keys.to_vec(), signal_rx) {
-                        Err(e) => {
-                            vec![Err(format!("Error while retrieving secret from backend \"{}\": {}.", backend_name, e))]
-                        },
-                        Ok(s) => {
-                            s.into_iter().map(|(k, v)| {
-                                trace!(message = "Successfully retrieved a secret.", backend = ?backend_name, secret_key = ?k);
-                                Ok((format!("{}.{}", backend_name, k), v))
-                            }).collect::<Vec<Result<(String, String), String>>>()
-                        }
+    ) -> Result<ResolvedSecrets, String> {
+        let stale = self.stale_secrets(previous);
+        let mut secrets = HashMap::new();
+        for (backend_name, keys) in &self.secret_keys {
+            let backend = self
+                .backends
+                .get_mut(&ComponentKey::from(backend_name.clone()))
+                .ok_or_else(|| {
+                    format!(
+                        "Backend \"{}\" is required for secret retrieval but was not found in config.",
+                        backend_name
+                    )
+                })?;
+            let backend_type = backend.get_component_name();
+
+            let mut missing = Vec::new();
+            for key in keys {
+                let id = format!("{}.{}", backend_name, key);
+                match previous.secrets.get(&id) {
+                    Some(value) if !stale.contains(&id) => {
+                        trace!(message = "Reusing previously retrieved secret.", backend = ?backend_name, secret_key = ?key);
+                        secrets.insert(id, value.clone());
                     }
-                },
+                    _ => missing.push(key.clone()),
+                }
+            }
+            let reused = keys.len() - missing.len();
+            if reused > 0 {
+                emit!(SecretsReused {
+                    backend: backend_name,
+                    backend_type,
+                    count: reused,
+                });
+            }
+            if missing.is_empty() {
+                continue;
+            }
+
+            let span = info_span!("secret_retrieval", backend = %backend_name, backend_type);
+            let _enter = span.enter();
+            debug!(message = "Retrieving secret from a backend.", backend = ?backend_name);
+            let start = Instant::now();
+            let retrieved = backend.retrieve(missing, signal_rx).map_err(|e| {
+                emit!(SecretRetrievalFailed {
+                    backend: backend_name,
+                    backend_type,
+                    duration: start.elapsed(),
+                });
+                format!(
+                    "Error while retrieving secret from backend \"{}\": {}.",
+                    backend_name, e
+                )
+            })?;
+            emit!(SecretsRetrieved {
+                backend: backend_name,
+                backend_type,
+                count: retrieved.len(),
+                duration: start.elapsed(),
+            });
+            for (k, v) in retrieved {
+                trace!(message = "Successfully retrieved a secret.", backend = ?backend_name, secret_key = ?k);
+                secrets.insert(format!("{}.{}", backend_name, k), v);
             }
-        }).collect::<Result<HashMap<String, String>, String>>()?;
-        Ok(secrets)
+        }
+
+        Ok(ResolvedSecrets {
+            backends: self.backend_configs.clone(),
+            components: self
+                .components
+                .iter()
+                .map(|(id, component)| (id.clone(), component.config.clone()))
+                .collect(),
+            secrets,
+        })
     }
 
     pub(crate) fn has_secrets_to_retrieve(&self) -> bool {
         !self.secret_keys.is_empty()
     }
+
+    /// Returns the secrets which can't be reused from `previous`: those referenced outside of
+    /// components or by a changed component, and those held by a changed or non-reusable backend.
+    fn stale_secrets(&self, previous: &ResolvedSecrets) -> HashSet<String> {
+        let mut stale = self.unowned_secrets.clone();
+        for (id, component) in &self.components {
+            if previous.components.get(id) != Some(&component.config) {
+                stale.extend(component.secrets.iter().cloned());
+            }
+        }
+        for (backend_name, keys) in &self.secret_keys {
+            let reusable = self
+                .backends
+                .get(&ComponentKey::from(backend_name.clone()))
+                .map_or(false, |backend| backend.reusable());
+            if !reusable
+                || previous.backends.get(backend_name) != self.backend_configs.get(backend_name)
+            {
+                stale.extend(keys.iter().map(|key| format!("{}.{}", backend_name, key)));
+            }
+        }
+        stale
+    }
+
+    /// Records which secrets are referenced by each component of `table`, and which are
+    /// referenced outside of any component.
+    fn track_references(&mut self, table: &Table, hint: Option<ComponentHint>) {
+        let mut remainder = table.clone();
+        match hint {
+            Some(ComponentHint::Test) => {}
+            Some(hint) => {
+                self.track_components(hint.as_component_field(), table);
+                remainder.clear();
+            }
+            None => {
+                for field in COMPONENT_FIELDS {
+                    if let Some(Value::Table(components)) = table.get(field) {
+                        self.track_components(field, components);
+                        remainder.remove(field);
+                    }
+                }
+            }
+        }
+        self.unowned_secrets
+            .extend(secret_ids(&Value::Table(remainder).to_string()));
+    }
+
+    fn track_components(&mut self, field: &str, components: &Table) {
+        for (id, component) in components {
+            let config = component.to_string();
+            let secrets = secret_ids(&config).collect();
+            self.components.insert(
+                format!("{}.{}", field, id),
+                ComponentSecrets { config, secrets },
+            );
+        }
+    }
 }
 
 impl Process for SecretBackendLoader {
@@ -86,10 +253,22 @@ impl Process for SecretBackendLoader {
         let (config_string, warnings) = prepare_input(input)?;
         // Collect secret placeholders just after env var processing
         collect_secret_keys(&config_string, &mut self.secret_keys);
+        // Quote the placeholders standing for something other than a string, such as a number or
+        // a table name, so that the configuration parses before they are replaced.
+        let config_string =
+            replace_unquoted(&config_string, |placeholder| format!("\"{}\"", placeholder));
         Ok((config_string, warnings))
     }
 
-    fn merge(&mut self, table: Table, _: Option<ComponentHint>) -> Result<(), Vec<String>> {
+    fn merge(&mut self, table: Table, hint: Option<ComponentHint>) -> Result<(), Vec<String>> {
+        self.track_references(&table, hint);
+        if let Some(Value::Table(backends)) = table.get("secret") {
+            self.backend_configs.extend(
+                backends
+                    .iter()
+                    .map(|(name, backend)| (name.clone(), backend.to_string())),
+            );
+        }
         if table.contains_key("secret") {
             let additional = deserialize_table::<SecretBackendOuter>(table)?;
             self.backends.extend(additional.secret);
@@ -104,6 +283,13 @@ impl Loader<SecretBackendLoader> for SecretBackendLoader {
     }
 }
 
+/// Returns the secrets referenced in `input`, as `backend.key`.
+fn secret_ids(input: &str) -> impl Iterator<Item = String> + '_ {
+    COLLECTOR
+        .captures_iter(input)
+        .map(|cap| format!("{}.{}", &cap[1], &cap[2]))
+}
+
 fn collect_secret_keys(input: &str, keys: &mut HashMap<String, Vec<String>>) {
     COLLECTOR.captures_iter(input).for_each(|cap| {
         if let (Some(backend), Some(key)) = (cap.get(1), cap.get(2)) {
@@ -116,6 +302,165 @@ fn collect_secret_keys(input: &str, keys: &mut HashMap<String, Vec<String>>) {
     });
 }
 
+/// Replaces the secret placeholders of `input` which are outside of quoted strings, such as those
+/// standing for numbers, booleans or table names.
+///
+/// Placeholders within quotes are left to `interpolate_table`, once the configuration is parsed,
+/// so that their secrets can hold characters which would otherwise need escaping, such as quotes
+/// or newlines. Quotes are only tracked within a line, so placeholders in multi-line strings are
+/// replaced here too.
+pub fn interpolate_unquoted(
+    input: &str,
+    secrets: &HashMap<String, String>,
+) -> Result<String, Vec<String>> {
+    let mut errors = Vec::new();
+    let output = replace_unquoted(input, |placeholder| {
+        interpolate(placeholder, secrets).unwrap_or_else(|interpolation_errors| {
+            errors.extend(interpolation_errors);
+            String::new()
+        })
+    });
+    if errors.is_empty() {
+        Ok(output)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Replaces the secret placeholders of `input` which are outside of quoted strings with the
+/// result of `replace`.
+fn replace_unquoted(input: &str, mut replace: impl FnMut(&str) -> String) -> String {
+    input
+        .split_inclusive('\n')
+        .map(|line| {
+            let mut replaced = String::with_capacity(line.len());
+            let mut last = 0;
+            for placeholder in COLLECTOR.find_iter(line) {
+                if is_quoted(&line[..placeholder.start()]) {
+                    continue;
+                }
+                replaced.push_str(&line[last..placeholder.start()]);
+                replaced.push_str(&replace(placeholder.as_str()));
+                last = placeholder.end();
+            }
+            replaced.push_str(&line[last..]);
+            replaced
+        })
+        .collect()
+}
+
+/// Returns whether the end of `prefix`, the start of a line, is within a quoted string.
+fn is_quoted(prefix: &str) -> bool {
+    let mut quote = None;
+    let mut chars = prefix.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some('"'), '\\') => {
+                chars.next();
+            }
+            (Some(open), _) if open == c => quote = None,
+            _ => {}
+        }
+    }
+    quote.is_some()
+}
+
+/// Replaces the secret placeholders in every string value and key of `table`, including those of
+/// nested tables and arrays.
+///
+/// Replacing them in the parsed values, rather than in the raw configuration, lets secrets hold
+/// characters which would otherwise need escaping in the configuration format, such as quotes or
+/// newlines.
+pub fn interpolate_table(
+    table: &mut Table,
+    secrets: &HashMap<String, String>,
+) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+    interpolate_entries(table, secrets, &mut errors);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn interpolate_entries(
+    table: &mut Table,
+    secrets: &HashMap<String, String>,
+    errors: &mut Vec<String>,
+) {
+    let entries = std::mem::take(table);
+    for (key, mut value) in entries {
+        interpolate_value(&mut value, secrets, errors);
+        let key = if COLLECTOR.is_match(&key) {
+            interpolate(&key, secrets).unwrap_or_else(|interpolation_errors| {
+                errors.extend(interpolation_errors);
+                key
+            })
+        } else {
+            key
+        };
+        table.insert(key, value);
+    }
+}
+
+fn interpolate_value(
+    value: &mut Value,
+    secrets: &HashMap<String, String>,
+    errors: &mut Vec<String>,
+) {
+    match value {
+        Value::String(string) if COLLECTOR.is_match(string) => match interpolate(string, secrets) {
+            Ok(interpolated) => *string = interpolated,
+            Err(interpolation_errors) => errors.extend(interpolation_errors),
+        },
+        Value::Array(values) => {
+            for value in values {
+                interpolate_value(value, secrets, errors);
+            }
+        }
+        Value::Table(table) => interpolate_entries(table, secrets, errors),
+        _ => {}
+    }
+}
+
+/// Replaces the values of `secrets` found in `message`, so that it can be logged or displayed.
+///
+/// Values shorter than `MIN_REDACTED_LENGTH` are only replaced where they make up a whole token,
+/// as they would otherwise mangle unrelated parts of the message.
+pub fn redact(message: &str, secrets: &HashMap<String, String>) -> String {
+    secrets.values().filter(|secret| !secret.is_empty()).fold(
+        message.to_string(),
+        |message, secret| {
+            if secret.chars().count() >= MIN_REDACTED_LENGTH {
+                message.replace(secret.as_str(), REDACTED)
+            } else {
+                redact_tokens(&message, secret)
+            }
+        },
+    )
+}
+
+/// Replaces the occurrences of `secret` in `message` which aren't part of a longer word.
+fn redact_tokens(message: &str, secret: &str) -> String {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let mut redacted = String::with_capacity(message.len());
+    let mut last = 0;
+    for (start, _) in message.match_indices(secret) {
+        let end = start + secret.len();
+        let before = message[..start].chars().next_back();
+        let after = message[end..].chars().next();
+        if !before.map_or(false, is_word) && !after.map_or(false, is_word) {
+            redacted.push_str(&message[last..start]);
+            redacted.push_str(REDACTED);
+            last = end;
+        }
+    }
+    redacted.push_str(&message[last..]);
+    redacted
+}
+
 pub fn interpolate(input: &str, secrets: &HashMap<String, String>) -> Result<String, Vec<String>> {
     let mut errors = Vec::<String>::new();
     let output = COLLECTOR
@@ -146,7 +491,46 @@ mod tests {
 
     use indoc::indoc;
 
-    use super::{collect_secret_keys, interpolate};
+    use super::{
+        collect_secret_keys, interpolate, interpolate_table, interpolate_unquoted, redact,
+        ResolvedSecrets, SecretBackendLoader,
+    };
+    use crate::{
+        config::{
+            loading::{load_builder_from_paths_resolving_secrets, process::Process},
+            ConfigPath,
+        },
+        signal,
+    };
+
+    fn load(config: &str) -> SecretBackendLoader {
+        let mut loader = SecretBackendLoader::new();
+        let (input, _) = loader.prepare(config.as_bytes()).unwrap();
+        loader.merge(toml::from_str(&input).unwrap(), None).unwrap();
+        loader
+    }
+
+    fn retrieve(config: &str, previous: &ResolvedSecrets) -> ResolvedSecrets {
+        let (_, mut signal_rx) = signal::SignalHandler::new();
+        load(config).retrieve(previous, &mut signal_rx).unwrap()
+    }
+
+    const CONFIG: &str = indoc! {r#"
+        [api]
+        address = "SECRET[backend.unowned]"
+
+        [secret.backend]
+        type = "test"
+        replacement = "retrieved"
+
+        [sources.unchanged]
+        type = "demo_logs"
+        format = "SECRET[backend.first]"
+
+        [sources.changed]
+        type = "demo_logs"
+        format = "SECRET[backend.second]"
+    "#};
 
     #[test]
     fn replacement() {
@@ -186,6 +570,118 @@ mod tests {
         );
     }
 
+    #[test]
+    fn table_replacement() {
+        let secrets: HashMap<String, String> = vec![
+            ("a.token".into(), "with \"quotes\"\nand newlines".into()),
+            ("a.host".into(), "example.com".into()),
+        ]
+        .into_iter()
+        .collect();
+
+        let mut table: toml::value::Table = toml::from_str(indoc! {r#"
+            [sinks.out]
+            type = "http"
+            uri = "https://SECRET[a.host]/{{ path }}"
+            auth.token = "SECRET[a.token]"
+            request.headers = ["SECRET[a.token]"]
+            batch.max_events = 10
+
+            [sinks.out.labels]
+            "SECRET[a.host]" = "host"
+        "#})
+        .unwrap();
+        interpolate_table(&mut table, &secrets).unwrap();
+
+        let sink = &table["sinks"]["out"];
+        assert_eq!(sink["uri"].as_str(), Some("https://example.com/{{ path }}"));
+        assert_eq!(
+            sink["auth"]["token"].as_str(),
+            Some("with \"quotes\"\nand newlines")
+        );
+        assert_eq!(
+            sink["request"]["headers"][0].as_str(),
+            Some("with \"quotes\"\nand newlines")
+        );
+        assert_eq!(sink["batch"]["max_events"].as_integer(), Some(10));
+        assert_eq!(sink["labels"]["example.com"].as_str(), Some("host"));
+
+        let mut table: toml::value::Table =
+            toml::from_str(r#"address = "SECRET[a.missing]""#).unwrap();
+        assert_eq!(
+            interpolate_table(&mut table, &secrets),
+            Err(vec![
+                "Unable to find secret replacement for SECRET[a.missing].".into()
+            ])
+        );
+    }
+
+    #[test]
+    fn unquoted_replacement() {
+        let secrets: HashMap<String, String> = vec![
+            ("a.port".into(), "9000".into()),
+            ("a.name".into(), "in".into()),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            interpolate_unquoted(
+                indoc! {r#"
+                    [sources.SECRET[a.name]]
+                    port = SECRET[a.port]
+                    address = "0.0.0.0:SECRET[a.port]"
+                    path = 'SECRET[a.name]' # SECRET[a.port]
+                    escaped = "\"SECRET[a.name]"
+                "#},
+                &secrets
+            ),
+            Ok(indoc! {r#"
+                [sources.in]
+                port = 9000
+                address = "0.0.0.0:SECRET[a.port]"
+                path = 'SECRET[a.name]' # 9000
+                escaped = "\"SECRET[a.name]"
+            "#}
+            .into())
+        );
+        assert_eq!(
+            interpolate_unquoted("port = SECRET[a.missing]", &secrets),
+            Err(vec![
+                "Unable to find secret replacement for SECRET[a.missing].".into()
+            ])
+        );
+    }
+
+    #[test]
+    fn redaction() {
+        let secrets: HashMap<String, String> = vec![
+            ("a.password".into(), "hunter2".into()),
+            ("a.empty".into(), "".into()),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            redact("invalid value: string \"hunter2\"", &secrets),
+            "invalid value: string \"**REDACTED**\""
+        );
+    }
+
+    #[test]
+    fn redaction_of_short_secrets() {
+        let secrets: HashMap<String, String> =
+            vec![("a.port".into(), "2".into())].into_iter().collect();
+
+        assert_eq!(
+            redact(
+                "invalid value: string \"2\", expected 12 characters at line 22",
+                &secrets
+            ),
+            "invalid value: string \"**REDACTED**\", expected 12 characters at line 22"
+        );
+    }
+
     #[test]
     fn collection() {
         let mut keys = HashMap::new();
@@ -197,6 +693,7 @@ mod tests {
             SECRET[second_backend.secret.key]
             SECRET[first_backend.a_third.secret_key]
             SECRET[first_backend...an_extra_secret_key]
+            SECRET[second_backend.my-secret/key]
             SECRET[non_matching_syntax]
             SECRET[.non.matching.syntax]
         "#},
@@ -214,8 +711,112 @@ mod tests {
         assert!(first_backend_keys.contains(&"..an_extra_secret_key".into()));
 
         let second_backend_keys = keys.get("second_backend").unwrap();
-        assert_eq!(second_backend_keys.len(), 2);
+        assert_eq!(second_backend_keys.len(), 3);
         assert!(second_backend_keys.contains(&"secret_key".into()));
+        assert!(second_backend_keys.contains(&"my-secret/key".into()));
         assert!(second_backend_keys.contains(&"secret.key".into()));
     }
+
+    #[test]
+    fn reuses_secrets_of_unchanged_components() {
+        let mut resolved = retrieve(CONFIG, &ResolvedSecrets::default());
+        assert_eq!(resolved.secrets.len(), 3);
+        assert!(resolved.secrets.values().all(|value| value == "retrieved"));
+
+        // Stand in for secrets having been rotated in the backend since the last load.
+        for value in resolved.secrets.values_mut() {
+            *value = "cached".into();
+        }
+
+        let changed = CONFIG.replace(
+            "format = \"SECRET[backend.second]\"",
+            "format = \"SECRET[backend.second]\"\ninterval = 2.0",
+        );
+        let secrets = retrieve(&changed, &resolved).secrets;
+        assert_eq!(secrets["backend.first"], "cached");
+        assert_eq!(secrets["backend.second"], "retrieved");
+        assert_eq!(secrets["backend.unowned"], "retrieved");
+    }
+
+    #[test]
+    fn retrieves_all_secrets_of_changed_backend() {
+        let mut resolved = retrieve(CONFIG, &ResolvedSecrets::default());
+        for value in resolved.secrets.values_mut() {
+            *value = "cached".into();
+        }
+
+        let changed = CONFIG.replace("replacement = \"retrieved\"", "replacement = \"rotated\"");
+        let secrets = retrieve(&changed, &resolved).secrets;
+        assert_eq!(secrets.len(), 3);
+        assert!(secrets.values().all(|value| value == "rotated"));
+    }
+
+    #[test]
+    fn resolves_secrets_in_file_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vector.toml");
+        std::fs::write(
+            &path,
+            indoc! {r#"
+                [secret.backend]
+                type = "test"
+                replacement = "/etc/vector/\"tables\""
+
+                [enrichment_tables.hosts]
+                type = "file"
+                file.path = "SECRET[backend.tables_dir]/hosts.csv"
+                file.encoding.type = "csv"
+            "#},
+        )
+        .unwrap();
+
+        let (_, mut signal_rx) = signal::SignalHandler::new();
+        let (builder, _, _) = load_builder_from_paths_resolving_secrets(
+            &[ConfigPath::File(path, None)],
+            &ResolvedSecrets::default(),
+            &mut signal_rx,
+        )
+        .unwrap();
+        let config = serde_json::to_value(&builder.enrichment_tables).unwrap();
+        assert_eq!(
+            config["hosts"]["file"]["path"],
+            "/etc/vector/\"tables\"/hosts.csv"
+        );
+    }
+
+    #[test]
+    fn resolves_secrets_outside_of_strings() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vector.toml");
+        std::fs::write(
+            &path,
+            indoc! {r#"
+                [secret.number]
+                type = "test"
+                replacement = "2"
+
+                [secret.name]
+                type = "test"
+                replacement = "demo"
+
+                [sources.SECRET[name.source]]
+                type = "demo_logs"
+                format = "shuffle"
+                lines = ["SECRET[name.line]"]
+                interval = SECRET[number.interval]
+            "#},
+        )
+        .unwrap();
+
+        let (_, mut signal_rx) = signal::SignalHandler::new();
+        let (builder, _, _) = load_builder_from_paths_resolving_secrets(
+            &[ConfigPath::File(path, None)],
+            &ResolvedSecrets::default(),
+            &mut signal_rx,
+        )
+        .unwrap();
+        let config = serde_json::to_value(&builder.sources).unwrap();
+        assert_eq!(config["demo"]["interval"], 2.0);
+        assert_eq!(config["demo"]["lines"][0], "demo");
+    }
 }
@@ -13,4 +13,13 @@ pub trait SecretBackend: NamedComponent + core::fmt::Debug + Send + Sync {
         secret_keys: Vec<String>,
         signal_rx: &mut signal::SignalRx,
     ) -> crate::Result<HashMap<String, String>>;
+
+    /// Whether the secrets retrieved from this backend can be reused by a reload of the
+    /// configuration, as long as the backend and the components referencing them are unchanged.
+    ///
+    /// Backends holding secrets which may be rotated behind Vector's back shouldn't be reusable,
+    /// so that a reload picks up their current value.
+    fn reusable(&self) -> bool {
+        false
+    }
 }
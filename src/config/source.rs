@@ -11,7 +11,10 @@ use vector_core::{
 };
 
 use super::{schema, ComponentKey, ProxyConfig, Resource};
-use crate::{shutdown::ShutdownSignal, sources::Sources, SourceSender};
+use crate::{
+    internal_telemetry::guard::SelfTelemetryComponents, shutdown::ShutdownSignal, sources::Sources,
+    SourceSender,
+};
 
 /// Fully resolved source component.
 #[configurable_component]
@@ -100,6 +103,9 @@ pub struct SourceContext {
     /// Given a source can expose multiple [`Output`] channels, the ID is tied to the identifier of
     /// that `Output`.
     pub schema_definitions: HashMap<Option<String>, schema::Definition>,
+
+    /// The components of the self-telemetry pipeline of the topology the source is built for.
+    pub self_telemetry: SelfTelemetryComponents,
 }
 
 impl SourceContext {
@@ -120,6 +126,7 @@ impl SourceContext {
                 acknowledgements: false,
                 schema_definitions: HashMap::default(),
                 schema: Default::default(),
+                self_telemetry: Default::default(),
             },
             shutdown,
         )
@@ -139,6 +146,7 @@ impl SourceContext {
             acknowledgements: false,
             schema_definitions: schema_definitions.unwrap_or_default(),
             schema: Default::default(),
+            self_telemetry: Default::default(),
         }
     }
 
@@ -95,6 +95,13 @@ impl TransformOuter<String> {
         if let Some(inner_topology) = expansion {
             let mut children = Vec::new();
 
+            for (name, outputs) in &inner_topology.named_outputs {
+                expansions.insert(
+                    ComponentKey::from(key.port(name)),
+                    outputs.iter().cloned().map(ComponentKey::from).collect(),
+                );
+            }
+
             expansions.insert(
                 key,
                 inner_topology
@@ -119,7 +126,7 @@ impl TransformOuter<String> {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct TransformContext {
     // This is optional because currently there are a lot of places we use `TransformContext` that
     // may not have the relevant data available (e.g. tests). In the future it'd be nice to make it
@@ -287,6 +294,9 @@ pub struct InnerTopologyTransform {
 pub struct InnerTopology {
     pub inner: IndexMap<ComponentKey, InnerTopologyTransform>,
     pub outputs: Vec<(ComponentKey, Vec<Output>)>,
+    /// The named outputs of the expanded transform, each mapped to the inner outputs it stands
+    /// for. Inputs referencing `<transform>.<name>` are connected to those inner outputs.
+    pub named_outputs: IndexMap<String, Vec<String>>,
 }
 
 impl InnerTopology {
@@ -301,4 +311,11 @@ impl InnerTopology {
             })
             .collect()
     }
+
+    /// Adds the inner outputs of each named output in `named_outputs` to those already known.
+    pub fn merge_named_outputs(&mut self, named_outputs: IndexMap<String, Vec<String>>) {
+        for (name, outputs) in named_outputs {
+            self.named_outputs.entry(name).or_default().extend(outputs);
+        }
+    }
 }
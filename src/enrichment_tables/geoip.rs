@@ -1,5 +1,13 @@
-use std::{collections::BTreeMap, fs, net::IpAddr, sync::Arc, time::SystemTime};
+use std::{
+    collections::BTreeMap,
+    fs,
+    net::IpAddr,
+    path::Path,
+    sync::{Arc, Weak},
+    time::{Duration, SystemTime},
+};
 
+use arc_swap::ArcSwap;
 use enrichment::{Case, Condition, IndexHandle, Table};
 use maxminddb::{
     geoip2::{City, ConnectionType, Isp},
@@ -57,6 +65,15 @@ pub struct GeoipConfig {
     /// [locale_docs]: https://support.maxmind.com/hc/en-us/articles/4414877149467-IP-Geolocation-Data#h_01FRRGRYTGZB29ERDBZCX3MR8Q
     #[serde(default = "default_locale")]
     pub locale: String,
+
+    /// The interval, in seconds, between checks for changes to the database file.
+    ///
+    /// When the file has changed, the database is reloaded without reloading the configuration. If
+    /// the new file can't be read, lookups keep using the previous database.
+    ///
+    /// Set to `0` to only reload the database when the configuration is reloaded.
+    #[serde(default = "default_reload_interval_secs")]
+    pub reload_interval_secs: u64,
 }
 
 fn default_locale() -> String {
@@ -71,11 +88,16 @@ fn default_locale() -> String {
     "en".to_string()
 }
 
+const fn default_reload_interval_secs() -> u64 {
+    60
+}
+
 impl GenerateConfig for GeoipConfig {
     fn generate_config() -> toml::Value {
         toml::Value::try_from(Self {
             path: "/path/to/GeoLite2-City.mmdb".to_string(),
             locale: default_locale(),
+            reload_interval_secs: default_reload_interval_secs(),
         })
         .unwrap()
     }
@@ -87,42 +109,103 @@ impl EnrichmentTableConfig for GeoipConfig {
         &self,
         _: &crate::config::GlobalOptions,
     ) -> crate::Result<Box<dyn Table + Send + Sync>> {
-        Ok(Box::new(Geoip::new(self.clone())?))
+        let geoip = Geoip::new(self.clone())?;
+        if self.reload_interval_secs > 0 {
+            tokio::spawn(watch_database(
+                Arc::downgrade(&geoip.database),
+                self.path.clone(),
+                Duration::from_secs(self.reload_interval_secs),
+            ));
+        }
+        Ok(Box::new(geoip))
     }
 }
 
-#[derive(Clone)]
-pub struct Geoip {
-    config: GeoipConfig,
-    dbreader: Arc<maxminddb::Reader<Vec<u8>>>,
-    dbkind: DatabaseKind,
+/// A database file, read into memory.
+struct Database {
+    reader: Reader<Vec<u8>>,
+    kind: DatabaseKind,
     last_modified: SystemTime,
 }
 
-impl Geoip {
-    pub fn new(config: GeoipConfig) -> crate::Result<Self> {
-        let dbreader = Arc::new(Reader::open_readfile(config.path.clone())?);
-        let dbkind = DatabaseKind::from(dbreader.metadata.database_type.as_str());
+impl Database {
+    fn open(path: impl AsRef<Path>) -> crate::Result<Self> {
+        let path = path.as_ref();
+        // Read before the file, so a file changed in between is picked up next time.
+        let last_modified = fs::metadata(path)?.modified()?;
+        let reader = Reader::open_readfile(path)?;
+        let kind = DatabaseKind::from(reader.metadata.database_type.as_str());
 
         // Check if we can read database with dummy Ip.
         let ip = IpAddr::V4(std::net::Ipv4Addr::new(0, 0, 0, 0));
-        let result = match dbkind {
-            DatabaseKind::Asn | DatabaseKind::Isp => dbreader.lookup::<Isp>(ip).map(|_| ()),
-            DatabaseKind::ConnectionType => dbreader.lookup::<ConnectionType>(ip).map(|_| ()),
-            DatabaseKind::City => dbreader.lookup::<City>(ip).map(|_| ()),
+        let result = match kind {
+            DatabaseKind::Asn | DatabaseKind::Isp => reader.lookup::<Isp>(ip).map(|_| ()),
+            DatabaseKind::ConnectionType => reader.lookup::<ConnectionType>(ip).map(|_| ()),
+            DatabaseKind::City => reader.lookup::<City>(ip).map(|_| ()),
         };
 
         match result {
-            Ok(_) | Err(MaxMindDBError::AddressNotFoundError(_)) => Ok(Geoip {
-                last_modified: fs::metadata(&config.path)?.modified()?,
-                dbreader,
-                dbkind,
-                config,
+            Ok(_) | Err(MaxMindDBError::AddressNotFoundError(_)) => Ok(Database {
+                reader,
+                kind,
+                last_modified,
             }),
             Err(error) => Err(error.into()),
         }
     }
 
+    fn is_outdated(&self, path: impl AsRef<Path>) -> bool {
+        matches!(fs::metadata(path)
+            .and_then(|metadata| metadata.modified()),
+            Ok(modified) if modified > self.last_modified)
+    }
+}
+
+/// Reloads the database at `path` whenever it changes, until the table is dropped.
+async fn watch_database(database: Weak<ArcSwap<Database>>, path: String, interval: Duration) {
+    let mut interval = tokio::time::interval(interval);
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+        let database = match database.upgrade() {
+            Some(database) => database,
+            None => break,
+        };
+        if !database.load().is_outdated(&path) {
+            continue;
+        }
+        match Database::open(&path) {
+            Ok(reloaded) => {
+                database.store(Arc::new(reloaded));
+                info!(message = "Reloaded GeoIP database.", %path);
+            }
+            Err(error) => {
+                error!(
+                    message = "Failed to reload GeoIP database, keeping the previous one.",
+                    %path,
+                    %error,
+                    internal_log_rate_limit = true
+                );
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Geoip {
+    config: GeoipConfig,
+    database: Arc<ArcSwap<Database>>,
+}
+
+impl Geoip {
+    pub fn new(config: GeoipConfig) -> crate::Result<Self> {
+        let database = Database::open(&config.path)?;
+        Ok(Geoip {
+            database: Arc::new(ArcSwap::from_pointee(database)),
+            config,
+        })
+    }
+
     fn lookup(&self, ip: IpAddr, select: Option<&[String]>) -> Option<BTreeMap<String, Value>> {
         let mut map = BTreeMap::new();
         let mut add_field = |key: &str, value: Option<Value>| {
@@ -140,9 +223,10 @@ impl Geoip {
             };
         }
 
-        match self.dbkind {
+        let database = self.database.load();
+        match database.kind {
             DatabaseKind::Asn | DatabaseKind::Isp => {
-                let data = self.dbreader.lookup::<Isp>(ip).ok()?;
+                let data = database.reader.lookup::<Isp>(ip).ok()?;
 
                 add_field!("autonomous_system_number", data.autonomous_system_number);
                 add_field!(
@@ -153,7 +237,7 @@ impl Geoip {
                 add_field!("organization", data.organization);
             }
             DatabaseKind::City => {
-                let data = self.dbreader.lookup::<City>(ip).ok()?;
+                let data = database.reader.lookup::<City>(ip).ok()?;
 
                 add_field!(
                     "city_name",
@@ -194,7 +278,7 @@ impl Geoip {
                 add_field!("postal_code", data.postal.and_then(|p| p.code));
             }
             DatabaseKind::ConnectionType => {
-                let data = self.dbreader.lookup::<ConnectionType>(ip).ok()?;
+                let data = database.reader.lookup::<ConnectionType>(ip).ok()?;
 
                 add_field!("connection_type", data.connection_type);
             }
@@ -282,9 +366,7 @@ impl Table for Geoip {
 
     /// Returns true if the underlying data has changed and the table needs reloading.
     fn needs_reload(&self) -> bool {
-        matches!(fs::metadata(&self.config.path)
-            .and_then(|metadata| metadata.modified()),
-            Ok(modified) if modified > self.last_modified)
+        self.database.load().is_outdated(&self.config.path)
     }
 }
 
@@ -425,6 +507,35 @@ mod tests {
         assert!(values.is_none());
     }
 
+    #[tokio::test(start_paused = true)]
+    async fn reloads_replaced_database() {
+        let dir = crate::test_util::temp_dir();
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("GeoLite2.mmdb");
+        std::fs::copy("tests/data/GeoIP2-City-Test.mmdb", &path).unwrap();
+
+        let geoip = Geoip::new(GeoipConfig {
+            path: path.to_str().unwrap().to_string(),
+            locale: default_locale(),
+            reload_interval_secs: 0,
+        })
+        .unwrap();
+        tokio::spawn(watch_database(
+            Arc::downgrade(&geoip.database),
+            geoip.config.path.clone(),
+            Duration::from_secs(1),
+        ));
+        assert!(geoip.lookup("208.192.1.2".parse().unwrap(), None).is_none());
+
+        std::fs::copy("tests/data/GeoIP2-ISP-Test.mmdb", &path).unwrap();
+        // Time is paused, so this returns as soon as the watcher has checked the file again.
+        tokio::time::sleep(Duration::from_secs(2)).await;
+
+        let values = geoip.lookup("208.192.1.2".parse().unwrap(), None).unwrap();
+        assert_eq!(values["isp"], "Verizon Business".into());
+        assert!(!geoip.needs_reload());
+    }
+
     fn find(ip: &str, database: &str) -> Option<BTreeMap<String, Value>> {
         find_select(ip, database, None)
     }
@@ -437,6 +548,7 @@ mod tests {
         Geoip::new(GeoipConfig {
             path: database.to_string(),
             locale: default_locale(),
+            reload_interval_secs: 0,
         })
         .unwrap()
         .find_table_rows(
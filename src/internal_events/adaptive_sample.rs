@@ -0,0 +1,14 @@
+use crate::emit;
+use vector_core::internal_event::{ComponentEventsDropped, InternalEvent, INTENTIONAL};
+
+#[derive(Debug)]
+pub struct AdaptiveSampleEventDiscarded;
+
+impl InternalEvent for AdaptiveSampleEventDiscarded {
+    fn emit(self) {
+        emit!(ComponentEventsDropped::<INTENTIONAL> {
+            count: 1,
+            reason: "Adaptive sample discarded."
+        })
+    }
+}
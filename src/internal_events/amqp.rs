@@ -93,11 +93,90 @@ pub mod source {
 pub mod sink {
     use crate::emit;
     use metrics::counter;
-    use vector_common::internal_event::{
-        error_stage, error_type, ComponentEventsDropped, UNINTENTIONAL,
+    use vector_common::{
+        finalization::EventStatus,
+        internal_event::{error_stage, error_type, ComponentEventsDropped, UNINTENTIONAL},
     };
     use vector_core::internal_event::InternalEvent;
 
+    #[derive(Debug)]
+    pub struct AmqpEventsFinalized<'a> {
+        pub exchange: &'a str,
+        pub status: EventStatus,
+        pub count: usize,
+    }
+
+    impl InternalEvent for AmqpEventsFinalized<'_> {
+        fn emit(self) {
+            let status = match self.status {
+                EventStatus::Dropped => "dropped",
+                EventStatus::Delivered => "delivered",
+                EventStatus::Errored => "errored",
+                EventStatus::Rejected => "rejected",
+                EventStatus::Recorded => "recorded",
+            };
+
+            trace!(
+                message = "Events finalized.",
+                exchange = %self.exchange,
+                status,
+                count = %self.count,
+            );
+            counter!(
+                "amqp_events_finalized_total", self.count as u64,
+                "exchange" => self.exchange.to_owned(),
+                "status" => status,
+            );
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct AmqpDeadLetterExchangeMissing<'a> {
+        pub exchange: &'a str,
+        pub dead_letter_exchange: &'a str,
+    }
+
+    impl InternalEvent for AmqpDeadLetterExchangeMissing<'_> {
+        fn emit(self) {
+            warn!(
+                message = "Published message with a dead-letter exchange which doesn't exist.",
+                exchange = %self.exchange,
+                dead_letter_exchange = %self.dead_letter_exchange,
+                internal_log_rate_limit = true,
+            );
+            counter!(
+                "amqp_dead_letter_exchange_missing_total", 1,
+                "exchange" => self.exchange.to_owned(),
+                "dead_letter_exchange" => self.dead_letter_exchange.to_owned(),
+            );
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct AmqpInvalidMessageTtl<'a> {
+        pub ttl: &'a str,
+    }
+
+    impl InternalEvent for AmqpInvalidMessageTtl<'_> {
+        fn emit(self) {
+            let reason = "Message TTL is not a whole number of milliseconds.";
+
+            error!(
+                message = reason,
+                ttl = %self.ttl,
+                error_type = error_type::TEMPLATE_FAILED,
+                stage = error_stage::PROCESSING,
+                internal_log_rate_limit = true,
+            );
+            counter!(
+                "component_errors_total", 1,
+                "error_type" => error_type::TEMPLATE_FAILED,
+                "stage" => error_stage::PROCESSING,
+            );
+            emit!(ComponentEventsDropped::<UNINTENTIONAL> { count: 1, reason });
+        }
+    }
+
     #[derive(Debug)]
     pub struct AmqpDeliveryError<'a> {
         pub error: &'a lapin::Error,
@@ -151,4 +230,64 @@ pub mod sink {
             });
         }
     }
+
+    #[derive(Debug)]
+    pub struct AmqpNegativeAcknowledgementError<'a> {
+        pub exchange: &'a str,
+        pub count: usize,
+    }
+
+    impl InternalEvent for AmqpNegativeAcknowledgementError<'_> {
+        fn emit(self) {
+            let reason = "Received negative acknowledgement from AMQP server.";
+
+            error!(message = reason,
+                   exchange = %self.exchange,
+                   error_code = "negative_acknowledgement",
+                   error_type = error_type::ACKNOWLEDGMENT_FAILED,
+                   stage = error_stage::SENDING,
+                   internal_log_rate_limit = true,
+            );
+            counter!(
+                "component_errors_total", 1,
+                "error_code" => "negative_acknowledgement",
+                "error_type" => error_type::ACKNOWLEDGMENT_FAILED,
+                "stage" => error_stage::SENDING,
+            );
+            emit!(ComponentEventsDropped::<UNINTENTIONAL> {
+                count: self.count,
+                reason
+            });
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct AmqpMessageReturnedError<'a> {
+        pub exchange: &'a str,
+        pub count: usize,
+    }
+
+    impl InternalEvent for AmqpMessageReturnedError<'_> {
+        fn emit(self) {
+            let reason = "Message was returned as unroutable by AMQP server.";
+
+            error!(message = reason,
+                   exchange = %self.exchange,
+                   error_code = "message_returned",
+                   error_type = error_type::REQUEST_FAILED,
+                   stage = error_stage::SENDING,
+                   internal_log_rate_limit = true,
+            );
+            counter!(
+                "component_errors_total", 1,
+                "error_code" => "message_returned",
+                "error_type" => error_type::REQUEST_FAILED,
+                "stage" => error_stage::SENDING,
+            );
+            emit!(ComponentEventsDropped::<UNINTENTIONAL> {
+                count: self.count,
+                reason
+            });
+        }
+    }
 }
@@ -20,3 +20,59 @@ impl InternalEvent for ApiStarted {
         counter!("api_started_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct ApiAlertTriggered<'a> {
+    pub id: i32,
+    pub component_id: Option<&'a str>,
+    pub metric: &'static str,
+    pub rate: f64,
+    pub threshold: f64,
+}
+
+impl<'a> InternalEvent for ApiAlertTriggered<'a> {
+    fn emit(self) {
+        warn!(
+            message = "Alert triggered.",
+            alert_id = self.id,
+            component_id = self.component_id.unwrap_or("*"),
+            metric = self.metric,
+            rate = self.rate,
+            threshold = self.threshold,
+        );
+        counter!("api_alerts_triggered_total", 1, "metric" => self.metric);
+    }
+}
+
+#[derive(Debug)]
+pub struct ApiAlertResolved<'a> {
+    pub id: i32,
+    pub component_id: Option<&'a str>,
+    pub metric: &'static str,
+}
+
+impl<'a> InternalEvent for ApiAlertResolved<'a> {
+    fn emit(self) {
+        info!(
+            message = "Alert resolved.",
+            alert_id = self.id,
+            component_id = self.component_id.unwrap_or("*"),
+            metric = self.metric,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct ApiTapEventsDropped {
+    pub count: u64,
+}
+
+impl InternalEvent for ApiTapEventsDropped {
+    fn emit(self) {
+        trace!(
+            message = "Tapped events dropped by sampling.",
+            count = self.count
+        );
+        counter!("api_tap_events_dropped_total", self.count);
+    }
+}
@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use metrics::counter;
 use vector_core::internal_event::InternalEvent;
 
@@ -36,3 +38,27 @@ impl InternalEvent for AwsEc2MetadataRefreshError {
         counter!("metadata_refresh_failed_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct AwsEc2MetadataCacheError<'a> {
+    pub error: crate::Error,
+    pub path: &'a Path,
+}
+
+impl<'a> InternalEvent for AwsEc2MetadataCacheError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Unable to access the AWS EC2 metadata cache.",
+            error = %self.error,
+            path = ?self.path,
+            error_type = error_type::IO_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::IO_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
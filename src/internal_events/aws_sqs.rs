@@ -177,6 +177,90 @@ impl<'a> InternalEvent for SqsMessageProcessingSucceeded<'a> {
 
 // AWS SQS source
 
+#[cfg(feature = "sources-aws_sqs")]
+#[derive(Debug)]
+pub struct SqsMessageDecompressionError<'a, E> {
+    pub message_id: &'a str,
+    pub error: &'a E,
+}
+
+#[cfg(feature = "sources-aws_sqs")]
+impl<'a, E: std::fmt::Display> InternalEvent for SqsMessageDecompressionError<'a, E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to decompress SQS message.",
+            message_id = %self.message_id,
+            error = %self.error,
+            error_code = "failed_decompressing_sqs_message",
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "failed_decompressing_sqs_message",
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
+#[cfg(feature = "sources-aws_sqs")]
+#[derive(Debug)]
+pub struct SqsS3NotificationError<'a, E> {
+    pub message_id: &'a str,
+    pub error: &'a E,
+}
+
+#[cfg(feature = "sources-aws_sqs")]
+impl<'a, E: std::fmt::Display> InternalEvent for SqsS3NotificationError<'a, E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to process S3 event notification.",
+            message_id = %self.message_id,
+            error = %self.error,
+            error_code = "failed_processing_s3_notification",
+            error_type = error_type::READER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "failed_processing_s3_notification",
+            "error_type" => error_type::READER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[cfg(feature = "sources-aws_sqs")]
+#[derive(Debug)]
+pub struct SqsPoisonMessage<'a> {
+    pub message_id: &'a str,
+    pub receive_count: u32,
+    pub action: crate::sources::aws_sqs::PoisonMessageAction,
+}
+
+#[cfg(feature = "sources-aws_sqs")]
+impl<'a> InternalEvent for SqsPoisonMessage<'a> {
+    fn emit(self) {
+        use crate::sources::aws_sqs::PoisonMessageAction;
+
+        let action = match self.action {
+            PoisonMessageAction::Route => "route",
+            PoisonMessageAction::Delete => "delete",
+        };
+        warn!(
+            message = "Removing poison message from the SQS queue.",
+            message_id = %self.message_id,
+            receive_count = %self.receive_count,
+            action,
+            internal_log_rate_limit = true,
+        );
+        counter!("sqs_poison_messages_total", 1, "action" => action);
+    }
+}
+
 #[cfg(feature = "sources-aws_sqs")]
 #[derive(Debug)]
 pub struct SqsMessageDeleteError<'a, E> {
@@ -0,0 +1,13 @@
+use std::time::Duration;
+
+use metrics::{register_histogram, Histogram};
+
+vector_common::registered_event!(
+    BlackholeEndToEndLatency => {
+        latency: Histogram = register_histogram!("end_to_end_latency_seconds"),
+    }
+
+    fn emit(&self, latency: Duration) {
+        self.latency.record(latency);
+    }
+);
@@ -1,6 +1,6 @@
 use metrics::counter;
 use vector_common::internal_event::{error_stage, error_type};
-use vector_core::internal_event::InternalEvent;
+use vector_core::{event::BatchStatus, internal_event::InternalEvent};
 
 #[derive(Debug)]
 pub struct FileDescriptorReadError<E> {
@@ -26,3 +26,70 @@ where
         );
     }
 }
+
+#[derive(Debug)]
+pub struct FileDescriptorFrameGap {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl InternalEvent for FileDescriptorFrameGap {
+    fn emit(self) {
+        warn!(
+            message = "Gap detected in frame sequence numbers.",
+            gap_start = self.start,
+            gap_end = self.end,
+            internal_log_rate_limit = true
+        );
+        counter!("frame_sequence_gaps_total", 1);
+        counter!("frames_missing_total", self.end - self.start + 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct FileDescriptorFrameCorrupted {
+    pub sequence: Option<u64>,
+    pub reason: &'static str,
+}
+
+impl InternalEvent for FileDescriptorFrameCorrupted {
+    fn emit(self) {
+        error!(
+            message = "Dropping corrupted frame.",
+            sequence = ?self.sequence,
+            reason = self.reason,
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct FileDescriptorDeliveryFailed {
+    pub status: BatchStatus,
+}
+
+impl InternalEvent for FileDescriptorDeliveryFailed {
+    fn emit(self) {
+        error!(
+            message = "Events were not delivered, stopping reading.",
+            status = ?self.status,
+            error_code = "delivery_failed",
+            error_type = error_type::ACKNOWLEDGMENT_FAILED,
+            stage = error_stage::SENDING,
+            internal_log_rate_limit = true
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "delivery_failed",
+            "error_type" => error_type::ACKNOWLEDGMENT_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct HumioTagValueInvalid<'a> {
+    pub field: &'a str,
+    pub reason: &'static str,
+}
+
+impl<'a> InternalEvent for HumioTagValueInvalid<'a> {
+    fn emit(self) {
+        warn!(
+            message = "Field not sent as a tag, leaving it in the event.",
+            field = %self.field,
+            reason = %self.reason,
+            internal_log_rate_limit = true,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct HumioEventTypeDefaulted<'a> {
+    pub field: &'a str,
+    pub reason: &'static str,
+}
+
+impl<'a> InternalEvent for HumioEventTypeDefaulted<'a> {
+    fn emit(self) {
+        debug!(
+            message = "Event type field replaced with the default.",
+            field = %self.field,
+            reason = %self.reason,
+            internal_log_rate_limit = true,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct HumioTagDropped<'a> {
+    pub tag: &'a str,
+    pub reason: &'static str,
+}
+
+impl<'a> InternalEvent for HumioTagDropped<'a> {
+    fn emit(self) {
+        warn!(
+            message = "Tag not sent.",
+            tag = %self.tag,
+            reason = %self.reason,
+            internal_log_rate_limit = true,
+        );
+    }
+}
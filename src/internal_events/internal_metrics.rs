@@ -1,6 +1,10 @@
-use metrics::counter;
+use std::time::Duration;
+
+use metrics::{counter, gauge};
 use vector_core::internal_event::InternalEvent;
 
+use vector_common::internal_event::{error_stage, error_type};
+
 #[derive(Debug)]
 pub struct InternalMetricsBytesReceived {
     pub byte_size: usize,
@@ -19,3 +23,44 @@ impl InternalEvent for InternalMetricsBytesReceived {
         );
     }
 }
+
+#[derive(Debug)]
+pub struct InternalMetricsProcessScrapeError<E> {
+    pub message: &'static str,
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for InternalMetricsProcessScrapeError<E> {
+    fn emit(self) {
+        error!(
+            message = self.message,
+            error = %self.error,
+            error_type = error_type::READER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::READER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct InternalMetricsScrapeIntervalAdjusted {
+    pub interval: Duration,
+}
+
+impl InternalEvent for InternalMetricsScrapeIntervalAdjusted {
+    fn emit(self) {
+        debug!(
+            message = "Scrape interval adjusted.",
+            interval_secs = %self.interval.as_secs_f64(),
+        );
+        gauge!(
+            "internal_metrics_scrape_interval_seconds",
+            self.interval.as_secs_f64()
+        );
+    }
+}
@@ -33,3 +33,31 @@ impl InternalEvent for MetricToLogSerializeError {
         emit!(ComponentEventsDropped::<UNINTENTIONAL> { count: 1, reason })
     }
 }
+
+#[derive(Debug)]
+pub struct MetricToLogWideEventError<'a> {
+    pub name: &'a str,
+    pub reason: &'static str,
+}
+
+impl<'a> InternalEvent for MetricToLogWideEventError<'a> {
+    fn emit(self) {
+        error!(
+            message = self.reason,
+            metric_name = %self.name,
+            error_type = error_type::INVALID_METRIC,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_limit = true
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::INVALID_METRIC,
+            "stage" => error_stage::PROCESSING,
+        );
+
+        emit!(ComponentEventsDropped::<UNINTENTIONAL> {
+            count: 1,
+            reason: self.reason
+        })
+    }
+}
@@ -1,6 +1,8 @@
 pub mod prelude;
 
 mod adaptive_concurrency;
+#[cfg(feature = "transforms-adaptive_sample")]
+mod adaptive_sample;
 mod aggregate;
 #[cfg(any(feature = "sources-amqp", feature = "sinks-amqp"))]
 mod amqp;
@@ -26,6 +28,8 @@ mod aws_kinesis_firehose;
 #[cfg(any(feature = "sources-aws_s3", feature = "sources-aws_sqs",))]
 mod aws_sqs;
 mod batch;
+#[cfg(feature = "sinks-blackhole")]
+mod blackhole;
 mod codecs;
 mod common;
 mod conditions;
@@ -63,6 +67,8 @@ mod http;
 pub mod http_client;
 #[cfg(feature = "sources-utils-http-client")]
 mod http_client_source;
+#[cfg(feature = "sinks-humio")]
+mod humio;
 #[cfg(feature = "sinks-influxdb")]
 mod influxdb;
 #[cfg(feature = "sources-internal_logs")]
@@ -91,6 +97,8 @@ mod nats;
 mod nginx_metrics;
 mod open;
 mod parser;
+#[cfg(feature = "transforms-pipelines")]
+mod pipelines;
 #[cfg(feature = "sources-postgresql_metrics")]
 mod postgresql_metrics;
 mod process;
@@ -104,6 +112,8 @@ mod redis;
 mod reduce;
 mod remap;
 mod sample;
+mod secrets;
+mod self_telemetry;
 #[cfg(feature = "sinks-sematext")]
 mod sematext_metrics;
 mod socket;
@@ -133,6 +143,8 @@ mod windows;
 #[cfg(feature = "sources-mongodb_metrics")]
 pub(crate) use mongodb_metrics::*;
 
+#[cfg(feature = "transforms-adaptive_sample")]
+pub(crate) use self::adaptive_sample::*;
 #[cfg(feature = "transforms-aggregate")]
 pub(crate) use self::aggregate::*;
 #[cfg(any(feature = "sources-amqp", feature = "sinks-amqp"))]
@@ -158,6 +170,8 @@ pub(crate) use self::aws_kinesis::*;
 pub(crate) use self::aws_kinesis_firehose::*;
 #[cfg(any(feature = "sources-aws_s3", feature = "sources-aws_sqs",))]
 pub(crate) use self::aws_sqs::*;
+#[cfg(feature = "sinks-blackhole")]
+pub(crate) use self::blackhole::*;
 pub(crate) use self::codecs::*;
 #[cfg(feature = "sinks-datadog_metrics")]
 pub(crate) use self::datadog_metrics::*;
@@ -202,6 +216,8 @@ pub(crate) use self::host_metrics::*;
 pub(crate) use self::http::*;
 #[cfg(feature = "sources-utils-http-client")]
 pub(crate) use self::http_client_source::*;
+#[cfg(feature = "sinks-humio")]
+pub(crate) use self::humio::*;
 #[cfg(feature = "sinks-influxdb")]
 pub(crate) use self::influxdb::*;
 #[cfg(feature = "sources-internal_logs")]
@@ -228,6 +244,8 @@ pub(crate) use self::nats::*;
 #[cfg(feature = "sources-nginx_metrics")]
 pub(crate) use self::nginx_metrics::*;
 pub(crate) use self::parser::*;
+#[cfg(feature = "transforms-pipelines")]
+pub(crate) use self::pipelines::*;
 #[cfg(feature = "sources-postgresql_metrics")]
 pub(crate) use self::postgresql_metrics::*;
 #[cfg(any(feature = "sources-prometheus", feature = "sinks-prometheus"))]
@@ -242,6 +260,8 @@ pub(crate) use self::reduce::*;
 pub(crate) use self::remap::*;
 #[cfg(feature = "transforms-sample")]
 pub(crate) use self::sample::*;
+pub(crate) use self::secrets::*;
+pub(crate) use self::self_telemetry::*;
 #[cfg(feature = "sinks-sematext")]
 pub(crate) use self::sematext_metrics::*;
 #[cfg(any(feature = "sources-splunk_hec", feature = "sinks-splunk_hec"))]
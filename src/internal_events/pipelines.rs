@@ -0,0 +1,69 @@
+use std::time::Duration;
+
+use metrics::{register_counter, register_histogram, Counter, Histogram};
+
+/// Events processed by a pipeline, or by one of the transforms within it, in a single batch.
+#[derive(Clone, Copy, Debug)]
+pub struct PipelineBatch {
+    pub received: usize,
+    pub sent: usize,
+    pub elapsed: Duration,
+}
+
+vector_common::registered_event!(
+    PipelineEventsProcessed {
+        pipeline: String,
+    } => {
+        received: Counter = register_counter!("pipeline_received_events_total", "pipeline" => self.pipeline.clone()),
+        sent: Counter = register_counter!("pipeline_sent_events_total", "pipeline" => self.pipeline.clone()),
+        discarded: Counter = register_counter!("pipeline_discarded_events_total", "pipeline" => self.pipeline.clone()),
+        processing_time: Histogram = register_histogram!("pipeline_processing_seconds", "pipeline" => self.pipeline),
+    }
+
+    fn emit(&self, data: PipelineBatch) {
+        self.received.increment(data.received as u64);
+        self.sent.increment(data.sent as u64);
+        self.discarded.increment(data.received.saturating_sub(data.sent) as u64);
+        self.processing_time.record(data.elapsed);
+    }
+);
+
+vector_common::registered_event!(
+    PipelineTransformEventsProcessed {
+        pipeline: String,
+        transform_index: usize,
+        transform_type: &'static str,
+    } => {
+        received: Counter = register_counter!(
+            "pipeline_transform_received_events_total",
+            "pipeline" => self.pipeline.clone(),
+            "transform_index" => self.transform_index.to_string(),
+            "transform_type" => self.transform_type
+        ),
+        sent: Counter = register_counter!(
+            "pipeline_transform_sent_events_total",
+            "pipeline" => self.pipeline.clone(),
+            "transform_index" => self.transform_index.to_string(),
+            "transform_type" => self.transform_type
+        ),
+        discarded: Counter = register_counter!(
+            "pipeline_transform_discarded_events_total",
+            "pipeline" => self.pipeline.clone(),
+            "transform_index" => self.transform_index.to_string(),
+            "transform_type" => self.transform_type
+        ),
+        processing_time: Histogram = register_histogram!(
+            "pipeline_transform_processing_seconds",
+            "pipeline" => self.pipeline,
+            "transform_index" => self.transform_index.to_string(),
+            "transform_type" => self.transform_type
+        ),
+    }
+
+    fn emit(&self, data: PipelineBatch) {
+        self.received.increment(data.received as u64);
+        self.sent.increment(data.sent as u64);
+        self.discarded.increment(data.received.saturating_sub(data.sent) as u64);
+        self.processing_time.record(data.elapsed);
+    }
+);
@@ -3,7 +3,6 @@ use std::borrow::Cow;
 
 use hyper::StatusCode;
 use metrics::counter;
-#[cfg(feature = "sources-prometheus")]
 use prometheus_parser::ParserError;
 use vector_core::internal_event::InternalEvent;
 
@@ -71,6 +70,28 @@ impl InternalEvent for PrometheusRemoteWriteParseError {
     }
 }
 
+#[derive(Debug)]
+pub struct PrometheusPushParseError {
+    pub error: ParserError,
+}
+
+impl InternalEvent for PrometheusPushParseError {
+    fn emit(self) {
+        error!(
+            message = "Could not parse pushed metrics.",
+            error = ?self.error,
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
 #[derive(Debug)]
 pub struct PrometheusServerRequestComplete {
     pub status_code: StatusCode,
@@ -111,3 +132,37 @@ impl InternalEvent for PrometheusNormalizationError {
         });
     }
 }
+
+#[cfg(feature = "sinks-prometheus")]
+#[derive(Debug)]
+pub struct PrometheusRemoteWriteSeriesRejected<'a> {
+    pub status: StatusCode,
+    pub labels: &'a [prometheus_parser::proto::Label],
+    pub body: &'a str,
+}
+
+#[cfg(feature = "sinks-prometheus")]
+impl<'a> InternalEvent for PrometheusRemoteWriteSeriesRejected<'a> {
+    fn emit(self) {
+        let series = self
+            .labels
+            .iter()
+            .map(|label| format!("{}={:?}", label.name, label.value))
+            .collect::<Vec<_>>()
+            .join(",");
+        error!(
+            message = "Series rejected by remote write endpoint.",
+            status = %self.status,
+            series = %format!("{{{}}}", series),
+            body = %self.body,
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::SENDING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+    }
+}
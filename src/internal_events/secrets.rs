@@ -0,0 +1,85 @@
+use std::time::Duration;
+
+use metrics::{counter, histogram};
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct SecretsRetrieved<'a> {
+    pub backend: &'a str,
+    pub backend_type: &'static str,
+    pub count: usize,
+    pub duration: Duration,
+}
+
+impl<'a> InternalEvent for SecretsRetrieved<'a> {
+    fn emit(self) {
+        debug!(
+            message = "Retrieved secrets from a backend.",
+            backend = %self.backend,
+            backend_type = %self.backend_type,
+            count = %self.count,
+            elapsed_millis = %self.duration.as_millis(),
+        );
+        counter!(
+            "secret_retrievals_total", 1,
+            "backend" => self.backend.to_owned(),
+            "backend_type" => self.backend_type,
+        );
+        counter!(
+            "secrets_retrieved_total", self.count as u64,
+            "backend" => self.backend.to_owned(),
+            "backend_type" => self.backend_type,
+        );
+        histogram!(
+            "secret_retrieval_duration_seconds", self.duration,
+            "backend" => self.backend.to_owned(),
+            "backend_type" => self.backend_type,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct SecretRetrievalFailed<'a> {
+    pub backend: &'a str,
+    pub backend_type: &'static str,
+    pub duration: Duration,
+}
+
+impl<'a> InternalEvent for SecretRetrievalFailed<'a> {
+    fn emit(self) {
+        // The error itself fails the configuration load, and is reported there.
+        debug!(
+            message = "Failed to retrieve secrets from a backend.",
+            backend = %self.backend,
+            backend_type = %self.backend_type,
+            elapsed_millis = %self.duration.as_millis(),
+        );
+        counter!(
+            "secret_retrieval_errors_total", 1,
+            "backend" => self.backend.to_owned(),
+            "backend_type" => self.backend_type,
+        );
+        histogram!(
+            "secret_retrieval_duration_seconds", self.duration,
+            "backend" => self.backend.to_owned(),
+            "backend_type" => self.backend_type,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct SecretsReused<'a> {
+    pub backend: &'a str,
+    pub backend_type: &'static str,
+    pub count: usize,
+}
+
+impl<'a> InternalEvent for SecretsReused<'a> {
+    fn emit(self) {
+        counter!(
+            "secret_cache_hits_total", self.count as u64,
+            "backend" => self.backend.to_owned(),
+            "backend_type" => self.backend_type,
+        );
+    }
+}
@@ -0,0 +1,48 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use crate::internal_telemetry::guard::SELF_TELEMETRY_GUARD_TARGET;
+
+#[derive(Debug)]
+pub struct SelfTelemetryThrottled {
+    pub source_type: &'static str,
+    pub utilization: f64,
+    pub max_utilization: f64,
+}
+
+impl InternalEvent for SelfTelemetryThrottled {
+    fn emit(self) {
+        warn!(
+            target: SELF_TELEMETRY_GUARD_TARGET,
+            message = "Self-telemetry is busier than allowed, throttling it.",
+            source_type = %self.source_type,
+            utilization = %self.utilization,
+            max_utilization = %self.max_utilization,
+        );
+        counter!(
+            "self_telemetry_throttled_total", 1,
+            "source_type" => self.source_type,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct SelfTelemetryFeedbackDropped {
+    pub source_type: &'static str,
+    pub count: u64,
+}
+
+impl InternalEvent for SelfTelemetryFeedbackDropped {
+    fn emit(self) {
+        warn!(
+            target: SELF_TELEMETRY_GUARD_TARGET,
+            message = "Dropped telemetry about self-telemetry components to break a feedback loop.",
+            source_type = %self.source_type,
+            count = %self.count,
+        );
+        counter!(
+            "self_telemetry_feedback_dropped_total", self.count,
+            "source_type" => self.source_type,
+        );
+    }
+}
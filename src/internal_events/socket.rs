@@ -131,6 +131,34 @@ impl<E: std::fmt::Display> InternalEvent for SocketBindError<E> {
     }
 }
 
+#[derive(Debug)]
+pub struct SocketMulticastJoinError<E> {
+    pub group: std::net::IpAddr,
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for SocketMulticastJoinError<E> {
+    fn emit(self) {
+        error!(
+            message = "Error joining multicast group.",
+            group = %self.group,
+            error = %self.error,
+            error_code = "socket_multicast_join",
+            error_type = error_type::IO_FAILED,
+            stage = error_stage::RECEIVING,
+            mode = SocketMode::Udp.as_str(),
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "socket_multicast_join",
+            "error_type" => error_type::IO_FAILED,
+            "stage" => error_stage::RECEIVING,
+            "mode" => SocketMode::Udp.as_str(),
+        );
+    }
+}
+
 #[derive(Debug)]
 pub struct SocketReceiveError<E> {
     pub mode: SocketMode,
@@ -7,7 +7,7 @@ pub use self::source::*;
 
 #[cfg(feature = "sinks-splunk_hec")]
 mod sink {
-    use metrics::{counter, decrement_gauge, increment_gauge};
+    use metrics::{counter, decrement_gauge, gauge, increment_gauge};
     use serde_json::Error;
     use vector_core::internal_event::InternalEvent;
 
@@ -168,6 +168,57 @@ mod sink {
         }
     }
 
+    pub struct SplunkEndpointActive<'a> {
+        pub endpoint: &'a str,
+        pub active: bool,
+    }
+
+    impl<'a> InternalEvent for SplunkEndpointActive<'a> {
+        fn emit(self) {
+            debug!(
+                message = "Endpoint activity changed.",
+                endpoint = %self.endpoint,
+                active = %self.active,
+            );
+            gauge!(
+                "active_endpoint", if self.active { 1.0 } else { 0.0 },
+                "endpoint" => self.endpoint.to_owned(),
+            );
+        }
+    }
+
+    pub struct SplunkEndpointUnhealthy<'a> {
+        pub endpoint: &'a str,
+        pub retry_after_secs: u64,
+    }
+
+    impl<'a> InternalEvent for SplunkEndpointUnhealthy<'a> {
+        fn emit(self) {
+            warn!(
+                message = "Endpoint marked unhealthy, failing over to remaining endpoints.",
+                endpoint = %self.endpoint,
+                retry_after_secs = %self.retry_after_secs,
+                internal_log_rate_limit = true,
+            );
+        }
+    }
+
+    pub struct SplunkEndpointHealthCheckFailed<'a> {
+        pub endpoint: &'a str,
+        pub error: &'a crate::Error,
+    }
+
+    impl<'a> InternalEvent for SplunkEndpointHealthCheckFailed<'a> {
+        fn emit(self) {
+            warn!(
+                message = "Endpoint failed its health check.",
+                endpoint = %self.endpoint,
+                error = %self.error,
+                internal_log_rate_limit = true,
+            );
+        }
+    }
+
     pub struct SplunkEventTimestampInvalidType<'a> {
         pub r#type: &'a str,
     }
@@ -1,4 +1,4 @@
-use std::net::SocketAddr;
+use std::{net::SocketAddr, path::Path};
 
 use metrics::counter;
 use vector_common::internal_event::{error_stage, error_type};
@@ -128,6 +128,128 @@ impl InternalEvent for TcpSendAckError {
     }
 }
 
+#[derive(Debug)]
+pub struct TcpSocketTlsClientRejected {
+    pub peer_addr: SocketAddr,
+}
+
+impl InternalEvent for TcpSocketTlsClientRejected {
+    fn emit(self) {
+        error!(
+            message = "Rejected client certificate not matching the allowlist.",
+            peer_addr = %self.peer_addr,
+            error_code = "client_not_allowed",
+            error_type = error_type::CONNECTION_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "client_not_allowed",
+            "error_type" => error_type::CONNECTION_FAILED,
+            "stage" => error_stage::RECEIVING,
+            "mode" => "tcp",
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct TcpFirstFrameRejected {
+    pub peer_addr: SocketAddr,
+}
+
+impl InternalEvent for TcpFirstFrameRejected {
+    fn emit(self) {
+        error!(
+            message = "Rejected connection failing authentication.",
+            peer_addr = %self.peer_addr,
+            error_code = "authentication_failed",
+            error_type = error_type::CONNECTION_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "authentication_failed",
+            "error_type" => error_type::CONNECTION_FAILED,
+            "stage" => error_stage::RECEIVING,
+            "mode" => "tcp",
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct TcpFirstFrameRejectionSendError {
+    pub error: std::io::Error,
+    pub peer_addr: SocketAddr,
+}
+
+impl InternalEvent for TcpFirstFrameRejectionSendError {
+    fn emit(self) {
+        error!(
+            message = "Error writing the response to a connection failing authentication.",
+            error = %self.error,
+            peer_addr = %self.peer_addr,
+            error_code = "authentication_response_failed",
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "authentication_response_failed",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::RECEIVING,
+            "mode" => "tcp",
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct TcpConnectionHandoffError {
+    pub error: std::io::Error,
+}
+
+impl InternalEvent for TcpConnectionHandoffError {
+    fn emit(self) {
+        error!(
+            message = "Failed receiving a handed off connection.",
+            error = %self.error,
+            error_code = "connection_handoff_failed",
+            error_type = error_type::CONNECTION_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "connection_handoff_failed",
+            "error_type" => error_type::CONNECTION_FAILED,
+            "stage" => error_stage::RECEIVING,
+            "mode" => "tcp",
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct TcpConnectionIdleTimeout {
+    pub peer_addr: SocketAddr,
+    pub idle_timeout_secs: u64,
+}
+
+impl InternalEvent for TcpConnectionIdleTimeout {
+    fn emit(self) {
+        debug!(
+            message = "Closing idle connection.",
+            peer_addr = %self.peer_addr,
+            idle_timeout_secs = %self.idle_timeout_secs,
+        );
+        counter!(
+            "connection_idle_timeouts_total", 1,
+            "mode" => "tcp",
+        );
+    }
+}
+
 #[derive(Debug)]
 pub struct TcpBytesReceived {
     pub byte_size: usize,
@@ -149,3 +271,27 @@ impl InternalEvent for TcpBytesReceived {
         );
     }
 }
+
+#[derive(Debug)]
+pub struct TcpWireCaptureWriteError<'a> {
+    pub error: std::io::Error,
+    pub path: &'a Path,
+}
+
+impl<'a> InternalEvent for TcpWireCaptureWriteError<'a> {
+    fn emit(self) {
+        error!(
+            message = "Failed writing connection capture.",
+            error = %self.error,
+            path = ?self.path,
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
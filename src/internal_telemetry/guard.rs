@@ -0,0 +1,381 @@
+//! Keeps Vector's own telemetry from taking resources away from the data plane.
+//!
+//! The `internal_logs` and `internal_metrics` sources, along with every component fed only by
+//! them, form the self-telemetry pipeline. A source with a guard estimates how busy that pipeline
+//! is, and throttles itself when it is busier than allowed. It also drops the telemetry about the
+//! self-telemetry pipeline itself, which would otherwise feed back into it.
+
+use std::{
+    collections::HashSet,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use vector_config::{configurable_component, NamedComponent};
+
+use crate::{
+    config::Config,
+    emit,
+    event::{LogEvent, Metric, MetricValue, Value},
+    internal_events::{SelfTelemetryFeedbackDropped, SelfTelemetryThrottled},
+    metrics::Controller,
+    trace::is_drop_report,
+};
+
+const INVARIANT: &str =
+    "Couldn't acquire lock on the self-telemetry components. Please report this.";
+
+/// The `tracing` target the warnings of the guard are emitted under.
+pub const SELF_TELEMETRY_GUARD_TARGET: &str = "vector::self_telemetry_guard";
+
+/// The prefix of the metrics emitted by the guard.
+const SELF_TELEMETRY_GUARD_METRICS: &str = "self_telemetry_";
+
+/// The types of the sources producing self-telemetry.
+const SELF_TELEMETRY_SOURCES: [&str; 2] = ["internal_logs", "internal_metrics"];
+
+/// Configuration for guarding the data plane against the cost of self-telemetry.
+///
+/// The self-telemetry pipeline is made of the `internal_logs` and `internal_metrics` sources, and
+/// every component whose inputs all come from that pipeline. At the end of each window, the utilization of the pipeline
+/// is computed as the share of the window the source spent producing telemetry, plus the
+/// `utilization` of each of the downstream components. When it exceeds `max_utilization`, the
+/// source is throttled for the following windows, until the utilization falls back under half of
+/// `max_utilization`: `internal_logs` only forwards drop reports, and `internal_metrics` skips its
+/// scrapes.
+///
+/// Utilization is the share of time components spend busy rather than waiting for events, so it
+/// includes time spent waiting on I/O, and doesn't account for the CPU used by other threads on
+/// their behalf. It is a proxy for the CPU used by the pipeline, not a measure of it.
+///
+/// Warnings about the guard can be told apart by their `metadata.target` field, which is always
+/// `vector::self_telemetry_guard`, and are never throttled nor dropped.
+#[configurable_component]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct SelfTelemetryGuardConfig {
+    /// The highest utilization the self-telemetry pipeline may have.
+    ///
+    /// A utilization of `1.0` is that of a single component busy all the time.
+    #[derivative(Default(value = "0.05"))]
+    pub max_utilization: f64,
+
+    /// The window, in seconds, the utilization of the self-telemetry pipeline is computed over.
+    #[derivative(Default(value = "10"))]
+    pub window_secs: u64,
+
+    /// Whether or not to drop the telemetry about the components of the self-telemetry pipeline.
+    ///
+    /// Such telemetry describes the work done to handle telemetry, which in turn produces more of
+    /// it. Drop reports are never dropped, so that data loss is always accounted for.
+    #[derivative(Default(value = "true"))]
+    pub drop_feedback: bool,
+}
+
+/// The ids of the components of the self-telemetry pipeline of a running topology.
+///
+/// The topology updates them whenever its configuration changes, and shares them with the sources
+/// it builds, so that guards see the pipeline as it currently runs.
+#[derive(Clone, Debug, Default)]
+pub struct SelfTelemetryComponents(Arc<RwLock<HashSet<String>>>);
+
+impl SelfTelemetryComponents {
+    /// Records the components of the self-telemetry pipeline of `config`, which is now running.
+    pub fn update(&self, config: &Config) {
+        let sources = config
+            .sources()
+            .filter(|(_, source)| {
+                SELF_TELEMETRY_SOURCES.contains(&source.inner.get_component_name())
+            })
+            .map(|(key, _)| key.id());
+        let nodes = config
+            .transforms()
+            .map(|(key, transform)| (key.id(), &transform.inputs))
+            .chain(config.sinks().map(|(key, sink)| (key.id(), &sink.inputs)))
+            .map(|(id, inputs)| {
+                (
+                    id,
+                    inputs
+                        .iter()
+                        .map(|input| input.component.id())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        *self.0.write().expect(INVARIANT) = fed_only_by(sources, &nodes);
+    }
+
+    fn contains(&self, component_id: &str) -> bool {
+        self.0.read().expect(INVARIANT).contains(component_id)
+    }
+
+    /// Returns the sum of the `utilization` of the transforms and sinks of the self-telemetry
+    /// pipeline.
+    fn downstream_utilization(&self) -> f64 {
+        let controller = match Controller::get() {
+            Ok(controller) => controller,
+            Err(_) => return 0.0,
+        };
+        let metrics = controller.capture_metrics();
+        let components = self.0.read().expect(INVARIANT);
+        metrics
+            .iter()
+            .filter(|metric| metric.name() == "utilization")
+            .filter(|metric| {
+                metric
+                    .tags()
+                    .and_then(|tags| tags.get("component_id"))
+                    .map_or(false, |component_id| components.contains(component_id))
+            })
+            .filter_map(|metric| match metric.value() {
+                MetricValue::Gauge { value } => Some(*value),
+                _ => None,
+            })
+            .sum()
+    }
+}
+
+/// Returns the ids of `sources` and of all the nodes whose inputs all come from them, directly or
+/// through other such nodes.
+///
+/// Nodes also fed by other components are left out, as their work isn't only spent on telemetry.
+fn fed_only_by<'a>(
+    sources: impl Iterator<Item = &'a str>,
+    nodes: &[(&'a str, Vec<&'a str>)],
+) -> HashSet<String> {
+    let mut components = sources.map(str::to_owned).collect::<HashSet<_>>();
+    loop {
+        let downstream = nodes
+            .iter()
+            .filter(|(id, inputs)| {
+                !components.contains(*id)
+                    && !inputs.is_empty()
+                    && inputs.iter().all(|input| components.contains(*input))
+            })
+            .map(|(id, _)| (*id).to_owned())
+            .collect::<Vec<_>>();
+        if downstream.is_empty() {
+            return components;
+        }
+        components.extend(downstream);
+    }
+}
+
+/// Throttles a self-telemetry source, and drops the telemetry feeding back into it.
+pub struct SelfTelemetryGuard {
+    components: SelfTelemetryComponents,
+    source_type: &'static str,
+    max_utilization: f64,
+    window: Duration,
+    drop_feedback: bool,
+    window_start: Instant,
+    busy: Duration,
+    feedback_dropped: u64,
+    throttled: bool,
+}
+
+impl SelfTelemetryGuard {
+    pub fn new(
+        config: &SelfTelemetryGuardConfig,
+        source_type: &'static str,
+        components: SelfTelemetryComponents,
+    ) -> crate::Result<Self> {
+        if !config.max_utilization.is_finite() || config.max_utilization <= 0.0 {
+            return Err("`guard.max_utilization` must be greater than 0.".into());
+        }
+        if config.window_secs == 0 {
+            return Err("`guard.window_secs` must be greater than 0.".into());
+        }
+        Ok(Self {
+            components,
+            source_type,
+            max_utilization: config.max_utilization,
+            window: Duration::from_secs(config.window_secs),
+            drop_feedback: config.drop_feedback,
+            window_start: Instant::now(),
+            busy: Duration::ZERO,
+            feedback_dropped: 0,
+            throttled: false,
+        })
+    }
+
+    /// Whether the source must hold back its telemetry.
+    pub const fn is_throttled(&self) -> bool {
+        self.throttled
+    }
+
+    /// Records time the source spent producing telemetry.
+    pub fn record_busy(&mut self, busy: Duration) {
+        self.busy += busy;
+    }
+
+    /// Returns whether `log` must be dropped, either because the source is throttled or to break a
+    /// feedback loop.
+    pub fn should_drop_log(&mut self, log: &LogEvent) -> bool {
+        let is_guard_warning = log.get("metadata.target").map_or(false, |target| {
+            *target == Value::from(SELF_TELEMETRY_GUARD_TARGET)
+        });
+        if is_drop_report(log) || is_guard_warning {
+            return false;
+        }
+
+        self.throttled
+            || self.is_feedback(
+                log.get("vector.component_id")
+                    .and_then(Value::as_str)
+                    .as_deref(),
+            )
+    }
+
+    /// Returns whether `metric` must be dropped to break a feedback loop.
+    pub fn should_drop_metric(&mut self, metric: &Metric) -> bool {
+        !metric.name().starts_with(SELF_TELEMETRY_GUARD_METRICS)
+            && self.is_feedback(metric.tags().and_then(|tags| tags.get("component_id")))
+    }
+
+    fn is_feedback(&mut self, component_id: Option<&str>) -> bool {
+        let feedback = self.drop_feedback
+            && component_id.map_or(false, |component_id| self.components.contains(component_id));
+        if feedback {
+            self.feedback_dropped += 1;
+        }
+        feedback
+    }
+
+    /// Ends the current window if it is over, deciding whether the source is throttled for the
+    /// next one.
+    pub fn check(&mut self) {
+        let now = Instant::now();
+        if now.duration_since(self.window_start) >= self.window {
+            let downstream_utilization = self.components.downstream_utilization();
+            self.end_window(now, downstream_utilization);
+        }
+    }
+
+    fn end_window(&mut self, now: Instant, downstream_utilization: f64) {
+        let elapsed = now.duration_since(self.window_start).as_secs_f64();
+        let utilization = self.busy.as_secs_f64() / elapsed + downstream_utilization;
+
+        if self.feedback_dropped > 0 {
+            emit!(SelfTelemetryFeedbackDropped {
+                source_type: self.source_type,
+                count: self.feedback_dropped,
+            });
+        }
+
+        // A throttled source is barely busy, so it is only let go once the pipeline is well under
+        // its maximum, to avoid flapping.
+        let throttled = if self.throttled {
+            utilization > self.max_utilization / 2.0
+        } else {
+            utilization > self.max_utilization
+        };
+        if throttled && !self.throttled {
+            emit!(SelfTelemetryThrottled {
+                source_type: self.source_type,
+                utilization,
+                max_utilization: self.max_utilization,
+            });
+        }
+
+        self.throttled = throttled;
+        self.window_start = now;
+        self.busy = Duration::ZERO;
+        self.feedback_dropped = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guard(max_utilization: f64) -> SelfTelemetryGuard {
+        SelfTelemetryGuard::new(
+            &SelfTelemetryGuardConfig {
+                max_utilization,
+                ..Default::default()
+            },
+            "internal_logs",
+            SelfTelemetryComponents::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn finds_components_fed_only_by_self_telemetry() {
+        let nodes = vec![
+            ("parse", vec!["logs"]),
+            ("merge", vec!["parse", "metrics"]),
+            ("console", vec!["parse", "in"]),
+            ("blackhole", vec!["in"]),
+            ("metrics_sink", vec!["merge"]),
+        ];
+        let mut components = fed_only_by(["logs", "metrics"].into_iter(), &nodes)
+            .into_iter()
+            .collect::<Vec<_>>();
+        components.sort();
+        assert_eq!(
+            components,
+            vec!["logs", "merge", "metrics", "metrics_sink", "parse"]
+        );
+    }
+
+    #[test]
+    fn throttles_over_the_utilization() {
+        let mut guard = guard(0.1);
+        let start = guard.window_start;
+
+        // 0.5s busy over 10s is a utilization of 0.05, plus the downstream utilization.
+        guard.record_busy(Duration::from_millis(500));
+        guard.end_window(start + Duration::from_secs(10), 0.02);
+        assert!(!guard.is_throttled());
+
+        guard.record_busy(Duration::from_millis(500));
+        guard.end_window(start + Duration::from_secs(20), 0.08);
+        assert!(guard.is_throttled());
+
+        // Back under the maximum, but not under half of it.
+        guard.end_window(start + Duration::from_secs(30), 0.08);
+        assert!(guard.is_throttled());
+
+        guard.end_window(start + Duration::from_secs(40), 0.04);
+        assert!(!guard.is_throttled());
+    }
+
+    #[test]
+    fn never_drops_guard_warnings() {
+        let mut guard = guard(0.1);
+        guard.throttled = true;
+
+        let log = LogEvent::from("Some log.");
+        assert!(guard.should_drop_log(&log));
+
+        let mut warning = LogEvent::from("Throttled.");
+        warning.insert("metadata.target", SELF_TELEMETRY_GUARD_TARGET);
+        assert!(!guard.should_drop_log(&warning));
+    }
+
+    #[test]
+    fn rejects_invalid_config() {
+        assert!(SelfTelemetryGuard::new(
+            &SelfTelemetryGuardConfig {
+                max_utilization: 0.0,
+                ..Default::default()
+            },
+            "internal_metrics",
+            SelfTelemetryComponents::default(),
+        )
+        .is_err());
+        assert!(SelfTelemetryGuard::new(
+            &SelfTelemetryGuardConfig {
+                window_secs: 0,
+                ..Default::default()
+            },
+            "internal_metrics",
+            SelfTelemetryComponents::default(),
+        )
+        .is_err());
+    }
+}
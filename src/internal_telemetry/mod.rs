@@ -1,5 +1,6 @@
 #[cfg(feature = "allocation-tracing")]
 pub mod allocations;
+pub mod guard;
 
 pub const fn is_allocation_tracking_enabled() -> bool {
     cfg!(feature = "allocation-tracing")
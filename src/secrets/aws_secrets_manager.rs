@@ -0,0 +1,212 @@
+use std::collections::{HashMap, HashSet};
+
+use aws_sdk_secretsmanager::{Client, Config};
+use futures::executor;
+use vector_config::{component::GenerateConfig, configurable_component};
+
+use crate::{
+    aws::{create_client, AwsAuthentication, ClientBuilder, RegionOrEndpoint},
+    config::{ProxyConfig, SecretBackend},
+    signal,
+    tls::TlsConfig,
+};
+
+pub(crate) struct SecretsManagerClientBuilder;
+
+impl ClientBuilder for SecretsManagerClientBuilder {
+    type Config = Config;
+    type Client = Client;
+    type DefaultMiddleware = aws_sdk_secretsmanager::middleware::DefaultMiddleware;
+
+    fn default_middleware() -> Self::DefaultMiddleware {
+        aws_sdk_secretsmanager::middleware::DefaultMiddleware::new()
+    }
+
+    fn build(client: aws_smithy_client::Client, config: &aws_types::SdkConfig) -> Self::Client {
+        Client::with_config(client, config.into())
+    }
+}
+
+/// Configuration for the `aws_secrets_manager` secrets backend.
+///
+/// The secret is expected to hold a JSON object of string values. Each placeholder key refers to
+/// a field of that object, so `SECRET[backend_name.my_secret_key]` resolves to the value of the
+/// `my_secret_key` field.
+#[configurable_component(secrets("aws_secrets_manager"))]
+#[derive(Clone, Debug)]
+pub struct AwsSecretsManagerBackend {
+    /// The ID of the secret to resolve, either its name or its ARN.
+    pub secret_id: String,
+
+    #[serde(flatten)]
+    #[configurable(derived)]
+    pub region: RegionOrEndpoint,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub auth: AwsAuthentication,
+
+    #[configurable(derived)]
+    pub tls: Option<TlsConfig>,
+}
+
+impl GenerateConfig for AwsSecretsManagerBackend {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(AwsSecretsManagerBackend {
+            secret_id: String::from("secret-id"),
+            region: Default::default(),
+            auth: Default::default(),
+            tls: None,
+        })
+        .unwrap()
+    }
+}
+
+impl SecretBackend for AwsSecretsManagerBackend {
+    fn retrieve(
+        &mut self,
+        secret_keys: Vec<String>,
+        signal_rx: &mut signal::SignalRx,
+    ) -> crate::Result<HashMap<String, String>> {
+        let secret_string = executor::block_on(async {
+            tokio::select! {
+                biased;
+                Ok(signal::SignalTo::Shutdown | signal::SignalTo::Quit) = signal_rx.recv() => {
+                    Err("Secret retrieval was interrupted.".into())
+                }
+                secret_string = self.get_secret_string() => secret_string,
+            }
+        })?;
+
+        let mut output =
+            serde_json::from_str::<HashMap<String, String>>(&secret_string).map_err(|error| {
+                format!(
+                    "secret '{}' is not a JSON object of strings: {}",
+                    self.secret_id, error
+                )
+            })?;
+
+        let mut secrets = HashMap::new();
+        for k in secret_keys.into_iter().collect::<HashSet<_>>() {
+            match output.remove(&k) {
+                Some(v) if !v.is_empty() => {
+                    secrets.insert(k, v);
+                }
+                Some(_) => return Err(format!("secret for key '{}' was empty", k).into()),
+                None => return Err(format!("secret for key '{}' was not retrieved", k).into()),
+            }
+        }
+        Ok(secrets)
+    }
+}
+
+impl AwsSecretsManagerBackend {
+    async fn get_secret_string(&self) -> crate::Result<String> {
+        let client = create_client::<SecretsManagerClientBuilder>(
+            &self.auth,
+            self.region.region(),
+            self.region.endpoint()?,
+            &ProxyConfig::from_env(),
+            &self.tls,
+            false,
+        )
+        .await?;
+
+        client
+            .get_secret_value()
+            .secret_id(&self.secret_id)
+            .send()
+            .await?
+            .secret_string()
+            .map(ToOwned::to_owned)
+            .ok_or_else(|| format!("secret '{}' has no string value", self.secret_id).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{matchers, Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<AwsSecretsManagerBackend>();
+    }
+
+    async fn backend_with_secret(secret_string: &str) -> (MockServer, AwsSecretsManagerBackend) {
+        let server = MockServer::start().await;
+        Mock::given(matchers::method("POST"))
+            .and(matchers::header(
+                "x-amz-target",
+                "secretsmanager.GetSecretValue",
+            ))
+            .and(matchers::body_partial_json(
+                serde_json::json!({ "SecretId": "vector" }),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "ARN": "arn:aws:secretsmanager:us-east-1:123456789012:secret:vector-a1b2c3",
+                "Name": "vector",
+                "SecretString": secret_string,
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let backend = AwsSecretsManagerBackend {
+            secret_id: "vector".to_string(),
+            region: RegionOrEndpoint::with_both("us-east-1", server.uri()),
+            auth: AwsAuthentication::test_auth(),
+            tls: None,
+        };
+        (server, backend)
+    }
+
+    async fn retrieve(
+        mut backend: AwsSecretsManagerBackend,
+        secret_keys: &[&str],
+    ) -> crate::Result<HashMap<String, String>> {
+        let secret_keys = secret_keys.iter().map(ToString::to_string).collect();
+        // `retrieve` blocks on the request, so it can't run on the runtime's only worker thread.
+        tokio::task::spawn_blocking(move || {
+            let (_signal_handler, mut signal_rx) = signal::SignalHandler::new();
+            backend.retrieve(secret_keys, &mut signal_rx)
+        })
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn retrieves_secret_fields() {
+        let (_server, backend) =
+            backend_with_secret(r#"{"username": "vector", "password": "hunter2"}"#).await;
+
+        let secrets = retrieve(backend, &["password", "username"]).await.unwrap();
+        assert_eq!(secrets.len(), 2);
+        assert_eq!(secrets["username"], "vector");
+        assert_eq!(secrets["password"], "hunter2");
+    }
+
+    #[tokio::test]
+    async fn rejects_missing_and_empty_fields() {
+        let (_server, backend) = backend_with_secret(r#"{"password": ""}"#).await;
+        let error = retrieve(backend, &["password"]).await.unwrap_err();
+        assert_eq!(error.to_string(), "secret for key 'password' was empty");
+
+        let (_server, backend) = backend_with_secret(r#"{"password": "hunter2"}"#).await;
+        let error = retrieve(backend, &["username"]).await.unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "secret for key 'username' was not retrieved"
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_secrets_not_holding_json_objects() {
+        let (_server, backend) = backend_with_secret("hunter2").await;
+        let error = retrieve(backend, &["password"]).await.unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("secret 'vector' is not a JSON object of strings"));
+    }
+}
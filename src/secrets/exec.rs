@@ -92,6 +92,12 @@ impl SecretBackend for ExecBackend {
         }
         Ok(secrets)
     }
+
+    fn reusable(&self) -> bool {
+        // Running the command again on every reload is costly, and rotating its secrets is
+        // covered by `--refresh-secrets`.
+        true
+    }
 }
 
 async fn query_backend(
@@ -0,0 +1,368 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::{Component, Path, PathBuf},
+    time::Duration,
+};
+
+use futures::executor;
+use k8s_openapi::{api::core::v1::Secret, ByteString};
+use kube::{
+    api::ListParams,
+    config::{self, KubeConfigOptions},
+    Api, Client, Config as ClientConfig,
+};
+use tokio::{sync::broadcast::error::RecvError, time};
+use vector_config::{component::GenerateConfig, configurable_component};
+
+use crate::{config::SecretBackend, signal};
+
+const fn default_watch_interval_secs() -> u64 {
+    30
+}
+
+/// Configuration for the `kubernetes` secrets backend.
+///
+/// Secrets are read either from the Kubernetes API, so they don't need to be mounted into the
+/// Vector pod, or from a mounted secret volume. Each placeholder key refers to a key of the data of
+/// the secrets, so `SECRET[backend_name.my_secret_key]` resolves to the value of the
+/// `my_secret_key` key, and `SECRET[backend_name.my-secret/my_secret_key]` resolves to the value of
+/// the `my_secret_key` key of the `my-secret` secret.
+///
+/// Keys without a secret name are read from a single secret by `name`, which only requires the
+/// service account Vector runs as to be allowed to `get` that secret, or from the secrets
+/// discovered with a `label_selector`, which requires it to be allowed to `list` secrets in the
+/// namespace.
+#[configurable_component(secrets("kubernetes"))]
+#[derive(Clone, Debug, Default)]
+pub struct KubernetesSecretsBackend {
+    /// The namespace of the secrets.
+    ///
+    /// By default, the namespace of the service account Vector runs as is used.
+    pub namespace: Option<String>,
+
+    /// The name of the secret to read.
+    pub name: Option<String>,
+
+    /// The label selector used to discover the secrets to read.
+    ///
+    /// The data of all the secrets matching the selector is merged, and keys found in more than
+    /// one of them are an error.
+    pub label_selector: Option<String>,
+
+    /// Optional path to a readable [kubeconfig][kubeconfig] file.
+    ///
+    /// If not set, Vector will try to connect to Kubernetes using in-cluster configuration.
+    ///
+    /// [kubeconfig]: https://kubernetes.io/docs/concepts/configuration/organize-cluster-access-kubeconfig/
+    pub kube_config_file: Option<PathBuf>,
+
+    /// The path a secret volume is mounted at.
+    ///
+    /// When set, secrets are read from the files under this path instead of from the Kubernetes
+    /// API: the key `my_secret_key` is read from `<mount_path>/my_secret_key`, and the key
+    /// `my-secret/my_secret_key` from `<mount_path>/my-secret/my_secret_key`, so that several
+    /// secrets can be mounted in subdirectories of the same path.
+    pub mount_path: Option<PathBuf>,
+
+    /// Whether to reload the configuration when the retrieved secrets change.
+    ///
+    /// The secrets are read again every `watch_interval_secs` seconds, and the configuration is
+    /// reloaded, as when Vector receives `SIGHUP`, as soon as any of them has changed. Only
+    /// supported on Unix.
+    #[serde(default)]
+    pub watch: bool,
+
+    /// The interval, in seconds, between checks of the secrets for changes when `watch` is enabled.
+    #[serde(default = "default_watch_interval_secs")]
+    pub watch_interval_secs: u64,
+}
+
+impl GenerateConfig for KubernetesSecretsBackend {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(KubernetesSecretsBackend {
+            name: Some(String::from("vector-secrets")),
+            watch_interval_secs: default_watch_interval_secs(),
+            ..Default::default()
+        })
+        .unwrap()
+    }
+}
+
+impl SecretBackend for KubernetesSecretsBackend {
+    fn retrieve(
+        &mut self,
+        secret_keys: Vec<String>,
+        signal_rx: &mut signal::SignalRx,
+    ) -> crate::Result<HashMap<String, String>> {
+        if self.watch && (cfg!(not(unix)) || self.watch_interval_secs == 0) {
+            return Err(
+                "`watch` is only supported on Unix, with a `watch_interval_secs` of at least 1"
+                    .into(),
+            );
+        }
+
+        let secret_keys = secret_keys
+            .into_iter()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        let secrets = executor::block_on(async {
+            tokio::select! {
+                biased;
+                Ok(signal::SignalTo::Shutdown | signal::SignalTo::Quit) = signal_rx.recv() => {
+                    Err("Secret retrieval was interrupted.".into())
+                }
+                output = self.read_secrets(&secret_keys) => output,
+            }
+        })?;
+
+        if self.watch {
+            self.spawn_watch(secret_keys, secrets.clone(), signal_rx.resubscribe());
+        }
+        Ok(secrets)
+    }
+}
+
+impl KubernetesSecretsBackend {
+    async fn read_secrets(&self, secret_keys: &[String]) -> crate::Result<HashMap<String, String>> {
+        let mut output = match &self.mount_path {
+            Some(path) => read_mounted_secrets(path, secret_keys).await?,
+            None => self.read_api_secrets(secret_keys).await?,
+        };
+
+        let mut secrets = HashMap::new();
+        for k in secret_keys {
+            match output.remove(k) {
+                Some(v) if !v.is_empty() => {
+                    secrets.insert(k.clone(), v);
+                }
+                Some(_) => return Err(format!("secret for key '{}' was empty", k).into()),
+                None => return Err(format!("secret for key '{}' was not retrieved", k).into()),
+            }
+        }
+        Ok(secrets)
+    }
+
+    async fn read_api_secrets(
+        &self,
+        secret_keys: &[String],
+    ) -> crate::Result<HashMap<String, String>> {
+        let client_config = match &self.kube_config_file {
+            Some(path) => {
+                ClientConfig::from_custom_kubeconfig(
+                    config::Kubeconfig::read_from(path)?,
+                    &KubeConfigOptions::default(),
+                )
+                .await?
+            }
+            None => ClientConfig::infer().await?,
+        };
+        let namespace = self
+            .namespace
+            .clone()
+            .unwrap_or_else(|| client_config.default_namespace.clone());
+        let api = Api::<Secret>::namespaced(Client::try_from(client_config)?, &namespace);
+
+        let mut output = HashMap::new();
+        if secret_keys.iter().any(|key| !key.contains('/')) {
+            let secrets = match (&self.name, &self.label_selector) {
+                (Some(name), None) => vec![api.get(name).await?],
+                (None, Some(selector)) => {
+                    api.list(&ListParams::default().labels(selector))
+                        .await?
+                        .items
+                }
+                _ => {
+                    return Err(
+                        "exactly one of `name` or `label_selector` must be set to retrieve keys without a secret name"
+                            .into(),
+                    )
+                }
+            };
+            output = merge_secret_data(secrets)?;
+        }
+
+        // Data keys can't contain `/`, so keys naming their secret never collide with the others.
+        let mut named_secrets = HashMap::new();
+        for key in secret_keys {
+            if let Some((secret_name, data_key)) = key.split_once('/') {
+                if !named_secrets.contains_key(secret_name) {
+                    let data = merge_secret_data(vec![api.get(secret_name).await?])?;
+                    named_secrets.insert(secret_name, data);
+                }
+                if let Some(value) = named_secrets[secret_name].get(data_key) {
+                    output.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        Ok(output)
+    }
+
+    /// Checks the secrets for changes until the configuration they were retrieved for is replaced,
+    /// and reloads it if they do.
+    fn spawn_watch(
+        &self,
+        secret_keys: Vec<String>,
+        retrieved: HashMap<String, String>,
+        mut signal_rx: signal::SignalRx,
+    ) {
+        let backend = self.clone();
+        let interval = Duration::from_secs(self.watch_interval_secs);
+        tokio::spawn(async move {
+            let mut checks = time::interval_at(time::Instant::now() + interval, interval);
+            loop {
+                tokio::select! {
+                    signal = signal_rx.recv() => match signal {
+                        Err(RecvError::Lagged(_)) => continue,
+                        // Any other signal replaces or stops the configuration.
+                        _ => break,
+                    },
+                    _ = checks.tick() => match backend.read_secrets(&secret_keys).await {
+                        Ok(secrets) if secrets != retrieved => {
+                            info!("Kubernetes secrets changed.");
+                            #[cfg(unix)]
+                            crate::config::watcher::raise_sighup();
+                            break;
+                        }
+                        Ok(_) => {}
+                        Err(error) => {
+                            warn!(message = "Failed to check Kubernetes secrets for changes.", %error);
+                        }
+                    },
+                }
+            }
+        });
+    }
+}
+
+/// Reads the values of `secret_keys` from the files of a secret volume mounted at `path`.
+async fn read_mounted_secrets(
+    path: &Path,
+    secret_keys: &[String],
+) -> crate::Result<HashMap<String, String>> {
+    let mut output = HashMap::new();
+    for key in secret_keys {
+        let relative = Path::new(key);
+        if !relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(format!("key '{}' is not a valid path in the secret volume", key).into());
+        }
+
+        let file = path.join(relative);
+        let value = match tokio::fs::read(&file).await {
+            Ok(value) => value,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(error) => {
+                return Err(format!("could not read '{}': {}", file.display(), error).into())
+            }
+        };
+        let value =
+            String::from_utf8(value).map_err(|_| format!("key '{}' is not valid UTF-8", key))?;
+        output.insert(key.clone(), value);
+    }
+    Ok(output)
+}
+
+/// Merges the data of `secrets` into a single map of string values.
+fn merge_secret_data(secrets: Vec<Secret>) -> crate::Result<HashMap<String, String>> {
+    let mut output = HashMap::new();
+    for secret in secrets {
+        let secret_name = secret.metadata.name.unwrap_or_default();
+        for (key, ByteString(value)) in secret.data.unwrap_or_else(BTreeMap::new) {
+            let value = String::from_utf8(value).map_err(|_| {
+                format!(
+                    "key '{}' of secret '{}' is not valid UTF-8",
+                    key, secret_name
+                )
+            })?;
+            if output.insert(key.clone(), value).is_some() {
+                return Err(format!(
+                    "key '{}' is found in more than one secret, including '{}'",
+                    key, secret_name
+                )
+                .into());
+            }
+        }
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+
+    use super::*;
+
+    fn secret(name: &str, data: &[(&str, &[u8])]) -> Secret {
+        Secret {
+            metadata: ObjectMeta {
+                name: Some(name.to_owned()),
+                ..Default::default()
+            },
+            data: Some(
+                data.iter()
+                    .map(|(key, value)| (key.to_string(), ByteString(value.to_vec())))
+                    .collect(),
+            ),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<KubernetesSecretsBackend>();
+    }
+
+    #[test]
+    fn merges_secret_data() {
+        let output = merge_secret_data(vec![
+            secret("first", &[("username", b"vector")]),
+            secret("second", &[("password", b"hunter2")]),
+        ])
+        .unwrap();
+
+        assert_eq!(output["username"], "vector");
+        assert_eq!(output["password"], "hunter2");
+    }
+
+    #[test]
+    fn rejects_conflicting_and_binary_data() {
+        assert!(merge_secret_data(vec![
+            secret("first", &[("password", b"one")]),
+            secret("second", &[("password", b"two")]),
+        ])
+        .is_err());
+
+        assert!(merge_secret_data(vec![secret("binary", &[("key", &[0xff, 0xfe])])]).is_err());
+    }
+
+    #[tokio::test]
+    async fn reads_mounted_secrets() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("username"), "vector").unwrap();
+        std::fs::create_dir(dir.path().join("my-secret")).unwrap();
+        std::fs::write(dir.path().join("my-secret").join("password"), "hunter2").unwrap();
+
+        let backend = KubernetesSecretsBackend {
+            mount_path: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        };
+        let output = backend
+            .read_secrets(&["username".to_string(), "my-secret/password".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(output["username"], "vector");
+        assert_eq!(output["my-secret/password"], "hunter2");
+
+        assert!(backend
+            .read_secrets(&["missing".to_string()])
+            .await
+            .is_err());
+        assert!(backend
+            .read_secrets(&["../username".to_string()])
+            .await
+            .is_err());
+    }
+}
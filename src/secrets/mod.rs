@@ -5,8 +5,13 @@ use vector_config::{configurable_component, NamedComponent};
 
 use crate::{config::SecretBackend, signal};
 
+#[cfg(feature = "secrets-aws-secrets-manager")]
+mod aws_secrets_manager;
 mod exec;
+#[cfg(feature = "secrets-kubernetes")]
+mod kubernetes;
 mod test;
+mod vault;
 
 /// Configurable secret backends in Vector.
 #[configurable_component]
@@ -14,12 +19,24 @@ mod test;
 #[enum_dispatch(SecretBackend)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum SecretBackends {
+    /// AWS Secrets Manager.
+    #[cfg(feature = "secrets-aws-secrets-manager")]
+    AwsSecretsManager(#[configurable(derived)] aws_secrets_manager::AwsSecretsManagerBackend),
+
     /// Exec.
     Exec(#[configurable(derived)] exec::ExecBackend),
 
+    /// Kubernetes Secrets.
+    #[cfg(feature = "secrets-kubernetes")]
+    #[serde(alias = "kubernetes_secret")]
+    Kubernetes(#[configurable(derived)] kubernetes::KubernetesSecretsBackend),
+
     /// Test.
     #[configurable(metadata(docs::hidden))]
     Test(#[configurable(derived)] test::TestBackend),
+
+    /// HashiCorp Vault.
+    Vault(#[configurable(derived)] vault::VaultBackend),
 }
 
 // We can't use `enum_dispatch` here because it doesn't support associated constants.
@@ -28,8 +45,13 @@ impl NamedComponent for SecretBackends {
 
     fn get_component_name(&self) -> &'static str {
         match self {
+            #[cfg(feature = "secrets-aws-secrets-manager")]
+            Self::AwsSecretsManager(config) => config.get_component_name(),
             Self::Exec(config) => config.get_component_name(),
+            #[cfg(feature = "secrets-kubernetes")]
+            Self::Kubernetes(config) => config.get_component_name(),
             Self::Test(config) => config.get_component_name(),
+            Self::Vault(config) => config.get_component_name(),
         }
     }
 }
@@ -25,4 +25,9 @@ impl SecretBackend for TestBackend {
             .map(|k| (k, self.replacement.clone()))
             .collect())
     }
+
+    fn reusable(&self) -> bool {
+        // The replacement is fixed, so it only changes along with the backend configuration.
+        true
+    }
 }
@@ -0,0 +1,595 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    path::PathBuf,
+    sync::Mutex,
+    time::Duration,
+};
+
+use futures::executor;
+use http::{Method, Request, StatusCode};
+use hyper::Body;
+use once_cell::sync::Lazy;
+use serde::{de::DeserializeOwned, Deserialize};
+use vector_common::sensitive_string::SensitiveString;
+use vector_config::{component::GenerateConfig, configurable_component};
+
+use crate::{
+    config::{ProxyConfig, SecretBackend},
+    http::HttpClient,
+    signal,
+    tls::{TlsConfig, TlsSettings},
+};
+
+/// Tokens obtained from Vault, by the login they were obtained with.
+///
+/// These outlive the backend configurations, which are rebuilt on every reload, so that tokens
+/// keep being renewed in the background for as long as Vector runs.
+static TOKENS: Lazy<Mutex<HashMap<String, VaultToken>>> = Lazy::new(Default::default);
+
+fn default_kv_mount() -> String {
+    "secret".to_string()
+}
+
+fn default_approle_mount() -> String {
+    "approle".to_string()
+}
+
+fn default_kubernetes_mount() -> String {
+    "kubernetes".to_string()
+}
+
+fn default_kubernetes_token_path() -> PathBuf {
+    PathBuf::from("/var/run/secrets/kubernetes.io/serviceaccount/token")
+}
+
+/// Configuration for the `vault` secrets backend.
+///
+/// Secrets are read from a HashiCorp Vault KV version 2 secrets engine. The secret is expected to
+/// hold string values, and each placeholder key refers to one of them, so
+/// `SECRET[backend_name.my_secret_key]` resolves to the value of the `my_secret_key` key.
+///
+/// The Vault token used is renewed in the background, or obtained again through the configured
+/// authentication method once it can no longer be renewed, so that secrets can still be read
+/// when the configuration is reloaded long after Vector started.
+#[configurable_component(secrets("vault"))]
+#[derive(Clone, Debug)]
+pub struct VaultBackend {
+    /// The address of the Vault server, such as `https://vault.example.com:8200`.
+    pub address: String,
+
+    /// The Vault Enterprise namespace to use, if any.
+    pub namespace: Option<String>,
+
+    /// The path the KV version 2 secrets engine is mounted at.
+    #[serde(default = "default_kv_mount")]
+    pub mount: String,
+
+    /// The path of the secret to resolve, within the secrets engine.
+    pub path: String,
+
+    #[configurable(derived)]
+    pub auth: VaultAuth,
+
+    #[configurable(derived)]
+    pub tls: Option<TlsConfig>,
+}
+
+/// Method used to authenticate to Vault.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields, rename_all = "snake_case", tag = "strategy")]
+#[configurable(metadata(docs::enum_tag_description = "The authentication method to use."))]
+pub enum VaultAuth {
+    /// Authenticate with a token obtained out of band.
+    Token {
+        /// The Vault token.
+        token: SensitiveString,
+    },
+
+    /// Authenticate with the AppRole auth method.
+    #[serde(rename = "approle")]
+    AppRole {
+        /// The role ID of the AppRole.
+        role_id: String,
+
+        /// The secret ID of the AppRole.
+        secret_id: SensitiveString,
+
+        /// The path the AppRole auth method is mounted at.
+        #[serde(default = "default_approle_mount")]
+        mount: String,
+    },
+
+    /// Authenticate with the Kubernetes auth method, using the service account token of the pod.
+    Kubernetes {
+        /// The Vault role to log in as.
+        role: String,
+
+        /// The path of the service account token to log in with.
+        #[serde(default = "default_kubernetes_token_path")]
+        token_path: PathBuf,
+
+        /// The path the Kubernetes auth method is mounted at.
+        #[serde(default = "default_kubernetes_mount")]
+        mount: String,
+    },
+}
+
+impl GenerateConfig for VaultBackend {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(VaultBackend {
+            address: String::from("https://127.0.0.1:8200"),
+            namespace: None,
+            mount: default_kv_mount(),
+            path: String::from("path/to/secret"),
+            auth: VaultAuth::Token {
+                token: String::from("${VAULT_TOKEN}").into(),
+            },
+            tls: None,
+        })
+        .unwrap()
+    }
+}
+
+impl SecretBackend for VaultBackend {
+    fn retrieve(
+        &mut self,
+        secret_keys: Vec<String>,
+        signal_rx: &mut signal::SignalRx,
+    ) -> crate::Result<HashMap<String, String>> {
+        let mut output = executor::block_on(async {
+            tokio::select! {
+                biased;
+                Ok(signal::SignalTo::Shutdown | signal::SignalTo::Quit) = signal_rx.recv() => {
+                    Err("Secret retrieval was interrupted.".into())
+                }
+                output = self.read_secret() => output,
+            }
+        })?;
+
+        let mut secrets = HashMap::new();
+        for k in secret_keys.into_iter().collect::<HashSet<_>>() {
+            match output.remove(&k) {
+                Some(serde_json::Value::String(v)) if !v.is_empty() => {
+                    secrets.insert(k, v);
+                }
+                Some(serde_json::Value::String(_)) => {
+                    return Err(format!("secret for key '{}' was empty", k).into())
+                }
+                Some(_) => return Err(format!("secret for key '{}' is not a string", k).into()),
+                None => return Err(format!("secret for key '{}' was not retrieved", k).into()),
+            }
+        }
+        Ok(secrets)
+    }
+}
+
+/// Vault denied a request, such as because its token expired or was revoked.
+#[derive(Debug)]
+struct PermissionDenied {
+    uri: String,
+    body: String,
+}
+
+impl fmt::Display for PermissionDenied {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Vault request to '{}' was denied: {}",
+            self.uri, self.body
+        )
+    }
+}
+
+impl std::error::Error for PermissionDenied {}
+
+#[derive(Clone, Debug)]
+struct VaultToken {
+    token: String,
+    /// How long the token is valid for, from when it was obtained or last renewed. Zero for
+    /// tokens that never expire.
+    lease_duration: u64,
+    renewable: bool,
+}
+
+#[derive(Deserialize)]
+struct AuthResponse {
+    auth: AuthInfo,
+}
+
+#[derive(Deserialize)]
+struct AuthInfo {
+    client_token: String,
+    lease_duration: u64,
+    renewable: bool,
+}
+
+#[derive(Deserialize)]
+struct LookupResponse {
+    data: LookupData,
+}
+
+#[derive(Deserialize)]
+struct LookupData {
+    ttl: u64,
+    renewable: bool,
+}
+
+#[derive(Deserialize)]
+struct KvResponse {
+    data: KvData,
+}
+
+#[derive(Deserialize)]
+struct KvData {
+    data: HashMap<String, serde_json::Value>,
+}
+
+impl VaultBackend {
+    async fn read_secret(&self) -> crate::Result<HashMap<String, serde_json::Value>> {
+        let client = self.client()?;
+        let token = self.token(&client).await?;
+        let path = format!(
+            "{}/data/{}",
+            self.mount.trim_matches('/'),
+            self.path.trim_matches('/')
+        );
+
+        let response = match self
+            .request::<KvResponse>(&client, Method::GET, &path, Some(&token.token), None)
+            .await
+        {
+            // The cached token expired or was revoked since it was obtained, so log in again.
+            Err(error) if error.is::<PermissionDenied>() => {
+                debug!(message = "Vault denied the cached token, logging in again.", %error);
+                let token = self.login(&client).await?;
+                self.store_token(token.clone());
+                self.request::<KvResponse>(&client, Method::GET, &path, Some(&token.token), None)
+                    .await?
+            }
+            response => response?,
+        };
+        Ok(response.data.data)
+    }
+
+    fn client(&self) -> crate::Result<HttpClient> {
+        let tls_settings = TlsSettings::from_options(&self.tls)?;
+        Ok(HttpClient::new(tls_settings, &ProxyConfig::from_env())?)
+    }
+
+    /// Key identifying the login tokens are obtained with.
+    fn login_key(&self) -> String {
+        let login = match &self.auth {
+            VaultAuth::Token { token } => format!("token:{}", token.inner()),
+            VaultAuth::AppRole { role_id, mount, .. } => format!("approle:{}:{}", mount, role_id),
+            VaultAuth::Kubernetes { role, mount, .. } => format!("kubernetes:{}:{}", mount, role),
+        };
+        format!(
+            "{}|{}|{}",
+            self.address,
+            self.namespace.as_deref().unwrap_or_default(),
+            login
+        )
+    }
+
+    /// Returns the token to use, logging in if there is none yet.
+    async fn token(&self, client: &HttpClient) -> crate::Result<VaultToken> {
+        let key = self.login_key();
+        if let Some(token) = TOKENS.lock().unwrap().get(&key) {
+            return Ok(token.clone());
+        }
+
+        let token = self.login(client).await?;
+        self.store_token(token.clone());
+        Ok(token)
+    }
+
+    /// Caches `token`, renewing it in the background unless it already is.
+    fn store_token(&self, token: VaultToken) {
+        let key = self.login_key();
+        let lease_duration = token.lease_duration;
+        let renew = TOKENS.lock().unwrap().insert(key.clone(), token).is_none();
+
+        if renew && lease_duration > 0 {
+            match tokio::runtime::Handle::try_current() {
+                Ok(handle) => {
+                    handle.spawn(renew_in_background(self.clone(), key));
+                }
+                Err(_) => warn!(
+                    message = "Not renewing Vault token, as there is no runtime to do so in.",
+                    address = %self.address,
+                ),
+            }
+        }
+    }
+
+    async fn login(&self, client: &HttpClient) -> crate::Result<VaultToken> {
+        let (path, body) = match &self.auth {
+            VaultAuth::Token { token } => {
+                let response = self
+                    .request::<LookupResponse>(
+                        client,
+                        Method::GET,
+                        "auth/token/lookup-self",
+                        Some(token.inner()),
+                        None,
+                    )
+                    .await?;
+                return Ok(VaultToken {
+                    token: token.inner().to_string(),
+                    lease_duration: response.data.ttl,
+                    renewable: response.data.renewable,
+                });
+            }
+            VaultAuth::AppRole {
+                role_id,
+                secret_id,
+                mount,
+            } => (
+                format!("auth/{}/login", mount.trim_matches('/')),
+                serde_json::json!({ "role_id": role_id, "secret_id": secret_id.inner() }),
+            ),
+            VaultAuth::Kubernetes {
+                role,
+                token_path,
+                mount,
+            } => {
+                let jwt = tokio::fs::read_to_string(token_path)
+                    .await
+                    .map_err(|error| {
+                        format!(
+                            "failed reading Kubernetes service account token from {:?}: {}",
+                            token_path, error
+                        )
+                    })?;
+                (
+                    format!("auth/{}/login", mount.trim_matches('/')),
+                    serde_json::json!({ "role": role, "jwt": jwt.trim() }),
+                )
+            }
+        };
+
+        let response = self
+            .request::<AuthResponse>(client, Method::POST, &path, None, Some(body))
+            .await?;
+        Ok(response.auth.into())
+    }
+
+    async fn renew(&self, client: &HttpClient, token: &str) -> crate::Result<VaultToken> {
+        let response = self
+            .request::<AuthResponse>(
+                client,
+                Method::POST,
+                "auth/token/renew-self",
+                Some(token),
+                Some(serde_json::json!({})),
+            )
+            .await?;
+        Ok(response.auth.into())
+    }
+
+    async fn request<T: DeserializeOwned>(
+        &self,
+        client: &HttpClient,
+        method: Method,
+        path: &str,
+        token: Option<&str>,
+        body: Option<serde_json::Value>,
+    ) -> crate::Result<T> {
+        let uri = format!("{}/v1/{}", self.address.trim_end_matches('/'), path);
+        let mut request = Request::builder().method(method).uri(&uri);
+        if let Some(token) = token {
+            request = request.header("X-Vault-Token", token);
+        }
+        if let Some(namespace) = &self.namespace {
+            request = request.header("X-Vault-Namespace", namespace);
+        }
+        let request = match body {
+            Some(body) => request
+                .header("Content-Type", "application/json")
+                .body(Body::from(serde_json::to_vec(&body)?))?,
+            None => request.body(Body::empty())?,
+        };
+
+        let response = client.send(request).await?;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+
+        if status == StatusCode::FORBIDDEN {
+            return Err(PermissionDenied {
+                uri,
+                body: String::from_utf8_lossy(&body).into_owned(),
+            }
+            .into());
+        }
+        if status != StatusCode::OK {
+            return Err(format!(
+                "Vault request to '{}' failed with status {}: {}",
+                uri,
+                status,
+                String::from_utf8_lossy(&body)
+            )
+            .into());
+        }
+
+        serde_json::from_slice(&body)
+            .map_err(|error| format!("invalid response from Vault for '{}': {}", uri, error).into())
+    }
+}
+
+impl From<AuthInfo> for VaultToken {
+    fn from(auth: AuthInfo) -> Self {
+        Self {
+            token: auth.client_token,
+            lease_duration: auth.lease_duration,
+            renewable: auth.renewable,
+        }
+    }
+}
+
+/// Keeps the token stored under `key` valid, renewing it once two thirds of its lease have
+/// elapsed, or logging in again if it can't be renewed.
+///
+/// Stops once a new token can't be obtained either, in which case the next retrieval of secrets
+/// logs in again.
+async fn renew_in_background(backend: VaultBackend, key: String) {
+    let client = match backend.client() {
+        Ok(client) => client,
+        Err(error) => {
+            warn!(message = "Failed building client to renew Vault token.", %error);
+            TOKENS.lock().unwrap().remove(&key);
+            return;
+        }
+    };
+
+    loop {
+        let token = match TOKENS.lock().unwrap().get(&key).cloned() {
+            Some(token) if token.lease_duration > 0 => token,
+            _ => break,
+        };
+
+        let renew_after =
+            Duration::from_secs(token.lease_duration * 2 / 3).max(Duration::from_secs(1));
+        tokio::time::sleep(renew_after).await;
+
+        // The token was replaced while sleeping, after Vault denied it.
+        match TOKENS.lock().unwrap().get(&key) {
+            Some(current) if current.token == token.token => (),
+            Some(_) => continue,
+            None => break,
+        }
+
+        let renewed = if token.renewable {
+            backend.renew(&client, &token.token).await
+        } else {
+            Err("token is not renewable".into())
+        };
+        let renewed = match renewed {
+            Ok(renewed) => Ok(renewed),
+            Err(error) => {
+                debug!(message = "Failed renewing Vault token, logging in again.", %error);
+                backend.login(&client).await
+            }
+        };
+
+        match renewed {
+            Ok(renewed) => {
+                TOKENS.lock().unwrap().insert(key.clone(), renewed);
+            }
+            Err(error) => {
+                warn!(
+                    message = "Failed renewing Vault token.",
+                    address = %backend.address,
+                    %error,
+                    internal_log_rate_limit = true,
+                );
+                TOKENS.lock().unwrap().remove(&key);
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{matchers, Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<VaultBackend>();
+    }
+
+    #[test]
+    fn parses_auth_methods() {
+        let backend: VaultBackend = toml::from_str(
+            r#"
+            address = "https://vault:8200"
+            path = "vector"
+            auth.strategy = "approle"
+            auth.role_id = "role"
+            auth.secret_id = "secret"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(backend.mount, "secret");
+        assert!(matches!(
+            backend.auth,
+            VaultAuth::AppRole { ref mount, .. } if mount == "approle"
+        ));
+
+        let backend: VaultBackend = toml::from_str(
+            r#"
+            address = "https://vault:8200"
+            path = "vector"
+            auth.strategy = "kubernetes"
+            auth.role = "vector"
+            "#,
+        )
+        .unwrap();
+        assert!(matches!(
+            backend.auth,
+            VaultAuth::Kubernetes { ref token_path, .. } if token_path == &default_kubernetes_token_path()
+        ));
+    }
+
+    #[tokio::test]
+    async fn logs_in_again_when_token_is_denied() {
+        let server = MockServer::start().await;
+        Mock::given(matchers::method("POST"))
+            .and(matchers::path("/v1/auth/approle/login"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "auth": {
+                    "client_token": "fresh",
+                    "lease_duration": 0,
+                    "renewable": false,
+                }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(matchers::method("GET"))
+            .and(matchers::path("/v1/secret/data/vector"))
+            .and(matchers::header("X-Vault-Token", "revoked"))
+            .respond_with(ResponseTemplate::new(403))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(matchers::method("GET"))
+            .and(matchers::path("/v1/secret/data/vector"))
+            .and(matchers::header("X-Vault-Token", "fresh"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": { "data": { "password": "hunter2" } }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let backend = VaultBackend {
+            address: server.uri(),
+            namespace: None,
+            mount: default_kv_mount(),
+            path: "vector".to_string(),
+            auth: VaultAuth::AppRole {
+                role_id: "role".to_string(),
+                secret_id: "secret".to_string().into(),
+                mount: default_approle_mount(),
+            },
+            tls: None,
+        };
+        TOKENS.lock().unwrap().insert(
+            backend.login_key(),
+            VaultToken {
+                token: "revoked".to_string(),
+                lease_duration: 0,
+                renewable: false,
+            },
+        );
+
+        let secret = backend.read_secret().await.unwrap();
+        assert_eq!(secret["password"], "hunter2");
+        assert_eq!(TOKENS.lock().unwrap()[&backend.login_key()].token, "fresh");
+    }
+}
@@ -1,3 +1,4 @@
+use once_cell::sync::Lazy;
 use tokio::sync::broadcast;
 use tokio_stream::{Stream, StreamExt};
 
@@ -7,6 +8,24 @@ pub type ShutdownTx = broadcast::Sender<()>;
 pub type SignalTx = broadcast::Sender<SignalTo>;
 pub type SignalRx = broadcast::Receiver<SignalTo>;
 
+/// Requests for reloading the configuration from disk, such as on `SIGHUP`.
+///
+/// Components whose configuration is unchanged keep running across reloads, so those fetching
+/// state of their own subscribe to these to refresh it.
+static RELOAD_REQUESTS: Lazy<broadcast::Sender<()>> = Lazy::new(|| broadcast::channel(1).0);
+
+/// Subscribes to requests for reloading the configuration from disk.
+pub fn subscribe_to_reload_requests() -> broadcast::Receiver<()> {
+    RELOAD_REQUESTS.subscribe()
+}
+
+/// Notifies the subscribers of `subscribe_to_reload_requests` of a request for reloading the
+/// configuration from disk.
+pub(crate) fn notify_reload_requested() {
+    // An error just means there are no subscribers; safe to ignore.
+    let _ = RELOAD_REQUESTS.send(());
+}
+
 #[derive(Debug, Clone)]
 /// Control messages used by Vector to drive topology and shutdown events.
 #[allow(clippy::large_enum_variant)] // discovered during Rust upgrade to 1.57; just allowing for now since we did previously
@@ -8,7 +8,7 @@ use crate::{
 };
 use codecs::TextSerializerConfig;
 use futures::FutureExt;
-use std::sync::Arc;
+use std::{num::NonZeroUsize, sync::Arc};
 use vector_config::configurable_component;
 use vector_core::config::AcknowledgementsConfig;
 
@@ -29,6 +29,14 @@ pub struct AmqpSinkConfig {
     /// Connection options for the `amqp` sink.
     pub(crate) connection: AmqpConfig,
 
+    #[configurable(derived)]
+    #[serde(default)]
+    pub(crate) channels: AmqpChannelPoolConfig,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub(crate) dead_letter: AmqpDeadLetterConfig,
+
     #[configurable(derived)]
     pub(crate) encoding: EncodingConfig,
 
@@ -41,6 +49,78 @@ pub struct AmqpSinkConfig {
     pub(crate) acknowledgements: AcknowledgementsConfig,
 }
 
+/// Configuration for the pool of channels messages are published on.
+///
+/// Each message is assigned a channel by hashing its exchange and routing key, so that messages
+/// with the same exchange and routing key are always published on the same channel, in order.
+/// Confirmations are awaited independently on each channel.
+#[configurable_component]
+#[derive(Clone, Copy, Debug)]
+#[serde(deny_unknown_fields, default)]
+pub struct AmqpChannelPoolConfig {
+    /// The number of channels opened on the connection to publish messages.
+    pub size: NonZeroUsize,
+
+    /// The maximum number of messages awaiting confirmation on each channel.
+    ///
+    /// Once a channel has this many messages awaiting confirmation, the messages assigned to it
+    /// wait until some are confirmed. By default, there is no limit.
+    pub max_in_flight: Option<NonZeroUsize>,
+}
+
+/// Configuration for the dead-lettering annotations of the messages published.
+///
+/// These let the time messages are retained for, and where they are meant to go once expired or
+/// rejected, be set from the producer side.
+///
+/// RabbitMQ only dead-letters messages according to the queues they are routed to: those queues
+/// must have a dead-letter exchange set, by their `x-dead-letter-exchange` argument or by a
+/// policy, for expired or rejected messages to be dead-lettered at all. The dead-letter headers of
+/// the messages are not acted upon by the broker, and are meant for consumers and tooling.
+#[configurable_component]
+#[derive(Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AmqpDeadLetterConfig {
+    /// Template used to generate the time-to-live of each message, in milliseconds.
+    ///
+    /// It's set as the `expiration` property of the message. Events for which it doesn't render to
+    /// a whole number are dropped.
+    #[configurable(metadata(docs::examples = "60000"))]
+    pub message_ttl_ms: Option<Template>,
+
+    /// Template used to generate the exchange messages are dead-lettered to.
+    ///
+    /// It's set as the `x-dead-letter-exchange` header of the message, which the broker doesn't
+    /// act upon.
+    #[configurable(metadata(docs::examples = "{{ service }}-dlx"))]
+    pub exchange: Option<Template>,
+
+    /// Template used to generate the routing key of the messages once dead-lettered.
+    ///
+    /// It's set as the `x-dead-letter-routing-key` header of the message, which the broker
+    /// doesn't act upon.
+    pub routing_key: Option<Template>,
+
+    /// Whether to check that the dead-letter exchange of each message exists, by declaring it
+    /// passively.
+    ///
+    /// Messages published with a dead-letter exchange which doesn't exist are counted by the
+    /// `amqp_dead_letter_exchange_missing_total` internal metric. Each exchange is checked at most
+    /// once a minute, without holding up publishing: messages published before the first check of
+    /// an exchange completes aren't counted.
+    #[serde(default)]
+    pub check_exchange: bool,
+}
+
+impl Default for AmqpChannelPoolConfig {
+    fn default() -> Self {
+        Self {
+            size: NonZeroUsize::new(1).unwrap(),
+            max_in_flight: None,
+        }
+    }
+}
+
 impl Default for AmqpSinkConfig {
     fn default() -> Self {
         Self {
@@ -48,6 +128,8 @@ impl Default for AmqpSinkConfig {
             routing_key: None,
             encoding: TextSerializerConfig::new().into(),
             connection: AmqpConfig::default(),
+            channels: AmqpChannelPoolConfig::default(),
+            dead_letter: AmqpDeadLetterConfig::default(),
             acknowledgements: AcknowledgementsConfig::default(),
         }
     }
@@ -69,7 +151,7 @@ impl GenerateConfig for AmqpSinkConfig {
 impl SinkConfig for AmqpSinkConfig {
     async fn build(&self, _cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
         let sink = AmqpSink::new(self.clone()).await?;
-        let hc = healthcheck(Arc::clone(&sink.channel)).boxed();
+        let hc = healthcheck(Arc::new(sink.channels[0].channel.clone())).boxed();
         Ok((VectorSink::from_event_streamsink(sink), hc))
     }
 
@@ -1,17 +1,29 @@
 use super::*;
 use crate::{
     config::{SinkConfig, SinkContext},
+    event::{BatchNotifier, BatchStatus},
     serde::{default_decoding, default_framing_message_based},
     shutdown::ShutdownSignal,
+    sinks::{
+        amqp::{
+            config::{AmqpChannelPoolConfig, AmqpDeadLetterConfig},
+            service::AmqpFault,
+            sink::AmqpSink,
+        },
+        VectorSink,
+    },
     template::Template,
     test_util::{
-        components::{run_and_assert_sink_compliance, SINK_TAGS},
+        components::{
+            run_and_assert_sink_compliance, run_and_assert_sink_error, COMPONENT_ERROR_TAGS,
+            SINK_TAGS,
+        },
         random_lines_with_stream, random_string,
     },
     SourceSender,
 };
 use futures::StreamExt;
-use std::{sync::Arc, time::Duration};
+use std::{num::NonZeroUsize, sync::Arc, time::Duration};
 use vector_core::config::LogNamespace;
 
 pub fn make_config() -> AmqpSinkConfig {
@@ -42,7 +54,26 @@ async fn healthcheck() {
 async fn amqp_happy_path_plaintext() {
     crate::test_util::trace_init();
 
-    amqp_happy_path().await;
+    amqp_happy_path(make_config()).await;
+}
+
+#[tokio::test]
+async fn amqp_happy_path_channel_pool() {
+    crate::test_util::trace_init();
+
+    // Messages with the same exchange and routing key must be published in order, even though
+    // their dead-letter exchange is checked, and confirmations are awaited concurrently.
+    let mut config = make_config();
+    config.channels = AmqpChannelPoolConfig {
+        size: NonZeroUsize::new(4).unwrap(),
+        max_in_flight: NonZeroUsize::new(2),
+    };
+    config.dead_letter = AmqpDeadLetterConfig {
+        exchange: Some(Template::try_from(format!("test-{}-dlx", random_string(10))).unwrap()),
+        check_exchange: true,
+        ..Default::default()
+    };
+    amqp_happy_path(config).await;
 }
 
 #[tokio::test]
@@ -52,8 +83,7 @@ async fn amqp_round_trip_plaintext() {
     amqp_round_trip().await;
 }
 
-async fn amqp_happy_path() {
-    let mut config = make_config();
+async fn amqp_happy_path(mut config: AmqpSinkConfig) {
     let exchange = format!("test-{}-exchange", random_string(10));
     config.exchange = Template::try_from(exchange.as_str()).unwrap();
     let queue = format!("test-{}-queue", random_string(10));
@@ -216,3 +246,80 @@ async fn amqp_round_trip() {
 
     assert_eq!(output.len(), nb_events_published);
 }
+
+async fn declare_exchange(config: &AmqpSinkConfig) -> String {
+    let exchange = format!("test-{}-exchange", random_string(10));
+
+    let (_conn, channel) = config.connection.connect().await.unwrap();
+    let exchange_opts = lapin::options::ExchangeDeclareOptions {
+        auto_delete: true,
+        ..Default::default()
+    };
+    channel
+        .exchange_declare(
+            &exchange,
+            lapin::ExchangeKind::Fanout,
+            exchange_opts,
+            lapin::types::FieldTable::default(),
+        )
+        .await
+        .unwrap();
+
+    exchange
+}
+
+/// Publishes a batch of events through a sink that injects `fault` into the first `faulty`
+/// requests, and returns the resulting status of the batch.
+async fn publish_with_faults(fault: AmqpFault, faulty: usize, num_events: usize) -> BatchStatus {
+    let mut config = make_config();
+    let exchange = declare_exchange(&config).await;
+    config.exchange = Template::try_from(exchange.as_str()).unwrap();
+
+    let sink = AmqpSink::new(config).await.unwrap();
+    sink.faults.inject(fault, faulty);
+    let sink = VectorSink::from_event_streamsink(sink);
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    let (_, events) = random_lines_with_stream(100, num_events, Some(batch));
+    if faulty > 0 {
+        run_and_assert_sink_error(sink, events, &COMPONENT_ERROR_TAGS).await;
+    } else {
+        run_and_assert_sink_compliance(sink, events, &SINK_TAGS).await;
+    }
+
+    receiver.try_recv().expect("batch was not finalized")
+}
+
+#[tokio::test]
+async fn amqp_acknowledges_delivered_events() {
+    crate::test_util::trace_init();
+
+    let status = publish_with_faults(AmqpFault::Nack, 0, 10).await;
+    assert_eq!(status, BatchStatus::Delivered);
+}
+
+#[tokio::test]
+async fn amqp_nack_rejects_events() {
+    crate::test_util::trace_init();
+
+    // A nacked message isn't published again: its request fails, and the driver finalizes the
+    // events of failed requests as rejected rather than errored, as they won't be retried.
+    let status = publish_with_faults(AmqpFault::Nack, 1, 10).await;
+    assert_eq!(status, BatchStatus::Rejected);
+}
+
+#[tokio::test]
+async fn amqp_return_rejects_events() {
+    crate::test_util::trace_init();
+
+    let status = publish_with_faults(AmqpFault::Return, 1, 10).await;
+    assert_eq!(status, BatchStatus::Rejected);
+}
+
+#[tokio::test]
+async fn amqp_disconnect_rejects_events() {
+    crate::test_util::trace_init();
+
+    let status = publish_with_faults(AmqpFault::Disconnect, 10, 10).await;
+    assert_eq!(status, BatchStatus::Rejected);
+}
@@ -15,11 +15,16 @@ use vector_common::{
     request_metadata::RequestMetadata,
 };
 
-use super::{encoder::AmqpEncoder, service::AmqpRequest, sink::AmqpEvent};
+use super::{
+    encoder::AmqpEncoder,
+    service::{AmqpAnnotations, AmqpRequest},
+    sink::AmqpEvent,
+};
 
 pub(super) struct AmqpMetadata {
     exchange: String,
     routing_key: String,
+    annotations: AmqpAnnotations,
     finalizers: EventFinalizers,
 }
 
@@ -55,6 +60,7 @@ impl RequestBuilder<AmqpEvent> for AmqpRequestBuilder {
         let metadata = AmqpMetadata {
             exchange: input.exchange,
             routing_key: input.routing_key,
+            annotations: input.annotations,
             finalizers: input.event.take_finalizers(),
         };
 
@@ -72,6 +78,7 @@ impl RequestBuilder<AmqpEvent> for AmqpRequestBuilder {
             body,
             amqp_metadata.exchange,
             amqp_metadata.routing_key,
+            amqp_metadata.annotations,
             amqp_metadata.finalizers,
             metadata,
         )
@@ -1,14 +1,30 @@
 //! The main tower service that takes the request created by the request builder
 //! and sends it to `AMQP`.
-use crate::internal_events::sink::{AmqpAcknowledgementError, AmqpDeliveryError};
+use crate::internal_events::sink::{
+    AmqpAcknowledgementError, AmqpDeadLetterExchangeMissing, AmqpDeliveryError,
+    AmqpEventsFinalized, AmqpMessageReturnedError, AmqpNegativeAcknowledgementError,
+};
 use bytes::Bytes;
-use futures::future::BoxFuture;
-use lapin::{options::BasicPublishOptions, BasicProperties};
+use futures::{
+    future::{self, BoxFuture},
+    FutureExt,
+};
+use lapin::{
+    options::{BasicPublishOptions, ExchangeDeclareOptions},
+    protocol::AMQPSoftError,
+    publisher_confirm::{Confirmation, PublisherConfirm},
+    types::{AMQPValue, FieldTable, LongString, ShortString},
+    BasicProperties, ExchangeKind,
+};
 use snafu::Snafu;
 use std::{
-    sync::Arc,
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    sync::{Arc, Mutex},
     task::{Context, Poll},
+    time::{Duration, Instant},
 };
+use tokio::sync::{mpsc, oneshot, OwnedSemaphorePermit, Semaphore};
 use tower::Service;
 use vector_common::{
     finalization::{EventFinalizers, EventStatus, Finalizable},
@@ -17,12 +33,52 @@ use vector_common::{
 };
 use vector_core::stream::DriverResponse;
 
+/// How often the existence of each dead-letter exchange is checked, at most.
+const DEAD_LETTER_EXCHANGE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The dead-lettering annotations of a message, rendered from the event it's encoded from.
+#[derive(Clone, Debug, Default)]
+pub(super) struct AmqpAnnotations {
+    pub(super) expiration: Option<String>,
+    pub(super) dead_letter_exchange: Option<String>,
+    pub(super) dead_letter_routing_key: Option<String>,
+}
+
+impl AmqpAnnotations {
+    /// Returns the properties of the message, holding its annotations.
+    fn properties(&self) -> BasicProperties {
+        let mut properties = BasicProperties::default();
+        if let Some(expiration) = &self.expiration {
+            properties = properties.with_expiration(ShortString::from(expiration.clone()));
+        }
+
+        let mut headers = FieldTable::default();
+        for (name, value) in [
+            ("x-dead-letter-exchange", &self.dead_letter_exchange),
+            ("x-dead-letter-routing-key", &self.dead_letter_routing_key),
+        ] {
+            if let Some(value) = value {
+                headers.insert(
+                    ShortString::from(name),
+                    AMQPValue::LongString(LongString::from(value.clone())),
+                );
+            }
+        }
+        if !headers.inner().is_empty() {
+            properties = properties.with_headers(headers);
+        }
+
+        properties
+    }
+}
+
 /// The request contains the data to send to `AMQP` together
 /// with the information need to route the message.
 pub(super) struct AmqpRequest {
     body: Bytes,
     exchange: String,
     routing_key: String,
+    annotations: AmqpAnnotations,
     finalizers: EventFinalizers,
     metadata: RequestMetadata,
 }
@@ -32,6 +88,7 @@ impl AmqpRequest {
         body: Bytes,
         exchange: String,
         routing_key: String,
+        annotations: AmqpAnnotations,
         finalizers: EventFinalizers,
         metadata: RequestMetadata,
     ) -> Self {
@@ -39,6 +96,7 @@ impl AmqpRequest {
             body,
             exchange,
             routing_key,
+            annotations,
             finalizers,
             metadata,
         }
@@ -60,11 +118,12 @@ impl MetaDescriptive for AmqpRequest {
 /// A successful response from `AMQP`.
 pub(super) struct AmqpResponse {
     byte_size: usize,
+    event_status: EventStatus,
 }
 
 impl DriverResponse for AmqpResponse {
     fn event_status(&self) -> EventStatus {
-        EventStatus::Delivered
+        self.event_status
     }
 
     fn events_sent(&self) -> CountByteSize {
@@ -76,9 +135,253 @@ impl DriverResponse for AmqpResponse {
     }
 }
 
+/// How the broker handled a published message.
+enum PublishOutcome {
+    /// The message was confirmed by the broker.
+    Confirmed,
+
+    /// The broker negatively acknowledged the message.
+    Nacked,
+
+    /// The message could not be routed and was returned by the broker.
+    Returned,
+}
+
+/// A fault the service can be told to inject in place of the broker's response, so that
+/// acknowledgement handling can be exercised without having to break a real broker.
+#[cfg(test)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum AmqpFault {
+    /// The broker negatively acknowledges the message.
+    Nack,
+
+    /// The broker returns the message as unroutable.
+    Return,
+
+    /// The connection to the broker is lost before the message is published.
+    Disconnect,
+}
+
+#[cfg(test)]
+impl AmqpFault {
+    fn outcome(self) -> Result<PublishOutcome, AmqpError> {
+        match self {
+            Self::Nack => Ok(PublishOutcome::Nacked),
+            Self::Return => Ok(PublishOutcome::Returned),
+            Self::Disconnect => Err(AmqpError::AmqpDeliveryFailed {
+                error: lapin::Error::InvalidChannelState(lapin::ChannelState::Closed),
+            }),
+        }
+    }
+}
+
+/// A queue of faults to inject, shared between a test and the service under test.
+///
+/// Each request consumes at most one fault, requests made while the queue is empty are published
+/// to the broker as usual.
+#[cfg(test)]
+#[derive(Clone, Debug, Default)]
+pub(super) struct FaultInjector(Arc<std::sync::Mutex<std::collections::VecDeque<AmqpFault>>>);
+
+#[cfg(test)]
+impl FaultInjector {
+    /// Injects `fault` into the next `count` requests.
+    pub(super) fn inject(&self, fault: AmqpFault, count: usize) {
+        let mut faults = self.0.lock().expect("fault injector lock poisoned");
+        faults.extend(std::iter::repeat(fault).take(count));
+    }
+
+    fn next(&self) -> Option<AmqpFault> {
+        self.0
+            .lock()
+            .expect("fault injector lock poisoned")
+            .pop_front()
+    }
+}
+
+/// A channel of the pool, along with the permits for messages awaiting confirmation on it.
+#[derive(Clone)]
+pub(super) struct PooledChannel {
+    pub(super) channel: lapin::Channel,
+    pub(super) in_flight: Option<Arc<Semaphore>>,
+}
+
+/// Checks whether the dead-letter exchanges messages are annotated with exist.
+pub(super) struct DeadLetterExchanges {
+    connection: lapin::Connection,
+    checked: Mutex<HashMap<String, (Instant, Option<bool>)>>,
+}
+
+impl DeadLetterExchanges {
+    pub(super) fn new(connection: lapin::Connection) -> Self {
+        Self {
+            connection,
+            checked: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns whether `exchange` exists, as of its last check, or `None` if it hasn't been
+    /// checked successfully yet.
+    ///
+    /// This doesn't wait for the broker, so that messages are handed to their channel in the order
+    /// they were received. Instead, once the last check of `exchange` is older than
+    /// `DEAD_LETTER_EXCHANGE_CHECK_INTERVAL`, it's declared passively again in the background, and
+    /// the last known answer is returned in the meantime.
+    fn exists(self: &Arc<Self>, exchange: &str) -> Option<bool> {
+        let now = Instant::now();
+        let mut checked = self
+            .checked
+            .lock()
+            .expect("dead-letter exchanges lock poisoned");
+        let last = checked.get(exchange).copied();
+        if let Some((at, exists)) = last {
+            if now.duration_since(at) < DEAD_LETTER_EXCHANGE_CHECK_INTERVAL {
+                return exists;
+            }
+        }
+
+        // Marked as checked right away, so that the exchange isn't checked again by the messages
+        // published while this check is ongoing.
+        let exists = last.and_then(|(_, exists)| exists);
+        checked.insert(exchange.to_owned(), (now, exists));
+        drop(checked);
+
+        let this = Arc::clone(self);
+        let exchange = exchange.to_owned();
+        tokio::spawn(async move {
+            let exists = this.declare(&exchange).await;
+            this.checked
+                .lock()
+                .expect("dead-letter exchanges lock poisoned")
+                .insert(exchange, (Instant::now(), exists));
+        });
+
+        exists
+    }
+
+    /// Declares `exchange` passively, returning whether it exists, or `None` if that couldn't be
+    /// checked.
+    async fn declare(&self, exchange: &str) -> Option<bool> {
+        // A failed passive declare closes the channel it's made on, so each check has its own.
+        let channel = match self.connection.create_channel().await {
+            Ok(channel) => channel,
+            Err(error) => {
+                warn!(message = "Failed opening channel to check dead-letter exchange.", %error);
+                return None;
+            }
+        };
+        match channel
+            .exchange_declare(
+                exchange,
+                ExchangeKind::Direct,
+                ExchangeDeclareOptions {
+                    passive: true,
+                    ..Default::default()
+                },
+                FieldTable::default(),
+            )
+            .await
+        {
+            Ok(()) => {
+                let _ = channel.close(200, "OK").await;
+                Some(true)
+            }
+            Err(lapin::Error::ProtocolError(error))
+                if error.get_id() == AMQPSoftError::NOTFOUND.get_id() =>
+            {
+                Some(false)
+            }
+            Err(error) => {
+                warn!(message = "Failed checking dead-letter exchange.", %error);
+                None
+            }
+        }
+    }
+}
+
+/// Returns the index of the channel, among `channels`, messages published to `exchange` with
+/// `routing_key` are assigned to.
+fn partition(exchange: &str, routing_key: &str, channels: usize) -> usize {
+    let mut hasher = DefaultHasher::new();
+    (exchange, routing_key).hash(&mut hasher);
+    (hasher.finish() % channels as u64) as usize
+}
+
+/// The outcome of a published message, once confirmed by the broker.
+type PendingOutcome = BoxFuture<'static, Result<PublishOutcome, AmqpError>>;
+
+/// A message waiting to be published on a channel of the pool.
+struct QueuedMessage {
+    req: AmqpRequest,
+    #[cfg(test)]
+    fault: Option<AmqpFault>,
+    pending: oneshot::Sender<PendingOutcome>,
+}
+
+/// Publishes the messages queued for a channel one at a time, in the order they were queued, so
+/// that messages with the same exchange and routing key reach the broker in order.
+///
+/// Only the publishing itself is serialized: the confirmation of each message is awaited by the
+/// request it belongs to, holding one of the in-flight permits of the channel until then.
+async fn run_publisher(pooled: PooledChannel, mut queue: mpsc::UnboundedReceiver<QueuedMessage>) {
+    while let Some(message) = queue.recv().await {
+        let permit = match &pooled.in_flight {
+            Some(in_flight) => Some(
+                Arc::clone(in_flight)
+                    .acquire_owned()
+                    .await
+                    .expect("in-flight semaphore is never closed"),
+            ),
+            None => None,
+        };
+
+        #[cfg(test)]
+        if let Some(fault) = message.fault {
+            let _ = message.pending.send(future::ready(fault.outcome()).boxed());
+            continue;
+        }
+
+        let pending = match publish(&pooled.channel, message.req).await {
+            Ok(confirm) => confirmation(confirm, permit).boxed(),
+            Err(error) => future::ready(Err(error)).boxed(),
+        };
+        // The request may have been dropped, in which case nobody awaits the confirmation.
+        let _ = message.pending.send(pending);
+    }
+}
+
 /// The tower service that handles the actual sending of data to `AMQP`.
 pub(super) struct AmqpService {
-    pub(super) channel: Arc<lapin::Channel>,
+    publishers: Vec<mpsc::UnboundedSender<QueuedMessage>>,
+    dead_letter_exchanges: Option<Arc<DeadLetterExchanges>>,
+    #[cfg(test)]
+    faults: FaultInjector,
+}
+
+impl AmqpService {
+    /// Creates the service, spawning a publisher for each channel of the pool. Publishers stop
+    /// once the service is dropped.
+    pub(super) fn new(
+        channels: &[PooledChannel],
+        dead_letter_exchanges: Option<Arc<DeadLetterExchanges>>,
+        #[cfg(test)] faults: FaultInjector,
+    ) -> Self {
+        let publishers = channels
+            .iter()
+            .map(|pooled| {
+                let (queue, messages) = mpsc::unbounded_channel();
+                tokio::spawn(run_publisher(pooled.clone(), messages));
+                queue
+            })
+            .collect();
+
+        Self {
+            publishers,
+            dead_letter_exchanges,
+            #[cfg(test)]
+            faults,
+        }
+    }
 }
 
 #[derive(Debug, Snafu)]
@@ -88,6 +391,47 @@ pub(super) enum AmqpError {
 
     #[snafu(display("Failed AMQP request: {}", error))]
     AmqpDeliveryFailed { error: lapin::Error },
+
+    #[snafu(display("Received Negative Acknowledgement from AMQP server"))]
+    AmqpNegativeAcknowledgement,
+}
+
+/// Publishes the message of `req` on `channel`, which is in confirm mode, returning its pending
+/// confirmation.
+async fn publish(
+    channel: &lapin::Channel,
+    req: AmqpRequest,
+) -> Result<PublisherConfirm, AmqpError> {
+    // Mandatory messages which can't be routed to any queue are returned by the broker, rather
+    // than silently discarded.
+    channel
+        .basic_publish(
+            &req.exchange,
+            &req.routing_key,
+            BasicPublishOptions {
+                mandatory: true,
+                ..Default::default()
+            },
+            req.body.as_ref(),
+            req.annotations.properties(),
+        )
+        .await
+        .map_err(|error| AmqpError::AmqpDeliveryFailed { error })
+}
+
+/// Awaits the confirmation of a published message, releasing `_permit` once it's received.
+async fn confirmation(
+    confirm: PublisherConfirm,
+    _permit: Option<OwnedSemaphorePermit>,
+) -> Result<PublishOutcome, AmqpError> {
+    match confirm.await {
+        Ok(Confirmation::Ack(None) | Confirmation::NotRequested) => Ok(PublishOutcome::Confirmed),
+        Ok(Confirmation::Nack(None)) => Ok(PublishOutcome::Nacked),
+        Ok(Confirmation::Ack(Some(_)) | Confirmation::Nack(Some(_))) => {
+            Ok(PublishOutcome::Returned)
+        }
+        Err(error) => Err(AmqpError::AmqpAcknowledgementFailed { error }),
+    }
 }
 
 impl Service<AmqpRequest> for AmqpService {
@@ -102,44 +446,133 @@ impl Service<AmqpRequest> for AmqpService {
     }
 
     fn call(&mut self, req: AmqpRequest) -> Self::Future {
-        let channel = Arc::clone(&self.channel);
+        let byte_size = req.body.len();
+        let event_count = req.metadata.event_count();
+        let exchange = req.exchange.clone();
+
+        if let (Some(dead_letter_exchanges), Some(dead_letter_exchange)) = (
+            &self.dead_letter_exchanges,
+            &req.annotations.dead_letter_exchange,
+        ) {
+            if dead_letter_exchanges.exists(dead_letter_exchange) == Some(false) {
+                emit!(AmqpDeadLetterExchangeMissing {
+                    exchange: &exchange,
+                    dead_letter_exchange,
+                });
+            }
+        }
+
+        // Messages are queued right away, rather than from the returned future, so that they are
+        // published in the order requests are made.
+        let publisher =
+            &self.publishers[partition(&req.exchange, &req.routing_key, self.publishers.len())];
+        let (pending, pending_rx) = oneshot::channel();
+        // If the publisher is gone, the message is dropped along with `pending`, which the
+        // returned future reports as a delivery failure.
+        let _ = publisher.send(QueuedMessage {
+            req,
+            #[cfg(test)]
+            fault: self.faults.next(),
+            pending,
+        });
 
         Box::pin(async move {
-            channel
-                .confirm_select(lapin::options::ConfirmSelectOptions::default())
-                .await
-                .unwrap();
-
-            let byte_size = req.body.len();
-            let fut = channel
-                .basic_publish(
-                    &req.exchange,
-                    &req.routing_key,
-                    BasicPublishOptions::default(),
-                    req.body.as_ref(),
-                    BasicProperties::default(),
-                )
-                .await;
-
-            match fut {
-                Ok(result) => match result.await {
-                    Ok(lapin::publisher_confirm::Confirmation::Nack(_)) => {
-                        warn!("Received Negative Acknowledgement from AMQP server.");
-                        Ok(AmqpResponse { byte_size })
-                    }
-                    Err(error) => {
-                        // TODO: In due course the caller could emit these on error.
-                        emit!(AmqpAcknowledgementError { error: &error });
-                        Err(AmqpError::AmqpAcknowledgementFailed { error })
-                    }
-                    Ok(_) => Ok(AmqpResponse { byte_size }),
-                },
+            let outcome = match pending_rx.await {
+                Ok(pending) => pending.await,
+                Err(_) => Err(AmqpError::AmqpDeliveryFailed {
+                    error: lapin::Error::InvalidChannelState(lapin::ChannelState::Closed),
+                }),
+            };
+
+            let result = match outcome {
+                Ok(PublishOutcome::Confirmed) => Ok(AmqpResponse {
+                    byte_size,
+                    event_status: EventStatus::Delivered,
+                }),
+                Ok(PublishOutcome::Nacked) => {
+                    emit!(AmqpNegativeAcknowledgementError {
+                        exchange: &exchange,
+                        count: event_count,
+                    });
+                    Err(AmqpError::AmqpNegativeAcknowledgement)
+                }
+                Ok(PublishOutcome::Returned) => {
+                    emit!(AmqpMessageReturnedError {
+                        exchange: &exchange,
+                        count: event_count,
+                    });
+                    Ok(AmqpResponse {
+                        byte_size,
+                        event_status: EventStatus::Rejected,
+                    })
+                }
                 Err(error) => {
                     // TODO: In due course the caller could emit these on error.
-                    emit!(AmqpDeliveryError { error: &error });
-                    Err(AmqpError::AmqpDeliveryFailed { error })
+                    match &error {
+                        AmqpError::AmqpAcknowledgementFailed { error } => {
+                            emit!(AmqpAcknowledgementError { error })
+                        }
+                        AmqpError::AmqpDeliveryFailed { error } => {
+                            emit!(AmqpDeliveryError { error })
+                        }
+                        AmqpError::AmqpNegativeAcknowledgement => {}
+                    }
+                    Err(error)
                 }
-            }
+            };
+
+            // Events of failed requests are finalized as rejected by the driver.
+            emit!(AmqpEventsFinalized {
+                exchange: &exchange,
+                status: result
+                    .as_ref()
+                    .map_or(EventStatus::Rejected, |response| response.event_status),
+                count: event_count,
+            });
+
+            result
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partitions_by_exchange_and_routing_key() {
+        assert_eq!(partition("logs", "a", 1), 0);
+        assert_eq!(partition("logs", "a", 8), partition("logs", "a", 8));
+
+        let used = (0..100)
+            .map(|key| partition("logs", &key.to_string(), 4))
+            .collect::<std::collections::HashSet<_>>();
+        assert_eq!(used.len(), 4);
+    }
+
+    #[test]
+    fn annotations_set_properties() {
+        let properties = AmqpAnnotations::default().properties();
+        assert_eq!(properties.expiration(), &None);
+        assert_eq!(properties.headers(), &None);
+
+        let properties = AmqpAnnotations {
+            expiration: Some("60000".to_string()),
+            dead_letter_exchange: Some("logs-dlx".to_string()),
+            dead_letter_routing_key: None,
+        }
+        .properties();
+        assert_eq!(
+            properties.expiration(),
+            &Some(ShortString::from("60000".to_string()))
+        );
+        let headers = properties.headers().as_ref().unwrap().inner();
+        assert_eq!(headers.len(), 1);
+        assert_eq!(
+            headers.get(&ShortString::from("x-dead-letter-exchange")),
+            Some(&AMQPValue::LongString(LongString::from(
+                "logs-dlx".to_string()
+            )))
+        );
+    }
+}
@@ -1,8 +1,11 @@
 //! The sink for the `AMQP` sink that wires together the main stream that takes the
 //! event and sends it to `AMQP`.
 use crate::{
-    codecs::Transformer, event::Event, internal_events::TemplateRenderingError,
-    sinks::util::builder::SinkBuilderExt, template::Template,
+    codecs::Transformer,
+    event::Event,
+    internal_events::{sink::AmqpInvalidMessageTtl, TemplateRenderingError},
+    sinks::util::builder::SinkBuilderExt,
+    template::Template,
 };
 use async_trait::async_trait;
 use futures::StreamExt;
@@ -10,16 +13,22 @@ use futures_util::stream::BoxStream;
 use lapin::options::ConfirmSelectOptions;
 use serde::Serialize;
 use std::sync::Arc;
+use tokio::sync::Semaphore;
 use tower::ServiceBuilder;
 use vector_buffers::EventCount;
 use vector_core::{sink::StreamSink, ByteSizeOf, EstimatedJsonEncodedSizeOf};
 
+#[cfg(test)]
+use super::service::FaultInjector;
 use super::{
-    config::AmqpSinkConfig, encoder::AmqpEncoder, request_builder::AmqpRequestBuilder,
-    service::AmqpService, BuildError,
+    config::{AmqpDeadLetterConfig, AmqpSinkConfig},
+    encoder::AmqpEncoder,
+    request_builder::AmqpRequestBuilder,
+    service::{AmqpAnnotations, AmqpService, DeadLetterExchanges, PooledChannel},
+    BuildError,
 };
 
-/// Stores the event together with the rendered exchange and routing_key values.
+/// Stores the event together with the rendered exchange, routing_key and annotation values.
 /// This is passed into the `RequestBuilder` which then splits it out into the event
 /// and metadata containing the exchange and routing_key.
 /// This event needs to be created prior to building the request so we can filter out
@@ -29,6 +38,8 @@ pub(super) struct AmqpEvent {
     pub(super) event: Event,
     pub(super) exchange: String,
     pub(super) routing_key: String,
+    #[serde(skip)]
+    pub(super) annotations: AmqpAnnotations,
 }
 
 impl EventCount for AmqpEvent {
@@ -51,38 +62,70 @@ impl EstimatedJsonEncodedSizeOf for AmqpEvent {
 }
 
 pub(super) struct AmqpSink {
-    pub(super) channel: Arc<lapin::Channel>,
+    pub(super) channels: Arc<Vec<PooledChannel>>,
     exchange: Template,
     routing_key: Option<Template>,
+    dead_letter: AmqpDeadLetterConfig,
+    dead_letter_exchanges: Option<Arc<DeadLetterExchanges>>,
     transformer: Transformer,
     encoder: crate::codecs::Encoder<()>,
+    #[cfg(test)]
+    pub(super) faults: FaultInjector,
 }
 
 impl AmqpSink {
     pub(super) async fn new(config: AmqpSinkConfig) -> crate::Result<Self> {
-        let (_, channel) = config
+        let (connection, channel) = config
             .connection
             .connect()
             .await
             .map_err(|e| BuildError::AmqpCreateFailed { source: e })?;
 
-        channel
-            .confirm_select(ConfirmSelectOptions::default())
-            .await
-            .map_err(|e| BuildError::AmqpCreateFailed {
-                source: Box::new(e),
-            })?;
+        let mut channels = vec![channel];
+        for _ in 1..config.channels.size.get() {
+            channels.push(connection.create_channel().await.map_err(|e| {
+                BuildError::AmqpCreateFailed {
+                    source: Box::new(e),
+                }
+            })?);
+        }
+
+        let mut pool = Vec::with_capacity(channels.len());
+        for channel in channels {
+            channel
+                .confirm_select(ConfirmSelectOptions::default())
+                .await
+                .map_err(|e| BuildError::AmqpCreateFailed {
+                    source: Box::new(e),
+                })?;
+            pool.push(PooledChannel {
+                channel,
+                in_flight: config
+                    .channels
+                    .max_in_flight
+                    .map(|max_in_flight| Arc::new(Semaphore::new(max_in_flight.get()))),
+            });
+        }
+
+        let dead_letter_exchanges = config
+            .dead_letter
+            .check_exchange
+            .then(|| Arc::new(DeadLetterExchanges::new(connection)));
 
         let transformer = config.encoding.transformer();
         let serializer = config.encoding.build()?;
         let encoder = crate::codecs::Encoder::<()>::new(serializer);
 
         Ok(AmqpSink {
-            channel: Arc::new(channel),
+            channels: Arc::new(pool),
             exchange: config.exchange,
             routing_key: config.routing_key,
+            dead_letter: config.dead_letter,
+            dead_letter_exchanges,
             transformer,
             encoder,
+            #[cfg(test)]
+            faults: FaultInjector::default(),
         })
     }
 
@@ -101,24 +144,39 @@ impl AmqpSink {
             })
             .ok()?;
 
-        let routing_key = match &self.routing_key {
-            None => String::new(),
-            Some(key) => key
-                .render_string(&event)
-                .map_err(|missing_keys| {
-                    emit!(TemplateRenderingError {
-                        error: missing_keys,
-                        field: Some("routing_key"),
-                        drop_event: true,
-                    })
-                })
-                .ok()?,
+        let routing_key =
+            render_optional(self.routing_key.as_ref(), &event, "routing_key")?.unwrap_or_default();
+
+        let expiration = render_optional(
+            self.dead_letter.message_ttl_ms.as_ref(),
+            &event,
+            "dead_letter.message_ttl_ms",
+        )?;
+        if let Some(ttl) = &expiration {
+            if ttl.parse::<u64>().is_err() {
+                emit!(AmqpInvalidMessageTtl { ttl });
+                return None;
+            }
+        }
+        let annotations = AmqpAnnotations {
+            expiration,
+            dead_letter_exchange: render_optional(
+                self.dead_letter.exchange.as_ref(),
+                &event,
+                "dead_letter.exchange",
+            )?,
+            dead_letter_routing_key: render_optional(
+                self.dead_letter.routing_key.as_ref(),
+                &event,
+                "dead_letter.routing_key",
+            )?,
         };
 
         Some(AmqpEvent {
             event,
             exchange,
             routing_key,
+            annotations,
         })
     }
 
@@ -129,9 +187,12 @@ impl AmqpSink {
                 transformer: self.transformer.clone(),
             },
         };
-        let service = ServiceBuilder::new().service(AmqpService {
-            channel: Arc::clone(&self.channel),
-        });
+        let service = ServiceBuilder::new().service(AmqpService::new(
+            &self.channels,
+            self.dead_letter_exchanges.clone(),
+            #[cfg(test)]
+            self.faults.clone(),
+        ));
 
         let sink = input
             .filter_map(|event| std::future::ready(self.make_amqp_event(event)))
@@ -151,6 +212,27 @@ impl AmqpSink {
     }
 }
 
+/// Renders `template`, if set, for `event`. Returns `None` if it fails to render, in which case the
+/// event is dropped.
+fn render_optional(
+    template: Option<&Template>,
+    event: &Event,
+    field: &'static str,
+) -> Option<Option<String>> {
+    template
+        .map(|template| {
+            template.render_string(event).map_err(|missing_keys| {
+                emit!(TemplateRenderingError {
+                    error: missing_keys,
+                    field: Some(field),
+                    drop_event: true,
+                })
+            })
+        })
+        .transpose()
+        .ok()
+}
+
 #[async_trait]
 impl StreamSink<Event> for AmqpSink {
     async fn run(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
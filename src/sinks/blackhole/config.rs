@@ -28,6 +28,14 @@ pub struct BlackholeConfig {
     /// By default, there is no limit.
     pub rate: Option<usize>,
 
+    /// Event field holding the monotonic timestamp at which each log event was generated.
+    ///
+    /// When set, the end-to-end latency of each log event is recorded in the
+    /// `end_to_end_latency_seconds` histogram, and included in the summary of activity. The
+    /// timestamp is added by the `generation_timestamp_key` option of the `demo_logs` source, in
+    /// the same Vector instance. Events without it are not measured.
+    pub latency_key: Option<String>,
+
     #[configurable(derived)]
     #[serde(
         default,
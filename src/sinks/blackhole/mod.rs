@@ -21,6 +21,7 @@ mod tests {
         let config = BlackholeConfig {
             print_interval_secs: 10,
             rate: None,
+            latency_key: None,
             acknowledgements: Default::default(),
         };
         let sink = BlackholeSink::new(config);
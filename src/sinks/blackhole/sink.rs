@@ -1,6 +1,6 @@
 use std::{
     sync::{
-        atomic::{AtomicUsize, Ordering},
+        atomic::{AtomicU64, AtomicUsize, Ordering},
         Arc,
     },
     time::{Duration, Instant},
@@ -14,18 +14,53 @@ use tokio::{
     time::{interval, sleep_until},
 };
 use vector_common::internal_event::{
-    BytesSent, CountByteSize, EventsSent, InternalEventHandle as _, Output,
+    BytesSent, CountByteSize, EventsSent, InternalEventHandle as _, Output, Registered,
 };
 use vector_core::EstimatedJsonEncodedSizeOf;
 
 use crate::{
+    common::latency,
     event::{EventArray, EventContainer},
+    internal_events::BlackholeEndToEndLatency,
     sinks::{blackhole::config::BlackholeConfig, util::StreamSink},
 };
 
+/// The end-to-end latencies measured since the last summary of activity.
+#[derive(Default)]
+struct LatencyStats {
+    count: AtomicU64,
+    total_nanos: AtomicU64,
+    max_nanos: AtomicU64,
+}
+
+impl LatencyStats {
+    fn record(&self, latency: Duration) {
+        let nanos = latency.as_nanos() as u64;
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.total_nanos.fetch_add(nanos, Ordering::Relaxed);
+        self.max_nanos.fetch_max(nanos, Ordering::Relaxed);
+    }
+
+    /// Logs the latencies measured since the last call, if any, and starts over.
+    fn report(&self) {
+        let count = self.count.swap(0, Ordering::Relaxed);
+        let total_nanos = self.total_nanos.swap(0, Ordering::Relaxed);
+        let max_nanos = self.max_nanos.swap(0, Ordering::Relaxed);
+        if count > 0 {
+            info!(
+                events = count,
+                mean_latency_secs = Duration::from_nanos(total_nanos / count).as_secs_f64(),
+                max_latency_secs = Duration::from_nanos(max_nanos).as_secs_f64(),
+                "Measured end-to-end latency."
+            );
+        }
+    }
+}
+
 pub struct BlackholeSink {
     total_events: Arc<AtomicUsize>,
     total_raw_bytes: Arc<AtomicUsize>,
+    latency_stats: Arc<LatencyStats>,
     config: BlackholeConfig,
     last: Option<Instant>,
 }
@@ -36,9 +71,31 @@ impl BlackholeSink {
             config,
             total_events: Arc::new(AtomicUsize::new(0)),
             total_raw_bytes: Arc::new(AtomicUsize::new(0)),
+            latency_stats: Arc::default(),
             last: None,
         }
     }
+
+    /// Records the end-to-end latency of the log events carrying a generation timestamp.
+    fn measure_latency(
+        &self,
+        events: &EventArray,
+        key: &str,
+        latency_measured: &Registered<BlackholeEndToEndLatency>,
+    ) {
+        if let EventArray::Logs(logs) = events {
+            for log in logs {
+                if let Some(latency) = log
+                    .get(key)
+                    .and_then(|value| value.as_integer())
+                    .and_then(latency::elapsed_since)
+                {
+                    latency_measured.emit(latency);
+                    self.latency_stats.record(latency);
+                }
+            }
+        }
+    }
 }
 
 #[async_trait]
@@ -49,8 +106,10 @@ impl StreamSink<EventArray> for BlackholeSink {
         // the printing.
         let total_events = Arc::clone(&self.total_events);
         let total_raw_bytes = Arc::clone(&self.total_raw_bytes);
+        let latency_stats = Arc::clone(&self.latency_stats);
         let (shutdown, mut tripwire) = watch::channel(());
         let events_sent = register!(EventsSent::from(Output(None)));
+        let latency_measured = register!(BlackholeEndToEndLatency);
 
         if self.config.print_interval_secs > 0 {
             let interval_dur = Duration::from_secs(self.config.print_interval_secs);
@@ -64,6 +123,7 @@ impl StreamSink<EventArray> for BlackholeSink {
                                 raw_bytes_collected = total_raw_bytes.load(Ordering::Relaxed),
                                 "Collected events."
                             );
+                            latency_stats.report();
                         },
                         _ = tripwire.changed() => break,
                     }
@@ -74,6 +134,7 @@ impl StreamSink<EventArray> for BlackholeSink {
                     raw_bytes_collected = total_raw_bytes.load(Ordering::Relaxed),
                     "Collected events."
                 );
+                latency_stats.report();
             });
         }
 
@@ -86,6 +147,10 @@ impl StreamSink<EventArray> for BlackholeSink {
                 self.last = Some(until);
             }
 
+            if let Some(key) = &self.config.latency_key {
+                self.measure_latency(&events, key, &latency_measured);
+            }
+
             let message_len = events.estimated_json_encoded_size_of();
 
             let _ = self.total_events.fetch_add(events.len(), Ordering::AcqRel);
@@ -106,3 +171,31 @@ impl StreamSink<EventArray> for BlackholeSink {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::LogEvent;
+
+    #[test]
+    fn measures_latency_of_timestamped_logs() {
+        let sink = BlackholeSink::new(BlackholeConfig {
+            latency_key: Some("generated_at".to_string()),
+            ..Default::default()
+        });
+
+        let mut timestamped = LogEvent::from("timestamped");
+        timestamped.insert("generated_at", latency::monotonic_nanos());
+        let events = EventArray::from(vec![timestamped, LogEvent::from("not timestamped")]);
+        std::thread::sleep(Duration::from_millis(10));
+        sink.measure_latency(
+            &events,
+            "generated_at",
+            &register!(BlackholeEndToEndLatency),
+        );
+
+        let stats = &sink.latency_stats;
+        assert_eq!(stats.count.load(Ordering::Relaxed), 1);
+        assert!(stats.max_nanos.load(Ordering::Relaxed) >= 10_000_000);
+    }
+}
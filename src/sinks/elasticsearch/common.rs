@@ -117,11 +117,9 @@ impl ElasticsearchCommon {
         let request = config.request;
 
         let metric_config = config.metrics.clone().unwrap_or_default();
-        let metric_to_log = MetricToLog::new(
-            metric_config.host_tag,
-            metric_config.timezone.unwrap_or_default(),
-            LogNamespace::Legacy,
-        );
+        let metric_to_log = MetricToLog::new(metric_config.host_tag.clone(), LogNamespace::Legacy)
+            .with_flattened_tags(metric_config.flattened_tag_prefix())
+            .with_embedded_metadata(metric_config.embed_metadata);
 
         let region = config.aws.as_ref().and_then(|config| config.region());
 
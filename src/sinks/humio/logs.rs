@@ -1,18 +1,30 @@
+use std::{collections::BTreeMap, time::Duration};
+
 use codecs::JsonSerializerConfig;
+use lookup::LookupBuf;
 use vector_common::sensitive_string::SensitiveString;
 use vector_config::configurable_component;
 
-use super::host_key;
+use super::{
+    host_key,
+    normalization::{
+        tag_field, tag_path, HumioEventTypeFieldConfig, HumioNormalizationConfig, Normalizer,
+        NormalizingSink,
+    },
+};
 use crate::{
     codecs::EncodingConfig,
     config::{AcknowledgementsConfig, DataType, GenerateConfig, Input, SinkConfig, SinkContext},
+    schema,
+    serde::OneOrMany,
     sinks::{
         splunk_hec::{
             common::{
-                acknowledgements::HecClientAcknowledgementsConfig, timestamp_key, EndpointTarget,
-                SplunkHecDefaultBatchSettings,
+                acknowledgements::HecClientAcknowledgementsConfig,
+                failover::{EndpointPoolSettings, PoolEndpoint},
+                timestamp_key, EndpointTarget, SplunkHecDefaultBatchSettings,
             },
-            logs::config::HecLogsSinkConfig,
+            logs::config::{HecLogsSinkConfig, HecLogsSinkOptions},
         },
         util::{BatchConfig, Compression, TowerRequestConfig},
         Healthcheck, VectorSink,
@@ -23,6 +35,15 @@ use crate::{
 
 const HOST: &str = "https://cloud.humio.com";
 
+/// The maximum length of a Humio repository name, checked against a static `index`.
+const MAX_REPOSITORY_NAME_LEN: usize = 100;
+
+/// The maximum length of a Humio parser name, checked against a static `event_type`.
+const MAX_PARSER_NAME_LEN: usize = 255;
+
+/// The maximum length of the `@source` of a Humio event, checked against a static `source`.
+const MAX_SOURCE_LEN: usize = 4096;
+
 /// Configuration for the `humio_logs` sink.
 #[configurable_component(sink("humio_logs"))]
 #[derive(Clone, Debug)]
@@ -35,9 +56,38 @@ pub struct HumioLogsConfig {
     #[serde(alias = "host")]
     pub(super) endpoint: Option<String>,
 
+    /// A set of Humio endpoints to send events to, instead of a single `endpoint`.
+    ///
+    /// Events are spread over the healthy endpoints with the lowest `priority`, according to
+    /// their `weight`. When an endpoint fails a request, it is taken out of rotation and traffic
+    /// fails over to the remaining endpoints, such as a disaster recovery region.
+    ///
+    /// Cannot be used together with `endpoint`.
+    #[serde(default)]
+    pub(super) endpoints: Vec<HumioEndpointConfig>,
+
+    /// The amount of time, in seconds, that an endpoint which failed a request is skipped for
+    /// before being tried again.
+    ///
+    /// Only relevant when `endpoints` is set.
+    #[serde(default = "default_endpoint_retry_after_secs")]
+    pub(super) endpoint_retry_after_secs: u64,
+
+    /// The interval, in seconds, on which each of the `endpoints` is health checked.
+    ///
+    /// An endpoint failing its health check is taken out of rotation, as if it had failed a
+    /// request, before any events are sent to it, and is put back as soon as it passes one. By
+    /// default, endpoints are only taken out of rotation when they fail a request.
+    ///
+    /// Only relevant when `endpoints` is set.
+    pub(super) endpoint_health_check_interval_secs: Option<u64>,
+
     /// The source of events sent to this sink.
     ///
     /// Typically the filename the logs originated from. Maps to `@source` in Humio.
+    ///
+    /// When schema validation is enabled, the fields referenced by this template, `event_type`,
+    /// and `index` must be able to exist in the events received.
     pub(super) source: Option<Template>,
 
     #[configurable(derived)]
@@ -45,16 +95,23 @@ pub struct HumioLogsConfig {
 
     /// The type of events sent to this sink. Humio uses this as the name of the parser to use to ingest the data.
     ///
-    /// If unset, Humio will default it to none.
+    /// If unset, Humio will default it to none. A static value must be a valid parser name, of at
+    /// most 255 printable characters.
     pub(super) event_type: Option<Template>,
 
+    #[configurable(derived)]
+    pub(super) event_type_field: Option<HumioEventTypeFieldConfig>,
+
     /// Overrides the name of the log field used to grab the hostname to send to Humio.
     ///
+    /// A list of fields can be given instead, in which case the first one present in each event
+    /// is used.
+    ///
     /// By default, the [global `log_schema.host_key` option][global_host_key] is used.
     ///
     /// [global_host_key]: https://vector.dev/docs/reference/configuration/global-options/#log_schema.host_key
-    #[serde(default = "host_key")]
-    pub(super) host_key: String,
+    #[serde(default = "default_host_key")]
+    pub(super) host_key: OneOrMany<String>,
 
     /// Event fields to be added to Humio’s extra fields.
     ///
@@ -66,12 +123,35 @@ pub struct HumioLogsConfig {
     #[serde(default)]
     pub(super) indexed_fields: Vec<String>,
 
+    /// Humio tags rendered from each event.
+    ///
+    /// Each key is the name of a tag, which is sent as an indexed field prefixed with `#`, and
+    /// each value is a template rendered with the event. For example, `env = "{{ environment }}"`
+    /// sends the `environment` field of each event as the `#env` tag.
+    ///
+    /// Events for which a template can't be rendered are sent without the tag.
+    #[serde(default)]
+    pub(super) tags: BTreeMap<String, Template>,
+
+    /// The maximum number of distinct values of each tag in `tags`.
+    ///
+    /// Humio tags should have few distinct values. Once a tag has had this many distinct values,
+    /// events with a new value are sent without the tag.
+    #[serde(default = "default_max_tag_values")]
+    pub(super) max_tag_values: usize,
+
+    #[configurable(derived)]
+    pub(super) normalization: Option<HumioNormalizationConfig>,
+
     /// Optional name of the repository to ingest into.
     ///
     /// In public-facing APIs, this must (if present) be equal to the repository used to create the ingest token used for authentication.
     ///
     /// In private cluster setups, Humio can be configured to allow these to be different.
     ///
+    /// A static value must be a valid repository name, of at most 100 letters, digits, `-`, and
+    /// `_`.
+    ///
     /// For more information, see [Humio’s Format of Data][humio_data_format].
     ///
     /// [humio_data_format]: https://docs.humio.com/integrations/data-shippers/hec/#format-of-data
@@ -109,11 +189,60 @@ pub struct HumioLogsConfig {
 
     /// Overrides the name of the log field used to grab the timestamp to send to Humio.
     ///
+    /// A list of fields can be given instead, in which case the first one present in each event
+    /// is used.
+    ///
     /// By default, the [global `log_schema.timestamp_key` option][global_timestamp_key] is used.
     ///
     /// [global_timestamp_key]: https://vector.dev/docs/reference/configuration/global-options/#log_schema.timestamp_key
-    #[serde(default = "timestamp_key")]
-    pub(super) timestamp_key: String,
+    #[serde(default = "default_timestamp_key")]
+    pub(super) timestamp_key: OneOrMany<String>,
+}
+
+/// A Humio endpoint taking part in client-side failover.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct HumioEndpointConfig {
+    /// The base URL of the Humio instance.
+    pub endpoint: String,
+
+    /// The relative share of events sent to this endpoint, among endpoints of the same priority.
+    #[serde(default = "default_endpoint_weight")]
+    pub weight: u32,
+
+    /// The priority of this endpoint.
+    ///
+    /// Endpoints with a lower value are preferred. Endpoints with a higher value only receive
+    /// events while every endpoint with a lower value is unhealthy.
+    #[serde(default)]
+    pub priority: u8,
+}
+
+const fn default_endpoint_weight() -> u32 {
+    1
+}
+
+pub(super) const fn default_endpoint_retry_after_secs() -> u64 {
+    30
+}
+
+pub(super) const fn default_max_tag_values() -> usize {
+    100
+}
+
+fn default_host_key() -> OneOrMany<String> {
+    host_key().into()
+}
+
+fn default_timestamp_key() -> OneOrMany<String> {
+    timestamp_key().into()
+}
+
+/// Splits a list of keys into the first one and the ones to fall back to.
+fn split_keys(keys: &OneOrMany<String>) -> (String, Vec<String>) {
+    let mut keys = keys.clone().to_vec().into_iter();
+    (keys.next().unwrap_or_default(), keys.collect())
 }
 
 pub fn timestamp_nanos_key() -> Option<String> {
@@ -125,19 +254,26 @@ impl GenerateConfig for HumioLogsConfig {
         toml::Value::try_from(Self {
             token: "${HUMIO_TOKEN}".to_owned().into(),
             endpoint: None,
+            endpoints: vec![],
+            endpoint_retry_after_secs: default_endpoint_retry_after_secs(),
+            endpoint_health_check_interval_secs: None,
             source: None,
             encoding: JsonSerializerConfig::new().into(),
             event_type: None,
+            event_type_field: None,
             indexed_fields: vec![],
+            tags: BTreeMap::new(),
+            max_tag_values: default_max_tag_values(),
+            normalization: None,
             index: None,
-            host_key: host_key(),
+            host_key: default_host_key(),
             compression: Compression::default(),
             request: TowerRequestConfig::default(),
             batch: BatchConfig::default(),
             tls: None,
             timestamp_nanos_key: None,
             acknowledgements: Default::default(),
-            timestamp_key: timestamp_key(),
+            timestamp_key: default_timestamp_key(),
         })
         .unwrap()
     }
@@ -146,11 +282,33 @@ impl GenerateConfig for HumioLogsConfig {
 #[async_trait::async_trait]
 impl SinkConfig for HumioLogsConfig {
     async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
-        self.build_hec_config().build(cx).await
+        let (sink, healthcheck) = self.build_hec_config()?.build(cx)?;
+
+        let normalizer = match &self.normalization {
+            Some(normalization) => Some(Normalizer::new(
+                normalization,
+                self.timestamp_key.clone().to_vec(),
+            )),
+            None => {
+                (!self.tags.is_empty() || self.event_type_field.is_some()).then(Normalizer::default)
+            }
+        };
+        let sink = match normalizer {
+            Some(normalizer) => VectorSink::Stream(Box::new(NormalizingSink {
+                inner: sink,
+                normalizer: normalizer
+                    .with_templated_tags(&self.tags, self.max_tag_values)
+                    .with_event_type(self.event_type_field.clone()),
+            })),
+            None => sink,
+        };
+
+        Ok((sink, healthcheck))
     }
 
     fn input(&self) -> Input {
         Input::new(self.encoding.config().input_type() & DataType::Log)
+            .with_schema_requirement(self.schema_requirement())
     }
 
     fn acknowledgements(&self) -> &AcknowledgementsConfig {
@@ -158,32 +316,187 @@ impl SinkConfig for HumioLogsConfig {
     }
 }
 
+/// Checks a static template against Humio's limits on the value of the field it maps to, as
+/// events would otherwise fail to render or be rejected by Humio one by one.
+fn validate_static_value(
+    option: &str,
+    template: &Option<Template>,
+    max_len: usize,
+    is_allowed: impl Fn(char) -> bool,
+    allowed: &str,
+) -> crate::Result<()> {
+    let value = match template {
+        Some(template) if !template.is_dynamic() => template.get_ref(),
+        _ => return Ok(()),
+    };
+    if value.is_empty() || value.chars().count() > max_len {
+        return Err(format!(
+            "`{}` must be between 1 and {} characters long.",
+            option, max_len
+        )
+        .into());
+    }
+    if !value.chars().all(is_allowed) {
+        return Err(format!("`{}` can only contain {}.", option, allowed).into());
+    }
+    Ok(())
+}
+
 impl HumioLogsConfig {
-    fn build_hec_config(&self) -> HecLogsSinkConfig {
-        let endpoint = self.endpoint.clone().unwrap_or_else(|| HOST.to_string());
-
-        HecLogsSinkConfig {
-            default_token: self.token.clone(),
-            endpoint,
-            host_key: self.host_key.clone(),
-            indexed_fields: self.indexed_fields.clone(),
-            index: self.index.clone(),
-            sourcetype: self.event_type.clone(),
-            source: self.source.clone(),
-            timestamp_nanos_key: self.timestamp_nanos_key.clone(),
-            encoding: self.encoding.clone(),
-            compression: self.compression,
-            batch: self.batch,
-            request: self.request,
-            tls: self.tls.clone(),
-            acknowledgements: HecClientAcknowledgementsConfig {
-                indexer_acknowledgements_enabled: false,
-                ..Default::default()
-            },
-            timestamp_key: timestamp_key(),
-            endpoint_target: EndpointTarget::Event,
-            auto_extract_timestamp: None,
+    /// The fields referenced by the `source`, `event_type`, and `index` templates, which must be
+    /// able to exist in the events received.
+    fn schema_requirement(&self) -> schema::Requirement {
+        [&self.source, &self.event_type, &self.index]
+            .into_iter()
+            .flatten()
+            .filter_map(Template::get_fields)
+            .flatten()
+            .filter_map(|field| field.parse::<LookupBuf>().ok())
+            .fold(schema::Requirement::empty(), |requirement, field| {
+                requirement.required_field(field)
+            })
+    }
+
+    fn validate_templates(&self) -> crate::Result<()> {
+        validate_static_value(
+            "index",
+            &self.index,
+            MAX_REPOSITORY_NAME_LEN,
+            |c| c.is_ascii_alphanumeric() || c == '-' || c == '_',
+            "letters, digits, `-`, and `_`",
+        )?;
+        validate_static_value(
+            "event_type",
+            &self.event_type,
+            MAX_PARSER_NAME_LEN,
+            |c| !c.is_control(),
+            "printable characters",
+        )?;
+        validate_static_value(
+            "source",
+            &self.source,
+            MAX_SOURCE_LEN,
+            |c| !c.is_control(),
+            "printable characters",
+        )
+    }
+
+    fn build_hec_config(&self) -> crate::Result<HumioHecConfig> {
+        if self.endpoint.is_some() && !self.endpoints.is_empty() {
+            return Err("`endpoint` and `endpoints` cannot both be set.".into());
+        }
+        if self.endpoint_health_check_interval_secs == Some(0) {
+            return Err("`endpoint_health_check_interval_secs` must be at least 1.".into());
+        }
+        if let Some(endpoint) = self.endpoints.iter().find(|endpoint| endpoint.weight == 0) {
+            return Err(format!(
+                "The `weight` of endpoint {:?} must be at least 1.",
+                endpoint.endpoint
+            )
+            .into());
+        }
+        self.validate_templates()?;
+
+        // Requests, health checks and acknowledgement queries are routed through the endpoint
+        // pool, the preferred endpoint only stands in as the default one.
+        let endpoint = match self
+            .endpoints
+            .iter()
+            .min_by_key(|endpoint| endpoint.priority)
+        {
+            Some(preferred) => preferred.endpoint.clone(),
+            None => self.endpoint.clone().unwrap_or_else(|| HOST.to_string()),
+        };
+        let endpoint_pool = (!self.endpoints.is_empty()).then(|| EndpointPoolSettings {
+            endpoints: self
+                .endpoints
+                .iter()
+                .map(|endpoint| PoolEndpoint {
+                    endpoint: endpoint.endpoint.clone(),
+                    weight: endpoint.weight,
+                    priority: endpoint.priority,
+                })
+                .collect(),
+            retry_after: Duration::from_secs(self.endpoint_retry_after_secs),
+            health_check_interval: self
+                .endpoint_health_check_interval_secs
+                .map(Duration::from_secs),
+        });
+
+        let sourcetype = match &self.event_type_field {
+            Some(_) if self.event_type.is_some() => {
+                return Err("`event_type` and `event_type_field` cannot both be set.".into());
+            }
+            Some(event_type_field) => {
+                event_type_field.validate()?;
+                Some(event_type_field.template()?)
+            }
+            None => self.event_type.clone(),
+        };
+
+        let (host_key, host_key_fallbacks) = split_keys(&self.host_key);
+        let (timestamp_key, timestamp_key_fallbacks) = split_keys(&self.timestamp_key);
+
+        let mut indexed_fields = self.indexed_fields.clone();
+        if let Some(normalization) = &self.normalization {
+            indexed_fields.extend(normalization.tag_fields());
+        }
+        for name in self.tags.keys() {
+            let field = tag_path(name);
+            if tag_field(name).len() == 1 {
+                return Err("`tags` can't have an empty tag name.".into());
+            }
+            if indexed_fields.contains(&field) {
+                return Err(format!("The tag {:?} is set more than once.", tag_field(name)).into());
+            }
+            indexed_fields.push(field);
         }
+        if !self.tags.is_empty() && self.max_tag_values == 0 {
+            return Err("`max_tag_values` must be at least 1.".into());
+        }
+
+        Ok(HumioHecConfig {
+            hec: HecLogsSinkConfig {
+                default_token: self.token.clone(),
+                endpoint,
+                host_key,
+                indexed_fields,
+                index: self.index.clone(),
+                sourcetype,
+                source: self.source.clone(),
+                encoding: self.encoding.clone(),
+                compression: self.compression,
+                batch: self.batch,
+                request: self.request,
+                tls: self.tls.clone(),
+                acknowledgements: HecClientAcknowledgementsConfig {
+                    indexer_acknowledgements_enabled: false,
+                    ..Default::default()
+                },
+                timestamp_key,
+                endpoint_target: EndpointTarget::Event,
+                auto_extract_timestamp: None,
+            },
+            options: HecLogsSinkOptions {
+                timestamp_nanos_key: self.timestamp_nanos_key.clone(),
+                endpoint_pool,
+                host_key_fallbacks,
+                timestamp_key_fallbacks,
+            },
+        })
+    }
+}
+
+/// The `splunk_hec_logs` sink the `humio_logs` sink is built on, along with the Humio settings
+/// it has no options for.
+struct HumioHecConfig {
+    hec: HecLogsSinkConfig,
+    options: HecLogsSinkOptions,
+}
+
+impl HumioHecConfig {
+    fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        self.hec.build_with_options(cx, &self.options)
     }
 }
 
@@ -195,6 +508,213 @@ mod tests {
     fn generate_config() {
         crate::test_util::test_generate_config::<HumioLogsConfig>();
     }
+
+    #[test]
+    fn endpoints_build_pool() {
+        let config: HumioLogsConfig = toml::from_str(indoc::indoc! {r#"
+            token = "atoken"
+            encoding.codec = "json"
+
+            [[endpoints]]
+            endpoint = "https://dr.humio.example"
+            priority = 1
+
+            [[endpoints]]
+            endpoint = "https://primary.humio.example"
+            weight = 2
+        "#})
+        .unwrap();
+
+        let hec_config = config.build_hec_config().unwrap();
+        assert_eq!(hec_config.hec.endpoint, "https://primary.humio.example");
+
+        let pool = hec_config.options.endpoint_pool.unwrap();
+        assert_eq!(pool.endpoints.len(), 2);
+        assert_eq!(pool.endpoints[1].weight, 2);
+        assert_eq!(pool.retry_after, Duration::from_secs(30));
+        assert_eq!(pool.health_check_interval, None);
+    }
+
+    #[test]
+    fn endpoint_and_endpoints_conflict() {
+        let config: HumioLogsConfig = toml::from_str(indoc::indoc! {r#"
+            token = "atoken"
+            endpoint = "https://cloud.humio.com"
+            encoding.codec = "json"
+
+            [[endpoints]]
+            endpoint = "https://primary.humio.example"
+        "#})
+        .unwrap();
+
+        assert!(config.build_hec_config().is_err());
+    }
+
+    #[test]
+    fn endpoint_weight_must_not_be_zero() {
+        let config: HumioLogsConfig = toml::from_str(indoc::indoc! {r#"
+            token = "atoken"
+            encoding.codec = "json"
+
+            [[endpoints]]
+            endpoint = "https://primary.humio.example"
+            weight = 0
+        "#})
+        .unwrap();
+
+        assert!(config.build_hec_config().is_err());
+    }
+
+    #[test]
+    fn host_and_timestamp_key_lists() {
+        let config: HumioLogsConfig = toml::from_str(indoc::indoc! {r#"
+            token = "atoken"
+            encoding.codec = "json"
+            host_key = ["hostname", "kubernetes.node_name", "host"]
+            timestamp_key = "ts"
+        "#})
+        .unwrap();
+
+        let hec_config = config.build_hec_config().unwrap();
+        assert_eq!(hec_config.hec.host_key, "hostname");
+        assert_eq!(
+            hec_config.options.host_key_fallbacks,
+            vec!["kubernetes.node_name".to_string(), "host".to_string()]
+        );
+        assert_eq!(hec_config.hec.timestamp_key, "ts");
+        assert!(hec_config.options.timestamp_key_fallbacks.is_empty());
+    }
+
+    #[test]
+    fn normalization_tags_are_indexed() {
+        let config: HumioLogsConfig = toml::from_str(indoc::indoc! {r#"
+            token = "atoken"
+            encoding.codec = "json"
+            indexed_fields = ["service"]
+            normalization.tags = ["env"]
+        "#})
+        .unwrap();
+
+        let hec_config = config.build_hec_config().unwrap();
+        assert_eq!(
+            hec_config.hec.indexed_fields,
+            vec!["service".to_string(), "\"#env\"".to_string()]
+        );
+    }
+
+    #[test]
+    fn templated_tags_are_indexed() {
+        let config: HumioLogsConfig = toml::from_str(indoc::indoc! {r##"
+            token = "atoken"
+            encoding.codec = "json"
+            indexed_fields = ["service"]
+            tags.env = "{{ environment }}"
+            tags."#team" = "{{ team }}"
+        "##})
+        .unwrap();
+
+        let hec_config = config.build_hec_config().unwrap();
+        assert_eq!(
+            hec_config.hec.indexed_fields,
+            vec![
+                "service".to_string(),
+                "\"#env\"".to_string(),
+                "\"#team\"".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn templated_tags_conflict_with_normalization_tags() {
+        let config: HumioLogsConfig = toml::from_str(indoc::indoc! {r#"
+            token = "atoken"
+            encoding.codec = "json"
+            normalization.tags = ["env"]
+            tags.env = "{{ environment }}"
+        "#})
+        .unwrap();
+
+        assert!(config.build_hec_config().is_err());
+    }
+
+    #[test]
+    fn event_type_field_renders_sourcetype() {
+        let config: HumioLogsConfig = toml::from_str(indoc::indoc! {r#"
+            token = "atoken"
+            encoding.codec = "json"
+            event_type_field.field = "parser"
+            event_type_field.default = "json"
+            event_type_field.allowed = ["json", "accesslog"]
+        "#})
+        .unwrap();
+
+        let hec_config = config.build_hec_config().unwrap();
+        assert_eq!(hec_config.hec.sourcetype.unwrap().get_ref(), "{{ parser }}");
+
+        let mut config = config;
+        config.event_type_field.as_mut().unwrap().default = "syslog".to_string();
+        assert!(config.build_hec_config().is_err());
+
+        config.event_type_field.as_mut().unwrap().default = "json".to_string();
+        config.event_type = Some(Template::try_from("json").unwrap());
+        assert!(config.build_hec_config().is_err());
+    }
+
+    #[test]
+    fn static_templates_within_humio_limits() {
+        let config: HumioLogsConfig = toml::from_str(indoc::indoc! {r#"
+            token = "atoken"
+            encoding.codec = "json"
+            index = "my-repo_1"
+            event_type = "json"
+        "#})
+        .unwrap();
+        assert!(config.build_hec_config().is_ok());
+
+        let mut invalid = config.clone();
+        invalid.index = Some(Template::try_from("my repo").unwrap());
+        assert!(invalid.build_hec_config().is_err());
+
+        let mut invalid = config.clone();
+        invalid.index = Some(Template::try_from("a".repeat(101)).unwrap());
+        assert!(invalid.build_hec_config().is_err());
+
+        let mut invalid = config.clone();
+        invalid.event_type = Some(Template::try_from("json\n").unwrap());
+        assert!(invalid.build_hec_config().is_err());
+
+        // Dynamic templates are only checked once rendered.
+        let mut dynamic = config;
+        dynamic.index = Some(Template::try_from("{{ repository }} logs").unwrap());
+        assert!(dynamic.build_hec_config().is_ok());
+    }
+
+    #[test]
+    fn templates_require_referenced_fields() {
+        let config: HumioLogsConfig = toml::from_str(indoc::indoc! {r#"
+            token = "atoken"
+            encoding.codec = "json"
+            source = "{{ file }}"
+            index = "{{ kubernetes.namespace }}"
+        "#})
+        .unwrap();
+
+        let requirement = config.input().schema_requirement().clone();
+        let definition = schema::Definition::empty_legacy_namespace().with_event_field(
+            &lookup::owned_value_path!("file"),
+            value::Kind::bytes(),
+            None,
+        );
+        let errors = requirement.validate(&definition).unwrap_err();
+        assert_eq!(errors.errors().len(), 1);
+        assert!(errors.errors()[0].is_field_missing());
+
+        assert!(requirement
+            .validate(
+                &schema::Definition::empty_legacy_namespace().unknown_fields(value::Kind::any())
+            )
+            .is_ok());
+    }
 }
 
 #[cfg(test)]
@@ -355,11 +875,18 @@ mod integration_tests {
         HumioLogsConfig {
             token: token.to_string().into(),
             endpoint: Some(humio_address()),
+            endpoints: vec![],
+            endpoint_retry_after_secs: default_endpoint_retry_after_secs(),
+            endpoint_health_check_interval_secs: None,
             source: None,
             encoding: JsonSerializerConfig::new().into(),
             event_type: None,
-            host_key: log_schema().host_key().to_string(),
+            event_type_field: None,
+            host_key: log_schema().host_key().to_string().into(),
             indexed_fields: vec![],
+            tags: BTreeMap::new(),
+            max_tag_values: default_max_tag_values(),
+            normalization: None,
             index: None,
             compression: Compression::None,
             request: TowerRequestConfig::default(),
@@ -367,7 +894,7 @@ mod integration_tests {
             tls: None,
             timestamp_nanos_key: timestamp_nanos_key(),
             acknowledgements: Default::default(),
-            timestamp_key: Default::default(),
+            timestamp_key: default_timestamp_key(),
         }
     }
 
@@ -7,7 +7,13 @@ use vector_common::sensitive_string::SensitiveString;
 use vector_config::configurable_component;
 use vector_core::{sink::StreamSink, transform::Transform};
 
-use super::{host_key, logs::HumioLogsConfig};
+use super::{
+    host_key,
+    logs::{
+        default_endpoint_retry_after_secs, default_max_tag_values, HumioEndpointConfig,
+        HumioLogsConfig,
+    },
+};
 use crate::{
     config::{
         AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext, TransformConfig,
@@ -46,6 +52,32 @@ pub struct HumioMetricsConfig {
     #[serde(alias = "host")]
     pub(super) endpoint: Option<String>,
 
+    /// A set of Humio endpoints to send metrics to, instead of a single `endpoint`.
+    ///
+    /// Metrics are spread over the healthy endpoints with the lowest `priority`, according to
+    /// their `weight`. When an endpoint fails a request, it is taken out of rotation and traffic
+    /// fails over to the remaining endpoints.
+    ///
+    /// Cannot be used together with `endpoint`.
+    #[serde(default)]
+    endpoints: Vec<HumioEndpointConfig>,
+
+    /// The amount of time, in seconds, that an endpoint which failed a request is skipped for
+    /// before being tried again.
+    ///
+    /// Only relevant when `endpoints` is set.
+    #[serde(default = "default_endpoint_retry_after_secs")]
+    endpoint_retry_after_secs: u64,
+
+    /// The interval, in seconds, on which each of the `endpoints` is health checked.
+    ///
+    /// An endpoint failing its health check is taken out of rotation, as if it had failed a
+    /// request, before any metrics are sent to it, and is put back as soon as it passes one. By
+    /// default, endpoints are only taken out of rotation when they fail a request.
+    ///
+    /// Only relevant when `endpoints` is set.
+    endpoint_health_check_interval_secs: Option<u64>,
+
     /// The source of events sent to this sink.
     ///
     /// Typically the filename the metrics originated from. Maps to `@source` in Humio.
@@ -132,11 +164,18 @@ impl SinkConfig for HumioMetricsConfig {
         let sink = HumioLogsConfig {
             token: self.token.clone(),
             endpoint: self.endpoint.clone(),
+            endpoints: self.endpoints.clone(),
+            endpoint_retry_after_secs: self.endpoint_retry_after_secs,
+            endpoint_health_check_interval_secs: self.endpoint_health_check_interval_secs,
             source: self.source.clone(),
             encoding: JsonSerializerConfig::new().into(),
             event_type: self.event_type.clone(),
-            host_key: self.host_key.clone(),
+            event_type_field: None,
+            host_key: self.host_key.clone().into(),
             indexed_fields: self.indexed_fields.clone(),
+            tags: Default::default(),
+            max_tag_values: default_max_tag_values(),
+            normalization: None,
             index: self.index.clone(),
             compression: self.compression,
             request: self.request,
@@ -145,7 +184,7 @@ impl SinkConfig for HumioMetricsConfig {
             timestamp_nanos_key: None,
             acknowledgements: Default::default(),
             // hard coded as humio expects this format so no sense in making it configurable
-            timestamp_key: "timestamp".to_string(),
+            timestamp_key: "timestamp".to_string().into(),
         };
 
         let (sink, healthcheck) = sink.clone().build(cx).await?;
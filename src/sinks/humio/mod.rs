@@ -1,5 +1,6 @@
 pub mod logs;
 pub mod metrics;
+mod normalization;
 
 fn host_key() -> String {
     crate::config::log_schema().host_key().to_string()
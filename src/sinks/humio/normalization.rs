@@ -0,0 +1,421 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use async_trait::async_trait;
+use chrono::{DateTime, TimeZone, Utc};
+use futures::{stream::BoxStream, StreamExt};
+use lookup::event_path;
+use vector_config::configurable_component;
+use vector_core::{
+    event::{EventArray, LogEvent, Value},
+    sink::StreamSink,
+};
+
+use crate::{
+    internal_events::{
+        HumioEventTypeDefaulted, HumioTagDropped, HumioTagValueInvalid, TemplateRenderingError,
+    },
+    sinks::VectorSink,
+    template::Template,
+};
+
+/// Configuration for normalizing events to Humio conventions.
+///
+/// When set, the timestamp field, if it holds an RFC 3339 string or an integer number of
+/// milliseconds since the Unix epoch, is converted so that it is sent as Humio's `@timestamp`,
+/// and each field listed in `tags` is sent as a Humio tag.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct HumioNormalizationConfig {
+    /// Event fields to send as Humio tags.
+    ///
+    /// Each field is moved to a field named after it, prefixed with `#`, which is sent as an
+    /// indexed field. For example, `env` is sent as the `#env` tag.
+    ///
+    /// Tags should have few distinct values, so only string values are tagged, and only until a
+    /// field has had `max_tag_values` distinct values. Other values are left in the event as is.
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// The maximum number of distinct values of each field listed in `tags`.
+    #[serde(default = "default_max_tag_values")]
+    pub max_tag_values: usize,
+}
+
+const fn default_max_tag_values() -> usize {
+    100
+}
+
+/// Configuration for deriving the type of each event from one of its fields.
+///
+/// Humio uses the type of an event as the name of the parser to ingest it with, so this lets
+/// events of different kinds sent to the same repository each be parsed by the right parser.
+///
+/// Events whose field is missing, isn't a string, or names a parser that isn't in `allowed` have
+/// the field set to `default`, so that they are still ingested with a known parser.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct HumioEventTypeFieldConfig {
+    /// The event field holding the name of the parser.
+    pub field: String,
+
+    /// The name of the parser used for events whose field is missing or not allowed.
+    pub default: String,
+
+    /// The names of the parsers events can be assigned.
+    ///
+    /// By default, any name is allowed.
+    #[serde(default)]
+    pub allowed: Vec<String>,
+}
+
+impl HumioEventTypeFieldConfig {
+    pub(super) fn validate(&self) -> crate::Result<()> {
+        if self.field.is_empty() {
+            return Err("`event_type_field.field` can't be empty.".into());
+        }
+        if !self.allowed.is_empty() && !self.allowed.contains(&self.default) {
+            return Err(format!(
+                "`event_type_field.default` {:?} must be one of `event_type_field.allowed`.",
+                self.default
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Returns the template rendering the type of each event once it has been normalized.
+    pub(super) fn template(&self) -> crate::Result<Template> {
+        Ok(Template::try_from(format!("{{{{ {} }}}}", self.field))?)
+    }
+}
+
+impl HumioNormalizationConfig {
+    /// Returns the paths of the fields tags are sent as.
+    pub(super) fn tag_fields(&self) -> impl Iterator<Item = String> + '_ {
+        self.tags.iter().map(|field| tag_path(field))
+    }
+}
+
+/// Returns the name of the field a tag is sent as, which is prefixed with `#`.
+pub(super) fn tag_field(name: &str) -> String {
+    if name.starts_with('#') {
+        name.to_string()
+    } else {
+        format!("#{}", name)
+    }
+}
+
+/// Returns the path of the field a tag is sent as. The field name is quoted, as a path segment
+/// can't start with `#`.
+pub(super) fn tag_path(name: &str) -> String {
+    format!("{:?}", tag_field(name))
+}
+
+/// Normalizes events to Humio conventions, and adds the tags rendered from templates.
+#[derive(Default)]
+pub(super) struct Normalizer {
+    timestamp_keys: Vec<String>,
+    tags: Vec<String>,
+    max_tag_values: usize,
+    templated_tags: Vec<(String, Template)>,
+    max_templated_tag_values: usize,
+    tag_values: HashMap<String, HashSet<String>>,
+    event_type: Option<HumioEventTypeFieldConfig>,
+}
+
+impl Normalizer {
+    pub(super) fn new(config: &HumioNormalizationConfig, timestamp_keys: Vec<String>) -> Self {
+        Self {
+            timestamp_keys,
+            tags: config.tags.clone(),
+            max_tag_values: config.max_tag_values,
+            ..Default::default()
+        }
+    }
+
+    /// Adds the tags rendered from `templates` to each event, keeping at most `max_values`
+    /// distinct values of each.
+    pub(super) fn with_templated_tags(
+        mut self,
+        templates: &BTreeMap<String, Template>,
+        max_values: usize,
+    ) -> Self {
+        self.templated_tags = templates
+            .iter()
+            .map(|(name, template)| (tag_field(name), template.clone()))
+            .collect();
+        self.max_templated_tag_values = max_values;
+        self
+    }
+
+    /// Sets the event type field of each event to a valid parser name.
+    pub(super) fn with_event_type(mut self, event_type: Option<HumioEventTypeFieldConfig>) -> Self {
+        self.event_type = event_type;
+        self
+    }
+
+    fn normalize(&mut self, log: &mut LogEvent) {
+        for key in &self.timestamp_keys {
+            let timestamp = match log.get(key.as_str()) {
+                Some(Value::Bytes(bytes)) => {
+                    DateTime::parse_from_rfc3339(&String::from_utf8_lossy(bytes))
+                        .ok()
+                        .map(|timestamp| timestamp.with_timezone(&Utc))
+                }
+                Some(Value::Integer(millis)) => Utc.timestamp_millis_opt(*millis).single(),
+                _ => None,
+            };
+            if let Some(timestamp) = timestamp {
+                log.insert(key.as_str(), timestamp);
+            }
+        }
+
+        for field in &self.tags {
+            let value = match log.get(field.as_str()) {
+                Some(Value::Bytes(bytes)) => String::from_utf8_lossy(bytes).into_owned(),
+                Some(_) => {
+                    emit!(HumioTagValueInvalid {
+                        field,
+                        reason: "not a string",
+                    });
+                    continue;
+                }
+                None => continue,
+            };
+
+            let tag = tag_field(field);
+            if !admit(&mut self.tag_values, &tag, &value, self.max_tag_values) {
+                emit!(HumioTagValueInvalid {
+                    field,
+                    reason: "too many distinct values",
+                });
+                continue;
+            }
+
+            if let Some(value) = log.remove(field.as_str()) {
+                log.insert(event_path!(tag.as_str()), value);
+            }
+        }
+
+        for (tag, template) in &self.templated_tags {
+            let value = match template.render_string(&*log) {
+                Ok(value) => value,
+                Err(error) => {
+                    emit!(TemplateRenderingError {
+                        error,
+                        field: Some("tags"),
+                        drop_event: false,
+                    });
+                    continue;
+                }
+            };
+
+            if !admit(
+                &mut self.tag_values,
+                tag,
+                &value,
+                self.max_templated_tag_values,
+            ) {
+                emit!(HumioTagDropped {
+                    tag,
+                    reason: "too many distinct values",
+                });
+                continue;
+            }
+            log.insert(event_path!(tag.as_str()), value);
+        }
+
+        if let Some(event_type) = &self.event_type {
+            let reason = match log.get(event_type.field.as_str()) {
+                Some(Value::Bytes(bytes)) => {
+                    let allowed = event_type.allowed.is_empty()
+                        || event_type
+                            .allowed
+                            .iter()
+                            .any(|parser| parser.as_bytes() == bytes.as_ref());
+                    (!allowed).then_some("parser not allowed")
+                }
+                Some(_) => Some("not a string"),
+                None => Some("field missing"),
+            };
+            if let Some(reason) = reason {
+                emit!(HumioEventTypeDefaulted {
+                    field: &event_type.field,
+                    reason,
+                });
+                log.insert(event_type.field.as_str(), event_type.default.clone());
+            }
+        }
+    }
+}
+
+/// Records `value` as a value of the tag sent as `tag`, and returns whether it can be sent without
+/// the tag going over `max_values` distinct values.
+fn admit(
+    tag_values: &mut HashMap<String, HashSet<String>>,
+    tag: &str,
+    value: &str,
+    max_values: usize,
+) -> bool {
+    let values = tag_values.entry(tag.to_string()).or_default();
+    if values.contains(value) {
+        true
+    } else if values.len() < max_values {
+        values.insert(value.to_string());
+        true
+    } else {
+        false
+    }
+}
+
+/// Normalizes events before handing them to the underlying sink.
+pub(super) struct NormalizingSink {
+    pub(super) inner: VectorSink,
+    pub(super) normalizer: Normalizer,
+}
+
+#[async_trait]
+impl StreamSink<EventArray> for NormalizingSink {
+    async fn run(self: Box<Self>, input: BoxStream<'_, EventArray>) -> Result<(), ()> {
+        let mut normalizer = self.normalizer;
+        self.inner
+            .run(input.map(move |mut events| {
+                if let EventArray::Logs(logs) = &mut events {
+                    for log in logs {
+                        normalizer.normalize(log);
+                    }
+                }
+                events
+            }))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalizer(max_tag_values: usize) -> Normalizer {
+        let config = HumioNormalizationConfig {
+            tags: vec!["env".to_string()],
+            max_tag_values,
+        };
+        Normalizer::new(&config, vec!["timestamp".to_string()])
+    }
+
+    #[test]
+    fn converts_timestamps() {
+        let mut normalizer = normalizer(1);
+        let expected = Utc.timestamp_millis(1_600_000_000_123);
+
+        let mut log = LogEvent::default();
+        log.insert("timestamp", "2020-09-13T12:26:40.123Z");
+        normalizer.normalize(&mut log);
+        assert_eq!(log["timestamp"], expected.into());
+
+        let mut log = LogEvent::default();
+        log.insert("timestamp", 1_600_000_000_123_i64);
+        normalizer.normalize(&mut log);
+        assert_eq!(log["timestamp"], expected.into());
+    }
+
+    #[test]
+    fn moves_tags_up_to_max_values() {
+        let mut normalizer = normalizer(1);
+
+        let mut log = LogEvent::default();
+        log.insert("env", "production");
+        normalizer.normalize(&mut log);
+        assert_eq!(log.get("env"), None);
+        assert_eq!(
+            log.get(event_path!("#env")),
+            Some(&Value::from("production"))
+        );
+        // The tag is found at the path it is indexed as.
+        assert_eq!(
+            log.get(tag_path("env").as_str()),
+            Some(&Value::from("production"))
+        );
+
+        let mut log = LogEvent::default();
+        log.insert("env", "staging");
+        normalizer.normalize(&mut log);
+        assert_eq!(log["env"], "staging".into());
+        assert_eq!(log.get(event_path!("#env")), None);
+
+        let mut log = LogEvent::default();
+        log.insert("env", 1);
+        normalizer.normalize(&mut log);
+        assert_eq!(log["env"], 1.into());
+    }
+
+    #[test]
+    fn renders_templated_tags_up_to_max_values() {
+        let templates = BTreeMap::from([
+            (
+                "env".to_string(),
+                Template::try_from("{{ environment }}").unwrap(),
+            ),
+            (
+                "#region".to_string(),
+                Template::try_from("eu-{{ zone }}").unwrap(),
+            ),
+        ]);
+        let mut normalizer = Normalizer::default().with_templated_tags(&templates, 1);
+
+        let mut log = LogEvent::default();
+        log.insert("environment", "production");
+        log.insert("zone", "west");
+        normalizer.normalize(&mut log);
+        assert_eq!(
+            log.get(event_path!("#env")),
+            Some(&Value::from("production"))
+        );
+        assert_eq!(
+            log.get(event_path!("#region")),
+            Some(&Value::from("eu-west"))
+        );
+        assert_eq!(log["environment"], "production".into());
+
+        // A new value goes over the limit, and a missing field can't be rendered.
+        let mut log = LogEvent::default();
+        log.insert("environment", "staging");
+        normalizer.normalize(&mut log);
+        assert_eq!(log.get(event_path!("#env")), None);
+        assert_eq!(log.get(event_path!("#region")), None);
+
+        let mut log = LogEvent::default();
+        log.insert("environment", "production");
+        normalizer.normalize(&mut log);
+        assert_eq!(
+            log.get(event_path!("#env")),
+            Some(&Value::from("production"))
+        );
+    }
+
+    #[test]
+    fn defaults_event_types_not_allowed() {
+        let mut normalizer =
+            Normalizer::default().with_event_type(Some(HumioEventTypeFieldConfig {
+                field: "parser".to_string(),
+                default: "json".to_string(),
+                allowed: vec!["json".to_string(), "accesslog".to_string()],
+            }));
+
+        let mut log = LogEvent::default();
+        log.insert("parser", "accesslog");
+        normalizer.normalize(&mut log);
+        assert_eq!(log["parser"], "accesslog".into());
+
+        for value in [Some(Value::from("syslog")), Some(Value::from(1)), None] {
+            let mut log = LogEvent::default();
+            if let Some(value) = value {
+                log.insert("parser", value);
+            }
+            normalizer.normalize(&mut log);
+            assert_eq!(log["parser"], "json".into());
+        }
+    }
+}
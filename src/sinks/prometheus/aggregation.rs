@@ -0,0 +1,206 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    num::NonZeroU64,
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use futures::{stream::BoxStream, Sink, SinkExt, StreamExt};
+use vector_config::configurable_component;
+use vector_core::event::{
+    metric::{Metric, MetricSeries},
+    Event,
+};
+
+use crate::sinks::util::StreamSink;
+
+const fn default_window_secs() -> NonZeroU64 {
+    unsafe { NonZeroU64::new_unchecked(15) }
+}
+
+/// Client-side aggregation of metrics before they are written.
+///
+/// Within each window, the increments of incremental metrics are summed per series, and only the
+/// last value of absolute metrics, such as gauges, is kept. This reduces the number of samples
+/// written for high-frequency sources, at the cost of delaying them by up to one window.
+#[configurable_component]
+#[derive(Clone, Copy, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct AggregationConfig {
+    /// The length of the aggregation window, in seconds.
+    #[serde(default = "default_window_secs")]
+    pub window_secs: NonZeroU64,
+}
+
+impl Default for AggregationConfig {
+    fn default() -> Self {
+        Self {
+            window_secs: default_window_secs(),
+        }
+    }
+}
+
+/// Metrics received during the current window, by series.
+#[derive(Default)]
+struct AggregationWindow {
+    metrics: HashMap<MetricSeries, Metric>,
+}
+
+impl AggregationWindow {
+    fn push(&mut self, mut metric: Metric) {
+        match self.metrics.entry(metric.series().clone()) {
+            Entry::Occupied(mut entry) => {
+                let existing = entry.get_mut();
+                if existing.data_mut().add(metric.data()) {
+                    let (_, _, metadata) = metric.into_parts();
+                    existing.metadata_mut().merge(metadata);
+                } else {
+                    // Absolute values, or a change of type, overwrite the series. The finalizers of
+                    // the overwritten metric are kept so it's acknowledged once the window is sent.
+                    let finalizers = existing.metadata_mut().take_finalizers();
+                    metric.metadata_mut().merge_finalizers(finalizers);
+                    *existing = metric;
+                }
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(metric);
+            }
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.metrics.is_empty()
+    }
+
+    fn drain(&mut self) -> impl Iterator<Item = Metric> + '_ {
+        self.metrics.drain().map(|(_, metric)| metric)
+    }
+}
+
+/// Aggregates metrics over fixed windows before passing them on to the wrapped sink.
+pub(super) struct AggregatingSink<S> {
+    window: Duration,
+    sink: S,
+}
+
+impl<S> AggregatingSink<S> {
+    pub(super) fn new(config: AggregationConfig, sink: S) -> Self {
+        Self {
+            window: Duration::from_secs(config.window_secs.get()),
+            sink,
+        }
+    }
+}
+
+impl<S> AggregatingSink<S>
+where
+    S: Sink<Event, Error = ()> + Send + Unpin,
+{
+    async fn flush(&mut self, window: &mut AggregationWindow) -> Result<(), ()> {
+        if window.is_empty() {
+            return Ok(());
+        }
+        let mut metrics =
+            futures::stream::iter(window.drain().map(|metric| Ok(Event::from(metric))));
+        self.sink.send_all(&mut metrics).await
+    }
+}
+
+#[async_trait]
+impl<S> StreamSink<Event> for AggregatingSink<S>
+where
+    S: Sink<Event, Error = ()> + Send + Unpin,
+{
+    async fn run(mut self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let mut input = input.fuse();
+        let mut window = AggregationWindow::default();
+        let mut ticks = tokio::time::interval(self.window);
+        // The first tick completes immediately.
+        ticks.tick().await;
+
+        loop {
+            tokio::select! {
+                event = input.next() => match event {
+                    Some(event) => window.push(event.into_metric()),
+                    None => break,
+                },
+                _ = ticks.tick() => self.flush(&mut window).await?,
+            }
+        }
+
+        self.flush(&mut window).await?;
+        self.sink.close().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_core::event::{
+        metric::{MetricKind, MetricValue},
+        BatchNotifier, BatchStatus, EventFinalizer, EventStatus,
+    };
+
+    use super::*;
+
+    fn metric(name: &str, kind: MetricKind, value: MetricValue) -> Metric {
+        Metric::new(name, kind, value)
+    }
+
+    fn drained(window: &mut AggregationWindow) -> Vec<Metric> {
+        let mut metrics = window.drain().collect::<Vec<_>>();
+        metrics.sort_by(|a, b| a.name().cmp(b.name()));
+        metrics
+    }
+
+    #[test]
+    fn sums_increments_and_keeps_last_absolute_value() {
+        let mut window = AggregationWindow::default();
+        for value in [1.0, 2.0, 3.0] {
+            window.push(metric(
+                "counter",
+                MetricKind::Incremental,
+                MetricValue::Counter { value },
+            ));
+            window.push(metric(
+                "gauge",
+                MetricKind::Absolute,
+                MetricValue::Gauge { value },
+            ));
+        }
+
+        let metrics = drained(&mut window);
+        assert_eq!(metrics.len(), 2);
+        assert_eq!(metrics[0].value(), &MetricValue::Counter { value: 6.0 });
+        assert_eq!(metrics[1].value(), &MetricValue::Gauge { value: 3.0 });
+        assert!(window.is_empty());
+    }
+
+    #[tokio::test]
+    async fn keeps_finalizers_of_overwritten_metrics() {
+        let (batch, receiver) = BatchNotifier::new_with_receiver();
+        let mut window = AggregationWindow::default();
+        let mut first = metric(
+            "gauge",
+            MetricKind::Absolute,
+            MetricValue::Gauge { value: 1.0 },
+        );
+        first
+            .metadata_mut()
+            .add_finalizer(EventFinalizer::new(batch));
+        window.push(first);
+        window.push(metric(
+            "gauge",
+            MetricKind::Absolute,
+            MetricValue::Gauge { value: 2.0 },
+        ));
+
+        let mut metrics = drained(&mut window);
+        assert_eq!(metrics[0].value(), &MetricValue::Gauge { value: 2.0 });
+        metrics[0]
+            .metadata_mut()
+            .take_finalizers()
+            .update_status(EventStatus::Rejected);
+        drop(metrics);
+        assert_eq!(receiver.await, BatchStatus::Rejected);
+    }
+}
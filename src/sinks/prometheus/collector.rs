@@ -2,11 +2,15 @@ use std::{collections::BTreeMap, fmt::Write as _};
 
 use chrono::Utc;
 use indexmap::map::IndexMap;
+use lookup::path;
 use prometheus_parser::{proto, METRIC_NAME_LABEL};
 use vector_core::event::metric::{samples_to_buckets, MetricSketch, MetricTags, Quantile};
 
 use crate::{
-    event::metric::{Metric, MetricKind, MetricValue, StatisticKind},
+    event::{
+        metric::{Metric, MetricKind, MetricValue, StatisticKind},
+        Value,
+    },
     sinks::util::{encode_namespace, statistic::DistributionStatistic},
 };
 
@@ -318,6 +322,7 @@ type Labels = Vec<proto::Label>;
 
 pub(super) struct TimeSeries {
     buffer: IndexMap<Labels, Vec<proto::Sample>>,
+    exemplars: IndexMap<Labels, Vec<proto::Exemplar>>,
     metadata: IndexMap<String, proto::MetricMetadata>,
     timestamp: Option<i64>,
 }
@@ -354,6 +359,86 @@ impl TimeSeries {
             .timestamp
             .get_or_insert_with(|| Utc::now().timestamp_millis())
     }
+
+    /// Attaches the exemplars of a histogram to the series of the buckets they fall in.
+    ///
+    /// Exemplars are read from the `exemplars` array of the metadata of the metric. Each is an
+    /// object with a numeric `value`, and optionally a `timestamp`, as a timestamp or an integer
+    /// number of milliseconds, and an object of `labels`, such as a `trace_id`. Exemplars without
+    /// a numeric `value` are ignored.
+    pub(super) fn encode_exemplars(
+        &mut self,
+        default_namespace: Option<&str>,
+        buckets: &[f64],
+        metric: &Metric,
+    ) {
+        if metric.kind() != MetricKind::Absolute {
+            return;
+        }
+        let upper_limits = match metric.value() {
+            MetricValue::Distribution {
+                statistic: StatisticKind::Histogram,
+                ..
+            } => buckets.to_vec(),
+            MetricValue::AggregatedHistogram { buckets, .. } => buckets
+                .iter()
+                .map(|bucket| bucket.upper_limit)
+                .filter(|upper_limit| upper_limit.is_finite())
+                .collect(),
+            _ => return,
+        };
+        let exemplars = match metric.metadata().value().get(path!("exemplars")) {
+            Some(Value::Array(exemplars)) => exemplars,
+            _ => return,
+        };
+
+        let name = encode_namespace(metric.namespace().or(default_namespace), '_', metric.name());
+        let timestamp = match metric.timestamp() {
+            Some(timestamp) => timestamp.timestamp_millis(),
+            None => self.default_timestamp(),
+        };
+        for exemplar in exemplars {
+            let exemplar = match decode_exemplar(exemplar, timestamp) {
+                Some(exemplar) => exemplar,
+                None => continue,
+            };
+            let le = upper_limits
+                .iter()
+                .find(|upper_limit| exemplar.value <= **upper_limit)
+                .map_or_else(|| "+Inf".to_string(), ToString::to_string);
+            let labels = Self::make_labels(metric.tags(), &name, "_bucket", Some(("le", le)));
+            self.exemplars.entry(labels).or_default().push(exemplar);
+        }
+    }
+}
+
+fn decode_exemplar(exemplar: &Value, default_timestamp: i64) -> Option<proto::Exemplar> {
+    let value = match exemplar.get(path!("value"))? {
+        Value::Float(value) => value.into_inner(),
+        Value::Integer(value) => *value as f64,
+        _ => return None,
+    };
+    let timestamp = match exemplar.get(path!("timestamp")) {
+        Some(Value::Timestamp(timestamp)) => timestamp.timestamp_millis(),
+        Some(Value::Integer(millis)) => *millis,
+        _ => default_timestamp,
+    };
+    let mut labels = match exemplar.get(path!("labels")) {
+        Some(Value::Object(labels)) => labels
+            .iter()
+            .map(|(name, value)| proto::Label {
+                name: name.clone(),
+                value: value.to_string_lossy().into_owned(),
+            })
+            .collect(),
+        _ => Labels::new(),
+    };
+    labels.sort();
+    Some(proto::Exemplar {
+        labels,
+        value,
+        timestamp,
+    })
 }
 
 impl MetricCollector for TimeSeries {
@@ -362,6 +447,7 @@ impl MetricCollector for TimeSeries {
     fn new() -> Self {
         Self {
             buffer: Default::default(),
+            exemplars: Default::default(),
             metadata: Default::default(),
             timestamp: None,
         }
@@ -397,10 +483,19 @@ impl MetricCollector for TimeSeries {
     }
 
     fn finish(self) -> proto::WriteRequest {
+        let mut exemplars = self.exemplars;
         let timeseries = self
             .buffer
             .into_iter()
-            .map(|(labels, samples)| proto::TimeSeries { labels, samples })
+            .map(|(labels, samples)| {
+                // Exemplars of series without samples are dropped.
+                let exemplars = exemplars.remove(&labels).unwrap_or_default();
+                proto::TimeSeries {
+                    labels,
+                    samples,
+                    exemplars,
+                }
+            })
             .collect::<Vec<_>>();
         let metadata = self
             .metadata
@@ -490,6 +585,7 @@ mod tests {
                                 value: $svalue,
                                 timestamp: $timestamp,
                             }],
+                            exemplars: vec![],
                         },
                     )*
                 ],
@@ -744,6 +840,68 @@ mod tests {
         );
     }
 
+    #[test]
+    fn encodes_histogram_exemplars() {
+        let mut metric = Metric::new(
+            "requests".to_owned(),
+            MetricKind::Absolute,
+            MetricValue::Distribution {
+                samples: vector_core::samples![0.4 => 1, 2.0 => 1, 7.5 => 1],
+                statistic: StatisticKind::Histogram,
+            },
+        )
+        .with_timestamp(Some(timestamp()));
+        let exemplar = |value: f64, trace_id: &str| {
+            Value::from(BTreeMap::from([
+                ("value".to_string(), Value::from(value)),
+                (
+                    "labels".to_string(),
+                    Value::from(BTreeMap::from([(
+                        "trace_id".to_string(),
+                        Value::from(trace_id),
+                    )])),
+                ),
+            ]))
+        };
+        metric.metadata_mut().value_mut().insert(
+            path!("exemplars"),
+            Value::Array(vec![
+                exemplar(0.4, "a"),
+                exemplar(7.5, "b"),
+                Value::from("not an exemplar"),
+            ]),
+        );
+
+        let mut time_series = TimeSeries::new();
+        time_series.encode_metric(Some("vector"), &[1.0, 3.0], &[], &metric);
+        time_series.encode_exemplars(Some("vector"), &[1.0, 3.0], &metric);
+        let request = time_series.finish();
+
+        let exemplars = request
+            .timeseries
+            .iter()
+            .filter(|series| !series.exemplars.is_empty())
+            .map(|series| {
+                let le = series.labels.iter().find(|label| label.name == "le");
+                (le.unwrap().value.as_str(), &series.exemplars)
+            })
+            .collect::<Vec<_>>();
+        let expected = |value: f64, trace_id: &str| {
+            vec![proto::Exemplar {
+                labels: vec![proto::Label {
+                    name: "trace_id".into(),
+                    value: trace_id.into(),
+                }],
+                value,
+                timestamp: 1612325106789,
+            }]
+        };
+        assert_eq!(
+            exemplars,
+            vec![("1", &expected(0.4, "a")), ("+Inf", &expected(7.5, "b"))]
+        );
+    }
+
     fn encode_histogram<T: MetricCollector>(add_inf_bound: bool) -> T::Output {
         let bounds = if add_inf_bound {
             &[1.0, 2.1, 3.0, f64::INFINITY][..]
@@ -1,6 +1,7 @@
 use std::{
+    collections::{BTreeMap, HashSet},
     convert::Infallible,
-    hash::Hash,
+    hash::{Hash, Hasher},
     mem::{discriminant, Discriminant},
     net::SocketAddr,
     sync::{Arc, RwLock},
@@ -8,8 +9,10 @@ use std::{
 };
 
 use async_trait::async_trait;
+use bytes::BytesMut;
 use futures::{future, stream::BoxStream, FutureExt, StreamExt};
 use hyper::{
+    body::HttpBody,
     header::HeaderValue,
     service::{make_service_fn, service_fn},
     Body, Method, Request, Response, Server, StatusCode,
@@ -30,13 +33,16 @@ use vector_core::{
 
 use super::collector::{MetricCollector, StringCollector};
 use crate::{
+    common::prometheus::parse_text,
     config::{AcknowledgementsConfig, GenerateConfig, Input, Resource, SinkConfig, SinkContext},
     event::{
         metric::{Metric, MetricData, MetricKind, MetricSeries, MetricValue},
         Event, EventStatus, Finalizable,
     },
     http::Auth,
-    internal_events::{PrometheusNormalizationError, PrometheusServerRequestComplete},
+    internal_events::{
+        PrometheusNormalizationError, PrometheusPushParseError, PrometheusServerRequestComplete,
+    },
     sinks::{
         util::{
             buffer::metrics::{MetricNormalize, MetricNormalizer, MetricSet},
@@ -52,10 +58,15 @@ const MIN_FLUSH_PERIOD_SECS: u64 = 1;
 
 const LOCK_FAILED: &str = "Prometheus exporter data lock is poisoned";
 
+/// The largest request body accepted by the Pushgateway API.
+const MAX_PUSH_BODY_BYTES: usize = 10 * 1024 * 1024;
+
 #[derive(Debug, Snafu)]
 enum BuildError {
     #[snafu(display("Flush period for sets must be greater or equal to {} secs", min))]
     FlushPeriodTooShort { min: u64 },
+    #[snafu(display("Shard index {} must be less than the shard count {}", index, count))]
+    InvalidShard { index: u64, count: u64 },
 }
 
 /// Configuration for the `prometheus_exporter` sink.
@@ -81,9 +92,16 @@ pub struct PrometheusExporterConfig {
     #[serde(default = "default_address")]
     pub address: SocketAddr,
 
+    /// Authentication required from clients scraping the exposed metrics.
+    ///
+    /// Scrapes without matching credentials are rejected with a `401 Unauthorized` response.
     #[configurable(derived)]
     pub auth: Option<Auth>,
 
+    /// TLS configuration of the exposed endpoint.
+    ///
+    /// When `tls.verify_certificate` is enabled, clients must present a certificate issued by one
+    /// of the authorities in `tls.ca_file`, and connections without one are rejected.
     #[configurable(derived)]
     pub tls: Option<TlsEnableableConfig>,
 
@@ -129,6 +147,13 @@ pub struct PrometheusExporterConfig {
     #[serde(default)]
     pub suppress_timestamp: bool,
 
+    #[configurable(derived)]
+    #[serde(default)]
+    pub pushgateway: PushgatewayConfig,
+
+    #[configurable(derived)]
+    pub shard: Option<ShardConfig>,
+
     #[configurable(derived)]
     #[serde(
         default,
@@ -138,6 +163,81 @@ pub struct PrometheusExporterConfig {
     pub acknowledgements: AcknowledgementsConfig,
 }
 
+/// Configuration for accepting metrics pushed with the Pushgateway API.
+///
+/// When enabled, short-lived jobs can push metrics in the Prometheus text format to
+/// `/metrics/job/<job>{/<label>/<value>}`, as they would to a [Pushgateway][pushgateway_docs].
+/// The `job` and any other labels of the path form the grouping key of the pushed metrics, and are
+/// added to them as tags.
+///
+/// A `PUT` replaces all the metrics of the group, a `POST` only replaces the metrics with the same
+/// name as the pushed ones, and a `DELETE` removes the group. Pushes larger than 10 MiB are
+/// rejected.
+///
+/// [pushgateway_docs]: https://github.com/prometheus/pushgateway#api
+#[serde_as]
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct PushgatewayConfig {
+    /// Whether or not to accept pushed metrics.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// The amount of time, in seconds, pushed metrics are exposed after they were last pushed.
+    #[serde(default = "default_push_ttl_secs")]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub ttl_secs: Duration,
+}
+
+/// Configuration for exposing only a shard of the metric series.
+///
+/// Each series is assigned to one of `count` shards by a hash of its name and tags, and only the
+/// series of shard `index` are exposed. Instances receiving the same metrics, such as a pair of
+/// Vector instances deployed for high availability, can be given the same `count` and distinct
+/// `index`es so that each exposes a disjoint share of the series, and scraping all of them does not
+/// count any series twice.
+#[configurable_component]
+#[derive(Clone, Copy, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ShardConfig {
+    /// The number of shards the series are split into.
+    pub count: u64,
+
+    /// The shard to expose, from `0` to `count - 1`.
+    pub index: u64,
+}
+
+impl ShardConfig {
+    /// Whether or not the series belongs to this shard.
+    ///
+    /// The hash is stable across instances and releases, so that all instances agree on the shard
+    /// of every series.
+    fn contains(&self, series: &MetricSeries) -> bool {
+        let mut hasher = seahash::SeaHasher::default();
+        series.name().namespace().hash(&mut hasher);
+        series.name().name().hash(&mut hasher);
+        for (key, value) in series
+            .tags()
+            .into_iter()
+            .flat_map(|tags| tags.iter_single())
+        {
+            key.hash(&mut hasher);
+            value.hash(&mut hasher);
+        }
+        hasher.finish() % self.count == self.index
+    }
+}
+
+impl Default for PushgatewayConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ttl_secs: default_push_ttl_secs(),
+        }
+    }
+}
+
 impl Default for PrometheusExporterConfig {
     fn default() -> Self {
         Self {
@@ -150,6 +250,8 @@ impl Default for PrometheusExporterConfig {
             distributions_as_summaries: default_distributions_as_summaries(),
             flush_period_secs: default_flush_period_secs(),
             suppress_timestamp: default_suppress_timestamp(),
+            pushgateway: Default::default(),
+            shard: None,
             acknowledgements: Default::default(),
         }
     }
@@ -169,6 +271,10 @@ const fn default_flush_period_secs() -> Duration {
     Duration::from_secs(60)
 }
 
+const fn default_push_ttl_secs() -> Duration {
+    Duration::from_secs(300)
+}
+
 const fn default_suppress_timestamp() -> bool {
     false
 }
@@ -190,6 +296,12 @@ impl SinkConfig for PrometheusExporterConfig {
 
         validate_quantiles(&self.quantiles)?;
 
+        if let Some(ShardConfig { count, index }) = self.shard {
+            if index >= count {
+                return Err(Box::new(BuildError::InvalidShard { index, count }));
+            }
+        }
+
         let sink = PrometheusExporter::new(self.clone());
         let healthcheck = future::ok(()).boxed();
 
@@ -215,11 +327,16 @@ struct PrometheusExporter {
     metrics: Arc<RwLock<IndexMap<MetricRef, (Metric, MetricMetadata)>>>,
 }
 
+/// The labels identifying a group of pushed metrics.
+type GroupingKey = BTreeMap<String, String>;
+
 /// Expiration metadata for a metric.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 struct MetricMetadata {
     expiration_window: Duration,
     expires_at: Instant,
+    /// The group the metric was pushed to, if it was pushed rather than received as an event.
+    group: Option<Arc<GroupingKey>>,
 }
 
 impl MetricMetadata {
@@ -227,9 +344,21 @@ impl MetricMetadata {
         Self {
             expiration_window,
             expires_at: Instant::now() + expiration_window,
+            group: None,
         }
     }
 
+    /// Marks the metric as pushed to the given group.
+    pub fn with_group(mut self, group: Arc<GroupingKey>) -> Self {
+        self.group = Some(group);
+        self
+    }
+
+    /// Whether or not the metric was pushed to the given group.
+    fn is_in_group(&self, group: &GroupingKey) -> bool {
+        self.group.as_deref() == Some(group)
+    }
+
     /// Resets the expiration deadline.
     pub fn refresh(&mut self) {
         self.expires_at = Instant::now() + self.expiration_window;
@@ -371,25 +500,55 @@ fn authorized(req: &Request<Body>, auth: &Option<Auth>) -> bool {
     false
 }
 
+/// Whether or not the series is exposed, given the shard to expose, if any.
+fn in_shard(shard: Option<ShardConfig>, series: &MetricSeries) -> bool {
+    shard.map_or(true, |shard| shard.contains(series))
+}
+
+/// Parses the grouping key out of a Pushgateway path, `/metrics/job/<job>{/<label>/<value>}`.
+fn parse_grouping_key(path: &str) -> Option<GroupingKey> {
+    let decode = |segment: &str| {
+        percent_encoding::percent_decode_str(segment)
+            .decode_utf8()
+            .ok()
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| segment.into_owned())
+    };
+
+    let mut segments = path.strip_prefix("/metrics/job/")?.split('/');
+    let mut group = GroupingKey::new();
+    group.insert("job".to_string(), decode(segments.next()?)?);
+    while let Some(label) = segments.next() {
+        group.insert(decode(label)?, decode(segments.next()?)?);
+    }
+
+    Some(group)
+}
+
 #[derive(Clone)]
 struct Handler {
     auth: Option<Auth>,
     default_namespace: Option<String>,
     buckets: Box<[f64]>,
     quantiles: Box<[f64]>,
+    push_ttl: Option<Duration>,
+    suppress_timestamp: bool,
+    shard: Option<ShardConfig>,
     bytes_sent: Registered<BytesSent>,
     events_sent: Registered<EventsSent>,
 }
 
 impl Handler {
-    fn handle(
+    async fn handle(
         &self,
         req: Request<Body>,
         metrics: &RwLock<IndexMap<MetricRef, (Metric, MetricMetadata)>>,
     ) -> Response<Body> {
         let mut response = Response::new(Body::empty());
+        let method = req.method().clone();
+        let path = req.uri().path().to_owned();
 
-        match (authorized(&req, &self.auth), req.method(), req.uri().path()) {
+        match (authorized(&req, &self.auth), &method, path.as_str()) {
             (false, _, _) => {
                 *response.status_mut() = StatusCode::UNAUTHORIZED;
                 response.headers_mut().insert(
@@ -401,15 +560,24 @@ impl Handler {
             (true, &Method::GET, "/metrics") => {
                 let metrics = metrics.read().expect(LOCK_FAILED);
 
-                let count = metrics.len();
-                let byte_size = metrics
+                // Pushed metrics are only expired when new events come in, which may never
+                // happen, so expired ones are skipped here as well.
+                let now = Instant::now();
+                let exposed = metrics
+                    .values()
+                    .filter(|(_, metadata)| metadata.group.is_none() || !metadata.has_expired(now))
+                    .map(|(metric, _)| metric)
+                    .collect::<Vec<_>>();
+
+                let count = exposed.len();
+                let byte_size = exposed
                     .iter()
-                    .map(|(_, (metric, _))| metric.estimated_json_encoded_size_of())
+                    .map(|metric| metric.estimated_json_encoded_size_of())
                     .sum();
 
                 let mut collector = StringCollector::new();
 
-                for (_, (metric, _)) in metrics.iter() {
+                for metric in exposed {
                     collector.encode_metric(
                         self.default_namespace.as_deref(),
                         &self.buckets,
@@ -434,6 +602,15 @@ impl Handler {
                 self.bytes_sent.emit(ByteSize(body_size));
             }
 
+            (true, &Method::PUT | &Method::POST | &Method::DELETE, path)
+                if path.starts_with("/metrics/job/") =>
+            {
+                *response.status_mut() = match self.push_ttl {
+                    Some(ttl) => self.push(req, ttl, metrics).await,
+                    None => StatusCode::NOT_FOUND,
+                };
+            }
+
             (true, _, _) => {
                 *response.status_mut() = StatusCode::NOT_FOUND;
             }
@@ -441,6 +618,83 @@ impl Handler {
 
         response
     }
+
+    /// Handles a Pushgateway API request, merging the pushed metrics into the exposed ones.
+    async fn push(
+        &self,
+        req: Request<Body>,
+        ttl: Duration,
+        metrics: &RwLock<IndexMap<MetricRef, (Metric, MetricMetadata)>>,
+    ) -> StatusCode {
+        let group = match parse_grouping_key(req.uri().path()) {
+            Some(group) => Arc::new(group),
+            None => return StatusCode::BAD_REQUEST,
+        };
+        let method = req.method().clone();
+
+        let mut pushed = Vec::new();
+        if method != Method::DELETE {
+            let mut body = req.into_body();
+            if body.size_hint().lower() > MAX_PUSH_BODY_BYTES as u64 {
+                return StatusCode::PAYLOAD_TOO_LARGE;
+            }
+            let mut buffer = BytesMut::new();
+            while let Some(chunk) = body.data().await {
+                let chunk = match chunk {
+                    Ok(chunk) => chunk,
+                    Err(_) => return StatusCode::BAD_REQUEST,
+                };
+                if buffer.len() + chunk.len() > MAX_PUSH_BODY_BYTES {
+                    return StatusCode::PAYLOAD_TOO_LARGE;
+                }
+                buffer.extend_from_slice(&chunk);
+            }
+            let body = buffer.freeze();
+            let events = match parse_text(&String::from_utf8_lossy(&body)) {
+                Ok(events) => events,
+                Err(error) => {
+                    emit!(PrometheusPushParseError { error });
+                    return StatusCode::BAD_REQUEST;
+                }
+            };
+
+            for event in events {
+                let mut metric = event.into_metric();
+                for (label, value) in group.iter() {
+                    metric.replace_tag(label.clone(), value.clone());
+                }
+                if self.suppress_timestamp {
+                    metric = metric.with_timestamp(None);
+                }
+                if in_shard(self.shard, metric.series()) {
+                    pushed.push(metric);
+                }
+            }
+        }
+
+        let now = Instant::now();
+        let mut metrics = metrics.write().expect(LOCK_FAILED);
+
+        // Drop any pushed metrics that have expired along the way, since there may be no events
+        // coming in to do so.
+        metrics.retain(|_, (_, metadata)| metadata.group.is_none() || !metadata.has_expired(now));
+
+        if method == Method::POST {
+            let names = pushed.iter().map(Metric::name).collect::<HashSet<_>>();
+            metrics.retain(|metric_ref, (_, metadata)| {
+                !(metadata.is_in_group(&group) && names.contains(metric_ref.series.name().name()))
+            });
+        } else {
+            metrics.retain(|_, (_, metadata)| !metadata.is_in_group(&group));
+        }
+
+        for metric in pushed {
+            let metadata = MetricMetadata::new(ttl).with_group(Arc::clone(&group));
+            metrics.insert(MetricRef::from_metric(&metric), (metric, metadata));
+        }
+
+        StatusCode::OK
+    }
 }
 
 impl PrometheusExporter {
@@ -464,6 +718,13 @@ impl PrometheusExporter {
             buckets: self.config.buckets.clone().into(),
             quantiles: self.config.quantiles.clone().into(),
             auth: self.config.auth.clone(),
+            push_ttl: self
+                .config
+                .pushgateway
+                .enabled
+                .then_some(self.config.pushgateway.ttl_secs),
+            suppress_timestamp: self.config.suppress_timestamp,
+            shard: self.config.shard,
         };
 
         let span = Span::current();
@@ -476,15 +737,19 @@ impl PrometheusExporter {
 
             async move {
                 Ok::<_, Infallible>(service_fn(move |req| {
-                    span.in_scope(|| {
-                        let response = handler.handle(req, &metrics);
+                    let handler = handler.clone();
+                    let metrics = Arc::clone(&metrics);
+
+                    async move {
+                        let response = handler.handle(req, &metrics).await;
 
                         emit!(PrometheusServerRequestComplete {
                             status_code: response.status(),
                         });
 
-                        future::ok::<_, Infallible>(response)
-                    })
+                        Ok::<_, Infallible>(response)
+                    }
+                    .instrument(span.clone())
                 }))
             }
         });
@@ -559,6 +824,13 @@ impl StreamSink<Event> for PrometheusExporter {
             let mut metric = event.into_metric();
             let finalizers = metric.take_finalizers();
 
+            // Series of other shards are exposed by other instances, so there's no need to track
+            // them here.
+            if !in_shard(self.config.shard, metric.series()) {
+                finalizers.update_status(EventStatus::Delivered);
+                continue;
+            }
+
             if let Some(normalized) = normalizer.normalize(metric) {
                 let normalized = if self.config.suppress_timestamp {
                     normalized.with_timestamp(None)
@@ -614,7 +886,7 @@ mod tests {
             components::{run_and_assert_sink_compliance, SINK_TAGS},
             next_addr, random_string, trace_init,
         },
-        tls::MaybeTlsSettings,
+        tls::{MaybeTlsSettings, TlsConfig},
     };
 
     #[test]
@@ -634,6 +906,58 @@ mod tests {
         export_and_fetch_simple(Some(tls_config)).await;
     }
 
+    #[tokio::test]
+    async fn prometheus_tls_client_certificate_required() {
+        trace_init();
+
+        let address = next_addr();
+        let mut server_tls = TlsEnableableConfig::test_config();
+        server_tls.options.verify_certificate = Some(true);
+        let config = PrometheusExporterConfig {
+            address,
+            tls: Some(server_tls),
+            ..Default::default()
+        };
+        let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+        let sink_handle = tokio::spawn(sink.run(stream::pending()));
+
+        time::sleep(time::Duration::from_millis(100)).await;
+
+        let scrape = |options: TlsConfig| async move {
+            let tls = Some(TlsEnableableConfig {
+                enabled: Some(true),
+                options: TlsConfig {
+                    ca_file: Some(crate::tls::TEST_PEM_CA_PATH.into()),
+                    verify_hostname: Some(false),
+                    ..options
+                },
+            });
+            let request = Request::get(format!("https://{}/metrics", address))
+                .body(Body::empty())
+                .expect("Error creating request.");
+            HttpClient::new(
+                MaybeTlsSettings::from_config(&tls, false).unwrap(),
+                &ProxyConfig::default(),
+            )
+            .unwrap()
+            .send(request)
+            .await
+        };
+
+        assert!(scrape(TlsConfig::default()).await.is_err());
+
+        let response = scrape(TlsConfig {
+            crt_file: Some(crate::tls::TEST_PEM_CLIENT_CRT_PATH.into()),
+            key_file: Some(crate::tls::TEST_PEM_CLIENT_KEY_PATH.into()),
+            ..Default::default()
+        })
+        .await
+        .expect("Could not fetch query");
+        assert!(response.status().is_success());
+
+        sink_handle.abort();
+    }
+
     #[tokio::test]
     async fn prometheus_noauth() {
         let (name1, event1) = create_metric_gauge(None, 123.4);
@@ -986,6 +1310,142 @@ mod tests {
         )));
     }
 
+    #[test]
+    fn parses_grouping_key() {
+        assert_eq!(
+            parse_grouping_key("/metrics/job/backup/instance/db%2F1"),
+            Some(GroupingKey::from([
+                ("job".to_string(), "backup".to_string()),
+                ("instance".to_string(), "db/1".to_string()),
+            ]))
+        );
+        assert_eq!(parse_grouping_key("/metrics/job/"), None);
+        assert_eq!(parse_grouping_key("/metrics/job/backup/instance"), None);
+        assert_eq!(parse_grouping_key("/metrics"), None);
+    }
+
+    #[tokio::test]
+    async fn pushgateway_push_and_delete() {
+        trace_init();
+
+        let address = next_addr();
+        let config = PrometheusExporterConfig {
+            address,
+            pushgateway: PushgatewayConfig {
+                enabled: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<Event>();
+        let sink_handle = tokio::spawn(async move {
+            sink.run(tokio_stream::wrappers::UnboundedReceiverStream::new(rx))
+                .await
+                .unwrap()
+        });
+
+        time::sleep(time::Duration::from_millis(100)).await;
+
+        let client = HttpClient::new(None, &ProxyConfig::default()).unwrap();
+        let send = |method: Method, path: &str, body: Body| {
+            let request = Request::builder()
+                .method(method)
+                .uri(format!("http://{}{}", address, path))
+                .body(body)
+                .expect("Error creating request.");
+            client.send(request)
+        };
+        let scrape = || async {
+            let response = send(Method::GET, "/metrics", Body::empty()).await.unwrap();
+            let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+            String::from_utf8(body.to_vec()).unwrap()
+        };
+
+        let response = send(
+            Method::PUT,
+            "/metrics/job/backup/instance/db1",
+            Body::from("backup_duration_seconds 12.5\nbackup_size_bytes 1024\n"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = scrape().await;
+        assert!(body.contains(r#"backup_duration_seconds{instance="db1",job="backup"} 12.5"#));
+        assert!(body.contains(r#"backup_size_bytes{instance="db1",job="backup"} 1024"#));
+
+        // A `POST` only replaces the metrics with the same name.
+        send(
+            Method::POST,
+            "/metrics/job/backup/instance/db1",
+            Body::from("backup_duration_seconds 3\n"),
+        )
+        .await
+        .unwrap();
+
+        let body = scrape().await;
+        assert!(body.contains(r#"backup_duration_seconds{instance="db1",job="backup"} 3"#));
+        assert!(body.contains(r#"backup_size_bytes{instance="db1",job="backup"} 1024"#));
+
+        // Bodies too large to be buffered are rejected.
+        let response = send(
+            Method::PUT,
+            "/metrics/job/backup/instance/db1",
+            Body::from(vec![b'\n'; MAX_PUSH_BODY_BYTES + 1]),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        send(
+            Method::DELETE,
+            "/metrics/job/backup/instance/db1",
+            Body::empty(),
+        )
+        .await
+        .unwrap();
+
+        let body = scrape().await;
+        assert!(!body.contains("backup_"));
+
+        drop(tx);
+        sink_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn pushgateway_disabled() {
+        trace_init();
+
+        let address = next_addr();
+        let config = PrometheusExporterConfig {
+            address,
+            ..Default::default()
+        };
+        let (sink, _) = config.build(SinkContext::new_test()).await.unwrap();
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<Event>();
+        let sink_handle = tokio::spawn(async move {
+            sink.run(tokio_stream::wrappers::UnboundedReceiverStream::new(rx))
+                .await
+                .unwrap()
+        });
+
+        time::sleep(time::Duration::from_millis(100)).await;
+
+        let request = Request::put(format!("http://{}/metrics/job/backup", address))
+            .body(Body::from("backup_size_bytes 1024\n"))
+            .expect("Error creating request.");
+        let response = HttpClient::new(None, &ProxyConfig::default())
+            .unwrap()
+            .send(request)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        drop(tx);
+        sink_handle.await.unwrap();
+    }
+
     pub fn create_metric_gauge(name: Option<String>, value: f64) -> (String, Event) {
         create_metric(name, MetricValue::Gauge { value })
     }
@@ -1053,6 +1513,54 @@ mod tests {
         assert_eq!(expected_m2.0.value(), &expected_m2_value);
     }
 
+    #[tokio::test]
+    async fn sink_shards_series() {
+        let metrics = (0..32)
+            .map(|n| {
+                Metric::new(
+                    "sharded",
+                    MetricKind::Absolute,
+                    MetricValue::Counter { value: 1. },
+                )
+                .with_tags(Some(metric_tags!("instance" => n.to_string())))
+            })
+            .collect::<Vec<_>>();
+
+        let mut exposed = Vec::new();
+        for index in 0..2 {
+            let config = PrometheusExporterConfig {
+                address: next_addr(), // Not actually bound, just needed to fill config
+                shard: Some(ShardConfig { count: 2, index }),
+                ..Default::default()
+            };
+            let sink = PrometheusExporter::new(config);
+            let metrics_handle = Arc::clone(&sink.metrics);
+
+            let sink = VectorSink::from_event_streamsink(sink);
+            let input_events =
+                stream::iter(metrics.clone()).map(|metric| Event::Metric(metric).into());
+            sink.run(input_events).await.unwrap();
+
+            let metrics_after = metrics_handle.read().unwrap();
+            assert!(!metrics_after.is_empty());
+            exposed.push(metrics_after.keys().cloned().collect::<HashSet<_>>());
+        }
+
+        // Each series is exposed by exactly one of the shards.
+        assert!(exposed[0].is_disjoint(&exposed[1]));
+        assert_eq!(exposed[0].len() + exposed[1].len(), metrics.len());
+    }
+
+    #[tokio::test]
+    async fn sink_rejects_invalid_shard() {
+        let config = PrometheusExporterConfig {
+            address: next_addr(),
+            shard: Some(ShardConfig { count: 2, index: 2 }),
+            ..Default::default()
+        };
+        assert!(config.build(SinkContext::new_test()).await.is_err());
+    }
+
     #[tokio::test]
     async fn sink_distributions_as_histograms() {
         // When we get summary distributions, unless we've been configured to actually emit
@@ -4,21 +4,28 @@ use std::task;
 use aws_types::credentials::SharedCredentialsProvider;
 use aws_types::region::Region;
 use bytes::{Bytes, BytesMut};
-use futures::{future::BoxFuture, stream, FutureExt, SinkExt};
-use http::{Request, Uri};
+use derivative::Derivative;
+use futures::{future::BoxFuture, stream, FutureExt, SinkExt, StreamExt};
+use http::{Request, StatusCode, Uri};
+use prometheus_parser::proto;
 use prost::Message;
 use snafu::{ResultExt, Snafu};
 use tower::Service;
 use vector_config::configurable_component;
 use vector_core::ByteSizeOf;
 
-use super::collector::{self, MetricCollector as _};
+use super::{
+    aggregation::{AggregatingSink, AggregationConfig},
+    collector::{self, MetricCollector as _},
+};
 use crate::{
     aws::RegionOrEndpoint,
     config::{self, AcknowledgementsConfig, Input, SinkConfig},
     event::{Event, Metric},
     http::{Auth, HttpClient},
-    internal_events::{EndpointBytesSent, TemplateRenderingError},
+    internal_events::{
+        EndpointBytesSent, PrometheusRemoteWriteSeriesRejected, TemplateRenderingError,
+    },
     sinks::{
         self,
         prometheus::PrometheusRemoteWriteAuth,
@@ -43,6 +50,9 @@ impl SinkBatchSettings for PrometheusRemoteWriteDefaultBatchSettings {
     const TIMEOUT_SECS: f64 = 1.0;
 }
 
+/// The number of requests sent concurrently when a rejected request is split by series.
+const SPLIT_REQUEST_CONCURRENCY: usize = 16;
+
 #[derive(Debug, Snafu)]
 enum Errors {
     #[snafu(display(r#"Prometheus remote_write sink cannot accept "set" metrics"#))]
@@ -53,7 +63,8 @@ enum Errors {
 
 /// Configuration for the `prometheus_remote_write` sink.
 #[configurable_component(sink("prometheus_remote_write"))]
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
 #[serde(deny_unknown_fields)]
 pub struct RemoteWriteConfig {
     /// The endpoint to send data to.
@@ -97,6 +108,37 @@ pub struct RemoteWriteConfig {
     #[serde(default)]
     pub tenant_id: Option<Template>,
 
+    /// Whether or not to guard against out-of-order samples.
+    ///
+    /// When enabled, the samples of each series are sorted by timestamp before being sent. In
+    /// addition, as receivers such as Cortex or Mimir reject a whole request if any of its series
+    /// contains an out-of-order sample, a request rejected with `400 Bad Request` is split and
+    /// each of its series is sent on its own, so that only the offending series are dropped. If any
+    /// of those series fail with a retriable error, the whole request is retried. Samples that were
+    /// already accepted are sent again, which receivers accept as duplicates.
+    #[serde(default)]
+    pub out_of_order_handling: bool,
+
+    /// Whether or not to send the metadata of metrics.
+    ///
+    /// When enabled, each request includes a `TYPE` and `HELP` record for each metric family in
+    /// it. Receivers that don't support metadata can reject such requests.
+    #[serde(default = "crate::serde::default_true")]
+    #[derivative(Default(value = "true"))]
+    pub send_metadata: bool,
+
+    /// Whether or not to send the exemplars of histograms.
+    ///
+    /// Exemplars are read from the `exemplars` array of the metadata of absolute histogram metrics,
+    /// each being an object with a numeric `value`, and optionally a `timestamp` and an object of
+    /// `labels`, such as a `trace_id`. Each exemplar is attached to the series of the bucket its
+    /// value falls in.
+    #[serde(default)]
+    pub send_exemplars: bool,
+
+    #[configurable(derived)]
+    pub aggregation: Option<AggregationConfig>,
+
     #[configurable(derived)]
     pub tls: Option<TlsConfig>,
 
@@ -179,6 +221,9 @@ impl SinkConfig for RemoteWriteConfig {
             client,
             buckets,
             quantiles,
+            out_of_order_handling: self.out_of_order_handling,
+            send_metadata: self.send_metadata,
+            send_exemplars: self.send_exemplars,
             http_request_builder,
         };
 
@@ -215,7 +260,14 @@ impl SinkConfig for RemoteWriteConfig {
                 )
         };
 
-        Ok((sinks::VectorSink::from_event_sink(sink), healthcheck))
+        let sink = match self.aggregation {
+            Some(aggregation) => {
+                sinks::VectorSink::from_event_streamsink(AggregatingSink::new(aggregation, sink))
+            }
+            None => sinks::VectorSink::from_event_sink(sink),
+        };
+
+        Ok((sink, healthcheck))
     }
 
     fn input(&self) -> Input {
@@ -263,11 +315,14 @@ struct RemoteWriteService {
     client: HttpClient,
     buckets: Vec<f64>,
     quantiles: Vec<f64>,
+    out_of_order_handling: bool,
+    send_metadata: bool,
+    send_exemplars: bool,
     http_request_builder: Arc<HttpRequestBuilder>,
 }
 
 impl RemoteWriteService {
-    fn encode_events(&self, metrics: Vec<Metric>) -> Bytes {
+    fn encode_events(&self, metrics: Vec<Metric>) -> proto::WriteRequest {
         let mut time_series = collector::TimeSeries::new();
         for metric in metrics {
             time_series.encode_metric(
@@ -276,15 +331,131 @@ impl RemoteWriteService {
                 &self.quantiles,
                 &metric,
             );
+            if self.send_exemplars {
+                time_series.encode_exemplars(
+                    self.default_namespace.as_deref(),
+                    &self.buckets,
+                    &metric,
+                );
+            }
+        }
+        let mut request = time_series.finish();
+
+        if !self.send_metadata {
+            request.metadata.clear();
+        }
+
+        if self.out_of_order_handling {
+            sort_samples(&mut request);
         }
-        let request = time_series.finish();
 
-        let mut out = BytesMut::with_capacity(request.encoded_len());
-        request.encode(&mut out).expect("Out of memory");
-        out.freeze()
+        request
+    }
+}
+
+fn sort_samples(request: &mut proto::WriteRequest) {
+    for series in &mut request.timeseries {
+        series.samples.sort_by_key(|sample| sample.timestamp);
     }
 }
 
+fn encode_request(request: &proto::WriteRequest) -> Vec<u8> {
+    let mut out = BytesMut::with_capacity(request.encoded_len());
+    request.encode(&mut out).expect("Out of memory");
+    snap_block(out.freeze())
+}
+
+/// Splits a write request into one request per series. The metadata is only sent along with the
+/// first series.
+fn split_by_series(request: proto::WriteRequest) -> Vec<proto::WriteRequest> {
+    let mut metadata = Some(request.metadata);
+    request
+        .timeseries
+        .into_iter()
+        .map(|series| proto::WriteRequest {
+            timeseries: vec![series],
+            metadata: metadata.take().unwrap_or_default(),
+        })
+        .collect()
+}
+
+fn is_retriable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+async fn send_request(
+    client: &HttpClient,
+    request_builder: &HttpRequestBuilder,
+    body: Vec<u8>,
+    tenant_id: Option<String>,
+) -> crate::Result<http::Response<Bytes>> {
+    let request = request_builder
+        .build_request(http::Method::POST, body, tenant_id)
+        .await?;
+
+    let (protocol, endpoint) = uri::protocol_endpoint(request.uri().clone());
+
+    let response = client.send(request).await?;
+    let (parts, body) = response.into_parts();
+    let body = hyper::body::to_bytes(body).await?;
+
+    emit!(EndpointBytesSent {
+        byte_size: body.len(),
+        protocol: &protocol,
+        endpoint: &endpoint
+    });
+
+    Ok(hyper::Response::from_parts(parts, body))
+}
+
+/// Sends each series of a rejected request on its own, and picks the response to report for the
+/// whole request: a retriable failure if there is one, so the request is retried, otherwise a
+/// success if any series was accepted.
+async fn send_split_request(
+    client: HttpClient,
+    request_builder: Arc<HttpRequestBuilder>,
+    request: proto::WriteRequest,
+    tenant_id: Option<String>,
+    rejected: http::Response<Bytes>,
+) -> crate::Result<http::Response<Bytes>> {
+    let responses = stream::iter(split_by_series(request))
+        .map(|request| {
+            let client = &client;
+            let request_builder = &request_builder;
+            let tenant_id = tenant_id.clone();
+            async move {
+                let body = encode_request(&request);
+                send_request(client, request_builder, body, tenant_id)
+                    .await
+                    .map(|response| (request, response))
+            }
+        })
+        .buffer_unordered(SPLIT_REQUEST_CONCURRENCY)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<crate::Result<Vec<_>>>()?;
+
+    let mut accepted = None;
+    let mut retriable = None;
+    for (request, response) in responses {
+        let status = response.status();
+        if status.is_success() {
+            accepted.get_or_insert(response);
+        } else if is_retriable(status) {
+            retriable.get_or_insert(response);
+        } else {
+            emit!(PrometheusRemoteWriteSeriesRejected {
+                status,
+                labels: &request.timeseries[0].labels,
+                body: &String::from_utf8_lossy(response.body()),
+            });
+        }
+    }
+
+    Ok(retriable.or(accepted).unwrap_or(rejected))
+}
+
 impl Service<PartitionInnerBuffer<Vec<Metric>, PartitionKey>> for RemoteWriteService {
     type Response = http::Response<Bytes>;
     type Error = crate::Error;
@@ -298,30 +469,27 @@ impl Service<PartitionInnerBuffer<Vec<Metric>, PartitionKey>> for RemoteWriteSer
     // Emission of internal events for errors and dropped events is handled upstream by the caller.
     fn call(&mut self, buffer: PartitionInnerBuffer<Vec<Metric>, PartitionKey>) -> Self::Future {
         let (events, key) = buffer.into_parts();
-        let body = self.encode_events(events);
-        let body = snap_block(body);
+        let request = self.encode_events(events);
+        let body = encode_request(&request);
 
         let client = self.client.clone();
         let request_builder = Arc::clone(&self.http_request_builder);
+        let out_of_order_handling = self.out_of_order_handling;
 
         Box::pin(async move {
-            let request = request_builder
-                .build_request(http::Method::POST, body, key.tenant_id)
-                .await?;
-
-            let (protocol, endpoint) = uri::protocol_endpoint(request.uri().clone());
-
-            let response = client.send(request).await?;
-            let (parts, body) = response.into_parts();
-            let body = hyper::body::to_bytes(body).await?;
-
-            emit!(EndpointBytesSent {
-                byte_size: body.len(),
-                protocol: &protocol,
-                endpoint: &endpoint
-            });
-
-            Ok(hyper::Response::from_parts(parts, body))
+            let response =
+                send_request(&client, &request_builder, body, key.tenant_id.clone()).await?;
+
+            // The receiver rejects the whole request if any of its series is invalid, for
+            // example because of an out-of-order sample, so split it up to only drop those.
+            if out_of_order_handling
+                && response.status() == StatusCode::BAD_REQUEST
+                && request.timeseries.len() > 1
+            {
+                send_split_request(client, request_builder, request, key.tenant_id, response).await
+            } else {
+                Ok(response)
+            }
         })
     }
 }
@@ -383,6 +551,8 @@ async fn sign_request(
 
 #[cfg(test)]
 mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
     use futures::StreamExt;
     use http::HeaderMap;
     use indoc::indoc;
@@ -393,7 +563,7 @@ mod tests {
     use crate::{
         config::SinkContext,
         event::{MetricKind, MetricValue},
-        sinks::util::test::build_test_server,
+        sinks::util::test::build_test_server_generic,
         test_util::{
             self,
             components::{assert_sink_compliance, HTTP_SINK_TAGS},
@@ -434,6 +604,20 @@ mod tests {
         assert_eq!(req.metadata[0].metric_family_name, "gauge-2");
     }
 
+    #[tokio::test]
+    async fn sends_request_without_metadata() {
+        let outputs = send_request(
+            "send_metadata = false",
+            vec![create_event("gauge-2".into(), 32.0)],
+        )
+        .await;
+        assert_eq!(outputs.len(), 1);
+        let (_headers, req) = &outputs[0];
+
+        assert_eq!(req.timeseries.len(), 1);
+        assert!(req.metadata.is_empty());
+    }
+
     #[tokio::test]
     async fn sends_authenticated_request() {
         let outputs = send_request(
@@ -546,13 +730,86 @@ mod tests {
         check_output(2, "counter-1", 26.0);
     }
 
+    #[test]
+    fn sorts_samples_by_timestamp() {
+        let sample = |timestamp| proto::Sample {
+            value: timestamp as f64,
+            timestamp,
+        };
+        let mut request = proto::WriteRequest {
+            timeseries: vec![proto::TimeSeries {
+                labels: labels!("__name__" => "gauge-1"),
+                samples: vec![sample(3), sample(1), sample(2)],
+                exemplars: vec![],
+            }],
+            metadata: vec![],
+        };
+
+        sort_samples(&mut request);
+
+        let timestamps = request.timeseries[0]
+            .samples
+            .iter()
+            .map(|sample| sample.timestamp)
+            .collect::<Vec<_>>();
+        assert_eq!(timestamps, vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn splits_rejected_request_by_series() {
+        // Reject the first request, which contains both series, and accept all others.
+        let requests = Arc::new(AtomicUsize::new(0));
+        let outputs = send_request_with_responder(
+            "out_of_order_handling = true",
+            vec![
+                create_event("gauge-1".into(), 1.0),
+                create_event("gauge-2".into(), 2.0),
+            ],
+            move || {
+                let status = if requests.fetch_add(1, Ordering::SeqCst) == 0 {
+                    StatusCode::BAD_REQUEST
+                } else {
+                    StatusCode::OK
+                };
+                hyper::Response::builder()
+                    .status(status)
+                    .body(hyper::Body::empty())
+                    .unwrap()
+            },
+        )
+        .await;
+
+        assert_eq!(outputs.len(), 2);
+        for (_, req) in &outputs {
+            assert_eq!(req.timeseries.len(), 1);
+        }
+        let metadata = outputs
+            .iter()
+            .map(|(_, req)| req.metadata.len())
+            .sum::<usize>();
+        assert_eq!(metadata, 2);
+    }
+
     async fn send_request(
         config: &str,
         events: Vec<Event>,
+    ) -> Vec<(HeaderMap, proto::WriteRequest)> {
+        send_request_with_responder(
+            config,
+            events,
+            || hyper::Response::new(hyper::Body::empty()),
+        )
+        .await
+    }
+
+    async fn send_request_with_responder(
+        config: &str,
+        events: Vec<Event>,
+        responder: impl Fn() -> hyper::Response<hyper::Body> + Clone + Send + Sync + 'static,
     ) -> Vec<(HeaderMap, proto::WriteRequest)> {
         assert_sink_compliance(&HTTP_SINK_TAGS, async {
             let addr = test_util::next_addr();
-            let (rx, trigger, server) = build_test_server(addr);
+            let (rx, trigger, server) = build_test_server_generic(addr, responder);
             tokio::spawn(server);
 
             let config = format!("endpoint = \"http://{}/write\"\n{}", addr, config);
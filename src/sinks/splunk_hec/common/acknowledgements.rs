@@ -73,6 +73,15 @@ pub struct HecAckStatusResponse {
     pub acks: HashMap<u64, bool>,
 }
 
+/// An acknowledgement ID to query the status of, along with the endpoint it was issued by.
+pub struct PendingAck {
+    pub ack_id: u64,
+    /// The endpoint the request was sent to, if it was chosen from an endpoint pool. Ack IDs are
+    /// only known to the indexer which issued them.
+    pub endpoint: Option<Arc<str>>,
+    pub status_tx: Sender<EventStatus>,
+}
+
 #[derive(Debug)]
 pub enum HecAckApiError {
     ClientBuildRequest,
@@ -86,6 +95,7 @@ struct HecAckClient {
     retry_limit: u8,
     client: HttpClient,
     http_request_builder: Arc<HttpRequestBuilder>,
+    endpoint: Option<Arc<str>>,
 }
 
 impl HecAckClient {
@@ -93,12 +103,14 @@ impl HecAckClient {
         retry_limit: u8,
         client: HttpClient,
         http_request_builder: Arc<HttpRequestBuilder>,
+        endpoint: Option<Arc<str>>,
     ) -> Self {
         Self {
             acks: HashMap::new(),
             retry_limit,
             client,
             http_request_builder,
+            endpoint,
         }
     }
 
@@ -218,6 +230,7 @@ impl HecAckClient {
             .http_request_builder
             .build_request(
                 request_body_bytes,
+                self.endpoint.as_deref(),
                 "/services/collector/ack",
                 None,
                 MetadataFields::default(),
@@ -247,7 +260,7 @@ impl HecAckClient {
 }
 
 pub async fn run_acknowledgements(
-    mut receiver: Receiver<(u64, Sender<EventStatus>)>,
+    mut receiver: Receiver<PendingAck>,
     client: HttpClient,
     http_request_builder: Arc<HttpRequestBuilder>,
     indexer_acknowledgements: HecClientAcknowledgementsConfig,
@@ -255,21 +268,28 @@ pub async fn run_acknowledgements(
     let mut interval = tokio::time::interval(Duration::from_secs(
         indexer_acknowledgements.query_interval.get() as u64,
     ));
-    let mut ack_client = HecAckClient::new(
-        indexer_acknowledgements.retry_limit.get(),
-        client,
-        http_request_builder,
-    );
+    // Acks are queried from the endpoint which issued them, one client per endpoint.
+    let mut ack_clients: HashMap<Option<Arc<str>>, HecAckClient> = HashMap::new();
 
     loop {
         tokio::select! {
             _ = interval.tick() => {
-                ack_client.run().await;
+                for ack_client in ack_clients.values_mut() {
+                    ack_client.run().await;
+                }
             },
             ack_info = receiver.recv() => {
                 match ack_info {
-                    Some((ack_id, tx)) => {
-                        ack_client.add(ack_id, tx);
+                    Some(PendingAck { ack_id, endpoint, status_tx }) => {
+                        ack_clients
+                            .entry(endpoint.clone())
+                            .or_insert_with(|| HecAckClient::new(
+                                indexer_acknowledgements.retry_limit.get(),
+                                client.clone(),
+                                Arc::clone(&http_request_builder),
+                                endpoint,
+                            ))
+                            .add(ack_id, status_tx);
                         debug!(message = "Stored ack id.", ?ack_id);
                     },
                     None => break,
@@ -306,7 +326,7 @@ mod tests {
             String::from(""),
             Compression::default(),
         );
-        HecAckClient::new(retry_limit, client, Arc::new(http_request_builder))
+        HecAckClient::new(retry_limit, client, Arc::new(http_request_builder), None)
     }
 
     fn populate_ack_client(
@@ -0,0 +1,415 @@
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, Weak,
+    },
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use bytes::Bytes;
+use futures_util::future::{join_all, BoxFuture};
+use tower::Service;
+
+use super::request::HecRequest;
+use crate::internal_events::{
+    SplunkEndpointActive, SplunkEndpointHealthCheckFailed, SplunkEndpointUnhealthy,
+};
+
+/// A single endpoint participating in an [`EndpointPool`].
+#[derive(Clone, Debug)]
+pub struct PoolEndpoint {
+    pub endpoint: String,
+    pub weight: u32,
+    pub priority: u8,
+}
+
+/// The endpoints and failover behavior an [`EndpointPool`] is built from.
+#[derive(Clone, Debug)]
+pub struct EndpointPoolSettings {
+    pub endpoints: Vec<PoolEndpoint>,
+    pub retry_after: Duration,
+    /// The interval on which each endpoint is health checked, if at all.
+    pub health_check_interval: Option<Duration>,
+}
+
+#[derive(Debug)]
+struct EndpointState {
+    endpoint: Arc<str>,
+    weight: u32,
+    priority: u8,
+    unhealthy_until: Option<Instant>,
+    active: bool,
+}
+
+/// A set of endpoints requests are spread over, with client-side failover.
+///
+/// Requests go to the healthy endpoints sharing the lowest priority value, weighted by their
+/// `weight`. An endpoint that fails a request is skipped until `retry_after` has passed, at which
+/// point it becomes eligible again. If every endpoint is unhealthy, the whole pool is used as if
+/// it were healthy rather than refusing to send.
+#[derive(Debug)]
+pub struct EndpointPool {
+    states: Mutex<Vec<EndpointState>>,
+    retry_after: Duration,
+    counter: AtomicUsize,
+}
+
+impl EndpointPool {
+    /// Creates a pool from `settings`, which must hold at least one endpoint, each with a
+    /// non-zero weight.
+    pub fn new(settings: &EndpointPoolSettings) -> Self {
+        let states = settings
+            .endpoints
+            .iter()
+            .map(|endpoint| EndpointState {
+                endpoint: endpoint.endpoint.as_str().into(),
+                weight: endpoint.weight,
+                priority: endpoint.priority,
+                unhealthy_until: None,
+                active: false,
+            })
+            .collect();
+
+        Self {
+            states: Mutex::new(states),
+            retry_after: settings.retry_after,
+            counter: AtomicUsize::new(0),
+        }
+    }
+
+    /// Selects the endpoint the next request should be sent to.
+    ///
+    /// Returns the index of the endpoint, to be passed back when reporting the outcome, along
+    /// with its base URL.
+    pub fn select(&self) -> (usize, Arc<str>) {
+        let now = Instant::now();
+        let mut states = self.states.lock().expect("endpoint pool lock poisoned");
+
+        let is_healthy = |state: &EndpointState| {
+            state
+                .unhealthy_until
+                .map_or(true, |unhealthy_until| unhealthy_until <= now)
+        };
+        let any_healthy = states.iter().any(is_healthy);
+        let eligible = |state: &EndpointState| !any_healthy || is_healthy(state);
+
+        let priority = states
+            .iter()
+            .filter(|state| eligible(state))
+            .map(|state| state.priority)
+            .min()
+            .expect("endpoint pool must not be empty");
+
+        for state in states.iter_mut() {
+            let active = eligible(state) && state.priority == priority;
+            if active != state.active {
+                state.active = active;
+                emit!(SplunkEndpointActive {
+                    endpoint: &state.endpoint,
+                    active,
+                });
+            }
+        }
+
+        let total_weight: usize = states
+            .iter()
+            .filter(|state| state.active)
+            .map(|state| state.weight as usize)
+            .sum();
+        let mut slot = self.counter.fetch_add(1, Ordering::Relaxed) % total_weight;
+
+        for (index, state) in states.iter().enumerate().filter(|(_, state)| state.active) {
+            if slot < state.weight as usize {
+                return (index, Arc::clone(&state.endpoint));
+            }
+            slot -= state.weight as usize;
+        }
+
+        unreachable!("slot is always within the total weight of active endpoints")
+    }
+
+    /// The base URLs of the endpoints, in the order of their indexes.
+    pub fn endpoints(&self) -> Vec<Arc<str>> {
+        let states = self.states.lock().expect("endpoint pool lock poisoned");
+        states
+            .iter()
+            .map(|state| Arc::clone(&state.endpoint))
+            .collect()
+    }
+
+    /// Records a successful request against the endpoint at `index`.
+    pub fn report_success(&self, index: usize) {
+        let mut states = self.states.lock().expect("endpoint pool lock poisoned");
+        states[index].unhealthy_until = None;
+    }
+
+    /// Records a failed request against the endpoint at `index`, taking it out of rotation for a
+    /// while.
+    pub fn report_failure(&self, index: usize) {
+        let now = Instant::now();
+        let mut states = self.states.lock().expect("endpoint pool lock poisoned");
+        let state = &mut states[index];
+        if state
+            .unhealthy_until
+            .map_or(true, |unhealthy_until| unhealthy_until <= now)
+        {
+            emit!(SplunkEndpointUnhealthy {
+                endpoint: &state.endpoint,
+                retry_after_secs: self.retry_after.as_secs(),
+            });
+        }
+        state.unhealthy_until = Some(now + self.retry_after);
+    }
+}
+
+/// Health checks each of `endpoints`, succeeding as long as any of them passes, since requests
+/// fail over to it.
+pub async fn check_any_healthy<F, Fut>(endpoints: &[PoolEndpoint], check: F) -> crate::Result<()>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = crate::Result<()>>,
+{
+    let results = join_all(
+        endpoints
+            .iter()
+            .map(|endpoint| check(endpoint.endpoint.clone())),
+    )
+    .await;
+    let mut first_error = None;
+    for (endpoint, result) in endpoints.iter().zip(results) {
+        match result {
+            Ok(()) => return Ok(()),
+            Err(error) => {
+                emit!(SplunkEndpointHealthCheckFailed {
+                    endpoint: &endpoint.endpoint,
+                    error: &error,
+                });
+                first_error.get_or_insert(error);
+            }
+        }
+    }
+    Err(first_error.expect("endpoint pool must not be empty"))
+}
+
+/// Health checks every endpoint of `pool` on `interval`, feeding the outcome back into the pool.
+///
+/// An endpoint failing its health check is taken out of rotation as if it had failed a request,
+/// before any request is sent to it, and put back as soon as it passes one. Checks stop once the
+/// pool is dropped along with the sink.
+pub async fn run_health_checks<F, Fut>(pool: Weak<EndpointPool>, interval: Duration, check: F)
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = crate::Result<()>>,
+{
+    let mut ticks = tokio::time::interval(interval);
+    loop {
+        ticks.tick().await;
+        let endpoints = match pool.upgrade() {
+            Some(pool) => pool.endpoints(),
+            None => break,
+        };
+
+        let results = join_all(endpoints.iter().map(|endpoint| check(endpoint.to_string()))).await;
+
+        let pool = match pool.upgrade() {
+            Some(pool) => pool,
+            None => break,
+        };
+        for (index, (endpoint, result)) in endpoints.iter().zip(results).enumerate() {
+            match result {
+                Ok(()) => pool.report_success(index),
+                Err(error) => {
+                    emit!(SplunkEndpointHealthCheckFailed {
+                        endpoint,
+                        error: &error,
+                    });
+                    pool.report_failure(index);
+                }
+            }
+        }
+    }
+}
+
+/// The base URL of the endpoint a response was received from, when it was chosen from an
+/// [`EndpointPool`].
+///
+/// Inserted into the extensions of the responses, so that follow-up requests, such as indexer
+/// acknowledgement queries, are sent to the same endpoint.
+#[derive(Clone, Debug)]
+pub struct SelectedEndpoint(pub Arc<str>);
+
+/// Routes each request to an endpoint chosen from an [`EndpointPool`], feeding the outcome back
+/// into the pool.
+///
+/// Without a pool, requests are passed through untouched and go to the configured endpoint.
+#[derive(Clone)]
+pub struct FailoverService<S> {
+    inner: S,
+    pool: Option<Arc<EndpointPool>>,
+}
+
+impl<S> FailoverService<S> {
+    pub const fn new(inner: S, pool: Option<Arc<EndpointPool>>) -> Self {
+        Self { inner, pool }
+    }
+}
+
+impl<S> Service<HecRequest> for FailoverService<S>
+where
+    S: Service<HecRequest, Response = http::Response<Bytes>, Error = crate::Error>,
+    S::Future: Send + 'static,
+{
+    type Response = http::Response<Bytes>;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: HecRequest) -> Self::Future {
+        let pool = match &self.pool {
+            Some(pool) => Arc::clone(pool),
+            None => return Box::pin(self.inner.call(req)),
+        };
+
+        let (index, endpoint) = pool.select();
+        req.endpoint = Some(Arc::clone(&endpoint));
+        let response = self.inner.call(req);
+
+        Box::pin(async move {
+            let mut response = response.await;
+            match &mut response {
+                Ok(response) => {
+                    if response.status().is_server_error() {
+                        pool.report_failure(index);
+                    } else {
+                        pool.report_success(index);
+                    }
+                    response.extensions_mut().insert(SelectedEndpoint(endpoint));
+                }
+                Err(_) => pool.report_failure(index),
+            }
+            response
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use super::{
+        check_any_healthy, run_health_checks, EndpointPool, EndpointPoolSettings, PoolEndpoint,
+    };
+
+    fn pool(endpoints: Vec<PoolEndpoint>) -> EndpointPool {
+        EndpointPool::new(&EndpointPoolSettings {
+            endpoints,
+            retry_after: Duration::from_secs(30),
+            health_check_interval: None,
+        })
+    }
+
+    fn endpoint(endpoint: &str, weight: u32, priority: u8) -> PoolEndpoint {
+        PoolEndpoint {
+            endpoint: endpoint.to_string(),
+            weight,
+            priority,
+        }
+    }
+
+    #[test]
+    fn selects_by_weight_within_lowest_priority() {
+        let pool = pool(vec![
+            endpoint("http://a", 3, 0),
+            endpoint("http://b", 1, 0),
+            endpoint("http://dr", 1, 1),
+        ]);
+
+        let selected = (0..8).map(|_| pool.select().0).collect::<Vec<_>>();
+        assert_eq!(selected.iter().filter(|index| **index == 0).count(), 6);
+        assert_eq!(selected.iter().filter(|index| **index == 1).count(), 2);
+        assert!(!selected.contains(&2));
+    }
+
+    #[test]
+    fn fails_over_and_back() {
+        let pool = pool(vec![
+            endpoint("http://primary", 1, 0),
+            endpoint("http://dr", 1, 1),
+        ]);
+
+        assert_eq!(&*pool.select().1, "http://primary");
+
+        pool.report_failure(0);
+        assert_eq!(&*pool.select().1, "http://dr");
+
+        pool.report_success(0);
+        assert_eq!(&*pool.select().1, "http://primary");
+    }
+
+    #[test]
+    fn uses_all_endpoints_when_none_are_healthy() {
+        let pool = pool(vec![
+            endpoint("http://primary", 1, 0),
+            endpoint("http://dr", 1, 1),
+        ]);
+
+        pool.report_failure(0);
+        pool.report_failure(1);
+        assert_eq!(&*pool.select().1, "http://primary");
+    }
+
+    #[tokio::test]
+    async fn fails_over_on_health_check() {
+        let pool = Arc::new(pool(vec![
+            endpoint("http://primary", 1, 0),
+            endpoint("http://dr", 1, 1),
+        ]));
+
+        // Only the first round of checks runs before the next tick, an hour later.
+        let checks = run_health_checks(
+            Arc::downgrade(&pool),
+            Duration::from_secs(3600),
+            |endpoint| async move {
+                if endpoint == "http://primary" {
+                    Err("unavailable".into())
+                } else {
+                    Ok(())
+                }
+            },
+        );
+        assert!(tokio::time::timeout(Duration::from_millis(100), checks)
+            .await
+            .is_err());
+
+        assert_eq!(&*pool.select().1, "http://dr");
+    }
+
+    #[tokio::test]
+    async fn healthy_while_any_endpoint_is() {
+        let endpoints = vec![
+            endpoint("http://primary", 1, 0),
+            endpoint("http://dr", 1, 1),
+        ];
+        let check = |healthy: &'static str| {
+            move |endpoint: String| async move {
+                if endpoint == healthy {
+                    Ok(())
+                } else {
+                    Err("unavailable".into())
+                }
+            }
+        };
+
+        assert!(check_any_healthy(&endpoints, check("http://dr"))
+            .await
+            .is_ok());
+        assert!(check_any_healthy(&endpoints, check("http://other"))
+            .await
+            .is_err());
+    }
+}
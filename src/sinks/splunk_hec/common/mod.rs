@@ -1,4 +1,5 @@
 pub mod acknowledgements;
+pub mod failover;
 pub mod request;
 pub mod response;
 pub mod service;
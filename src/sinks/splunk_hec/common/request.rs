@@ -19,6 +19,8 @@ pub struct HecRequest {
     pub source: Option<String>,
     pub sourcetype: Option<String>,
     pub host: Option<String>,
+    // Overrides the configured endpoint when set, see `failover::FailoverService`.
+    pub endpoint: Option<Arc<str>>,
 }
 
 impl ByteSizeOf for HecRequest {
@@ -17,7 +17,8 @@ use vector_common::request_metadata::MetaDescriptive;
 use vector_core::event::EventStatus;
 
 use super::{
-    acknowledgements::{run_acknowledgements, HecClientAcknowledgementsConfig},
+    acknowledgements::{run_acknowledgements, HecClientAcknowledgementsConfig, PendingAck},
+    failover::SelectedEndpoint,
     EndpointTarget,
 };
 use crate::{
@@ -32,7 +33,7 @@ use crate::{
 
 pub struct HecService<S> {
     pub inner: S,
-    ack_finalizer_tx: Option<mpsc::Sender<(u64, oneshot::Sender<EventStatus>)>>,
+    ack_finalizer_tx: Option<mpsc::Sender<PendingAck>>,
     ack_slots: PollSemaphore,
     current_ack_slot: Option<OwnedSemaphorePermit>,
 }
@@ -119,6 +120,7 @@ where
 
         Box::pin(async move {
             let response = response.await.map_err(Into::into)?;
+            let endpoint = response.endpoint();
             let event_status = if response.is_successful() {
                 if let Some(ack_finalizer_tx) = ack_finalizer_tx {
                     let _ack_slot = ack_slot.expect("poll_ready not called before invoking call");
@@ -127,7 +129,12 @@ where
                         Ok(body) => {
                             if let Some(ack_id) = body.ack_id {
                                 let (tx, rx) = oneshot::channel();
-                                match ack_finalizer_tx.send((ack_id, tx)).await {
+                                let pending = PendingAck {
+                                    ack_id,
+                                    endpoint,
+                                    status_tx: tx,
+                                };
+                                match ack_finalizer_tx.send(pending).await {
                                     Ok(_) => rx.await.unwrap_or(EventStatus::Rejected),
                                     // If we cannot send ack ids to the ack client, fall back to default behavior
                                     Err(error) => {
@@ -169,12 +176,21 @@ where
 
 pub trait ResponseExt {
     fn body(&self) -> &Bytes;
+
+    /// The endpoint the response was received from, if it was chosen from an endpoint pool.
+    fn endpoint(&self) -> Option<Arc<str>>;
 }
 
 impl ResponseExt for http::Response<Bytes> {
     fn body(&self) -> &Bytes {
         self.body()
     }
+
+    fn endpoint(&self) -> Option<Arc<str>> {
+        self.extensions()
+            .get::<SelectedEndpoint>()
+            .map(|selected| Arc::clone(&selected.0))
+    }
 }
 
 pub struct HttpRequestBuilder {
@@ -215,11 +231,13 @@ impl HttpRequestBuilder {
     pub(super) fn build_request(
         &self,
         body: Bytes,
+        endpoint: Option<&str>,
         path: &str,
         passthrough_token: Option<Arc<str>>,
         metadata_fields: MetadataFields,
         auto_extract_timestamp: bool,
     ) -> Result<Request<Bytes>, crate::Error> {
+        let endpoint = endpoint.unwrap_or(self.endpoint.as_str());
         let uri = match self.endpoint_target {
             EndpointTarget::Raw => {
                 // `auto_extract_timestamp` doesn't apply to the raw endpoint since the raw endpoint
@@ -232,10 +250,10 @@ impl HttpRequestBuilder {
                 ]
                 .into_iter()
                 .filter_map(|(key, value)| value.map(|value| (key, value)));
-                build_uri(self.endpoint.as_str(), path, metadata).context(UriParseSnafu)?
+                build_uri(endpoint, path, metadata).context(UriParseSnafu)?
             }
             EndpointTarget::Event => build_uri(
-                self.endpoint.as_str(),
+                endpoint,
                 path,
                 if auto_extract_timestamp {
                     Some((super::AUTO_EXTRACT_TIMESTAMP_FIELD, "true".to_string()))
@@ -352,6 +370,7 @@ mod tests {
             source: None,
             sourcetype: None,
             host: None,
+            endpoint: None,
         }
     }
 
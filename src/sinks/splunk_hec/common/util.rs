@@ -61,6 +61,7 @@ pub fn build_http_batch_service(
             Box::pin(async move {
                 request_builder.build_request(
                     req.body,
+                    req.endpoint.as_deref(),
                     match endpoint_target {
                         EndpointTarget::Event => "/services/collector/event",
                         EndpointTarget::Raw => "/services/collector/raw",
@@ -268,6 +269,7 @@ mod tests {
         let request = http_request_builder
             .build_request(
                 events.clone(),
+                None,
                 "/services/collector/event",
                 None,
                 MetadataFields::default(),
@@ -311,6 +313,7 @@ mod tests {
         let request = http_request_builder
             .build_request(
                 events.clone(),
+                None,
                 "/services/collector/event",
                 None,
                 MetadataFields::default(),
@@ -357,6 +360,7 @@ mod tests {
         let err = http_request_builder
             .build_request(
                 events,
+                None,
                 "/services/collector/event",
                 None,
                 MetadataFields::default(),
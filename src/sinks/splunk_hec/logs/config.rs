@@ -15,7 +15,12 @@ use crate::{
     sinks::{
         splunk_hec::common::{
             acknowledgements::HecClientAcknowledgementsConfig,
-            build_healthcheck, build_http_batch_service, create_client, host_key,
+            build_healthcheck, build_http_batch_service, create_client,
+            failover::{
+                check_any_healthy, run_health_checks, EndpointPool, EndpointPoolSettings,
+                FailoverService,
+            },
+            host_key,
             service::{HecService, HttpRequestBuilder},
             timestamp_key, EndpointTarget, SplunkHecDefaultBatchSettings,
         },
@@ -95,11 +100,6 @@ pub struct HecLogsSinkConfig {
     #[serde(default)]
     pub acknowledgements: HecClientAcknowledgementsConfig,
 
-    // This settings is relevant only for the `humio_logs` sink and should be left as `None`
-    // everywhere else.
-    #[serde(skip)]
-    pub timestamp_nanos_key: Option<String>,
-
     /// Overrides the name of the log field used to grab the timestamp to send to Splunk HEC.
     ///
     /// By default, the [global `log_schema.timestamp_key` option][global_timestamp_key] is used.
@@ -125,6 +125,23 @@ const fn default_endpoint_target() -> EndpointTarget {
     EndpointTarget::Event
 }
 
+/// Settings that are only used by the `humio_logs` sink, which is built on top of this one, and
+/// can't be set on a `splunk_hec_logs` sink.
+#[derive(Clone, Debug, Default)]
+pub struct HecLogsSinkOptions {
+    /// The field the nanoseconds of the event timestamp are written to.
+    pub timestamp_nanos_key: Option<String>,
+
+    /// The endpoints to spread requests over, when multiple endpoints are configured.
+    pub endpoint_pool: Option<EndpointPoolSettings>,
+
+    /// Fields tried in order when an event has no `host_key` field.
+    pub host_key_fallbacks: Vec<String>,
+
+    /// Fields tried in order when an event has no `timestamp_key` field.
+    pub timestamp_key_fallbacks: Vec<String>,
+}
+
 impl GenerateConfig for HecLogsSinkConfig {
     fn generate_config() -> toml::Value {
         toml::Value::try_from(Self {
@@ -141,7 +158,6 @@ impl GenerateConfig for HecLogsSinkConfig {
             request: TowerRequestConfig::default(),
             tls: None,
             acknowledgements: Default::default(),
-            timestamp_nanos_key: None,
             timestamp_key: timestamp_key(),
             auto_extract_timestamp: None,
             endpoint_target: EndpointTarget::Event,
@@ -153,20 +169,7 @@ impl GenerateConfig for HecLogsSinkConfig {
 #[async_trait::async_trait]
 impl SinkConfig for HecLogsSinkConfig {
     async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
-        if self.auto_extract_timestamp.is_some() && self.endpoint_target == EndpointTarget::Raw {
-            return Err("`auto_extract_timestamp` cannot be set for the `raw` endpoint.".into());
-        }
-
-        let client = create_client(&self.tls, cx.proxy())?;
-        let healthcheck = build_healthcheck(
-            self.endpoint.clone(),
-            self.default_token.inner().to_owned(),
-            client.clone(),
-        )
-        .boxed();
-        let sink = self.build_processor(client, cx)?;
-
-        Ok((sink, healthcheck))
+        self.build_with_options(cx, &HecLogsSinkOptions::default())
     }
 
     fn input(&self) -> Input {
@@ -179,10 +182,42 @@ impl SinkConfig for HecLogsSinkConfig {
 }
 
 impl HecLogsSinkConfig {
+    /// Builds the sink along with the settings of the `humio_logs` sink.
+    pub fn build_with_options(
+        &self,
+        cx: SinkContext,
+        options: &HecLogsSinkOptions,
+    ) -> crate::Result<(VectorSink, Healthcheck)> {
+        if self.auto_extract_timestamp.is_some() && self.endpoint_target == EndpointTarget::Raw {
+            return Err("`auto_extract_timestamp` cannot be set for the `raw` endpoint.".into());
+        }
+
+        let client = create_client(&self.tls, cx.proxy())?;
+        let token = self.default_token.inner().to_owned();
+        let healthcheck = match &options.endpoint_pool {
+            Some(settings) => {
+                let endpoints = settings.endpoints.clone();
+                let client = client.clone();
+                async move {
+                    check_any_healthy(&endpoints, |endpoint| {
+                        build_healthcheck(endpoint, token.clone(), client.clone())
+                    })
+                    .await
+                }
+                .boxed()
+            }
+            None => build_healthcheck(self.endpoint.clone(), token, client.clone()).boxed(),
+        };
+        let sink = self.build_processor(client, cx, options)?;
+
+        Ok((sink, healthcheck))
+    }
+
     pub fn build_processor(
         &self,
         client: HttpClient,
         cx: SinkContext,
+        options: &HecLogsSinkOptions,
     ) -> crate::Result<VectorSink> {
         let ack_client = if self.acknowledgements.indexer_acknowledgements_enabled {
             Some(client.clone())
@@ -210,13 +245,35 @@ impl HecLogsSinkConfig {
             self.default_token.inner().to_owned(),
             self.compression,
         ));
+        let endpoint_pool = options
+            .endpoint_pool
+            .as_ref()
+            .map(|settings| Arc::new(EndpointPool::new(settings)));
+        if let (Some(pool), Some(interval)) = (
+            &endpoint_pool,
+            options
+                .endpoint_pool
+                .as_ref()
+                .and_then(|settings| settings.health_check_interval),
+        ) {
+            let token = self.default_token.inner().to_owned();
+            let client = client.clone();
+            tokio::spawn(run_health_checks(
+                Arc::downgrade(pool),
+                interval,
+                move |endpoint| build_healthcheck(endpoint, token.clone(), client.clone()),
+            ));
+        }
         let http_service = ServiceBuilder::new()
             .settings(request_settings, HttpRetryLogic)
-            .service(build_http_batch_service(
-                client,
-                Arc::clone(&http_request_builder),
-                self.endpoint_target,
-                self.auto_extract_timestamp.unwrap_or_default(),
+            .service(FailoverService::new(
+                build_http_batch_service(
+                    client,
+                    Arc::clone(&http_request_builder),
+                    self.endpoint_target,
+                    self.auto_extract_timestamp.unwrap_or_default(),
+                ),
+                endpoint_pool,
             ));
 
         let service = HecService::new(
@@ -238,8 +295,10 @@ impl HecLogsSinkConfig {
             index: self.index.clone(),
             indexed_fields: self.indexed_fields.clone(),
             host: self.host_key.clone(),
-            timestamp_nanos_key: self.timestamp_nanos_key.clone(),
+            host_fallbacks: options.host_key_fallbacks.clone(),
+            timestamp_nanos_key: options.timestamp_nanos_key.clone(),
             timestamp_key: self.timestamp_key.clone(),
+            timestamp_key_fallbacks: options.timestamp_key_fallbacks.clone(),
             endpoint_target: self.endpoint_target,
         };
 
@@ -117,7 +117,6 @@ async fn config(encoding: EncodingConfig, indexed_fields: Vec<String>) -> HecLog
         request: TowerRequestConfig::default(),
         tls: None,
         acknowledgements: Default::default(),
-        timestamp_nanos_key: None,
         timestamp_key: Default::default(),
         auto_extract_timestamp: None,
         endpoint_target: EndpointTarget::Event,
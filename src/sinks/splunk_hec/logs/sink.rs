@@ -40,8 +40,10 @@ pub struct HecLogsSink<S> {
     pub index: Option<Template>,
     pub indexed_fields: Vec<String>,
     pub host: String,
+    pub host_fallbacks: Vec<String>,
     pub timestamp_nanos_key: Option<String>,
     pub timestamp_key: String,
+    pub timestamp_key_fallbacks: Vec<String>,
     pub endpoint_target: EndpointTarget,
 }
 
@@ -51,8 +53,10 @@ pub struct HecLogData<'a> {
     pub index: Option<&'a Template>,
     pub indexed_fields: &'a [String],
     pub host_key: &'a str,
+    pub host_key_fallbacks: &'a [String],
     pub timestamp_nanos_key: Option<&'a String>,
     pub timestamp_key: &'a str,
+    pub timestamp_key_fallbacks: &'a [String],
     pub endpoint_target: EndpointTarget,
 }
 
@@ -72,8 +76,10 @@ where
             index: self.index.as_ref(),
             indexed_fields: self.indexed_fields.as_slice(),
             host_key: self.host.as_ref(),
+            host_key_fallbacks: self.host_fallbacks.as_slice(),
             timestamp_nanos_key: self.timestamp_nanos_key.as_ref(),
             timestamp_key: self.timestamp_key.as_ref(),
+            timestamp_key_fallbacks: self.timestamp_key_fallbacks.as_slice(),
             endpoint_target: self.endpoint_target,
         };
 
@@ -246,12 +252,17 @@ pub fn process_log(event: Event, data: &HecLogData) -> HecProcessedEvent {
         .index
         .and_then(|index| render_template_string(index, &log, INDEX_FIELD));
 
-    let host = log.get(data.host_key).cloned();
+    let host = first_present_key(&log, data.host_key, data.host_key_fallbacks)
+        .and_then(|key| log.get(key))
+        .cloned();
 
     let timestamp = if data.timestamp_key.is_empty() {
         None
     } else {
-        match log.remove(data.timestamp_key) {
+        let timestamp_key =
+            first_present_key(&log, data.timestamp_key, data.timestamp_key_fallbacks)
+                .unwrap_or(data.timestamp_key);
+        match log.remove(timestamp_key) {
             Some(Value::Timestamp(ts)) => {
                 // set nanos in log if valid timestamp in event and timestamp_nanos_key is configured
                 if let Some(key) = data.timestamp_nanos_key {
@@ -295,6 +306,13 @@ pub fn process_log(event: Event, data: &HecLogData) -> HecProcessedEvent {
     }
 }
 
+/// Returns the first of `key` and its `fallbacks` that is present in `log`.
+fn first_present_key<'a>(log: &LogEvent, key: &'a str, fallbacks: &'a [String]) -> Option<&'a str> {
+    std::iter::once(key)
+        .chain(fallbacks.iter().map(String::as_str))
+        .find(|&key| log.contains(key))
+}
+
 impl EventCount for HecProcessedEvent {
     fn event_count(&self) -> usize {
         // A HecProcessedEvent is mapped one-to-one with an event.
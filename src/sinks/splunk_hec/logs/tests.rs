@@ -81,9 +81,11 @@ fn get_processed_event_timestamp(
             source: source.as_ref(),
             index: index.as_ref(),
             host_key: "host_key",
+            host_key_fallbacks: &[],
             indexed_fields: indexed_fields.as_slice(),
             timestamp_nanos_key: timestamp_nanos_key.as_ref(),
             timestamp_key,
+            timestamp_key_fallbacks: &[],
             endpoint_target: EndpointTarget::Event,
         },
     )
@@ -202,7 +204,6 @@ async fn splunk_passthrough_token() {
         request: Default::default(),
         tls: None,
         acknowledgements: Default::default(),
-        timestamp_nanos_key: None,
         timestamp_key: log_schema().timestamp_key().into(),
         auto_extract_timestamp: None,
         endpoint_target: EndpointTarget::Event,
@@ -1,4 +1,8 @@
-use std::{collections::HashMap, fmt};
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 use chrono::Utc;
 use futures::{Stream, StreamExt};
@@ -27,6 +31,27 @@ const TEST_BUFFER_SIZE: usize = 100;
 
 const LAG_TIME_NAME: &str = "source_lag_time_seconds";
 
+/// Counts the taps correlating the versions of events output by successive components. Events are
+/// only assigned identifiers while there is at least one.
+static EVENT_IDS: AtomicUsize = AtomicUsize::new(0);
+
+/// Has sources assign identifiers to the events they send, for as long as the returned guard is
+/// held.
+#[must_use]
+pub fn enable_event_ids() -> EventIdsGuard {
+    EVENT_IDS.fetch_add(1, Ordering::Relaxed);
+    EventIdsGuard(())
+}
+
+/// Keeps events assigned identifiers while held. See [`enable_event_ids`].
+pub struct EventIdsGuard(());
+
+impl Drop for EventIdsGuard {
+    fn drop(&mut self) {
+        EVENT_IDS.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
 pub struct Builder {
     buf_size: usize,
     inner: Option<Inner>,
@@ -258,8 +283,9 @@ impl Inner {
         )
     }
 
-    async fn send(&mut self, events: EventArray) -> Result<(), ClosedError> {
+    async fn send(&mut self, mut events: EventArray) -> Result<(), ClosedError> {
         let reference = Utc::now().timestamp_millis();
+        assign_ids(&mut events);
         events
             .iter_events()
             .for_each(|event| self.emit_lag_time(event, reference));
@@ -293,7 +319,8 @@ impl Inner {
     {
         let reference = Utc::now().timestamp_millis();
         let events = events.into_iter().map(Into::into);
-        for events in array::events_into_arrays(events, Some(CHUNK_SIZE)) {
+        for mut events in array::events_into_arrays(events, Some(CHUNK_SIZE)) {
+            assign_ids(&mut events);
             events
                 .iter_events()
                 .for_each(|event| self.emit_lag_time(event, reference));
@@ -337,6 +364,16 @@ impl Inner {
     }
 }
 
+/// Assigns identifiers to the events while a tap asks for them, which the `tap` API uses to
+/// correlate the versions of an event output by successive components.
+fn assign_ids(events: &mut EventArray) {
+    if EVENT_IDS.load(Ordering::Relaxed) > 0 {
+        events
+            .iter_events_mut()
+            .for_each(|mut event| event.metadata_mut().assign_id());
+    }
+}
+
 fn get_timestamp_millis(value: &Value) -> Option<i64> {
     match value {
         Value::Timestamp(timestamp) => Some(timestamp.timestamp_millis()),
@@ -1,22 +1,64 @@
-use std::num::NonZeroUsize;
+use std::{
+    collections::HashSet,
+    num::{NonZeroU32, NonZeroUsize},
+};
 
-use codecs::decoding::{DeserializerConfig, FramingConfig};
+use codecs::{
+    decoding::{DeserializerConfig, FramingConfig},
+    BytesDeserializerConfig,
+};
+use futures::future;
 use lookup::owned_value_path;
-use value::Kind;
+use value::{kind::Collection, Kind};
 use vector_config::{configurable_component, NamedComponent};
-use vector_core::config::{LegacyKey, LogNamespace};
+use vector_core::{
+    config::{LegacyKey, LogNamespace},
+    schema::Definition,
+};
 
+use super::{
+    s3_notification::{S3NotificationConfig, S3NotificationHandler},
+    source::RawOutput,
+};
 use crate::aws::create_client;
 use crate::codecs::DecodingConfig;
-use crate::common::sqs::SqsClientBuilder;
+use crate::common::{s3::S3ClientBuilder, sqs::SqsClientBuilder};
 use crate::tls::TlsConfig;
 use crate::{
     aws::{auth::AwsAuthentication, region::RegionOrEndpoint},
-    config::{Output, SourceAcknowledgementsConfig, SourceConfig, SourceContext},
+    conditions::AnyCondition,
+    config::{DataType, Output, SourceAcknowledgementsConfig, SourceConfig, SourceContext},
     serde::{bool_or_struct, default_decoding, default_framing_message_based},
     sources::aws_sqs::source::SqsSource,
 };
 
+/// The name of the output the messages received are sent to as is, if `raw_output` is set.
+pub const RAW_OUTPUT: &str = "raw";
+
+/// The name of the output poison messages are sent to, if `poison_messages.action` is `route`.
+pub const ERRORS_OUTPUT: &str = "errors";
+
+/// Compression scheme for message bodies.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Derivative, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[derivative(Default)]
+pub enum Compression {
+    /// Automatically determine the compression scheme.
+    ///
+    /// Vector will determine the compression scheme of each message from its `content-encoding`
+    /// message attribute, which can be either `gzip` or `zstd`. Messages without that attribute are
+    /// not decompressed.
+    #[derivative(Default)]
+    Auto,
+    /// Uncompressed.
+    None,
+    /// GZIP.
+    Gzip,
+    /// ZSTD.
+    Zstd,
+}
+
 /// Configuration for the `aws_sqs` source.
 #[configurable_component(source("aws_sqs"))]
 #[derive(Clone, Debug, Derivative)]
@@ -31,8 +73,19 @@ pub struct AwsSqsConfig {
     pub auth: AwsAuthentication,
 
     /// The URL of the SQS queue to poll for messages.
+    ///
+    /// Can be omitted if `queues` is set.
+    #[serde(default)]
     pub queue_url: String,
 
+    /// Additional SQS queues to poll for messages.
+    ///
+    /// Each queue can override the region, endpoint, and authentication used to access it, in
+    /// which case it is polled with a separate client. This allows consuming queues from several
+    /// regions or accounts with a single source.
+    #[serde(default)]
+    pub queues: Vec<SqsQueueConfig>,
+
     /// How long to wait while polling the queue for new messages, in seconds.
     ///
     /// Generally should not be changed unless instructed to do so, as if messages are available, they will always be
@@ -61,7 +114,7 @@ pub struct AwsSqsConfig {
     #[derivative(Default(value = "default_true()"))]
     pub(super) delete_message: bool,
 
-    /// Number of concurrent tasks to create for polling the queue for messages.
+    /// Number of concurrent tasks to create for polling each queue for messages.
     ///
     /// Defaults to the number of available CPUs on the system.
     ///
@@ -71,6 +124,36 @@ pub struct AwsSqsConfig {
     /// fetching the messages than processing them.
     pub client_concurrency: Option<NonZeroUsize>,
 
+    /// The compression scheme of the message bodies.
+    ///
+    /// SQS message bodies must be text, so compressed bodies are expected to be base64-encoded.
+    /// Messages that fail to be decompressed are left in the queue, to be redelivered after the
+    /// visibility timeout or moved to a dead-letter queue by its redrive policy.
+    ///
+    /// When `s3_notification` is set, this is the compression scheme of the fetched objects
+    /// instead, which is determined from their metadata and key when set to `auto`. Notifications
+    /// themselves are only decompressed according to their `content-encoding` attribute.
+    #[serde(default)]
+    pub compression: Compression,
+
+    /// The maximum size, in bytes, of a decompressed message body, or of a fetched object when
+    /// `s3_notification` is set.
+    ///
+    /// Messages whose body, or one of whose objects, exceeds this size once decompressed are
+    /// treated as failing to be decompressed.
+    #[serde(default = "default_max_decompressed_bytes")]
+    #[derivative(Default(value = "default_max_decompressed_bytes()"))]
+    pub max_decompressed_bytes: usize,
+
+    #[configurable(derived)]
+    pub s3_notification: Option<S3NotificationConfig>,
+
+    #[configurable(derived)]
+    pub raw_output: Option<RawOutputConfig>,
+
+    #[configurable(derived)]
+    pub poison_messages: Option<PoisonMessagesConfig>,
+
     #[configurable(derived)]
     #[serde(default = "default_framing_message_based")]
     #[derivative(Default(value = "default_framing_message_based()"))]
@@ -94,21 +177,139 @@ pub struct AwsSqsConfig {
     pub log_namespace: Option<bool>,
 }
 
+/// An SQS queue to poll for messages, in addition to the one set by `queue_url`.
+#[configurable_component]
+#[derive(Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct SqsQueueConfig {
+    /// The URL of the SQS queue.
+    pub queue_url: String,
+
+    /// The AWS region of the queue.
+    ///
+    /// If neither `region` nor `endpoint` is set, the region and endpoint of the source are used.
+    pub region: Option<String>,
+
+    /// The API endpoint of the service for the queue.
+    ///
+    /// If neither `region` nor `endpoint` is set, the region and endpoint of the source are used.
+    pub endpoint: Option<String>,
+
+    /// The authentication to use for the queue, such as a role to assume in the account owning it.
+    ///
+    /// If not set, the authentication of the source is used.
+    #[configurable(derived)]
+    pub auth: Option<AwsAuthentication>,
+}
+
+/// Also sends messages received, as is, to the `raw` output.
+///
+/// Each message is sent as a single event, whose message is the body of the message as received,
+/// before any decompression or decoding, along with the same metadata as the decoded events. This
+/// lets pipelines archive the original messages while others process the decoded events, without
+/// consuming the queue twice.
+///
+/// Messages are only sent to the `raw` output once their body has been decoded, as those that
+/// can't be are left in the queue to be redelivered.
+#[configurable_component]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct RawOutputConfig {
+    /// Send 1 in every `sample_rate` of the messages matching `condition`.
+    #[serde(default = "default_sample_rate")]
+    #[derivative(Default(value = "default_sample_rate()"))]
+    pub sample_rate: u64,
+
+    /// A condition the events of the `raw` output must match to be sent.
+    ///
+    /// By default, every message is sent.
+    #[configurable(derived)]
+    pub condition: Option<AnyCondition>,
+}
+
+/// Handling of poison messages, those that keep failing to be processed.
+///
+/// Messages whose body fails to be decompressed, or whose S3 event notification fails to be
+/// processed, are left in the queue to be retried. Without a redrive policy on the queue, such
+/// messages are retried forever. Once a message that fails has been received `max_receive_attempts`
+/// times, it is instead handled according to `action`, and removed from the queue.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct PoisonMessagesConfig {
+    /// The number of times a message can be received before it is handled as a poison message
+    /// when it fails to be processed.
+    ///
+    /// This is the approximate receive count of the message, as counted by SQS. To have messages
+    /// handled by Vector rather than by the redrive policy of the queue, if any, this must be lower
+    /// than the `maxReceiveCount` of that policy.
+    pub max_receive_attempts: NonZeroU32,
+
+    /// What to do with poison messages.
+    #[configurable(derived)]
+    #[serde(default)]
+    pub action: PoisonMessageAction,
+}
+
+/// What to do with poison messages.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Derivative, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[derivative(Default)]
+pub enum PoisonMessageAction {
+    /// Send the message, as is, to the `errors` output, and then delete it.
+    ///
+    /// Each message is sent as a single event, whose message is the body of the message as
+    /// received, along with the same metadata as the decoded events. The message is only deleted
+    /// once the event has been delivered, when acknowledgements are enabled.
+    #[derivative(Default)]
+    Route,
+    /// Delete the message.
+    Delete,
+}
+
+/// A queue to poll, with the region, endpoint, and authentication resolved against the source.
+#[derive(Debug)]
+struct ResolvedQueue<'a> {
+    queue_url: &'a str,
+    region: RegionOrEndpoint,
+    auth: &'a AwsAuthentication,
+}
+
 #[async_trait::async_trait]
 impl SourceConfig for AwsSqsConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<crate::sources::Source> {
         let log_namespace = cx.log_namespace(self.log_namespace);
 
-        let client = self.build_client(&cx).await?;
         let decoder =
             DecodingConfig::new(self.framing.clone(), self.decoding.clone(), log_namespace).build();
         let acknowledgements = cx.do_acknowledgements(self.acknowledgements);
+        let raw_output = self.raw_output.as_ref().map(RawOutput::new).transpose()?;
+
+        let mut sources = Vec::new();
+        for queue in self.resolve_queues()? {
+            let client = self.build_client(&queue, &cx).await?;
+            let s3_notification = match &self.s3_notification {
+                Some(config) => Some(S3NotificationHandler {
+                    client: if config.fetch_objects {
+                        Some(self.build_s3_client(&queue, &cx).await?)
+                    } else {
+                        None
+                    },
+                    compression: self.compression,
+                    max_decompressed_bytes: self.max_decompressed_bytes,
+                    fetch_concurrency: config.fetch_concurrency.get(),
+                    decoder: decoder.clone(),
+                    log_namespace,
+                }),
+                None => None,
+            };
 
-        Ok(Box::pin(
-            SqsSource {
+            sources.push(SqsSource {
                 client,
-                queue_url: self.queue_url.clone(),
-                decoder,
+                queue_url: queue.queue_url.to_owned(),
+                decoder: decoder.clone(),
                 poll_secs: self.poll_secs,
                 concurrency: self
                     .client_concurrency
@@ -116,27 +317,74 @@ impl SourceConfig for AwsSqsConfig {
                     .unwrap_or_else(crate::num_threads),
                 visibility_timeout_secs: self.visibility_timeout_secs,
                 delete_message: self.delete_message,
+                compression: self.compression,
+                max_decompressed_bytes: self.max_decompressed_bytes,
+                s3_notification,
+                raw_output: raw_output.clone(),
+                poison_messages: self.poison_messages.clone(),
                 acknowledgements,
                 log_namespace,
-            }
-            .run(cx.out, cx.shutdown),
-        ))
+            });
+        }
+
+        let out = cx.out;
+        let shutdown = cx.shutdown;
+        Ok(Box::pin(async move {
+            let results = future::join_all(
+                sources
+                    .into_iter()
+                    .map(|source| source.run(out.clone(), shutdown.clone())),
+            )
+            .await;
+            results.into_iter().collect()
+        }))
     }
 
     fn outputs(&self, global_log_namespace: LogNamespace) -> Vec<Output> {
-        let schema_definition = self
-            .decoding
-            .schema_definition(global_log_namespace.merge(self.log_namespace))
-            .with_standard_vector_source_metadata()
-            .with_source_metadata(
-                Self::NAME,
-                Some(LegacyKey::Overwrite(owned_value_path!("timestamp"))),
-                &owned_value_path!("timestamp"),
-                Kind::timestamp().or_undefined(),
-                Some("timestamp"),
-            );
+        let log_namespace = global_log_namespace.merge(self.log_namespace);
+        let mut schema_definition = with_timestamp(self.decoding.schema_definition(log_namespace));
+
+        if self.s3_notification.is_some() {
+            for field in ["bucket", "object", "region"] {
+                schema_definition = schema_definition.with_source_metadata(
+                    Self::NAME,
+                    Some(LegacyKey::Overwrite(owned_value_path!(field))),
+                    &owned_value_path!(field),
+                    Kind::bytes(),
+                    None,
+                );
+            }
+        } else {
+            schema_definition = with_message_metadata(schema_definition);
+        }
 
-        vec![Output::default(self.decoding.output_type()).with_schema_definition(schema_definition)]
+        let mut outputs =
+            vec![Output::default(self.decoding.output_type())
+                .with_schema_definition(schema_definition)];
+        let raw_schema_definition = with_message_metadata(with_timestamp(
+            BytesDeserializerConfig.schema_definition(log_namespace),
+        ));
+        if self.raw_output.is_some() {
+            outputs.push(
+                Output::default(DataType::Log)
+                    .with_port(RAW_OUTPUT)
+                    .with_schema_definition(raw_schema_definition.clone()),
+            );
+        }
+        if matches!(
+            &self.poison_messages,
+            Some(PoisonMessagesConfig {
+                action: PoisonMessageAction::Route,
+                ..
+            })
+        ) {
+            outputs.push(
+                Output::default(DataType::Log)
+                    .with_port(ERRORS_OUTPUT)
+                    .with_schema_definition(raw_schema_definition),
+            );
+        }
+        outputs
     }
 
     fn can_acknowledge(&self) -> bool {
@@ -144,18 +392,109 @@ impl SourceConfig for AwsSqsConfig {
     }
 }
 
+/// Adds the standard source metadata and the time the message was sent to `definition`.
+fn with_timestamp(definition: Definition) -> Definition {
+    definition
+        .with_standard_vector_source_metadata()
+        .with_source_metadata(
+            AwsSqsConfig::NAME,
+            Some(LegacyKey::Overwrite(owned_value_path!("timestamp"))),
+            &owned_value_path!("timestamp"),
+            Kind::timestamp().or_undefined(),
+            Some("timestamp"),
+        )
+}
+
+/// Adds the identifier and the attributes of the message to `definition`.
+fn with_message_metadata(definition: Definition) -> Definition {
+    definition
+        .with_source_metadata(
+            AwsSqsConfig::NAME,
+            Some(LegacyKey::InsertIfEmpty(owned_value_path!("message_id"))),
+            &owned_value_path!("message_id"),
+            Kind::bytes().or_undefined(),
+            None,
+        )
+        .with_source_metadata(
+            AwsSqsConfig::NAME,
+            Some(LegacyKey::InsertIfEmpty(owned_value_path!("attributes"))),
+            &owned_value_path!("attributes"),
+            Kind::object(Collection::empty().with_unknown(Kind::bytes())).or_undefined(),
+            None,
+        )
+}
+
 impl AwsSqsConfig {
-    async fn build_client(&self, cx: &SourceContext) -> crate::Result<aws_sdk_sqs::Client> {
+    /// Lists the queues to poll, applying the overrides of each of the `queues`.
+    fn resolve_queues(&self) -> crate::Result<Vec<ResolvedQueue<'_>>> {
+        let mut queues = Vec::with_capacity(self.queues.len() + 1);
+        if !self.queue_url.is_empty() {
+            queues.push(ResolvedQueue {
+                queue_url: &self.queue_url,
+                region: self.region.clone(),
+                auth: &self.auth,
+            });
+        }
+        for queue in &self.queues {
+            let region = if queue.region.is_some() || queue.endpoint.is_some() {
+                RegionOrEndpoint {
+                    region: queue.region.clone(),
+                    endpoint: queue.endpoint.clone(),
+                }
+            } else {
+                self.region.clone()
+            };
+            queues.push(ResolvedQueue {
+                queue_url: &queue.queue_url,
+                region,
+                auth: queue.auth.as_ref().unwrap_or(&self.auth),
+            });
+        }
+
+        if queues.is_empty() {
+            return Err("Either `queue_url` or `queues` must be set.".into());
+        }
+        let mut seen = HashSet::new();
+        if let Some(queue) = queues.iter().find(|queue| !seen.insert(queue.queue_url)) {
+            return Err(
+                format!("Queue {:?} is configured more than once.", queue.queue_url).into(),
+            );
+        }
+
+        Ok(queues)
+    }
+
+    async fn build_client(
+        &self,
+        queue: &ResolvedQueue<'_>,
+        cx: &SourceContext,
+    ) -> crate::Result<aws_sdk_sqs::Client> {
         create_client::<SqsClientBuilder>(
-            &self.auth,
-            self.region.region(),
-            self.region.endpoint()?,
+            queue.auth,
+            queue.region.region(),
+            queue.region.endpoint()?,
             &cx.proxy,
             &self.tls,
             false,
         )
         .await
     }
+
+    async fn build_s3_client(
+        &self,
+        queue: &ResolvedQueue<'_>,
+        cx: &SourceContext,
+    ) -> crate::Result<aws_sdk_s3::Client> {
+        create_client::<S3ClientBuilder>(
+            queue.auth,
+            queue.region.region(),
+            queue.region.endpoint()?,
+            &cx.proxy,
+            &self.tls,
+            true,
+        )
+        .await
+    }
 }
 
 const fn default_poll_secs() -> u32 {
@@ -166,8 +505,73 @@ const fn default_visibility_timeout_secs() -> u32 {
     300
 }
 
+const fn default_max_decompressed_bytes() -> usize {
+    10 * 1024 * 1024
+}
+
+const fn default_sample_rate() -> u64 {
+    1
+}
+
 const fn default_true() -> bool {
     true
 }
 
 impl_generate_config_from_default!(AwsSqsConfig);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_queue_overrides() {
+        let config: AwsSqsConfig = toml::from_str(
+            r#"
+            region = "us-east-1"
+            queue_url = "https://sqs.us-east-1.amazonaws.com/111111111111/local"
+
+            [[queues]]
+            queue_url = "https://sqs.eu-west-1.amazonaws.com/222222222222/remote"
+            region = "eu-west-1"
+            auth.assume_role = "arn:aws:iam::222222222222:role/vector"
+            auth.external_id = "external"
+
+            [[queues]]
+            queue_url = "https://sqs.us-east-1.amazonaws.com/111111111111/other"
+            "#,
+        )
+        .unwrap();
+
+        let queues = config.resolve_queues().unwrap();
+        assert_eq!(queues.len(), 3);
+        assert_eq!(queues[0].region, config.region);
+        assert!(std::ptr::eq(queues[0].auth, &config.auth));
+        assert_eq!(
+            queues[1].region,
+            RegionOrEndpoint::with_region("eu-west-1".to_owned())
+        );
+        assert!(matches!(
+            queues[1].auth,
+            AwsAuthentication::Role { external_id: Some(id), .. } if id == "external"
+        ));
+        assert_eq!(queues[2].region, config.region);
+        assert!(std::ptr::eq(queues[2].auth, &config.auth));
+    }
+
+    #[test]
+    fn requires_a_queue() {
+        let config: AwsSqsConfig = toml::from_str(r#"region = "us-east-1""#).unwrap();
+        assert!(config.resolve_queues().is_err());
+
+        let config: AwsSqsConfig = toml::from_str(
+            r#"
+            queue_url = "https://sqs.us-east-1.amazonaws.com/111111111111/local"
+
+            [[queues]]
+            queue_url = "https://sqs.us-east-1.amazonaws.com/111111111111/local"
+            "#,
+        )
+        .unwrap();
+        assert!(config.resolve_queues().is_err());
+    }
+}
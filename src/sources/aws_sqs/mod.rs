@@ -1,5 +1,6 @@
 mod config;
 mod integration_tests;
+mod s3_notification;
 mod source;
 
-pub use config::AwsSqsConfig;
+pub use config::{AwsSqsConfig, PoisonMessageAction};
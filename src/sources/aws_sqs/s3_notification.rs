@@ -0,0 +1,442 @@
+use std::{io, num::NonZeroUsize};
+
+use async_compression::tokio::bufread;
+use aws_sdk_s3::{error::GetObjectError, Client as S3Client};
+use aws_smithy_client::SdkError;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::{stream, StreamExt, TryStreamExt};
+use percent_encoding::percent_decode;
+use serde::Deserialize;
+use snafu::{ResultExt, Snafu};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio_util::io::StreamReader;
+use vector_config::{configurable_component, NamedComponent};
+use vector_core::config::{LegacyKey, LogNamespace};
+
+use super::{config::Compression, AwsSqsConfig};
+use crate::{
+    codecs::Decoder,
+    event::{BatchNotifier, Event, Value},
+    internal_events::SqsS3EventRecordInvalidEventIgnored,
+    sources::util,
+};
+
+/// Configuration for unwrapping S3 event notifications.
+///
+/// When set, each message body is parsed as an [S3 event notification][notification], and each
+/// record for a created object is emitted as configured here. Records for other kinds of events,
+/// and test notifications, are ignored.
+///
+/// [notification]: https://docs.aws.amazon.com/AmazonS3/latest/userguide/notification-content-structure.html
+#[configurable_component]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields)]
+pub struct S3NotificationConfig {
+    /// Whether or not to fetch the created objects.
+    ///
+    /// When enabled, the contents of each object are decoded as configured by `framing` and
+    /// `decoding`. Otherwise, the JSON of each notification record is decoded instead.
+    ///
+    /// Objects are decompressed according to `compression`, and are subject to
+    /// `max_decompressed_bytes` once decompressed.
+    ///
+    /// Either way, the SQS message is only deleted once all the events decoded from it have been
+    /// delivered, and is left in the queue if any of its objects can't be fetched.
+    #[serde(default = "crate::serde::default_true")]
+    #[derivative(Default(value = "true"))]
+    pub fetch_objects: bool,
+
+    /// The maximum number of objects referenced by a single notification to fetch at once.
+    #[serde(default = "default_fetch_concurrency")]
+    #[derivative(Default(value = "default_fetch_concurrency()"))]
+    pub fetch_concurrency: NonZeroUsize,
+}
+
+fn default_fetch_concurrency() -> NonZeroUsize {
+    NonZeroUsize::new(4).expect("static non-zero number")
+}
+
+#[derive(Debug, Snafu)]
+pub enum S3NotificationError {
+    #[snafu(display("Invalid S3 event notification: {}", source))]
+    InvalidNotification { source: serde_json::Error },
+    #[snafu(display("Failed to fetch s3://{}/{}: {}", bucket, key, source))]
+    GetObject {
+        source: SdkError<GetObjectError>,
+        bucket: String,
+        key: String,
+    },
+    #[snafu(display("Failed to read all of s3://{}/{}: {}", bucket, key, source))]
+    ReadObject {
+        source: io::Error,
+        bucket: String,
+        key: String,
+    },
+    #[snafu(display(
+        "Decompressed s3://{}/{} exceeds the limit of {} bytes",
+        bucket,
+        key,
+        max_bytes
+    ))]
+    ObjectTooLarge {
+        bucket: String,
+        key: String,
+        max_bytes: usize,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct S3Notification {
+    // Test notifications have no records.
+    #[serde(rename = "Records", default)]
+    records: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct S3NotificationRecord {
+    aws_region: String,
+    event_name: String,
+    s3: S3Entity,
+}
+
+#[derive(Debug, Deserialize)]
+struct S3Entity {
+    bucket: S3Bucket,
+    object: S3Object,
+}
+
+#[derive(Debug, Deserialize)]
+struct S3Bucket {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct S3Object {
+    key: String,
+}
+
+/// Unwraps S3 event notifications into the events decoded from the objects they reference.
+#[derive(Clone)]
+pub(super) struct S3NotificationHandler {
+    /// The client used to fetch objects, if they are fetched at all.
+    pub(super) client: Option<S3Client>,
+    pub(super) compression: Compression,
+    pub(super) max_decompressed_bytes: usize,
+    pub(super) fetch_concurrency: usize,
+    pub(super) decoder: Decoder,
+    pub(super) log_namespace: LogNamespace,
+}
+
+impl S3NotificationHandler {
+    /// Decodes the events of every created object referenced by the notification in `body`.
+    ///
+    /// Either all of them are returned, or none at all if any object can't be fetched, so that the
+    /// message can be processed again later.
+    pub(super) async fn handle(
+        &self,
+        body: &[u8],
+        timestamp: Option<DateTime<Utc>>,
+        batch: &Option<BatchNotifier>,
+    ) -> Result<Vec<Event>, S3NotificationError> {
+        let notification: S3Notification =
+            serde_json::from_slice(body).context(InvalidNotificationSnafu)?;
+
+        let mut records = Vec::new();
+        for value in notification.records {
+            let record: S3NotificationRecord =
+                serde_json::from_value(value.clone()).context(InvalidNotificationSnafu)?;
+            let key = decode_object_key(&record.s3.object.key);
+
+            let (kind, name) = record
+                .event_name
+                .split_once(':')
+                .unwrap_or((record.event_name.as_str(), ""));
+            if kind != "ObjectCreated" {
+                emit!(SqsS3EventRecordInvalidEventIgnored {
+                    bucket: &record.s3.bucket.name,
+                    key: &key,
+                    kind,
+                    name,
+                });
+                continue;
+            }
+            records.push((record, key, value));
+        }
+
+        // Objects are fetched concurrently, but their events are kept in the order of the records.
+        // The fetches are collected beforehand, as the future must not hold on to a closure over
+        // the records to be `Send`.
+        let fetches = records
+            .iter()
+            .map(|(record, key, value)| async move {
+                match &self.client {
+                    Some(client) => self.fetch_object(client, &record.s3.bucket.name, key).await,
+                    None => Ok(Bytes::from(value.to_string())),
+                }
+            })
+            .collect::<Vec<_>>();
+        let contents = stream::iter(fetches)
+            .buffered(self.fetch_concurrency)
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        let mut events = Vec::new();
+        for ((record, key, _), contents) in records.iter().zip(contents) {
+            let decoded = util::decode_message(
+                self.decoder.clone(),
+                AwsSqsConfig::NAME,
+                &contents,
+                timestamp,
+                vec![
+                    (
+                        LegacyKey::Overwrite("bucket"),
+                        Value::from(record.s3.bucket.name.as_str()),
+                    ),
+                    (LegacyKey::Overwrite("object"), Value::from(key.as_str())),
+                    (
+                        LegacyKey::Overwrite("region"),
+                        Value::from(record.aws_region.as_str()),
+                    ),
+                ],
+                batch,
+                self.log_namespace,
+            );
+            events.extend(decoded);
+        }
+
+        Ok(events)
+    }
+
+    /// Fetches and decompresses the object at `key`, reading at most `max_decompressed_bytes` of
+    /// it.
+    async fn fetch_object(
+        &self,
+        client: &S3Client,
+        bucket: &str,
+        key: &str,
+    ) -> Result<Bytes, S3NotificationError> {
+        let object = client
+            .get_object()
+            .bucket(bucket)
+            .key(key)
+            .send()
+            .await
+            .context(GetObjectSnafu { bucket, key })?;
+
+        let compression = match self.compression {
+            Compression::Auto => object_compression(
+                object.content_encoding.as_deref(),
+                object.content_type.as_deref(),
+                key,
+            ),
+            compression => compression,
+        };
+        let body = tokio::io::BufReader::new(StreamReader::new(
+            object
+                .body
+                .map_err(|error| io::Error::new(io::ErrorKind::Other, error)),
+        ));
+        let reader: Box<dyn AsyncRead + Send + Unpin> = match compression {
+            Compression::Auto | Compression::None => Box::new(body),
+            Compression::Gzip => Box::new({
+                let mut decoder = bufread::GzipDecoder::new(body);
+                decoder.multiple_members(true);
+                decoder
+            }),
+            Compression::Zstd => Box::new({
+                let mut decoder = bufread::ZstdDecoder::new(body);
+                decoder.multiple_members(true);
+                decoder
+            }),
+        };
+
+        // Reading one byte past the limit tells an oversized object apart from one right at the
+        // limit.
+        let max_bytes = self.max_decompressed_bytes;
+        let mut contents = Vec::new();
+        reader
+            .take(max_bytes as u64 + 1)
+            .read_to_end(&mut contents)
+            .await
+            .context(ReadObjectSnafu { bucket, key })?;
+        if contents.len() > max_bytes {
+            return Err(S3NotificationError::ObjectTooLarge {
+                bucket: bucket.to_owned(),
+                key: key.to_owned(),
+                max_bytes,
+            });
+        }
+
+        Ok(contents.into())
+    }
+}
+
+/// Determines the compression scheme of an object from its `Content-Encoding` and `Content-Type`
+/// metadata, then from the suffix of its key, in that order.
+fn object_compression(
+    content_encoding: Option<&str>,
+    content_type: Option<&str>,
+    key: &str,
+) -> Compression {
+    let from_content_encoding =
+        content_encoding.and_then(|content_encoding| match content_encoding {
+            "gzip" => Some(Compression::Gzip),
+            "zstd" => Some(Compression::Zstd),
+            _ => None,
+        });
+    let from_content_type = || {
+        content_type.and_then(|content_type| match content_type {
+            "application/gzip" | "application/x-gzip" => Some(Compression::Gzip),
+            "application/zstd" => Some(Compression::Zstd),
+            _ => None,
+        })
+    };
+    let from_key = || {
+        if key.ends_with(".gz") {
+            Some(Compression::Gzip)
+        } else if key.ends_with(".zst") {
+            Some(Compression::Zstd)
+        } else {
+            None
+        }
+    };
+
+    from_content_encoding
+        .or_else(from_content_type)
+        .or_else(from_key)
+        .unwrap_or(Compression::None)
+}
+
+/// Decodes an object key, which is URL-encoded in notifications, with spaces encoded as `+`.
+fn decode_object_key(key: &str) -> String {
+    let key = key.replace('+', " ");
+    percent_decode(key.as_bytes())
+        .decode_utf8_lossy()
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use codecs::decoding::{DeserializerConfig, FramingConfig};
+    use lookup::event_path;
+
+    use super::*;
+    use crate::codecs::DecodingConfig;
+
+    const NOTIFICATION: &str = r#"{
+        "Records": [
+            {
+                "eventVersion": "2.1",
+                "eventSource": "aws:s3",
+                "awsRegion": "us-east-1",
+                "eventName": "ObjectCreated:Put",
+                "s3": {
+                    "bucket": { "name": "bucket" },
+                    "object": { "key": "some+dir/file%3A1.log", "size": 42 }
+                }
+            },
+            {
+                "eventVersion": "2.1",
+                "eventSource": "aws:s3",
+                "awsRegion": "us-east-1",
+                "eventName": "ObjectRemoved:Delete",
+                "s3": {
+                    "bucket": { "name": "bucket" },
+                    "object": { "key": "removed.log" }
+                }
+            }
+        ]
+    }"#;
+
+    fn handler() -> S3NotificationHandler {
+        S3NotificationHandler {
+            client: None,
+            compression: Compression::Auto,
+            max_decompressed_bytes: 1024,
+            fetch_concurrency: 1,
+            decoder: DecodingConfig::new(
+                FramingConfig::Bytes,
+                DeserializerConfig::Json,
+                LogNamespace::Legacy,
+            )
+            .build(),
+            log_namespace: LogNamespace::Legacy,
+        }
+    }
+
+    #[test]
+    fn decodes_object_keys() {
+        assert_eq!(
+            decode_object_key("some+dir/file%3A1.log"),
+            "some dir/file:1.log"
+        );
+    }
+
+    #[test]
+    fn determines_object_compression() {
+        assert_eq!(
+            object_compression(Some("gzip"), Some("application/zstd"), "file.zst"),
+            Compression::Gzip
+        );
+        assert_eq!(
+            object_compression(None, Some("application/zstd"), "file.gz"),
+            Compression::Zstd
+        );
+        assert_eq!(
+            object_compression(None, None, "file.log.gz"),
+            Compression::Gzip
+        );
+        assert_eq!(
+            object_compression(None, None, "file.log"),
+            Compression::None
+        );
+    }
+
+    #[tokio::test]
+    async fn decodes_created_object_records() {
+        let events = handler()
+            .handle(NOTIFICATION.as_bytes(), None, &None)
+            .await
+            .unwrap();
+
+        assert_eq!(events.len(), 1);
+        let log = events[0].as_log();
+        assert_eq!(log.get(event_path!("bucket")), Some(&"bucket".into()));
+        assert_eq!(
+            log.get(event_path!("object")),
+            Some(&"some dir/file:1.log".into())
+        );
+        assert_eq!(log.get(event_path!("region")), Some(&"us-east-1".into()));
+        assert_eq!(
+            log.get(event_path!("eventName")),
+            Some(&"ObjectCreated:Put".into())
+        );
+    }
+
+    #[tokio::test]
+    async fn ignores_test_notifications() {
+        let body = r#"{
+            "Service": "Amazon S3",
+            "Event": "s3:TestEvent",
+            "Time": "2014-10-13T15:57:02.089Z",
+            "Bucket": "bucket"
+        }"#;
+
+        let events = handler()
+            .handle(body.as_bytes(), None, &None)
+            .await
+            .unwrap();
+        assert!(events.is_empty());
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_notifications() {
+        assert!(matches!(
+            handler().handle(b"not json", None, &None).await,
+            Err(S3NotificationError::InvalidNotification { .. })
+        ));
+    }
+}
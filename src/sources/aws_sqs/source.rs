@@ -1,21 +1,47 @@
-use std::{collections::HashMap, panic, str::FromStr, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{self, Read},
+    panic,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
 
 use aws_sdk_sqs::{
-    model::{DeleteMessageBatchRequestEntry, MessageSystemAttributeName, QueueAttributeName},
+    model::{
+        DeleteMessageBatchRequestEntry, Message, MessageAttributeValue, MessageSystemAttributeName,
+        QueueAttributeName,
+    },
     Client as SqsClient,
 };
+use bytes::Bytes;
 use chrono::{DateTime, TimeZone, Utc};
+use flate2::read::MultiGzDecoder;
 use futures::{FutureExt, StreamExt};
+use lookup::path;
+use snafu::{ResultExt, Snafu};
 use tokio::{pin, select};
 use tracing_futures::Instrument;
 use vector_common::finalizer::UnorderedFinalizer;
-use vector_core::config::LogNamespace;
+use vector_core::config::{LegacyKey, LogNamespace};
 
+use super::{
+    config::{
+        Compression, PoisonMessageAction, PoisonMessagesConfig, RawOutputConfig, ERRORS_OUTPUT,
+        RAW_OUTPUT,
+    },
+    s3_notification::S3NotificationHandler,
+};
 use crate::{
     codecs::Decoder,
-    event::{BatchNotifier, BatchStatus},
+    conditions::Condition,
+    config::log_schema,
+    event::{BatchNotifier, BatchStatus, Event, LogEvent, Value},
     internal_events::{
-        EndpointBytesReceived, SqsMessageDeleteError, SqsMessageReceiveError, StreamClosedError,
+        EndpointBytesReceived, SqsMessageDecompressionError, SqsMessageDeleteError,
+        SqsMessageReceiveError, SqsPoisonMessage, SqsS3NotificationError, StreamClosedError,
     },
     shutdown::ShutdownSignal,
     sources::util,
@@ -25,6 +51,9 @@ use crate::{
 // This is the maximum SQS supports in a single batch request
 const MAX_BATCH_SIZE: i32 = 10;
 
+// The message attribute producers set to the compression scheme of the message body.
+const CONTENT_ENCODING_ATTRIBUTE: &str = "content-encoding";
+
 type Finalizer = UnorderedFinalizer<Vec<String>>;
 
 #[derive(Clone)]
@@ -35,7 +64,12 @@ pub struct SqsSource {
     pub poll_secs: u32,
     pub visibility_timeout_secs: u32,
     pub delete_message: bool,
+    pub compression: Compression,
+    pub max_decompressed_bytes: usize,
     pub concurrency: usize,
+    pub(super) s3_notification: Option<S3NotificationHandler>,
+    pub(super) raw_output: Option<RawOutput>,
+    pub(super) poison_messages: Option<PoisonMessagesConfig>,
     pub(super) acknowledgements: bool,
     pub(super) log_namespace: LogNamespace,
 }
@@ -103,6 +137,11 @@ impl SqsSource {
             // I think this should be a known attribute
             // https://github.com/awslabs/aws-sdk-rust/issues/411
             .attribute_names(QueueAttributeName::Unknown(String::from("SentTimestamp")))
+            .attribute_names(QueueAttributeName::Unknown(String::from(
+                "ApproximateReceiveCount",
+            )))
+            // All message attributes are received, to be added to the events.
+            .message_attribute_names("All")
             .send()
             .await;
 
@@ -127,54 +166,343 @@ impl SqsSource {
 
             let mut receipts_to_ack = Vec::with_capacity(messages.len());
             let mut events = Vec::with_capacity(messages.len());
+            let mut raw_events = Vec::new();
+            let mut error_events = Vec::new();
 
             let (batch, batch_receiver) =
                 BatchNotifier::maybe_new_with_receiver(finalizer.is_some());
             for message in messages {
-                if let Some(body) = message.body {
-                    // a receipt handle should always exist
-                    if let Some(receipt_handle) = message.receipt_handle {
-                        receipts_to_ack.push(receipt_handle);
+                let timestamp = get_timestamp(&message.attributes);
+                let body = match self.message_body(&message) {
+                    Some(Ok(body)) => body,
+                    Some(Err(error)) => {
+                        // The message is not acknowledged, so that it is redelivered or moved
+                        // to a dead-letter queue rather than being lost, unless it is a poison
+                        // message.
+                        emit!(SqsMessageDecompressionError {
+                            message_id: message.message_id().unwrap_or_default(),
+                            error: &error,
+                        });
+                        if self.handle_poison_message(
+                            &message,
+                            timestamp,
+                            &batch,
+                            &mut error_events,
+                        ) {
+                            receipts_to_ack.extend(message.receipt_handle);
+                        }
+                        continue;
                     }
-                    let timestamp = get_timestamp(&message.attributes);
-                    // Error is logged by `crate::codecs::Decoder`, no further handling
-                    // is needed here.
-                    let decoded = util::decode_message(
-                        self.decoder.clone(),
-                        "aws_sqs",
-                        body.as_bytes(),
-                        timestamp,
-                        &batch,
-                        self.log_namespace,
+                    None => continue,
+                };
+                match &self.s3_notification {
+                    Some(handler) => match handler.handle(&body, timestamp, &batch).await {
+                        Ok(decoded) => events.extend(decoded),
+                        Err(error) => {
+                            // As above, the message is left in the queue to be retried.
+                            emit!(SqsS3NotificationError {
+                                message_id: message.message_id().unwrap_or_default(),
+                                error: &error,
+                            });
+                            if self.handle_poison_message(
+                                &message,
+                                timestamp,
+                                &batch,
+                                &mut error_events,
+                            ) {
+                                receipts_to_ack.extend(message.receipt_handle);
+                            }
+                            continue;
+                        }
+                    },
+                    None => {
+                        // Error is logged by `crate::codecs::Decoder`, no further handling
+                        // is needed here.
+                        let decoded = util::decode_message(
+                            self.decoder.clone(),
+                            "aws_sqs",
+                            &body,
+                            timestamp,
+                            message_metadata(&message),
+                            &batch,
+                            self.log_namespace,
+                        );
+                        events.extend(decoded);
+                    }
+                }
+                if let Some(raw_output) = &self.raw_output {
+                    raw_events.extend(
+                        raw_output
+                            .event(&message, timestamp, self.log_namespace)
+                            .map(|event| event.with_batch_notifier_option(&batch)),
                     );
-                    events.extend(decoded);
+                }
+                // a receipt handle should always exist
+                if let Some(receipt_handle) = message.receipt_handle {
+                    receipts_to_ack.push(receipt_handle);
                 }
             }
             drop(batch); // Drop last reference to batch acknowledgement finalizer
             let count = events.len();
 
-            match out.send_batch(events).await {
-                Ok(()) => {
-                    if self.delete_message {
-                        match batch_receiver {
-                            Some(receiver) => finalizer
-                                .expect("Finalizer must exist for the batch receiver to be created")
-                                .add(receipts_to_ack, receiver),
-                            None => {
-                                delete_messages(
-                                    self.client.clone(),
-                                    receipts_to_ack,
-                                    self.queue_url.clone(),
-                                )
-                                .await
-                            }
-                        }
+            if let Err(error) = out.send_batch(events).await {
+                emit!(StreamClosedError { error, count });
+                return;
+            }
+            for (output, events) in [(RAW_OUTPUT, raw_events), (ERRORS_OUTPUT, error_events)] {
+                if !events.is_empty() {
+                    let count = events.len();
+                    if let Err(error) = out.send_batch_named(output, events).await {
+                        emit!(StreamClosedError { error, count });
+                        return;
                     }
                 }
-                Err(error) => emit!(StreamClosedError { error, count }),
             }
+
+            if self.delete_message {
+                match batch_receiver {
+                    Some(receiver) => finalizer
+                        .expect("Finalizer must exist for the batch receiver to be created")
+                        .add(receipts_to_ack, receiver),
+                    None => {
+                        delete_messages(
+                            self.client.clone(),
+                            receipts_to_ack,
+                            self.queue_url.clone(),
+                        )
+                        .await
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Selects the messages sent to the `raw` output, and builds their events.
+#[derive(Clone)]
+pub(super) struct RawOutput {
+    sample_rate: u64,
+    condition: Option<Condition>,
+    matched: Arc<AtomicU64>,
+}
+
+impl RawOutput {
+    pub(super) fn new(config: &RawOutputConfig) -> crate::Result<Self> {
+        if config.sample_rate == 0 {
+            return Err("`raw_output.sample_rate` must be at least 1.".into());
+        }
+        let condition = config
+            .condition
+            .as_ref()
+            .map(|condition| condition.build(&Default::default()))
+            .transpose()?;
+        Ok(Self {
+            sample_rate: config.sample_rate,
+            condition,
+            matched: Arc::default(),
+        })
+    }
+
+    /// Returns the event of the `raw` output for `message`, if it is selected.
+    fn event(
+        &self,
+        message: &Message,
+        timestamp: Option<DateTime<Utc>>,
+        log_namespace: LogNamespace,
+    ) -> Option<Event> {
+        let log = raw_event(message, timestamp, log_namespace)?;
+        let event = match &self.condition {
+            Some(condition) => match condition.check(log.into()) {
+                (true, event) => event,
+                (false, _) => return None,
+            },
+            None => log.into(),
+        };
+        let matched = self.matched.fetch_add(1, Ordering::Relaxed);
+        (matched % self.sample_rate == 0).then_some(event)
+    }
+}
+
+/// Builds an event from `message` as is, whose message is the body of the message as received.
+fn raw_event(
+    message: &Message,
+    timestamp: Option<DateTime<Utc>>,
+    log_namespace: LogNamespace,
+) -> Option<LogEvent> {
+    let body = message.body()?;
+    let mut log = match log_namespace {
+        LogNamespace::Vector => LogEvent::from(Value::from(body)),
+        LogNamespace::Legacy => LogEvent::from_str_legacy(body),
+    };
+    log_namespace.insert_standard_vector_source_metadata(&mut log, "aws_sqs", Utc::now());
+    if let Some(timestamp) = timestamp {
+        log_namespace.insert_source_metadata(
+            "aws_sqs",
+            &mut log,
+            Some(LegacyKey::Overwrite(path!(log_schema().timestamp_key()))),
+            path!("timestamp"),
+            timestamp,
+        );
+    }
+    for (legacy_key, value) in message_metadata(message) {
+        let (field, legacy_key) = match legacy_key {
+            LegacyKey::Overwrite(field) => (field, LegacyKey::Overwrite(path!(field))),
+            LegacyKey::InsertIfEmpty(field) => (field, LegacyKey::InsertIfEmpty(path!(field))),
+        };
+        log_namespace.insert_source_metadata(
+            "aws_sqs",
+            &mut log,
+            Some(legacy_key),
+            path!(field),
+            value,
+        );
+    }
+    Some(log)
+}
+
+impl SqsSource {
+    /// Handles `message`, which failed to be processed, if it is a poison message.
+    ///
+    /// Returns whether or not the message is to be removed from the queue.
+    fn handle_poison_message(
+        &self,
+        message: &Message,
+        timestamp: Option<DateTime<Utc>>,
+        batch: &Option<BatchNotifier>,
+        errors: &mut Vec<Event>,
+    ) -> bool {
+        let config = match &self.poison_messages {
+            Some(config) => config,
+            None => return false,
+        };
+        let receive_count = get_receive_count(&message.attributes);
+        if receive_count.map_or(true, |count| count < config.max_receive_attempts.get()) {
+            return false;
         }
+
+        emit!(SqsPoisonMessage {
+            message_id: message.message_id().unwrap_or_default(),
+            receive_count: receive_count.unwrap_or_default(),
+            action: config.action,
+        });
+        if config.action == PoisonMessageAction::Route {
+            errors.extend(
+                raw_event(message, timestamp, self.log_namespace)
+                    .map(|log| Event::from(log).with_batch_notifier_option(batch)),
+            );
+        }
+        true
+    }
+
+    /// Gets the body of the message, decompressed according to the configured compression scheme.
+    fn message_body(&self, message: &Message) -> Option<Result<Bytes, DecompressionError>> {
+        let body = message.body()?;
+        let compression = match self.compression {
+            Compression::Auto => get_content_encoding(&message.message_attributes),
+            // The configured compression scheme applies to the objects S3 notifications refer to.
+            _ if self.s3_notification.is_some() => {
+                get_content_encoding(&message.message_attributes)
+            }
+            compression => compression,
+        };
+        Some(decompress(body, compression, self.max_decompressed_bytes))
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum DecompressionError {
+    #[snafu(display("Compressed body is not valid base64: {}", source))]
+    InvalidBase64 { source: base64::DecodeError },
+    #[snafu(display("Failed to decompress body: {}", source))]
+    Decompress { source: io::Error },
+    #[snafu(display("Decompressed body exceeds the limit of {} bytes", max_bytes))]
+    TooLarge { max_bytes: usize },
+}
+
+fn decompress(
+    body: &str,
+    compression: Compression,
+    max_bytes: usize,
+) -> Result<Bytes, DecompressionError> {
+    if matches!(compression, Compression::Auto | Compression::None) {
+        return Ok(Bytes::copy_from_slice(body.as_bytes()));
+    }
+
+    let compressed = base64::decode(body.trim()).context(InvalidBase64Snafu)?;
+    let reader: Box<dyn Read> = match compression {
+        Compression::Gzip => Box::new(MultiGzDecoder::new(compressed.as_slice())),
+        _ => Box::new(
+            zstd::stream::read::Decoder::new(compressed.as_slice()).context(DecompressSnafu)?,
+        ),
+    };
+
+    // Reading one byte past the limit tells an oversized body apart from one right at the limit.
+    let mut decompressed = Vec::new();
+    reader
+        .take(max_bytes as u64 + 1)
+        .read_to_end(&mut decompressed)
+        .context(DecompressSnafu)?;
+    if decompressed.len() > max_bytes {
+        return Err(DecompressionError::TooLarge { max_bytes });
+    }
+
+    Ok(decompressed.into())
+}
+
+fn get_content_encoding(
+    attributes: &Option<HashMap<String, MessageAttributeValue>>,
+) -> Compression {
+    let content_encoding = attributes
+        .as_ref()
+        .and_then(|attributes| attributes.get(CONTENT_ENCODING_ATTRIBUTE))
+        .and_then(|attribute| attribute.string_value());
+    match content_encoding {
+        Some("gzip") => Compression::Gzip,
+        Some("zstd") => Compression::Zstd,
+        _ => Compression::None,
+    }
+}
+
+/// Returns the identifier and the attributes of the message, added to the events decoded from it.
+///
+/// With the legacy namespace, they are only inserted if the event doesn't have such fields yet, so
+/// as not to overwrite the fields decoded from the message body.
+fn message_metadata(message: &Message) -> Vec<(LegacyKey<&'static str>, Value)> {
+    let mut metadata = Vec::new();
+    if let Some(message_id) = message.message_id() {
+        metadata.push((
+            LegacyKey::InsertIfEmpty("message_id"),
+            Value::from(message_id),
+        ));
+    }
+    if let Some(attributes) = message.message_attributes() {
+        let attributes = attributes
+            .iter()
+            .filter_map(|(name, attribute)| {
+                let value = match (attribute.string_value(), attribute.binary_value()) {
+                    (Some(value), _) => Value::from(value),
+                    (None, Some(value)) => Value::from(value.as_ref()),
+                    (None, None) => return None,
+                };
+                Some((name.clone(), value))
+            })
+            .collect::<BTreeMap<_, _>>();
+        metadata.push((
+            LegacyKey::InsertIfEmpty("attributes"),
+            Value::from(attributes),
+        ));
     }
+    metadata
+}
+
+fn get_receive_count(
+    attributes: &Option<HashMap<MessageSystemAttributeName, String>>,
+) -> Option<u32> {
+    attributes
+        .as_ref()?
+        .get(&MessageSystemAttributeName::ApproximateReceiveCount)?
+        .parse()
+        .ok()
 }
 
 fn get_timestamp(
@@ -208,12 +536,15 @@ async fn delete_messages(client: SqsClient, receipts: Vec<String>, queue_url: St
 mod tests {
     use crate::codecs::DecodingConfig;
     use chrono::SecondsFormat;
-    use lookup::path;
+    use codecs::decoding::DeserializerConfig;
     use vector_config::NamedComponent;
 
     use super::*;
     use crate::config::{log_schema, SourceConfig};
+    use crate::serde::default_framing_message_based;
     use crate::sources::aws_sqs::AwsSqsConfig;
+    use aws_types::region::Region;
+    use std::num::NonZeroU32;
 
     #[tokio::test]
     async fn test_decode_vector_namespace() {
@@ -238,6 +569,7 @@ mod tests {
             "aws_sqs",
             b"test",
             Some(now),
+            message_metadata(&message_with_attributes()),
             &None,
             LogNamespace::Vector,
         )
@@ -263,6 +595,14 @@ mod tests {
                 .to_string_lossy(),
             now.to_rfc3339_opts(SecondsFormat::AutoSi, true)
         );
+        assert_eq!(
+            events[0].as_log().metadata().value().get(path!(
+                AwsSqsConfig::NAME,
+                "attributes",
+                "team"
+            )),
+            Some(&Value::from("ops"))
+        );
         definition.assert_valid_for_event(&events[0]);
     }
 
@@ -289,6 +629,7 @@ mod tests {
             "aws_sqs",
             b"test",
             Some(now),
+            message_metadata(&message_with_attributes()),
             &None,
             LogNamespace::Legacy,
         )
@@ -312,9 +653,282 @@ mod tests {
                 .to_string_lossy(),
             now.to_rfc3339_opts(SecondsFormat::AutoSi, true)
         );
+        assert_eq!(
+            events[0].as_log().get("message_id"),
+            Some(&Value::from("message-id"))
+        );
         definition.assert_valid_for_event(&events[0]);
     }
 
+    #[test]
+    fn test_decode_legacy_namespace_keeps_decoded_fields() {
+        let events: Vec<_> = util::decode_message(
+            DecodingConfig::new(
+                default_framing_message_based(),
+                DeserializerConfig::Json,
+                LogNamespace::Legacy,
+            )
+            .build(),
+            "aws_sqs",
+            br#"{"message_id": "decoded", "attributes": "decoded"}"#,
+            None,
+            message_metadata(&message_with_attributes()),
+            &None,
+            LogNamespace::Legacy,
+        )
+        .collect();
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events[0].as_log().get("message_id"),
+            Some(&Value::from("decoded"))
+        );
+        assert_eq!(
+            events[0].as_log().get("attributes"),
+            Some(&Value::from("decoded"))
+        );
+    }
+
+    #[test]
+    fn sends_sampled_matching_messages_to_raw_output() {
+        let config: AwsSqsConfig = toml::from_str(
+            r#"
+            queue_url = "https://sqs.us-east-1.amazonaws.com/111111111111/local"
+            raw_output.sample_rate = 2
+            raw_output.condition = '.attributes.team == "ops"'
+            "#,
+        )
+        .unwrap();
+        let outputs = config.outputs(LogNamespace::Legacy);
+        assert_eq!(outputs[1].port.as_deref(), Some(RAW_OUTPUT));
+        let definition = outputs[1].log_schema_definition.clone().unwrap();
+
+        let raw_output = RawOutput::new(config.raw_output.as_ref().unwrap()).unwrap();
+        let now = Utc::now();
+        let message = Message::builder()
+            .body("H4sI...")
+            .message_id("message-id")
+            .message_attributes(
+                "team",
+                MessageAttributeValue::builder()
+                    .data_type("String")
+                    .string_value("ops")
+                    .build(),
+            )
+            .build();
+        let other = Message::builder().body("other").build();
+
+        let event = raw_output
+            .event(&message, Some(now), LogNamespace::Legacy)
+            .unwrap();
+        let log = event.as_log();
+        assert_eq!(log[log_schema().message_key()], "H4sI...".into());
+        assert_eq!(log["message_id"], "message-id".into());
+        assert_eq!(log[log_schema().timestamp_key()], now.into());
+        definition.assert_valid_for_event(&event);
+
+        // Messages not matching the condition aren't sampled.
+        assert!(raw_output
+            .event(&other, None, LogNamespace::Legacy)
+            .is_none());
+        assert!(raw_output
+            .event(&message, None, LogNamespace::Legacy)
+            .is_none());
+        assert!(raw_output
+            .event(&message, None, LogNamespace::Legacy)
+            .is_some());
+    }
+
+    #[test]
+    fn rejects_zero_raw_output_sample_rate() {
+        let config = RawOutputConfig {
+            sample_rate: 0,
+            condition: None,
+        };
+        assert!(RawOutput::new(&config).is_err());
+    }
+
+    #[test]
+    fn routes_poison_messages_to_errors_output() {
+        let config: AwsSqsConfig = toml::from_str(
+            r#"
+            queue_url = "https://sqs.us-east-1.amazonaws.com/111111111111/local"
+            poison_messages.max_receive_attempts = 3
+            "#,
+        )
+        .unwrap();
+        let outputs = config.outputs(LogNamespace::Legacy);
+        assert_eq!(outputs[1].port.as_deref(), Some(ERRORS_OUTPUT));
+
+        let config: AwsSqsConfig = toml::from_str(
+            r#"
+            queue_url = "https://sqs.us-east-1.amazonaws.com/111111111111/local"
+            poison_messages.max_receive_attempts = 3
+            poison_messages.action = "delete"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.outputs(LogNamespace::Legacy).len(), 1);
+    }
+
+    fn sqs_source(poison_messages: Option<PoisonMessagesConfig>) -> SqsSource {
+        let client = SqsClient::from_conf(
+            aws_sdk_sqs::config::Builder::new()
+                .region(Some(Region::new("us-east-1")))
+                .build(),
+        );
+        SqsSource {
+            client,
+            queue_url: "https://sqs.us-east-1.amazonaws.com/111111111111/local".to_string(),
+            decoder: DecodingConfig::new(
+                default_framing_message_based(),
+                DeserializerConfig::Bytes,
+                LogNamespace::Legacy,
+            )
+            .build(),
+            poll_secs: 15,
+            visibility_timeout_secs: 300,
+            delete_message: true,
+            compression: Compression::Gzip,
+            max_decompressed_bytes: 1024,
+            concurrency: 1,
+            s3_notification: None,
+            raw_output: None,
+            poison_messages,
+            acknowledgements: false,
+            log_namespace: LogNamespace::Legacy,
+        }
+    }
+
+    fn received_message(receive_count: &str) -> Message {
+        Message::builder()
+            .message_id("message-id")
+            .body("not gzip")
+            .attributes(
+                MessageSystemAttributeName::ApproximateReceiveCount,
+                receive_count,
+            )
+            .build()
+    }
+
+    #[test]
+    fn handles_poison_messages() {
+        let config = PoisonMessagesConfig {
+            max_receive_attempts: NonZeroU32::new(3).unwrap(),
+            action: PoisonMessageAction::Route,
+        };
+        let source = sqs_source(Some(config.clone()));
+        let mut errors = Vec::new();
+
+        // Messages received fewer times than allowed are left in the queue to be retried.
+        assert!(!source.handle_poison_message(&received_message("2"), None, &None, &mut errors));
+        assert!(errors.is_empty());
+
+        assert!(source.handle_poison_message(&received_message("3"), None, &None, &mut errors));
+        assert_eq!(errors.len(), 1);
+        let log = errors[0].as_log();
+        assert_eq!(log[log_schema().message_key()], "not gzip".into());
+        assert_eq!(log["message_id"], "message-id".into());
+
+        // Deleted poison messages aren't sent to the errors output.
+        let source = sqs_source(Some(PoisonMessagesConfig {
+            action: PoisonMessageAction::Delete,
+            ..config
+        }));
+        let mut errors = Vec::new();
+        assert!(source.handle_poison_message(&received_message("4"), None, &None, &mut errors));
+        assert!(errors.is_empty());
+
+        // Without a configuration, messages are always left in the queue.
+        let source = sqs_source(None);
+        assert!(!source.handle_poison_message(&received_message("10"), None, &None, &mut errors));
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn test_get_receive_count() {
+        let attributes = HashMap::from([(
+            MessageSystemAttributeName::ApproximateReceiveCount,
+            "4".to_string(),
+        )]);
+
+        assert_eq!(get_receive_count(&Some(attributes)), Some(4));
+        assert_eq!(get_receive_count(&None), None);
+    }
+
+    fn message_with_attributes() -> Message {
+        Message::builder()
+            .message_id("message-id")
+            .message_attributes(
+                "team",
+                MessageAttributeValue::builder()
+                    .data_type("String")
+                    .string_value("ops")
+                    .build(),
+            )
+            .build()
+    }
+
+    fn content_encoding(value: &str) -> Option<HashMap<String, MessageAttributeValue>> {
+        Some(HashMap::from([(
+            CONTENT_ENCODING_ATTRIBUTE.to_string(),
+            MessageAttributeValue::builder()
+                .data_type("String")
+                .string_value(value)
+                .build(),
+        )]))
+    }
+
+    #[test]
+    fn test_get_content_encoding() {
+        assert_eq!(
+            get_content_encoding(&content_encoding("gzip")),
+            Compression::Gzip
+        );
+        assert_eq!(
+            get_content_encoding(&content_encoding("zstd")),
+            Compression::Zstd
+        );
+        assert_eq!(
+            get_content_encoding(&content_encoding("br")),
+            Compression::None
+        );
+        assert_eq!(get_content_encoding(&None), Compression::None);
+    }
+
+    #[test]
+    fn test_decompress() {
+        use std::io::Write;
+
+        let message = "hello world ".repeat(10);
+
+        let mut gzip = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        gzip.write_all(message.as_bytes()).unwrap();
+        let gzip = base64::encode(gzip.finish().unwrap());
+        assert_eq!(
+            decompress(&gzip, Compression::Gzip, 1024).unwrap(),
+            message.as_bytes()
+        );
+
+        let zstd = base64::encode(zstd::encode_all(message.as_bytes(), 0).unwrap());
+        assert_eq!(
+            decompress(&zstd, Compression::Zstd, 1024).unwrap(),
+            message.as_bytes()
+        );
+
+        assert_eq!(
+            decompress(&message, Compression::None, 1).unwrap(),
+            message.as_bytes()
+        );
+        assert!(matches!(
+            decompress(&gzip, Compression::Gzip, message.len() - 1),
+            Err(DecompressionError::TooLarge { .. })
+        ));
+        assert!(matches!(
+            decompress("not base64!", Compression::Zstd, 1024),
+            Err(DecompressionError::InvalidBase64 { .. })
+        ));
+    }
+
     #[test]
     fn test_get_timestamp() {
         let attributes = HashMap::from([(
@@ -1,22 +1,31 @@
-use chrono::Utc;
+use bytes::BytesMut;
+use chrono::{TimeZone, Utc};
 use codecs::{
-    decoding::{DeserializerConfig, FramingConfig},
+    decoding::{format::Deserializer as _, DeserializerConfig, FramingConfig},
     StreamDecodingError,
 };
 use fakedata::logs::*;
 use futures::StreamExt;
-use rand::seq::SliceRandom;
+use rand::{rngs::SmallRng, seq::SliceRandom, Rng, SeedableRng};
+use rand_distr::Exp1;
 use snafu::Snafu;
-use std::task::Poll;
-use tokio::time::{self, Duration};
-use tokio_util::codec::FramedRead;
+use std::{collections::BTreeMap, task::Poll};
+use tokio::time::{self, Duration, Instant};
+use tokio_util::codec::{Decoder as _, FramedRead};
 use vector_common::internal_event::{ByteSize, BytesReceived, InternalEventHandle as _, Protocol};
 use vector_config::{configurable_component, NamedComponent};
-use vector_core::{config::LogNamespace, EstimatedJsonEncodedSizeOf};
+use vector_core::{
+    config::LogNamespace, metric_tags, metrics::AgentDDSketch, EstimatedJsonEncodedSizeOf,
+};
 
 use crate::{
     codecs::{Decoder, DecodingConfig},
-    config::{Output, SourceConfig, SourceContext},
+    common::latency,
+    config::{DataType, Output, SourceConfig, SourceContext},
+    event::{
+        metric::{Bucket, Metric, MetricKind, MetricSketch, MetricValue},
+        Event, TraceEvent, Value,
+    },
     internal_events::{DemoLogsEventProcessed, EventsReceived, StreamClosedError},
     serde::{default_decoding, default_framing_message_based},
     shutdown::ShutdownSignal,
@@ -37,8 +46,14 @@ pub struct DemoLogsConfig {
     #[derivative(Default(value = "default_interval()"))]
     pub interval: f64,
 
+    #[configurable(derived)]
+    pub pacing: PacingConfig,
+
     /// The total number of lines to output.
     ///
+    /// With the `metrics` format, this is the total number of batches of metrics to output, and with
+    /// the `traces` format, the total number of traces.
+    ///
     /// By default, the source continuously prints logs (infinitely).
     #[derivative(Default(value = "default_count()"))]
     pub count: usize,
@@ -46,6 +61,36 @@ pub struct DemoLogsConfig {
     #[serde(flatten)]
     pub format: OutputFormat,
 
+    /// The seed used to generate the output.
+    ///
+    /// When set, the lines chosen by the `shuffle` format and the values of the `metrics` and
+    /// `traces` formats are the same on every run, and on every instance sharing the seed.
+    /// Timestamps, and the contents of the other formats, are still random.
+    pub seed: Option<u64>,
+
+    /// The index of the shard of the output generated by this instance, starting at 0.
+    ///
+    /// Combined with `shard_count`, this allows multiple instances to each generate a disjoint
+    /// portion of the same output: instance `i` only outputs the lines whose sequence number `n`
+    /// satisfies `n % shard_count == i`, or with the `metrics` format, only the series whose
+    /// number satisfies the same.
+    pub shard_index: usize,
+
+    /// The number of shards the output is split into.
+    ///
+    /// `count` is the total across all shards.
+    #[derivative(Default(value = "default_shard_count()"))]
+    pub shard_count: usize,
+
+    /// Event field for the monotonic timestamp at which each log event was generated.
+    ///
+    /// The value is a number of nanoseconds, only meaningful within the same Vector instance. Paired
+    /// with the `latency_key` option of the `blackhole` sink, it measures the end-to-end latency of
+    /// events through the topology.
+    ///
+    /// By default, no timestamp is added.
+    pub generation_timestamp_key: Option<String>,
+
     #[configurable(derived)]
     #[derivative(Default(value = "default_framing_message_based()"))]
     pub framing: FramingConfig,
@@ -68,10 +113,257 @@ const fn default_count() -> usize {
     isize::MAX as usize
 }
 
+const fn default_shard_count() -> usize {
+    1
+}
+
+const fn default_cardinality() -> usize {
+    10
+}
+
+const fn default_spans_per_trace() -> usize {
+    5
+}
+
+const fn default_burst_size() -> usize {
+    10
+}
+
+const fn default_burst_period_secs() -> f64 {
+    60.0
+}
+
+const fn default_burst_duty_cycle() -> f64 {
+    0.1
+}
+
+/// Variations of the pauses between batches set by `interval`, to resemble production traffic.
+///
+/// They have no effect when `interval` is `0.0`.
+#[configurable_component]
+#[derive(Clone, Debug, Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct PacingConfig {
+    #[configurable(derived)]
+    pub arrival: Arrival,
+
+    /// The maximum deviation of each pause from its length, as a fraction of that length.
+    ///
+    /// For example, with a `jitter` of `0.2`, each pause lasts between 80% and 120% of its length,
+    /// chosen at random. Must be between `0.0` and `1.0`.
+    pub jitter: f64,
+
+    #[configurable(derived)]
+    pub burst: Option<BurstConfig>,
+}
+
+/// How the pauses between batches are distributed.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Derivative, PartialEq, Eq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Arrival {
+    /// Each pause lasts `interval`.
+    #[derivative(Default)]
+    Fixed,
+
+    /// The pauses follow an exponential distribution with a mean of `interval`, so batches arrive
+    /// as a Poisson process.
+    Poisson,
+}
+
+/// Periodic bursts of output.
+///
+/// Output alternates between bursts, during which `size` batches are output after each pause, and
+/// quiet periods, during which a single batch is.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct BurstConfig {
+    /// The number of batches output after each pause during a burst.
+    #[serde(default = "default_burst_size")]
+    pub size: usize,
+
+    /// The length, in seconds, of a cycle made of a burst followed by a quiet period.
+    #[serde(default = "default_burst_period_secs")]
+    pub period_secs: f64,
+
+    /// The fraction of each cycle spent bursting, between `0.0` and `1.0`.
+    #[serde(default = "default_burst_duty_cycle")]
+    pub duty_cycle: f64,
+}
+
+impl PacingConfig {
+    fn validate(&self) -> Result<(), DemoLogsConfigError> {
+        if !(0.0..=1.0).contains(&self.jitter) {
+            return Err(DemoLogsConfigError::JitterOutOfRange);
+        }
+        if let Some(burst) = &self.burst {
+            if burst.size == 0 {
+                return Err(DemoLogsConfigError::BurstSizeZero);
+            }
+            if !(burst.period_secs > 0.0 && (0.0..=1.0).contains(&burst.duty_cycle)) {
+                return Err(DemoLogsConfigError::BurstCycleInvalid);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Paces the output according to `interval` and a [`PacingConfig`].
+struct Pacer {
+    interval: Duration,
+    pacing: PacingConfig,
+    rng: SmallRng,
+    start: Instant,
+    next: Instant,
+}
+
+impl Pacer {
+    fn new(interval: Duration, pacing: PacingConfig) -> Self {
+        let start = Instant::now();
+        Self {
+            interval,
+            pacing,
+            rng: SmallRng::from_entropy(),
+            start,
+            next: start,
+        }
+    }
+
+    /// Waits for the next batches, and returns how many are output.
+    async fn tick(&mut self) -> usize {
+        time::sleep_until(self.next).await;
+        let now = self.next;
+        let pause = self.pause();
+        self.next += pause;
+        self.batches_at(now)
+    }
+
+    fn pause(&mut self) -> Duration {
+        let mut scale: f64 = match self.pacing.arrival {
+            Arrival::Fixed => 1.0,
+            Arrival::Poisson => self.rng.sample(Exp1),
+        };
+        let jitter = self.pacing.jitter;
+        if jitter > 0.0 {
+            scale *= self.rng.gen_range(1.0 - jitter..=1.0 + jitter);
+        }
+        self.interval.mul_f64(scale)
+    }
+
+    fn batches_at(&self, at: Instant) -> usize {
+        match &self.pacing.burst {
+            Some(burst) => {
+                let phase = at.duration_since(self.start).as_secs_f64() % burst.period_secs;
+                if phase < burst.duty_cycle * burst.period_secs {
+                    burst.size
+                } else {
+                    1
+                }
+            }
+            None => 1,
+        }
+    }
+}
+
+/// The upper limits of the buckets of generated histograms.
+const HISTOGRAM_BUCKETS: [f64; 6] = [0.01, 0.05, 0.1, 0.5, 1.0, 5.0];
+
+/// The number of samples making up each generated histogram and sketch.
+const SAMPLES_PER_DISTRIBUTION: usize = 10;
+
+/// The operations spans are generated for, as their service, name, resource, and type. Traces
+/// start with one of the first `TRACE_ROOT_OPERATIONS`, and call the others.
+const TRACE_OPERATIONS: [(&str, &str, &str, &str); 6] = [
+    ("web-store", "http.request", "GET /products", "web"),
+    ("web-store", "http.request", "POST /checkout", "web"),
+    ("auth", "grpc.server", "auth.Authenticate", "rpc"),
+    (
+        "orders",
+        "postgresql.query",
+        "SELECT * FROM orders WHERE id = ?",
+        "sql",
+    ),
+    ("inventory", "http.request", "GET /stock/{sku}", "http"),
+    ("sessions", "redis.command", "GET", "cache"),
+];
+
+const TRACE_ROOT_OPERATIONS: usize = 2;
+
+/// The range of the durations of generated traces, in nanoseconds.
+const TRACE_DURATION_NANOS: std::ops::Range<i64> = 1_000_000..500_000_000;
+
+/// The probability of each generated span being an error.
+const SPAN_ERROR_PROBABILITY: f64 = 0.05;
+
+/// The number of lines of the randomly generated formats decoded when the source is built.
+const DECODING_CHECK_SAMPLES: usize = 10;
+
 #[derive(Debug, PartialEq, Eq, Snafu)]
 pub enum DemoLogsConfigError {
     #[snafu(display("A non-empty list of lines is required for the shuffle format"))]
     ShuffleDemoLogsItemsEmpty,
+    #[snafu(display("A cardinality of at least 1 is required for the metrics format"))]
+    MetricsCardinalityZero,
+    #[snafu(display("At least 1 span per trace is required for the traces format"))]
+    TraceSpansZero,
+    #[snafu(display("A shard count of at least 1 is required"))]
+    ShardCountZero,
+    #[snafu(display("The pacing jitter must be between 0.0 and 1.0"))]
+    JitterOutOfRange,
+    #[snafu(display("A burst size of at least 1 is required"))]
+    BurstSizeZero,
+    #[snafu(display(
+        "A positive burst period and a burst duty cycle between 0.0 and 1.0 are required"
+    ))]
+    BurstCycleInvalid,
+    #[snafu(display(
+        "Shard index {} is out of range for a shard count of {}",
+        shard_index,
+        shard_count
+    ))]
+    ShardIndexOutOfRange {
+        shard_index: usize,
+        shard_count: usize,
+    },
+    #[snafu(display(
+        "Lines of the {} format can't be decoded with the {} codec: {} (line: {:?})",
+        format,
+        codec,
+        reason,
+        line
+    ))]
+    UndecodableFormat {
+        format: &'static str,
+        codec: String,
+        reason: String,
+        line: String,
+    },
+}
+
+/// The portion of the output generated by this instance.
+#[derive(Clone, Copy, Debug)]
+struct Shard {
+    seed: Option<u64>,
+    index: usize,
+    count: usize,
+}
+
+impl Shard {
+    /// Whether the line or series numbered `n` belongs to this shard.
+    const fn owns(&self, n: usize) -> bool {
+        n % self.count == self.index
+    }
+
+    /// Returns the random number generator for the line or series numbered `n`, which is the same
+    /// on every instance if a seed is set, or is seeded from `entropy` otherwise.
+    fn rng(&self, n: u64, entropy: &mut SmallRng) -> SmallRng {
+        match self.seed {
+            Some(seed) => SmallRng::seed_from_u64(seed.wrapping_add(n)),
+            None => SmallRng::seed_from_u64(entropy.gen()),
+        }
+    }
 }
 
 /// Output format configuration.
@@ -106,28 +398,215 @@ pub enum OutputFormat {
     /// Randomly generated HTTP server logs in [JSON](\(urls.json)) format.
     #[derivative(Default)]
     Json,
+
+    /// Randomly generated metrics, instead of logs.
+    ///
+    /// Each batch holds a counter, a gauge, a histogram, and a sketch for every series. The
+    /// `framing` and `decoding` options do not apply.
+    Metrics {
+        /// The number of distinct series to output for each kind of metric.
+        ///
+        /// Series are told apart by their `series` tag.
+        #[serde(default = "default_cardinality")]
+        cardinality: usize,
+    },
+
+    /// Randomly generated traces, instead of logs.
+    ///
+    /// Each line is replaced by a trace, shaped as those received by the `datadog_agent` source,
+    /// made of a root span and the spans of the operations it calls, with their services,
+    /// resources, durations, and errors. The `framing` and `decoding` options do not apply.
+    Traces {
+        /// The number of spans of each trace, including its root span.
+        #[serde(default = "default_spans_per_trace")]
+        spans_per_trace: usize,
+    },
 }
 
 impl OutputFormat {
-    fn generate_line(&self, n: usize) -> String {
+    fn generate_line(&self, n: usize, rng: &mut impl Rng) -> String {
         emit!(DemoLogsEventProcessed);
 
+        self.line(n, rng)
+    }
+
+    fn line(&self, n: usize, rng: &mut impl Rng) -> String {
         match self {
             Self::Shuffle {
                 sequence,
                 ref lines,
-            } => Self::shuffle_generate(*sequence, lines, n),
+            } => Self::shuffle_generate(*sequence, lines, n, rng),
             Self::ApacheCommon => apache_common_log_line(),
             Self::ApacheError => apache_error_log_line(),
             Self::Syslog => syslog_5424_log_line(),
             Self::BsdSyslog => syslog_3164_log_line(),
             Self::Json => json_log_line(),
+            // Metrics and traces are generated as events, by `generate_metrics` and
+            // `generate_trace`.
+            Self::Metrics { .. } | Self::Traces { .. } => {
+                unreachable!("metrics and traces are not generated as lines")
+            }
         }
     }
 
-    fn shuffle_generate(sequence: bool, lines: &[String], n: usize) -> String {
+    fn generate_metrics(
+        cardinality: usize,
+        n: usize,
+        shard: Shard,
+        entropy: &mut SmallRng,
+    ) -> Vec<Event> {
+        emit!(DemoLogsEventProcessed);
+
+        let now = Utc::now();
+        (0..cardinality)
+            .filter(|&series| shard.owns(series))
+            .flat_map(|series| {
+                // Seeding each series separately keeps its values the same however the series are
+                // split across shards.
+                // Only used as a seed, so it may as well wrap around on large configurations.
+                let id = (n as u64)
+                    .wrapping_mul(cardinality as u64)
+                    .wrapping_add(series as u64);
+                let mut rng = shard.rng(id, entropy);
+                let samples = (0..SAMPLES_PER_DISTRIBUTION)
+                    .map(|_| rng.gen_range(0.0..HISTOGRAM_BUCKETS[HISTOGRAM_BUCKETS.len() - 1]))
+                    .collect::<Vec<f64>>();
+
+                let mut sketch = AgentDDSketch::with_agent_defaults();
+                sketch.insert_many(&samples);
+
+                [
+                    (
+                        "counter_total",
+                        MetricKind::Incremental,
+                        MetricValue::Counter {
+                            value: rng.gen_range(0..100) as f64,
+                        },
+                    ),
+                    (
+                        "gauge",
+                        MetricKind::Absolute,
+                        MetricValue::Gauge {
+                            value: rng.gen_range(0.0..100.0),
+                        },
+                    ),
+                    (
+                        "histogram",
+                        MetricKind::Incremental,
+                        MetricValue::AggregatedHistogram {
+                            // Each bucket counts the samples above the limit of the previous one.
+                            buckets: HISTOGRAM_BUCKETS
+                                .iter()
+                                .enumerate()
+                                .map(|(i, &upper_limit)| {
+                                    let lower_limit = i
+                                        .checked_sub(1)
+                                        .map_or(f64::NEG_INFINITY, |i| HISTOGRAM_BUCKETS[i]);
+                                    Bucket {
+                                        upper_limit,
+                                        count: samples
+                                            .iter()
+                                            .filter(|&&s| s > lower_limit && s <= upper_limit)
+                                            .count()
+                                            as u64,
+                                    }
+                                })
+                                .collect(),
+                            count: samples.len() as u64,
+                            sum: samples.iter().sum(),
+                        },
+                    ),
+                    (
+                        "sketch",
+                        MetricKind::Incremental,
+                        MetricValue::Sketch {
+                            sketch: MetricSketch::AgentDDSketch(sketch),
+                        },
+                    ),
+                ]
+                .into_iter()
+                .map(move |(name, kind, value)| {
+                    Metric::new(name, kind, value)
+                        .with_namespace(Some("demo"))
+                        .with_tags(Some(metric_tags!("series" => series.to_string())))
+                        .with_timestamp(Some(now))
+                        .into()
+                })
+            })
+            .collect()
+    }
+
+    fn generate_trace(spans_per_trace: usize, rng: &mut impl Rng) -> Event {
+        emit!(DemoLogsEventProcessed);
+
+        let trace_id = rng.gen_range(1..i64::MAX);
+        let root_id = rng.gen_range(1..i64::MAX);
+        let duration = rng.gen_range(TRACE_DURATION_NANOS);
+        let start = Utc::now().timestamp_nanos() - duration;
+
+        let spans = (0..spans_per_trace)
+            .map(|index| {
+                let (operation, span_id, parent_id, span_start, span_duration) = if index == 0 {
+                    let operation = rng.gen_range(0..TRACE_ROOT_OPERATIONS);
+                    (operation, root_id, 0, start, duration)
+                } else {
+                    // The operations called start and end within the root span.
+                    let operation = rng.gen_range(TRACE_ROOT_OPERATIONS..TRACE_OPERATIONS.len());
+                    let offset = rng.gen_range(0..duration);
+                    let span_duration = rng.gen_range(1..=duration - offset);
+                    let span_id = rng.gen_range(1..i64::MAX);
+                    (operation, span_id, root_id, start + offset, span_duration)
+                };
+                let (service, name, resource, span_type) = TRACE_OPERATIONS[operation];
+
+                let error = rng.gen_bool(SPAN_ERROR_PROBABILITY);
+                let mut meta = BTreeMap::from([("env".to_string(), Value::from("demo"))]);
+                if error {
+                    meta.insert(
+                        "error.msg".to_string(),
+                        Value::from(format!("{} failed", resource)),
+                    );
+                }
+
+                Value::from(BTreeMap::from([
+                    ("service".to_string(), Value::from(service)),
+                    ("name".to_string(), Value::from(name)),
+                    ("resource".to_string(), Value::from(resource)),
+                    ("type".to_string(), Value::from(span_type)),
+                    ("trace_id".to_string(), Value::from(trace_id)),
+                    ("span_id".to_string(), Value::from(span_id)),
+                    ("parent_id".to_string(), Value::from(parent_id)),
+                    (
+                        "start".to_string(),
+                        Value::from(Utc.timestamp_nanos(span_start)),
+                    ),
+                    ("duration".to_string(), Value::from(span_duration)),
+                    ("error".to_string(), Value::from(error as i64)),
+                    ("meta".to_string(), Value::from(meta)),
+                    (
+                        "metrics".to_string(),
+                        Value::from(BTreeMap::from([(
+                            "_sampling_priority_v1".to_string(),
+                            Value::from(1.0),
+                        )])),
+                    ),
+                ]))
+            })
+            .collect::<Vec<_>>();
+
+        let mut trace = TraceEvent::default();
+        trace.insert("trace_id", trace_id);
+        trace.insert("start_time", Utc.timestamp_nanos(start));
+        trace.insert("end_time", Utc.timestamp_nanos(start + duration));
+        trace.insert("env", "demo");
+        trace.insert("priority", 1_i64);
+        trace.insert("spans", spans);
+        trace.into()
+    }
+
+    fn shuffle_generate(sequence: bool, lines: &[String], n: usize, rng: &mut impl Rng) -> String {
         // unwrap can be called here because `lines` can't be empty
-        let line = lines.choose(&mut rand::thread_rng()).unwrap();
+        let line = lines.choose(rng).unwrap();
 
         if sequence {
             format!("{} {}", n, line)
@@ -146,12 +625,105 @@ impl OutputFormat {
                     Ok(())
                 }
             }
+            Self::Metrics { cardinality: 0 } => Err(DemoLogsConfigError::MetricsCardinalityZero),
+            Self::Traces { spans_per_trace: 0 } => Err(DemoLogsConfigError::TraceSpansZero),
             _ => Ok(()),
         }
     }
+
+    const fn name(&self) -> &'static str {
+        match self {
+            Self::Shuffle { .. } => "shuffle",
+            Self::ApacheCommon => "apache_common",
+            Self::ApacheError => "apache_error",
+            Self::Syslog => "syslog",
+            Self::BsdSyslog => "bsd_syslog",
+            Self::Json => "json",
+            Self::Metrics { .. } => "metrics",
+            Self::Traces { .. } => "traces",
+        }
+    }
+
+    /// Ensures that the generated lines can be decoded with the configured codec.
+    ///
+    /// Every line of `Shuffle` is checked, while the other formats are checked on a sample of
+    /// lines, since they are generated at random.
+    fn validate_decoding(
+        &self,
+        framing: &FramingConfig,
+        decoding: &DeserializerConfig,
+        log_namespace: LogNamespace,
+    ) -> Result<(), DemoLogsConfigError> {
+        // Any line can be decoded as bytes, and metrics and traces are not decoded at all.
+        if matches!(decoding, DeserializerConfig::Bytes)
+            || matches!(self, Self::Metrics { .. } | Self::Traces { .. })
+        {
+            return Ok(());
+        }
+        // The codec is named as in the configuration.
+        let codec: String = serde_json::to_value(decoding)
+            .ok()
+            .and_then(|value| value["codec"].as_str().map(Into::into))
+            .unwrap_or_default();
+
+        let lines: Vec<String> = match self {
+            Self::Shuffle { sequence, lines } => lines
+                .iter()
+                .map(|line| match sequence {
+                    true => format!("0 {}", line),
+                    false => line.clone(),
+                })
+                .collect(),
+            _ => {
+                let mut rng = SmallRng::seed_from_u64(0);
+                (0..DECODING_CHECK_SAMPLES)
+                    .map(|n| self.line(n, &mut rng))
+                    .collect()
+            }
+        };
+
+        let deserializer = decoding.build();
+        for line in lines {
+            let undecodable = |reason: String| DemoLogsConfigError::UndecodableFormat {
+                format: self.name(),
+                codec: codec.clone(),
+                reason,
+                line: line.clone(),
+            };
+
+            let mut framer = framing.build();
+            let mut buffer = BytesMut::from(line.as_bytes());
+            while let Some(frame) = framer
+                .decode_eof(&mut buffer)
+                .map_err(|error| undecodable(error.to_string()))?
+            {
+                deserializer
+                    .parse(frame, log_namespace)
+                    .map_err(|error| undecodable(error.to_string()))?;
+            }
+        }
+        Ok(())
+    }
 }
 
 impl DemoLogsConfig {
+    fn shard(&self) -> Result<Shard, DemoLogsConfigError> {
+        if self.shard_count == 0 {
+            Err(DemoLogsConfigError::ShardCountZero)
+        } else if self.shard_index >= self.shard_count {
+            Err(DemoLogsConfigError::ShardIndexOutOfRange {
+                shard_index: self.shard_index,
+                shard_count: self.shard_count,
+            })
+        } else {
+            Ok(Shard {
+                seed: self.seed,
+                index: self.shard_index,
+                count: self.shard_count,
+            })
+        }
+    }
+
     #[cfg(test)]
     pub fn repeat(
         lines: Vec<String>,
@@ -162,10 +734,15 @@ impl DemoLogsConfig {
         Self {
             count,
             interval,
+            pacing: PacingConfig::default(),
             format: OutputFormat::Shuffle {
                 lines,
                 sequence: false,
             },
+            seed: None,
+            shard_index: 0,
+            shard_count: default_shard_count(),
+            generation_timestamp_key: None,
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             log_namespace,
@@ -175,30 +752,72 @@ impl DemoLogsConfig {
 
 async fn demo_logs_source(
     interval: f64,
+    pacing: PacingConfig,
     count: usize,
     format: OutputFormat,
+    shard: Shard,
+    generation_timestamp_key: Option<String>,
     decoder: Decoder,
     mut shutdown: ShutdownSignal,
     mut out: SourceSender,
     log_namespace: LogNamespace,
 ) -> Result<(), ()> {
-    let maybe_interval: Option<f64> = (interval != 0.0).then_some(interval);
-
-    let mut interval = maybe_interval.map(|i| time::interval(Duration::from_secs_f64(i)));
+    let mut pacer =
+        (interval != 0.0).then(|| Pacer::new(Duration::from_secs_f64(interval), pacing));
+    // The number of batches left to output before the next pause.
+    let mut pending = 0;
 
     let bytes_received = register!(BytesReceived::from(Protocol::NONE));
+    let mut entropy = SmallRng::from_entropy();
 
     for n in 0..count {
+        // Metrics are split across shards by series instead, in every batch.
+        let is_metrics = matches!(format, OutputFormat::Metrics { .. });
+        if !is_metrics && !shard.owns(n) {
+            continue;
+        }
+
         if matches!(futures::poll!(&mut shutdown), Poll::Ready(_)) {
             break;
         }
 
-        if let Some(interval) = &mut interval {
-            interval.tick().await;
+        if let Some(pacer) = &mut pacer {
+            if pending == 0 {
+                pending = pacer.tick().await;
+            }
+            pending -= 1;
         }
         bytes_received.emit(ByteSize(0));
 
-        let line = format.generate_line(n);
+        if let OutputFormat::Metrics { cardinality } = format {
+            let events = OutputFormat::generate_metrics(cardinality, n, shard, &mut entropy);
+            let count = events.len();
+            emit!(EventsReceived {
+                count,
+                byte_size: events.estimated_json_encoded_size_of()
+            });
+            out.send_batch(events).await.map_err(|error| {
+                emit!(StreamClosedError { error, count });
+            })?;
+            continue;
+        }
+
+        if let OutputFormat::Traces { spans_per_trace } = format {
+            let event = OutputFormat::generate_trace(
+                spans_per_trace,
+                &mut shard.rng(n as u64, &mut entropy),
+            );
+            emit!(EventsReceived {
+                count: 1,
+                byte_size: event.estimated_json_encoded_size_of()
+            });
+            out.send_event(event).await.map_err(|error| {
+                emit!(StreamClosedError { error, count: 1 });
+            })?;
+            continue;
+        }
+
+        let line = format.generate_line(n, &mut shard.rng(n as u64, &mut entropy));
 
         let mut stream = FramedRead::new(line.as_bytes(), decoder.clone());
         while let Some(next) = stream.next().await {
@@ -218,6 +837,9 @@ async fn demo_logs_source(
                             DemoLogsConfig::NAME,
                             now,
                         );
+                        if let Some(key) = &generation_timestamp_key {
+                            log.insert(key.as_str(), latency::monotonic_nanos());
+                        }
 
                         event
                     });
@@ -247,12 +869,19 @@ impl SourceConfig for DemoLogsConfig {
         let log_namespace = cx.log_namespace(self.log_namespace);
 
         self.format.validate()?;
+        self.pacing.validate()?;
+        self.format
+            .validate_decoding(&self.framing, &self.decoding, log_namespace)?;
+        let shard = self.shard()?;
         let decoder =
             DecodingConfig::new(self.framing.clone(), self.decoding.clone(), log_namespace).build();
         Ok(Box::pin(demo_logs_source(
             self.interval,
+            self.pacing.clone(),
             self.count,
             self.format.clone(),
+            shard,
+            self.generation_timestamp_key.clone(),
             decoder,
             cx.shutdown,
             cx.out,
@@ -265,6 +894,12 @@ impl SourceConfig for DemoLogsConfig {
         // and is merged here.
         let log_namespace = global_log_namespace.merge(self.log_namespace);
 
+        match self.format {
+            OutputFormat::Metrics { .. } => return vec![Output::default(DataType::Metric)],
+            OutputFormat::Traces { .. } => return vec![Output::default(DataType::Trace)],
+            _ => {}
+        }
+
         let schema_definition = self
             .decoding
             .schema_definition(log_namespace)
@@ -308,10 +943,14 @@ mod tests {
                 LogNamespace::Legacy,
             )
             .build();
+            let shard = config.shard().unwrap();
             demo_logs_source(
                 config.interval,
+                config.pacing,
                 config.count,
                 config.format,
+                shard,
+                config.generation_timestamp_key,
                 decoder,
                 ShutdownSignal::noop(),
                 tx,
@@ -343,6 +982,58 @@ mod tests {
         );
     }
 
+    fn validate_decoding(format: OutputFormat) -> Result<(), DemoLogsConfigError> {
+        format.validate_decoding(
+            &default_framing_message_based(),
+            &DeserializerConfig::Json,
+            LogNamespace::Legacy,
+        )
+    }
+
+    #[test]
+    fn config_rejects_format_undecodable_with_codec() {
+        match validate_decoding(OutputFormat::ApacheCommon) {
+            Err(DemoLogsConfigError::UndecodableFormat { format, codec, .. }) => {
+                assert_eq!(format, "apache_common");
+                assert_eq!(codec, "json");
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        assert_eq!(validate_decoding(OutputFormat::Json), Ok(()));
+        assert_eq!(
+            validate_decoding(OutputFormat::Metrics { cardinality: 1 }),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn config_rejects_shuffle_lines_undecodable_with_codec() {
+        let lines = vec![r#"{"message":"one"}"#.to_string(), "two".to_string()];
+
+        match validate_decoding(OutputFormat::Shuffle {
+            sequence: false,
+            lines: lines.clone(),
+        }) {
+            Err(DemoLogsConfigError::UndecodableFormat { line, .. }) => assert_eq!(line, "two"),
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        // The sequence number makes otherwise valid lines undecodable.
+        assert!(validate_decoding(OutputFormat::Shuffle {
+            sequence: true,
+            lines: lines[..1].to_vec(),
+        })
+        .is_err());
+        assert_eq!(
+            validate_decoding(OutputFormat::Shuffle {
+                sequence: false,
+                lines: lines[..1].to_vec(),
+            }),
+            Ok(())
+        );
+    }
+
     #[tokio::test]
     async fn shuffle_demo_logs_copies_lines() {
         let message_key = log_schema().message_key();
@@ -407,6 +1098,31 @@ mod tests {
         assert_eq!(poll!(rx.next()), Poll::Ready(None));
     }
 
+    #[tokio::test]
+    async fn shuffle_demo_logs_adds_generation_timestamp() {
+        let start = latency::monotonic_nanos();
+        let mut rx = runit(
+            r#"format = "shuffle"
+               lines = ["one", "two"]
+               generation_timestamp_key = "generated_at"
+               count = 2"#,
+        )
+        .await;
+
+        let mut last = start;
+        for _ in 0..2 {
+            let event = match poll!(rx.next()) {
+                Poll::Ready(event) => event.unwrap(),
+                _ => unreachable!(),
+            };
+            let generated_at = *event.as_log()["generated_at"].as_integer().unwrap();
+            assert!(generated_at >= last);
+            last = generated_at;
+        }
+
+        assert_eq!(poll!(rx.next()), Poll::Ready(None));
+    }
+
     #[tokio::test]
     async fn shuffle_demo_logs_obeys_interval() {
         let start = Instant::now();
@@ -427,6 +1143,74 @@ mod tests {
         assert!(duration >= Duration::from_secs(2));
     }
 
+    #[tokio::test]
+    async fn shuffle_demo_logs_bursts() {
+        let start = Instant::now();
+        let mut rx = runit(
+            r#"format = "shuffle"
+               lines = ["one", "two"]
+               count = 5
+               interval = 1.0
+               pacing.burst.size = 3
+               pacing.burst.period_secs = 1.5
+               pacing.burst.duty_cycle = 0.5"#,
+        )
+        .await;
+
+        for _ in 0..5 {
+            assert!(poll!(rx.next()).is_ready());
+        }
+        assert_eq!(poll!(rx.next()), Poll::Ready(None));
+
+        // 3 lines are output at once at 0s, during a burst, 1 at 1s, in a quiet period, and 1 at
+        // 2s, during the next burst.
+        let duration = start.elapsed();
+        assert!(duration >= Duration::from_secs(2));
+        assert!(duration < Duration::from_secs(3));
+    }
+
+    #[test]
+    fn pacing_jitters_pauses() {
+        let pacing: PacingConfig = toml::from_str(
+            r#"arrival = "poisson"
+            jitter = 0.5"#,
+        )
+        .unwrap();
+        let mut pacer = Pacer::new(Duration::from_secs(1), pacing);
+
+        let pauses = (0..1000).map(|_| pacer.pause()).collect::<Vec<_>>();
+        let mean = pauses.iter().sum::<Duration>() / 1000;
+        assert!(
+            pauses
+                .iter()
+                .collect::<std::collections::HashSet<_>>()
+                .len()
+                > 1
+        );
+        assert!(mean > Duration::from_millis(800) && mean < Duration::from_millis(1200));
+    }
+
+    #[test]
+    fn config_pacing_in_range() {
+        let config: DemoLogsConfig = toml::from_str("pacing.jitter = 1.5").unwrap();
+        assert_eq!(
+            config.pacing.validate(),
+            Err(DemoLogsConfigError::JitterOutOfRange)
+        );
+
+        let config: DemoLogsConfig = toml::from_str("pacing.burst.duty_cycle = 2.0").unwrap();
+        assert_eq!(
+            config.pacing.validate(),
+            Err(DemoLogsConfigError::BurstCycleInvalid)
+        );
+
+        let config: DemoLogsConfig = toml::from_str("pacing.burst.size = 0").unwrap();
+        assert_eq!(
+            config.pacing.validate(),
+            Err(DemoLogsConfigError::BurstSizeZero)
+        );
+    }
+
     #[tokio::test]
     async fn apache_common_format_generates_output() {
         let mut rx = runit(
@@ -503,4 +1287,182 @@ mod tests {
         }
         assert_eq!(poll!(rx.next()), Poll::Ready(None));
     }
+
+    #[tokio::test]
+    async fn metrics_format_generates_output() {
+        let mut rx = runit(
+            r#"format = "metrics"
+            cardinality = 3
+            count = 2"#,
+        )
+        .await;
+
+        let mut series = std::collections::HashSet::new();
+        for _ in 0..2 * 3 * 4 {
+            let event = match poll!(rx.next()) {
+                Poll::Ready(event) => event.unwrap(),
+                _ => unreachable!(),
+            };
+            let metric = event.as_metric();
+            assert_eq!(metric.namespace(), Some("demo"));
+            series.insert((
+                metric.name().to_owned(),
+                metric.tag_value("series").unwrap(),
+            ));
+        }
+        assert_eq!(poll!(rx.next()), Poll::Ready(None));
+        assert_eq!(series.len(), 3 * 4);
+    }
+
+    #[test]
+    fn metrics_format_histogram_buckets_sum_to_count() {
+        let shard = Shard {
+            seed: Some(1),
+            index: 0,
+            count: 1,
+        };
+        let mut entropy = SmallRng::seed_from_u64(0);
+        let mut histograms = 0;
+        for n in 0..10 {
+            for event in OutputFormat::generate_metrics(2, n, shard, &mut entropy) {
+                if let MetricValue::AggregatedHistogram { buckets, count, .. } =
+                    event.as_metric().value()
+                {
+                    assert_eq!(
+                        buckets.iter().map(|bucket| bucket.count).sum::<u64>(),
+                        *count
+                    );
+                    histograms += 1;
+                }
+            }
+        }
+        assert_eq!(histograms, 10 * 2);
+    }
+
+    #[test]
+    fn config_metrics_cardinality_not_zero() {
+        let config: DemoLogsConfig = toml::from_str(
+            r#"format = "metrics"
+            cardinality = 0"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.format.validate(),
+            Err(DemoLogsConfigError::MetricsCardinalityZero)
+        );
+    }
+
+    #[tokio::test]
+    async fn traces_format_generates_output() {
+        let traces = runit(
+            r#"format = "traces"
+            spans_per_trace = 4
+            count = 3"#,
+        )
+        .await
+        .collect::<Vec<_>>()
+        .await;
+        assert_eq!(traces.len(), 3);
+
+        for trace in traces {
+            let trace = trace.into_trace();
+            let spans = trace.get("spans").unwrap().as_array().unwrap();
+            assert_eq!(spans.len(), 4);
+
+            let field = |span: &Value, name: &str| span.as_object().unwrap()[name].clone();
+            let root = &spans[0];
+            assert_eq!(field(root, "parent_id"), Value::from(0_i64));
+            assert_eq!(&field(root, "trace_id"), trace.get("trace_id").unwrap());
+            let root_end = match (field(root, "start"), field(root, "duration")) {
+                (Value::Timestamp(start), Value::Integer(duration)) => {
+                    start.timestamp_nanos() + duration
+                }
+                _ => unreachable!(),
+            };
+
+            for span in &spans[1..] {
+                assert_eq!(field(span, "parent_id"), field(root, "span_id"));
+                assert!(matches!(field(span, "service"), Value::Bytes(_)));
+                assert!(matches!(field(span, "resource"), Value::Bytes(_)));
+                match (field(span, "start"), field(span, "duration")) {
+                    (Value::Timestamp(start), Value::Integer(duration)) => {
+                        assert!(duration > 0);
+                        assert!(start.timestamp_nanos() + duration <= root_end);
+                    }
+                    _ => unreachable!(),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn config_traces_spans_not_zero() {
+        let config: DemoLogsConfig = toml::from_str(
+            r#"format = "traces"
+            spans_per_trace = 0"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.format.validate(),
+            Err(DemoLogsConfigError::TraceSpansZero)
+        );
+    }
+
+    async fn collect_messages(config: &str) -> Vec<String> {
+        let message_key = log_schema().message_key();
+        runit(config)
+            .await
+            .map(|event| event.as_log()[&message_key].to_string_lossy())
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn shards_split_seeded_output() {
+        let config = r#"format = "shuffle"
+            lines = ["one", "two", "three", "four"]
+            sequence = true
+            seed = 42
+            count = 10
+            interval = 0.0"#;
+
+        let mut all = collect_messages(config).await;
+        let mut sharded = Vec::new();
+        for shard_index in 0..3 {
+            let shard = collect_messages(&format!(
+                "{}\nshard_index = {}\nshard_count = 3",
+                config, shard_index
+            ))
+            .await;
+            assert!(shard.iter().all(|message| {
+                let n: usize = message.split(' ').next().unwrap().parse().unwrap();
+                n % 3 == shard_index
+            }));
+            sharded.extend(shard);
+        }
+
+        all.sort();
+        sharded.sort();
+        assert_eq!(all.len(), 10);
+        assert_eq!(sharded, all);
+    }
+
+    #[test]
+    fn config_shard_index_in_range() {
+        let config: DemoLogsConfig = toml::from_str(
+            r#"shard_index = 2
+            shard_count = 2"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.shard().unwrap_err(),
+            DemoLogsConfigError::ShardIndexOutOfRange {
+                shard_index: 2,
+                shard_count: 2
+            }
+        );
+    }
 }
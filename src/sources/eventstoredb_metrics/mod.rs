@@ -1,17 +1,30 @@
-use std::time::Duration;
+use std::{
+    collections::HashMap,
+    io::{self, BufReader, Read},
+    time::Duration,
+};
 
-use futures::{FutureExt, StreamExt};
-use http::Uri;
+use futures::{FutureExt, StreamExt, TryStreamExt};
+use http::{uri::PathAndQuery, Uri};
 use hyper::{Body, Request};
+use serde::de::DeserializeOwned;
+use tokio::task::JoinError;
 use tokio_stream::wrappers::IntervalStream;
-use vector_common::internal_event::{ByteSize, BytesReceived, InternalEventHandle as _, Protocol};
+use tokio_util::io::{StreamReader, SyncIoBridge};
+use vector_common::internal_event::{
+    ByteSize, BytesReceived, InternalEventHandle as _, Protocol, Registered,
+};
 use vector_config::configurable_component;
 use vector_core::config::LogNamespace;
 use vector_core::EstimatedJsonEncodedSizeOf;
 
-use self::types::Stats;
+use self::{
+    relabel::{RelabelRuleConfig, RelabelRules},
+    types::{PersistentSubscription, Projections, Stats},
+};
 use crate::{
     config::{self, Output, SourceConfig, SourceContext},
+    event::{metric::MetricSeries, Metric, MetricKind, MetricValue},
     http::HttpClient,
     internal_events::{
         EventStoreDbMetricsHttpError, EventStoreDbStatsParsingError, EventsReceived,
@@ -20,6 +33,7 @@ use crate::{
     tls::TlsSettings,
 };
 
+mod relabel;
 pub mod types;
 
 /// Configuration for the `eventstoredb_metrics` source.
@@ -38,6 +52,34 @@ pub struct EventStoreDbConfig {
     ///
     /// By default, `eventstoredb` is used.
     default_namespace: Option<String>,
+
+    /// Whether or not to emit counters as absolute values.
+    ///
+    /// By default, counters such as `disk_read_bytes_total` are emitted as incremental metrics,
+    /// holding the increase since the previous scrape, so nothing is emitted for them on the first
+    /// scrape. When enabled, they are instead emitted as absolute metrics, holding the totals
+    /// reported by EventStoreDB, as in previous versions.
+    #[serde(default)]
+    absolute_counters: bool,
+
+    /// Whether or not to also collect the status and progress of projections.
+    ///
+    /// These are read from the `/projections/any` endpoint of the host `endpoint` points to.
+    #[serde(default)]
+    collect_projections: bool,
+
+    /// Whether or not to also collect statistics of persistent subscriptions, such as their lag.
+    ///
+    /// These are read from the `/subscriptions` endpoint of the host `endpoint` points to.
+    #[serde(default)]
+    collect_subscriptions: bool,
+
+    /// Rules relabeling the metrics scraped, applied in order before they are emitted.
+    ///
+    /// This allows normalizing the names and values of tags at the source, close to the rest of
+    /// the scrape configuration, rather than in a downstream transform.
+    #[serde(default)]
+    relabel_rules: Vec<RelabelRuleConfig>,
 }
 
 const fn default_scrape_interval_secs() -> u64 {
@@ -53,12 +95,7 @@ impl_generate_config_from_default!(EventStoreDbConfig);
 #[async_trait::async_trait]
 impl SourceConfig for EventStoreDbConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
-        eventstoredb(
-            self.endpoint.clone(),
-            self.scrape_interval_secs,
-            self.default_namespace.clone(),
-            cx,
-        )
+        eventstoredb(self, cx)
     }
 
     fn outputs(&self, _global_log_namespace: LogNamespace) -> Vec<Output> {
@@ -71,68 +108,76 @@ impl SourceConfig for EventStoreDbConfig {
 }
 
 fn eventstoredb(
-    endpoint: String,
-    interval: u64,
-    namespace: Option<String>,
+    config: &EventStoreDbConfig,
     mut cx: SourceContext,
 ) -> crate::Result<super::Source> {
-    let mut ticks = IntervalStream::new(tokio::time::interval(Duration::from_secs(interval)))
-        .take_until(cx.shutdown);
+    let mut ticks = IntervalStream::new(tokio::time::interval(Duration::from_secs(
+        config.scrape_interval_secs,
+    )))
+    .take_until(cx.shutdown);
     let tls_settings = TlsSettings::from_options(&None)?;
     let client = HttpClient::new(tls_settings, &cx.proxy)?;
-    let url: Uri = endpoint.as_str().parse()?;
+    let url: Uri = config.endpoint.as_str().parse()?;
+    let projections_url = config
+        .collect_projections
+        .then(|| with_path(&url, "/projections/any"))
+        .transpose()?;
+    let subscriptions_url = config
+        .collect_subscriptions
+        .then(|| with_path(&url, "/subscriptions"))
+        .transpose()?;
+    let namespace = config.default_namespace.clone();
+    let absolute_counters = config.absolute_counters;
+    let relabel_rules = RelabelRules::new(&config.relabel_rules)?;
 
     let bytes_received = register!(BytesReceived::from(Protocol::HTTP));
+    let mut counters = IncrementalCounters::default();
 
     Ok(Box::pin(
         async move {
             while ticks.next().await.is_some() {
-                let req = Request::get(&url)
-                    .header("content-type", "application/json")
-                    .body(Body::empty())
-                    .expect("Building request should be infallible.");
-
-                match client.send(req).await {
-                    Err(error) => {
-                        emit!(EventStoreDbMetricsHttpError {
-                            error: error.into(),
-                        });
-                        continue;
+                let mut stats = match fetch::<Stats>(&client, &url, &bytes_received).await {
+                    Some(stats) => stats,
+                    None => continue,
+                };
+
+                if let Some(url) = &projections_url {
+                    if let Some(projections) =
+                        fetch::<Projections>(&client, url, &bytes_received).await
+                    {
+                        stats.projections = projections.projections;
+                    }
+                }
+
+                if let Some(url) = &subscriptions_url {
+                    if let Some(subscriptions) =
+                        fetch::<Vec<PersistentSubscription>>(&client, url, &bytes_received).await
+                    {
+                        stats.subscriptions = subscriptions;
                     }
+                }
 
-                    Ok(resp) => {
-                        let bytes = match hyper::body::to_bytes(resp.into_body()).await {
-                            Ok(b) => b,
-                            Err(error) => {
-                                emit!(EventStoreDbMetricsHttpError {
-                                    error: error.into(),
-                                });
-                                continue;
-                            }
-                        };
-                        bytes_received.emit(ByteSize(bytes.len()));
-
-                        match serde_json::from_slice::<Stats>(bytes.as_ref()) {
-                            Err(error) => {
-                                emit!(EventStoreDbStatsParsingError { error });
-                                continue;
-                            }
-
-                            Ok(stats) => {
-                                let metrics = stats.metrics(namespace.clone());
-                                let count = metrics.len();
-                                let byte_size = metrics.estimated_json_encoded_size_of();
-
-                                emit!(EventsReceived { count, byte_size });
-
-                                if let Err(error) = cx.out.send_batch(metrics).await {
-                                    emit!(StreamClosedError { count, error });
-                                    break;
-                                }
-                            }
-                        }
+                let mut metrics = stats.metrics(namespace.clone());
+                if !relabel_rules.is_empty() {
+                    for metric in &mut metrics {
+                        relabel_rules.apply(metric);
                     }
                 }
+                if !absolute_counters {
+                    metrics = metrics
+                        .into_iter()
+                        .filter_map(|metric| counters.make_incremental(metric))
+                        .collect();
+                }
+                let count = metrics.len();
+                let byte_size = metrics.estimated_json_encoded_size_of();
+
+                emit!(EventsReceived { count, byte_size });
+
+                if let Err(error) = cx.out.send_batch(metrics).await {
+                    emit!(StreamClosedError { count, error });
+                    break;
+                }
             }
         }
         .map(Ok)
@@ -140,6 +185,278 @@ fn eventstoredb(
     ))
 }
 
+/// Returns `url` with its path replaced by `path`, to reach other endpoints of the same host.
+fn with_path(url: &Uri, path: &'static str) -> crate::Result<Uri> {
+    let mut parts = url.clone().into_parts();
+    parts.path_and_query = Some(PathAndQuery::from_static(path));
+    Ok(Uri::from_parts(parts)?)
+}
+
+/// Requests `url` and parses the JSON response, emitting an error if either fails.
+async fn fetch<T: DeserializeOwned + Send + 'static>(
+    client: &HttpClient,
+    url: &Uri,
+    bytes_received: &Registered<BytesReceived>,
+) -> Option<T> {
+    let req = Request::get(url)
+        .header("content-type", "application/json")
+        .body(Body::empty())
+        .expect("Building request should be infallible.");
+
+    let resp = match client.send(req).await {
+        Ok(resp) => resp,
+        Err(error) => {
+            emit!(EventStoreDbMetricsHttpError {
+                error: error.into(),
+            });
+            return None;
+        }
+    };
+
+    match parse_body::<T>(resp.into_body()).await {
+        Ok((value, count)) => {
+            bytes_received.emit(ByteSize(count));
+            match value {
+                Ok(value) => Some(value),
+                // Errors reading the body surface as I/O errors of the deserializer.
+                Err(error) if error.is_io() => {
+                    emit!(EventStoreDbMetricsHttpError {
+                        error: error.into(),
+                    });
+                    None
+                }
+                Err(error) => {
+                    emit!(EventStoreDbStatsParsingError { error });
+                    None
+                }
+            }
+        }
+        Err(error) => {
+            emit!(EventStoreDbMetricsHttpError {
+                error: error.into(),
+            });
+            None
+        }
+    }
+}
+
+/// Deserializes `body` as its chunks are received, on a blocking thread, and returns the result
+/// along with the number of bytes read.
+///
+/// The multi-megabyte stats of large clusters are thus never buffered whole, and only the chunk
+/// being parsed is held in memory.
+async fn parse_body<T: DeserializeOwned + Send + 'static>(
+    body: Body,
+) -> Result<(serde_json::Result<T>, usize), JoinError> {
+    let body = SyncIoBridge::new(StreamReader::new(
+        body.map_err(|error| io::Error::new(io::ErrorKind::Other, error)),
+    ));
+    tokio::task::spawn_blocking(move || {
+        let mut reader = CountingReader {
+            inner: body,
+            count: 0,
+        };
+        let value = serde_json::from_reader(BufReader::new(&mut reader));
+        (value, reader.count)
+    })
+    .await
+}
+
+/// Counts the bytes read from a response body.
+struct CountingReader<R> {
+    inner: R,
+    count: usize,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.count += read;
+        Ok(read)
+    }
+}
+
+/// Converts the absolute counters reported by EventStoreDB into incremental ones.
+#[derive(Default)]
+struct IncrementalCounters {
+    previous: HashMap<MetricSeries, f64>,
+}
+
+impl IncrementalCounters {
+    /// Returns the increase of `metric` since it was last seen if it is an absolute counter, or
+    /// `metric` itself otherwise.
+    ///
+    /// Nothing is returned for counters seen for the first time, as their increase is unknown.
+    fn make_incremental(&mut self, metric: Metric) -> Option<Metric> {
+        let value = match (metric.kind(), metric.value()) {
+            (MetricKind::Absolute, MetricValue::Counter { value }) => *value,
+            _ => return Some(metric),
+        };
+
+        let previous = self.previous.insert(metric.series().clone(), value)?;
+        // A counter lower than before means EventStoreDB was restarted, and it counts from zero
+        // again.
+        let delta = if value >= previous {
+            value - previous
+        } else {
+            value
+        };
+        Some(
+            metric
+                .with_value(MetricValue::Counter { value: delta })
+                .into_incremental(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STATS: &str = r#"{
+        "proc": {
+            "id": 1,
+            "mem": 1024,
+            "cpu": 0.5,
+            "threadsCount": 10,
+            "thrownExceptionsRate": 0.0,
+            "diskIo": { "readBytes": 100, "writtenBytes": 200, "readOps": 10, "writeOps": 20 }
+        },
+        "sys": {
+            "freeMem": 2048,
+            "loadavg": { "1m": 0.1, "5m": 0.2, "15m": 0.3 }
+        }
+    }"#;
+
+    fn stats(read_bytes: usize) -> Vec<Metric> {
+        let mut stats: Stats = serde_json::from_str(STATS).unwrap();
+        stats.proc.disk_io.read_bytes = read_bytes;
+        stats.metrics(None)
+    }
+
+    fn find<'a>(metrics: &'a [Metric], name: &str) -> Option<&'a Metric> {
+        metrics.iter().find(|metric| metric.name() == name)
+    }
+
+    #[test]
+    fn emits_counters_as_incremental() {
+        let mut counters = IncrementalCounters::default();
+        let mut scrape = |read_bytes| {
+            stats(read_bytes)
+                .into_iter()
+                .filter_map(|metric| counters.make_incremental(metric))
+                .collect::<Vec<_>>()
+        };
+
+        // Counters need a previous value to be emitted, gauges do not.
+        let first = scrape(100);
+        assert!(find(&first, "disk_read_bytes_total").is_none());
+        let gauge = find(&first, "process_memory_used_bytes").unwrap();
+        assert_eq!(gauge.kind(), MetricKind::Absolute);
+        assert_eq!(gauge.value(), &MetricValue::Gauge { value: 1024.0 });
+
+        let second = scrape(150);
+        let counter = find(&second, "disk_read_bytes_total").unwrap();
+        assert_eq!(counter.kind(), MetricKind::Incremental);
+        assert_eq!(counter.value(), &MetricValue::Counter { value: 50.0 });
+        let unchanged = find(&second, "disk_written_bytes_total").unwrap();
+        assert_eq!(unchanged.value(), &MetricValue::Counter { value: 0.0 });
+
+        // EventStoreDB was restarted.
+        let third = scrape(30);
+        let counter = find(&third, "disk_read_bytes_total").unwrap();
+        assert_eq!(counter.value(), &MetricValue::Counter { value: 30.0 });
+    }
+
+    #[test]
+    fn emits_projection_and_subscription_gauges() {
+        let mut stats: Stats = serde_json::from_str(STATS).unwrap();
+        let projections: Projections = serde_json::from_str(
+            r#"{"projections": [
+                {"name": "$by_category", "status": "Running", "progress": 42.5, "bufferedEvents": 3},
+                {"name": "$streams", "status": "Stopped", "progress": 100.0}
+            ]}"#,
+        )
+        .unwrap();
+        stats.projections = projections.projections;
+        stats.subscriptions = serde_json::from_str(
+            r#"[{
+                "eventStreamId": "orders",
+                "groupName": "billing",
+                "status": "Live",
+                "totalItemsProcessed": 90,
+                "lastProcessedEventNumber": 89,
+                "lastKnownEventNumber": 99,
+                "connectionCount": 2,
+                "totalInFlightMessages": 5
+            }]"#,
+        )
+        .unwrap();
+
+        let metrics = stats.metrics(None);
+        let tagged = |name: &str, tag: &str, value: &str| {
+            metrics
+                .iter()
+                .find(|metric| {
+                    metric.name() == name && metric.tag_value(tag).as_deref() == Some(value)
+                })
+                .unwrap()
+                .value()
+                .clone()
+        };
+
+        assert_eq!(
+            tagged("projection_progress_percent", "projection", "$by_category"),
+            MetricValue::Gauge { value: 42.5 }
+        );
+        assert_eq!(
+            tagged("projection_running", "projection", "$by_category"),
+            MetricValue::Gauge { value: 1.0 }
+        );
+        assert_eq!(
+            tagged("projection_running", "projection", "$streams"),
+            MetricValue::Gauge { value: 0.0 }
+        );
+        assert_eq!(
+            tagged("subscription_lag_events", "group", "billing"),
+            MetricValue::Gauge { value: 10.0 }
+        );
+        assert_eq!(
+            tagged("subscription_items_processed_total", "stream", "orders"),
+            MetricValue::Counter { value: 90.0 }
+        );
+    }
+
+    #[tokio::test]
+    async fn parses_chunked_bodies() {
+        let chunked = |chunks: Vec<&'static str>| {
+            Body::wrap_stream(futures::stream::iter(
+                chunks.into_iter().map(Ok::<_, std::io::Error>),
+            ))
+        };
+
+        let (projections, count) =
+            parse_body::<Projections>(chunked(vec!["{\"projections\"", ": []}"]))
+                .await
+                .unwrap();
+        assert!(projections.unwrap().projections.is_empty());
+        assert_eq!(count, 19);
+
+        let (projections, _) = parse_body::<Projections>(chunked(vec!["{\"projections\"", ": ["]))
+            .await
+            .unwrap();
+        assert!(!projections.unwrap_err().is_io());
+    }
+
+    #[test]
+    fn emits_absolute_counters() {
+        let metrics = stats(100);
+        let counter = find(&metrics, "disk_read_bytes_total").unwrap();
+        assert_eq!(counter.kind(), MetricKind::Absolute);
+        assert_eq!(counter.value(), &MetricValue::Counter { value: 100.0 });
+    }
+}
+
 #[cfg(all(test, feature = "eventstoredb_metrics-integration-tests"))]
 mod integration_tests {
     use tokio::time::Duration;
@@ -155,6 +472,10 @@ mod integration_tests {
             endpoint: EVENTSTOREDB_SCRAPE_ADDRESS.to_owned(),
             scrape_interval_secs: 1,
             default_namespace: None,
+            absolute_counters: false,
+            collect_projections: false,
+            collect_subscriptions: false,
+            relabel_rules: vec![],
         };
 
         let events =
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+
+use regex::Regex;
+use vector_config::configurable_component;
+
+use crate::event::Metric;
+
+/// A rule relabeling the metrics scraped, before they are emitted.
+///
+/// Within a rule, tags are renamed first, then removed, and finally added.
+#[configurable_component]
+#[derive(Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct RelabelRuleConfig {
+    /// A regular expression the name of a metric must match for the rule to apply to it.
+    ///
+    /// The expression is anchored, so that it must match the whole name, excluding the namespace.
+    /// The rule applies to all metrics if unset.
+    #[configurable(metadata(docs::examples = "projection_.*"))]
+    pub name_regex: Option<String>,
+
+    /// Tags to rename, from their current name to their new one.
+    #[serde(default)]
+    #[configurable(metadata(docs::additional_props_description = "The new name of the tag."))]
+    pub rename_tags: HashMap<String, String>,
+
+    /// Tags to remove.
+    #[serde(default)]
+    pub remove_tags: Vec<String>,
+
+    /// Tags to add, replacing the value of those already present.
+    #[serde(default)]
+    #[configurable(metadata(docs::additional_props_description = "The value of the tag."))]
+    pub add_tags: HashMap<String, String>,
+}
+
+struct RelabelRule {
+    name: Option<Regex>,
+    rename_tags: Vec<(String, String)>,
+    remove_tags: Vec<String>,
+    add_tags: Vec<(String, String)>,
+}
+
+/// The relabeling rules of the source, applied in order.
+pub(super) struct RelabelRules(Vec<RelabelRule>);
+
+impl RelabelRules {
+    pub(super) fn new(configs: &[RelabelRuleConfig]) -> crate::Result<Self> {
+        configs
+            .iter()
+            .enumerate()
+            .map(|(index, config)| -> crate::Result<RelabelRule> {
+                let name = config
+                    .name_regex
+                    .as_ref()
+                    .map(|pattern| Regex::new(&format!("^(?:{})$", pattern)))
+                    .transpose()
+                    .map_err(|error| {
+                        format!("Invalid `name_regex` of relabel rule {}: {}", index, error)
+                    })?;
+                Ok(RelabelRule {
+                    name,
+                    rename_tags: config
+                        .rename_tags
+                        .iter()
+                        .map(|(from, to)| (from.clone(), to.clone()))
+                        .collect(),
+                    remove_tags: config.remove_tags.clone(),
+                    add_tags: config
+                        .add_tags
+                        .iter()
+                        .map(|(name, value)| (name.clone(), value.clone()))
+                        .collect(),
+                })
+            })
+            .collect::<crate::Result<Vec<_>>>()
+            .map(Self)
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Applies the rules whose `name_regex` matches the name of `metric` to it.
+    pub(super) fn apply(&self, metric: &mut Metric) {
+        for rule in &self.0 {
+            if let Some(name) = &rule.name {
+                if !name.is_match(metric.name()) {
+                    continue;
+                }
+            }
+
+            for (from, to) in &rule.rename_tags {
+                if let Some(value) = metric.remove_tag(from) {
+                    metric.replace_tag(to.clone(), value);
+                }
+            }
+            for name in &rule.remove_tags {
+                metric.remove_tag(name);
+            }
+            for (name, value) in &rule.add_tags {
+                metric.replace_tag(name.clone(), value.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{MetricKind, MetricValue};
+
+    fn gauge(name: &str) -> Metric {
+        Metric::new(
+            name,
+            MetricKind::Absolute,
+            MetricValue::Gauge { value: 1.0 },
+        )
+        .with_tags(Some(vector_core::metric_tags!(
+            "projection" => "$streams",
+            "status" => "Running",
+        )))
+    }
+
+    #[test]
+    fn applies_matching_rules_in_order() {
+        let rules = RelabelRules::new(&[
+            RelabelRuleConfig {
+                name_regex: Some("projection_.*".to_string()),
+                rename_tags: HashMap::from([("projection".to_string(), "name".to_string())]),
+                remove_tags: vec!["status".to_string()],
+                ..Default::default()
+            },
+            RelabelRuleConfig {
+                add_tags: HashMap::from([("cluster".to_string(), "primary".to_string())]),
+                ..Default::default()
+            },
+        ])
+        .unwrap();
+
+        let mut projection = gauge("projection_running");
+        rules.apply(&mut projection);
+        assert_eq!(projection.tag_value("name"), Some("$streams".to_string()));
+        assert_eq!(projection.tag_value("projection"), None);
+        assert_eq!(projection.tag_value("status"), None);
+        assert_eq!(projection.tag_value("cluster"), Some("primary".to_string()));
+
+        // The name must match as a whole.
+        let mut other = gauge("running_projection_count");
+        rules.apply(&mut other);
+        assert_eq!(other.tag_value("projection"), Some("$streams".to_string()));
+        assert_eq!(other.tag_value("status"), Some("Running".to_string()));
+        assert_eq!(other.tag_value("cluster"), Some("primary".to_string()));
+    }
+
+    #[test]
+    fn rejects_invalid_name_regex() {
+        let error = RelabelRules::new(&[RelabelRuleConfig {
+            name_regex: Some("projection_(".to_string()),
+            ..Default::default()
+        }])
+        .err()
+        .unwrap();
+        assert!(error.to_string().contains("relabel rule 0"));
+    }
+}
@@ -10,6 +10,12 @@ use crate::event::{Metric, MetricKind, MetricTags, MetricValue};
 pub struct Stats {
     pub proc: Proc,
     pub sys: Sys,
+    /// Filled in from the `/projections/any` endpoint, if collected.
+    #[serde(skip)]
+    pub projections: Vec<Projection>,
+    /// Filled in from the `/subscriptions` endpoint, if collected.
+    #[serde(skip)]
+    pub subscriptions: Vec<PersistentSubscription>,
 }
 
 impl Stats {
@@ -136,7 +142,75 @@ impl Stats {
                         value: drive.stats.used_bytes as f64,
                     },
                 )
-                .with_namespace(Some(namespace))
+                .with_namespace(Some(namespace.clone()))
+                .with_tags(Some(tags))
+                .with_timestamp(Some(now)),
+            );
+        }
+
+        for projection in &self.projections {
+            let mut tags = MetricTags::default();
+            tags.replace("projection".to_string(), projection.name.clone());
+
+            let gauges = [
+                ("projection_progress_percent", projection.progress),
+                (
+                    "projection_running",
+                    if projection.status.starts_with("Running") {
+                        1.0
+                    } else {
+                        0.0
+                    },
+                ),
+                (
+                    "projection_buffered_events",
+                    projection.buffered_events as f64,
+                ),
+            ];
+            for (name, value) in gauges {
+                result.push(
+                    Metric::new(name, MetricKind::Absolute, MetricValue::Gauge { value })
+                        .with_namespace(Some(namespace.clone()))
+                        .with_tags(Some(tags.clone()))
+                        .with_timestamp(Some(now)),
+                );
+            }
+        }
+
+        for subscription in &self.subscriptions {
+            let mut tags = MetricTags::default();
+            tags.replace("stream".to_string(), subscription.event_stream_id.clone());
+            tags.replace("group".to_string(), subscription.group_name.clone());
+
+            let gauges = [
+                ("subscription_lag_events", subscription.lag() as f64),
+                (
+                    "subscription_connections",
+                    subscription.connection_count as f64,
+                ),
+                (
+                    "subscription_in_flight_messages",
+                    subscription.total_in_flight_messages as f64,
+                ),
+            ];
+            for (name, value) in gauges {
+                result.push(
+                    Metric::new(name, MetricKind::Absolute, MetricValue::Gauge { value })
+                        .with_namespace(Some(namespace.clone()))
+                        .with_tags(Some(tags.clone()))
+                        .with_timestamp(Some(now)),
+                );
+            }
+
+            result.push(
+                Metric::new(
+                    "subscription_items_processed_total",
+                    MetricKind::Absolute,
+                    MetricValue::Counter {
+                        value: subscription.total_items_processed as f64,
+                    },
+                )
+                .with_namespace(Some(namespace.clone()))
                 .with_tags(Some(tags))
                 .with_timestamp(Some(now)),
             );
@@ -146,6 +220,53 @@ impl Stats {
     }
 }
 
+/// Response of the `/projections/any` endpoint.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Projections {
+    pub projections: Vec<Projection>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Projection {
+    pub name: String,
+    pub status: String,
+    pub progress: f64,
+    #[serde(default)]
+    pub buffered_events: i64,
+}
+
+/// Entry of the response of the `/subscriptions` endpoint.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PersistentSubscription {
+    pub event_stream_id: String,
+    pub group_name: String,
+    #[serde(default)]
+    pub total_items_processed: i64,
+    #[serde(default = "no_event_number")]
+    pub last_processed_event_number: i64,
+    #[serde(default = "no_event_number")]
+    pub last_known_event_number: i64,
+    #[serde(default)]
+    pub connection_count: i64,
+    #[serde(default)]
+    pub total_in_flight_messages: i64,
+}
+
+impl PersistentSubscription {
+    /// The number of events in the stream the subscription hasn't processed yet.
+    pub fn lag(&self) -> i64 {
+        (self.last_known_event_number - self.last_processed_event_number).max(0)
+    }
+}
+
+/// EventStoreDB reports `-1` for event numbers when there is no such event.
+const fn no_event_number() -> i64 {
+    -1
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Proc {
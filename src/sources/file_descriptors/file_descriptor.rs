@@ -1,17 +1,45 @@
-use std::fs::File;
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
 use std::io;
-use std::os::unix::io::FromRawFd;
+use std::os::unix::{fs::FileTypeExt, io::FromRawFd};
+use std::path::{Path, PathBuf};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use std::time::Duration;
 
-use super::{outputs, FileDescriptorConfig};
-use codecs::decoding::{DeserializerConfig, FramingConfig};
+use super::{
+    listen_fds::{ListenFdSelector, ListenFds},
+    outputs, process_stream, read_credits, read_from_fd,
+    validation::{FrameValidationConfig, ValidatingFramer},
+    CreditReceiver, FileDescriptorConfig, FrameGaps, Sender,
+};
+use codecs::decoding::{DeserializerConfig, Framer, FramingConfig};
+use futures::{channel::mpsc, future};
 use indoc::indoc;
+use tracing::Span;
 use vector_config::{configurable_component, NamedComponent};
 use vector_core::config::LogNamespace;
 
 use crate::{
-    config::{GenerateConfig, Output, Resource, SourceConfig, SourceContext},
-    serde::default_decoding,
+    codecs::Decoder,
+    config::{
+        log_schema, GenerateConfig, Output, Resource, SourceAcknowledgementsConfig, SourceConfig,
+        SourceContext,
+    },
+    internal_events::FileDescriptorReadError,
+    serde::{bool_or_struct, default_decoding},
+    shutdown::ShutdownSignal,
+    SourceSender,
 };
+
+/// How long to wait before trying to open a named pipe again after failing to.
+const REOPEN_DELAY: Duration = Duration::from_secs(1);
+
+/// How often readers of named pipes are woken up while the source shuts down.
+const WAKE_INTERVAL: Duration = Duration::from_millis(100);
+
 /// Configuration for the `file_descriptor` source.
 #[configurable_component(source("file_descriptor"))]
 #[derive(Clone, Debug)]
@@ -38,7 +66,36 @@ pub struct FileDescriptorSourceConfig {
     pub decoding: DeserializerConfig,
 
     /// The file descriptor number to read from.
-    pub fd: u32,
+    ///
+    /// Exactly one of `fd`, `pipes`, and `listen_fds` must be set.
+    pub fd: Option<u32>,
+
+    /// The named pipes (FIFOs) to read from.
+    ///
+    /// Each pipe is read independently, and is reopened whenever its writer closes it, so writers
+    /// can restart or recreate the pipe without the source stopping.
+    ///
+    /// Exactly one of `fd`, `pipes`, and `listen_fds` must be set.
+    #[serde(default)]
+    pub pipes: Vec<NamedPipeConfig>,
+
+    /// The file descriptors passed by systemd socket activation to read from.
+    ///
+    /// Each file descriptor is selected either by its index among those passed, starting at 0, or
+    /// by the name set with `FileDescriptorName=` in the socket unit. This allows reading from
+    /// pipes set up by systemd, such as those of `ListenFIFO=`, without redirecting them to a
+    /// fixed file descriptor number.
+    ///
+    /// Exactly one of `fd`, `pipes`, and `listen_fds` must be set.
+    #[serde(default)]
+    pub listen_fds: Vec<ListenFdSelector>,
+
+    #[configurable(derived)]
+    pub validation: Option<FrameValidationConfig>,
+
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    pub acknowledgements: SourceAcknowledgementsConfig,
 
     /// The namespace to use for logs. This overrides the global setting.
     #[configurable(metadata(docs::hidden))]
@@ -46,6 +103,25 @@ pub struct FileDescriptorSourceConfig {
     log_namespace: Option<bool>,
 }
 
+/// Configuration for a named pipe read by the `file_descriptor` source.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct NamedPipeConfig {
+    /// The path of the named pipe.
+    pub path: PathBuf,
+
+    /// Overrides the name of the log field used to add the current hostname to events read from
+    /// this pipe.
+    ///
+    /// By default, the `host_key` option of the source is used.
+    pub host_key: Option<String>,
+
+    /// Fields added to each event read from this pipe.
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+}
+
 impl FileDescriptorConfig for FileDescriptorSourceConfig {
     fn host_key(&self) -> Option<String> {
         self.host_key.clone()
@@ -59,8 +135,230 @@ impl FileDescriptorConfig for FileDescriptorSourceConfig {
         self.decoding.clone()
     }
 
+    fn framed_decoder(&self, log_namespace: LogNamespace) -> (Decoder, Option<FrameGaps>) {
+        match &self.validation {
+            Some(validation) => {
+                let frame_gaps = validation.insert_gap_markers.then(FrameGaps::default);
+                let framer = ValidatingFramer::new(validation, frame_gaps.clone());
+                let decoder = Decoder::new(Framer::Boxed(Box::new(framer)), self.decoding.build())
+                    .with_log_namespace(log_namespace);
+                (decoder, frame_gaps)
+            }
+            None => (self.decoder(log_namespace), None),
+        }
+    }
+
     fn description(&self) -> String {
-        format!("file descriptor {}", self.fd)
+        match self.fd {
+            Some(fd) => format!("file descriptor {}", fd),
+            None if self.pipes.is_empty() => "systemd file descriptors".to_string(),
+            None => "named pipes".to_string(),
+        }
+    }
+}
+
+/// An input of the source read on its own thread.
+struct ThreadedInput {
+    host_key: Option<String>,
+    tags: BTreeMap<String, String>,
+    read: Box<dyn FnOnce(Sender, Option<CreditReceiver>) + Send>,
+}
+
+impl FileDescriptorSourceConfig {
+    fn pipes_source(
+        &self,
+        shutdown: ShutdownSignal,
+        out: SourceSender,
+        log_namespace: LogNamespace,
+        acknowledgements: bool,
+    ) -> crate::Result<crate::sources::Source> {
+        // Opening a named pipe blocks until a writer opens it, so this is also done on the thread.
+        let readers = self
+            .pipes
+            .iter()
+            .map(|pipe| PipeReader {
+                path: pipe.path.clone(),
+                stopping: Arc::new(AtomicBool::new(false)),
+            })
+            .collect::<Vec<_>>();
+        let inputs = self
+            .pipes
+            .iter()
+            .zip(&readers)
+            .map(|(pipe, reader)| {
+                let reader = reader.clone();
+                ThreadedInput {
+                    host_key: pipe.host_key.clone(),
+                    tags: pipe.tags.clone(),
+                    read: Box::new(move |sender, credits| {
+                        info!("Capturing named pipe {:?}.", reader.path);
+                        reader.read(sender, credits);
+                    }),
+                }
+            })
+            .collect();
+
+        let mut stop = shutdown.clone();
+        let source =
+            self.threaded_source(inputs, shutdown, out, log_namespace, acknowledgements)?;
+        Ok(Box::pin(async move {
+            tokio::pin!(source);
+            tokio::select! {
+                result = &mut source => return result,
+                _ = &mut stop => {}
+            }
+            for reader in &readers {
+                reader.stopping.store(true, Ordering::Relaxed);
+            }
+            // A reader may be about to wait for a writer, so keep waking them until they are done.
+            loop {
+                for reader in &readers {
+                    reader.wake();
+                }
+                tokio::select! {
+                    result = &mut source => return result,
+                    _ = tokio::time::sleep(WAKE_INTERVAL) => {}
+                }
+            }
+        }))
+    }
+
+    fn listen_fds_source(
+        &self,
+        shutdown: ShutdownSignal,
+        out: SourceSender,
+        log_namespace: LogNamespace,
+        acknowledgements: bool,
+    ) -> crate::Result<crate::sources::Source> {
+        let listen_fds = ListenFds::from_env()?;
+        let inputs = listen_fds
+            .select(&self.listen_fds)?
+            .into_iter()
+            .map(|(fd, name)| {
+                let name = name.map(ToString::to_string);
+                ThreadedInput {
+                    host_key: None,
+                    tags: BTreeMap::new(),
+                    read: Box::new(move |sender, credits| {
+                        info!(
+                            message = "Capturing file descriptor passed by systemd.",
+                            fd,
+                            name = ?name,
+                        );
+                        let file = unsafe { File::from_raw_fd(fd) };
+                        read_from_fd(io::BufReader::new(file), sender, credits.as_ref());
+                    }),
+                }
+            })
+            .collect();
+
+        self.threaded_source(inputs, shutdown, out, log_namespace, acknowledgements)
+    }
+
+    /// Reads each of `inputs` on a background thread with blocking I/O, as for a single file
+    /// descriptor, and decodes them independently.
+    fn threaded_source(
+        &self,
+        inputs: Vec<ThreadedInput>,
+        shutdown: ShutdownSignal,
+        out: SourceSender,
+        log_namespace: LogNamespace,
+        acknowledgements: bool,
+    ) -> crate::Result<crate::sources::Source> {
+        let hostname = crate::get_hostname().ok();
+
+        let streams = inputs
+            .into_iter()
+            .map(|input| {
+                let host_key = input
+                    .host_key
+                    .or_else(|| self.host_key.clone())
+                    .unwrap_or_else(|| log_schema().host_key().to_string());
+
+                let (decoder, frame_gaps) = self.framed_decoder(log_namespace);
+                let (sender, receiver) = mpsc::channel(1024);
+                let (credits_tx, credits_rx) = read_credits(acknowledgements);
+
+                let read = input.read;
+                let span = Span::current();
+                std::thread::spawn(move || {
+                    let _entered = span.enter();
+                    read(sender, credits_rx);
+                });
+
+                process_stream(
+                    receiver,
+                    decoder,
+                    out.clone(),
+                    shutdown.clone(),
+                    host_key,
+                    Self::NAME,
+                    hostname.clone(),
+                    input.tags,
+                    frame_gaps,
+                    log_namespace,
+                    credits_tx,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        Ok(Box::pin(async move {
+            future::join_all(streams)
+                .await
+                .into_iter()
+                .collect::<Result<Vec<()>, ()>>()
+                .map(|_| ())
+        }))
+    }
+}
+
+/// Reads from a named pipe on a background thread, reopening it whenever its writer closes it.
+#[derive(Clone)]
+struct PipeReader {
+    path: PathBuf,
+    stopping: Arc<AtomicBool>,
+}
+
+impl PipeReader {
+    /// Reads from the named pipe, reopening it whenever its writer closes it, until the receiving
+    /// end of `sender` is dropped or the reader is stopped.
+    fn read(&self, sender: Sender, credits: Option<CreditReceiver>) {
+        while !sender.is_closed() && !self.stopping.load(Ordering::Relaxed) {
+            read_from_pipe(&self.path, sender.clone(), credits.as_ref());
+        }
+    }
+
+    /// Wakes the reader if it is waiting for a writer to open the pipe.
+    ///
+    /// Opening a named pipe for both reading and writing doesn't wait for a reader, and counts as
+    /// a writer, so the waiting reader gets the pipe and reads its end right away.
+    fn wake(&self) {
+        let _ = OpenOptions::new().read(true).write(true).open(&self.path);
+    }
+}
+
+/// Reads from the named pipe at `path` until its writer closes it.
+fn read_from_pipe(path: &Path, sender: Sender, credits: Option<&CreditReceiver>) {
+    let file = File::open(path).and_then(|file| {
+        if file.metadata()?.file_type().is_fifo() {
+            Ok(file)
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{:?} is not a named pipe", path),
+            ))
+        }
+    });
+
+    match file {
+        Ok(file) => {
+            read_from_fd(io::BufReader::new(file), sender, credits);
+            debug!(message = "Named pipe closed by its writer, reopening.", path = ?path);
+        }
+        Err(error) => {
+            emit!(FileDescriptorReadError { error });
+            std::thread::sleep(REOPEN_DELAY);
+        }
     }
 }
 
@@ -76,24 +374,62 @@ impl GenerateConfig for FileDescriptorSourceConfig {
 #[async_trait::async_trait]
 impl SourceConfig for FileDescriptorSourceConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<crate::sources::Source> {
-        let pipe = io::BufReader::new(unsafe { File::from_raw_fd(self.fd as i32) });
         let log_namespace = cx.log_namespace(self.log_namespace);
-
-        self.source(pipe, cx.shutdown, cx.out, log_namespace)
+        let acknowledgements = cx.do_acknowledgements(self.acknowledgements);
+
+        if self.validation.is_some() && self.framing.is_some() {
+            return Err("`framing` can't be set along with `validation`".into());
+        }
+
+        match (self.fd, self.pipes.is_empty(), self.listen_fds.is_empty()) {
+            (Some(fd), true, true) => {
+                let pipe = io::BufReader::new(unsafe { File::from_raw_fd(fd as i32) });
+                self.source(pipe, cx.shutdown, cx.out, log_namespace, acknowledgements)
+            }
+            (None, false, true) => {
+                self.pipes_source(cx.shutdown, cx.out, log_namespace, acknowledgements)
+            }
+            (None, true, false) => {
+                self.listen_fds_source(cx.shutdown, cx.out, log_namespace, acknowledgements)
+            }
+            _ => Err("exactly one of `fd`, `pipes`, and `listen_fds` must be set".into()),
+        }
     }
 
     fn outputs(&self, global_log_namespace: LogNamespace) -> Vec<Output> {
         let log_namespace = global_log_namespace.merge(self.log_namespace);
 
-        outputs(log_namespace, &self.host_key, &self.decoding, Self::NAME)
+        let host_keys = if self.pipes.is_empty() {
+            vec![self.host_key.clone()]
+        } else {
+            self.pipes
+                .iter()
+                .map(|pipe| pipe.host_key.clone().or_else(|| self.host_key.clone()))
+                .collect()
+        };
+        let mut tag_keys = self
+            .pipes
+            .iter()
+            .flat_map(|pipe| pipe.tags.keys().map(String::as_str))
+            .collect::<Vec<_>>();
+        tag_keys.sort_unstable();
+        tag_keys.dedup();
+
+        outputs(
+            log_namespace,
+            &host_keys,
+            &tag_keys,
+            &self.decoding,
+            Self::NAME,
+        )
     }
 
     fn resources(&self) -> Vec<Resource> {
-        vec![Resource::Fd(self.fd)]
+        self.fd.into_iter().map(Resource::Fd).collect()
     }
 
     fn can_acknowledge(&self) -> bool {
-        false
+        true
     }
 }
 
@@ -102,9 +438,11 @@ mod tests {
     use lookup::path;
     use nix::unistd::{close, pipe, write};
 
+    use std::time::Instant;
+
     use super::*;
     use crate::{
-        config::log_schema,
+        config::{log_schema, ComponentKey},
         test_util::components::{
             assert_source_compliance, assert_source_error, COMPONENT_ERROR_TAGS, SOURCE_TAGS,
         },
@@ -127,7 +465,11 @@ mod tests {
                 host_key: Default::default(),
                 framing: None,
                 decoding: default_decoding(),
-                fd: read_fd as u32,
+                fd: Some(read_fd as u32),
+                pipes: Vec::new(),
+                listen_fds: Vec::new(),
+                validation: None,
+                acknowledgements: Default::default(),
                 log_namespace: None,
             };
 
@@ -171,7 +513,11 @@ mod tests {
                 host_key: Default::default(),
                 framing: None,
                 decoding: default_decoding(),
-                fd: read_fd as u32,
+                fd: Some(read_fd as u32),
+                pipes: Vec::new(),
+                listen_fds: Vec::new(),
+                validation: None,
+                acknowledgements: Default::default(),
                 log_namespace: Some(true),
             };
 
@@ -210,6 +556,184 @@ mod tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn file_descriptor_reopens_named_pipes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pipe");
+        assert!(std::process::Command::new("mkfifo")
+            .arg(&path)
+            .status()
+            .unwrap()
+            .success());
+
+        let (tx, mut stream) = SourceSender::new_test();
+        let config = FileDescriptorSourceConfig {
+            max_length: crate::serde::default_max_length(),
+            host_key: Default::default(),
+            framing: None,
+            decoding: default_decoding(),
+            fd: None,
+            pipes: vec![NamedPipeConfig {
+                path: path.clone(),
+                host_key: None,
+                tags: BTreeMap::from([("pipe".to_string(), "first".to_string())]),
+            }],
+            listen_fds: Vec::new(),
+            validation: None,
+            acknowledgements: Default::default(),
+            log_namespace: None,
+        };
+
+        let (context, shutdown) = SourceContext::new_shutdown(&ComponentKey::from("in"), tx);
+        let shutdown_complete = shutdown.shutdown_tripwire();
+        tokio::spawn(config.build(context).await.unwrap());
+
+        // Each writer closes the pipe once done, as a restarting writer would.
+        for line in ["hello world\n", "hello world again\n"] {
+            let path = path.clone();
+            tokio::task::spawn_blocking(move || std::fs::write(path, line))
+                .await
+                .unwrap()
+                .unwrap();
+        }
+
+        for expected in ["hello world", "hello world again"] {
+            let event = stream.next().await.unwrap();
+            let log = event.as_log();
+            assert_eq!(log[log_schema().message_key()].to_string_lossy(), expected);
+            assert_eq!(log["pipe"].to_string_lossy(), "first");
+        }
+
+        shutdown
+            .shutdown_all(Instant::now() + std::time::Duration::from_millis(100))
+            .await;
+        shutdown_complete.await;
+    }
+
+    #[tokio::test]
+    async fn file_descriptor_stops_named_pipes_without_writer() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pipe");
+        assert!(std::process::Command::new("mkfifo")
+            .arg(&path)
+            .status()
+            .unwrap()
+            .success());
+
+        let (tx, _stream) = SourceSender::new_test();
+        let config = FileDescriptorSourceConfig {
+            max_length: crate::serde::default_max_length(),
+            host_key: Default::default(),
+            framing: None,
+            decoding: default_decoding(),
+            fd: None,
+            pipes: vec![NamedPipeConfig {
+                path,
+                host_key: None,
+                tags: BTreeMap::new(),
+            }],
+            listen_fds: Vec::new(),
+            validation: None,
+            acknowledgements: Default::default(),
+            log_namespace: None,
+        };
+
+        let (context, shutdown) = SourceContext::new_shutdown(&ComponentKey::from("in"), tx);
+        let source = tokio::spawn(config.build(context).await.unwrap());
+
+        // Give the reader time to wait for a writer that never comes.
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        shutdown
+            .shutdown_all(Instant::now() + std::time::Duration::from_millis(100))
+            .await;
+
+        tokio::time::timeout(std::time::Duration::from_secs(5), source)
+            .await
+            .expect("source didn't stop while waiting for a writer")
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn file_descriptor_inserts_gap_markers() {
+        fn frame(sequence: u64, payload: &[u8]) -> Vec<u8> {
+            let mut frame = Vec::new();
+            frame.extend((payload.len() as u32).to_be_bytes());
+            frame.extend(sequence.to_be_bytes());
+            frame.extend(crc32fast::hash(payload).to_be_bytes());
+            frame.extend(payload);
+            frame
+        }
+
+        let (tx, mut stream) = SourceSender::new_test();
+        let (read_fd, write_fd) = pipe().unwrap();
+        let config = FileDescriptorSourceConfig {
+            max_length: crate::serde::default_max_length(),
+            host_key: Default::default(),
+            framing: None,
+            decoding: default_decoding(),
+            fd: Some(read_fd as u32),
+            pipes: Vec::new(),
+            listen_fds: Vec::new(),
+            validation: Some(FrameValidationConfig {
+                insert_gap_markers: true,
+                ..Default::default()
+            }),
+            acknowledgements: Default::default(),
+            log_namespace: None,
+        };
+
+        let mut input = frame(1, b"first");
+        input.extend(frame(4, b"fourth"));
+        write(write_fd, &input).unwrap();
+        close(write_fd).unwrap();
+
+        let context = SourceContext::new_test(tx, None);
+        config.build(context).await.unwrap().await.unwrap();
+
+        let event = stream.next().await.unwrap();
+        assert_eq!(
+            event.as_log()[log_schema().message_key()].to_string_lossy(),
+            "first"
+        );
+
+        let event = stream.next().await.unwrap();
+        assert_eq!(event.as_log()["gap_start"], 2.into());
+        assert_eq!(event.as_log()["gap_end"], 3.into());
+
+        let event = stream.next().await.unwrap();
+        assert_eq!(
+            event.as_log()[log_schema().message_key()].to_string_lossy(),
+            "fourth"
+        );
+
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn file_descriptor_requires_a_single_input() {
+        let config = FileDescriptorSourceConfig {
+            max_length: crate::serde::default_max_length(),
+            host_key: Default::default(),
+            framing: None,
+            decoding: default_decoding(),
+            fd: Some(10),
+            pipes: Vec::new(),
+            listen_fds: vec![ListenFdSelector::Name("audit".into())],
+            validation: None,
+            acknowledgements: Default::default(),
+            log_namespace: None,
+        };
+
+        let (tx, _rx) = SourceSender::new_test();
+        let error = config
+            .build(SourceContext::new_test(tx, None))
+            .await
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("`listen_fds`"), "{}", error);
+    }
+
     #[tokio::test]
     async fn file_descriptor_handles_invalid_fd() {
         assert_source_error(&COMPONENT_ERROR_TAGS, async {
@@ -220,7 +744,11 @@ mod tests {
                 host_key: Default::default(),
                 framing: None,
                 decoding: default_decoding(),
-                fd: write_fd as u32, // intentionally giving the source a write-only fd
+                fd: Some(write_fd as u32), // intentionally giving the source a write-only fd
+                pipes: Vec::new(),
+                listen_fds: Vec::new(),
+                validation: None,
+                acknowledgements: Default::default(),
                 log_namespace: None,
             };
 
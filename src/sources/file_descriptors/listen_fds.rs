@@ -0,0 +1,214 @@
+use std::{collections::HashSet, os::unix::io::RawFd};
+
+use snafu::Snafu;
+use vector_config::configurable_component;
+
+/// The first file descriptor passed by the service manager.
+const LISTEN_FDS_START: RawFd = 3;
+
+/// Selects one of the file descriptors passed by systemd socket activation.
+#[configurable_component]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum ListenFdSelector {
+    /// The position of the file descriptor among those passed, starting at 0.
+    Index(#[configurable(transparent)] usize),
+
+    /// The name of the file descriptor, as set with `FileDescriptorName=` in its unit.
+    Name(#[configurable(transparent)] String),
+}
+
+#[derive(Debug, PartialEq, Eq, Snafu)]
+pub enum ListenFdsError {
+    #[snafu(display("No file descriptors were passed by systemd (`LISTEN_FDS` is not set)"))]
+    NotPassed,
+    #[snafu(display("The file descriptors passed by systemd are meant for process {}", pid))]
+    OtherProcess { pid: String },
+    #[snafu(display("Invalid `{}` environment variable: {:?}", variable, value))]
+    InvalidVariable {
+        variable: &'static str,
+        value: String,
+    },
+    #[snafu(display("No file descriptor passed by systemd at index {}", index))]
+    IndexOutOfRange { index: usize },
+    #[snafu(display("No file descriptor passed by systemd is named {:?}", name))]
+    UnknownName { name: String },
+    #[snafu(display("File descriptor {} is selected more than once", fd))]
+    SelectedTwice { fd: RawFd },
+}
+
+/// The file descriptors passed to Vector by its service manager, as described in
+/// `sd_listen_fds(3)`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ListenFds {
+    names: Vec<Option<String>>,
+}
+
+impl ListenFds {
+    /// Reads the file descriptors passed to the current process from the environment.
+    pub fn from_env() -> Result<Self, ListenFdsError> {
+        let var = |name| std::env::var(name).ok();
+        Self::parse(
+            var("LISTEN_PID").as_deref(),
+            var("LISTEN_FDS").as_deref(),
+            var("LISTEN_FDNAMES").as_deref(),
+            std::process::id(),
+        )
+    }
+
+    fn parse(
+        listen_pid: Option<&str>,
+        listen_fds: Option<&str>,
+        listen_fdnames: Option<&str>,
+        pid: u32,
+    ) -> Result<Self, ListenFdsError> {
+        let listen_fds = listen_fds.ok_or(ListenFdsError::NotPassed)?;
+
+        // Like `sd_listen_fds`, file descriptors without a `LISTEN_PID` are taken to be ours.
+        if let Some(listen_pid) = listen_pid {
+            match listen_pid.parse::<u32>() {
+                Ok(listen_pid) if listen_pid == pid => {}
+                Ok(_) => {
+                    return Err(ListenFdsError::OtherProcess {
+                        pid: listen_pid.to_string(),
+                    })
+                }
+                Err(_) => return Err(invalid("LISTEN_PID", listen_pid)),
+            }
+        }
+
+        let count = listen_fds
+            .parse::<usize>()
+            .map_err(|_| invalid("LISTEN_FDS", listen_fds))?;
+
+        let mut names = vec![None; count];
+        if let Some(listen_fdnames) = listen_fdnames {
+            let listed = listen_fdnames.split(':').collect::<Vec<_>>();
+            if listed.len() != count {
+                return Err(invalid("LISTEN_FDNAMES", listen_fdnames));
+            }
+            for (name, listed) in names.iter_mut().zip(listed) {
+                *name = Some(listed.to_string());
+            }
+        }
+
+        Ok(Self { names })
+    }
+
+    /// Returns the file descriptors picked by `selectors`, along with their names.
+    pub fn select(
+        &self,
+        selectors: &[ListenFdSelector],
+    ) -> Result<Vec<(RawFd, Option<&str>)>, ListenFdsError> {
+        let mut selected = HashSet::new();
+        selectors
+            .iter()
+            .map(|selector| {
+                let index = match selector {
+                    ListenFdSelector::Index(index) if *index < self.names.len() => *index,
+                    ListenFdSelector::Index(index) => {
+                        return Err(ListenFdsError::IndexOutOfRange { index: *index })
+                    }
+                    ListenFdSelector::Name(name) => self
+                        .names
+                        .iter()
+                        .position(|listed| listed.as_deref() == Some(name.as_str()))
+                        .ok_or_else(|| ListenFdsError::UnknownName { name: name.clone() })?,
+                };
+
+                let fd = LISTEN_FDS_START + index as RawFd;
+                if !selected.insert(fd) {
+                    return Err(ListenFdsError::SelectedTwice { fd });
+                }
+                Ok((fd, self.names[index].as_deref()))
+            })
+            .collect()
+    }
+}
+
+fn invalid(variable: &'static str, value: &str) -> ListenFdsError {
+    ListenFdsError::InvalidVariable {
+        variable,
+        value: value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PID: u32 = 42;
+
+    #[test]
+    fn selects_by_index_and_name() {
+        let fds =
+            ListenFds::parse(Some("42"), Some("3"), Some("stdout:audit:stderr"), PID).unwrap();
+
+        let selected = fds
+            .select(&[
+                ListenFdSelector::Name("audit".into()),
+                ListenFdSelector::Index(2),
+            ])
+            .unwrap();
+        assert_eq!(selected, vec![(4, Some("audit")), (5, Some("stderr"))]);
+
+        assert_eq!(
+            fds.select(&[ListenFdSelector::Index(3)]),
+            Err(ListenFdsError::IndexOutOfRange { index: 3 })
+        );
+        assert_eq!(
+            fds.select(&[ListenFdSelector::Name("other".into())]),
+            Err(ListenFdsError::UnknownName {
+                name: "other".into()
+            })
+        );
+        assert_eq!(
+            fds.select(&[
+                ListenFdSelector::Index(1),
+                ListenFdSelector::Name("audit".into())
+            ]),
+            Err(ListenFdsError::SelectedTwice { fd: 4 })
+        );
+    }
+
+    #[test]
+    fn selects_unnamed_by_index() {
+        let fds = ListenFds::parse(None, Some("2"), None, PID).unwrap();
+
+        assert_eq!(
+            fds.select(&[ListenFdSelector::Index(1)]).unwrap(),
+            vec![(4, None)]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_environment() {
+        assert_eq!(
+            ListenFds::parse(Some("42"), None, None, PID),
+            Err(ListenFdsError::NotPassed)
+        );
+        assert_eq!(
+            ListenFds::parse(Some("7"), Some("1"), None, PID),
+            Err(ListenFdsError::OtherProcess { pid: "7".into() })
+        );
+        assert!(ListenFds::parse(Some("42"), Some("two"), None, PID).is_err());
+        assert!(ListenFds::parse(Some("42"), Some("2"), Some("only"), PID).is_err());
+    }
+
+    #[test]
+    fn parses_selectors() {
+        #[derive(serde::Deserialize)]
+        struct Config {
+            listen_fds: Vec<ListenFdSelector>,
+        }
+
+        let config: Config = toml::from_str(r#"listen_fds = [0, "audit"]"#).unwrap();
+        assert_eq!(
+            config.listen_fds,
+            vec![
+                ListenFdSelector::Index(0),
+                ListenFdSelector::Name("audit".into())
+            ]
+        );
+    }
+}
@@ -1,4 +1,12 @@
-use std::io;
+use std::{
+    collections::BTreeMap,
+    io,
+    sync::{
+        self,
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
 
 use async_stream::stream;
 use bytes::Bytes;
@@ -11,24 +19,36 @@ use futures::{channel::mpsc, executor, SinkExt, StreamExt};
 use lookup::{owned_value_path, path};
 use tokio_util::{codec::FramedRead, io::StreamReader};
 use value::Kind;
+use vector_common::finalization::AddBatchNotifier;
 use vector_common::internal_event::{ByteSize, BytesReceived, InternalEventHandle as _, Protocol};
 use vector_config::NamedComponent;
 use vector_core::config::{LegacyKey, LogNamespace, Output};
-use vector_core::event::Event;
+use vector_core::event::{BatchNotifier, BatchStatus, BatchStatusReceiver, Event, LogEvent};
 use vector_core::EstimatedJsonEncodedSizeOf;
 
 use crate::{
     codecs::{Decoder, DecodingConfig},
     config::log_schema,
-    internal_events::{EventsReceived, FileDescriptorReadError, StreamClosedError},
+    internal_events::{
+        EventsReceived, FileDescriptorDeliveryFailed, FileDescriptorReadError, StreamClosedError,
+    },
     shutdown::ShutdownSignal,
     SourceSender,
 };
 
 #[cfg(all(unix, feature = "sources-file-descriptor"))]
 pub mod file_descriptor;
+#[cfg(all(unix, feature = "sources-file-descriptor"))]
+mod listen_fds;
 #[cfg(feature = "sources-stdin")]
 pub mod stdin;
+#[cfg(all(unix, feature = "sources-file-descriptor"))]
+mod validation;
+
+#[cfg(all(unix, feature = "sources-file-descriptor"))]
+pub use listen_fds::ListenFdSelector;
+#[cfg(all(unix, feature = "sources-file-descriptor"))]
+pub use validation::FrameValidationConfig;
 
 pub trait FileDescriptorConfig: NamedComponent {
     fn host_key(&self) -> Option<String>;
@@ -36,12 +56,32 @@ pub trait FileDescriptorConfig: NamedComponent {
     fn decoding(&self) -> DeserializerConfig;
     fn description(&self) -> String;
 
+    fn decoder(&self, log_namespace: LogNamespace) -> Decoder {
+        let decoding = self.decoding();
+        let framing = self
+            .framing()
+            .unwrap_or_else(|| decoding.default_stream_framing());
+        DecodingConfig::new(framing, decoding, log_namespace).build()
+    }
+
+    /// Builds the decoder for a descriptor, along with where it records gaps in the sequence of
+    /// frames read, if gap markers are to be inserted.
+    fn framed_decoder(&self, log_namespace: LogNamespace) -> (Decoder, Option<FrameGaps>) {
+        (self.decoder(log_namespace), None)
+    }
+
+    /// Builds the source reading from `reader`.
+    ///
+    /// With `acknowledgements`, nothing more is read from `reader` until the events read so far
+    /// are delivered, so that a process writing to it is held back instead of having its data
+    /// lost if it can't be delivered.
     fn source<R>(
         &self,
         reader: R,
         shutdown: ShutdownSignal,
         out: SourceSender,
         log_namespace: LogNamespace,
+        acknowledgements: bool,
     ) -> crate::Result<crate::sources::Source>
     where
         R: Send + io::BufRead + 'static,
@@ -53,13 +93,10 @@ pub trait FileDescriptorConfig: NamedComponent {
 
         let description = self.description();
 
-        let decoding = self.decoding();
-        let framing = self
-            .framing()
-            .unwrap_or_else(|| decoding.default_stream_framing());
-        let decoder = DecodingConfig::new(framing, decoding, log_namespace).build();
+        let (decoder, frame_gaps) = self.framed_decoder(log_namespace);
 
         let (sender, receiver) = mpsc::channel(1024);
+        let (credits_tx, credits_rx) = read_credits(acknowledgements);
 
         // Spawn background thread with blocking I/O to process fd.
         //
@@ -68,7 +105,7 @@ pub trait FileDescriptorConfig: NamedComponent {
         // https://github.com/tokio-rs/tokio/blob/a73428252b08bf1436f12e76287acbc4600ca0e5/tokio/src/io/stdin.rs#L33-L42
         std::thread::spawn(move || {
             info!("Capturing {}.", description);
-            read_from_fd(reader, sender);
+            read_from_fd(reader, sender, credits_rx.as_ref());
         });
 
         Ok(Box::pin(process_stream(
@@ -79,14 +116,31 @@ pub trait FileDescriptorConfig: NamedComponent {
             host_key,
             Self::NAME,
             hostname,
+            BTreeMap::new(),
+            frame_gaps,
             log_namespace,
+            credits_tx,
         )))
     }
 }
 
 type Sender = mpsc::Sender<std::result::Result<bytes::Bytes, std::io::Error>>;
 
-fn read_from_fd<R>(mut reader: R, mut sender: Sender)
+/// Allows the thread reading a descriptor to read once more, after the events read so far were
+/// delivered.
+type CreditSender = sync::mpsc::Sender<()>;
+type CreditReceiver = sync::mpsc::Receiver<()>;
+
+fn read_credits(acknowledgements: bool) -> (Option<CreditSender>, Option<CreditReceiver>) {
+    if acknowledgements {
+        let (tx, rx) = sync::mpsc::channel();
+        (Some(tx), Some(rx))
+    } else {
+        (None, None)
+    }
+}
+
+fn read_from_fd<R>(mut reader: R, mut sender: Sender, credits: Option<&CreditReceiver>)
 where
     R: Send + io::BufRead + 'static,
 {
@@ -104,32 +158,89 @@ where
             // Receiver has closed so we should shutdown.
             break;
         }
+
+        if let Some(credits) = credits {
+            if credits.recv().is_err() {
+                // Reading was stopped, either on shutdown or because events were not delivered.
+                break;
+            }
+        }
     }
 }
 
 type Receiver = mpsc::Receiver<std::result::Result<bytes::Bytes, std::io::Error>>;
 
+/// A range of sequence numbers missing from a stream of validated frames.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FrameGap {
+    pub start: u64,
+    pub end: u64,
+}
+
+/// Gaps detected by a framer that have not yet been marked in the stream of events read through
+/// it.
+pub type FrameGaps = Arc<Mutex<Vec<FrameGap>>>;
+
+fn gap_marker(gap: FrameGap) -> Event {
+    let mut log = LogEvent::default();
+    log.insert("gap_start", gap.start);
+    log.insert("gap_end", gap.end);
+    log.into()
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn process_stream(
-    receiver: Receiver,
+    mut receiver: Receiver,
     decoder: Decoder,
     mut out: SourceSender,
     shutdown: ShutdownSignal,
     host_key: String,
     source_type: &'static str,
     hostname: Option<String>,
+    tags: BTreeMap<String, String>,
+    frame_gaps: Option<FrameGaps>,
     log_namespace: LogNamespace,
+    credits: Option<CreditSender>,
 ) -> Result<(), ()> {
     let bytes_received = register!(BytesReceived::from(Protocol::NONE));
-    let stream = receiver.inspect(|result| {
-        if let Err(error) = result {
-            emit!(FileDescriptorReadError { error: &error });
+    let acknowledgements = credits.is_some();
+    let (pending_tx, mut pending_rx) = mpsc::unbounded::<BatchStatusReceiver>();
+    let delivery_failed = Arc::new(AtomicBool::new(false));
+    let stop_reading = Arc::clone(&delivery_failed);
+    let chunks = stream! {
+        while let Some(chunk) = receiver.next().await {
+            if let Err(error) = &chunk {
+                emit!(FileDescriptorReadError { error });
+            }
+            yield chunk;
+
+            // Only polled again once everything read so far has been decoded, so the events
+            // decoded from it are all pending delivery.
+            while let Ok(Some(pending)) = pending_rx.try_next() {
+                let status = pending.await;
+                if status != BatchStatus::Delivered {
+                    emit!(FileDescriptorDeliveryFailed { status });
+                    stop_reading.store(true, Ordering::Relaxed);
+                    return;
+                }
+            }
+            // `credits` is only borrowed once done awaiting, as its sender can't be shared
+            // between threads.
+            if let Some(credits) = &credits {
+                if credits.send(()).is_err() {
+                    break;
+                }
+            }
         }
-    });
-    let stream = StreamReader::new(stream);
+    };
+    let stream = StreamReader::new(Box::pin(chunks));
     let mut stream = FramedRead::new(stream, decoder).take_until(shutdown);
     let mut stream = stream! {
         while let Some(result) = stream.next().await {
+            // Don't decode what's left of a partially read frame.
+            if delivery_failed.load(Ordering::Relaxed) {
+                break;
+            }
             match result {
                 Ok((events, byte_size)) => {
                     bytes_received.emit(ByteSize(byte_size));
@@ -139,8 +250,22 @@ async fn process_stream(
                     });
 
                     let now = Utc::now();
+                    let (batch, pending) = BatchNotifier::maybe_new_with_receiver(acknowledgements);
+                    if let Some(pending) = pending {
+                        let _ = pending_tx.unbounded_send(pending);
+                    }
+
+                    // Gaps are detected on reading the frame following them, so they are marked
+                    // before its events.
+                    let gap_markers = frame_gaps
+                        .as_ref()
+                        .map(|gaps| std::mem::take(&mut *gaps.lock().expect("poisoned lock")))
+                        .unwrap_or_default();
 
-                    for mut event in events {
+                    for mut event in gap_markers.into_iter().map(gap_marker).chain(events) {
+                        if let Some(batch) = &batch {
+                            event.add_batch_notifier(batch.clone());
+                        }
                         match event{
                             Event::Log(_) => {
                                 let log = event.as_mut_log();
@@ -161,6 +286,16 @@ async fn process_stream(
                                     );
                                 }
 
+                                for (key, value) in &tags {
+                                    log_namespace.insert_source_metadata(
+                                        source_type,
+                                        log,
+                                        Some(LegacyKey::Overwrite(path!(key.as_str()))),
+                                        path!(key.as_str()),
+                                        value.clone()
+                                    );
+                                }
+
                                 yield event;
                             },
                             _ => {
@@ -196,27 +331,44 @@ async fn process_stream(
 
 /// Builds the `vector_core::config::Outputs` for stdin and
 /// file_descriptor sources.
+///
+/// `host_keys` holds the host key overrides of each descriptor read from, and `tag_keys` the keys
+/// of the fields added to their events.
 fn outputs(
     log_namespace: LogNamespace,
-    host_key: &Option<String>,
+    host_keys: &[Option<String>],
+    tag_keys: &[&str],
     decoding: &DeserializerConfig,
     source_name: &'static str,
 ) -> Vec<Output> {
-    let host_key_path = host_key.as_ref().map_or_else(
-        || owned_value_path!(log_schema().host_key()),
-        |x| owned_value_path!(x),
-    );
-
-    let schema_definition = decoding
-        .schema_definition(log_namespace)
-        .with_source_metadata(
+    let mut schema_definition = decoding.schema_definition(log_namespace);
+
+    for host_key in host_keys {
+        let host_key_path = host_key.as_ref().map_or_else(
+            || owned_value_path!(log_schema().host_key()),
+            |x| owned_value_path!(x),
+        );
+
+        schema_definition = schema_definition.with_source_metadata(
             source_name,
             Some(LegacyKey::InsertIfEmpty(host_key_path)),
             &owned_value_path!("host"),
             Kind::bytes(),
             None,
-        )
-        .with_standard_vector_source_metadata();
+        );
+    }
+
+    for tag_key in tag_keys {
+        schema_definition = schema_definition.with_source_metadata(
+            source_name,
+            Some(LegacyKey::Overwrite(owned_value_path!(*tag_key))),
+            &owned_value_path!(*tag_key),
+            Kind::bytes(),
+            None,
+        );
+    }
+
+    let schema_definition = schema_definition.with_standard_vector_source_metadata();
 
     vec![Output::default(decoding.output_type()).with_schema_definition(schema_definition)]
 }
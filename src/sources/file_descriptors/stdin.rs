@@ -5,8 +5,8 @@ use vector_config::{configurable_component, NamedComponent};
 use vector_core::config::LogNamespace;
 
 use crate::{
-    config::{Output, Resource, SourceConfig, SourceContext},
-    serde::default_decoding,
+    config::{Output, Resource, SourceAcknowledgementsConfig, SourceConfig, SourceContext},
+    serde::{bool_or_struct, default_decoding},
 };
 
 use super::{outputs, FileDescriptorConfig};
@@ -38,6 +38,10 @@ pub struct StdinConfig {
     #[serde(default = "default_decoding")]
     pub decoding: DeserializerConfig,
 
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    pub acknowledgements: SourceAcknowledgementsConfig,
+
     /// The namespace to use for logs. This overrides the global setting.
     #[configurable(metadata(docs::hidden))]
     #[serde(default)]
@@ -69,6 +73,7 @@ impl Default for StdinConfig {
             host_key: Default::default(),
             framing: None,
             decoding: default_decoding(),
+            acknowledgements: Default::default(),
             log_namespace: None,
         }
     }
@@ -80,18 +85,26 @@ impl_generate_config_from_default!(StdinConfig);
 impl SourceConfig for StdinConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<crate::sources::Source> {
         let log_namespace = cx.log_namespace(self.log_namespace);
+        let acknowledgements = cx.do_acknowledgements(self.acknowledgements);
         self.source(
             io::BufReader::new(io::stdin()),
             cx.shutdown,
             cx.out,
             log_namespace,
+            acknowledgements,
         )
     }
 
     fn outputs(&self, global_log_namespace: LogNamespace) -> Vec<Output> {
         let log_namespace = global_log_namespace.merge(self.log_namespace);
 
-        outputs(log_namespace, &self.host_key, &self.decoding, Self::NAME)
+        outputs(
+            log_namespace,
+            &[self.host_key.clone()],
+            &[],
+            &self.decoding,
+            Self::NAME,
+        )
     }
 
     fn resources(&self) -> Vec<Resource> {
@@ -99,7 +112,7 @@ impl SourceConfig for StdinConfig {
     }
 
     fn can_acknowledge(&self) -> bool {
-        false
+        true
     }
 }
 
@@ -109,7 +122,7 @@ mod tests {
 
     use super::*;
     use crate::{
-        config::log_schema, shutdown::ShutdownSignal,
+        config::log_schema, event::EventStatus, shutdown::ShutdownSignal,
         test_util::components::assert_source_compliance, test_util::components::SOURCE_TAGS,
         SourceSender,
     };
@@ -129,7 +142,7 @@ mod tests {
             let buf = Cursor::new("hello world\nhello world again");
 
             config
-                .source(buf, ShutdownSignal::noop(), tx, LogNamespace::Legacy)
+                .source(buf, ShutdownSignal::noop(), tx, LogNamespace::Legacy, false)
                 .unwrap()
                 .await
                 .unwrap();
@@ -158,6 +171,38 @@ mod tests {
         .await;
     }
 
+    async fn read_acknowledged(status: EventStatus) -> Vec<String> {
+        let (tx, rx) = SourceSender::new_test_finalize(status);
+        // A small buffer splits the input over several reads.
+        let buf = io::BufReader::with_capacity(6, Cursor::new("one\ntwo\nthree\n"));
+
+        let source = StdinConfig::default()
+            .source(buf, ShutdownSignal::noop(), tx, LogNamespace::Legacy, true)
+            .unwrap();
+        tokio::spawn(source);
+
+        rx.map(|event| {
+            event.as_log()[log_schema().message_key()]
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect()
+        .await
+    }
+
+    #[tokio::test]
+    async fn stdin_reads_after_delivery() {
+        assert_eq!(
+            read_acknowledged(EventStatus::Delivered).await,
+            vec!["one", "two", "three"]
+        );
+    }
+
+    #[tokio::test]
+    async fn stdin_stops_reading_on_delivery_failure() {
+        assert_eq!(read_acknowledged(EventStatus::Rejected).await, vec!["one"]);
+    }
+
     #[tokio::test]
     async fn stdin_decodes_line_vector_namespace() {
         assert_source_compliance(&SOURCE_TAGS, async {
@@ -166,7 +211,7 @@ mod tests {
             let buf = Cursor::new("hello world\nhello world again");
 
             config
-                .source(buf, ShutdownSignal::noop(), tx, LogNamespace::Vector)
+                .source(buf, ShutdownSignal::noop(), tx, LogNamespace::Vector, false)
                 .unwrap()
                 .await
                 .unwrap();
@@ -0,0 +1,252 @@
+use bytes::{Buf, Bytes, BytesMut};
+use codecs::decoding::BoxedFramingError;
+use vector_config::configurable_component;
+
+use super::{FrameGap, FrameGaps};
+use crate::internal_events::{FileDescriptorFrameCorrupted, FileDescriptorFrameGap};
+
+/// Size of the header preceding each frame: the payload length (`u32`), the sequence number
+/// (`u64`) and the CRC-32 checksum of the payload (`u32`), all big-endian.
+const HEADER_LENGTH: usize = 4 + 8 + 4;
+
+/// Configuration for validating checksummed, sequenced frames.
+///
+/// When enabled, input is expected to be a stream of frames, each made of a 16 byte header and a
+/// payload. The header holds the length of the payload as a 32-bit integer, the sequence number
+/// of the frame as a 64-bit integer, and the CRC-32 (IEEE) checksum of the payload as a 32-bit
+/// integer, all big-endian. The payload of each valid frame is decoded as configured by
+/// `decoding`.
+///
+/// Frames whose checksum does not match are dropped, as are frames whose sequence number is not
+/// higher than the last one seen. Skipped sequence numbers are reported as gaps.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct FrameValidationConfig {
+    /// Whether or not to insert an event marking each detected gap in the sequence of frames.
+    ///
+    /// Gap markers are log events with `gap_start` and `gap_end` fields holding the first and
+    /// last missing sequence numbers.
+    #[serde(default)]
+    pub insert_gap_markers: bool,
+
+    /// The maximum length, in bytes, of a frame payload.
+    ///
+    /// A frame with a longer payload means the stream can no longer be trusted, and stops the
+    /// source from reading any further.
+    #[serde(default = "default_max_frame_length")]
+    pub max_frame_length: usize,
+}
+
+const fn default_max_frame_length() -> usize {
+    8 * 1024 * 1024
+}
+
+impl Default for FrameValidationConfig {
+    fn default() -> Self {
+        Self {
+            insert_gap_markers: false,
+            max_frame_length: default_max_frame_length(),
+        }
+    }
+}
+
+/// A framer that validates the checksum and sequence number of each frame, producing the payloads
+/// of valid frames.
+#[derive(Clone, Debug)]
+pub(super) struct ValidatingFramer {
+    max_frame_length: usize,
+    last_sequence: Option<u64>,
+    gaps: Option<FrameGaps>,
+}
+
+impl ValidatingFramer {
+    /// Creates a new `ValidatingFramer`, recording detected gaps in `gaps` if given.
+    pub(super) fn new(config: &FrameValidationConfig, gaps: Option<FrameGaps>) -> Self {
+        Self {
+            max_frame_length: config.max_frame_length,
+            last_sequence: None,
+            gaps,
+        }
+    }
+
+    fn validate(&mut self, sequence: u64, checksum: u32, payload: Bytes) -> Option<Bytes> {
+        if crc32fast::hash(&payload) != checksum {
+            emit!(FileDescriptorFrameCorrupted {
+                sequence: Some(sequence),
+                reason: "checksum mismatch",
+            });
+            return None;
+        }
+
+        match self.last_sequence {
+            Some(last) if sequence <= last => {
+                debug!(
+                    message = "Dropping duplicate or out of order frame.",
+                    sequence,
+                    last_sequence = last
+                );
+                return None;
+            }
+            Some(last) if sequence - last > 1 => {
+                let gap = FrameGap {
+                    start: last + 1,
+                    end: sequence - 1,
+                };
+                emit!(FileDescriptorFrameGap {
+                    start: gap.start,
+                    end: gap.end,
+                });
+                if let Some(gaps) = &self.gaps {
+                    gaps.lock().expect("poisoned lock").push(gap);
+                }
+            }
+            _ => {}
+        }
+        self.last_sequence = Some(sequence);
+
+        Some(payload)
+    }
+}
+
+impl tokio_util::codec::Decoder for ValidatingFramer {
+    type Item = Bytes;
+    type Error = BoxedFramingError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        loop {
+            if src.len() < HEADER_LENGTH {
+                return Ok(None);
+            }
+
+            let mut header = &src[..HEADER_LENGTH];
+            let length = header.get_u32() as usize;
+            let sequence = header.get_u64();
+            let checksum = header.get_u32();
+
+            if length > self.max_frame_length {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!(
+                        "frame length {} exceeds maximum of {} bytes",
+                        length, self.max_frame_length
+                    ),
+                )
+                .into());
+            }
+
+            if src.len() < HEADER_LENGTH + length {
+                src.reserve(HEADER_LENGTH + length - src.len());
+                return Ok(None);
+            }
+
+            src.advance(HEADER_LENGTH);
+            let payload = src.split_to(length).freeze();
+
+            // Invalid frames are dropped, and the next one is read straight away.
+            if let Some(payload) = self.validate(sequence, checksum, payload) {
+                return Ok(Some(payload));
+            }
+        }
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let frame = self.decode(src)?;
+        if frame.is_none() && !src.is_empty() {
+            emit!(FileDescriptorFrameCorrupted {
+                sequence: None,
+                reason: "truncated frame",
+            });
+            src.clear();
+        }
+        Ok(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BufMut;
+    use tokio_util::codec::Decoder;
+
+    use super::*;
+    use std::sync::Arc;
+
+    fn frame(sequence: u64, payload: &[u8]) -> BytesMut {
+        let mut frame = BytesMut::new();
+        frame.put_u32(payload.len() as u32);
+        frame.put_u64(sequence);
+        frame.put_u32(crc32fast::hash(payload));
+        frame.put_slice(payload);
+        frame
+    }
+
+    fn framer() -> (ValidatingFramer, FrameGaps) {
+        let gaps = FrameGaps::default();
+        let framer =
+            ValidatingFramer::new(&FrameValidationConfig::default(), Some(Arc::clone(&gaps)));
+        (framer, gaps)
+    }
+
+    #[test]
+    fn decodes_valid_frames() {
+        let (mut framer, gaps) = framer();
+        let mut input = frame(1, b"foo");
+        input.extend(frame(2, b"bar"));
+
+        assert_eq!(framer.decode(&mut input).unwrap().unwrap(), "foo");
+        assert_eq!(framer.decode(&mut input).unwrap().unwrap(), "bar");
+        assert_eq!(framer.decode(&mut input).unwrap(), None);
+        assert!(gaps.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn waits_for_partial_frames() {
+        let (mut framer, _) = framer();
+        let full = frame(1, b"foo");
+        let mut input = BytesMut::from(&full[..HEADER_LENGTH + 1]);
+
+        assert_eq!(framer.decode(&mut input).unwrap(), None);
+        input.extend_from_slice(&full[HEADER_LENGTH + 1..]);
+        assert_eq!(framer.decode(&mut input).unwrap().unwrap(), "foo");
+    }
+
+    #[test]
+    fn drops_corrupted_and_duplicate_frames() {
+        let (mut framer, _) = framer();
+        let mut corrupted = frame(2, b"bar");
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 0xff;
+
+        let mut input = frame(1, b"foo");
+        input.extend(frame(1, b"foo"));
+        input.extend(corrupted);
+        input.extend(frame(2, b"bar"));
+
+        assert_eq!(framer.decode(&mut input).unwrap().unwrap(), "foo");
+        assert_eq!(framer.decode(&mut input).unwrap().unwrap(), "bar");
+        assert!(input.is_empty());
+    }
+
+    #[test]
+    fn records_gaps() {
+        let (mut framer, gaps) = framer();
+        let mut input = frame(1, b"foo");
+        input.extend(frame(5, b"bar"));
+
+        assert_eq!(framer.decode(&mut input).unwrap().unwrap(), "foo");
+        assert_eq!(framer.decode(&mut input).unwrap().unwrap(), "bar");
+        assert_eq!(*gaps.lock().unwrap(), vec![FrameGap { start: 2, end: 4 }]);
+    }
+
+    #[test]
+    fn rejects_oversized_frames() {
+        let config = FrameValidationConfig {
+            max_frame_length: 2,
+            ..Default::default()
+        };
+        let mut framer = ValidatingFramer::new(&config, None);
+        let mut input = frame(1, b"foo");
+
+        assert!(framer.decode(&mut input).is_err());
+    }
+}
@@ -1,5 +1,4 @@
 use std::io::{self, Read};
-use std::net::SocketAddr;
 
 use bytes::{Buf, Bytes, BytesMut};
 use chrono::Utc;
@@ -17,7 +16,9 @@ use vector_config::{configurable_component, NamedComponent};
 use vector_core::config::{LegacyKey, LogNamespace};
 use vector_core::schema::Definition;
 
-use super::util::net::{SocketListenAddr, TcpSource, TcpSourceAck, TcpSourceAcker};
+use super::util::net::{
+    PeerAddrMetadata, SocketListenAddr, TcpSource, TcpSourceAck, TcpSourceAcker, TcpSourceOptions,
+};
 use crate::{
     config::{
         log_schema, DataType, GenerateConfig, Output, Resource, SourceAcknowledgementsConfig,
@@ -43,6 +44,12 @@ pub struct FluentConfig {
     /// The maximum number of TCP connections that will be allowed at any given time.
     connection_limit: Option<u32>,
 
+    /// The amount of time, in seconds, a connection can stay open without sending any data before it
+    /// is closed.
+    ///
+    /// By default, connections are never closed for being idle.
+    idle_timeout_secs: Option<u64>,
+
     #[configurable(derived)]
     keepalive: Option<TcpKeepaliveConfig>,
 
@@ -73,6 +80,7 @@ impl GenerateConfig for FluentConfig {
             receive_buffer_bytes: None,
             acknowledgements: Default::default(),
             connection_limit: Some(2),
+            idle_timeout_secs: None,
             log_namespace: None,
         })
         .unwrap()
@@ -84,12 +92,17 @@ impl SourceConfig for FluentConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
         let source = FluentSource::new(cx.log_namespace(self.log_namespace));
         let shutdown_secs = 30;
-        let tls_config = self.tls.as_ref().map(|tls| tls.tls_config.clone());
         let tls_client_metadata_key = self
             .tls
             .as_ref()
             .and_then(|tls| tls.client_metadata_key.clone());
-        let tls = MaybeTlsSettings::from_config(&tls_config, true)?;
+        let tls_client_allowlist = self
+            .tls
+            .as_ref()
+            .map(|tls| tls.client_allowlist())
+            .transpose()?
+            .flatten();
+        let tls = MaybeTlsSettings::from_source_config(&self.tls)?;
         source.run(
             self.address,
             self.keepalive,
@@ -100,6 +113,11 @@ impl SourceConfig for FluentConfig {
             cx,
             self.acknowledgements,
             self.connection_limit,
+            TcpSourceOptions {
+                tls_client_allowlist,
+                idle_timeout_secs: self.idle_timeout_secs,
+                ..Default::default()
+            },
         )
     }
 
@@ -193,28 +211,18 @@ impl TcpSource for FluentSource {
     type Item = FluentFrame;
     type Decoder = FluentDecoder;
     type Acker = FluentAcker;
+    type Instrumentation = ();
 
     fn decoder(&self) -> Self::Decoder {
         FluentDecoder::new(self.log_namespace)
     }
 
-    fn handle_events(&self, events: &mut [Event], host: SocketAddr) {
-        for event in events {
-            let log = event.as_mut_log();
-
-            let legacy_host_key = self
-                .legacy_host_key_path
-                .as_ref()
-                .map(LegacyKey::InsertIfEmpty);
-
-            self.log_namespace.insert_source_metadata(
-                FluentConfig::NAME,
-                log,
-                legacy_host_key,
-                path!("host"),
-                host.ip().to_string(),
-            );
-        }
+    fn peer_addr_metadata(&self) -> Option<PeerAddrMetadata> {
+        Some(PeerAddrMetadata::new(
+            FluentConfig::NAME,
+            self.log_namespace,
+            self.legacy_host_key_path.clone(),
+        ))
     }
 
     fn build_acker(&self, frame: &[Self::Item]) -> Self::Acker {
@@ -866,6 +874,7 @@ mod tests {
             receive_buffer_bytes: None,
             acknowledgements: true.into(),
             connection_limit: None,
+            idle_timeout_secs: None,
             log_namespace: None,
         }
         .build(SourceContext::new_test(sender, None))
@@ -930,6 +939,7 @@ mod tests {
             receive_buffer_bytes: None,
             acknowledgements: false.into(),
             connection_limit: None,
+            idle_timeout_secs: None,
             log_namespace: Some(true),
         };
 
@@ -966,6 +976,7 @@ mod tests {
             receive_buffer_bytes: None,
             acknowledgements: false.into(),
             connection_limit: None,
+            idle_timeout_secs: None,
             log_namespace: None,
         };
 
@@ -1187,6 +1198,7 @@ mod integration_tests {
                 receive_buffer_bytes: None,
                 acknowledgements: false.into(),
                 connection_limit: None,
+                idle_timeout_secs: None,
                 log_namespace: None,
             }
             .build(SourceContext::new_test(sender, None))
@@ -30,7 +30,7 @@ use vector_core::config::{LegacyKey, LogNamespace};
 use crate::{
     codecs::{Decoder, DecodingConfig},
     config::{DataType, Output, SourceAcknowledgementsConfig, SourceConfig, SourceContext},
-    event::{BatchNotifier, BatchStatus, Event, MaybeAsLogMut, Value},
+    event::{BatchNotifier, BatchStatus, Event, Value},
     gcp::{GcpAuthConfig, GcpAuthenticator, Scope, PUBSUB_URL},
     internal_events::{
         GcpPubsubConnectError, GcpPubsubReceiveError, GcpPubsubStreamingPullError,
@@ -645,10 +645,9 @@ impl PubsubSource {
                 .map(|(key, value)| (key, Value::Bytes(value.into())))
                 .collect(),
         );
-        let log_namespace = self.log_namespace;
         util::decode_message(
             self.decoder.clone(),
-            "gcp_pubsub",
+            PubsubConfig::NAME,
             &message.data,
             message.publish_time.map(|dt| {
                 DateTime::from_utc(
@@ -656,28 +655,16 @@ impl PubsubSource {
                     Utc,
                 )
             }),
+            vec![
+                (
+                    LegacyKey::Overwrite("message_id"),
+                    Value::from(message.message_id),
+                ),
+                (LegacyKey::Overwrite("attributes"), attributes),
+            ],
             batch,
-            log_namespace,
+            self.log_namespace,
         )
-        .map(move |mut event| {
-            if let Some(log) = event.maybe_as_log_mut() {
-                log_namespace.insert_source_metadata(
-                    PubsubConfig::NAME,
-                    log,
-                    Some(LegacyKey::Overwrite("message_id")),
-                    "message_id",
-                    message.message_id.clone(),
-                );
-                log_namespace.insert_source_metadata(
-                    PubsubConfig::NAME,
-                    log,
-                    Some(LegacyKey::Overwrite("attributes")),
-                    "attributes",
-                    attributes.clone(),
-                )
-            }
-            event
-        })
     }
 }
 
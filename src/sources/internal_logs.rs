@@ -1,3 +1,8 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::{Duration, Instant},
+};
+
 use chrono::Utc;
 use codecs::BytesDeserializerConfig;
 use futures::{stream, StreamExt};
@@ -7,6 +12,7 @@ use value::Kind;
 use vector_config::{configurable_component, NamedComponent};
 use vector_core::{
     config::{LegacyKey, LogNamespace},
+    event::{LogEvent, Value},
     schema::Definition,
 };
 
@@ -14,8 +20,9 @@ use crate::{
     config::{log_schema, DataType, Output, SourceConfig, SourceContext},
     event::{EstimatedJsonEncodedSizeOf, Event},
     internal_events::{InternalLogsBytesReceived, InternalLogsEventsReceived, StreamClosedError},
+    internal_telemetry::guard::{SelfTelemetryGuard, SelfTelemetryGuardConfig},
     shutdown::ShutdownSignal,
-    trace::TraceSubscription,
+    trace::{self, is_drop_report, TraceSubscription},
     SourceSender,
 };
 
@@ -42,12 +49,141 @@ pub struct InternalLogsConfig {
     #[serde(default)]
     pid_key: Option<OptionalValuePath>,
 
+    /// Whether or not to include drop reports.
+    ///
+    /// A drop report is emitted every time a component drops events, and consists of the
+    /// `count` of dropped events, the `reason` they were dropped, whether dropping them was
+    /// `intentional`, and the fields identifying the component under `vector`. Unlike other
+    /// internal logs, drop reports are never rate limited nor subject to the log level, which
+    /// makes them suitable for alerting on data loss.
+    ///
+    /// Drop reports can be told apart by their `metadata.target` field, which is always
+    /// `vector::drop_report`.
+    #[serde(default)]
+    drop_reports: bool,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    level: Option<InternalLogLevel>,
+
+    /// The window, in seconds, during which repeated internal logs are forwarded only once.
+    ///
+    /// Internal logs are considered repeated if they have the same message and were emitted by
+    /// the same component. This is applied on top of the rate limiting done when the logs are
+    /// emitted, and does not apply to drop reports.
+    ///
+    /// By default, repeated internal logs are not deduplicated.
+    #[serde(default)]
+    rate_limit_secs: Option<u64>,
+
+    /// Whether or not to include the timings of the spans each internal log was emitted in.
+    ///
+    /// When enabled, internal logs emitted within spans carry a `spans` array, ordered from the
+    /// outermost span to the innermost one. Each entry has the `name` of the span, and the time in
+    /// nanoseconds it had spent executing (`busy_ns`) and waiting (`idle_ns`) when the log was
+    /// emitted, which allows breaking down where time is spent within a component.
+    ///
+    /// Only spans created after this source is started are timed.
+    #[serde(default)]
+    include_span_timings: bool,
+
+    /// Whether or not to include the spans each internal log was emitted in, with all their fields.
+    ///
+    /// When enabled, internal logs emitted within spans carry a `span_hierarchy` array, ordered
+    /// from the outermost span to the innermost one. Each entry has the `name` and `target` of the
+    /// span, and a `fields` object with all of its fields, which allows reconstructing the context
+    /// a log was emitted in. Unlike the fields identifying components, which are always added under
+    /// `vector`, these may hold any data the span was created with.
+    ///
+    /// Only spans created after this source is started have all their fields.
+    #[serde(default)]
+    include_span_hierarchy: bool,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    component_quota: Option<ComponentQuotaConfig>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    guard: Option<SelfTelemetryGuardConfig>,
+
     /// The namespace to use for logs. This overrides the global setting.
     #[configurable(metadata(docs::hidden))]
     #[serde(default)]
     log_namespace: Option<bool>,
 }
 
+/// The minimum severity of internal logs to forward.
+///
+/// Internal logs less severe than this are dropped before entering the topology. Drop reports
+/// are always forwarded, regardless of their severity.
+///
+/// By default, all internal logs emitted at the global log level are forwarded.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum InternalLogLevel {
+    /// Forward all internal logs.
+    Trace,
+
+    /// Forward debug, info, warning and error internal logs.
+    Debug,
+
+    /// Forward info, warning and error internal logs.
+    Info,
+
+    /// Forward warning and error internal logs.
+    Warn,
+
+    /// Forward error internal logs only.
+    Error,
+}
+
+/// The quota of internal logs forwarded for each component.
+///
+/// When a component emits more internal logs in a second than its quota, the logs over the quota
+/// are suppressed, and a single summary log is forwarded for the component at the end of the
+/// second instead. The summary has the fields identifying the component under `vector`, the
+/// number of logs `suppressed`, and the most frequent `top_messages` suppressed, each with its
+/// `count`. This is applied after the other filters, and does not apply to drop reports.
+///
+/// Summaries can be told apart by their `metadata.target` field, which is always
+/// `vector::internal_logs::quota`.
+#[configurable_component]
+#[derive(Clone, Copy, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ComponentQuotaConfig {
+    /// The maximum number of internal logs forwarded for each component per second.
+    max_events_per_sec: u64,
+
+    /// The maximum number of distinct messages listed in each summary.
+    #[serde(default = "default_top_messages")]
+    top_messages: usize,
+}
+
+const fn default_top_messages() -> usize {
+    5
+}
+
+/// The target of the summaries of suppressed internal logs.
+const QUOTA_SUMMARY_TARGET: &str = "vector::internal_logs::quota";
+
+/// The maximum number of distinct messages counted for each component between summaries. Logs with
+/// other messages are still counted as suppressed.
+const MAX_COUNTED_MESSAGES: usize = 1_000;
+
+impl From<InternalLogLevel> for tracing::Level {
+    fn from(level: InternalLogLevel) -> Self {
+        match level {
+            InternalLogLevel::Trace => tracing::Level::TRACE,
+            InternalLogLevel::Debug => tracing::Level::DEBUG,
+            InternalLogLevel::Info => tracing::Level::INFO,
+            InternalLogLevel::Warn => tracing::Level::WARN,
+            InternalLogLevel::Error => tracing::Level::ERROR,
+        }
+    }
+}
+
 impl_generate_config_from_default!(InternalLogsConfig);
 
 impl InternalLogsConfig {
@@ -101,18 +237,53 @@ impl SourceConfig for InternalLogsConfig {
 
         let pid_key = self.pid_key.clone().and_then(|k| k.path);
 
+        // Spans are only timed and recorded in full, and drop reports broadcast, while a source
+        // including them runs.
+        let span_timings = self.include_span_timings.then(trace::enable_span_timings);
+        let span_hierarchy = self
+            .include_span_hierarchy
+            .then(trace::enable_span_hierarchy);
+        let drop_reports = self.drop_reports.then(trace::enable_drop_reports);
+
         let subscription = TraceSubscription::subscribe();
 
         let log_namespace = cx.log_namespace(self.log_namespace);
 
-        Ok(Box::pin(run(
+        let filter = InternalLogsFilter::new(
+            self.drop_reports,
+            self.level,
+            self.rate_limit_secs.map(Duration::from_secs),
+            self.component_quota,
+        );
+        let spans = IncludedSpans {
+            timings: self.include_span_timings,
+            hierarchy: self.include_span_hierarchy,
+        };
+        let guard = self
+            .guard
+            .as_ref()
+            .map(|config| {
+                SelfTelemetryGuard::new(config, InternalLogsConfig::NAME, cx.self_telemetry.clone())
+            })
+            .transpose()?;
+
+        let source = run(
             host_key,
             pid_key,
+            filter,
+            spans,
+            guard,
             subscription,
             cx.out,
             cx.shutdown,
             log_namespace,
-        )))
+        );
+        Ok(Box::pin(async move {
+            let _span_timings = span_timings;
+            let _span_hierarchy = span_hierarchy;
+            let _drop_reports = drop_reports;
+            source.await
+        }))
     }
 
     fn outputs(&self, global_log_namespace: LogNamespace) -> Vec<Output> {
@@ -127,9 +298,208 @@ impl SourceConfig for InternalLogsConfig {
     }
 }
 
+/// Decides which internal logs are forwarded by the source.
+struct InternalLogsFilter {
+    drop_reports: bool,
+    level: Option<tracing::Level>,
+    rate_limit: Option<Duration>,
+    last_forwarded: HashMap<(Option<Value>, Option<Value>), Instant>,
+    last_pruned: Instant,
+    quota: Option<ComponentQuota>,
+}
+
+impl InternalLogsFilter {
+    fn new(
+        drop_reports: bool,
+        level: Option<InternalLogLevel>,
+        rate_limit: Option<Duration>,
+        quota: Option<ComponentQuotaConfig>,
+    ) -> Self {
+        Self {
+            drop_reports,
+            level: level.map(Into::into),
+            rate_limit,
+            last_forwarded: HashMap::new(),
+            last_pruned: Instant::now(),
+            quota: quota.map(ComponentQuota::new),
+        }
+    }
+
+    const fn has_quota(&self) -> bool {
+        self.quota.is_some()
+    }
+
+    /// Returns the summaries of the logs suppressed by the component quota since the previous
+    /// call, and starts counting the logs of each component anew.
+    fn summarize(&mut self) -> Vec<LogEvent> {
+        self.quota
+            .as_mut()
+            .map(ComponentQuota::summarize)
+            .unwrap_or_default()
+    }
+
+    fn should_forward(&mut self, log: &LogEvent, now: Instant) -> bool {
+        if is_drop_report(log) {
+            return self.drop_reports;
+        }
+
+        if let Some(min_level) = self.level {
+            let level = log
+                .get("metadata.level")
+                .and_then(Value::as_str)
+                .and_then(|level| level.parse::<tracing::Level>().ok());
+            // More verbose levels compare as greater.
+            if matches!(level, Some(level) if level > min_level) {
+                return false;
+            }
+        }
+
+        let forward = match self.rate_limit {
+            Some(window) => {
+                if now.duration_since(self.last_pruned) >= window {
+                    self.last_forwarded
+                        .retain(|_, forwarded| now.duration_since(*forwarded) < window);
+                    self.last_pruned = now;
+                }
+
+                let key = (
+                    log.get("message").cloned(),
+                    log.get("vector.component_id").cloned(),
+                );
+                match self.last_forwarded.get(&key) {
+                    Some(forwarded) if now.duration_since(*forwarded) < window => false,
+                    _ => {
+                        self.last_forwarded.insert(key, now);
+                        true
+                    }
+                }
+            }
+            None => true,
+        };
+
+        match &mut self.quota {
+            Some(quota) if forward => quota.admit(log),
+            _ => forward,
+        }
+    }
+}
+
+/// Counts the internal logs of each component, suppressing those over the quota.
+struct ComponentQuota {
+    config: ComponentQuotaConfig,
+    components: HashMap<Option<Value>, ComponentUsage>,
+}
+
+#[derive(Default)]
+struct ComponentUsage {
+    forwarded: u64,
+    suppressed: u64,
+    component: Option<Value>,
+    messages: HashMap<String, u64>,
+}
+
+impl ComponentQuota {
+    fn new(config: ComponentQuotaConfig) -> Self {
+        Self {
+            config,
+            components: HashMap::new(),
+        }
+    }
+
+    fn admit(&mut self, log: &LogEvent) -> bool {
+        let usage = self
+            .components
+            .entry(log.get("vector.component_id").cloned())
+            .or_default();
+        if usage.forwarded < self.config.max_events_per_sec {
+            usage.forwarded += 1;
+            return true;
+        }
+
+        usage.suppressed += 1;
+        if usage.component.is_none() {
+            usage.component = log.get("vector").cloned();
+        }
+        let message = log
+            .get("message")
+            .map(|message| message.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        if let Some(count) = usage.messages.get_mut(&message) {
+            *count += 1;
+        } else if usage.messages.len() < MAX_COUNTED_MESSAGES {
+            usage.messages.insert(message, 1);
+        }
+        false
+    }
+
+    fn summarize(&mut self) -> Vec<LogEvent> {
+        let top_messages = self.config.top_messages;
+        self.components
+            .drain()
+            .filter(|(_, usage)| usage.suppressed > 0)
+            .map(|(_, usage)| usage.summary(top_messages))
+            .collect()
+    }
+}
+
+impl ComponentUsage {
+    fn summary(self, top_messages: usize) -> LogEvent {
+        let mut messages = self.messages.into_iter().collect::<Vec<_>>();
+        // Ties are broken by message, so summaries don't depend on the order logs were counted in.
+        messages.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+        let top_messages = messages
+            .into_iter()
+            .take(top_messages)
+            .map(|(message, count)| {
+                Value::from(BTreeMap::from([
+                    ("message".to_string(), Value::from(message)),
+                    ("count".to_string(), Value::from(count)),
+                ]))
+            })
+            .collect::<Vec<_>>();
+
+        let mut log = LogEvent::from_str_legacy("Internal logs suppressed by the component quota.");
+        log.insert("timestamp", Utc::now());
+        log.insert("metadata.kind", "event");
+        log.insert("metadata.level", "WARN");
+        log.insert("metadata.target", QUOTA_SUMMARY_TARGET);
+        if let Some(component) = self.component {
+            log.insert("vector", component);
+        }
+        log.insert("suppressed", self.suppressed);
+        log.insert("top_messages", top_messages);
+        log
+    }
+}
+
+/// The span information a source asked for. Span timings are only tracked while a source asks for
+/// them, and span hierarchies once any source has asked for them, so logs may carry either
+/// regardless of what this source asked for.
+#[derive(Clone, Copy, Debug)]
+struct IncludedSpans {
+    timings: bool,
+    hierarchy: bool,
+}
+
+impl IncludedSpans {
+    /// Removes the span information that wasn't asked for from `log`.
+    fn strip(self, log: &mut LogEvent) {
+        if !self.timings {
+            log.remove("spans");
+        }
+        if !self.hierarchy {
+            log.remove("span_hierarchy");
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn run(
     host_key: Option<OwnedValuePath>,
     pid_key: Option<OwnedValuePath>,
+    mut filter: InternalLogsFilter,
+    spans: IncludedSpans,
+    mut guard: Option<SelfTelemetryGuard>,
     mut subscription: TraceSubscription,
     mut out: SourceSender,
     shutdown: ShutdownSignal,
@@ -145,10 +515,44 @@ async fn run(
         .chain(subscription.into_stream())
         .take_until(shutdown);
 
+    // Summaries of the logs suppressed by the component quota are sent every second.
+    let mut summary_interval = tokio::time::interval(Duration::from_secs(1));
+    let mut summaries = Vec::new();
+
+    // The guard, if any, decides whether the source is throttled once a second.
+    let mut guard_interval = tokio::time::interval(Duration::from_secs(1));
+
     // Note: This loop, or anything called within it, MUST NOT generate
     // any logs that don't break the loop, as that could cause an
-    // infinite loop since it receives all such logs.
-    while let Some(mut log) = rx.next().await {
+    // infinite loop since it receives all such logs. The warnings of the
+    // guard are the exception, as it emits at most one per window and never
+    // warns about them.
+    loop {
+        let mut log = match summaries.pop() {
+            Some(summary) => summary,
+            None => tokio::select! {
+                log = rx.next() => match log {
+                    Some(log) if guard.as_mut().map_or(false, |g| g.should_drop_log(&log)) => continue,
+                    Some(log) if filter.should_forward(&log, Instant::now()) => log,
+                    Some(_) => continue,
+                    None => break,
+                },
+                _ = summary_interval.tick(), if filter.has_quota() => {
+                    summaries = filter.summarize();
+                    continue;
+                }
+                _ = guard_interval.tick(), if guard.is_some() => {
+                    if let Some(guard) = &mut guard {
+                        guard.check();
+                    }
+                    continue;
+                }
+            },
+        };
+
+        let processing_start = Instant::now();
+        spans.strip(&mut log);
+
         let byte_size = log.estimated_json_encoded_size_of();
         // This event doesn't emit any log
         emit!(InternalLogsBytesReceived { byte_size });
@@ -183,6 +587,9 @@ async fn run(
             Utc::now(),
         );
 
+        if let Some(guard) = &mut guard {
+            guard.record_busy(processing_start.elapsed());
+        }
         if let Err(error) = out.send_event(Event::from(log)).await {
             // this wont trigger any infinite loop considering it stops the component
             emit!(StreamClosedError { error, count: 1 });
@@ -316,6 +723,112 @@ mod tests {
         }
     }
 
+    fn internal_log(message: &str, level: &str) -> LogEvent {
+        let mut log = LogEvent::from(message);
+        log.insert("metadata.level", level);
+        log.insert("metadata.target", "vector::test");
+        log
+    }
+
+    #[test]
+    fn strips_spans_not_asked_for() {
+        let mut log = internal_log("In a span.", "INFO");
+        log.insert("spans", vec![Value::from(1)]);
+        log.insert(
+            "span_hierarchy",
+            vec![Value::from(BTreeMap::from([(
+                "name".to_string(),
+                Value::from("source"),
+            )]))],
+        );
+
+        let mut stripped = log.clone();
+        IncludedSpans {
+            timings: false,
+            hierarchy: true,
+        }
+        .strip(&mut stripped);
+        assert!(stripped.get("spans").is_none());
+        assert_eq!(stripped["span_hierarchy[0].name"], "source".into());
+
+        IncludedSpans {
+            timings: true,
+            hierarchy: false,
+        }
+        .strip(&mut log);
+        assert!(log.get("spans").is_some());
+        assert!(log.get("span_hierarchy").is_none());
+    }
+
+    #[test]
+    fn filters_logs_below_level() {
+        let mut filter = InternalLogsFilter::new(false, Some(InternalLogLevel::Info), None, None);
+        let now = Instant::now();
+
+        assert!(filter.should_forward(&internal_log("error", "ERROR"), now));
+        assert!(filter.should_forward(&internal_log("info", "INFO"), now));
+        assert!(!filter.should_forward(&internal_log("debug", "DEBUG"), now));
+        assert!(!filter.should_forward(&internal_log("trace", "TRACE"), now));
+    }
+
+    #[test]
+    fn rate_limits_repeated_logs() {
+        let mut filter = InternalLogsFilter::new(false, None, Some(Duration::from_secs(10)), None);
+        let now = Instant::now();
+
+        assert!(filter.should_forward(&internal_log("foo", "INFO"), now));
+        assert!(filter.should_forward(&internal_log("bar", "INFO"), now));
+        assert!(!filter.should_forward(&internal_log("foo", "INFO"), now + Duration::from_secs(5)));
+
+        let mut other_component = internal_log("foo", "INFO");
+        other_component.insert("vector.component_id", "other");
+        assert!(filter.should_forward(&other_component, now + Duration::from_secs(5)));
+
+        assert!(filter.should_forward(&internal_log("foo", "INFO"), now + Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn summarizes_logs_over_component_quota() {
+        let mut filter = InternalLogsFilter::new(
+            false,
+            None,
+            None,
+            Some(ComponentQuotaConfig {
+                max_events_per_sec: 2,
+                top_messages: 1,
+            }),
+        );
+        let now = Instant::now();
+        let component_log = |message: &str, component_id: &str| {
+            let mut log = internal_log(message, "ERROR");
+            log.insert("vector.component_id", component_id);
+            log.insert("vector.component_kind", "sink");
+            log
+        };
+
+        assert!(filter.should_forward(&component_log("foo", "storm"), now));
+        assert!(filter.should_forward(&component_log("foo", "storm"), now));
+        assert!(!filter.should_forward(&component_log("foo", "storm"), now));
+        assert!(!filter.should_forward(&component_log("bar", "storm"), now));
+        assert!(!filter.should_forward(&component_log("foo", "storm"), now));
+        assert!(filter.should_forward(&component_log("foo", "other"), now));
+
+        let summaries = filter.summarize();
+        assert_eq!(summaries.len(), 1);
+        let summary = &summaries[0];
+        assert_eq!(summary["metadata.target"], QUOTA_SUMMARY_TARGET.into());
+        assert_eq!(summary["vector.component_id"], "storm".into());
+        assert_eq!(summary["vector.component_kind"], "sink".into());
+        assert_eq!(summary["suppressed"], 3.into());
+        assert_eq!(summary["top_messages[0].message"], "foo".into());
+        assert_eq!(summary["top_messages[0].count"], 2.into());
+        assert!(summary.get("top_messages[1]").is_none());
+
+        // The quota is reset after each summary.
+        assert!(filter.should_forward(&component_log("foo", "storm"), now));
+        assert!(filter.summarize().is_empty());
+    }
+
     async fn start_source() -> impl Stream<Item = Event> + Unpin {
         let (tx, rx) = SourceSender::new_test();
 
@@ -1,13 +1,21 @@
-use futures::StreamExt;
-use tokio::time;
-use tokio_stream::wrappers::IntervalStream;
-use vector_config::configurable_component;
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use heim::units::{information::byte, time::second};
+use indexmap::IndexMap;
+use tokio::time::{self, Instant};
+use vector_config::{configurable_component, NamedComponent};
 use vector_core::config::LogNamespace;
-use vector_core::EstimatedJsonEncodedSizeOf;
+use vector_core::{metric_tags, EstimatedJsonEncodedSizeOf};
 
 use crate::{
     config::{log_schema, DataType, Output, SourceConfig, SourceContext},
-    internal_events::{EventsReceived, InternalMetricsBytesReceived, StreamClosedError},
+    event::metric::{Metric, MetricKind, MetricSeries, MetricTags, MetricValue},
+    internal_events::{
+        EventsReceived, InternalMetricsBytesReceived, InternalMetricsProcessScrapeError,
+        InternalMetricsScrapeIntervalAdjusted, StreamClosedError,
+    },
+    internal_telemetry::guard::{SelfTelemetryGuard, SelfTelemetryGuardConfig},
     metrics::Controller,
     shutdown::ShutdownSignal,
     SourceSender,
@@ -30,6 +38,18 @@ pub struct InternalMetricsConfig {
     ///
     /// By default, `vector` is used.
     pub namespace: Option<String>,
+
+    #[configurable(derived)]
+    pub top_components: Option<TopComponentsConfig>,
+
+    #[configurable(derived)]
+    pub process_metrics: Option<ProcessMetricsConfig>,
+
+    #[configurable(derived)]
+    pub adaptive_interval: Option<AdaptiveIntervalConfig>,
+
+    #[configurable(derived)]
+    pub guard: Option<SelfTelemetryGuardConfig>,
 }
 
 impl InternalMetricsConfig {
@@ -60,6 +80,73 @@ pub struct TagsConfig {
     pub pid_key: Option<String>,
 }
 
+/// Configuration for only emitting the metrics of the most relevant components individually.
+///
+/// On each scrape, components are ranked by the value of `metric`, and only the metrics of the
+/// top `k` components are emitted as is. The metrics of all other components are summed up into
+/// a single series per metric, with a `component_id` tag of `other`. As the components summed up
+/// change from one scrape to the next, counters summed up this way are emitted as gauges. Metrics
+/// that are not specific to a component are always emitted.
+///
+/// This bounds the volume of internal metrics in topologies with many components.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct TopComponentsConfig {
+    /// The number of components whose metrics are emitted individually.
+    pub k: usize,
+
+    /// The name of the metric to rank components by.
+    ///
+    /// Components are ranked by how much a counter increased since the previous scrape, or by the
+    /// current value of a gauge, summed over all of their series of that metric.
+    #[serde(default = "default_top_components_metric")]
+    pub metric: String,
+}
+
+fn default_top_components_metric() -> String {
+    "component_sent_events_total".to_string()
+}
+
+/// Configuration for emitting metrics about the Vector process itself.
+///
+/// On each scrape, the CPU time, the resident and virtual memory, and, on Linux, the number of
+/// open file descriptors of the process are emitted alongside the component metrics.
+#[configurable_component]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct ProcessMetricsConfig {
+    /// Whether to also emit statistics of the Tokio runtime Vector runs on.
+    ///
+    /// Tokio only exposes these statistics when Vector is built with `--cfg tokio_unstable`, so
+    /// this has no effect otherwise.
+    #[derivative(Default(value = "true"))]
+    pub tokio_runtime: bool,
+}
+
+/// Configuration for lowering the scrape frequency when scrapes get expensive.
+///
+/// After each scrape, if capturing the metrics took longer than `max_capture_ratio` of the current
+/// interval, the interval is lengthened so that it no longer does, up to `max_interval_secs`. Once
+/// scrapes get cheap again, the interval is shortened back towards `scrape_interval_secs`.
+///
+/// This keeps the internal metrics of very large topologies from taking up a material share of
+/// CPU. The interval in effect is emitted as the `internal_metrics_scrape_interval_seconds` gauge.
+#[configurable_component]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct AdaptiveIntervalConfig {
+    /// The largest share of the interval capturing metrics may take, between 0 and 1.
+    #[derivative(Default(value = "0.1"))]
+    pub max_capture_ratio: f64,
+
+    /// The longest interval, in seconds, the scrape interval is lengthened to.
+    #[derivative(Default(value = "60.0"))]
+    pub max_interval_secs: f64,
+}
+
 impl_generate_config_from_default!(InternalMetricsConfig);
 
 #[async_trait::async_trait]
@@ -71,6 +158,10 @@ impl SourceConfig for InternalMetricsConfig {
             );
         }
         let interval = time::Duration::from_secs_f64(self.scrape_interval_secs);
+        let adaptive_interval = match &self.adaptive_interval {
+            Some(config) => Some(AdaptiveInterval::new(config, interval)?),
+            None => None,
+        };
         let namespace = self.namespace.clone();
 
         let host_key = match self
@@ -87,13 +178,30 @@ impl SourceConfig for InternalMetricsConfig {
             .pid_key
             .as_deref()
             .and_then(|tag| (!tag.is_empty()).then(|| tag.to_owned()));
+        let top_components = self.top_components.as_ref().map(TopComponents::new);
+        let process_metrics = self.process_metrics.as_ref().map(ProcessMetrics::new);
+        let guard = self
+            .guard
+            .as_ref()
+            .map(|config| {
+                SelfTelemetryGuard::new(
+                    config,
+                    InternalMetricsConfig::NAME,
+                    cx.self_telemetry.clone(),
+                )
+            })
+            .transpose()?;
         Ok(Box::pin(
             InternalMetrics {
                 namespace,
                 host_key,
                 pid_key,
+                top_components,
+                process_metrics,
                 controller: Controller::get()?,
                 interval,
+                adaptive_interval,
+                guard,
                 out: cx.out,
                 shutdown: cx.shutdown,
             }
@@ -114,21 +222,61 @@ struct InternalMetrics<'a> {
     namespace: Option<String>,
     host_key: Option<String>,
     pid_key: Option<String>,
+    top_components: Option<TopComponents>,
+    process_metrics: Option<ProcessMetrics>,
     controller: &'a Controller,
     interval: time::Duration,
+    adaptive_interval: Option<AdaptiveInterval>,
+    guard: Option<SelfTelemetryGuard>,
     out: SourceSender,
     shutdown: ShutdownSignal,
 }
 
 impl<'a> InternalMetrics<'a> {
     async fn run(mut self) -> Result<(), ()> {
-        let mut interval =
-            IntervalStream::new(time::interval(self.interval)).take_until(self.shutdown);
-        while interval.next().await.is_some() {
+        let mut interval = time::interval(self.interval);
+        if let Some(adaptive_interval) = &self.adaptive_interval {
+            emit!(InternalMetricsScrapeIntervalAdjusted {
+                interval: adaptive_interval.current,
+            });
+        }
+
+        loop {
+            tokio::select! {
+                _ = &mut self.shutdown => break,
+                _ = interval.tick() => {}
+            }
+
+            if let Some(guard) = &mut self.guard {
+                guard.check();
+                if guard.is_throttled() {
+                    continue;
+                }
+            }
+
             let hostname = crate::get_hostname();
             let pid = std::process::id().to_string();
 
-            let metrics = self.controller.capture_metrics();
+            let capture_start = Instant::now();
+            let mut metrics = self.controller.capture_metrics();
+            if let Some(guard) = &mut self.guard {
+                metrics.retain(|metric| !guard.should_drop_metric(metric));
+            }
+            if let Some(top_components) = &mut self.top_components {
+                metrics = top_components.apply(metrics);
+            }
+            if let Some(process_metrics) = &self.process_metrics {
+                process_metrics.capture(&mut metrics).await;
+            }
+            if let Some(adaptive_interval) = &mut self.adaptive_interval {
+                if let Some(period) = adaptive_interval.adjust(capture_start.elapsed()) {
+                    emit!(InternalMetricsScrapeIntervalAdjusted { interval: period });
+                    interval = time::interval_at(Instant::now() + period, period);
+                }
+            }
+            if let Some(guard) = &mut self.guard {
+                guard.record_busy(capture_start.elapsed());
+            }
             let count = metrics.len();
             let byte_size = metrics.estimated_json_encoded_size_of();
 
@@ -163,17 +311,318 @@ impl<'a> InternalMetrics<'a> {
     }
 }
 
+/// Adjusts the scrape interval to how long capturing metrics takes.
+struct AdaptiveInterval {
+    base: time::Duration,
+    max: time::Duration,
+    max_capture_ratio: f64,
+    current: time::Duration,
+}
+
+impl AdaptiveInterval {
+    fn new(config: &AdaptiveIntervalConfig, base: time::Duration) -> crate::Result<Self> {
+        if !(config.max_capture_ratio > 0.0 && config.max_capture_ratio <= 1.0) {
+            return Err("`adaptive_interval.max_capture_ratio` must be between 0 and 1.".into());
+        }
+        // Durations can't be negative, nor longer than `u64::MAX` seconds.
+        if !(config.max_interval_secs >= 0.0 && config.max_interval_secs < u64::MAX as f64) {
+            return Err("`adaptive_interval.max_interval_secs` is out of range.".into());
+        }
+        let max = time::Duration::from_secs_f64(config.max_interval_secs);
+        if max < base {
+            return Err(
+                "`adaptive_interval.max_interval_secs` can't be less than `scrape_interval_secs`."
+                    .into(),
+            );
+        }
+        Ok(Self {
+            base,
+            max,
+            max_capture_ratio: config.max_capture_ratio,
+            current: base,
+        })
+    }
+
+    /// Returns the new interval, if capturing metrics in `capture` calls for a change.
+    fn adjust(&mut self, capture: time::Duration) -> Option<time::Duration> {
+        let budget = self.current.mul_f64(self.max_capture_ratio);
+        let next = if capture > budget {
+            // Lengthen the interval enough for the capture to fit in the budget, and at least
+            // double it so that sustained load converges quickly.
+            capture
+                .div_f64(self.max_capture_ratio)
+                .max(self.current * 2)
+                .min(self.max)
+        } else if capture < budget / 4 && self.current > self.base {
+            // Shorten the interval gradually, so that it doesn't flap under steady load.
+            (self.current / 2).max(self.base)
+        } else {
+            self.current
+        };
+
+        (next != self.current).then(|| {
+            self.current = next;
+            next
+        })
+    }
+}
+
+/// Tags identifying the component a metric was emitted by.
+const COMPONENT_TAGS: [&str; 4] = [
+    "component_id",
+    "component_kind",
+    "component_type",
+    "component_name",
+];
+
+/// The `component_id` of the series aggregating components outside of the top ones.
+const OTHER_COMPONENTS: &str = "other";
+
+/// Keeps the metrics of the top components, aggregating the metrics of all other components.
+struct TopComponents {
+    k: usize,
+    metric: String,
+    /// The value of each counter series ranked by at the previous scrape.
+    previous: HashMap<MetricSeries, f64>,
+}
+
+impl TopComponents {
+    fn new(config: &TopComponentsConfig) -> Self {
+        Self {
+            k: config.k,
+            metric: config.metric.clone(),
+            previous: HashMap::new(),
+        }
+    }
+
+    fn apply(&mut self, metrics: Vec<Metric>) -> Vec<Metric> {
+        let top = self.rank(&metrics);
+
+        let mut output = Vec::with_capacity(metrics.len());
+        let mut others = IndexMap::<MetricSeries, Metric>::new();
+        for metric in metrics {
+            match metric.tag_value("component_id") {
+                Some(id) if !top.contains(&id) => {
+                    let mut metric = metric;
+                    for tag in COMPONENT_TAGS {
+                        metric.remove_tag(tag);
+                    }
+                    metric.replace_tag("component_id".into(), OTHER_COMPONENTS.into());
+                    // The sum goes backwards when a component enters the top ones.
+                    if let MetricValue::Counter { value } = *metric.value() {
+                        metric = metric.with_value(MetricValue::Gauge { value });
+                    }
+
+                    match others.get_mut(metric.series()) {
+                        // Values that can't be added up, such as histograms with different
+                        // buckets, are left out.
+                        Some(other) => {
+                            let _ = other.value_mut().add(metric.value());
+                        }
+                        None => {
+                            others.insert(metric.series().clone(), metric);
+                        }
+                    }
+                }
+                _ => output.push(metric),
+            }
+        }
+
+        output.extend(others.into_values());
+        output
+    }
+
+    /// Returns the IDs of the top `k` components.
+    fn rank(&mut self, metrics: &[Metric]) -> Vec<String> {
+        let mut scores = HashMap::<String, f64>::new();
+        let mut previous = HashMap::new();
+        for metric in metrics.iter().filter(|metric| metric.name() == self.metric) {
+            let id = match metric.tag_value("component_id") {
+                Some(id) => id,
+                None => continue,
+            };
+            let score = match metric.value() {
+                MetricValue::Counter { value } => {
+                    let increase = match self.previous.get(metric.series()) {
+                        // Counters reset when the component is reloaded.
+                        Some(last) if last <= value => value - last,
+                        _ => *value,
+                    };
+                    previous.insert(metric.series().clone(), *value);
+                    increase
+                }
+                MetricValue::Gauge { value } => *value,
+                _ => continue,
+            };
+            *scores.entry(id).or_default() += score;
+        }
+        self.previous = previous;
+
+        let mut scores = scores.into_iter().collect::<Vec<_>>();
+        scores.sort_by(|(a_id, a), (b_id, b)| b.total_cmp(a).then_with(|| a_id.cmp(b_id)));
+        scores.into_iter().take(self.k).map(|(id, _)| id).collect()
+    }
+}
+
+/// Captures the metrics of the Vector process and of the runtime it runs on.
+struct ProcessMetrics {
+    #[cfg_attr(not(tokio_unstable), allow(dead_code))]
+    tokio_runtime: bool,
+}
+
+impl ProcessMetrics {
+    const fn new(config: &ProcessMetricsConfig) -> Self {
+        Self {
+            tokio_runtime: config.tokio_runtime,
+        }
+    }
+
+    async fn capture(&self, metrics: &mut Vec<Metric>) {
+        let timestamp = Utc::now();
+
+        match heim::process::current().await {
+            Ok(process) => {
+                match process.memory().await {
+                    Ok(memory) => {
+                        metrics.push(process_metric(
+                            "process_resident_memory_bytes",
+                            MetricValue::Gauge {
+                                value: memory.rss().get::<byte>() as f64,
+                            },
+                            None,
+                            timestamp,
+                        ));
+                        metrics.push(process_metric(
+                            "process_virtual_memory_bytes",
+                            MetricValue::Gauge {
+                                value: memory.vms().get::<byte>() as f64,
+                            },
+                            None,
+                            timestamp,
+                        ));
+                    }
+                    Err(error) => emit!(InternalMetricsProcessScrapeError {
+                        message: "Failed to load process memory info.",
+                        error,
+                    }),
+                }
+
+                match process.cpu_time().await {
+                    Ok(time) => {
+                        for (mode, time) in [("user", time.user()), ("system", time.system())] {
+                            metrics.push(process_metric(
+                                "process_cpu_seconds_total",
+                                MetricValue::Counter {
+                                    value: time.get::<second>(),
+                                },
+                                Some(metric_tags!("mode" => mode)),
+                                timestamp,
+                            ));
+                        }
+                    }
+                    Err(error) => emit!(InternalMetricsProcessScrapeError {
+                        message: "Failed to load process CPU time.",
+                        error,
+                    }),
+                }
+            }
+            Err(error) => emit!(InternalMetricsProcessScrapeError {
+                message: "Failed to load process info.",
+                error,
+            }),
+        }
+
+        #[cfg(target_os = "linux")]
+        match std::fs::read_dir("/proc/self/fd") {
+            Ok(fds) => metrics.push(process_metric(
+                "process_open_fds",
+                MetricValue::Gauge {
+                    value: fds.count() as f64,
+                },
+                None,
+                timestamp,
+            )),
+            Err(error) => emit!(InternalMetricsProcessScrapeError {
+                message: "Failed to count open file descriptors.",
+                error,
+            }),
+        }
+
+        // The runtime metrics are only exposed by Tokio when built with `--cfg tokio_unstable`.
+        #[cfg(tokio_unstable)]
+        if self.tokio_runtime {
+            if let Ok(handle) = tokio::runtime::Handle::try_current() {
+                capture_runtime_metrics(&handle.metrics(), metrics, timestamp);
+            }
+        }
+    }
+}
+
+#[cfg(tokio_unstable)]
+fn capture_runtime_metrics(
+    runtime: &tokio::runtime::RuntimeMetrics,
+    metrics: &mut Vec<Metric>,
+    timestamp: DateTime<Utc>,
+) {
+    metrics.push(process_metric(
+        "tokio_workers",
+        MetricValue::Gauge {
+            value: runtime.num_workers() as f64,
+        },
+        None,
+        timestamp,
+    ));
+    metrics.push(process_metric(
+        "tokio_global_queue_depth",
+        MetricValue::Gauge {
+            value: runtime.injection_queue_depth() as f64,
+        },
+        None,
+        timestamp,
+    ));
+    for worker in 0..runtime.num_workers() {
+        let tags = || Some(metric_tags!("worker" => worker.to_string()));
+        metrics.push(process_metric(
+            "tokio_worker_steals_total",
+            MetricValue::Counter {
+                value: runtime.worker_steal_count(worker) as f64,
+            },
+            tags(),
+            timestamp,
+        ));
+        metrics.push(process_metric(
+            "tokio_worker_local_queue_depth",
+            MetricValue::Gauge {
+                value: runtime.worker_local_queue_depth(worker) as f64,
+            },
+            tags(),
+            timestamp,
+        ));
+    }
+}
+
+fn process_metric(
+    name: &str,
+    value: MetricValue,
+    tags: Option<MetricTags>,
+    timestamp: DateTime<Utc>,
+) -> Metric {
+    Metric::new(name, MetricKind::Absolute, value)
+        .with_namespace(Some("vector"))
+        .with_tags(tags)
+        .with_timestamp(Some(timestamp))
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::BTreeMap;
 
     use metrics::{counter, gauge, histogram};
-    use vector_core::metric_tags;
 
     use super::*;
     use crate::{
         event::{
-            metric::{Metric, MetricValue},
+            metric::{Metric, MetricKind, MetricValue},
             Event,
         },
         metrics::Controller,
@@ -303,6 +752,183 @@ mod tests {
         assert!(metric.tag_value("pid").is_none());
     }
 
+    #[tokio::test]
+    async fn captures_process_metrics() {
+        let process_metrics = ProcessMetrics::new(&ProcessMetricsConfig::default());
+        let mut metrics = Vec::new();
+        process_metrics.capture(&mut metrics).await;
+
+        let value = |name: &str| {
+            metrics
+                .iter()
+                .find(|metric| metric.name() == name)
+                .map(|metric| metric.value().clone())
+        };
+        assert!(matches!(
+            value("process_resident_memory_bytes"),
+            Some(MetricValue::Gauge { value }) if value > 0.0
+        ));
+        assert!(matches!(
+            value("process_cpu_seconds_total"),
+            Some(MetricValue::Counter { .. })
+        ));
+        #[cfg(tokio_unstable)]
+        assert!(matches!(
+            value("tokio_workers"),
+            Some(MetricValue::Gauge { .. })
+        ));
+        #[cfg(target_os = "linux")]
+        assert!(matches!(
+            value("process_open_fds"),
+            Some(MetricValue::Gauge { value }) if value > 0.0
+        ));
+    }
+
+    #[tokio::test]
+    async fn no_process_metrics_by_default() {
+        let events = run_and_assert_source_compliance(
+            InternalMetricsConfig::default(),
+            time::Duration::from_millis(100),
+            &SOURCE_TAGS,
+        )
+        .await;
+
+        assert!(events
+            .iter()
+            .all(|event| !event.as_metric().name().starts_with("process_")));
+    }
+
+    #[test]
+    fn adapts_interval_to_capture_duration() {
+        let config = AdaptiveIntervalConfig {
+            max_capture_ratio: 0.25,
+            max_interval_secs: 16.0,
+        };
+        let secs = time::Duration::from_secs_f64;
+        let mut interval = AdaptiveInterval::new(&config, secs(1.0)).unwrap();
+
+        assert_eq!(interval.adjust(secs(0.1)), None);
+        // Capturing takes 0.75s, which fits in a 3s interval.
+        assert_eq!(interval.adjust(secs(0.75)), Some(secs(3.0)));
+        // Doubled at least, up to the maximum.
+        assert_eq!(interval.adjust(secs(1.0)), Some(secs(6.0)));
+        assert_eq!(interval.adjust(secs(8.0)), Some(secs(16.0)));
+        assert_eq!(interval.adjust(secs(8.0)), None);
+        assert_eq!(interval.adjust(secs(2.0)), None);
+
+        // Shortened gradually back to the base interval once capturing is cheap again.
+        assert_eq!(interval.adjust(secs(0.5)), Some(secs(8.0)));
+        assert_eq!(interval.adjust(secs(0.5)), None);
+        assert_eq!(interval.adjust(secs(0.0)), Some(secs(4.0)));
+        assert_eq!(interval.adjust(secs(0.0)), Some(secs(2.0)));
+        assert_eq!(interval.adjust(secs(0.0)), Some(secs(1.0)));
+        assert_eq!(interval.adjust(secs(0.0)), None);
+
+        assert!(AdaptiveInterval::new(&config, secs(20.0)).is_err());
+    }
+
+    fn component_counter(name: &str, component_id: &str, value: f64) -> Metric {
+        Metric::new(name, MetricKind::Absolute, MetricValue::Counter { value }).with_tags(Some(
+            metric_tags!(
+                "component_id" => component_id,
+                "component_kind" => "sink",
+            ),
+        ))
+    }
+
+    #[test]
+    fn rejects_invalid_adaptive_interval() {
+        for max_interval_secs in [-1.0, f64::NAN, f64::INFINITY, 1e20, 0.5] {
+            let config = AdaptiveIntervalConfig {
+                max_capture_ratio: 0.25,
+                max_interval_secs,
+            };
+            assert!(AdaptiveInterval::new(&config, time::Duration::from_secs(1)).is_err());
+        }
+    }
+
+    #[test]
+    fn keeps_top_components() {
+        let mut top_components = TopComponents::new(&TopComponentsConfig {
+            k: 1,
+            metric: "component_sent_events_total".into(),
+        });
+
+        let metrics = vec![
+            component_counter("component_sent_events_total", "a", 10.0),
+            component_counter("component_sent_events_total", "b", 5.0),
+            component_counter("component_sent_events_total", "c", 1.0),
+            component_counter("component_errors_total", "b", 2.0),
+            component_counter("component_errors_total", "c", 3.0),
+            Metric::new(
+                "uptime_seconds",
+                MetricKind::Absolute,
+                MetricValue::Gauge { value: 1.0 },
+            ),
+        ];
+        let output = top_components.apply(metrics);
+
+        let values = output
+            .iter()
+            .map(|metric| {
+                (
+                    metric.name(),
+                    metric.tag_value("component_id"),
+                    metric.tag_value("component_kind"),
+                    metric.value().clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let other = Some(OTHER_COMPONENTS.to_string());
+        assert_eq!(
+            values,
+            vec![
+                (
+                    "component_sent_events_total",
+                    Some("a".into()),
+                    Some("sink".into()),
+                    MetricValue::Counter { value: 10.0 }
+                ),
+                (
+                    "uptime_seconds",
+                    None,
+                    None,
+                    MetricValue::Gauge { value: 1.0 }
+                ),
+                (
+                    "component_sent_events_total",
+                    other.clone(),
+                    None,
+                    MetricValue::Gauge { value: 6.0 }
+                ),
+                (
+                    "component_errors_total",
+                    other,
+                    None,
+                    MetricValue::Gauge { value: 5.0 }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn ranks_counters_by_increase() {
+        let mut top_components = TopComponents::new(&TopComponentsConfig {
+            k: 1,
+            metric: "component_sent_events_total".into(),
+        });
+
+        let scrape = |a, b| {
+            vec![
+                component_counter("component_sent_events_total", "a", a),
+                component_counter("component_sent_events_total", "b", b),
+            ]
+        };
+
+        assert_eq!(top_components.rank(&scrape(100.0, 10.0)), vec!["a"]);
+        assert_eq!(top_components.rank(&scrape(101.0, 50.0)), vec!["b"]);
+    }
+
     #[tokio::test]
     async fn namespace() {
         let namespace = "totally_custom";
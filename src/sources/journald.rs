@@ -11,7 +11,9 @@ use std::{
 use bytes::Bytes;
 use chrono::{TimeZone, Utc};
 use codecs::{decoding::BoxedFramingError, CharacterDelimitedDecoder};
-use futures::{poll, stream::BoxStream, task::Poll, StreamExt};
+use futures::{poll, stream, stream::BoxStream, task::Poll, StreamExt, TryStreamExt};
+use http::{header, Request, Uri};
+use hyper::Body;
 use lookup::{lookup_v2::parse_value_path, metadata_path, owned_value_path, path, PathPrefix};
 use nix::{
     sys::signal::{kill, Signal},
@@ -27,7 +29,7 @@ use tokio::{
     sync::{Mutex, MutexGuard},
     time::sleep,
 };
-use tokio_util::codec::FramedRead;
+use tokio_util::{codec::FramedRead, io::StreamReader};
 use value::{kind::Collection, Kind, Value};
 use vector_common::{
     finalizer::OrderedFinalizer,
@@ -45,6 +47,7 @@ use crate::{
         log_schema, DataType, Output, SourceAcknowledgementsConfig, SourceConfig, SourceContext,
     },
     event::{BatchNotifier, BatchStatus, BatchStatusReceiver, LogEvent},
+    http::HttpClient,
     internal_events::{
         EventsReceived, JournaldCheckpointFileOpenError, JournaldCheckpointSetError,
         JournaldInvalidRecordError, JournaldReadError, JournaldStartJournalctlError,
@@ -52,6 +55,7 @@ use crate::{
     },
     serde::bool_or_struct,
     shutdown::ShutdownSignal,
+    tls::{TlsConfig, TlsSettings},
     SourceSender,
 };
 
@@ -87,6 +91,13 @@ enum BuildError {
         value,
     ))]
     DuplicatedMatches { field: String, value: String },
+    #[snafu(display("Invalid gateway endpoint {:?}: {}", endpoint, source))]
+    InvalidGatewayEndpoint {
+        endpoint: String,
+        source: http::uri::InvalidUri,
+    },
+    #[snafu(display("Cannot use `since_now` when reading from a journal gateway"))]
+    GatewaySinceNow,
 }
 
 type Matches = HashMap<String, HashSet<String>>;
@@ -147,6 +158,9 @@ pub struct JournaldConfig {
     /// If not set, `journalctl` will use the default system journal paths.
     pub journal_directory: Option<PathBuf>,
 
+    #[configurable(derived)]
+    pub gateway: Option<JournaldGatewayConfig>,
+
     #[configurable(derived)]
     #[serde(default, deserialize_with = "bool_or_struct")]
     acknowledgements: SourceAcknowledgementsConfig,
@@ -164,6 +178,23 @@ pub struct JournaldConfig {
     log_namespace: Option<bool>,
 }
 
+/// Configuration for reading from a remote `systemd-journal-gatewayd` endpoint.
+///
+/// When set, journal entries are streamed from the gateway over HTTP instead of being read by
+/// running `journalctl`, and `journalctl_path` and `journal_directory` are ignored. The cursor of
+/// the last entry read is checkpointed as it is when reading the local journal, so that reading
+/// resumes right after it on restart.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct JournaldGatewayConfig {
+    /// The base URL of the gateway, such as `http://localhost:19531`.
+    pub endpoint: String,
+
+    #[configurable(derived)]
+    pub tls: Option<TlsConfig>,
+}
+
 impl JournaldConfig {
     fn merged_include_matches(&self) -> crate::Result<Matches> {
         let include_units = match (!self.units.is_empty(), !self.include_units.is_empty()) {
@@ -275,17 +306,34 @@ impl SourceConfig for JournaldConfig {
         let mut checkpoint_path = data_dir;
         checkpoint_path.push(CHECKPOINT_FILENAME);
 
-        let journalctl_path = self
-            .journalctl_path
-            .clone()
-            .unwrap_or_else(|| JOURNALCTL.clone());
+        let starter = match &self.gateway {
+            Some(gateway) => {
+                if self.since_now.unwrap_or(false) {
+                    return Err(BuildError::GatewaySinceNow.into());
+                }
 
-        let starter = StartJournalctl::new(
-            journalctl_path,
-            self.journal_directory.clone(),
-            self.current_boot_only.unwrap_or(true),
-            self.since_now.unwrap_or(false),
-        );
+                let tls = TlsSettings::from_options(&gateway.tls)?;
+                let client = HttpClient::new(tls, &cx.proxy)?;
+                JournalStarter::Gateway(StartGateway::new(
+                    client,
+                    &gateway.endpoint,
+                    self.current_boot_only.unwrap_or(true),
+                )?)
+            }
+            None => {
+                let journalctl_path = self
+                    .journalctl_path
+                    .clone()
+                    .unwrap_or_else(|| JOURNALCTL.clone());
+
+                JournalStarter::Journalctl(StartJournalctl::new(
+                    journalctl_path,
+                    self.journal_directory.clone(),
+                    self.current_boot_only.unwrap_or(true),
+                    self.since_now.unwrap_or(false),
+                ))
+            }
+        };
 
         let batch_size = self.batch_size.unwrap_or(DEFAULT_BATCH_SIZE);
         let acknowledgements = cx.do_acknowledgements(self.acknowledgements);
@@ -327,7 +375,7 @@ struct JournaldSource {
     remap_priority: bool,
     out: SourceSender,
     acknowledgements: bool,
-    starter: StartJournalctl,
+    starter: JournalStarter,
     log_namespace: LogNamespace,
 }
 
@@ -369,7 +417,7 @@ impl JournaldSource {
                 break;
             }
 
-            info!("Starting journalctl.");
+            info!("Starting to read the journal.");
             let cursor = checkpointer.lock().await.cursor.clone();
             match self.starter.start(cursor.as_deref()) {
                 Ok((stream, running)) => {
@@ -464,7 +512,7 @@ impl<'a> Batch<'a> {
     fn handle_next(&mut self, result: Option<Result<Bytes, BoxedFramingError>>) -> bool {
         match result {
             None => {
-                warn!("Journalctl process stopped.");
+                warn!("Journal stream stopped.");
                 self.exiting = Some(true);
                 false
             }
@@ -546,6 +594,88 @@ impl<'a> Batch<'a> {
 
 type JournalStream = BoxStream<'static, Result<Bytes, BoxedFramingError>>;
 
+/// Starts reading the journal, either by running `journalctl` or from a journal gateway.
+enum JournalStarter {
+    Journalctl(StartJournalctl),
+    Gateway(StartGateway),
+}
+
+impl JournalStarter {
+    fn start(
+        &mut self,
+        checkpoint: Option<&str>,
+    ) -> crate::Result<(JournalStream, Option<RunningJournalctl>)> {
+        match self {
+            Self::Journalctl(starter) => starter
+                .start(checkpoint)
+                .map(|(stream, running)| (stream, Some(running))),
+            Self::Gateway(starter) => Ok((starter.start(checkpoint), None)),
+        }
+    }
+}
+
+struct StartGateway {
+    client: HttpClient,
+    uri: Uri,
+}
+
+impl StartGateway {
+    fn new(client: HttpClient, endpoint: &str, current_boot_only: bool) -> crate::Result<Self> {
+        let mut uri = format!("{}/entries?follow", endpoint.trim_end_matches('/'));
+        if current_boot_only {
+            uri.push_str("&boot");
+        }
+        let uri = uri
+            .parse()
+            .context(InvalidGatewayEndpointSnafu { endpoint })?;
+
+        Ok(Self { client, uri })
+    }
+
+    fn make_request(&self, checkpoint: Option<&str>) -> Request<Body> {
+        let mut request = Request::get(&self.uri).header(header::ACCEPT, "application/json");
+        if let Some(cursor) = checkpoint {
+            // Skip the checkpointed entry itself, as it was already read.
+            request = request.header(header::RANGE, format!("entries={}:1:", cursor));
+        }
+        request
+            .body(Body::empty())
+            .expect("journal gateway request is valid")
+    }
+
+    /// Streams the entries of the journal from the gateway, with the same framing as the output
+    /// of `journalctl`.
+    fn start(&self, checkpoint: Option<&str>) -> JournalStream {
+        let response = self.client.send(self.make_request(checkpoint));
+
+        stream::once(async move {
+            let response = response
+                .await
+                .map_err(|error| io::Error::new(io::ErrorKind::Other, error))?;
+            if !response.status().is_success() {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("journal gateway responded with {}", response.status()),
+                ));
+            }
+
+            let body = response
+                .into_body()
+                .map_err(|error| io::Error::new(io::ErrorKind::Other, error));
+            Ok(FramedRead::new(
+                StreamReader::new(body),
+                CharacterDelimitedDecoder::new(b'\n'),
+            ))
+        })
+        .map(|result| match result {
+            Ok(frames) => frames.boxed(),
+            Err(error) => stream::once(async move { Err(BoxedFramingError::from(error)) }).boxed(),
+        })
+        .flatten()
+        .boxed()
+    }
+}
+
 struct StartJournalctl {
     path: PathBuf,
     journal_dir: Option<PathBuf>,
@@ -1360,6 +1490,77 @@ mod tests {
         assert!(cmd_line.contains("--after-cursor="));
     }
 
+    #[tokio::test]
+    async fn reads_from_gateway_after_checkpoint() {
+        use warp::Filter;
+
+        let addr = crate::test_util::next_addr();
+        let gateway = warp::path!("entries")
+            .and(warp::header::exact("Range", "entries=s=1:1:"))
+            .map(|| {
+                concat!(
+                    r#"{"__CURSOR":"s=2","__REALTIME_TIMESTAMP":"1578529839140002","#,
+                    r#""MESSAGE":"from gateway","_SYSTEMD_UNIT":"sysinit.target"}"#,
+                    "\n"
+                )
+            });
+        tokio::spawn(warp::serve(gateway).run(addr));
+        crate::test_util::wait_for_tcp(addr).await;
+
+        let tempdir = tempdir().unwrap();
+        let mut checkpoint_path = tempdir.path().join(TEST_COMPONENT);
+        fs::create_dir(&checkpoint_path).unwrap();
+        checkpoint_path.push(CHECKPOINT_FILENAME);
+        let mut checkpointer = Checkpointer::new(checkpoint_path.clone()).await.unwrap();
+        checkpointer.set("s=1").await.unwrap();
+
+        let (tx, rx) = SourceSender::new_test_finalize(EventStatus::Delivered);
+        let (cx, shutdown) = SourceContext::new_shutdown(&ComponentKey::from(TEST_COMPONENT), tx);
+        let config = JournaldConfig {
+            gateway: Some(JournaldGatewayConfig {
+                endpoint: format!("http://{}", addr),
+                tls: None,
+            }),
+            data_dir: Some(tempdir.path().to_path_buf()),
+            acknowledgements: false.into(),
+            ..Default::default()
+        };
+        let source = config.build(cx).await.unwrap();
+        tokio::spawn(async move { source.await.unwrap() });
+
+        sleep(Duration::from_millis(100)).await;
+        shutdown
+            .shutdown_all(Instant::now() + Duration::from_secs(1))
+            .await;
+
+        let received: Vec<Event> = timeout(Duration::from_secs(1), rx.collect()).await.unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(message(&received[0]), Value::Bytes("from gateway".into()));
+        assert_eq!(checkpointer.get().await.unwrap().as_deref(), Some("s=2"));
+    }
+
+    #[test]
+    fn gateway_requests() {
+        let client = HttpClient::new(None, &Default::default()).unwrap();
+
+        let starter = StartGateway::new(client.clone(), "http://localhost:19531/", true).unwrap();
+        let request = starter.make_request(None);
+        assert_eq!(
+            request.uri().to_string(),
+            "http://localhost:19531/entries?follow&boot"
+        );
+        assert_eq!(request.headers()[header::ACCEPT], "application/json");
+        assert!(request.headers().get(header::RANGE).is_none());
+
+        let starter = StartGateway::new(client, "http://localhost:19531", false).unwrap();
+        let request = starter.make_request(Some("s=abc;i=1"));
+        assert_eq!(
+            request.uri().to_string(),
+            "http://localhost:19531/entries?follow"
+        );
+        assert_eq!(request.headers()[header::RANGE], "entries=s=abc;i=1:1:");
+    }
+
     fn create_command(
         path: &Path,
         journal_dir: Option<PathBuf>,
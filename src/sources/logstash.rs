@@ -20,7 +20,9 @@ use vector_core::{
     schema::Definition,
 };
 
-use super::util::net::{SocketListenAddr, TcpSource, TcpSourceAck, TcpSourceAcker};
+use super::util::net::{
+    PeerAddrMetadata, SocketListenAddr, TcpSource, TcpSourceAck, TcpSourceAcker, TcpSourceOptions,
+};
 use crate::{
     config::{
         log_schema, DataType, GenerateConfig, Output, Resource, SourceAcknowledgementsConfig,
@@ -54,6 +56,12 @@ pub struct LogstashConfig {
     /// The maximum number of TCP connections that will be allowed at any given time.
     connection_limit: Option<u32>,
 
+    /// The amount of time, in seconds, a connection can stay open without sending any data before it
+    /// is closed.
+    ///
+    /// By default, connections are never closed for being idle.
+    idle_timeout_secs: Option<u64>,
+
     #[configurable(derived)]
     #[serde(default, deserialize_with = "bool_or_struct")]
     acknowledgements: SourceAcknowledgementsConfig,
@@ -101,6 +109,7 @@ impl Default for LogstashConfig {
             receive_buffer_bytes: None,
             acknowledgements: Default::default(),
             connection_limit: None,
+            idle_timeout_secs: None,
             log_namespace: None,
         }
     }
@@ -121,12 +130,17 @@ impl SourceConfig for LogstashConfig {
             legacy_host_key_path: parse_value_path(log_schema().host_key()).ok(),
         };
         let shutdown_secs = 30;
-        let tls_config = self.tls.as_ref().map(|tls| tls.tls_config.clone());
         let tls_client_metadata_key = self
             .tls
             .as_ref()
             .and_then(|tls| tls.client_metadata_key.clone());
-        let tls = MaybeTlsSettings::from_config(&tls_config, true)?;
+        let tls_client_allowlist = self
+            .tls
+            .as_ref()
+            .map(|tls| tls.client_allowlist())
+            .transpose()?
+            .flatten();
+        let tls = MaybeTlsSettings::from_source_config(&self.tls)?;
         source.run(
             self.address,
             self.keepalive,
@@ -137,6 +151,11 @@ impl SourceConfig for LogstashConfig {
             cx,
             self.acknowledgements,
             self.connection_limit,
+            TcpSourceOptions {
+                tls_client_allowlist,
+                idle_timeout_secs: self.idle_timeout_secs,
+                ..Default::default()
+            },
         )
     }
 
@@ -169,12 +188,21 @@ impl TcpSource for LogstashSource {
     type Item = LogstashEventFrame;
     type Decoder = LogstashDecoder;
     type Acker = LogstashAcker;
+    type Instrumentation = ();
 
     fn decoder(&self) -> Self::Decoder {
         LogstashDecoder::new()
     }
 
-    fn handle_events(&self, events: &mut [Event], host: SocketAddr) {
+    fn peer_addr_metadata(&self) -> Option<PeerAddrMetadata> {
+        Some(PeerAddrMetadata::new(
+            LogstashConfig::NAME,
+            self.log_namespace,
+            self.legacy_host_key_path.clone(),
+        ))
+    }
+
+    fn handle_events(&self, events: &mut [Event], _host: SocketAddr) {
         let now = chrono::Utc::now();
         for event in events {
             let log = event.as_mut_log();
@@ -210,16 +238,6 @@ impl TcpSource for LogstashSource {
                     );
                 }
             }
-
-            self.log_namespace.insert_source_metadata(
-                LogstashConfig::NAME,
-                log,
-                self.legacy_host_key_path
-                    .as_ref()
-                    .map(LegacyKey::InsertIfEmpty),
-                path!("host"),
-                host.ip().to_string(),
-            );
         }
     }
 
@@ -690,6 +708,7 @@ mod test {
                 receive_buffer_bytes: None,
                 acknowledgements: true.into(),
                 connection_limit: None,
+                idle_timeout_secs: None,
                 log_namespace: None,
             }
             .build(SourceContext::new_test(sender, None))
@@ -910,6 +929,9 @@ mod integration_tests {
         };
         let tls_config = TlsSourceConfig {
             client_metadata_key: None,
+            client_allowlist: None,
+            policy: None,
+            sni_certificates: vec![],
             tls_config: tls_options,
         };
         tokio::spawn(async move {
@@ -920,6 +942,7 @@ mod integration_tests {
                 receive_buffer_bytes: None,
                 acknowledgements: false.into(),
                 connection_limit: None,
+                idle_timeout_secs: None,
                 log_namespace: None,
             }
             .build(SourceContext::new_test(sender, None))
@@ -7,7 +7,7 @@ use vector_config::configurable_component;
 use vector_core::config::LogNamespace;
 use warp::http::{HeaderMap, StatusCode};
 
-use super::parser;
+use crate::common::prometheus as parser;
 use crate::{
     config::{
         self, GenerateConfig, Output, SourceAcknowledgementsConfig, SourceConfig, SourceContext,
@@ -14,7 +14,7 @@ use crate::serde::default_framing_message_based;
 use crate::{
     codecs::DecodingConfig,
     config::{GenerateConfig, Output, Resource, SourceConfig, SourceContext},
-    sources::util::net::TcpSource,
+    sources::util::net::{TcpSource, TcpSourceOptions},
     tls::MaybeTlsSettings,
 };
 
@@ -137,12 +137,22 @@ impl SourceConfig for SocketConfig {
                 let decoder = DecodingConfig::new(framing, decoding, log_namespace).build();
 
                 let tcp = tcp::RawTcpSource::new(config.clone(), decoder, log_namespace);
-                let tls_config = config.tls().as_ref().map(|tls| tls.tls_config.clone());
                 let tls_client_metadata_key = config
                     .tls()
                     .as_ref()
                     .and_then(|tls| tls.client_metadata_key.clone());
-                let tls = MaybeTlsSettings::from_config(&tls_config, true)?;
+                let tls_client_allowlist = config
+                    .tls()
+                    .as_ref()
+                    .map(|tls| tls.client_allowlist())
+                    .transpose()?
+                    .flatten();
+                let connection_metadata = config
+                    .connection_metadata
+                    .as_ref()
+                    .map(|metadata| metadata.build(Self::NAME, log_namespace))
+                    .transpose()?;
+                let tls = MaybeTlsSettings::from_source_config(config.tls())?;
                 tcp.run(
                     config.address(),
                     config.keepalive(),
@@ -151,11 +161,21 @@ impl SourceConfig for SocketConfig {
                     tls_client_metadata_key,
                     config.receive_buffer_bytes(),
                     cx,
-                    false.into(),
+                    config.acknowledgements,
                     config.connection_limit,
+                    TcpSourceOptions {
+                        tls_client_allowlist,
+                        idle_timeout_secs: config.idle_timeout_secs,
+                        handoff_socket: config.handoff_socket.clone(),
+                        adaptive_read_buffer: config.adaptive_read_buffer,
+                        decoding_concurrency: config.decoding_concurrency,
+                        wire_capture: config.wire_capture,
+                        connection_metadata,
+                    },
                 )
             }
             Mode::Udp(config) => {
+                config.validate()?;
                 let log_namespace = cx.log_namespace(config.log_namespace);
                 let decoder = DecodingConfig::new(
                     config.framing().clone(),
@@ -245,7 +265,7 @@ impl SourceConfig for SocketConfig {
                         LegacyKey::InsertIfEmpty,
                     );
 
-                schema_definition
+                let schema_definition = schema_definition
                     .with_source_metadata(
                         Self::NAME,
                         Some(host_key_path),
@@ -259,7 +279,14 @@ impl SourceConfig for SocketConfig {
                         &owned_value_path!("port"),
                         Kind::bytes(),
                         None,
-                    )
+                    );
+
+                match &config.connection_metadata {
+                    Some(connection_metadata) => {
+                        connection_metadata.schema_definition(Self::NAME, schema_definition)
+                    }
+                    None => schema_definition,
+                }
             }
             Mode::Udp(config) => {
                 let host_key_path = config
@@ -352,7 +379,9 @@ impl SourceConfig for SocketConfig {
     }
 
     fn can_acknowledge(&self) -> bool {
-        false
+        // Delivery can only be confirmed to clients of the TCP mode, and only if they are sent
+        // responses at all.
+        matches!(&self.mode, Mode::Tcp(config) if config.ack_format.is_some())
     }
 }
 
@@ -361,6 +390,7 @@ mod test {
     use std::{
         collections::{BTreeMap, HashMap},
         net::{SocketAddr, UdpSocket},
+        num::NonZeroUsize,
         sync::{
             atomic::{AtomicBool, Ordering},
             Arc,
@@ -398,19 +428,29 @@ mod test {
         tokio_util::codec::{FramedWrite, LinesCodec},
     };
 
-    use super::{tcp::TcpConfig, udp::UdpConfig, SocketConfig};
+    use super::{
+        tcp::{TcpAckFormat, TcpConfig},
+        udp::{MulticastGroupConfig, UdpConfig},
+        SocketConfig,
+    };
     use crate::{
         config::{log_schema, ComponentKey, GlobalOptions, SourceConfig, SourceContext},
-        event::{Event, LogEvent},
+        event::{Event, EventStatus, LogEvent},
         shutdown::{ShutdownSignal, SourceShutdownCoordinator},
         sinks::util::tcp::TcpSinkConfig,
-        sources::util::net::SocketListenAddr,
+        sources::util::net::{
+            AdaptiveReadBufferConfig, ConnectionMetadataConfig, DecodingConcurrencyConfig,
+            SocketListenAddr,
+        },
         test_util::{
             collect_n, collect_n_limited,
             components::{assert_source_compliance, SOCKET_HIGH_CARDINALITY_PUSH_SOURCE_TAGS},
             next_addr, random_string, send_lines, send_lines_tls, wait_for_tcp,
         },
-        tls::{self, TlsConfig, TlsEnableableConfig, TlsSourceConfig},
+        tls::{
+            self, TlsClientAllowlistConfig, TlsConfig, TlsEnableableConfig, TlsPolicyConfig,
+            TlsSniCertificateConfig, TlsSourceConfig,
+        },
         SourceSender,
     };
 
@@ -511,6 +551,66 @@ mod test {
         .await;
     }
 
+    #[tokio::test]
+    async fn tcp_with_adaptive_read_buffer() {
+        let (tx, rx) = SourceSender::new_test();
+        let addr = next_addr();
+
+        let mut config = TcpConfig::from_address(addr.into());
+        config.adaptive_read_buffer = Some(AdaptiveReadBufferConfig {
+            min_bytes: 16,
+            max_bytes: 256,
+        });
+
+        let server = SocketConfig::from(config)
+            .build(SourceContext::new_test(tx, None))
+            .await
+            .unwrap();
+        tokio::spawn(server);
+
+        let lines = (0..100).map(|i| format!("{:0>200}", i)).collect::<Vec<_>>();
+
+        wait_for_tcp(addr).await;
+        send_lines(addr, lines.clone().into_iter()).await.unwrap();
+
+        let events = collect_n(rx, lines.len()).await;
+
+        assert_eq!(events.len(), lines.len());
+        for (event, line) in events.iter().zip(lines) {
+            assert_eq!(event.as_log()[log_schema().message_key()], line.into());
+        }
+    }
+
+    #[tokio::test]
+    async fn tcp_with_decoding_concurrency() {
+        let (tx, rx) = SourceSender::new_test();
+        let addr = next_addr();
+
+        let mut config = TcpConfig::from_address(addr.into());
+        config.decoding_concurrency = Some(DecodingConcurrencyConfig {
+            workers: NonZeroUsize::new(4).unwrap(),
+            ordered: true,
+        });
+
+        let server = SocketConfig::from(config)
+            .build(SourceContext::new_test(tx, None))
+            .await
+            .unwrap();
+        tokio::spawn(server);
+
+        let lines = (0..10_000).map(|i| i.to_string()).collect::<Vec<_>>();
+
+        wait_for_tcp(addr).await;
+        send_lines(addr, lines.clone().into_iter()).await.unwrap();
+
+        let events = collect_n(rx, lines.len()).await;
+
+        assert_eq!(events.len(), lines.len());
+        for (event, line) in events.iter().zip(lines) {
+            assert_eq!(event.as_log()[log_schema().message_key()], line.into());
+        }
+    }
+
     #[tokio::test]
     async fn tcp_it_includes_source_type() {
         assert_source_compliance(&SOCKET_HIGH_CARDINALITY_PUSH_SOURCE_TAGS, async {
@@ -595,7 +695,16 @@ mod test {
                     },
                 },
                 client_metadata_key: Some("tls_peer".into()),
+                client_allowlist: None,
+                policy: None,
+                sni_certificates: vec![],
             }));
+            config.connection_metadata = Some(ConnectionMetadataConfig {
+                local_addr_key: Some("listener".into()),
+                tls_version_key: Some("tls_version".into()),
+                tls_cipher_key: Some("tls_cipher".into()),
+                tls_server_name_key: Some("tls_server_name".into()),
+            });
 
             let server = SocketConfig::from(config)
                 .build(SourceContext::new_test(tx, None))
@@ -628,6 +737,12 @@ mod test {
             );
 
             assert_eq!(event.as_log()["tls_peer"], tls_meta.clone().into(),);
+            assert_eq!(event.as_log()["listener"], addr.to_string().into());
+            assert!(event.as_log()["tls_version"]
+                .to_string_lossy()
+                .starts_with("TLSv1."));
+            assert!(event.as_log().contains("tls_cipher"));
+            assert_eq!(event.as_log()["tls_server_name"], "localhost".into());
 
             let event = rx.next().await.unwrap();
             assert_eq!(
@@ -640,6 +755,216 @@ mod test {
         .await;
     }
 
+    fn tls_source_config_with_allowlist(allowlist: TlsClientAllowlistConfig) -> TlsSourceConfig {
+        TlsSourceConfig {
+            tls_config: TlsEnableableConfig {
+                enabled: Some(true),
+                options: TlsConfig {
+                    verify_certificate: Some(true),
+                    crt_file: Some(tls::TEST_PEM_CRT_PATH.into()),
+                    key_file: Some(tls::TEST_PEM_KEY_PATH.into()),
+                    ca_file: Some(tls::TEST_PEM_CA_PATH.into()),
+                    ..Default::default()
+                },
+            },
+            client_metadata_key: Some("tls_peer".into()),
+            client_allowlist: Some(allowlist),
+            policy: None,
+            sni_certificates: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn tcp_with_fips_tls_policy() {
+        let (tx, mut rx) = SourceSender::new_test();
+        let addr = next_addr();
+
+        let mut tls = tls_source_config_with_allowlist(Default::default());
+        tls.client_allowlist = None;
+        tls.policy = Some(TlsPolicyConfig {
+            fips: true,
+            require_client_certificate: true,
+            ..Default::default()
+        });
+        let mut config = TcpConfig::from_address(addr.into());
+        config.set_tls(Some(tls));
+
+        let server = SocketConfig::from(config)
+            .build(SourceContext::new_test(tx, None))
+            .await
+            .unwrap();
+        tokio::spawn(server);
+
+        wait_for_tcp(addr).await;
+        send_lines_tls(
+            addr,
+            "localhost".into(),
+            vec!["one line".to_owned()].into_iter(),
+            std::path::Path::new(tls::TEST_PEM_CA_PATH),
+            std::path::Path::new(tls::TEST_PEM_CLIENT_CRT_PATH),
+            std::path::Path::new(tls::TEST_PEM_CLIENT_KEY_PATH),
+        )
+        .await
+        .unwrap();
+
+        let event = rx.next().await.unwrap();
+        assert_eq!(
+            event.as_log()[log_schema().message_key()],
+            "one line".into()
+        );
+    }
+
+    #[tokio::test]
+    async fn tcp_tls_policy_violations_fail_the_build() {
+        let mut tls = tls_source_config_with_allowlist(Default::default());
+        tls.policy = Some(TlsPolicyConfig {
+            fips: true,
+            cipher_suites: Some(vec!["TLS_CHACHA20_POLY1305_SHA256".into()]),
+            ..Default::default()
+        });
+        let mut config = TcpConfig::from_address(next_addr().into());
+        config.set_tls(Some(tls.clone()));
+        let error = SocketConfig::from(config)
+            .build(SourceContext::new_test(SourceSender::new_test().0, None))
+            .await
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("`cipher_suites`"), "{}", error);
+
+        tls.tls_config.enabled = Some(false);
+        tls.policy = Some(TlsPolicyConfig::default());
+        let mut config = TcpConfig::from_address(next_addr().into());
+        config.set_tls(Some(tls));
+        let error = SocketConfig::from(config)
+            .build(SourceContext::new_test(SourceSender::new_test().0, None))
+            .await
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("`enabled`"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn tcp_with_tls_client_allowlist() {
+        let (tx, mut rx) = SourceSender::new_test();
+        let addr = next_addr();
+
+        // The SPKI fingerprint of `TEST_PEM_CLIENT_CRT_PATH`.
+        let fingerprint = "86a709b3716e8c7ab159dc3c1d2c10cd8bd17376066196d5acdaa3e3e5162884";
+        let mut config = TcpConfig::from_address(addr.into());
+        config.set_tls(Some(tls_source_config_with_allowlist(
+            TlsClientAllowlistConfig {
+                spki_sha256: vec![fingerprint.into()],
+                san_patterns: vec![],
+            },
+        )));
+
+        let server = SocketConfig::from(config)
+            .build(SourceContext::new_test(tx, None))
+            .await
+            .unwrap();
+        tokio::spawn(server);
+
+        wait_for_tcp(addr).await;
+        send_lines_tls(
+            addr,
+            "localhost".into(),
+            vec!["one line".to_owned()].into_iter(),
+            std::path::Path::new(tls::TEST_PEM_CA_PATH),
+            std::path::Path::new(tls::TEST_PEM_CLIENT_CRT_PATH),
+            std::path::Path::new(tls::TEST_PEM_CLIENT_KEY_PATH),
+        )
+        .await
+        .unwrap();
+
+        let event = rx.next().await.unwrap();
+        assert_eq!(
+            event.as_log()["tls_peer.matched_identity"],
+            fingerprint.into()
+        );
+    }
+
+    #[tokio::test]
+    async fn tcp_with_tls_sni_certificates() {
+        let (tx, mut rx) = SourceSender::new_test();
+        let addr = next_addr();
+
+        let mut tls = tls_source_config_with_allowlist(Default::default());
+        tls.client_allowlist = None;
+        tls.sni_certificates = vec![TlsSniCertificateConfig {
+            server_names: vec!["kafka".into(), "*.kafka.example.com".into()],
+            crt_file: "tests/data/ca/intermediate_server/certs/kafka-chain.cert.pem".into(),
+            key_file: Some("tests/data/ca/intermediate_server/private/kafka.key.pem".into()),
+            key_pass: None,
+        }];
+        let mut config = TcpConfig::from_address(addr.into());
+        config.set_tls(Some(tls));
+        config.connection_metadata = Some(ConnectionMetadataConfig {
+            tls_server_name_key: Some("tls_server_name".into()),
+            ..Default::default()
+        });
+
+        let server = SocketConfig::from(config)
+            .build(SourceContext::new_test(tx, None))
+            .await
+            .unwrap();
+        tokio::spawn(server);
+
+        wait_for_tcp(addr).await;
+        // The client verifies the hostname, so it only connects if presented the certificate of
+        // the server name it requested.
+        for host in ["kafka", "localhost"] {
+            send_lines_tls(
+                addr,
+                host.into(),
+                vec![host.to_owned()].into_iter(),
+                std::path::Path::new(tls::TEST_PEM_CA_PATH),
+                std::path::Path::new(tls::TEST_PEM_CLIENT_CRT_PATH),
+                std::path::Path::new(tls::TEST_PEM_CLIENT_KEY_PATH),
+            )
+            .await
+            .unwrap();
+
+            let event = rx.next().await.unwrap();
+            assert_eq!(event.as_log()["tls_server_name"], host.into());
+        }
+    }
+
+    #[tokio::test]
+    async fn tcp_with_tls_client_allowlist_rejects_client() {
+        let (tx, mut rx) = SourceSender::new_test();
+        let addr = next_addr();
+
+        let mut config = TcpConfig::from_address(addr.into());
+        config.set_tls(Some(tls_source_config_with_allowlist(
+            TlsClientAllowlistConfig {
+                spki_sha256: vec![],
+                san_patterns: vec!["spiffe://example.org/*".into()],
+            },
+        )));
+
+        let server = SocketConfig::from(config)
+            .build(SourceContext::new_test(tx, None))
+            .await
+            .unwrap();
+        tokio::spawn(server);
+
+        wait_for_tcp(addr).await;
+        // The connection is closed by the source, so sending may or may not fail.
+        let _ = send_lines_tls(
+            addr,
+            "localhost".into(),
+            vec!["one line".to_owned()].into_iter(),
+            std::path::Path::new(tls::TEST_PEM_CA_PATH),
+            std::path::Path::new(tls::TEST_PEM_CLIENT_CRT_PATH),
+            std::path::Path::new(tls::TEST_PEM_CLIENT_KEY_PATH),
+        )
+        .await;
+
+        assert!(timeout(Duration::from_millis(500), rx.next())
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     async fn tcp_shutdown_simple() {
         assert_source_compliance(&SOCKET_HIGH_CARDINALITY_PUSH_SOURCE_TAGS, async {
@@ -676,6 +1001,202 @@ mod test {
         .await;
     }
 
+    #[tokio::test]
+    async fn tcp_closes_idle_connections() {
+        let (tx, _rx) = SourceSender::new_test();
+        let addr = next_addr();
+
+        let mut config = TcpConfig::from_address(addr.into());
+        config.idle_timeout_secs = Some(1);
+        let server = SocketConfig::from(config)
+            .build(SourceContext::new_test(tx, None))
+            .await
+            .unwrap();
+        tokio::spawn(server);
+
+        wait_for_tcp(addr).await;
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+
+        // The connection never sends anything, so the source closes it once the timeout expires.
+        let mut buf = [0u8; 1];
+        let read = timeout(
+            Duration::from_secs(5),
+            tokio::io::AsyncReadExt::read(&mut stream, &mut buf),
+        )
+        .await
+        .expect("connection should be closed by the source");
+        assert_eq!(read.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn tcp_sends_acknowledgements() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // The connection is closed after the first failed delivery, so only a single message is
+        // sent when delivery fails.
+        for (status, format, messages, expected) in [
+            (
+                EventStatus::Delivered,
+                TcpAckFormat::Newline,
+                &b"foo\nbar\n"[..],
+                &b"OK\nOK\n"[..],
+            ),
+            (
+                EventStatus::Delivered,
+                TcpAckFormat::LengthPrefixed,
+                &b"foo\nbar\n"[..],
+                &b"\0\0\0\x02OK\0\0\0\x02OK"[..],
+            ),
+            (
+                EventStatus::Rejected,
+                TcpAckFormat::Newline,
+                &b"foo\n"[..],
+                &b"ERR rejected\n"[..],
+            ),
+            (
+                EventStatus::Errored,
+                TcpAckFormat::LengthPrefixed,
+                &b"foo\n"[..],
+                &b"\0\0\0\x0bERR errored"[..],
+            ),
+        ] {
+            let (tx, _rx) = SourceSender::new_test_finalize(status);
+            let addr = next_addr();
+
+            let mut config = TcpConfig::from_address(addr.into());
+            config.ack_format = Some(format);
+            config.acknowledgements = true.into();
+            let server = SocketConfig::from(config)
+                .build(SourceContext::new_test(tx, None))
+                .await
+                .unwrap();
+            tokio::spawn(server);
+
+            wait_for_tcp(addr).await;
+            let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+            stream.write_all(messages).await.unwrap();
+
+            let mut response = Vec::new();
+            timeout(Duration::from_secs(5), async {
+                while response.len() < expected.len() {
+                    if stream.read_buf(&mut response).await.unwrap() == 0 {
+                        break;
+                    }
+                }
+            })
+            .await
+            .expect("source should respond to each message");
+            assert_eq!(response, expected);
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn tcp_accepts_handed_off_connections() {
+        use nix::sys::socket::{sendmsg, ControlMessage, MsgFlags, UnixAddr};
+        use std::{io::Write, os::unix::io::AsRawFd};
+
+        let (tx, mut rx) = SourceSender::new_test();
+        let addr = next_addr();
+        let handoff_path = tempfile::tempdir().unwrap().into_path().join("handoff");
+
+        let mut config = TcpConfig::from_address(addr.into());
+        config.handoff_socket = Some(handoff_path.clone());
+        let server = SocketConfig::from(config)
+            .build(SourceContext::new_test(tx, None))
+            .await
+            .unwrap();
+        tokio::spawn(server);
+
+        wait_for_tcp(addr).await;
+        let path = handoff_path.clone();
+        wait_for(move || ready(path.exists())).await;
+
+        // Establish a connection the source knows nothing about, and hand it off.
+        let listener = std::net::TcpListener::bind(next_addr()).unwrap();
+        let mut client = std::net::TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (accepted, _) = listener.accept().unwrap();
+
+        let control = std::os::unix::net::UnixStream::connect(&handoff_path).unwrap();
+        sendmsg::<UnixAddr>(
+            control.as_raw_fd(),
+            &[std::io::IoSlice::new(b"x")],
+            &[ControlMessage::ScmRights(&[accepted.as_raw_fd()])],
+            MsgFlags::empty(),
+            None,
+        )
+        .unwrap();
+        drop(accepted);
+
+        client.write_all(b"handed off\n").unwrap();
+        let event = timeout(Duration::from_secs(5), rx.next())
+            .await
+            .expect("handed off connection should be read by the source")
+            .unwrap();
+        assert_eq!(
+            event.as_log()[log_schema().message_key()],
+            "handed off".into()
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn tcp_replaces_stale_handoff_socket() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (tx, _rx) = SourceSender::new_test();
+        let addr = next_addr();
+        let handoff_path = tempfile::tempdir().unwrap().into_path().join("handoff");
+
+        // A socket file nothing listens on anymore, as left behind by a crash.
+        drop(std::os::unix::net::UnixListener::bind(&handoff_path).unwrap());
+
+        let mut config = TcpConfig::from_address(addr.into());
+        config.handoff_socket = Some(handoff_path.clone());
+        let server = SocketConfig::from(config)
+            .build(SourceContext::new_test(tx, None))
+            .await
+            .unwrap();
+        tokio::spawn(server);
+
+        wait_for_tcp(addr).await;
+        let path = handoff_path.clone();
+        wait_for(move || ready(std::os::unix::net::UnixStream::connect(&path).is_ok())).await;
+
+        let mode = std::fs::metadata(&handoff_path)
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn tcp_deletes_handoff_socket_before_completing() {
+        let addr = next_addr();
+        let handoff_path = tempfile::tempdir().unwrap().into_path().join("handoff");
+
+        let (source_tx, _source_rx) = SourceSender::new_test();
+        let source_key = ComponentKey::from("tcp_deletes_handoff_socket_before_completing");
+        let (source_cx, mut shutdown) = SourceContext::new_shutdown(&source_key, source_tx);
+
+        let mut config = TcpConfig::from_address(addr.into());
+        config.handoff_socket = Some(handoff_path.clone());
+        let source_task = SocketConfig::from(config).build(source_cx).await.unwrap();
+        let source_handle = tokio::spawn(source_task);
+
+        wait_for_tcp(addr).await;
+        let path = handoff_path.clone();
+        wait_for(move || ready(path.exists())).await;
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        assert!(shutdown.shutdown_source(&source_key, deadline).await);
+        assert_eq!(source_handle.await.unwrap(), Ok(()));
+
+        // A source replacing this one can bind the same path right away.
+        assert!(!handoff_path.exists());
+    }
+
     // Intentially not using assert_source_compliance here because this is a round-trip test which
     // means source and sink will both emit `EventsSent` , triggering multi-emission check.
     #[tokio::test]
@@ -855,6 +1376,7 @@ mod test {
                 acknowledgements: false,
                 schema: Default::default(),
                 schema_definitions: HashMap::default(),
+                self_telemetry: Default::default(),
             })
             .await
             .unwrap();
@@ -883,6 +1405,31 @@ mod test {
         .await;
     }
 
+    #[tokio::test]
+    async fn udp_multicast_group_requires_matching_interface() {
+        let socket = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+
+        let group: MulticastGroupConfig = toml::from_str(indoc::indoc! {r#"
+            group = "239.1.2.3"
+            interface_index = 1
+        "#})
+        .unwrap();
+        assert_eq!(
+            group.join(&socket).unwrap_err().kind(),
+            std::io::ErrorKind::InvalidInput
+        );
+
+        let group: MulticastGroupConfig = toml::from_str(indoc::indoc! {r#"
+            group = "ff02::1:3"
+            interface = "127.0.0.1"
+        "#})
+        .unwrap();
+        assert_eq!(
+            group.join(&socket).unwrap_err().kind(),
+            std::io::ErrorKind::InvalidInput
+        );
+    }
+
     #[tokio::test]
     async fn udp_message_preserves_newline() {
         assert_source_compliance(&SOCKET_HIGH_CARDINALITY_PUSH_SOURCE_TAGS, async {
@@ -988,6 +1535,39 @@ mod test {
         .await;
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn udp_multiple_sockets() {
+        assert_source_compliance(&SOCKET_HIGH_CARDINALITY_PUSH_SOURCE_TAGS, async {
+            let (tx, rx) = SourceSender::new_test();
+            let address = next_addr();
+            let mut config = UdpConfig::from_address(address.into());
+            config.set_sockets(NonZeroUsize::new(4).unwrap());
+            let address = init_udp_with_config(tx, config).await;
+
+            // Each sender is a separate flow, which may be received on any of the sockets.
+            for i in 0..8 {
+                send_lines_udp(address, vec![format!("test{}", i)]);
+            }
+
+            let mut messages = collect_n(rx, 8)
+                .await
+                .into_iter()
+                .map(|event| {
+                    event.as_log()[log_schema().message_key()]
+                        .to_string_lossy()
+                        .into_owned()
+                })
+                .collect::<Vec<_>>();
+            messages.sort();
+            assert_eq!(
+                messages,
+                (0..8).map(|i| format!("test{}", i)).collect::<Vec<_>>()
+            );
+        })
+        .await;
+    }
+
     #[tokio::test]
     async fn udp_it_includes_host() {
         assert_source_compliance(&SOCKET_HIGH_CARDINALITY_PUSH_SOURCE_TAGS, async {
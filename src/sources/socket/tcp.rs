@@ -1,16 +1,23 @@
+use std::path::PathBuf;
+
+use bytes::{BufMut, Bytes, BytesMut};
 use chrono::Utc;
 use codecs::decoding::{DeserializerConfig, FramingConfig};
-use lookup::{lookup_v2::BorrowedSegment, path};
+use lookup::OwnedValuePath;
 use smallvec::SmallVec;
 use vector_config::{configurable_component, NamedComponent};
-use vector_core::config::{LegacyKey, LogNamespace};
+use vector_core::config::LogNamespace;
 
 use crate::{
     codecs::Decoder,
-    config::log_schema,
+    config::{log_schema, SourceAcknowledgementsConfig},
     event::Event,
-    serde::default_decoding,
-    sources::util::net::{SocketListenAddr, TcpNullAcker, TcpSource},
+    serde::{bool_or_struct, default_decoding},
+    sources::util::net::{
+        AdaptiveReadBufferConfig, ConcurrentDecoder, ConnectionMetadataConfig,
+        DecodingConcurrencyConfig, PeerAddrMetadata, SocketListenAddr, TcpSource, TcpSourceAck,
+        TcpSourceAcker, WireCaptureConfig,
+    },
     tcp::TcpKeepaliveConfig,
     tls::TlsSourceConfig,
 };
@@ -60,9 +67,60 @@ pub struct TcpConfig {
     /// This should not typically needed to be changed.
     receive_buffer_bytes: Option<usize>,
 
+    /// Sizes the buffer each connection is read into based on the traffic observed on it.
+    ///
+    /// By default, a fixed size buffer is used for every connection.
+    #[configurable(derived)]
+    pub adaptive_read_buffer: Option<AdaptiveReadBufferConfig>,
+
+    /// Deserializes the frames of each connection on several tasks.
+    ///
+    /// This lets a single busy connection use more than one core. By default, the frames of each
+    /// connection are deserialized on a single task.
+    #[configurable(derived)]
+    pub decoding_concurrency: Option<DecodingConcurrencyConfig>,
+
+    #[configurable(derived)]
+    pub wire_capture: Option<WireCaptureConfig>,
+
+    #[configurable(derived)]
+    pub connection_metadata: Option<ConnectionMetadataConfig>,
+
     /// The maximum number of TCP connections that will be allowed at any given time.
     pub connection_limit: Option<u32>,
 
+    /// The amount of time, in seconds, a connection can stay open without sending any data before it
+    /// is closed.
+    ///
+    /// By default, connections are never closed for being idle.
+    pub idle_timeout_secs: Option<u64>,
+
+    /// The path of a Unix domain socket over which established connections can be handed off to
+    /// this source.
+    ///
+    /// Other processes, such as load balancers or connection redirectors, can pass connected TCP
+    /// sockets as file descriptors in `SCM_RIGHTS` control messages. These connections are then
+    /// handled as if they had been accepted on `address`, without the source having to rebind it.
+    ///
+    /// The socket is only accessible to the user Vector runs as. A socket file left behind by a
+    /// previous run is replaced, as long as nothing listens on it anymore.
+    ///
+    /// Only supported on Unix.
+    pub handoff_socket: Option<PathBuf>,
+
+    /// The format of the responses sent back to clients for each message received.
+    ///
+    /// When set, a response is written back on the connection once the events decoded from each
+    /// message have been accepted, or, if `acknowledgements` are enabled, once they have been
+    /// delivered. If delivery fails, an error response is written and the connection is closed.
+    ///
+    /// By default, nothing is ever written back to clients.
+    pub ack_format: Option<TcpAckFormat>,
+
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    pub acknowledgements: SourceAcknowledgementsConfig,
+
     #[configurable(derived)]
     framing: Option<FramingConfig>,
 
@@ -79,6 +137,62 @@ const fn default_shutdown_timeout_secs() -> u64 {
     30
 }
 
+/// Format of the acknowledgements sent back to clients.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TcpAckFormat {
+    /// Each response is a line of text terminated by a newline.
+    ///
+    /// The line is `OK` if the events were delivered, or `ERR` followed by a space and the reason
+    /// (`errored` or `rejected`) otherwise.
+    Newline,
+
+    /// Each response is the same text as for `newline`, without the trailing newline, prefixed by
+    /// its length as a 32-bit big-endian integer.
+    LengthPrefixed,
+}
+
+impl TcpAckFormat {
+    fn encode(self, ack: TcpSourceAck) -> Bytes {
+        let response: &[u8] = match ack {
+            TcpSourceAck::Ack => b"OK",
+            TcpSourceAck::Error => b"ERR errored",
+            TcpSourceAck::Reject => b"ERR rejected",
+        };
+
+        let mut bytes = BytesMut::with_capacity(response.len() + 4);
+        match self {
+            Self::Newline => {
+                bytes.put_slice(response);
+                bytes.put_u8(b'\n');
+            }
+            Self::LengthPrefixed => {
+                bytes.put_u32(response.len() as u32);
+                bytes.put_slice(response);
+            }
+        }
+        bytes.freeze()
+    }
+}
+
+/// Writes a response to the client for each message received, if an `ack_format` is configured.
+///
+/// Messages are acknowledged in batches of those read together, so the same response is written
+/// once for each message of the batch.
+pub struct RawTcpAcker {
+    format: Option<TcpAckFormat>,
+    messages: usize,
+}
+
+impl TcpSourceAcker for RawTcpAcker {
+    fn build_ack(self, ack: TcpSourceAck) -> Option<Bytes> {
+        let messages = self.messages;
+        self.format
+            .map(|format| format.encode(ack).repeat(messages).into())
+    }
+}
+
 impl TcpConfig {
     pub fn from_address(address: SocketListenAddr) -> Self {
         Self {
@@ -90,9 +204,17 @@ impl TcpConfig {
             port_key: Some(String::from("port")),
             tls: None,
             receive_buffer_bytes: None,
+            adaptive_read_buffer: None,
+            decoding_concurrency: None,
+            wire_capture: None,
+            connection_metadata: None,
             framing: None,
             decoding: default_decoding(),
             connection_limit: None,
+            idle_timeout_secs: None,
+            handoff_socket: None,
+            ack_format: None,
+            acknowledgements: Default::default(),
             log_namespace: None,
         }
     }
@@ -189,13 +311,32 @@ impl TcpSource for RawTcpSource {
     type Error = codecs::decoding::Error;
     type Item = SmallVec<[Event; 1]>;
     type Decoder = Decoder;
-    type Acker = TcpNullAcker;
+    type Acker = RawTcpAcker;
+    type Instrumentation = ();
 
     fn decoder(&self) -> Self::Decoder {
         self.decoder.clone()
     }
 
-    fn handle_events(&self, events: &mut [Event], host: std::net::SocketAddr) {
+    fn peer_addr_metadata(&self) -> Option<PeerAddrMetadata> {
+        let host_key = self
+            .config
+            .host_key
+            .as_deref()
+            .unwrap_or_else(|| log_schema().host_key());
+        let port_key = self.config.port_key.as_deref().unwrap_or("port");
+
+        Some(
+            PeerAddrMetadata::new(
+                SocketConfig::NAME,
+                self.log_namespace,
+                Some(OwnedValuePath::single_field(host_key)),
+            )
+            .with_port(Some(OwnedValuePath::single_field(port_key))),
+        )
+    }
+
+    fn handle_events(&self, events: &mut [Event], _host: std::net::SocketAddr) {
         let now = Utc::now();
 
         for event in events {
@@ -205,37 +346,18 @@ impl TcpSource for RawTcpSource {
                     SocketConfig::NAME,
                     now,
                 );
-
-                let host_key_path = self.config.host_key.as_ref().map_or_else(
-                    || [BorrowedSegment::from(log_schema().host_key())],
-                    |key| [BorrowedSegment::from(key)],
-                );
-
-                self.log_namespace.insert_source_metadata(
-                    SocketConfig::NAME,
-                    log,
-                    Some(LegacyKey::InsertIfEmpty(&host_key_path)),
-                    path!("host"),
-                    host.ip().to_string(),
-                );
-
-                let port_key_path = self.config.port_key.as_ref().map_or_else(
-                    || [BorrowedSegment::from("port")],
-                    |key| [BorrowedSegment::from(key)],
-                );
-
-                self.log_namespace.insert_source_metadata(
-                    SocketConfig::NAME,
-                    log,
-                    Some(LegacyKey::InsertIfEmpty(&port_key_path)),
-                    path!("port"),
-                    host.port(),
-                );
             }
         }
     }
 
-    fn build_acker(&self, _: &[Self::Item]) -> Self::Acker {
-        TcpNullAcker
+    fn build_acker(&self, frames: &[Self::Item]) -> Self::Acker {
+        RawTcpAcker {
+            format: self.config.ack_format,
+            messages: frames.len(),
+        }
+    }
+
+    fn concurrent_decoder(&self) -> Option<ConcurrentDecoder<Self::Item, Self::Error>> {
+        Some(ConcurrentDecoder::new(self.decoder.clone()))
     }
 }
@@ -1,3 +1,5 @@
+use std::{net::IpAddr, num::NonZeroUsize};
+
 use bytes::BytesMut;
 use chrono::Utc;
 use codecs::{
@@ -7,8 +9,12 @@ use codecs::{
 use futures::StreamExt;
 use listenfd::ListenFd;
 use lookup::{lookup_v2::BorrowedSegment, path};
+use tokio::{net::UdpSocket, task::JoinHandle};
 use tokio_util::codec::FramedRead;
-use vector_common::internal_event::{ByteSize, BytesReceived, InternalEventHandle as _, Protocol};
+use tracing::Instrument;
+use vector_common::internal_event::{
+    ByteSize, BytesReceived, InternalEventHandle as _, Protocol, Registered,
+};
 use vector_config::{configurable_component, NamedComponent};
 use vector_core::{
     config::{LegacyKey, LogNamespace},
@@ -20,13 +26,14 @@ use crate::{
     config::log_schema,
     event::Event,
     internal_events::{
-        SocketBindError, SocketEventsReceived, SocketMode, SocketReceiveError, StreamClosedError,
+        SocketBindError, SocketEventsReceived, SocketMode, SocketMulticastJoinError,
+        SocketReceiveError, StreamClosedError,
     },
     serde::{default_decoding, default_framing_message_based},
     shutdown::ShutdownSignal,
     sources::{
         socket::SocketConfig,
-        util::net::{try_bind_udp_socket, SocketListenAddr},
+        util::net::{try_bind_udp_sockets, SocketListenAddr},
         Source,
     },
     udp, SourceSender,
@@ -67,6 +74,37 @@ pub struct UdpConfig {
     /// This should not typically needed to be changed.
     receive_buffer_bytes: Option<usize>,
 
+    /// The multicast groups to join.
+    ///
+    /// The listening address should be a wildcard address, such as `0.0.0.0:9000`, or the address
+    /// of the groups themselves, for messages sent to the groups to be received.
+    #[serde(default)]
+    multicast_groups: Vec<MulticastGroupConfig>,
+
+    /// Whether multicast messages sent from the listening socket are looped back to the host.
+    ///
+    /// By default, the operating system's default is used.
+    multicast_loop: Option<bool>,
+
+    /// The time-to-live, or hop limit for IPv6, of multicast messages sent from the listening
+    /// socket.
+    ///
+    /// By default, the operating system's default is used.
+    multicast_ttl: Option<u32>,
+
+    /// The number of sockets to receive messages on.
+    ///
+    /// When more than one, the sockets are all bound to the listening address with `SO_REUSEPORT`
+    /// and each is read by its own task, so that the kernel spreads the load across them by
+    /// hashing the flows of the messages. Messages of a single flow are all received on the same
+    /// socket.
+    ///
+    /// Only supported on Unix, and can't be used with a systemd socket or combined with
+    /// `multicast_groups`, as each socket would receive every multicast message.
+    ///
+    /// By default, a single socket is used.
+    sockets: Option<NonZeroUsize>,
+
     #[configurable(derived)]
     #[serde(default = "default_framing_message_based")]
     pub(super) framing: FramingConfig,
@@ -80,6 +118,45 @@ pub struct UdpConfig {
     pub log_namespace: Option<bool>,
 }
 
+/// A multicast group to join.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct MulticastGroupConfig {
+    /// The address of the multicast group.
+    group: IpAddr,
+
+    /// The address of the local interface to join an IPv4 group on.
+    ///
+    /// By default, the operating system chooses the interface.
+    interface: Option<IpAddr>,
+
+    /// The index of the local interface to join an IPv6 group on.
+    ///
+    /// By default, the operating system chooses the interface.
+    interface_index: Option<u32>,
+}
+
+impl MulticastGroupConfig {
+    pub(super) fn join(&self, socket: &UdpSocket) -> std::io::Result<()> {
+        match (self.group, self.interface) {
+            (IpAddr::V4(group), None) if self.interface_index.is_none() => {
+                socket.join_multicast_v4(group, std::net::Ipv4Addr::UNSPECIFIED)
+            }
+            (IpAddr::V4(group), Some(IpAddr::V4(interface))) if self.interface_index.is_none() => {
+                socket.join_multicast_v4(group, interface)
+            }
+            (IpAddr::V6(group), None) => {
+                socket.join_multicast_v6(&group, self.interface_index.unwrap_or(0))
+            }
+            _ => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "IPv4 groups must be joined with an IPv4 `interface`, and IPv6 groups with an `interface_index`",
+            )),
+        }
+    }
+}
+
 impl UdpConfig {
     pub(super) const fn host_key(&self) -> &Option<String> {
         &self.host_key
@@ -108,28 +185,72 @@ impl UdpConfig {
             host_key: None,
             port_key: Some(String::from("port")),
             receive_buffer_bytes: None,
+            multicast_groups: Vec::new(),
+            multicast_loop: None,
+            multicast_ttl: None,
+            sockets: None,
             framing: default_framing_message_based(),
             decoding: default_decoding(),
             log_namespace: None,
         }
     }
 
+    pub(super) fn validate(&self) -> crate::Result<()> {
+        if self.sockets.map_or(false, |sockets| sockets.get() > 1)
+            && !self.multicast_groups.is_empty()
+        {
+            return Err(
+                "`multicast_groups` can't be joined when receiving on several `sockets`".into(),
+            );
+        }
+        Ok(())
+    }
+
+    pub fn set_sockets(&mut self, sockets: NonZeroUsize) -> &mut Self {
+        self.sockets = Some(sockets);
+        self
+    }
+
     pub fn set_log_namespace(&mut self, val: Option<bool>) -> &mut Self {
         self.log_namespace = val;
         self
     }
 }
 
+fn set_multicast_options(
+    socket: &UdpSocket,
+    multicast_loop: Option<bool>,
+    multicast_ttl: Option<u32>,
+) -> std::io::Result<()> {
+    let ipv4 = socket.local_addr()?.is_ipv4();
+    if let Some(multicast_loop) = multicast_loop {
+        if ipv4 {
+            socket.set_multicast_loop_v4(multicast_loop)?;
+        } else {
+            socket.set_multicast_loop_v6(multicast_loop)?;
+        }
+    }
+    if let Some(ttl) = multicast_ttl {
+        if ipv4 {
+            socket.set_multicast_ttl_v4(ttl)?;
+        } else {
+            udp::set_multicast_hops_v6(socket, ttl)?;
+        }
+    }
+    Ok(())
+}
+
 pub(super) fn udp(
     config: UdpConfig,
     decoder: Decoder,
-    mut shutdown: ShutdownSignal,
-    mut out: SourceSender,
+    shutdown: ShutdownSignal,
+    out: SourceSender,
     log_namespace: LogNamespace,
 ) -> Source {
     Box::pin(async move {
+        let count = config.sockets.map_or(1, NonZeroUsize::get);
         let listenfd = ListenFd::from_env();
-        let socket = try_bind_udp_socket(config.address, listenfd)
+        let sockets = try_bind_udp_sockets(config.address, listenfd, count)
             .await
             .map_err(|error| {
                 emit!(SocketBindError {
@@ -138,142 +259,221 @@ pub(super) fn udp(
                 })
             })?;
 
-        if let Some(receive_buffer_bytes) = config.receive_buffer_bytes {
-            if let Err(error) = udp::set_receive_buffer_size(&socket, receive_buffer_bytes) {
-                warn!(message = "Failed configuring receive buffer size on UDP socket.", %error);
-            }
+        for socket in &sockets {
+            configure_socket(socket, &config)?;
         }
 
-        let max_length = match config.receive_buffer_bytes {
-            Some(receive_buffer_bytes) => std::cmp::min(config.max_length, receive_buffer_bytes),
-            None => config.max_length,
+        let bytes_received = register!(BytesReceived::from(Protocol::UDP));
+
+        info!(message = "Listening.", address = %config.address, sockets = count);
+
+        if sockets.len() == 1 {
+            let socket = sockets.into_iter().next().unwrap();
+            return receive(
+                socket,
+                config,
+                decoder,
+                shutdown,
+                out,
+                log_namespace,
+                bytes_received,
+            )
+            .await;
+        }
+
+        let mut tasks = sockets
+            .into_iter()
+            .map(|socket| {
+                tokio::spawn(
+                    receive(
+                        socket,
+                        config.clone(),
+                        decoder.clone(),
+                        shutdown.clone(),
+                        out.clone(),
+                        log_namespace,
+                        bytes_received.clone(),
+                    )
+                    .in_current_span(),
+                )
+            })
+            .collect::<Vec<JoinHandle<Result<(), ()>>>>();
+
+        // Stop receiving on all sockets as soon as one fails.
+        let result = loop {
+            if tasks.is_empty() {
+                break Ok(());
+            }
+            let (result, _, remaining) = futures::future::select_all(tasks).await;
+            tasks = remaining;
+            if !matches!(result, Ok(Ok(()))) {
+                break Err(());
+            }
         };
+        for task in &tasks {
+            task.abort();
+        }
+        result
+    })
+}
 
-        let bytes_received = register!(BytesReceived::from(Protocol::UDP));
+fn configure_socket(socket: &UdpSocket, config: &UdpConfig) -> Result<(), ()> {
+    if let Some(receive_buffer_bytes) = config.receive_buffer_bytes {
+        if let Err(error) = udp::set_receive_buffer_size(socket, receive_buffer_bytes) {
+            warn!(message = "Failed configuring receive buffer size on UDP socket.", %error);
+        }
+    }
 
-        info!(message = "Listening.", address = %config.address);
+    for group in &config.multicast_groups {
+        group.join(socket).map_err(|error| {
+            emit!(SocketMulticastJoinError {
+                group: group.group,
+                error,
+            })
+        })?;
+    }
 
-        // We add 1 to the max_length in order to determine if the received data has been truncated.
-        let mut buf = BytesMut::with_capacity(max_length + 1);
-        loop {
-            buf.resize(max_length + 1, 0);
-            tokio::select! {
-                recv = socket.recv_from(&mut buf) => {
-                    let (byte_size, address) = match recv {
-                        Ok(res) => res,
-                        Err(error) => {
-                            #[cfg(windows)]
-                            if let Some(err) = error.raw_os_error() {
-                                if err == 10040 {
-                                    // 10040 is the Windows error that the Udp message has exceeded max_length
-                                    warn!(
-                                        message = "Discarding frame larger than max_length.",
-                                        max_length = max_length,
-                                        internal_log_rate_limit = true
-                                    );
-                                    continue;
-                                }
+    if let Err(error) = set_multicast_options(socket, config.multicast_loop, config.multicast_ttl) {
+        warn!(message = "Failed configuring multicast options on UDP socket.", %error);
+    }
+
+    Ok(())
+}
+
+async fn receive(
+    socket: UdpSocket,
+    config: UdpConfig,
+    decoder: Decoder,
+    mut shutdown: ShutdownSignal,
+    mut out: SourceSender,
+    log_namespace: LogNamespace,
+    bytes_received: Registered<BytesReceived>,
+) -> Result<(), ()> {
+    let max_length = match config.receive_buffer_bytes {
+        Some(receive_buffer_bytes) => std::cmp::min(config.max_length, receive_buffer_bytes),
+        None => config.max_length,
+    };
+
+    // We add 1 to the max_length in order to determine if the received data has been truncated.
+    let mut buf = BytesMut::with_capacity(max_length + 1);
+    loop {
+        buf.resize(max_length + 1, 0);
+        tokio::select! {
+            recv = socket.recv_from(&mut buf) => {
+                let (byte_size, address) = match recv {
+                    Ok(res) => res,
+                    Err(error) => {
+                        #[cfg(windows)]
+                        if let Some(err) = error.raw_os_error() {
+                            if err == 10040 {
+                                // 10040 is the Windows error that the Udp message has exceeded max_length
+                                warn!(
+                                    message = "Discarding frame larger than max_length.",
+                                    max_length = max_length,
+                                    internal_log_rate_limit = true
+                                );
+                                continue;
+                            }
+                        }
+
+                        return Err(emit!(SocketReceiveError {
+                            mode: SocketMode::Udp,
+                            error
+                        }));
+                   }
+                };
+
+                bytes_received.emit(ByteSize(byte_size));
+
+                let payload = buf.split_to(byte_size);
+                let truncated = byte_size == max_length + 1;
+
+                let mut stream = FramedRead::new(payload.as_ref(), decoder.clone()).peekable();
+
+                while let Some(result) = stream.next().await {
+                    let last = Pin::new(&mut stream).peek().await.is_none();
+                    match result {
+                        Ok((mut events, _byte_size)) => {
+                            if last && truncated {
+                                // The last event in this payload was truncated, so we want to drop it.
+                                let _ = events.pop();
+                                warn!(
+                                    message = "Discarding frame larger than max_length.",
+                                    max_length = max_length,
+                                    internal_log_rate_limit = true
+                                );
                             }
 
-                            return Err(emit!(SocketReceiveError {
+                            if events.is_empty() {
+                                continue;
+                            }
+
+                            let count = events.len();
+                            emit!(SocketEventsReceived {
                                 mode: SocketMode::Udp,
-                                error
-                            }));
-                       }
-                    };
-
-                    bytes_received.emit(ByteSize(byte_size));
-
-                    let payload = buf.split_to(byte_size);
-                    let truncated = byte_size == max_length + 1;
-
-                    let mut stream = FramedRead::new(payload.as_ref(), decoder.clone()).peekable();
-
-                    while let Some(result) = stream.next().await {
-                        let last = Pin::new(&mut stream).peek().await.is_none();
-                        match result {
-                            Ok((mut events, _byte_size)) => {
-                                if last && truncated {
-                                    // The last event in this payload was truncated, so we want to drop it.
-                                    let _ = events.pop();
-                                    warn!(
-                                        message = "Discarding frame larger than max_length.",
-                                        max_length = max_length,
-                                        internal_log_rate_limit = true
+                                byte_size: events.estimated_json_encoded_size_of(),
+                                count,
+                            });
+
+                            let now = Utc::now();
+
+                            for event in &mut events {
+                                if let Event::Log(ref mut log) = event {
+                                    log_namespace.insert_standard_vector_source_metadata(
+                                        log,
+                                        SocketConfig::NAME,
+                                        now,
                                     );
-                                }
 
-                                if events.is_empty() {
-                                    continue;
-                                }
+                                    let host_key_path = config.host_key.as_ref().map_or_else(
+                                        || [BorrowedSegment::from(log_schema().host_key())],
+                                        |key| [BorrowedSegment::from(key)],
+                                    );
 
-                                let count = events.len();
-                                emit!(SocketEventsReceived {
-                                    mode: SocketMode::Udp,
-                                    byte_size: events.estimated_json_encoded_size_of(),
-                                    count,
-                                });
-
-                                let now = Utc::now();
-
-                                for event in &mut events {
-                                    if let Event::Log(ref mut log) = event {
-                                        log_namespace.insert_standard_vector_source_metadata(
-                                            log,
-                                            SocketConfig::NAME,
-                                            now,
-                                        );
-
-                                        let host_key_path = config.host_key.as_ref().map_or_else(
-                                            || [BorrowedSegment::from(log_schema().host_key())],
-                                            |key| [BorrowedSegment::from(key)],
-                                        );
-
-                                        log_namespace.insert_source_metadata(
-                                            SocketConfig::NAME,
-                                            log,
-                                            Some(LegacyKey::InsertIfEmpty(&host_key_path)),
-                                            path!("host"),
-                                            address.ip().to_string()
-                                        );
-
-                                        let port_key_path = config.port_key.as_ref().map_or_else(
-                                            || [BorrowedSegment::from("port")],
-                                            |key| [BorrowedSegment::from(key)],
-                                        );
-
-                                        log_namespace.insert_source_metadata(
-                                            SocketConfig::NAME,
-                                            log,
-                                            Some(LegacyKey::InsertIfEmpty(&port_key_path)),
-                                            path!("port"),
-                                            address.port()
-                                        );
-                                    }
+                                    log_namespace.insert_source_metadata(
+                                        SocketConfig::NAME,
+                                        log,
+                                        Some(LegacyKey::InsertIfEmpty(&host_key_path)),
+                                        path!("host"),
+                                        address.ip().to_string()
+                                    );
+
+                                    let port_key_path = config.port_key.as_ref().map_or_else(
+                                        || [BorrowedSegment::from("port")],
+                                        |key| [BorrowedSegment::from(key)],
+                                    );
+
+                                    log_namespace.insert_source_metadata(
+                                        SocketConfig::NAME,
+                                        log,
+                                        Some(LegacyKey::InsertIfEmpty(&port_key_path)),
+                                        path!("port"),
+                                        address.port()
+                                    );
                                 }
+                            }
 
-                                tokio::select!{
-                                    result = out.send_batch(events) => {
-                                        if let Err(error) = result {
-                                            emit!(StreamClosedError { error, count });
-                                            return Ok(())
-                                        }
+                            tokio::select!{
+                                result = out.send_batch(events) => {
+                                    if let Err(error) = result {
+                                        emit!(StreamClosedError { error, count });
+                                        return Ok(())
                                     }
-                                    _ = &mut shutdown => return Ok(()),
                                 }
+                                _ = &mut shutdown => return Ok(()),
                             }
-                            Err(error) => {
-                                // Error is logged by `crate::codecs::Decoder`, no
-                                // further handling is needed here.
-                                if !error.can_continue() {
-                                    break;
-                                }
+                        }
+                        Err(error) => {
+                            // Error is logged by `crate::codecs::Decoder`, no
+                            // further handling is needed here.
+                            if !error.can_continue() {
+                                break;
                             }
                         }
                     }
                 }
-                _ = &mut shutdown => return Ok(()),
             }
+            _ = &mut shutdown => return Ok(()),
         }
-    })
+    }
 }
@@ -1263,7 +1263,6 @@ mod tests {
             request: TowerRequestConfig::default(),
             tls: None,
             acknowledgements: Default::default(),
-            timestamp_nanos_key: None,
             timestamp_key: timestamp_key(),
             auto_extract_timestamp: None,
             endpoint_target: Default::default(),
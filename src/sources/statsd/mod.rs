@@ -13,7 +13,9 @@ use vector_config::configurable_component;
 use vector_core::EstimatedJsonEncodedSizeOf;
 
 use self::parser::ParseError;
-use super::util::net::{try_bind_udp_socket, SocketListenAddr, TcpNullAcker, TcpSource};
+use super::util::net::{
+    try_bind_udp_socket, SocketListenAddr, TcpNullAcker, TcpSource, TcpSourceOptions,
+};
 use crate::{
     codecs::Decoder,
     config::{self, GenerateConfig, Output, Resource, SourceConfig, SourceContext},
@@ -100,6 +102,12 @@ pub struct TcpConfig {
 
     /// The maximum number of TCP connections that will be allowed at any given time.
     connection_limit: Option<u32>,
+
+    /// The amount of time, in seconds, a connection can stay open without sending any data before it
+    /// is closed.
+    ///
+    /// By default, connections are never closed for being idle.
+    idle_timeout_secs: Option<u64>,
 }
 
 impl TcpConfig {
@@ -112,6 +120,7 @@ impl TcpConfig {
             shutdown_timeout_secs: default_shutdown_timeout_secs(),
             receive_buffer_bytes: None,
             connection_limit: None,
+            idle_timeout_secs: None,
         }
     }
 }
@@ -140,12 +149,17 @@ impl SourceConfig for StatsdConfig {
                 Ok(Box::pin(statsd_udp(config.clone(), cx.shutdown, cx.out)))
             }
             StatsdConfig::Tcp(config) => {
-                let tls_config = config.tls.as_ref().map(|tls| tls.tls_config.clone());
                 let tls_client_metadata_key = config
                     .tls
                     .as_ref()
                     .and_then(|tls| tls.client_metadata_key.clone());
-                let tls = MaybeTlsSettings::from_config(&tls_config, true)?;
+                let tls_client_allowlist = config
+                    .tls
+                    .as_ref()
+                    .map(|tls| tls.client_allowlist())
+                    .transpose()?
+                    .flatten();
+                let tls = MaybeTlsSettings::from_source_config(&config.tls)?;
                 StatsdTcpSource.run(
                     config.address,
                     config.keepalive,
@@ -156,6 +170,11 @@ impl SourceConfig for StatsdConfig {
                     cx,
                     false.into(),
                     config.connection_limit,
+                    TcpSourceOptions {
+                        tls_client_allowlist,
+                        idle_timeout_secs: config.idle_timeout_secs,
+                        ..Default::default()
+                    },
                 )
             }
             #[cfg(unix)]
@@ -297,6 +316,7 @@ impl TcpSource for StatsdTcpSource {
     type Item = SmallVec<[Event; 1]>;
     type Decoder = Decoder;
     type Acker = TcpNullAcker;
+    type Instrumentation = ();
 
     fn decoder(&self) -> Self::Decoder {
         Decoder::new(
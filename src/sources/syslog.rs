@@ -25,7 +25,9 @@ use crate::{
     internal_events::StreamClosedError,
     internal_events::{SocketBindError, SocketMode, SocketReceiveError},
     shutdown::ShutdownSignal,
-    sources::util::net::{try_bind_udp_socket, SocketListenAddr, TcpNullAcker, TcpSource},
+    sources::util::net::{
+        try_bind_udp_socket, SocketListenAddr, TcpNullAcker, TcpSource, TcpSourceOptions,
+    },
     tcp::TcpKeepaliveConfig,
     tls::{MaybeTlsSettings, TlsSourceConfig},
     udp, SourceSender,
@@ -83,6 +85,12 @@ pub enum Mode {
 
         /// The maximum number of TCP connections that will be allowed at any given time.
         connection_limit: Option<u32>,
+
+        /// The amount of time, in seconds, a connection can stay open without sending any data before it
+        /// is closed.
+        ///
+        /// By default, connections are never closed for being idle.
+        idle_timeout_secs: Option<u64>,
     },
 
     /// Listen on UDP.
@@ -133,6 +141,7 @@ impl Default for SyslogConfig {
                 tls: None,
                 receive_buffer_bytes: None,
                 connection_limit: None,
+                idle_timeout_secs: None,
             },
             host_key: None,
             max_length: crate::serde::default_max_length(),
@@ -163,6 +172,7 @@ impl SourceConfig for SyslogConfig {
                 tls,
                 receive_buffer_bytes,
                 connection_limit,
+                idle_timeout_secs,
             } => {
                 let source = SyslogTcpSource {
                     max_length: self.max_length,
@@ -170,10 +180,14 @@ impl SourceConfig for SyslogConfig {
                     log_namespace,
                 };
                 let shutdown_secs = 30;
-                let tls_config = tls.as_ref().map(|tls| tls.tls_config.clone());
                 let tls_client_metadata_key =
                     tls.as_ref().and_then(|tls| tls.client_metadata_key.clone());
-                let tls = MaybeTlsSettings::from_config(&tls_config, true)?;
+                let tls_client_allowlist = tls
+                    .as_ref()
+                    .map(|tls| tls.client_allowlist())
+                    .transpose()?
+                    .flatten();
+                let tls = MaybeTlsSettings::from_source_config(&tls)?;
                 source.run(
                     address,
                     keepalive,
@@ -184,6 +198,11 @@ impl SourceConfig for SyslogConfig {
                     cx,
                     false.into(),
                     connection_limit,
+                    TcpSourceOptions {
+                        tls_client_allowlist,
+                        idle_timeout_secs,
+                        ..Default::default()
+                    },
                 )
             }
             Mode::Udp {
@@ -259,6 +278,7 @@ impl TcpSource for SyslogTcpSource {
     type Item = SmallVec<[Event; 1]>;
     type Decoder = Decoder;
     type Acker = TcpNullAcker;
+    type Instrumentation = ();
 
     fn decoder(&self) -> Self::Decoder {
         Decoder::new(
@@ -995,6 +1015,7 @@ mod test {
                 tls: None,
                 receive_buffer_bytes: None,
                 connection_limit: None,
+                idle_timeout_secs: None,
             });
 
             let key = ComponentKey::from("in");
@@ -1138,6 +1159,7 @@ mod test {
                 tls: None,
                 receive_buffer_bytes: None,
                 connection_limit: None,
+                idle_timeout_secs: None,
             });
 
             let key = ComponentKey::from("in");
@@ -6,16 +6,29 @@ use codecs::StreamDecodingError;
 use lookup::{metadata_path, path};
 use tokio_util::codec::Decoder as _;
 use vector_core::{
-    config::LogNamespace, internal_event::EventsReceived, EstimatedJsonEncodedSizeOf,
+    config::{LegacyKey, LogNamespace},
+    internal_event::EventsReceived,
+    EstimatedJsonEncodedSizeOf,
 };
 
-use crate::{codecs::Decoder, config::log_schema, event::BatchNotifier, event::Event};
+use crate::{
+    codecs::Decoder,
+    config::log_schema,
+    event::{BatchNotifier, Event, Value},
+};
 
+/// Decodes the events of a message.
+///
+/// Each decoded log is given the source type, the `timestamp` of the message if any, and each
+/// field of `metadata`, such as the identifier or the attributes of the message, as source
+/// metadata of `source_type`. With the legacy namespace, each field is inserted at the root of the
+/// log as its `LegacyKey` tells.
 pub fn decode_message<'a>(
     mut decoder: Decoder,
     source_type: &'static str,
     message: &[u8],
     timestamp: Option<DateTime<Utc>>,
+    metadata: Vec<(LegacyKey<&'static str>, Value)>,
     batch: &'a Option<BatchNotifier>,
     log_namespace: LogNamespace,
 ) -> impl Iterator<Item = Event> + 'a {
@@ -82,7 +95,29 @@ pub fn decode_message<'a>(
         };
     })
     .flatten()
-    .map(move |event| event.with_batch_notifier_option(batch))
+    .map(move |mut event| {
+        if let Event::Log(log) = &mut event {
+            for (legacy_key, value) in &metadata {
+                let (field, overwrite) = match legacy_key {
+                    LegacyKey::Overwrite(field) => (*field, true),
+                    LegacyKey::InsertIfEmpty(field) => (*field, false),
+                };
+                let key = path!(field);
+                let legacy_key = match overwrite {
+                    true => LegacyKey::Overwrite(key),
+                    false => LegacyKey::InsertIfEmpty(key),
+                };
+                log_namespace.insert_source_metadata(
+                    source_type,
+                    log,
+                    Some(legacy_key),
+                    path!(field),
+                    value.clone(),
+                );
+            }
+        }
+        event.with_batch_notifier_option(batch)
+    })
 }
 
 trait FoldFinallyExt: Sized {
@@ -11,9 +11,13 @@ use vector_config::configurable_component;
 use crate::config::{Protocol, Resource};
 
 #[cfg(feature = "sources-utils-net-tcp")]
-pub use self::tcp::{TcpNullAcker, TcpSource, TcpSourceAck, TcpSourceAcker};
+pub use self::tcp::{
+    AdaptiveReadBufferConfig, ConcurrentDecoder, ConnectionMetadataConfig,
+    DecodingConcurrencyConfig, FirstFrameAuthentication, PeerAddrMetadata, TcpNullAcker, TcpSource,
+    TcpSourceAck, TcpSourceAcker, TcpSourceInstrumentation, TcpSourceOptions, WireCaptureConfig,
+};
 #[cfg(feature = "sources-utils-net-udp")]
-pub use self::udp::try_bind_udp_socket;
+pub use self::udp::{try_bind_udp_socket, try_bind_udp_sockets};
 
 /// A listening address that can be given directly or be managed via `systemd` socket activation.
 #[configurable_component]
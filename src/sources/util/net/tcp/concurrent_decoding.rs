@@ -0,0 +1,287 @@
+use std::{
+    num::NonZeroUsize,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use codecs::decoding::{format::Deserializer as _, Deserializer, Error, Framer};
+use futures::{
+    stream::{FuturesOrdered, FuturesUnordered},
+    Stream, StreamExt,
+};
+use smallvec::SmallVec;
+use tokio::{io::AsyncRead, task::JoinHandle};
+use tokio_util::codec::FramedRead;
+use tracing::Instrument;
+use vector_config::configurable_component;
+use vector_core::config::LogNamespace;
+
+use crate::{
+    codecs::Decoder,
+    event::Event,
+    internal_events::{DecoderDeserializeError, DecoderFramingError},
+};
+
+/// The largest number of frames deserialized by a single task.
+const MAX_FRAMES_PER_TASK: usize = 1024;
+
+const fn default_ordered() -> bool {
+    true
+}
+
+/// Deserializing the frames of each connection on several tasks.
+///
+/// Frames are still split off the connection one after the other, but are then handed off in
+/// chunks to up to `workers` tasks, so that a single busy connection can use several cores.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct DecodingConcurrencyConfig {
+    /// The number of chunks of frames of a connection deserialized at the same time.
+    pub workers: NonZeroUsize,
+
+    /// Whether the events of a connection are forwarded in the order they were received in.
+    ///
+    /// When disabled, the events of each chunk are forwarded as soon as they are deserialized,
+    /// which keeps a slow chunk from holding back the ones after it.
+    #[serde(default = "default_ordered")]
+    pub ordered: bool,
+}
+
+/// The parts of a source's decoder needed to deserialize its frames concurrently.
+pub struct ConcurrentDecoder<I, E> {
+    framer: Framer,
+    deserializer: Deserializer,
+    log_namespace: LogNamespace,
+    into_item: fn(SmallVec<[Event; 1]>) -> I,
+    from_error: fn(Error) -> E,
+}
+
+impl ConcurrentDecoder<SmallVec<[Event; 1]>, Error> {
+    pub fn new(decoder: Decoder) -> Self {
+        let (framer, deserializer, log_namespace) = decoder.into_parts();
+        Self {
+            framer,
+            deserializer,
+            log_namespace,
+            into_item: |events| events,
+            from_error: |error| error,
+        }
+    }
+}
+
+impl<I, E> ConcurrentDecoder<I, E> {
+    pub(super) fn frames<R: AsyncRead>(
+        self,
+        reader: R,
+        capacity: Option<usize>,
+        config: DecodingConcurrencyConfig,
+    ) -> ConcurrentFrames<R, I, E> {
+        let reader = match capacity {
+            Some(capacity) => FramedRead::with_capacity(reader, self.framer, capacity),
+            None => FramedRead::new(reader, self.framer),
+        };
+        let in_flight = if config.ordered {
+            InFlight::Ordered(FuturesOrdered::new())
+        } else {
+            InFlight::Unordered(FuturesUnordered::new())
+        };
+        ConcurrentFrames {
+            inner: reader,
+            deserializer: self.deserializer,
+            log_namespace: self.log_namespace,
+            workers: config.workers.get(),
+            in_flight,
+            into_item: self.into_item,
+            from_error: self.from_error,
+            error_slot: None,
+            done: false,
+        }
+    }
+}
+
+type Chunk<I> = JoinHandle<(Vec<I>, usize)>;
+
+enum InFlight<I> {
+    Ordered(FuturesOrdered<Chunk<I>>),
+    Unordered(FuturesUnordered<Chunk<I>>),
+}
+
+impl<I> InFlight<I> {
+    fn len(&self) -> usize {
+        match self {
+            Self::Ordered(chunks) => chunks.len(),
+            Self::Unordered(chunks) => chunks.len(),
+        }
+    }
+
+    fn push(&mut self, chunk: Chunk<I>) {
+        match self {
+            Self::Ordered(chunks) => chunks.push_back(chunk),
+            Self::Unordered(chunks) => chunks.push(chunk),
+        }
+    }
+
+    fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<(Vec<I>, usize)>> {
+        let result = match self {
+            Self::Ordered(chunks) => chunks.poll_next_unpin(cx),
+            Self::Unordered(chunks) => chunks.poll_next_unpin(cx),
+        };
+        result.map(|chunk| {
+            chunk.map(|result| match result {
+                Ok(chunk) => chunk,
+                Err(error) => std::panic::resume_unwind(error.into_panic()),
+            })
+        })
+    }
+}
+
+/// A stream of the events decoded from a connection, in chunks, like `ReadyFrames` yields them,
+/// whose frames are deserialized on separate tasks.
+pub struct ConcurrentFrames<R, I, E> {
+    inner: FramedRead<R, Framer>,
+    deserializer: Deserializer,
+    log_namespace: LogNamespace,
+    workers: usize,
+    in_flight: InFlight<I>,
+    into_item: fn(SmallVec<[Event; 1]>) -> I,
+    from_error: fn(Error) -> E,
+    error_slot: Option<Error>,
+    done: bool,
+}
+
+impl<R, I: Send + 'static, E> ConcurrentFrames<R, I, E> {
+    /// Returns a reference to the underlying reader.
+    pub const fn get_ref(&self) -> &FramedRead<R, Framer> {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    pub fn get_mut(&mut self) -> &mut FramedRead<R, Framer> {
+        &mut self.inner
+    }
+
+    fn spawn(&mut self, frames: Vec<Bytes>) {
+        let deserializer = self.deserializer.clone();
+        let log_namespace = self.log_namespace;
+        let into_item = self.into_item;
+        // The task is spawned in the span of the connection, so that the errors it emits are
+        // tagged with the component.
+        let task = async move {
+            let byte_size = frames.iter().map(Bytes::len).sum();
+            let events = frames
+                .into_iter()
+                .filter_map(|frame| match deserializer.parse(frame, log_namespace) {
+                    Ok(events) => Some(into_item(events)),
+                    Err(error) => {
+                        // Like with serial decoding, frames that fail to be deserialized are
+                        // skipped.
+                        emit!(DecoderDeserializeError { error: &error });
+                        None
+                    }
+                })
+                .collect();
+            (events, byte_size)
+        };
+        self.in_flight.push(tokio::spawn(task.in_current_span()));
+    }
+}
+
+impl<R, I, E> Stream for ConcurrentFrames<R, I, E>
+where
+    R: AsyncRead + Unpin,
+    I: Send + Unpin + 'static,
+{
+    type Item = Result<(Vec<I>, usize), E>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+
+        loop {
+            // Frames are read ahead while there are free workers to deserialize them. Once a
+            // framing error occurs, reading stops until the chunks before it have been forwarded.
+            while !this.done && this.error_slot.is_none() && this.in_flight.len() < this.workers {
+                let mut frames = Vec::new();
+                while frames.len() < MAX_FRAMES_PER_TASK {
+                    match this.inner.poll_next_unpin(cx) {
+                        Poll::Ready(Some(Ok(frame))) => frames.push(frame),
+                        Poll::Ready(Some(Err(error))) => {
+                            emit!(DecoderFramingError { error: &error });
+                            this.error_slot = Some(Error::FramingError(error));
+                            break;
+                        }
+                        Poll::Ready(None) => {
+                            this.done = true;
+                            break;
+                        }
+                        Poll::Pending => break,
+                    }
+                }
+
+                if frames.is_empty() {
+                    break;
+                }
+                this.spawn(frames);
+            }
+
+            return match this.in_flight.poll_next(cx) {
+                // Chunks whose frames all failed to be deserialized are skipped.
+                Poll::Ready(Some((events, _))) if events.is_empty() => continue,
+                Poll::Ready(Some(chunk)) => Poll::Ready(Some(Ok(chunk))),
+                Poll::Ready(None) => match this.error_slot.take() {
+                    Some(error) => Poll::Ready(Some(Err((this.from_error)(error)))),
+                    None if this.done => Poll::Ready(None),
+                    // Nothing is in flight, and the reader has registered to be woken up.
+                    None => Poll::Pending,
+                },
+                Poll::Pending => Poll::Pending,
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use codecs::{JsonDeserializer, NewlineDelimitedDecoder};
+    use vector_core::config::log_schema;
+
+    use super::*;
+
+    fn decoder() -> ConcurrentDecoder<SmallVec<[Event; 1]>, Error> {
+        ConcurrentDecoder::new(Decoder::new(
+            Framer::NewlineDelimited(NewlineDelimitedDecoder::new()),
+            Deserializer::Json(JsonDeserializer::new()),
+        ))
+    }
+
+    async fn decode(input: &'static [u8], workers: usize, ordered: bool) -> Vec<i64> {
+        let config = DecodingConcurrencyConfig {
+            workers: NonZeroUsize::new(workers).unwrap(),
+            ordered,
+        };
+        decoder()
+            .frames(input, Some(4), config)
+            .flat_map(|chunk| futures::stream::iter(chunk.unwrap().0))
+            .flat_map(futures::stream::iter)
+            .map(|event| {
+                event.as_log()[log_schema().message_key()]
+                    .as_integer()
+                    .unwrap()
+            })
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn deserializes_every_frame_in_order() {
+        let input = b"{\"message\":1}\n{\"message\":2}\nnot json\n{\"message\":3}\n";
+
+        assert_eq!(decode(input, 1, true).await, vec![1, 2, 3]);
+        assert_eq!(decode(input, 4, true).await, vec![1, 2, 3]);
+
+        let mut unordered = decode(input, 4, false).await;
+        unordered.sort_unstable();
+        assert_eq!(unordered, vec![1, 2, 3]);
+    }
+}
@@ -0,0 +1,256 @@
+use std::net::SocketAddr;
+
+use lookup::{lookup_v2::parse_value_path, owned_value_path, path, OwnedValuePath};
+use openssl::ssl::NameType;
+use tokio::net::TcpStream;
+use value::Kind;
+use vector_config::configurable_component;
+use vector_core::config::{LegacyKey, LogNamespace};
+
+use crate::{
+    event::{Event, Value},
+    schema,
+    tls::MaybeTlsIncomingStream,
+};
+
+/// Connection-level metadata added to each event.
+///
+/// Each field is only added when its key is set. For connections accepted without TLS, the TLS
+/// fields are not added.
+///
+/// For the `Vector` log namespace, the fields are inserted as the `local_addr`, `tls_version`,
+/// `tls_cipher` and `tls_server_name` source metadata fields, and the keys only enable them.
+#[configurable_component]
+#[derive(Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ConnectionMetadataConfig {
+    /// Event field for the local address the connection was accepted on.
+    ///
+    /// The value is the address of the listener, including the port i.e. `10.0.0.1:9000`.
+    pub local_addr_key: Option<String>,
+
+    /// Event field for the TLS protocol version negotiated for the connection i.e. `TLSv1.3`.
+    pub tls_version_key: Option<String>,
+
+    /// Event field for the TLS cipher suite negotiated for the connection i.e.
+    /// `TLS_AES_256_GCM_SHA384`.
+    pub tls_cipher_key: Option<String>,
+
+    /// Event field for the server name requested by the client through SNI i.e.
+    /// `logs.example.com`.
+    ///
+    /// Not added for clients not using SNI.
+    pub tls_server_name_key: Option<String>,
+}
+
+impl ConnectionMetadataConfig {
+    /// The configured keys, along with the name of the source metadata field of each.
+    fn keys(&self) -> impl Iterator<Item = (&'static str, &String)> {
+        [
+            ("local_addr", &self.local_addr_key),
+            ("tls_version", &self.tls_version_key),
+            ("tls_cipher", &self.tls_cipher_key),
+            ("tls_server_name", &self.tls_server_name_key),
+        ]
+        .into_iter()
+        .filter_map(|(name, key)| Some((name, key.as_ref()?)))
+    }
+
+    /// Resolves the configured keys for the source named `source_name`.
+    pub fn build(
+        &self,
+        source_name: &'static str,
+        log_namespace: LogNamespace,
+    ) -> crate::Result<ConnectionMetadataKeys> {
+        let legacy_keys = self
+            .keys()
+            .map(|(name, key)| {
+                parse_value_path(key)
+                    .map(|path| (name, path))
+                    .map_err(|_| format!("Invalid connection metadata key `{}`.", key).into())
+            })
+            .collect::<crate::Result<_>>()?;
+        Ok(ConnectionMetadataKeys {
+            source_name,
+            log_namespace,
+            legacy_keys,
+        })
+    }
+
+    /// Adds the configured fields to the schema `definition` of the source named `source_name`.
+    pub fn schema_definition(
+        &self,
+        source_name: &'static str,
+        definition: schema::Definition,
+    ) -> schema::Definition {
+        self.keys().fold(definition, |definition, (name, key)| {
+            definition.with_source_metadata(
+                source_name,
+                parse_value_path(key).ok().map(LegacyKey::Overwrite),
+                &owned_value_path!(name),
+                Kind::bytes().or_undefined(),
+                None,
+            )
+        })
+    }
+}
+
+/// Where the metadata of a connection is inserted into each log event.
+#[derive(Clone, Debug)]
+pub struct ConnectionMetadataKeys {
+    source_name: &'static str,
+    log_namespace: LogNamespace,
+    legacy_keys: Vec<(&'static str, OwnedValuePath)>,
+}
+
+/// The metadata of a connection, added to each log event received over it.
+pub struct ConnectionMetadata {
+    source_name: &'static str,
+    log_namespace: LogNamespace,
+    fields: Vec<(&'static str, OwnedValuePath, Value)>,
+}
+
+impl ConnectionMetadata {
+    /// Reads the metadata of a connection, once its TLS handshake, if any, has completed.
+    pub fn new(keys: &ConnectionMetadataKeys, socket: &MaybeTlsIncomingStream<TcpStream>) -> Self {
+        let local_addr = socket.get_ref().and_then(|stream| stream.local_addr().ok());
+        let ssl = socket.ssl_stream().map(|stream| stream.ssl());
+        Self::from_parts(
+            keys,
+            local_addr,
+            ssl.map(|ssl| ssl.version_str()),
+            ssl.and_then(|ssl| ssl.current_cipher())
+                .map(|cipher| cipher.name()),
+            ssl.and_then(|ssl| ssl.servername(NameType::HOST_NAME)),
+        )
+    }
+
+    fn from_parts(
+        keys: &ConnectionMetadataKeys,
+        local_addr: Option<SocketAddr>,
+        tls_version: Option<&str>,
+        tls_cipher: Option<&str>,
+        tls_server_name: Option<&str>,
+    ) -> Self {
+        let fields = keys
+            .legacy_keys
+            .iter()
+            .filter_map(|(name, legacy_key)| {
+                let value = match *name {
+                    "local_addr" => local_addr.map(|addr| addr.to_string()),
+                    "tls_version" => tls_version.map(str::to_string),
+                    "tls_cipher" => tls_cipher.map(str::to_string),
+                    _ => tls_server_name.map(str::to_string),
+                }?;
+                Some((*name, legacy_key.clone(), Value::from(value)))
+            })
+            .collect();
+        Self {
+            source_name: keys.source_name,
+            log_namespace: keys.log_namespace,
+            fields,
+        }
+    }
+
+    pub fn insert(&self, events: &mut [Event]) {
+        for event in events {
+            if let Event::Log(log) = event {
+                for (name, legacy_key, value) in &self.fields {
+                    self.log_namespace.insert_source_metadata(
+                        self.source_name,
+                        log,
+                        Some(LegacyKey::Overwrite(legacy_key)),
+                        path!(*name),
+                        value.clone(),
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::LogEvent;
+
+    fn config() -> ConnectionMetadataConfig {
+        ConnectionMetadataConfig {
+            local_addr_key: Some("listener".to_string()),
+            tls_version_key: Some("tls.version".to_string()),
+            tls_cipher_key: None,
+            tls_server_name_key: Some("tls.server_name".to_string()),
+        }
+    }
+
+    #[test]
+    fn inserts_configured_fields() {
+        let keys = config().build("socket", LogNamespace::Legacy).unwrap();
+        let metadata = ConnectionMetadata::from_parts(
+            &keys,
+            Some("10.0.0.1:9000".parse().unwrap()),
+            Some("TLSv1.3"),
+            Some("TLS_AES_256_GCM_SHA384"),
+            Some("logs.example.com"),
+        );
+
+        let mut events = vec![Event::from(LogEvent::from("hello"))];
+        metadata.insert(&mut events);
+        let log = events[0].as_log();
+        assert_eq!(log["listener"], "10.0.0.1:9000".into());
+        assert_eq!(log["tls.version"], "TLSv1.3".into());
+        assert_eq!(log.get("tls.cipher"), None);
+        assert_eq!(log["tls.server_name"], "logs.example.com".into());
+
+        // Without TLS, only the local address is known.
+        let metadata = ConnectionMetadata::from_parts(
+            &keys,
+            Some("10.0.0.1:9000".parse().unwrap()),
+            None,
+            None,
+            None,
+        );
+        let mut events = vec![Event::from(LogEvent::from("hello"))];
+        metadata.insert(&mut events);
+        assert_eq!(events[0].as_log().get("tls"), None);
+    }
+
+    #[test]
+    fn inserts_source_metadata_for_vector_namespace() {
+        let keys = config().build("socket", LogNamespace::Vector).unwrap();
+        let metadata = ConnectionMetadata::from_parts(
+            &keys,
+            Some("10.0.0.1:9000".parse().unwrap()),
+            Some("TLSv1.3"),
+            Some("TLS_AES_256_GCM_SHA384"),
+            None,
+        );
+
+        let mut events = vec![Event::from(LogEvent::from("hello"))];
+        metadata.insert(&mut events);
+        let log = events[0].as_log();
+        assert_eq!(log.get("listener"), None);
+        assert_eq!(log.get("tls"), None);
+
+        let metadata = log.metadata().value();
+        assert_eq!(
+            metadata.get(path!("socket", "local_addr")),
+            Some(&Value::from("10.0.0.1:9000"))
+        );
+        assert_eq!(
+            metadata.get(path!("socket", "tls_version")),
+            Some(&Value::from("TLSv1.3"))
+        );
+        assert_eq!(metadata.get(path!("socket", "tls_cipher")), None);
+        assert_eq!(metadata.get(path!("socket", "tls_server_name")), None);
+    }
+
+    #[test]
+    fn rejects_invalid_keys() {
+        let config = ConnectionMetadataConfig {
+            local_addr_key: Some("listener[".to_string()),
+            ..Default::default()
+        };
+        assert!(config.build("socket", LogNamespace::Legacy).is_err());
+    }
+}
@@ -0,0 +1,230 @@
+use std::{
+    ffi::OsString,
+    fs::{self, remove_file, DirBuilder, Permissions},
+    io::{self, IoSliceMut},
+    os::unix::{
+        fs::{DirBuilderExt, FileTypeExt, PermissionsExt},
+        io::{AsRawFd, FromRawFd, RawFd},
+    },
+    path::Path,
+};
+
+use nix::sys::socket::{recvmsg, ControlMessageOwned, MsgFlags, UnixAddr};
+use socket2::{SockRef, Type};
+use tokio::{
+    io::Interest,
+    net::{TcpStream, UnixListener, UnixStream},
+    sync::mpsc,
+    task::JoinHandle,
+};
+
+use crate::{internal_events::TcpConnectionHandoffError, shutdown::ShutdownSignal};
+
+/// The maximum number of connections that can be handed off in a single message.
+const MAX_CONNECTIONS_PER_MESSAGE: usize = 16;
+
+/// The descriptors received are closed on exec, like those Vector opens itself, so that they aren't
+/// leaked into the processes spawned by the `exec` source. Where the kernel can't set the flag while
+/// receiving them, it's set on each descriptor right after.
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "linux",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+const RECV_FLAGS: MsgFlags = MsgFlags::MSG_CMSG_CLOEXEC;
+#[cfg(not(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "linux",
+    target_os = "netbsd",
+    target_os = "openbsd"
+)))]
+const RECV_FLAGS: MsgFlags = MsgFlags::empty();
+
+/// Listens on a Unix domain socket at `path` for established TCP connections handed off by other
+/// processes, returning them as they are received.
+///
+/// Connections are passed as file descriptors in `SCM_RIGHTS` control messages, which must be
+/// accompanied by at least one byte of regular data. Any number of processes can connect to the
+/// socket, and each can hand off any number of connections over the same control connection.
+///
+/// The socket is only accessible to the user Vector runs as. A socket file left behind by a
+/// previous run is replaced, as long as nothing listens on it anymore.
+///
+/// The socket is closed and its file deleted on `shutdown`, after which the returned task
+/// completes. Sources must wait for it before completing themselves, so that the source replacing
+/// them on reload can bind the same path.
+pub(super) fn receive_connections(
+    path: &Path,
+    mut shutdown: ShutdownSignal,
+) -> io::Result<(mpsc::Receiver<TcpStream>, JoinHandle<()>)> {
+    remove_stale_socket(path)?;
+    let listener = bind_private(path)?;
+    info!(message = "Listening for handed off connections.", path = ?path);
+
+    let (connections, handoff) = mpsc::channel(MAX_CONNECTIONS_PER_MESSAGE);
+    let path = path.to_owned();
+    let task = tokio::spawn(async move {
+        loop {
+            let control = tokio::select! {
+                _ = &mut shutdown => break,
+                accepted = listener.accept() => match accepted {
+                    Ok((control, _)) => control,
+                    Err(error) => {
+                        emit!(TcpConnectionHandoffError { error });
+                        continue;
+                    }
+                },
+            };
+            tokio::spawn(receive_from(control, connections.clone()));
+        }
+
+        drop(listener);
+        if let Err(error) = remove_file(&path) {
+            warn!(message = "Failed deleting handoff socket file.", path = ?path, %error);
+        }
+    });
+
+    Ok((handoff, task))
+}
+
+/// Binds a socket at `path` that is only accessible to the user Vector runs as.
+///
+/// The socket is bound in a private directory next to `path`, and only linked at `path` once its
+/// permissions are restricted, so that other users can't connect to it in between. Linking fails
+/// if anything already exists at `path`.
+fn bind_private(path: &Path) -> io::Result<UnixListener> {
+    let name = path.file_name().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "handoff socket path has no file name",
+        )
+    })?;
+    let mut private_name = OsString::from(".");
+    private_name.push(name);
+    private_name.push(format!(".{}", std::process::id()));
+    let private_dir = path.with_file_name(private_name);
+
+    DirBuilder::new().mode(0o700).create(&private_dir)?;
+    let private_path = private_dir.join(name);
+    let result = UnixListener::bind(&private_path).and_then(|listener| {
+        fs::set_permissions(&private_path, Permissions::from_mode(0o600))?;
+        fs::hard_link(&private_path, path)?;
+        Ok(listener)
+    });
+    let _ = remove_file(&private_path);
+    let _ = fs::remove_dir(&private_dir);
+    result
+}
+
+/// Removes the socket file at `path` if nothing listens on it, such as when Vector didn't shut
+/// down cleanly. Files that aren't sockets are left alone, so that binding fails instead.
+fn remove_stale_socket(path: &Path) -> io::Result<()> {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => {
+            match std::os::unix::net::UnixStream::connect(path) {
+                Err(error) if error.kind() == io::ErrorKind::ConnectionRefused => {
+                    info!(message = "Removing stale handoff socket file.", path = ?path);
+                    remove_file(path)
+                }
+                _ => Ok(()),
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
+async fn receive_from(control: UnixStream, connections: mpsc::Sender<TcpStream>) {
+    loop {
+        let fds = match receive_fds(&control).await {
+            Ok(Some(fds)) => fds,
+            Ok(None) => break,
+            Err(error) => {
+                emit!(TcpConnectionHandoffError { error });
+                break;
+            }
+        };
+
+        for fd in fds {
+            // SAFETY: the descriptor was just created by the kernel while receiving the message,
+            // so nothing else owns it.
+            let stream = unsafe { std::net::TcpStream::from_raw_fd(fd) };
+            match into_tcp_stream(stream) {
+                Ok(stream) => {
+                    if connections.send(stream).await.is_err() {
+                        // The source stopped accepting connections.
+                        return;
+                    }
+                }
+                Err(error) => emit!(TcpConnectionHandoffError { error }),
+            }
+        }
+    }
+}
+
+/// Checks that a handed off descriptor is a connected TCP socket, and registers it with the
+/// runtime. Descriptors of any other kind are closed.
+fn into_tcp_stream(stream: std::net::TcpStream) -> io::Result<TcpStream> {
+    if RECV_FLAGS.is_empty() {
+        SockRef::from(&stream).set_cloexec(true)?;
+    }
+    if SockRef::from(&stream).r#type()? != Type::STREAM {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "handed off socket is not a stream socket",
+        ));
+    }
+    // Sockets of other families, such as Unix domain sockets, have no IP peer address.
+    stream.peer_addr()?;
+    stream.set_nonblocking(true)?;
+    TcpStream::from_std(stream)
+}
+
+/// Receives the next message on the control connection, returning the descriptors it carried, or
+/// `None` once the other end closed the connection.
+async fn receive_fds(control: &UnixStream) -> io::Result<Option<Vec<RawFd>>> {
+    loop {
+        control.readable().await?;
+        match control.try_io(Interest::READABLE, || recv_fds(control.as_raw_fd())) {
+            Err(error) if error.kind() == io::ErrorKind::WouldBlock => continue,
+            result => return result,
+        }
+    }
+}
+
+fn recv_fds(fd: RawFd) -> io::Result<Option<Vec<RawFd>>> {
+    let mut buf = [0; 64];
+    let mut iov = [IoSliceMut::new(&mut buf)];
+    let mut cmsg_buffer = nix::cmsg_space!([RawFd; MAX_CONNECTIONS_PER_MESSAGE]);
+    let message = recvmsg::<UnixAddr>(fd, &mut iov, Some(&mut cmsg_buffer), RECV_FLAGS)?;
+
+    if message.flags.contains(MsgFlags::MSG_CTRUNC) {
+        emit!(TcpConnectionHandoffError {
+            error: io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "more than {} connections handed off in a single message, some were dropped",
+                    MAX_CONNECTIONS_PER_MESSAGE
+                ),
+            )
+        });
+    }
+
+    let fds = message
+        .cmsgs()
+        .flat_map(|cmsg| match cmsg {
+            ControlMessageOwned::ScmRights(fds) => fds,
+            _ => Vec::new(),
+        })
+        .collect::<Vec<_>>();
+
+    if message.bytes == 0 && fds.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(fds))
+    }
+}
@@ -0,0 +1,23 @@
+/// Internal telemetry of a connection of a TCP source, updated by the connection loop.
+///
+/// Sources register their own counters or histograms for each connection in
+/// `TcpSource::instrumentation`, and the loop shared by all TCP sources updates them as data is
+/// received, alongside the internal events it emits for every TCP source. All methods do nothing
+/// by default, so implementations only handle what they record.
+pub trait TcpSourceInstrumentation: Send + Sync + 'static {
+    /// Called with the number of bytes read from the connection, before they are decoded.
+    fn bytes_received(&self, _byte_size: usize) {}
+
+    /// Called with the number of frames decoded at once, and the number of bytes they were decoded
+    /// from.
+    fn frames_decoded(&self, _count: usize, _byte_size: usize) {}
+
+    /// Called when decoding fails. Unless `can_continue` is set, the connection is then closed.
+    fn decode_error(&self, _can_continue: bool) {}
+
+    /// Called once the connection is closed.
+    fn connection_closed(&self) {}
+}
+
+/// No instrumentation, for sources that only rely on the internal events shared by all TCP sources.
+impl TcpSourceInstrumentation for () {}
@@ -1,40 +1,74 @@
+mod concurrent_decoding;
+mod connection_metadata;
+#[cfg(unix)]
+mod handoff;
+mod instrumentation;
+mod read_buffer;
 mod request_limiter;
+mod wire_capture;
 
 use std::collections::BTreeMap;
 use std::net::SocketAddr;
-use std::{io, mem::drop, time::Duration};
+use std::path::PathBuf;
+use std::sync::{atomic::Ordering, Arc};
+use std::{
+    io,
+    mem::drop,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
 
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
 use codecs::StreamDecodingError;
-use futures::{future::BoxFuture, FutureExt, StreamExt};
+use futures::{future::BoxFuture, FutureExt, Stream, StreamExt};
 use listenfd::ListenFd;
+use lookup::{path, OwnedValuePath};
 use smallvec::SmallVec;
 use socket2::SockRef;
 use tokio::{
-    io::AsyncWriteExt,
+    io::{AsyncWrite, AsyncWriteExt},
     net::{TcpListener, TcpStream},
-    time::sleep,
+    time::{sleep, sleep_until, Instant},
 };
 use tokio_util::codec::{Decoder, FramedRead};
 use tracing::Instrument;
 use vector_common::finalization::AddBatchNotifier;
-use vector_core::{config::SourceAcknowledgementsConfig, EstimatedJsonEncodedSizeOf};
+use vector_core::{
+    config::{LegacyKey, LogNamespace, SourceAcknowledgementsConfig},
+    EstimatedJsonEncodedSizeOf,
+};
 
-use self::request_limiter::RequestLimiter;
+pub use self::concurrent_decoding::{ConcurrentDecoder, DecodingConcurrencyConfig};
+pub use self::connection_metadata::ConnectionMetadataConfig;
+pub use self::instrumentation::TcpSourceInstrumentation;
+pub use self::read_buffer::AdaptiveReadBufferConfig;
+pub use self::wire_capture::WireCaptureConfig;
+use self::{
+    concurrent_decoding::ConcurrentFrames,
+    connection_metadata::{ConnectionMetadata, ConnectionMetadataKeys},
+    read_buffer::AdaptiveReadBuffer,
+    request_limiter::RequestLimiter,
+    wire_capture::{CapturingStream, WireCapture},
+};
 use super::SocketListenAddr;
 use crate::{
     codecs::ReadyFrames,
     config::SourceContext,
-    event::{BatchNotifier, BatchStatus, Event},
+    event::{BatchNotifier, BatchStatus, Event, LogEvent},
     internal_events::{
         ConnectionOpen, DecoderFramingError, OpenGauge, SocketBindError, SocketEventsReceived,
-        SocketMode, SocketReceiveError, StreamClosedError, TcpBytesReceived, TcpSendAckError,
-        TcpSocketTlsConnectionError,
+        SocketMode, SocketReceiveError, StreamClosedError, TcpBytesReceived,
+        TcpConnectionIdleTimeout, TcpFirstFrameRejected, TcpFirstFrameRejectionSendError,
+        TcpSendAckError, TcpSocketTlsClientRejected, TcpSocketTlsConnectionError,
     },
     shutdown::ShutdownSignal,
     sources::util::AfterReadExt,
     tcp::TcpKeepaliveConfig,
-    tls::{CertificateMetadata, MaybeTlsIncomingStream, MaybeTlsListener, MaybeTlsSettings},
+    tls::{
+        CertificateMetadata, MaybeTlsIncomingStream, MaybeTlsListener, MaybeTlsSettings,
+        TlsClientAllowlist,
+    },
     SourceSender,
 };
 
@@ -79,6 +113,113 @@ impl TcpSourceAcker for TcpNullAcker {
     }
 }
 
+/// The outcome of authenticating a connection from the first frame decoded from it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FirstFrameAuthentication {
+    /// The connection is authenticated, and the frame is forwarded like any other.
+    Forward,
+
+    /// The connection is authenticated, and the frame, which only holds credentials, is dropped.
+    Consume,
+
+    /// The connection is closed, once `response` has been sent to the peer, if there is one.
+    Reject { response: Option<Bytes> },
+}
+
+/// Where the address of the peer a connection was accepted from is inserted into each log event.
+///
+/// For the `Vector` log namespace, the IP address is inserted as the `host` source metadata field
+/// and, if enabled, the port as the `port` source metadata field. For the `Legacy` log namespace,
+/// they are inserted at the configured event keys, unless those fields are already present.
+#[derive(Clone, Debug)]
+pub struct PeerAddrMetadata {
+    source_name: &'static str,
+    log_namespace: LogNamespace,
+    legacy_host_key: Option<OwnedValuePath>,
+    include_port: bool,
+    legacy_port_key: Option<OwnedValuePath>,
+}
+
+impl PeerAddrMetadata {
+    pub const fn new(
+        source_name: &'static str,
+        log_namespace: LogNamespace,
+        legacy_host_key: Option<OwnedValuePath>,
+    ) -> Self {
+        Self {
+            source_name,
+            log_namespace,
+            legacy_host_key,
+            include_port: false,
+            legacy_port_key: None,
+        }
+    }
+
+    /// Also inserts the port of the peer, at `legacy_port_key` for the `Legacy` log namespace.
+    #[must_use]
+    pub fn with_port(mut self, legacy_port_key: Option<OwnedValuePath>) -> Self {
+        self.include_port = true;
+        self.legacy_port_key = legacy_port_key;
+        self
+    }
+
+    pub fn insert(&self, log: &mut LogEvent, peer_addr: SocketAddr) {
+        self.log_namespace.insert_source_metadata(
+            self.source_name,
+            log,
+            self.legacy_host_key.as_ref().map(LegacyKey::InsertIfEmpty),
+            path!("host"),
+            peer_addr.ip().to_string(),
+        );
+
+        if self.include_port {
+            self.log_namespace.insert_source_metadata(
+                self.source_name,
+                log,
+                self.legacy_port_key.as_ref().map(LegacyKey::InsertIfEmpty),
+                path!("port"),
+                peer_addr.port(),
+            );
+        }
+    }
+}
+
+/// Optional settings of a TCP source and the connections it accepts, all disabled by default.
+#[derive(Clone, Debug, Default)]
+pub struct TcpSourceOptions {
+    /// Only accepts TLS connections from clients presenting an allowed certificate.
+    pub tls_client_allowlist: Option<TlsClientAllowlist>,
+
+    /// Closes connections that have not had a frame decoded for this many seconds.
+    pub idle_timeout_secs: Option<u64>,
+
+    /// Also accepts the connections handed off over the Unix domain socket at this path.
+    pub handoff_socket: Option<PathBuf>,
+
+    /// Sizes the read buffer of each connection from the amount of data it receives.
+    pub adaptive_read_buffer: Option<AdaptiveReadBufferConfig>,
+
+    /// Deserializes the frames of each connection concurrently with reading them.
+    pub decoding_concurrency: Option<DecodingConcurrencyConfig>,
+
+    /// Captures the bytes received on connections to files.
+    pub wire_capture: Option<WireCaptureConfig>,
+
+    /// Inserts metadata about the connection into each log event received on it.
+    pub connection_metadata: Option<ConnectionMetadataKeys>,
+}
+
+/// The settings of `TcpSourceOptions` applying to each connection, once built.
+#[derive(Clone)]
+struct ConnectionOptions {
+    tls_client_allowlist: Option<TlsClientAllowlist>,
+    idle_timeout_secs: Option<u64>,
+    adaptive_read_buffer: Option<AdaptiveReadBufferConfig>,
+    decoding_concurrency: Option<DecodingConcurrencyConfig>,
+    wire_capture: Option<WireCapture>,
+    connection_metadata: Option<ConnectionMetadataKeys>,
+}
+
 pub trait TcpSource: Clone + Send + Sync + 'static
 where
     <<Self as TcpSource>::Decoder as tokio_util::codec::Decoder>::Item: std::marker::Send,
@@ -91,16 +232,60 @@ where
         + std::fmt::Display
         + Send
         + Unpin;
-    type Item: Into<SmallVec<[Event; 1]>> + Send + Unpin;
+    type Item: Into<SmallVec<[Event; 1]>> + Send + Unpin + 'static;
     type Decoder: Decoder<Item = (Self::Item, usize), Error = Self::Error> + Send + 'static;
     type Acker: TcpSourceAcker + Send;
+    type Instrumentation: TcpSourceInstrumentation;
 
     fn decoder(&self) -> Self::Decoder;
 
+    /// Registers the source's own internal telemetry for a connection accepted from `peer_addr`,
+    /// which is then updated as the connection is read from.
+    ///
+    /// Connections aren't instrumented beyond the internal events of every TCP source by default.
+    fn instrumentation(&self, _peer_addr: SocketAddr) -> Option<Self::Instrumentation> {
+        None
+    }
+
+    /// Where to insert the address of the peer into each log event received, if anywhere.
+    fn peer_addr_metadata(&self) -> Option<PeerAddrMetadata> {
+        None
+    }
+
     fn handle_events(&self, _events: &mut [Event], _host: std::net::SocketAddr) {}
 
     fn build_acker(&self, item: &[Self::Item]) -> Self::Acker;
 
+    /// Authenticates a connection from the first frame decoded from it, such as one holding a
+    /// token or an API key, before any of its events are forwarded.
+    ///
+    /// Connections aren't authenticated by default.
+    fn authenticate_first_frame(
+        &self,
+        _frame: &Self::Item,
+        _peer_addr: SocketAddr,
+    ) -> FirstFrameAuthentication {
+        FirstFrameAuthentication::Forward
+    }
+
+    /// The decoder split into its framer and deserializer, for sources whose frames can be
+    /// deserialized concurrently with `decoding_concurrency`.
+    ///
+    /// Sources returning `None` always decode frames on the task of their connection.
+    fn concurrent_decoder(&self) -> Option<ConcurrentDecoder<Self::Item, Self::Error>> {
+        None
+    }
+
+    /// A protocol-specific "going away" frame, written to each connection when shutdown begins,
+    /// before our write side is shut down, so that well-behaved clients reconnect to another
+    /// instance rather than sending data that won't be read.
+    ///
+    /// With acknowledgements, the frame is written once the acknowledgement of the last batch
+    /// of the connection has been. Nothing is written by default.
+    fn shutdown_frame(&self) -> Option<Bytes> {
+        None
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn run(
         self,
@@ -113,8 +298,37 @@ where
         cx: SourceContext,
         acknowledgements: SourceAcknowledgementsConfig,
         max_connections: Option<u32>,
+        options: TcpSourceOptions,
     ) -> crate::Result<crate::sources::Source> {
         let acknowledgements = cx.do_acknowledgements(acknowledgements);
+        let TcpSourceOptions {
+            tls_client_allowlist,
+            idle_timeout_secs,
+            handoff_socket,
+            adaptive_read_buffer,
+            decoding_concurrency,
+            wire_capture,
+            connection_metadata,
+        } = options;
+        let connection_options = ConnectionOptions {
+            tls_client_allowlist,
+            idle_timeout_secs,
+            adaptive_read_buffer,
+            decoding_concurrency,
+            wire_capture: wire_capture
+                .map(|config| WireCapture::new(config, &cx))
+                .transpose()?,
+            connection_metadata,
+        };
+
+        if decoding_concurrency.is_some() && self.concurrent_decoder().is_none() {
+            return Err("Concurrent decoding is not supported by this source.".into());
+        }
+
+        #[cfg(not(unix))]
+        if handoff_socket.is_some() {
+            return Err("Handing off connections is only supported on Unix.".into());
+        }
 
         Ok(Box::pin(async move {
             let listenfd = ListenFd::from_env();
@@ -127,6 +341,21 @@ where
                     })
                 })?;
 
+            #[cfg(unix)]
+            let (listener, handoff_task) = match handoff_socket {
+                Some(path) => {
+                    let (handoff, task) = handoff::receive_connections(&path, cx.shutdown.clone())
+                        .map_err(|error| {
+                            emit!(SocketBindError {
+                                mode: SocketMode::Unix,
+                                error: &error,
+                            })
+                        })?;
+                    (listener.with_handoff(handoff), Some(task))
+                }
+                None => (listener, None),
+            };
+
             info!(
                 message = "Listening.",
                 addr = %listener
@@ -159,6 +388,7 @@ where
                     let connection_gauge = connection_gauge.clone();
                     let request_limiter = request_limiter.clone();
                     let tls_client_metadata_key = tls_client_metadata_key.clone();
+                    let connection_options = connection_options.clone();
 
                     async move {
                         let socket = match connection {
@@ -202,6 +432,7 @@ where
                                 acknowledgements,
                                 request_limiter,
                                 tls_client_metadata_key.clone(),
+                                connection_options,
                             );
 
                             tokio::spawn(
@@ -214,8 +445,16 @@ where
                         });
                     }
                 })
-                .map(Ok)
-                .await
+                .await;
+
+            // The handoff socket is only deleted once its task completes, and must be before the
+            // source replacing this one on reload binds it again.
+            #[cfg(unix)]
+            if let Some(task) = handoff_task {
+                let _ = task.await;
+            }
+
+            Ok(())
         }))
     }
 }
@@ -233,10 +472,20 @@ async fn handle_stream<T>(
     acknowledgements: bool,
     request_limiter: RequestLimiter,
     tls_client_metadata_key: Option<String>,
+    options: ConnectionOptions,
 ) where
     <<T as TcpSource>::Decoder as tokio_util::codec::Decoder>::Item: std::marker::Send,
     T: TcpSource,
 {
+    let ConnectionOptions {
+        tls_client_allowlist,
+        idle_timeout_secs,
+        adaptive_read_buffer,
+        decoding_concurrency,
+        wire_capture,
+        connection_metadata,
+    } = options;
+
     tokio::select! {
         result = socket.handshake() => {
             if let Err(error) = result {
@@ -249,6 +498,26 @@ async fn handle_stream<T>(
         }
     };
 
+    let peer_certificate = socket
+        .ssl_stream()
+        .and_then(|stream| stream.ssl().peer_certificate());
+
+    let matched_identity = match &tls_client_allowlist {
+        Some(allowlist) => {
+            match peer_certificate
+                .as_ref()
+                .and_then(|certificate| allowlist.matched_identity(certificate))
+            {
+                Some(identity) => Some(identity),
+                None => {
+                    emit!(TcpSocketTlsClientRejected { peer_addr });
+                    return;
+                }
+            }
+        }
+        None => None,
+    };
+
     if let Some(keepalive) = keepalive {
         if let Err(error) = socket.set_keepalive(keepalive) {
             warn!(message = "Failed configuring TCP keepalive.", %error);
@@ -261,26 +530,79 @@ async fn handle_stream<T>(
         }
     }
 
-    let socket = socket.after_read(move |byte_size| {
-        emit!(TcpBytesReceived {
-            byte_size,
-            peer_addr
-        });
+    let connection_metadata =
+        connection_metadata.map(|keys| ConnectionMetadata::new(&keys, &socket));
+
+    let instrumentation = source.instrumentation(peer_addr).map(Arc::new);
+
+    let mut adaptive_read_buffer = adaptive_read_buffer.map(AdaptiveReadBuffer::new);
+
+    let capture = wire_capture.and_then(|wire_capture| wire_capture.start(peer_addr));
+    let socket = CapturingStream::new(socket, capture).after_read({
+        let instrumentation = instrumentation.clone();
+        let bytes_read = adaptive_read_buffer
+            .as_ref()
+            .map(AdaptiveReadBuffer::bytes_read);
+        move |byte_size| {
+            if let Some(bytes_read) = &bytes_read {
+                bytes_read.fetch_add(byte_size, Ordering::Relaxed);
+            }
+            emit!(TcpBytesReceived {
+                byte_size,
+                peer_addr
+            });
+            if let Some(instrumentation) = &instrumentation {
+                instrumentation.bytes_received(byte_size);
+            }
+        }
     });
 
-    let certificate_metadata = socket
-        .get_ref()
-        .ssl_stream()
-        .and_then(|stream| stream.ssl().peer_certificate())
-        .map(CertificateMetadata::from);
+    let certificate_metadata = peer_certificate.map(CertificateMetadata::from);
 
-    let reader = FramedRead::new(socket, source.decoder());
-    let mut reader = ReadyFrames::new(reader);
+    let peer_addr_metadata = source.peer_addr_metadata();
+
+    let capacity = adaptive_read_buffer
+        .as_ref()
+        .map(AdaptiveReadBuffer::initial_capacity);
+    let mut reader: ConnectionReader<_, T> =
+        match (decoding_concurrency, source.concurrent_decoder()) {
+            (Some(config), Some(decoder)) => {
+                ConnectionReader::Concurrent(decoder.frames(socket, capacity, config))
+            }
+            _ => ConnectionReader::Serial(ReadyFrames::new(match capacity {
+                Some(capacity) => FramedRead::with_capacity(socket, source.decoder(), capacity),
+                None => FramedRead::new(socket, source.decoder()),
+            })),
+        };
+
+    // Connections that have not had a frame decoded for `idle_timeout_secs` are closed, so that they
+    // don't hold on to one of the `max_connections` slots forever.
+    let idle_timeout = idle_timeout_secs.map(Duration::from_secs);
+    let mut last_frame_at = Instant::now();
+
+    // With acknowledgements, shutting down our write side as soon as shutdown begins would keep the
+    // client from reading the acknowledgements of the batches still being delivered, so it would
+    // send them again once we're back. Instead the connection stops reading frames, and is drained
+    // once the acknowledgement of its last batch has been written.
+    let mut draining = false;
+
+    // Whether the first frame has been passed to `TcpSource::authenticate_first_frame`.
+    let mut authenticated = false;
 
     loop {
         let mut permit = tokio::select! {
+            biased;
+
             _ = &mut tripwire => break,
+            _ = idle_expired(idle_timeout, last_frame_at, peer_addr) => break,
             _ = &mut shutdown_signal => {
+                if acknowledgements {
+                    draining = true;
+                    break;
+                }
+                if !write_shutdown_frame(reader.get_mut(), source.shutdown_frame(), &mut tripwire).await {
+                    break;
+                }
                 if close_socket(reader.get_ref().get_ref().get_ref()) {
                     break;
                 }
@@ -296,8 +618,17 @@ async fn handle_stream<T>(
         tokio::pin!(timeout);
 
         tokio::select! {
+            biased;
+
             _ = &mut tripwire => break,
             _ = &mut shutdown_signal => {
+                if acknowledgements {
+                    draining = true;
+                    break;
+                }
+                if !write_shutdown_frame(reader.get_mut(), source.shutdown_frame(), &mut tripwire).await {
+                    break;
+                }
                 if close_socket(reader.get_ref().get_ref().get_ref()) {
                     break;
                 }
@@ -305,11 +636,47 @@ async fn handle_stream<T>(
             _ = &mut timeout => {
                 // This connection is currently holding a permit, but has not received data for some time. Release
                 // the permit to let another connection try
+                if let Some(adaptive) = &mut adaptive_read_buffer {
+                    adaptive.idle(Instant::now());
+                    adaptive.resize(reader.read_buffer_mut());
+                }
                 continue;
             }
+            _ = idle_expired(idle_timeout, last_frame_at, peer_addr) => break,
             res = reader.next() => {
                 match res {
-                    Some(Ok((frames, _byte_size))) => {
+                    Some(Ok((mut frames, byte_size))) => {
+                        last_frame_at = Instant::now();
+                        if let Some(instrumentation) = &instrumentation {
+                            instrumentation.frames_decoded(frames.len(), byte_size);
+                        }
+                        if let Some(adaptive) = &mut adaptive_read_buffer {
+                            adaptive.observe(byte_size);
+                            adaptive.resize(reader.read_buffer_mut());
+                        }
+
+                        if !authenticated && !frames.is_empty() {
+                            let authentication = source.authenticate_first_frame(&frames[0], peer_addr);
+                            match authentication {
+                                FirstFrameAuthentication::Forward => {}
+                                FirstFrameAuthentication::Consume => {
+                                    frames.remove(0);
+                                }
+                                FirstFrameAuthentication::Reject { response } => {
+                                    emit!(TcpFirstFrameRejected { peer_addr });
+                                    if let Some(response) = response {
+                                        if let Err(error) = reader.get_mut().write_all(&response).await {
+                                            emit!(TcpFirstFrameRejectionSendError { error, peer_addr });
+                                        }
+                                    }
+                                    break;
+                                }
+                            }
+                            authenticated = true;
+                            if frames.is_empty() {
+                                continue;
+                            }
+                        }
                         let _num_frames = frames.len();
                         let acker = source.build_acker(&frames);
                         let (batch, receiver) = BatchNotifier::maybe_new_with_receiver(acknowledgements);
@@ -340,6 +707,9 @@ async fn handle_stream<T>(
                             if let Some(certificate_metadata) = &certificate_metadata {
                                 let mut metadata: BTreeMap<String, value::Value> = BTreeMap::new();
                                 metadata.insert("subject".to_string(), certificate_metadata.subject().into());
+                                if let Some(identity) = &matched_identity {
+                                    metadata.insert("matched_identity".to_string(), identity.as_str().into());
+                                }
                                 for event in &mut events {
                                     let log = event.as_mut_log();
                                     log.insert(&tls_client_metadata_key[..], value::Value::from(metadata.clone()));
@@ -347,23 +717,38 @@ async fn handle_stream<T>(
                             }
                         }
 
+                        if let Some(connection_metadata) = &connection_metadata {
+                            connection_metadata.insert(&mut events);
+                        }
+
+                        if let Some(peer_addr_metadata) = &peer_addr_metadata {
+                            for event in &mut events {
+                                if let Event::Log(log) = event {
+                                    peer_addr_metadata.insert(log, peer_addr);
+                                }
+                            }
+                        }
+
                         source.handle_events(&mut events, peer_addr);
                         match out.send_batch(events).await {
                             Ok(_) => {
                                 let ack = match receiver {
                                     None => TcpSourceAck::Ack,
-                                    Some(receiver) =>
-                                        match receiver.await {
+                                    Some(receiver) => tokio::select! {
+                                        status = receiver => match status {
                                             BatchStatus::Delivered => TcpSourceAck::Ack,
                                             BatchStatus::Errored => {TcpSourceAck::Error},
                                             BatchStatus::Rejected => {
                                                 // Sinks are responsible for emitting ComponentEventsDropped.
                                                 TcpSourceAck::Reject
                                             }
-                                        }
+                                        },
+                                        // The batch is still being delivered when the shutdown timeout expires.
+                                        _ = &mut tripwire => break,
+                                    }
                                 };
                                 if let Some(ack_bytes) = acker.build_ack(ack){
-                                    let stream = reader.get_mut().get_mut();
+                                    let stream = reader.get_mut();
                                     if let Err(error) = stream.write_all(&ack_bytes).await {
                                         emit!(TcpSendAckError{ error });
                                         break;
@@ -380,7 +765,11 @@ async fn handle_stream<T>(
                         }
                     }
                     Some(Err(error)) => {
-                        if !<<T as TcpSource>::Error as StreamDecodingError>::can_continue(&error) {
+                        let can_continue = <<T as TcpSource>::Error as StreamDecodingError>::can_continue(&error);
+                        if let Some(instrumentation) = &instrumentation {
+                            instrumentation.decode_error(can_continue);
+                        }
+                        if !can_continue {
                             emit!(DecoderFramingError { error });
                             break;
                         }
@@ -396,6 +785,123 @@ async fn handle_stream<T>(
 
         drop(permit);
     }
+
+    // Our write side is shut down so that the client sees that no more acknowledgements are
+    // coming, and whatever it still sends is discarded until it closes its side, as closing a
+    // socket with unread data resets the connection, which can lose the acknowledgements the
+    // client hasn't read yet.
+    if draining
+        && write_shutdown_frame(reader.get_mut(), source.shutdown_frame(), &mut tripwire).await
+        && !close_socket(reader.get_ref().get_ref().get_ref())
+    {
+        let mut sink = tokio::io::sink();
+        tokio::select! {
+            _ = &mut tripwire => {},
+            result = tokio::io::copy(reader.get_mut(), &mut sink) => {
+                if let Err(error) = result {
+                    debug!(message = "Failed draining connection.", %error);
+                }
+            },
+        }
+    }
+
+    if let Some(instrumentation) = &instrumentation {
+        instrumentation.connection_closed();
+    }
+}
+
+/// The frames of a connection, decoded either on its own task or concurrently.
+enum ConnectionReader<R, T: TcpSource>
+where
+    <<T as TcpSource>::Decoder as tokio_util::codec::Decoder>::Item: std::marker::Send,
+{
+    Serial(ReadyFrames<FramedRead<R, T::Decoder>, T::Item, T::Error>),
+    Concurrent(ConcurrentFrames<R, T::Item, T::Error>),
+}
+
+impl<R, T: TcpSource> ConnectionReader<R, T>
+where
+    <<T as TcpSource>::Decoder as tokio_util::codec::Decoder>::Item: std::marker::Send,
+    R: tokio::io::AsyncRead + Unpin,
+{
+    fn get_ref(&self) -> &R {
+        match self {
+            Self::Serial(reader) => reader.get_ref().get_ref(),
+            Self::Concurrent(reader) => reader.get_ref().get_ref(),
+        }
+    }
+
+    fn get_mut(&mut self) -> &mut R {
+        match self {
+            Self::Serial(reader) => reader.get_mut().get_mut(),
+            Self::Concurrent(reader) => reader.get_mut().get_mut(),
+        }
+    }
+
+    fn read_buffer_mut(&mut self) -> &mut BytesMut {
+        match self {
+            Self::Serial(reader) => reader.get_mut().read_buffer_mut(),
+            Self::Concurrent(reader) => reader.get_mut().read_buffer_mut(),
+        }
+    }
+}
+
+impl<R, T: TcpSource> Stream for ConnectionReader<R, T>
+where
+    <<T as TcpSource>::Decoder as tokio_util::codec::Decoder>::Item: std::marker::Send,
+    R: tokio::io::AsyncRead + Unpin,
+{
+    type Item = Result<(Vec<T::Item>, usize), T::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match &mut *self {
+            Self::Serial(reader) => reader.poll_next_unpin(cx),
+            Self::Concurrent(reader) => reader.poll_next_unpin(cx),
+        }
+    }
+}
+
+/// Resolves once the connection from `peer_addr` has had no frame decoded for `idle_timeout` since
+/// `last_frame_at`, or never if there is no idle timeout.
+async fn idle_expired(
+    idle_timeout: Option<Duration>,
+    last_frame_at: Instant,
+    peer_addr: SocketAddr,
+) {
+    match idle_timeout {
+        Some(idle_timeout) => {
+            sleep_until(last_frame_at + idle_timeout).await;
+            emit!(TcpConnectionIdleTimeout {
+                peer_addr,
+                idle_timeout_secs: idle_timeout.as_secs(),
+            });
+        }
+        None => futures::future::pending().await,
+    }
+}
+
+/// Writes the `TcpSource::shutdown_frame` of a connection, if there is one.
+///
+/// Returns `false` if the shutdown timeout expired before the frame was written, in which case
+/// the connection is to be closed right away.
+async fn write_shutdown_frame<W: AsyncWrite + Unpin>(
+    stream: &mut W,
+    frame: Option<Bytes>,
+    tripwire: &mut BoxFuture<'static, ()>,
+) -> bool {
+    let frame = match frame {
+        Some(frame) => frame,
+        None => return true,
+    };
+    tokio::select! {
+        _ = tripwire => false,
+        result = stream.write_all(&frame) => {
+            if let Err(error) = result {
+                debug!(message = "Failed sending shutdown frame.", %error);
+            }
+            true
+        },
+    }
 }
 
 fn close_socket(socket: &MaybeTlsIncomingStream<TcpStream>) -> bool {
@@ -414,3 +920,285 @@ fn close_socket(socket: &MaybeTlsIncomingStream<TcpStream>) -> bool {
         true
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::AtomicUsize;
+
+    use codecs::decoding::{DeserializerConfig, FramingConfig, NewlineDelimitedDecoderOptions};
+    use lookup::{metadata_path, owned_value_path};
+    use tokio::io::AsyncReadExt;
+
+    use super::*;
+    use crate::{
+        codecs::DecodingConfig,
+        config::ComponentKey,
+        event::EventStatus,
+        test_util::{next_addr, wait_for_tcp},
+    };
+
+    /// A source reading newline-delimited frames, with the hooks of `TcpSource` set as needed.
+    #[derive(Clone)]
+    struct MockSource {
+        authentication: FirstFrameAuthentication,
+        instrumentation: Option<MockInstrumentation>,
+        shutdown_frame: Option<Bytes>,
+        ack: Option<Bytes>,
+    }
+
+    impl Default for MockSource {
+        fn default() -> Self {
+            Self {
+                authentication: FirstFrameAuthentication::Forward,
+                instrumentation: None,
+                shutdown_frame: None,
+                ack: None,
+            }
+        }
+    }
+
+    /// Replies with the acknowledgement of the source to the batches that were delivered.
+    struct MockAcker(Option<Bytes>);
+
+    impl TcpSourceAcker for MockAcker {
+        fn build_ack(self, ack: TcpSourceAck) -> Option<Bytes> {
+            match ack {
+                TcpSourceAck::Ack => self.0,
+                TcpSourceAck::Error | TcpSourceAck::Reject => None,
+            }
+        }
+    }
+
+    /// Counts the calls to the `TcpSourceInstrumentation` hooks, across connections.
+    #[derive(Clone, Default)]
+    struct MockInstrumentation {
+        bytes_received: Arc<AtomicUsize>,
+        frames_decoded: Arc<AtomicUsize>,
+        connections_closed: Arc<AtomicUsize>,
+    }
+
+    impl TcpSourceInstrumentation for MockInstrumentation {
+        fn bytes_received(&self, byte_size: usize) {
+            self.bytes_received.fetch_add(byte_size, Ordering::Relaxed);
+        }
+
+        fn frames_decoded(&self, count: usize, _byte_size: usize) {
+            self.frames_decoded.fetch_add(count, Ordering::Relaxed);
+        }
+
+        fn connection_closed(&self) {
+            self.connections_closed.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    impl TcpSource for MockSource {
+        type Error = codecs::decoding::Error;
+        type Item = SmallVec<[Event; 1]>;
+        type Decoder = crate::codecs::Decoder;
+        type Acker = MockAcker;
+        type Instrumentation = MockInstrumentation;
+
+        fn decoder(&self) -> Self::Decoder {
+            DecodingConfig::new(
+                FramingConfig::NewlineDelimited {
+                    newline_delimited: NewlineDelimitedDecoderOptions::default(),
+                },
+                DeserializerConfig::Bytes,
+                LogNamespace::Legacy,
+            )
+            .build()
+        }
+
+        fn instrumentation(&self, _peer_addr: SocketAddr) -> Option<Self::Instrumentation> {
+            self.instrumentation.clone()
+        }
+
+        fn build_acker(&self, _: &[Self::Item]) -> Self::Acker {
+            MockAcker(self.ack.clone())
+        }
+
+        fn authenticate_first_frame(
+            &self,
+            _frame: &Self::Item,
+            _peer_addr: SocketAddr,
+        ) -> FirstFrameAuthentication {
+            self.authentication.clone()
+        }
+
+        fn shutdown_frame(&self) -> Option<Bytes> {
+            self.shutdown_frame.clone()
+        }
+    }
+
+    /// Runs `source` on a new address until `shutdown` is shut down.
+    async fn run_source(
+        source: MockSource,
+        out: SourceSender,
+        acknowledgements: bool,
+    ) -> (SocketAddr, crate::shutdown::SourceShutdownCoordinator) {
+        let addr = next_addr();
+        let (cx, shutdown) = SourceContext::new_shutdown(&ComponentKey::from("tcp"), out);
+        let server = source
+            .run(
+                SocketListenAddr::SocketAddr(addr),
+                None,
+                1,
+                MaybeTlsSettings::Raw(()),
+                None,
+                None,
+                cx,
+                acknowledgements.into(),
+                None,
+                Default::default(),
+            )
+            .unwrap();
+        tokio::spawn(server);
+        wait_for_tcp(addr).await;
+        (addr, shutdown)
+    }
+
+    #[tokio::test]
+    async fn rejects_connections_failing_first_frame_authentication() {
+        let (out, mut rx) = SourceSender::new_test();
+        let source = MockSource {
+            authentication: FirstFrameAuthentication::Reject {
+                response: Some(Bytes::from("denied\n")),
+            },
+        };
+        let (addr, _shutdown) = run_source(source, out, false).await;
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(b"bad token\n").await.unwrap();
+
+        // The response is written, then the connection is closed.
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert_eq!(response, "denied\n");
+
+        assert!(tokio::time::timeout(Duration::from_millis(100), rx.next())
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn peer_addr_metadata_legacy_namespace() {
+        let peer_addr: SocketAddr = "192.168.0.1:5000".parse().unwrap();
+        let metadata = PeerAddrMetadata::new(
+            "socket",
+            LogNamespace::Legacy,
+            Some(owned_value_path!("host")),
+        )
+        .with_port(Some(owned_value_path!("port")));
+
+        let mut log = LogEvent::default();
+        log.insert("host", "already-set");
+        metadata.insert(&mut log, peer_addr);
+
+        assert_eq!(log["host"], "already-set".into());
+        assert_eq!(log["port"], 5000.into());
+    }
+
+    #[test]
+    fn peer_addr_metadata_vector_namespace() {
+        let peer_addr: SocketAddr = "192.168.0.1:5000".parse().unwrap();
+        let metadata = PeerAddrMetadata::new(
+            "fluent",
+            LogNamespace::Vector,
+            Some(owned_value_path!("host")),
+        );
+
+        let mut log = LogEvent::default();
+        metadata.insert(&mut log, peer_addr);
+
+        assert_eq!(
+            log.get(metadata_path!("fluent", "host")),
+            Some(&"192.168.0.1".into())
+        );
+        assert!(log.get(metadata_path!("fluent", "port")).is_none());
+        assert!(log.get("host").is_none());
+    }
+
+    #[tokio::test]
+    async fn instruments_connections() {
+        let (out, rx) = SourceSender::new_test();
+        let instrumentation = MockInstrumentation::default();
+        let source = MockSource {
+            instrumentation: Some(instrumentation.clone()),
+            ..Default::default()
+        };
+        let (addr, _shutdown) = run_source(source, out, false).await;
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(b"foo\nbar\n").await.unwrap();
+        drop(stream);
+
+        let events = rx.take(2).collect::<Vec<_>>().await;
+        assert_eq!(events.len(), 2);
+        for _ in 0..100 {
+            if instrumentation.connections_closed.load(Ordering::Relaxed) == 1 {
+                break;
+            }
+            sleep(Duration::from_millis(10)).await;
+        }
+
+        assert_eq!(instrumentation.bytes_received.load(Ordering::Relaxed), 8);
+        assert_eq!(instrumentation.frames_decoded.load(Ordering::Relaxed), 2);
+        assert_eq!(
+            instrumentation.connections_closed.load(Ordering::Relaxed),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn writes_shutdown_frame_on_shutdown() {
+        let (out, mut rx) = SourceSender::new_test();
+        let source = MockSource {
+            shutdown_frame: Some(Bytes::from("bye\n")),
+            ..Default::default()
+        };
+        let (addr, shutdown) = run_source(source, out, false).await;
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(b"foo\n").await.unwrap();
+        assert!(rx.next().await.is_some());
+
+        tokio::spawn(shutdown.shutdown_all(Instant::now() + Duration::from_secs(5)));
+
+        // The frame is written before our write side is shut down.
+        let mut received = String::new();
+        stream.read_to_string(&mut received).await.unwrap();
+        assert_eq!(received, "bye\n");
+    }
+
+    #[tokio::test]
+    async fn drains_pending_acks_on_shutdown() {
+        let (out, mut rx) = SourceSender::new_test();
+        let source = MockSource {
+            ack: Some(Bytes::from("ok\n")),
+            ..Default::default()
+        };
+        let (addr, shutdown) = run_source(source, out, true).await;
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(b"foo\n").await.unwrap();
+        let mut event = rx.next().await.unwrap();
+
+        // The batch is still being delivered once shutdown begins.
+        tokio::spawn(shutdown.shutdown_all(Instant::now() + Duration::from_secs(5)));
+        sleep(Duration::from_millis(100)).await;
+        let mut byte = [0; 1];
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), stream.read(&mut byte))
+                .await
+                .is_err()
+        );
+
+        event.metadata_mut().update_status(EventStatus::Delivered);
+        drop(event);
+
+        // The acknowledgement is written before our write side is shut down.
+        let mut received = String::new();
+        stream.read_to_string(&mut received).await.unwrap();
+        assert_eq!(received, "ok\n");
+    }
+}
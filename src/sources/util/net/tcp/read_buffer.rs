@@ -0,0 +1,227 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use bytes::BytesMut;
+use tokio::time::{Duration, Instant};
+use vector_config::configurable_component;
+
+/// How long a connection must not send any data for its read buffer to be shrunk.
+const IDLE_AFTER: Duration = Duration::from_secs(1);
+
+const fn default_min_bytes() -> usize {
+    1024
+}
+
+const fn default_max_bytes() -> usize {
+    1024 * 1024
+}
+
+/// Adaptive sizing of the buffer each connection reads into.
+///
+/// The buffer starts at `min_bytes` and grows, up to `max_bytes`, while reads keep filling it, as
+/// is the case for bulk senders. It shrinks back towards `min_bytes` as frames get smaller or the
+/// connection goes quiet, so that idle connections hold on to as little memory as possible.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct AdaptiveReadBufferConfig {
+    /// The smallest size, in bytes, the read buffer of a connection is shrunk to.
+    #[serde(default = "default_min_bytes")]
+    pub min_bytes: usize,
+
+    /// The largest size, in bytes, the read buffer of a connection is grown to.
+    ///
+    /// Frames larger than this are still read in full, the buffer is only sized for them while
+    /// they are being decoded.
+    #[serde(default = "default_max_bytes")]
+    pub max_bytes: usize,
+}
+
+impl Default for AdaptiveReadBufferConfig {
+    fn default() -> Self {
+        Self {
+            min_bytes: default_min_bytes(),
+            max_bytes: default_max_bytes(),
+        }
+    }
+}
+
+/// Tracks the read buffer size a connection should use given the traffic observed on it.
+pub(super) struct AdaptiveReadBuffer {
+    min_bytes: usize,
+    max_bytes: usize,
+    target: usize,
+    bytes_read: Arc<AtomicUsize>,
+    last_bytes_read: usize,
+    last_read_at: Instant,
+}
+
+impl AdaptiveReadBuffer {
+    pub(super) fn new(config: AdaptiveReadBufferConfig) -> Self {
+        let min_bytes = config.min_bytes.max(1);
+        let max_bytes = config.max_bytes.max(min_bytes);
+        Self {
+            min_bytes,
+            max_bytes,
+            target: min_bytes,
+            bytes_read: Arc::new(AtomicUsize::new(0)),
+            last_bytes_read: 0,
+            last_read_at: Instant::now(),
+        }
+    }
+
+    /// Returns the counter the bytes read from the connection are to be added to.
+    pub(super) fn bytes_read(&self) -> Arc<AtomicUsize> {
+        Arc::clone(&self.bytes_read)
+    }
+
+    pub(super) const fn initial_capacity(&self) -> usize {
+        self.min_bytes
+    }
+
+    /// Records that `byte_size` bytes of frames were decoded from a single read of the buffer.
+    ///
+    /// Filling the buffer means more data was likely waiting, so larger reads pay off. Using only
+    /// a small fraction of it means the buffer is oversized for this connection.
+    pub(super) fn observe(&mut self, byte_size: usize) {
+        if byte_size >= self.target {
+            self.target = (self.target * 2).min(self.max_bytes);
+        } else if byte_size < self.target / 4 {
+            self.shrink();
+        }
+    }
+
+    /// Records that no frame was decoded from the connection for a while.
+    ///
+    /// A frame may still be arriving over several reads, so the buffer is only shrunk once no
+    /// data at all has been read for `IDLE_AFTER`, and then at most once every `IDLE_AFTER`.
+    pub(super) fn idle(&mut self, now: Instant) {
+        let bytes_read = self.bytes_read.load(Ordering::Relaxed);
+        if bytes_read != self.last_bytes_read {
+            self.last_bytes_read = bytes_read;
+            self.last_read_at = now;
+        } else if now.duration_since(self.last_read_at) >= IDLE_AFTER {
+            self.shrink();
+            self.last_read_at = now;
+        }
+    }
+
+    fn shrink(&mut self) {
+        self.target = (self.target / 2).max(self.min_bytes);
+    }
+
+    /// Resizes `buffer` towards the current target.
+    ///
+    /// Growing only reserves additional capacity, but shrinking replaces the buffer and so is only
+    /// done once it doesn't hold any partially read frame.
+    pub(super) fn resize(&self, buffer: &mut BytesMut) {
+        let capacity = buffer.capacity();
+        if capacity < self.target {
+            buffer.reserve(self.target - buffer.len());
+        } else if capacity > self.target * 2 && buffer.is_empty() {
+            *buffer = BytesMut::with_capacity(self.target);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn adaptive(min_bytes: usize, max_bytes: usize) -> AdaptiveReadBuffer {
+        AdaptiveReadBuffer::new(AdaptiveReadBufferConfig {
+            min_bytes,
+            max_bytes,
+        })
+    }
+
+    #[test]
+    fn grows_while_reads_fill_the_buffer() {
+        let mut buffer = adaptive(1024, 4096);
+
+        buffer.observe(1024);
+        assert_eq!(buffer.target, 2048);
+        buffer.observe(2048);
+        buffer.observe(4096);
+        assert_eq!(buffer.target, 4096);
+    }
+
+    #[test]
+    fn shrinks_on_small_reads_and_idleness() {
+        let mut buffer = adaptive(1024, 8192);
+        buffer.target = 8192;
+
+        buffer.observe(4096);
+        assert_eq!(buffer.target, 8192);
+        buffer.observe(100);
+        assert_eq!(buffer.target, 4096);
+
+        let start = buffer.last_read_at;
+        buffer.idle(start + Duration::from_millis(10));
+        assert_eq!(buffer.target, 4096);
+        buffer.idle(start + IDLE_AFTER);
+        buffer.idle(start + IDLE_AFTER);
+        assert_eq!(buffer.target, 2048);
+        buffer.idle(start + IDLE_AFTER * 2);
+        assert_eq!(buffer.target, 1024);
+    }
+
+    #[test]
+    fn does_not_shrink_while_data_is_read() {
+        let mut buffer = adaptive(1024, 8192);
+        buffer.target = 8192;
+
+        // Frames keep arriving over several reads, without a single one being decoded.
+        let start = buffer.last_read_at;
+        for i in 1..=500 {
+            buffer.bytes_read.fetch_add(100, Ordering::Relaxed);
+            buffer.idle(start + Duration::from_millis(10 * i));
+        }
+        assert_eq!(buffer.target, 8192);
+    }
+
+    #[test]
+    fn keeps_capacity_under_sustained_load() {
+        let mut buffer = adaptive(1024, 64 * 1024);
+        let mut bytes = BytesMut::new();
+
+        let start = buffer.last_read_at;
+        let mut capacities = Vec::new();
+        for i in 1..=1000 {
+            // A read filling the buffer, then a pause shorter than `IDLE_AFTER`, as between the
+            // writes of a busy sender.
+            let read = bytes.capacity().max(buffer.target);
+            buffer.bytes_read.fetch_add(read, Ordering::Relaxed);
+            buffer.observe(read);
+            buffer.resize(&mut bytes);
+            buffer.idle(start + Duration::from_millis(20 * i));
+            buffer.resize(&mut bytes);
+            capacities.push(bytes.capacity());
+        }
+
+        assert_eq!(buffer.target, 64 * 1024);
+        let steady = capacities[100..].to_vec();
+        assert!(steady.iter().all(|&capacity| capacity == steady[0]));
+    }
+
+    #[test]
+    fn only_replaces_empty_buffers() {
+        let mut buffer = adaptive(1024, 1024 * 1024);
+        buffer.target = 64 * 1024;
+
+        let mut bytes = BytesMut::new();
+        buffer.resize(&mut bytes);
+        assert!(bytes.capacity() >= 64 * 1024);
+
+        buffer.target = 1024;
+        bytes.extend_from_slice(b"partial frame");
+        buffer.resize(&mut bytes);
+        assert!(bytes.capacity() >= 64 * 1024);
+
+        bytes.clear();
+        buffer.resize(&mut bytes);
+        assert!(bytes.capacity() < 64 * 1024);
+    }
+}
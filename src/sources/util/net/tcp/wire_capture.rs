@@ -0,0 +1,245 @@
+use std::{
+    io,
+    net::SocketAddr,
+    path::PathBuf,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+};
+
+use chrono::Utc;
+use pin_project::pin_project;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use vector_config::configurable_component;
+
+use crate::{config::SourceContext, internal_events::TcpWireCaptureWriteError};
+
+const fn default_sample_rate() -> u64 {
+    1
+}
+
+const fn default_max_connections() -> u64 {
+    10
+}
+
+const fn default_max_bytes() -> usize {
+    64 * 1024
+}
+
+/// Captures the bytes received on connections to files, to reproduce decoding issues offline.
+///
+/// The first `max_bytes` bytes received on each captured connection are written, as received
+/// after any TLS decryption, to a file in the `wire_capture` subdirectory of the source's data
+/// directory. Files are named after the time the connection was accepted, its number since the
+/// source started, and the address of the peer. They can be replayed by sending them to a source
+/// as is, for example with `nc <address> <port> < <file>`.
+///
+/// Captures can contain sensitive data, so this should only be enabled while debugging.
+#[configurable_component]
+#[derive(Clone, Copy, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct WireCaptureConfig {
+    /// Capture 1 in every `sample_rate` connections.
+    #[serde(default = "default_sample_rate")]
+    pub sample_rate: u64,
+
+    /// The maximum number of connections captured.
+    ///
+    /// Once this many connections have been captured, no others are until the source is
+    /// restarted.
+    #[serde(default = "default_max_connections")]
+    pub max_connections: u64,
+
+    /// The maximum number of bytes captured from each connection.
+    #[serde(default = "default_max_bytes")]
+    pub max_bytes: usize,
+}
+
+/// Selects the connections of a source to capture.
+#[derive(Clone)]
+pub struct WireCapture {
+    config: WireCaptureConfig,
+    dir: PathBuf,
+    accepted: Arc<AtomicU64>,
+    captured: Arc<AtomicU64>,
+}
+
+impl WireCapture {
+    pub fn new(config: WireCaptureConfig, cx: &SourceContext) -> crate::Result<Self> {
+        if config.sample_rate == 0 {
+            return Err("`wire_capture.sample_rate` must be at least 1.".into());
+        }
+        let dir = cx
+            .globals
+            .resolve_and_make_data_subdir(None, &format!("{}/wire_capture", cx.key.id()))?;
+        Ok(Self::with_dir(config, dir))
+    }
+
+    fn with_dir(config: WireCaptureConfig, dir: PathBuf) -> Self {
+        Self {
+            config,
+            dir,
+            accepted: Arc::default(),
+            captured: Arc::default(),
+        }
+    }
+
+    /// Returns a capture of the connection accepted from `peer_addr`, if it's selected.
+    pub fn start(&self, peer_addr: SocketAddr) -> Option<Capture> {
+        let accepted = self.accepted.fetch_add(1, Ordering::Relaxed);
+        if accepted % self.config.sample_rate != 0 {
+            return None;
+        }
+        let captured = self.captured.fetch_add(1, Ordering::Relaxed);
+        if captured >= self.config.max_connections {
+            return None;
+        }
+
+        let path = self.dir.join(format!(
+            "{}-{}-{}-{}.bin",
+            Utc::now().format("%Y%m%dT%H%M%SZ"),
+            accepted,
+            peer_addr.ip().to_string().replace(':', "_"),
+            peer_addr.port()
+        ));
+        debug!(message = "Capturing connection.", path = ?path);
+        Some(Capture {
+            path,
+            buffer: Vec::new(),
+            max_bytes: self.config.max_bytes,
+        })
+    }
+}
+
+/// The bytes captured from a connection, written out once `max_bytes` have been received or the
+/// connection is closed.
+pub struct Capture {
+    path: PathBuf,
+    buffer: Vec<u8>,
+    max_bytes: usize,
+}
+
+impl Capture {
+    /// Records `bytes`, and returns whether the capture is complete.
+    fn record(&mut self, bytes: &[u8]) -> bool {
+        let len = bytes.len().min(self.max_bytes - self.buffer.len());
+        self.buffer.extend_from_slice(&bytes[..len]);
+        self.buffer.len() >= self.max_bytes
+    }
+}
+
+impl Drop for Capture {
+    fn drop(&mut self) {
+        let path = std::mem::take(&mut self.path);
+        let buffer = std::mem::take(&mut self.buffer);
+        let write = move || {
+            if let Err(error) = std::fs::write(&path, buffer) {
+                emit!(TcpWireCaptureWriteError { error, path: &path });
+            }
+        };
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => drop(handle.spawn_blocking(write)),
+            Err(_) => write(),
+        }
+    }
+}
+
+/// Wraps a connection, capturing the bytes read from it if it was selected.
+#[pin_project]
+pub struct CapturingStream<T> {
+    #[pin]
+    inner: T,
+    capture: Option<Capture>,
+}
+
+impl<T> CapturingStream<T> {
+    pub const fn new(inner: T, capture: Option<Capture>) -> Self {
+        Self { inner, capture }
+    }
+
+    pub const fn get_ref(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T: AsyncRead> AsyncRead for CapturingStream<T> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let before = buf.filled().len();
+        let this = self.project();
+        let result = this.inner.poll_read(cx, buf);
+        if let (Poll::Ready(Ok(())), Some(capture)) = (&result, this.capture.as_mut()) {
+            if capture.record(&buf.filled()[before..]) {
+                // Dropping the capture writes it out.
+                *this.capture = None;
+            }
+        }
+        result
+    }
+}
+
+impl<T: AsyncWrite> AsyncWrite for CapturingStream<T> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<Result<usize, io::Error>> {
+        self.project().inner.poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), io::Error>> {
+        self.project().inner.poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), io::Error>> {
+        self.project().inner.poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::AsyncReadExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn captures_sampled_connections_up_to_limits() {
+        let dir = tempfile::tempdir().unwrap();
+        let capture = WireCapture::with_dir(
+            WireCaptureConfig {
+                sample_rate: 2,
+                max_connections: 2,
+                max_bytes: 8,
+            },
+            dir.path().to_path_buf(),
+        );
+        let peer_addr = "127.0.0.1:5000".parse().unwrap();
+
+        for _ in 0..6 {
+            let mut stream = CapturingStream::new(&b"hello world\n"[..], capture.start(peer_addr));
+            let mut read = Vec::new();
+            stream.read_to_end(&mut read).await.unwrap();
+            assert_eq!(read, b"hello world\n");
+        }
+
+        // The writes run on blocking tasks.
+        let mut files = Vec::new();
+        for _ in 0..100 {
+            files = std::fs::read_dir(dir.path())
+                .unwrap()
+                .map(|entry| std::fs::read(entry.unwrap().path()).unwrap())
+                .collect::<Vec<_>>();
+            if files.len() == 2 && files.iter().all(|file| !file.is_empty()) {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(files, vec![b"hello wo".to_vec(), b"hello wo".to_vec()]);
+    }
+}
@@ -1,4 +1,4 @@
-use std::io;
+use std::{io, net::SocketAddr};
 
 use listenfd::ListenFd;
 use tokio::net::UdpSocket;
@@ -21,3 +21,46 @@ pub async fn try_bind_udp_socket(
         },
     }
 }
+
+/// Binds `count` UDP sockets to the listen address.
+///
+/// When more than one socket is bound, they all set `SO_REUSEPORT`, so that the kernel spreads the
+/// datagrams received across them by hashing their flows.
+pub async fn try_bind_udp_sockets(
+    addr: SocketListenAddr,
+    listenfd: ListenFd,
+    count: usize,
+) -> io::Result<Vec<UdpSocket>> {
+    if count <= 1 {
+        return try_bind_udp_socket(addr, listenfd)
+            .await
+            .map(|socket| vec![socket]);
+    }
+
+    match addr {
+        SocketListenAddr::SocketAddr(addr) => (0..count).map(|_| bind_reuse_port(addr)).collect(),
+        SocketListenAddr::SystemdFd(_) => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "multiple sockets can't be bound to a systemd fd",
+        )),
+    }
+}
+
+#[cfg(unix)]
+fn bind_reuse_port(addr: SocketAddr) -> io::Result<UdpSocket> {
+    use socket2::{Domain, Protocol, Socket, Type};
+
+    let socket = Socket::new(Domain::for_address(addr), Type::DGRAM, Some(Protocol::UDP))?;
+    socket.set_reuse_port(true)?;
+    socket.set_nonblocking(true)?;
+    socket.bind(&addr.into())?;
+    UdpSocket::from_std(socket.into())
+}
+
+#[cfg(not(unix))]
+fn bind_reuse_port(_addr: SocketAddr) -> io::Result<UdpSocket> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "`SO_REUSEPORT` is only supported on Unix",
+    ))
+}
@@ -6,7 +6,10 @@ use url::Url;
 use vector_api_client::{
     connect_subscription_client,
     gql::{
-        output_events_by_component_id_patterns_subscription::OutputEventsByComponentIdPatternsSubscriptionOutputEventsByComponentIdPatterns,
+        output_events_by_component_id_patterns_subscription::{
+            OutputEventsByComponentIdPatternsSubscriptionOutputEventsByComponentIdPatterns,
+            OutputEventsByComponentIdPatternsSubscriptionOutputEventsByComponentIdPatternsOnEventNotificationNotification,
+        },
         TapEncodingFormat, TapSubscriptionExt,
     },
     Client,
@@ -61,11 +64,14 @@ pub(crate) async fn cmd(opts: &super::Opts, mut signal_rx: SignalRx) -> exitcode
 
     let formatter = EventFormatter::new(opts.meta, opts.format);
 
+    // Cursor of the last events received, to resume the tap from after reconnecting.
+    let mut cursor = None;
+
     loop {
         tokio::select! {
             biased;
             Ok(SignalTo::Shutdown | SignalTo::Quit) = signal_rx.recv() => break,
-            status = run(url.clone(), opts, outputs_patterns.clone(), formatter.clone()) => {
+            status = run(url.clone(), opts, outputs_patterns.clone(), formatter.clone(), &mut cursor) => {
                 if status == exitcode::UNAVAILABLE || status == exitcode::TEMPFAIL && !opts.no_reconnect {
                     eprintln!("[tap] Connection failed. Reconnecting in {:?} seconds.", RECONNECT_DELAY / 1000);
                     tokio::time::sleep(Duration::from_millis(RECONNECT_DELAY)).await;
@@ -84,6 +90,7 @@ async fn run(
     opts: &super::Opts,
     outputs_patterns: Vec<String>,
     formatter: EventFormatter,
+    cursor: &mut Option<String>,
 ) -> exitcode::ExitCode {
     let subscription_client = match connect_subscription_client(url).await {
         Ok(c) => c,
@@ -103,6 +110,8 @@ async fn run(
             opts.format,
             opts.limit as i64,
             opts.interval as i64,
+            opts.filter.clone(),
+            cursor.clone(),
         );
     };
 
@@ -125,7 +134,9 @@ async fn run(
                             println!("{}", formatter.format(ev.component_id.as_ref(), ev.component_kind.as_ref(), ev.component_type.as_ref(), ev.string.as_ref()));
                         },
                         OutputEventsByComponentIdPatternsSubscriptionOutputEventsByComponentIdPatterns::EventNotification(ev) => {
-                            if !opts.quiet {
+                            if let OutputEventsByComponentIdPatternsSubscriptionOutputEventsByComponentIdPatternsOnEventNotificationNotification::CursorAdvanced(advanced) = &ev.notification {
+                                *cursor = Some(advanced.cursor.clone());
+                            } else if !opts.quiet {
                                 eprintln!("{}", ev.message);
                             }
                         },
@@ -36,6 +36,11 @@ pub struct Opts {
     #[arg(value_delimiter(','), long)]
     inputs_of: Vec<String>,
 
+    /// VRL condition events must match to be sampled, e.g. '.status == 500'. Filtering happens
+    /// server-side, so non-matching events are never sent
+    #[arg(long)]
+    filter: Option<String>,
+
     /// Quiet output includes only events
     #[arg(short, long)]
     quiet: bool,
@@ -45,6 +45,7 @@ use crate::{
     },
     event::{EventArray, EventContainer},
     internal_events::EventsReceived,
+    internal_telemetry::guard::SelfTelemetryComponents,
     shutdown::SourceShutdownCoordinator,
     source_sender::CHUNK_SIZE,
     spawn_named,
@@ -134,13 +135,25 @@ pub struct Pieces {
     pub(super) healthchecks: HashMap<ComponentKey, Task>,
     pub(crate) shutdown_coordinator: SourceShutdownCoordinator,
     pub(crate) detach_triggers: HashMap<ComponentKey, Trigger>,
+    pub(crate) self_telemetry: SelfTelemetryComponents,
 }
 
 /// Builds only the new pieces, and doesn't check their topology.
 pub async fn build_pieces(
+    config: &super::Config,
+    diff: &ConfigDiff,
+    buffers: HashMap<ComponentKey, BuiltBuffer>,
+) -> Result<Pieces, Vec<String>> {
+    build_pieces_sharing(config, diff, buffers, SelfTelemetryComponents::default()).await
+}
+
+/// Builds only the new pieces of a topology whose sources share `self_telemetry`, and doesn't
+/// check their topology.
+pub(super) async fn build_pieces_sharing(
     config: &super::Config,
     diff: &ConfigDiff,
     mut buffers: HashMap<ComponentKey, BuiltBuffer>,
+    self_telemetry: SelfTelemetryComponents,
 ) -> Result<Pieces, Vec<String>> {
     let mut inputs = HashMap::new();
     let mut outputs = HashMap::new();
@@ -267,6 +280,7 @@ pub async fn build_pieces(
             acknowledgements: source.sink_acknowledgements,
             schema_definitions,
             schema: config.schema,
+            self_telemetry: self_telemetry.clone(),
         };
         let server = match source.inner.build(context).await {
             Err(error) => {
@@ -587,6 +601,7 @@ pub async fn build_pieces(
             healthchecks,
             shutdown_coordinator,
             detach_triggers,
+            self_telemetry,
         };
 
         Ok(pieces)
@@ -31,6 +31,7 @@ use vector_buffers::topology::channel::{BufferReceiverStream, BufferSender};
 use crate::{
     config::{ComponentKey, Config, ConfigDiff, Inputs, OutputId},
     event::EventArray,
+    internal_telemetry::guard::SelfTelemetryComponents,
     topology::{builder::Pieces, task::Task},
 };
 
@@ -106,7 +107,8 @@ pub async fn start_validated(
         return None;
     }
 
-    let mut running_topology = RunningTopology::new(config, abort_tx.clone());
+    let mut running_topology =
+        RunningTopology::new(config, abort_tx.clone(), pieces.self_telemetry.clone());
 
     if !running_topology
         .run_healthchecks(&diff, &mut pieces, running_topology.config.healthchecks)
@@ -116,6 +118,9 @@ pub async fn start_validated(
     }
     running_topology.connect_diff(&diff, &mut pieces).await;
     running_topology.spawn_diff(&diff, pieces);
+    running_topology
+        .self_telemetry
+        .update(&running_topology.config);
 
     Some((running_topology, (abort_tx, abort_rx)))
 }
@@ -125,7 +130,24 @@ pub async fn build_or_log_errors(
     diff: &ConfigDiff,
     buffers: HashMap<ComponentKey, BuiltBuffer>,
 ) -> Option<Pieces> {
-    match builder::build_pieces(config, diff, buffers).await {
+    log_build_errors(builder::build_pieces(config, diff, buffers).await)
+}
+
+/// Like [`build_or_log_errors`], for the pieces of a running topology, whose sources share its
+/// `self_telemetry`.
+pub(super) async fn rebuild_or_log_errors(
+    config: &Config,
+    diff: &ConfigDiff,
+    buffers: HashMap<ComponentKey, BuiltBuffer>,
+    self_telemetry: &SelfTelemetryComponents,
+) -> Option<Pieces> {
+    log_build_errors(
+        builder::build_pieces_sharing(config, diff, buffers, self_telemetry.clone()).await,
+    )
+}
+
+fn log_build_errors(result: Result<Pieces, Vec<String>>) -> Option<Pieces> {
+    match result {
         Err(errors) => {
             for error in errors {
                 error!(message = "Configuration error.", %error);
@@ -22,13 +22,14 @@ use crate::{
         SourceConfig,
     },
     event::EventArray,
+    internal_telemetry::guard::SelfTelemetryComponents,
     shutdown::SourceShutdownCoordinator,
     spawn_named,
     topology::{
-        build_or_log_errors, builder,
+        builder,
         builder::Pieces,
         fanout::{ControlChannel, ControlMessage},
-        handle_errors, retain, take_healthchecks,
+        handle_errors, rebuild_or_log_errors, retain, take_healthchecks,
         task::TaskOutput,
         BuiltBuffer, TaskHandle, WatchRx, WatchTx,
     },
@@ -46,10 +47,15 @@ pub struct RunningTopology {
     abort_tx: mpsc::UnboundedSender<()>,
     watch: (WatchTx, WatchRx),
     pub(crate) running: Arc<AtomicBool>,
+    pub(super) self_telemetry: SelfTelemetryComponents,
 }
 
 impl RunningTopology {
-    pub fn new(config: Config, abort_tx: mpsc::UnboundedSender<()>) -> Self {
+    pub fn new(
+        config: Config,
+        abort_tx: mpsc::UnboundedSender<()>,
+        self_telemetry: SelfTelemetryComponents,
+    ) -> Self {
         Self {
             inputs: HashMap::new(),
             outputs: HashMap::new(),
@@ -61,6 +67,7 @@ impl RunningTopology {
             abort_tx,
             watch: watch::channel(TapResource::default()),
             running: Arc::new(AtomicBool::new(true)),
+            self_telemetry,
         }
     }
 
@@ -236,7 +243,8 @@ impl RunningTopology {
         // Try to build all of the new components coming from the new configuration.  If we can
         // successfully build them, we'll attempt to connect them up to the topology and spawn their
         // respective component tasks.
-        if let Some(mut new_pieces) = build_or_log_errors(&new_config, &diff, buffers.clone()).await
+        if let Some(mut new_pieces) =
+            rebuild_or_log_errors(&new_config, &diff, buffers.clone(), &self.self_telemetry).await
         {
             // If healthchecks are configured for any of the changing/new components, try running
             // them before moving forward with connecting and spawning.  In some cases, healthchecks
@@ -248,6 +256,7 @@ impl RunningTopology {
                 self.connect_diff(&diff, &mut new_pieces).await;
                 self.spawn_diff(&diff, new_pieces);
                 self.config = new_config;
+                self.self_telemetry.update(&self.config);
 
                 info!("New configuration loaded successfully.");
 
@@ -261,7 +270,9 @@ impl RunningTopology {
         warn!("Failed to completely load new configuration. Restoring old configuration.");
 
         let diff = diff.flip();
-        if let Some(mut new_pieces) = build_or_log_errors(&self.config, &diff, buffers).await {
+        if let Some(mut new_pieces) =
+            rebuild_or_log_errors(&self.config, &diff, buffers, &self.self_telemetry).await
+        {
             if self
                 .run_healthchecks(&diff, &mut new_pieces, self.config.healthchecks)
                 .await
@@ -1,11 +1,12 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     marker::PhantomData,
     str::FromStr,
     sync::{
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicUsize, Ordering},
         Mutex, MutexGuard,
     },
+    time::{Duration, Instant},
 };
 
 use futures_util::{future::ready, Stream, StreamExt};
@@ -28,6 +29,7 @@ use tracing_subscriber::{
 };
 pub use tracing_tower::{InstrumentableService, InstrumentedService};
 use value::Value;
+use vector_common::internal_event::DROP_REPORT_TARGET;
 
 use crate::event::LogEvent;
 
@@ -52,6 +54,20 @@ static SUBSCRIBERS: Mutex<Option<Vec<oneshot::Sender<Vec<LogEvent>>>>> =
 /// has been initialized.
 static SENDER: OnceCell<Sender<LogEvent>> = OnceCell::new();
 
+/// SPAN_TIMINGS counts the subscribers asking for the busy and idle time of spans to be tracked, and added to internal
+/// log events as the `spans` array. It is only tracked while a subscriber asks for it, as tracking requires timing
+/// every span entry and exit.
+static SPAN_TIMINGS: AtomicUsize = AtomicUsize::new(0);
+
+/// DROP_REPORTS counts the subscribers asking for drop reports. Drop reports are only
+/// broadcast while a subscriber asks for them, as they bypass the configured log level.
+static DROP_REPORTS: AtomicUsize = AtomicUsize::new(0);
+
+/// SPAN_HIERARCHY counts the subscribers asking for all the fields of spans to be recorded, and the spans an internal
+/// log event is emitted in to be added to it as the `span_hierarchy` array. It is only enabled while a subscriber asks
+/// for it, as otherwise only the fields identifying components are recorded.
+static SPAN_HIERARCHY: AtomicUsize = AtomicUsize::new(0);
+
 fn metrics_layer_enabled() -> bool {
     !matches!(std::env::var("DISABLE_INTERNAL_METRICS_TRACING_INTEGRATION"), Ok(x) if x == "true")
 }
@@ -64,13 +80,31 @@ pub fn init(color: bool, json: bool, levels: &str, internal_log_rate_limit: u64)
     let metrics_layer =
         metrics_layer_enabled().then(|| MetricsLayer::new().with_filter(LevelFilter::INFO));
 
-    let broadcast_layer = RateLimitedLayer::new(BroadcastLayer::new())
+    let broadcast_layer = RateLimitedLayer::new(BroadcastLayer::new(false))
         .with_default_limit(internal_log_rate_limit)
         .with_filter(fmt_filter.clone());
 
+    // Drop reports bypass both rate limiting and the configured log level, as they're meant to
+    // account for every dropped event. They are only let through while a subscriber asks for them.
+    // Component spans are always let through, so that reports carry the fields of the component
+    // that dropped the events even when it was built before drop reports were asked for.
+    let drop_report_layer =
+        BroadcastLayer::new(true).with_filter(tracing_subscriber::filter::filter_fn(|metadata| {
+            if metadata.is_span() {
+                metadata
+                    .fields()
+                    .iter()
+                    .any(|field| is_component_field(field.name()))
+            } else {
+                metadata.target() == DROP_REPORT_TARGET && drop_reports_enabled()
+            }
+        }));
+
     let subscriber = tracing_subscriber::registry()
         .with(metrics_layer)
-        .with(broadcast_layer);
+        .with(SpanTimingsLayer)
+        .with(broadcast_layer)
+        .with(drop_report_layer);
 
     #[cfg(feature = "tokio-console")]
     let subscriber = {
@@ -232,6 +266,81 @@ pub fn stop_early_buffering() {
     }
 }
 
+/// Starts tracking the busy and idle time of spans, until the returned guard and those returned to other subscribers
+/// are dropped.
+///
+/// Internal log events emitted in between carry a `spans` array, from the outermost span to the innermost one, with
+/// the `name`, `busy_ns` and `idle_ns` of each span the event was emitted in. Only the spans created in between are
+/// tracked.
+#[must_use]
+pub fn enable_span_timings() -> SpanTimingsGuard {
+    SPAN_TIMINGS.fetch_add(1, Ordering::Relaxed);
+    SpanTimingsGuard(())
+}
+
+/// Keeps span timings tracked while held. See [`enable_span_timings`].
+pub struct SpanTimingsGuard(());
+
+impl Drop for SpanTimingsGuard {
+    fn drop(&mut self) {
+        SPAN_TIMINGS.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Returns whether span timings are being added to internal log events.
+pub fn span_timings_enabled() -> bool {
+    SPAN_TIMINGS.load(Ordering::Relaxed) > 0
+}
+
+/// Starts broadcasting drop reports, until the returned guard and those returned to other subscribers are dropped.
+///
+/// See [`DROP_REPORT_TARGET`] for more details.
+#[must_use]
+pub fn enable_drop_reports() -> DropReportsGuard {
+    DROP_REPORTS.fetch_add(1, Ordering::Relaxed);
+    DropReportsGuard(())
+}
+
+/// Keeps drop reports broadcast while held. See [`enable_drop_reports`].
+pub struct DropReportsGuard(());
+
+impl Drop for DropReportsGuard {
+    fn drop(&mut self) {
+        DROP_REPORTS.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Returns whether drop reports are being broadcast.
+fn drop_reports_enabled() -> bool {
+    DROP_REPORTS.load(Ordering::Relaxed) > 0
+}
+
+/// Starts recording all the fields of spans, until the returned guard and those returned to other subscribers are
+/// dropped.
+///
+/// Internal log events emitted in between carry a `span_hierarchy` array, from the outermost span to the innermost
+/// one, with the `name`, `target` and `fields` of each span the event was emitted in. Spans created before this is
+/// called only have the fields identifying components.
+#[must_use]
+pub fn enable_span_hierarchy() -> SpanHierarchyGuard {
+    SPAN_HIERARCHY.fetch_add(1, Ordering::Relaxed);
+    SpanHierarchyGuard(())
+}
+
+/// Keeps span hierarchies recorded while held. See [`enable_span_hierarchy`].
+pub struct SpanHierarchyGuard(());
+
+impl Drop for SpanHierarchyGuard {
+    fn drop(&mut self) {
+        SPAN_HIERARCHY.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Returns whether span hierarchies are being added to internal log events.
+pub fn span_hierarchy_enabled() -> bool {
+    SPAN_HIERARCHY.load(Ordering::Relaxed) > 0
+}
+
 /// A subscription to the log events flowing in via `tracing`, in the Vector native format.
 ///
 /// Used to capture tracing events from internal log telemetry, via `tracing`, and convert them to native Vector events,
@@ -276,13 +385,24 @@ impl TraceSubscription {
     }
 }
 
+/// Returns whether the given internal log event is a drop report.
+///
+/// See [`DROP_REPORT_TARGET`] for more details.
+pub fn is_drop_report(log: &LogEvent) -> bool {
+    log.get(event_path!("metadata", "target"))
+        .map_or(false, |target| *target == Value::from(DROP_REPORT_TARGET))
+}
+
 struct BroadcastLayer<S> {
+    drop_reports: bool,
     _subscriber: PhantomData<S>,
 }
 
 impl<S> BroadcastLayer<S> {
-    const fn new() -> Self {
+    /// Creates a layer broadcasting either only drop reports, or everything except drop reports.
+    const fn new(drop_reports: bool) -> Self {
         BroadcastLayer {
+            drop_reports,
             _subscriber: PhantomData,
         }
     }
@@ -293,17 +413,63 @@ where
     S: Subscriber + 'static + for<'lookup> LookupSpan<'lookup>,
 {
     fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
-        if should_process_tracing_event() {
+        let is_drop_report = event.metadata().target() == DROP_REPORT_TARGET;
+        if is_drop_report == self.drop_reports && should_process_tracing_event() {
             let mut log = LogEvent::from(event);
             // Add span fields if available
             if let Some(parent_span) = ctx.event_span(event) {
                 for span in parent_span.scope().from_root() {
                     if let Some(fields) = span.extensions().get::<SpanFields>() {
-                        for (k, v) in &fields.0 {
-                            log.insert(event_path!("vector", *k), v.clone());
+                        for (k, v) in fields.components() {
+                            log.insert(event_path!("vector", k), v.clone());
                         }
                     }
                 }
+
+                if span_hierarchy_enabled() {
+                    let spans = parent_span
+                        .scope()
+                        .from_root()
+                        .map(|span| {
+                            let fields = span
+                                .extensions()
+                                .get::<SpanFields>()
+                                .map(|fields| {
+                                    fields
+                                        .0
+                                        .iter()
+                                        .map(|(k, v)| (k.to_string(), v.clone()))
+                                        .collect::<BTreeMap<_, _>>()
+                                })
+                                .unwrap_or_default();
+                            Value::from(BTreeMap::from([
+                                ("name".to_string(), Value::from(span.name())),
+                                ("target".to_string(), Value::from(span.metadata().target())),
+                                ("fields".to_string(), Value::from(fields)),
+                            ]))
+                        })
+                        .collect::<Vec<_>>();
+                    log.insert(event_path!("span_hierarchy"), spans);
+                }
+
+                if span_timings_enabled() {
+                    let now = Instant::now();
+                    let spans = parent_span
+                        .scope()
+                        .from_root()
+                        .filter_map(|span| {
+                            let timings = span.extensions().get::<SpanTimings>()?.at(now);
+                            Some(Value::from(BTreeMap::from([
+                                ("name".to_string(), Value::from(span.name())),
+                                ("busy_ns".to_string(), duration_nanos(timings.busy)),
+                                ("idle_ns".to_string(), duration_nanos(timings.idle)),
+                            ])))
+                        })
+                        .collect::<Vec<_>>();
+                    if !spans.is_empty() {
+                        log.insert(event_path!("spans"), spans);
+                    }
+                }
             }
             // Try buffering the event, and if we're not buffering anymore, try to
             // send it along via the trace sender if it's been established.
@@ -320,12 +486,107 @@ where
         ctx: Context<'_, S>,
     ) {
         let span = ctx.span(id).expect("span must already exist!");
+        // Both broadcast layers may see the same span, but it only needs recording once.
+        if span.extensions().get::<SpanFields>().is_some() {
+            return;
+        }
         let mut fields = SpanFields::default();
         attrs.values().record(&mut fields);
         span.extensions_mut().insert(fields);
     }
 }
 
+/// Tracks the busy and idle time of spans, for internal log events to report.
+///
+/// This is a separate layer so that timings are recorded once, and regardless of the filters applied to the broadcast
+/// layers.
+struct SpanTimingsLayer;
+
+impl<S> Layer<S> for SpanTimingsLayer
+where
+    S: Subscriber + for<'lookup> LookupSpan<'lookup>,
+{
+    fn on_new_span(
+        &self,
+        _attrs: &tracing_core::span::Attributes<'_>,
+        id: &tracing_core::span::Id,
+        ctx: Context<'_, S>,
+    ) {
+        if span_timings_enabled() {
+            let span = ctx.span(id).expect("span must already exist!");
+            span.extensions_mut()
+                .insert(SpanTimings::new(Instant::now()));
+        }
+    }
+
+    fn on_enter(&self, id: &tracing_core::span::Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(timings) = span.extensions_mut().get_mut::<SpanTimings>() {
+                timings.enter(Instant::now());
+            }
+        }
+    }
+
+    fn on_exit(&self, id: &tracing_core::span::Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(timings) = span.extensions_mut().get_mut::<SpanTimings>() {
+                timings.exit(Instant::now());
+            }
+        }
+    }
+}
+
+/// Time a span has spent entered (busy) and not entered (idle) since it was created.
+#[derive(Clone, Copy, Debug)]
+struct SpanTimings {
+    busy: Duration,
+    idle: Duration,
+    last: Instant,
+    entered: usize,
+}
+
+impl SpanTimings {
+    const fn new(now: Instant) -> Self {
+        Self {
+            busy: Duration::ZERO,
+            idle: Duration::ZERO,
+            last: now,
+            entered: 0,
+        }
+    }
+
+    /// Accounts for the time elapsed since the last update, as busy if the span is entered.
+    fn advance(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last);
+        if self.entered > 0 {
+            self.busy += elapsed;
+        } else {
+            self.idle += elapsed;
+        }
+        self.last = now;
+    }
+
+    fn enter(&mut self, now: Instant) {
+        self.advance(now);
+        self.entered += 1;
+    }
+
+    fn exit(&mut self, now: Instant) {
+        self.advance(now);
+        self.entered = self.entered.saturating_sub(1);
+    }
+
+    /// Returns the timings accumulated up to `now`.
+    fn at(mut self, now: Instant) -> Self {
+        self.advance(now);
+        self
+    }
+}
+
+fn duration_nanos(duration: Duration) -> Value {
+    Value::from(i64::try_from(duration.as_nanos()).unwrap_or(i64::MAX))
+}
+
 #[derive(Default, Debug)]
 struct SpanFields(HashMap<&'static str, Value>);
 
@@ -335,13 +596,26 @@ impl SpanFields {
         // Filter for span fields such as component_id, component_type, etc.
         //
         // This captures all the basic component information provided in the
-        // span that each component is spawned with. We don't capture all fields
-        // to avoid adding unintentional noise and to prevent accidental
-        // security/privacy issues (e.g. leaking sensitive data).
-        if name.starts_with("component_") {
+        // span that each component is spawned with. We don't capture all fields,
+        // unless span hierarchies were asked for, to avoid adding unintentional
+        // noise and to prevent accidental security/privacy issues (e.g. leaking
+        // sensitive data).
+        if is_component_field(name) || span_hierarchy_enabled() {
             self.0.insert(name, value.into());
         }
     }
+
+    /// Returns the fields identifying the component the span belongs to.
+    fn components(&self) -> impl Iterator<Item = (&'static str, &Value)> {
+        self.0
+            .iter()
+            .filter(|(name, _)| is_component_field(name))
+            .map(|(name, value)| (*name, value))
+    }
+}
+
+fn is_component_field(name: &str) -> bool {
+    name.starts_with("component_")
 }
 
 impl tracing::field::Visit for SpanFields {
@@ -365,3 +639,42 @@ impl tracing::field::Visit for SpanFields {
         self.record(field, format!("{:?}", value));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn span_timings_split_busy_and_idle() {
+        let start = Instant::now();
+        let ms = |n| start + Duration::from_millis(n);
+        let mut timings = SpanTimings::new(start);
+
+        timings.enter(ms(10));
+        timings.exit(ms(30));
+        timings.enter(ms(35));
+
+        let at = timings.at(ms(50));
+        assert_eq!(at.busy, Duration::from_millis(35));
+        assert_eq!(at.idle, Duration::from_millis(15));
+
+        // Snapshots don't affect the running timings.
+        timings.exit(ms(60));
+        assert_eq!(timings.busy, Duration::from_millis(45));
+        assert_eq!(timings.idle, Duration::from_millis(15));
+    }
+
+    #[test]
+    fn span_hierarchy_disabled_once_guards_drop() {
+        assert!(!span_hierarchy_enabled());
+
+        let first = enable_span_hierarchy();
+        let second = enable_span_hierarchy();
+        assert!(span_hierarchy_enabled());
+
+        drop(first);
+        assert!(span_hierarchy_enabled());
+        drop(second);
+        assert!(!span_hierarchy_enabled());
+    }
+}
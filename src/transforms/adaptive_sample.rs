@@ -0,0 +1,382 @@
+use std::{collections::HashMap, time::Duration};
+
+use ordered_float::NotNan;
+use rand::{thread_rng, Rng};
+use serde_with::serde_as;
+use snafu::Snafu;
+use tokio::time::Instant;
+use vector_config::configurable_component;
+use vector_core::config::LogNamespace;
+
+use crate::{
+    conditions::{AnyCondition, Condition},
+    config::{DataType, GenerateConfig, Input, Output, TransformConfig, TransformContext},
+    event::{Event, Value},
+    internal_events::{AdaptiveSampleEventDiscarded, TemplateRenderingError},
+    schema,
+    template::Template,
+    transforms::{FunctionTransform, OutputBuffer, Transform},
+};
+
+const fn default_window_secs() -> Duration {
+    Duration::from_secs(60)
+}
+
+fn default_sample_rate_field() -> String {
+    "sample_rate".to_string()
+}
+
+/// Configuration for the `adaptive_sample` transform.
+///
+/// The rate of events of each key is tracked over a sliding window. As long as a key stays under
+/// `target_rate` events per window, all of its events are forwarded. Once it goes over, its events
+/// are sampled with a probability of `target_rate` divided by its rate, so that roughly
+/// `target_rate` events per window are forwarded.
+#[serde_as]
+#[configurable_component(transform("adaptive_sample"))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct AdaptiveSampleConfig {
+    /// The number of events of each key forwarded per `window_secs` before sampling starts.
+    pub target_rate: u64,
+
+    /// The length of the sliding window the rate of each key is tracked over, in seconds.
+    #[serde_as(as = "serde_with::DurationSeconds<f64>")]
+    #[serde(default = "default_window_secs")]
+    pub window_secs: Duration,
+
+    /// The key used to group events, whose rates are tracked separately.
+    ///
+    /// If left unspecified, or if the template fails to render, events are tracked under a single
+    /// shared key.
+    #[configurable(metadata(docs::examples = "{{ service }}", docs::examples = "{{ hostname }}",))]
+    pub key_field: Option<Template>,
+
+    /// The name of the field the effective sample rate of forwarded events is written to.
+    ///
+    /// The rate is expressed as `N`, meaning the event was forwarded with a probability of `1/N`,
+    /// so downstream components can reweight counts by multiplying by it.
+    #[serde(default = "default_sample_rate_field")]
+    pub sample_rate_field: String,
+
+    /// A logical condition used to exclude events from sampling.
+    pub exclude: Option<AnyCondition>,
+}
+
+impl GenerateConfig for AdaptiveSampleConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            target_rate: 100,
+            window_secs: default_window_secs(),
+            key_field: None,
+            sample_rate_field: default_sample_rate_field(),
+            exclude: None::<AnyCondition>,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+impl TransformConfig for AdaptiveSampleConfig {
+    async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        AdaptiveSample::new(self, context).map(Transform::function)
+    }
+
+    fn input(&self) -> Input {
+        Input::new(DataType::Log | DataType::Trace)
+    }
+
+    fn outputs(&self, merged_definition: &schema::Definition, _: LogNamespace) -> Vec<Output> {
+        vec![Output::default(DataType::Log | DataType::Trace)
+            .with_schema_definition(merged_definition.clone())]
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum ConfigError {
+    #[snafu(display("`target_rate` and `window_secs` must be non-zero"))]
+    NonZero,
+}
+
+/// Event counts of a key over the current and previous windows.
+#[derive(Clone, Debug)]
+struct KeyRate {
+    window_start: Instant,
+    current: u64,
+    previous: u64,
+}
+
+impl KeyRate {
+    const fn new(now: Instant) -> Self {
+        Self {
+            window_start: now,
+            current: 0,
+            previous: 0,
+        }
+    }
+
+    /// Counts an event at `now`, and returns the estimated number of events of the key over the
+    /// last `window`.
+    ///
+    /// The count of the previous window is weighted by how much of it still overlaps the sliding
+    /// window, which smooths the estimate without keeping the time of every event.
+    fn observe(&mut self, now: Instant, window: Duration) -> f64 {
+        let elapsed = now.saturating_duration_since(self.window_start);
+        if elapsed >= window * 2 {
+            self.previous = 0;
+            self.current = 0;
+            self.window_start = now;
+        } else if elapsed >= window {
+            self.previous = self.current;
+            self.current = 0;
+            self.window_start += window;
+        }
+        self.current += 1;
+
+        let elapsed = now.saturating_duration_since(self.window_start);
+        let overlap = 1.0 - (elapsed.as_secs_f64() / window.as_secs_f64()).min(1.0);
+        self.previous as f64 * overlap + self.current as f64
+    }
+
+    fn is_expired(&self, now: Instant, window: Duration) -> bool {
+        now.saturating_duration_since(self.window_start) >= window * 2
+    }
+}
+
+#[derive(Clone)]
+pub struct AdaptiveSample {
+    target_rate: f64,
+    window: Duration,
+    key_field: Option<Template>,
+    sample_rate_field: String,
+    exclude: Option<Condition>,
+    rates: HashMap<Option<String>, KeyRate>,
+    last_sweep: Instant,
+}
+
+impl AdaptiveSample {
+    pub fn new(config: &AdaptiveSampleConfig, context: &TransformContext) -> crate::Result<Self> {
+        if config.target_rate == 0 || config.window_secs.is_zero() {
+            return Err(Box::new(ConfigError::NonZero));
+        }
+
+        let exclude = config
+            .exclude
+            .as_ref()
+            .map(|condition| condition.build(&context.enrichment_tables))
+            .transpose()?;
+
+        Ok(Self {
+            target_rate: config.target_rate as f64,
+            window: config.window_secs,
+            key_field: config.key_field.clone(),
+            sample_rate_field: config.sample_rate_field.clone(),
+            exclude,
+            rates: HashMap::new(),
+            last_sweep: Instant::now(),
+        })
+    }
+
+    /// Returns the probability an event of `key` seen at `now` is forwarded with.
+    fn probability(&mut self, key: Option<String>, now: Instant) -> f64 {
+        // Keys that went quiet are forgotten, so that short-lived keys don't accumulate.
+        if now.saturating_duration_since(self.last_sweep) >= self.window {
+            let window = self.window;
+            self.rates.retain(|_, rate| !rate.is_expired(now, window));
+            self.last_sweep = now;
+        }
+
+        let rate = self
+            .rates
+            .entry(key)
+            .or_insert_with(|| KeyRate::new(now))
+            .observe(now, self.window);
+        (self.target_rate / rate).min(1.0)
+    }
+
+    fn sample(&mut self, output: &mut OutputBuffer, mut event: Event, now: Instant) {
+        if let Some(condition) = self.exclude.as_ref() {
+            let (result, checked) = condition.check(event);
+            if result {
+                output.push(checked);
+                return;
+            }
+            event = checked;
+        }
+
+        let key = self.key_field.as_ref().and_then(|template| {
+            template
+                .render_string(&event)
+                .map_err(|error| {
+                    emit!(TemplateRenderingError {
+                        error,
+                        field: Some("key_field"),
+                        drop_event: false,
+                    })
+                })
+                .ok()
+        });
+
+        let probability = self.probability(key, now);
+        if thread_rng().gen::<f64>() < probability {
+            let sample_rate =
+                Value::from(NotNan::new(1.0 / probability).expect("sample rate is never NaN"));
+            match event {
+                Event::Log(ref mut event) => {
+                    event.insert(self.sample_rate_field.as_str(), sample_rate)
+                }
+                Event::Trace(ref mut event) => {
+                    event.insert(self.sample_rate_field.as_str(), sample_rate)
+                }
+                Event::Metric(_) => panic!("component can never receive metric events"),
+            };
+            output.push(event);
+        } else {
+            emit!(AdaptiveSampleEventDiscarded);
+        }
+    }
+}
+
+impl FunctionTransform for AdaptiveSample {
+    fn transform(&mut self, output: &mut OutputBuffer, event: Event) {
+        self.sample(output, event, Instant::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use tokio::sync::mpsc;
+    use tokio_stream::wrappers::ReceiverStream;
+
+    use super::*;
+    use crate::{
+        event::LogEvent, test_util::components::assert_transform_compliance,
+        transforms::test::create_topology,
+    };
+
+    fn sampler(target_rate: u64, key_field: Option<&str>) -> AdaptiveSample {
+        let config = AdaptiveSampleConfig {
+            target_rate,
+            window_secs: Duration::from_secs(10),
+            key_field: key_field.map(|key_field| Template::try_from(key_field).unwrap()),
+            sample_rate_field: default_sample_rate_field(),
+            exclude: None,
+        };
+        AdaptiveSample::new(&config, &TransformContext::default()).unwrap()
+    }
+
+    fn event(service: &str) -> Event {
+        let mut log = LogEvent::from("message");
+        log.insert("service", service);
+        log.into()
+    }
+
+    /// Sends `count` events of `service` spread evenly over `duration` starting at `start`, and
+    /// returns the forwarded ones.
+    fn send(
+        sampler: &mut AdaptiveSample,
+        service: &str,
+        count: u32,
+        start: Instant,
+        duration: Duration,
+    ) -> Vec<Event> {
+        let mut output = OutputBuffer::with_capacity(count as usize);
+        for i in 0..count {
+            sampler.sample(&mut output, event(service), start + duration / count * i);
+        }
+        output.into_events().collect()
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<AdaptiveSampleConfig>();
+    }
+
+    #[test]
+    fn forwards_everything_under_the_target_rate() {
+        let mut sampler = sampler(100, None);
+        let forwarded = send(
+            &mut sampler,
+            "quiet",
+            50,
+            Instant::now(),
+            Duration::from_secs(10),
+        );
+
+        assert_eq!(forwarded.len(), 50);
+        for event in forwarded {
+            assert_eq!(event.as_log()["sample_rate"], Value::from(1.0));
+        }
+    }
+
+    #[test]
+    fn samples_noisy_keys_towards_the_target_rate() {
+        let mut sampler = sampler(100, Some("{{ service }}"));
+        let start = Instant::now();
+        let window = Duration::from_secs(10);
+
+        // A first window brings the noisy key up to its steady rate.
+        send(&mut sampler, "noisy", 10_000, start, window);
+        let noisy = send(&mut sampler, "noisy", 10_000, start + window, window);
+        let quiet = send(&mut sampler, "quiet", 50, start + window, window);
+
+        assert_relative_eq!(noisy.len() as f64, 100.0, epsilon = 50.0);
+        assert_eq!(quiet.len(), 50);
+
+        let sample_rate = noisy.last().unwrap().as_log()["sample_rate"].clone();
+        match sample_rate {
+            Value::Float(rate) => assert_relative_eq!(rate.into_inner(), 100.0, epsilon = 10.0),
+            _ => panic!("sample rate should be a float"),
+        }
+    }
+
+    #[test]
+    fn forgets_quiet_keys() {
+        let mut sampler = sampler(100, Some("{{ service }}"));
+        let start = Instant::now();
+        let window = Duration::from_secs(10);
+
+        send(&mut sampler, "gone", 10, start, window);
+        send(&mut sampler, "other", 10, start + window * 3, window);
+
+        assert_eq!(sampler.rates.len(), 1);
+        assert!(sampler.rates.contains_key(&Some("other".to_string())));
+    }
+
+    #[test]
+    fn rejects_zero_target_rate() {
+        let config = AdaptiveSampleConfig {
+            target_rate: 0,
+            window_secs: default_window_secs(),
+            key_field: None,
+            sample_rate_field: default_sample_rate_field(),
+            exclude: None,
+        };
+        assert!(AdaptiveSample::new(&config, &TransformContext::default()).is_err());
+    }
+
+    #[tokio::test]
+    async fn emits_internal_events() {
+        assert_transform_compliance(async move {
+            let config = AdaptiveSampleConfig {
+                target_rate: 10,
+                window_secs: default_window_secs(),
+                key_field: None,
+                sample_rate_field: default_sample_rate_field(),
+                exclude: None,
+            };
+            let (tx, rx) = mpsc::channel(1);
+            let (topology, mut out) = create_topology(ReceiverStream::new(rx), config).await;
+
+            tx.send(event("service")).await.unwrap();
+
+            _ = out.recv().await;
+
+            drop(tx);
+            topology.stop().await;
+            assert_eq!(out.recv().await, None);
+        })
+        .await
+    }
+}
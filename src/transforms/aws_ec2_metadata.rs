@@ -1,29 +1,47 @@
-use std::{collections::HashSet, error, fmt, future::ready, pin::Pin, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    error, fmt,
+    future::ready,
+    io,
+    path::PathBuf,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
 
 use arc_swap::ArcSwap;
 use bytes::Bytes;
 use futures::{Stream, StreamExt};
 use http::{uri::PathAndQuery, Request, StatusCode, Uri};
 use hyper::{body::to_bytes as body_to_bytes, Body};
-use lookup::lookup_v2::{OptionalTargetPath, OwnedSegment};
+use lookup::lookup_v2::{OptionalTargetPath, OwnedSegment, OwnedValuePath};
 use lookup::owned_value_path;
 use lookup::OwnedTargetPath;
 use once_cell::sync::Lazy;
 use serde::Deserialize;
 use serde_with::serde_as;
 use snafu::ResultExt as _;
-use tokio::time::{sleep, Duration, Instant};
+use tokio::{
+    sync::{broadcast, Notify},
+    time::{sleep_until, Duration, Instant},
+};
 use tracing::Instrument;
 use value::Kind;
 use vector_config::configurable_component;
 use vector_core::config::LogNamespace;
 
 use crate::{
-    config::{DataType, Input, Output, ProxyConfig, TransformConfig, TransformContext},
+    config::{
+        ComponentKey, DataType, Input, Output, ProxyConfig, TransformConfig, TransformContext,
+    },
     event::Event,
     http::HttpClient,
-    internal_events::{AwsEc2MetadataRefreshError, AwsEc2MetadataRefreshSuccessful},
-    schema,
+    internal_events::{
+        AwsEc2MetadataCacheError, AwsEc2MetadataRefreshError, AwsEc2MetadataRefreshSuccessful,
+    },
+    schema, signal,
     transforms::{TaskTransform, Transform},
 };
 
@@ -73,6 +91,131 @@ static DEFAULT_FIELD_ALLOWLIST: &[&str] = &[
 static API_TOKEN: Lazy<PathAndQuery> = Lazy::new(|| PathAndQuery::from_static("/latest/api/token"));
 static TOKEN_HEADER: Lazy<Bytes> = Lazy::new(|| Bytes::from("X-aws-ec2-metadata-token"));
 
+const ECS_CONTAINER_METADATA_URI_ENV: &str = "ECS_CONTAINER_METADATA_URI_V4";
+
+/// The environment variables the region is read from with the `eks` strategy, in order.
+const REGION_ENVS: [&str; 2] = ["AWS_REGION", "AWS_DEFAULT_REGION"];
+
+const CACHE_FILE_NAME: &str = "metadata.json";
+
+/// The minimum time between refreshes requested by events enriched while no metadata is available.
+const MIN_REQUESTED_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The strategy used to query metadata.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Derivative, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum MetadataStrategy {
+    /// Query the EC2 instance metadata service with a session token (IMDSv2).
+    #[derivative(Default)]
+    Imdsv2,
+
+    /// Query the EC2 instance metadata service with a session token (IMDSv2), falling back to
+    /// requests without a token (IMDSv1) when a token can't be fetched.
+    Imdsv1Fallback,
+
+    /// Query the ECS task metadata endpoint, as found in the `ECS_CONTAINER_METADATA_URI_V4`
+    /// environment variable.
+    ///
+    /// Only the `account-id`, `availability-zone`, `local-hostname`, `local-ipv4`, and `region`
+    /// fields are available from this endpoint. The `endpoint` option is ignored.
+    Ecs,
+
+    /// Query the EC2 instance metadata service of the node an EKS pod runs on, as
+    /// `imdsv1_fallback` does, since the IMDSv2 token response doesn't reach pods when the hop limit
+    /// of the node is 1.
+    ///
+    /// On EKS Fargate, where there is no instance metadata service, only the `region` field is
+    /// available, from the `AWS_REGION` or `AWS_DEFAULT_REGION` environment variable, as set for
+    /// IAM roles for service accounts.
+    Eks,
+}
+
+/// When the metadata is refreshed.
+///
+/// In either mode, the metadata is also refreshed right away when the configuration is reloaded
+/// from disk, such as on `SIGHUP`, and when events are enriched while no metadata is available.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Derivative, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum RefreshMode {
+    /// Refresh the metadata every `refresh_interval_secs`.
+    #[derivative(Default)]
+    Interval,
+
+    /// Refresh the metadata only once it is older than `refresh_interval_secs`, when the next
+    /// event is enriched.
+    ///
+    /// No requests are made while no events are flowing, which allows for long refresh intervals
+    /// for metadata that rarely changes.
+    Ttl,
+}
+
+/// What to do with previously fetched metadata when refreshing it fails.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Derivative, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum RefreshErrorPolicy {
+    /// Keep adding the last successfully fetched values to events (fail open).
+    #[derivative(Default)]
+    Retain,
+
+    /// Stop adding the previously fetched values to events until a refresh succeeds again (fail
+    /// closed).
+    Remove,
+}
+
+/// How the IAM role names of the instance are tagged on metrics.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Derivative, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum RoleNameTags {
+    /// Each role name is a separate tag, suffixed with its index, such as `role-name[0]`.
+    #[derivative(Default)]
+    Indexed,
+
+    /// The role names are joined with `,` into a single `role-name` tag.
+    Joined,
+}
+
+/// Where metadata fields are added to metrics.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Derivative, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[derivative(Default)]
+pub enum MetricPlacement {
+    /// Add the field as a tag, named as configured by `metric_tags`.
+    #[derivative(Default)]
+    Tag,
+
+    /// Add the field to the event metadata of the metric, at the same path as on logs.
+    ///
+    /// Unlike tags, metadata doesn't create a new series in downstream time series databases, while
+    /// it remains available to transforms and sinks, for example as `%aws.ec2.instance-id` in VRL.
+    Metadata,
+}
+
+/// Options for naming the tags added to metrics.
+///
+/// Logs and traces are unaffected.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct MetricTagsConfig {
+    /// Whether to make tag names valid Prometheus label names.
+    ///
+    /// Characters other than ASCII letters, digits, and `_` are replaced with `_`, so that, for
+    /// example, `role-name` is tagged as `role_name`, and indexed role names as `role_name_0`.
+    sanitize_names: bool,
+
+    #[configurable(derived)]
+    role_names: RoleNameTags,
+}
+
 /// Configuration for the `aws_ec2_metadata` transform.
 #[serde_as]
 #[configurable_component(transform("aws_ec2_metadata"))]
@@ -98,6 +241,10 @@ pub struct Ec2Metadata {
     #[derivative(Default(value = "default_refresh_interval_secs()"))]
     refresh_interval_secs: Duration,
 
+    #[configurable(derived)]
+    #[serde(default)]
+    refresh_mode: RefreshMode,
+
     /// A list of metadata fields to include in each transformed event.
     #[serde(default = "default_fields")]
     #[derivative(Default(value = "default_fields()"))]
@@ -118,9 +265,58 @@ pub struct Ec2Metadata {
     proxy: ProxyConfig,
 
     /// Requires the transform to be able to successfully query the EC2 metadata before Vector can start.
+    ///
+    /// With `cache` enabled, Vector also starts when the metadata was cached by a previous run.
     #[serde(default = "default_required")]
     #[derivative(Default(value = "default_required()"))]
     required: bool,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    strategy: MetadataStrategy,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    on_refresh_error: RefreshErrorPolicy,
+
+    /// Overrides `on_refresh_error` for specific metadata fields.
+    ///
+    /// This allows, for example, dropping a stale `role-name` while retaining the last known
+    /// values of all other fields.
+    #[serde(default)]
+    on_refresh_error_fields: HashMap<String, RefreshErrorPolicy>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    metric_tags: MetricTagsConfig,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    metric_placement: MetricPlacement,
+
+    /// Overrides `metric_placement` for specific metadata fields.
+    ///
+    /// This allows, for example, keeping `region` as a tag while adding the high cardinality
+    /// `instance-id` to metric metadata only.
+    #[serde(default)]
+    metric_placement_fields: HashMap<String, MetricPlacement>,
+
+    /// Static values of metadata fields, added to events when no value has been fetched for them.
+    ///
+    /// This is the case before the first successful refresh, when `required` is `false`, and for
+    /// fields that aren't available on the instance. A `role-name` value is added as a single role
+    /// name. Only fields listed in `fields` can be given a fallback value.
+    #[serde(default)]
+    #[configurable(metadata(docs::additional_props_description = "A static metadata value."))]
+    fallback_fields: HashMap<String, String>,
+
+    /// Whether to cache the last fetched metadata in the data directory.
+    ///
+    /// The cached metadata is added to events from startup until it is fetched again, so that
+    /// events are enriched consistently after a restart even when the metadata endpoint is briefly
+    /// unavailable. The cached metadata also satisfies `required`.
+    #[serde(default)]
+    cache: bool,
 }
 
 fn default_endpoint() -> String {
@@ -149,12 +345,62 @@ const fn default_required() -> bool {
 #[derive(Clone, Debug)]
 pub struct Ec2MetadataTransform {
     state: Arc<ArcSwap<Vec<(MetadataKey, Bytes)>>>,
+    refresh_requests: Arc<RefreshRequests>,
+}
+
+/// Requests from the transform for refreshing the metadata before it is due.
+#[derive(Debug, Default)]
+struct RefreshRequests {
+    /// Set once the metadata has expired in the `ttl` refresh mode, until the next event enriched
+    /// requests a refresh.
+    expired: AtomicBool,
+    notify: Notify,
+}
+
+impl RefreshRequests {
+    /// Requests a refresh if the metadata has expired, or if there is none to add to events.
+    fn enriching(&self, missing: bool) {
+        if missing || self.expired.swap(false, Ordering::Relaxed) {
+            self.notify.notify_one();
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 struct MetadataKey {
-    log_path: OwnedTargetPath,
-    metric_tag: String,
+    field: &'static str,
+    /// The path the value is added to logs and traces at, if it is.
+    log_path: Option<OwnedTargetPath>,
+    /// The tag the value is added to metrics as, if it is.
+    metric_tag: Option<String>,
+    /// The path the value is added to the metadata of metrics at, if it is.
+    metric_metadata_path: Option<OwnedValuePath>,
+}
+
+/// The `MetricPlacement` of each metadata field.
+#[derive(Debug)]
+struct MetricPlacements {
+    default: MetricPlacement,
+    fields: HashMap<String, MetricPlacement>,
+}
+
+impl MetricPlacements {
+    fn for_field(&self, field: &str) -> MetricPlacement {
+        self.fields.get(field).copied().unwrap_or(self.default)
+    }
+}
+
+/// The `RefreshErrorPolicy` of each metadata field.
+#[derive(Debug)]
+struct RefreshErrorPolicies {
+    default: RefreshErrorPolicy,
+    fields: HashMap<String, RefreshErrorPolicy>,
+}
+
+impl RefreshErrorPolicies {
+    fn for_field(&self, field: &str) -> RefreshErrorPolicy {
+        self.fields.get(field).copied().unwrap_or(self.default)
+    }
 }
 
 #[derive(Debug)]
@@ -172,38 +418,65 @@ struct Keys {
     subnet_id_key: MetadataKey,
     vpc_id_key: MetadataKey,
     role_name_key: MetadataKey,
+    metric_tags: MetricTagsConfig,
 }
 
 impl_generate_config_from_default!(Ec2Metadata);
 
+impl Ec2Metadata {
+    fn keys(&self) -> Keys {
+        let metric_placements = MetricPlacements {
+            default: self.metric_placement,
+            fields: self.metric_placement_fields.clone(),
+        };
+        Keys::new(self.namespace.clone(), self.metric_tags, &metric_placements)
+    }
+}
+
 #[async_trait::async_trait]
 impl TransformConfig for Ec2Metadata {
     async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
         let state = Arc::new(ArcSwap::new(Arc::new(vec![])));
-
-        let keys = Keys::new(self.namespace.clone());
-        let host = Uri::from_maybe_shared(self.endpoint.clone()).unwrap();
-        let refresh_interval = self.refresh_interval_secs;
-        let fields = self.fields.clone();
-        let refresh_timeout = self.refresh_timeout_secs;
+        let refresh_requests = Arc::new(RefreshRequests::default());
         let required = self.required;
 
+        for field in self.fallback_fields.keys() {
+            if !self.fields.contains(field) {
+                return Err(Ec2MetadataError::FallbackFieldNotFetched {
+                    field: field.clone(),
+                }
+                .into());
+            }
+        }
+
+        let cache = if self.cache {
+            let id = context
+                .key
+                .as_ref()
+                .map_or("aws_ec2_metadata", ComponentKey::id);
+            let dir = context.globals.resolve_and_make_data_subdir(None, id)?;
+            Some(MetadataCache::new(dir.join(CACHE_FILE_NAME)))
+        } else {
+            None
+        };
+
         let proxy = ProxyConfig::merge_with_env(&context.globals.proxy, &self.proxy);
         let http_client = HttpClient::new(None, &proxy)?;
 
         let mut client = MetadataClient::new(
+            self,
             http_client,
-            host,
-            keys,
+            |name| std::env::var(name).ok(),
             Arc::clone(&state),
-            refresh_interval,
-            refresh_timeout,
-            fields,
-        );
+            Arc::clone(&refresh_requests),
+            cache,
+        )?;
+        let restored = client.restore().await;
 
-        // If initial metadata is not required, log and proceed. Otherwise return error.
+        // If initial metadata is not required, or was cached, log and proceed. Otherwise return
+        // error.
         if let Err(error) = client.refresh_metadata().await {
-            if required {
+            if required && !restored {
                 return Err(error);
             } else {
                 emit!(AwsEc2MetadataRefreshError { error });
@@ -218,15 +491,18 @@ impl TransformConfig for Ec2Metadata {
             .instrument(info_span!("aws_ec2_metadata: worker").or_current()),
         );
 
-        Ok(Transform::event_task(Ec2MetadataTransform { state }))
+        Ok(Transform::event_task(Ec2MetadataTransform {
+            state,
+            refresh_requests,
+        }))
     }
 
     fn input(&self) -> Input {
-        Input::new(DataType::Metric | DataType::Log)
+        Input::new(DataType::all())
     }
 
     fn outputs(&self, merged_definition: &schema::Definition, _: LogNamespace) -> Vec<Output> {
-        let added_keys = Keys::new(self.namespace.clone());
+        let added_keys = self.keys();
 
         let paths = [
             &added_keys.account_id_key.log_path,
@@ -246,13 +522,12 @@ impl TransformConfig for Ec2Metadata {
 
         let mut schema_definition = merged_definition.clone();
 
-        for path in paths {
+        for path in paths.into_iter().flatten() {
             schema_definition =
                 schema_definition.with_field(path, Kind::bytes().or_undefined(), None);
         }
 
-        vec![Output::default(DataType::Metric | DataType::Log)
-            .with_schema_definition(schema_definition)]
+        vec![Output::default(DataType::all()).with_schema_definition(schema_definition)]
     }
 }
 
@@ -272,19 +547,39 @@ impl TaskTransform<Event> for Ec2MetadataTransform {
 impl Ec2MetadataTransform {
     fn transform_one(&mut self, mut event: Event) -> Event {
         let state = self.state.load();
+        self.refresh_requests.enriching(state.is_empty());
         match event {
             Event::Log(ref mut log) => {
                 state.iter().for_each(|(k, v)| {
-                    log.insert(&k.log_path, v.clone());
+                    if let Some(log_path) = &k.log_path {
+                        log.insert(log_path, v.clone());
+                    }
                 });
             }
             Event::Metric(ref mut metric) => {
                 state.iter().for_each(|(k, v)| {
-                    metric
-                        .replace_tag(k.metric_tag.clone(), String::from_utf8_lossy(v).to_string());
+                    if let Some(metric_tag) = &k.metric_tag {
+                        metric.replace_tag(
+                            metric_tag.clone(),
+                            String::from_utf8_lossy(v).to_string(),
+                        );
+                    }
+                    if let Some(metadata_path) = &k.metric_metadata_path {
+                        metric
+                            .metadata_mut()
+                            .value_mut()
+                            .insert(metadata_path, v.clone());
+                    }
+                });
+            }
+            Event::Trace(ref mut trace) => {
+                let trace = trace.as_mut();
+                state.iter().for_each(|(k, v)| {
+                    if let Some(log_path) = &k.log_path {
+                        trace.insert(log_path, v.clone());
+                    }
                 });
             }
-            Event::Trace(_) => panic!("Traces are not supported."),
         }
         event
     }
@@ -294,11 +589,24 @@ struct MetadataClient {
     client: HttpClient<Body>,
     host: Uri,
     token: Option<(Bytes, Instant)>,
+    strategy: MetadataStrategy,
+    // Set for the duration of a refresh when falling back to IMDSv1.
+    without_token: bool,
     keys: Keys,
     state: Arc<ArcSwap<Vec<(MetadataKey, Bytes)>>>,
     refresh_interval: Duration,
+    refresh_mode: RefreshMode,
+    refresh_requests: Arc<RefreshRequests>,
+    last_refresh: Instant,
     refresh_timeout: Duration,
     fields: HashSet<String>,
+    refresh_error_policies: RefreshErrorPolicies,
+    // The values of the fields, in the order they were fetched, before fallbacks are added.
+    values: Vec<(&'static str, Bytes)>,
+    fallbacks: Vec<(MetadataKey, Bytes)>,
+    cache: Option<MetadataCache>,
+    // The region used with the `eks` strategy when there is no instance metadata service.
+    environment_region: Option<Bytes>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -315,40 +623,206 @@ struct IdentityDocument {
     version: String,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct EcsTaskMetadata {
+    #[serde(rename = "TaskARN")]
+    task_arn: String,
+    availability_zone: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct EcsContainerMetadata {
+    #[serde(default)]
+    networks: Vec<EcsContainerNetwork>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EcsContainerNetwork {
+    #[serde(rename = "IPv4Addresses", default)]
+    ipv4_addresses: Vec<String>,
+    #[serde(rename = "PrivateDNSName")]
+    private_dns_name: Option<String>,
+}
+
 impl MetadataClient {
+    /// Creates a client fetching the metadata configured by `config` into `state`.
+    ///
+    /// The environment variables the `ecs` and `eks` strategies depend on are read with `env_var`.
     pub fn new(
+        config: &Ec2Metadata,
         client: HttpClient<Body>,
-        host: Uri,
-        keys: Keys,
+        env_var: impl Fn(&str) -> Option<String>,
         state: Arc<ArcSwap<Vec<(MetadataKey, Bytes)>>>,
-        refresh_interval: Duration,
-        refresh_timeout: Duration,
-        fields: Vec<String>,
-    ) -> Self {
-        Self {
+        refresh_requests: Arc<RefreshRequests>,
+        cache: Option<MetadataCache>,
+    ) -> crate::Result<Self> {
+        let host = match config.strategy {
+            MetadataStrategy::Ecs => env_var(ECS_CONTAINER_METADATA_URI_ENV)
+                .ok_or(Ec2MetadataError::MissingEcsMetadataUri)?
+                .parse::<Uri>()?,
+            _ => config
+                .endpoint
+                .parse::<Uri>()
+                .with_context(|_| ParseEndpointSnafu {
+                    value: config.endpoint.clone(),
+                })?,
+        };
+        let environment_region = match config.strategy {
+            MetadataStrategy::Eks => REGION_ENVS
+                .iter()
+                .find_map(|name| env_var(name))
+                .map(Bytes::from),
+            _ => None,
+        };
+
+        let keys = config.keys();
+        let fallbacks = keys.state(
+            config
+                .fallback_fields
+                .iter()
+                .map(|(field, value)| (field.as_str(), Bytes::from(value.clone()))),
+        );
+
+        Ok(Self {
             client,
             host,
             token: None,
+            strategy: config.strategy,
+            without_token: false,
             keys,
             state,
-            refresh_interval,
-            refresh_timeout,
-            fields: fields.into_iter().collect(),
+            refresh_interval: config.refresh_interval_secs,
+            refresh_mode: config.refresh_mode,
+            refresh_requests,
+            last_refresh: Instant::now(),
+            refresh_timeout: config.refresh_timeout_secs,
+            fields: config.fields.iter().cloned().collect(),
+            refresh_error_policies: RefreshErrorPolicies {
+                default: config.on_refresh_error,
+                fields: config.on_refresh_error_fields.clone(),
+            },
+            values: vec![],
+            fallbacks,
+            cache,
+            environment_region,
+        })
+    }
+
+    /// Adds the fallback values, and the cached metadata of the fields still fetched, to events
+    /// until the metadata is fetched. Returns whether any cached metadata was restored.
+    async fn restore(&mut self) -> bool {
+        let cached = match &self.cache {
+            Some(cache) => cache.load().await,
+            None => None,
+        };
+        let values = cached
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(field, _)| self.fields.contains(field))
+            .filter_map(|(field, value)| Some((self.keys.get(&field)?.field, value)))
+            .collect::<Vec<_>>();
+        let restored = !values.is_empty();
+        self.store(values);
+        restored
+    }
+
+    /// Stores freshly fetched `values`, caching them if they changed.
+    async fn fetched(&mut self, values: Vec<(&'static str, Bytes)>) {
+        if let Some(cache) = &self.cache {
+            if values != self.values {
+                cache.store(&values).await;
+            }
         }
+        self.store(values);
+    }
+
+    /// Stores `values` as the state added to events, along with the fallback values of the fields
+    /// they don't have a value for.
+    fn store(&mut self, values: Vec<(&'static str, Bytes)>) {
+        let mut state = self
+            .keys
+            .state(values.iter().map(|(field, value)| (*field, value.clone())));
+        let fields = values
+            .iter()
+            .map(|(field, _)| *field)
+            .collect::<HashSet<_>>();
+        state.extend(
+            self.fallbacks
+                .iter()
+                .filter(|(key, _)| !fields.contains(key.field))
+                .cloned(),
+        );
+        self.values = values;
+        self.state.store(Arc::new(state));
     }
 
     async fn run(&mut self) {
+        let mut reload_requests = signal::subscribe_to_reload_requests();
         loop {
+            self.wait_for_refresh(&mut reload_requests).await;
+
+            self.last_refresh = Instant::now();
+            self.refresh_requests
+                .expired
+                .store(false, Ordering::Relaxed);
             match self.refresh_metadata().await {
                 Ok(_) => {
                     emit!(AwsEc2MetadataRefreshSuccessful);
                 }
                 Err(error) => {
                     emit!(AwsEc2MetadataRefreshError { error });
+                    self.apply_refresh_error_policies();
                 }
             }
+        }
+    }
+
+    /// Waits until the metadata is due to be refreshed, or a refresh is requested.
+    async fn wait_for_refresh(&self, reload_requests: &mut broadcast::Receiver<()>) {
+        let expiry = sleep_until(self.last_refresh + self.refresh_interval);
+        tokio::pin!(expiry);
+        let mut expired = false;
+
+        loop {
+            tokio::select! {
+                _ = &mut expiry, if !expired => match self.refresh_mode {
+                    RefreshMode::Interval => return,
+                    RefreshMode::Ttl => {
+                        expired = true;
+                        self.refresh_requests.expired.store(true, Ordering::Relaxed);
+                    }
+                },
+                _ = reload_requests.recv() => {
+                    debug!(message = "Refreshing metadata as a configuration reload was requested.");
+                    return;
+                },
+                _ = self.refresh_requests.notify.notified() => {
+                    sleep_until(self.last_refresh + MIN_REQUESTED_REFRESH_INTERVAL).await;
+                    return;
+                },
+            }
+        }
+    }
 
-            sleep(self.refresh_interval).await;
+    /// Removes the previously fetched values of the fields that fail closed.
+    fn apply_refresh_error_policies(&mut self) {
+        let retained = self
+            .values
+            .iter()
+            .filter(|(field, _)| {
+                self.refresh_error_policies.for_field(field) == RefreshErrorPolicy::Retain
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if retained.len() != self.values.len() {
+            debug!(
+                message = "Removed previously fetched metadata after a failed refresh.",
+                removed = self.values.len() - retained.len()
+            );
+            self.store(retained);
         }
     }
 
@@ -401,63 +875,105 @@ impl MetadataClient {
     }
 
     pub async fn refresh_metadata(&mut self) -> Result<(), crate::Error> {
-        let mut new_state = vec![];
+        match self.strategy {
+            MetadataStrategy::Imdsv2 => self.refresh_instance_metadata().await,
+            MetadataStrategy::Imdsv1Fallback => {
+                self.check_token().await;
+                self.refresh_instance_metadata().await
+            }
+            MetadataStrategy::Eks => {
+                self.check_token().await;
+                match (
+                    self.refresh_instance_metadata().await,
+                    self.environment_region.clone(),
+                ) {
+                    // There is no instance metadata service on EKS Fargate.
+                    (Err(error), Some(region)) => {
+                        debug!(
+                            message = "Unable to fetch instance metadata, using the region of the environment.",
+                            %error
+                        );
+                        let mut values = vec![];
+                        if self.fields.contains(REGION_KEY) {
+                            values.push((REGION_KEY, region));
+                        }
+                        self.fetched(values).await;
+                        Ok(())
+                    }
+                    (result, _) => result,
+                }
+            }
+            MetadataStrategy::Ecs => self.refresh_ecs_metadata().await,
+        }
+    }
+
+    /// Checks once per refresh whether a token can be fetched, falling back to IMDSv1 if not,
+    /// rather than waiting on a failing token request for every metadata path.
+    async fn check_token(&mut self) {
+        self.without_token = match self.get_token().await {
+            Ok(_) => false,
+            Err(error) => {
+                debug!(
+                    message = "Unable to fetch metadata token, falling back to IMDSv1.",
+                    %error
+                );
+                true
+            }
+        };
+    }
+
+    async fn refresh_instance_metadata(&mut self) -> Result<(), crate::Error> {
+        let mut values = vec![];
 
         // Fetch all resources, _then_ add them to the state map.
         if let Some(document) = self.get_document().await? {
             if self.fields.contains(ACCOUNT_ID_KEY) {
-                new_state.push((self.keys.account_id_key.clone(), document.account_id.into()));
+                values.push((ACCOUNT_ID_KEY, document.account_id.into()));
             }
 
             if self.fields.contains(AMI_ID_KEY) {
-                new_state.push((self.keys.ami_id_key.clone(), document.image_id.into()));
+                values.push((AMI_ID_KEY, document.image_id.into()));
             }
 
             if self.fields.contains(INSTANCE_ID_KEY) {
-                new_state.push((
-                    self.keys.instance_id_key.clone(),
-                    document.instance_id.into(),
-                ));
+                values.push((INSTANCE_ID_KEY, document.instance_id.into()));
             }
 
             if self.fields.contains(INSTANCE_TYPE_KEY) {
-                new_state.push((
-                    self.keys.instance_type_key.clone(),
-                    document.instance_type.into(),
-                ));
+                values.push((INSTANCE_TYPE_KEY, document.instance_type.into()));
             }
 
             if self.fields.contains(REGION_KEY) {
-                new_state.push((self.keys.region_key.clone(), document.region.into()));
+                values.push((REGION_KEY, document.region.into()));
             }
 
             if self.fields.contains(AVAILABILITY_ZONE_KEY) {
                 if let Some(availability_zone) = self.get_metadata(&AVAILABILITY_ZONE).await? {
-                    new_state.push((self.keys.availability_zone_key.clone(), availability_zone));
+                    values.push((AVAILABILITY_ZONE_KEY, availability_zone));
                 }
             }
 
             if self.fields.contains(LOCAL_HOSTNAME_KEY) {
                 if let Some(local_hostname) = self.get_metadata(&LOCAL_HOSTNAME).await? {
-                    new_state.push((self.keys.local_hostname_key.clone(), local_hostname));
+                    values.push((LOCAL_HOSTNAME_KEY, local_hostname));
                 }
             }
 
             if self.fields.contains(LOCAL_IPV4_KEY) {
                 if let Some(local_ipv4) = self.get_metadata(&LOCAL_IPV4).await? {
-                    new_state.push((self.keys.local_ipv4_key.clone(), local_ipv4));
+                    values.push((LOCAL_IPV4_KEY, local_ipv4));
                 }
             }
 
             if self.fields.contains(PUBLIC_HOSTNAME_KEY) {
                 if let Some(public_hostname) = self.get_metadata(&PUBLIC_HOSTNAME).await? {
-                    new_state.push((self.keys.public_hostname_key.clone(), public_hostname));
+                    values.push((PUBLIC_HOSTNAME_KEY, public_hostname));
                 }
             }
 
             if self.fields.contains(PUBLIC_IPV4_KEY) {
                 if let Some(public_ipv4) = self.get_metadata(&PUBLIC_IPV4).await? {
-                    new_state.push((self.keys.public_ipv4_key.clone(), public_ipv4));
+                    values.push((PUBLIC_IPV4_KEY, public_ipv4));
                 }
             }
 
@@ -476,7 +992,7 @@ impl MetadataClient {
                         })?;
 
                         if let Some(subnet_id) = self.get_metadata(&subnet_path).await? {
-                            new_state.push((self.keys.subnet_id_key.clone(), subnet_id));
+                            values.push((SUBNET_ID_KEY, subnet_id));
                         }
                     }
 
@@ -489,7 +1005,7 @@ impl MetadataClient {
                         })?;
 
                         if let Some(vpc_id) = self.get_metadata(&vpc_path).await? {
-                            new_state.push((self.keys.vpc_id_key.clone(), vpc_id));
+                            values.push((VPC_ID_KEY, vpc_id));
                         }
                     }
                 }
@@ -498,37 +1014,109 @@ impl MetadataClient {
             if self.fields.contains(ROLE_NAME_KEY) {
                 if let Some(role_names) = self.get_metadata(&ROLE_NAME).await? {
                     let role_names = String::from_utf8_lossy(&role_names[..]);
+                    values.extend(role_names.lines().map(|role_name| {
+                        (ROLE_NAME_KEY, Bytes::copy_from_slice(role_name.as_bytes()))
+                    }));
+                }
+            }
 
-                    for (i, role_name) in role_names.lines().enumerate() {
-                        new_state.push((
-                            MetadataKey {
-                                log_path: self
-                                    .keys
-                                    .role_name_key
-                                    .log_path
-                                    .with_index_appended(i as isize),
-                                metric_tag: format!(
-                                    "{}[{}]",
-                                    self.keys.role_name_key.metric_tag, i
-                                ),
-                            },
-                            role_name.to_string().into(),
-                        ));
-                    }
+            self.fetched(values).await;
+        }
+
+        Ok(())
+    }
+
+    async fn refresh_ecs_metadata(&mut self) -> Result<(), crate::Error> {
+        let task: EcsTaskMetadata = self.get_ecs_metadata("/task").await?;
+        let container: EcsContainerMetadata = self.get_ecs_metadata("").await?;
+
+        let mut values = vec![];
+
+        // The task ARN is in the form `arn:aws:ecs:<region>:<account-id>:task/...`.
+        let mut arn = task.task_arn.split(':').skip(3);
+        let region = arn.next().filter(|region| !region.is_empty());
+        let account_id = arn.next().filter(|account_id| !account_id.is_empty());
+
+        if self.fields.contains(ACCOUNT_ID_KEY) {
+            if let Some(account_id) = account_id {
+                values.push((
+                    ACCOUNT_ID_KEY,
+                    Bytes::copy_from_slice(account_id.as_bytes()),
+                ));
+            }
+        }
+
+        if self.fields.contains(REGION_KEY) {
+            if let Some(region) = region {
+                values.push((REGION_KEY, Bytes::copy_from_slice(region.as_bytes())));
+            }
+        }
+
+        if self.fields.contains(AVAILABILITY_ZONE_KEY) {
+            if let Some(availability_zone) = task.availability_zone {
+                values.push((AVAILABILITY_ZONE_KEY, availability_zone.into()));
+            }
+        }
+
+        if let Some(network) = container.networks.into_iter().next() {
+            if self.fields.contains(LOCAL_IPV4_KEY) {
+                if let Some(local_ipv4) = network.ipv4_addresses.into_iter().next() {
+                    values.push((LOCAL_IPV4_KEY, local_ipv4.into()));
                 }
             }
 
-            self.state.store(Arc::new(new_state));
+            if self.fields.contains(LOCAL_HOSTNAME_KEY) {
+                if let Some(local_hostname) = network.private_dns_name {
+                    values.push((LOCAL_HOSTNAME_KEY, local_hostname.into()));
+                }
+            }
         }
 
+        self.fetched(values).await;
+
         Ok(())
     }
 
+    async fn get_ecs_metadata<T: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+    ) -> Result<T, crate::Error> {
+        let uri = format!("{}{}", self.host.to_string().trim_end_matches('/'), path);
+        let uri = uri
+            .parse::<Uri>()
+            .context(ParsePathSnafu { value: uri.clone() })?;
+
+        debug!(message = "Sending metadata request.", %uri);
+
+        let req = Request::get(uri).body(Body::empty())?;
+
+        let res = tokio::time::timeout(self.refresh_timeout, self.client.send(req))
+            .await?
+            .map_err(crate::Error::from)
+            .and_then(|res| match res.status() {
+                StatusCode::OK => Ok(res),
+                status_code => Err(UnexpectedHttpStatusError {
+                    status: status_code,
+                }
+                .into()),
+            })?;
+
+        let body = body_to_bytes(res.into_body()).await?;
+        serde_json::from_slice(&body[..])
+            .context(ParseEcsMetadataSnafu {})
+            .map_err(Into::into)
+    }
+
     async fn get_metadata(&mut self, path: &PathAndQuery) -> Result<Option<Bytes>, crate::Error> {
-        let token = self
-            .get_token()
-            .await
-            .with_context(|_| FetchTokenSnafu {})?;
+        let token = if self.without_token {
+            None
+        } else {
+            Some(
+                self.get_token()
+                    .await
+                    .with_context(|_| FetchTokenSnafu {})?,
+            )
+        };
 
         let mut parts = self.host.clone().into_parts();
 
@@ -538,9 +1126,11 @@ impl MetadataClient {
 
         debug!(message = "Sending metadata request.", %uri);
 
-        let req = Request::get(uri)
-            .header(TOKEN_HEADER.as_ref(), token.as_ref())
-            .body(Body::empty())?;
+        let mut req = Request::get(uri);
+        if let Some(token) = token {
+            req = req.header(TOKEN_HEADER.as_ref(), token.as_ref());
+        }
+        let req = req.body(Body::empty())?;
 
         match tokio::time::timeout(self.refresh_timeout, self.client.send(req))
             .await?
@@ -589,38 +1179,243 @@ fn create_metric_namespace(namespace: &OwnedTargetPath) -> String {
     output
 }
 
-fn create_key(namespace: &Option<OwnedTargetPath>, key: &str) -> MetadataKey {
-    if let Some(namespace) = namespace {
-        MetadataKey {
-            log_path: namespace.with_field_appended(key),
-            metric_tag: format!("{}.{}", create_metric_namespace(namespace), key),
-        }
+/// Replaces the characters that aren't allowed in Prometheus label names with `_`.
+fn sanitize_tag_name(name: &str) -> String {
+    let mut sanitized = name
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' => c,
+            _ => '_',
+        })
+        .collect::<String>();
+    if sanitized.starts_with(|c: char| c.is_ascii_digit()) {
+        sanitized.insert(0, '_');
+    }
+    sanitized
+}
+
+fn create_key(
+    namespace: &Option<OwnedTargetPath>,
+    key: &'static str,
+    metric_tags: MetricTagsConfig,
+    metric_placement: MetricPlacement,
+) -> MetadataKey {
+    let (log_path, metric_tag) = if let Some(namespace) = namespace {
+        (
+            namespace.with_field_appended(key),
+            format!("{}.{}", create_metric_namespace(namespace), key),
+        )
     } else {
-        MetadataKey {
-            log_path: OwnedTargetPath::event(owned_value_path!(key)),
-            metric_tag: key.to_owned(),
-        }
+        (
+            OwnedTargetPath::event(owned_value_path!(key)),
+            key.to_owned(),
+        )
+    };
+    let metric_tag = if metric_tags.sanitize_names {
+        sanitize_tag_name(&metric_tag)
+    } else {
+        metric_tag
+    };
+
+    let (metric_tag, metric_metadata_path) = match metric_placement {
+        MetricPlacement::Tag => (Some(metric_tag), None),
+        MetricPlacement::Metadata => (None, Some(log_path.path.clone())),
+    };
+
+    MetadataKey {
+        field: key,
+        log_path: Some(log_path),
+        metric_tag,
+        metric_metadata_path,
     }
 }
 
 impl Keys {
-    pub fn new(namespace: Option<OptionalTargetPath>) -> Self {
+    fn new(
+        namespace: Option<OptionalTargetPath>,
+        metric_tags: MetricTagsConfig,
+        metric_placements: &MetricPlacements,
+    ) -> Self {
         let namespace = namespace.and_then(|namespace| namespace.path);
+        let key = |name| {
+            create_key(
+                &namespace,
+                name,
+                metric_tags,
+                metric_placements.for_field(name),
+            )
+        };
 
         Keys {
-            account_id_key: create_key(&namespace, ACCOUNT_ID_KEY),
-            ami_id_key: create_key(&namespace, AMI_ID_KEY),
-            availability_zone_key: create_key(&namespace, AVAILABILITY_ZONE_KEY),
-            instance_id_key: create_key(&namespace, INSTANCE_ID_KEY),
-            instance_type_key: create_key(&namespace, INSTANCE_TYPE_KEY),
-            local_hostname_key: create_key(&namespace, LOCAL_HOSTNAME_KEY),
-            local_ipv4_key: create_key(&namespace, LOCAL_IPV4_KEY),
-            public_hostname_key: create_key(&namespace, PUBLIC_HOSTNAME_KEY),
-            public_ipv4_key: create_key(&namespace, PUBLIC_IPV4_KEY),
-            region_key: create_key(&namespace, REGION_KEY),
-            subnet_id_key: create_key(&namespace, SUBNET_ID_KEY),
-            vpc_id_key: create_key(&namespace, VPC_ID_KEY),
-            role_name_key: create_key(&namespace, ROLE_NAME_KEY),
+            account_id_key: key(ACCOUNT_ID_KEY),
+            ami_id_key: key(AMI_ID_KEY),
+            availability_zone_key: key(AVAILABILITY_ZONE_KEY),
+            instance_id_key: key(INSTANCE_ID_KEY),
+            instance_type_key: key(INSTANCE_TYPE_KEY),
+            local_hostname_key: key(LOCAL_HOSTNAME_KEY),
+            local_ipv4_key: key(LOCAL_IPV4_KEY),
+            public_hostname_key: key(PUBLIC_HOSTNAME_KEY),
+            public_ipv4_key: key(PUBLIC_IPV4_KEY),
+            region_key: key(REGION_KEY),
+            subnet_id_key: key(SUBNET_ID_KEY),
+            vpc_id_key: key(VPC_ID_KEY),
+            role_name_key: key(ROLE_NAME_KEY),
+            metric_tags,
+        }
+    }
+
+    /// Returns the key of the metadata field named `field`.
+    fn get(&self, field: &str) -> Option<&MetadataKey> {
+        match field {
+            ACCOUNT_ID_KEY => Some(&self.account_id_key),
+            AMI_ID_KEY => Some(&self.ami_id_key),
+            AVAILABILITY_ZONE_KEY => Some(&self.availability_zone_key),
+            INSTANCE_ID_KEY => Some(&self.instance_id_key),
+            INSTANCE_TYPE_KEY => Some(&self.instance_type_key),
+            LOCAL_HOSTNAME_KEY => Some(&self.local_hostname_key),
+            LOCAL_IPV4_KEY => Some(&self.local_ipv4_key),
+            PUBLIC_HOSTNAME_KEY => Some(&self.public_hostname_key),
+            PUBLIC_IPV4_KEY => Some(&self.public_ipv4_key),
+            REGION_KEY => Some(&self.region_key),
+            SUBNET_ID_KEY => Some(&self.subnet_id_key),
+            VPC_ID_KEY => Some(&self.vpc_id_key),
+            ROLE_NAME_KEY => Some(&self.role_name_key),
+            _ => None,
+        }
+    }
+
+    /// Returns the state adding the values of metadata fields to events.
+    ///
+    /// Each role name is a separate value of the `role-name` field. Values of unknown fields are
+    /// ignored.
+    fn state<'a>(
+        &self,
+        values: impl IntoIterator<Item = (&'a str, Bytes)>,
+    ) -> Vec<(MetadataKey, Bytes)> {
+        let mut state = vec![];
+        let mut role_names = vec![];
+        for (field, value) in values {
+            if field == ROLE_NAME_KEY {
+                role_names.push(value);
+            } else if let Some(key) = self.get(field) {
+                state.push((key.clone(), value));
+            }
+        }
+
+        let role_name_key = &self.role_name_key;
+        let metric_tags = self.metric_tags;
+        for (i, role_name) in role_names.iter().enumerate() {
+            state.push((
+                MetadataKey {
+                    field: ROLE_NAME_KEY,
+                    log_path: role_name_key
+                        .log_path
+                        .as_ref()
+                        .map(|log_path| log_path.with_index_appended(i as isize)),
+                    metric_tag: match metric_tags.role_names {
+                        RoleNameTags::Indexed => {
+                            role_name_key.metric_tag.as_ref().map(|metric_tag| {
+                                if metric_tags.sanitize_names {
+                                    format!("{}_{}", metric_tag, i)
+                                } else {
+                                    format!("{}[{}]", metric_tag, i)
+                                }
+                            })
+                        }
+                        RoleNameTags::Joined => None,
+                    },
+                    metric_metadata_path: role_name_key
+                        .metric_metadata_path
+                        .as_ref()
+                        .map(|path| path.with_index_appended(i as isize)),
+                },
+                role_name.clone(),
+            ));
+        }
+
+        if metric_tags.role_names == RoleNameTags::Joined && !role_names.is_empty() {
+            let joined = role_names
+                .iter()
+                .map(|role_name| String::from_utf8_lossy(role_name))
+                .collect::<Vec<_>>()
+                .join(",");
+            state.push((
+                MetadataKey {
+                    field: ROLE_NAME_KEY,
+                    log_path: None,
+                    metric_tag: role_name_key.metric_tag.clone(),
+                    metric_metadata_path: None,
+                },
+                joined.into(),
+            ));
+        }
+
+        state
+    }
+}
+
+/// The last fetched metadata, kept in a file so that it can be added to events from startup.
+struct MetadataCache {
+    path: PathBuf,
+}
+
+impl MetadataCache {
+    const fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Returns the cached values of metadata fields, if any.
+    async fn load(&self) -> Option<Vec<(String, Bytes)>> {
+        let contents = match tokio::fs::read(&self.path).await {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return None,
+            Err(error) => {
+                emit!(AwsEc2MetadataCacheError {
+                    error: error.into(),
+                    path: &self.path,
+                });
+                return None;
+            }
+        };
+        let values: Vec<(String, String)> = match serde_json::from_slice(&contents) {
+            Ok(values) => values,
+            Err(error) => {
+                emit!(AwsEc2MetadataCacheError {
+                    error: error.into(),
+                    path: &self.path,
+                });
+                return None;
+            }
+        };
+        debug!(message = "Loaded cached metadata.", path = ?self.path);
+        Some(
+            values
+                .into_iter()
+                .map(|(field, value)| (field, value.into()))
+                .collect(),
+        )
+    }
+
+    /// Replaces the cached values of metadata fields with `values`.
+    async fn store(&self, values: &[(&'static str, Bytes)]) {
+        let values = values
+            .iter()
+            .map(|(field, value)| (*field, String::from_utf8_lossy(value)))
+            .collect::<Vec<_>>();
+        // Writing to a temporary file first keeps a crash from leaving a truncated cache behind.
+        let temp_path = self.path.with_extension("tmp");
+        let result = async {
+            let contents = serde_json::to_vec(&values)?;
+            tokio::fs::write(&temp_path, contents).await?;
+            tokio::fs::rename(&temp_path, &self.path).await?;
+            Ok::<_, crate::Error>(())
+        }
+        .await;
+        if let Err(error) = result {
+            emit!(AwsEc2MetadataCacheError {
+                error,
+                path: &self.path,
+            });
         }
     }
 }
@@ -644,6 +1439,23 @@ enum Ec2MetadataError {
     FetchToken { source: crate::Error },
     #[snafu(display("Unable to parse identity document: {}.", source))]
     ParseIdentityDocument { source: serde_json::Error },
+    #[snafu(display("Unable to parse ECS task metadata: {}.", source))]
+    ParseEcsMetadata { source: serde_json::Error },
+    #[snafu(display(
+        "The {} environment variable must be set to use the ecs metadata strategy.",
+        ECS_CONTAINER_METADATA_URI_ENV
+    ))]
+    MissingEcsMetadataUri,
+    #[snafu(display(
+        "`fallback_fields` has a value for {:?}, which isn't one of `fields`.",
+        field
+    ))]
+    FallbackFieldNotFetched { field: String },
+    #[snafu(display("Unable to parse metadata endpoint {}, {}.", value, source))]
+    ParseEndpoint {
+        value: String,
+        source: http::uri::InvalidUri,
+    },
     #[snafu(display("Unable to parse metadata path {}, {}.", value, source))]
     ParsePath {
         value: String,
@@ -656,17 +1468,46 @@ enum Ec2MetadataError {
 mod integration_tests {
     use lookup::lookup_v2::{OwnedSegment, OwnedValuePath};
     use lookup::{event_path, PathPrefix};
-    use tokio::sync::mpsc;
+    use tokio::{sync::mpsc, time::sleep};
     use tokio_stream::wrappers::ReceiverStream;
 
     use super::*;
     use crate::{
-        event::{metric, LogEvent, Metric},
+        config::GlobalOptions,
+        event::{metric, EventArray, LogEvent, Metric, TraceEvent},
         test_util::{components::assert_transform_compliance, next_addr},
         transforms::test::create_topology,
     };
     use warp::Filter;
 
+    /// Fetches the metadata configured by `config` as if the environment variables of the process
+    /// were `environment`, and returns an empty log event enriched with it.
+    async fn enrich_with_environment(
+        config: &Ec2Metadata,
+        environment: HashMap<&str, String>,
+    ) -> LogEvent {
+        let state = Arc::new(ArcSwap::new(Arc::new(vec![])));
+        let refresh_requests = Arc::new(RefreshRequests::default());
+        let mut client = MetadataClient::new(
+            config,
+            HttpClient::new(None, &ProxyConfig::default()).unwrap(),
+            |name| environment.get(name).cloned(),
+            Arc::clone(&state),
+            Arc::clone(&refresh_requests),
+            None,
+        )
+        .unwrap();
+        client.refresh_metadata().await.unwrap();
+
+        let mut transform = Ec2MetadataTransform {
+            state,
+            refresh_requests,
+        };
+        transform
+            .transform_one(LogEvent::default().into())
+            .into_log()
+    }
+
     fn ec2_metadata_address() -> String {
         std::env::var("EC2_METADATA_ADDRESS").unwrap_or_else(|_| "http://localhost:8111".into())
     }
@@ -787,6 +1628,43 @@ mod integration_tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn enrich_trace() {
+        assert_transform_compliance(async {
+            let mut fields = default_fields();
+            fields.extend(vec![String::from(ACCOUNT_ID_KEY)].into_iter());
+
+            let transform_config = Ec2Metadata {
+                endpoint: ec2_metadata_address(),
+                fields,
+                ..Default::default()
+            };
+
+            let (tx, rx) = mpsc::channel(1);
+            let (topology, mut out) =
+                create_topology(ReceiverStream::new(rx), transform_config).await;
+
+            // We need to sleep to let the background task fetch the data.
+            sleep(Duration::from_secs(1)).await;
+
+            let trace = TraceEvent::default();
+            let mut expected_trace = trace.clone();
+            for (k, v) in expected_log_fields().iter().cloned() {
+                expected_trace.as_mut().insert((PathPrefix::Event, &k), v);
+            }
+
+            tx.send(trace.into()).await.unwrap();
+
+            let event = out.recv().await.unwrap();
+            assert_eq!(event.into_trace(), expected_trace);
+
+            drop(tx);
+            topology.stop().await;
+            assert_eq!(out.recv().await, None);
+        })
+        .await;
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn timeout() {
         let addr = next_addr();
@@ -844,6 +1722,257 @@ mod integration_tests {
         );
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn imdsv1_fallback() {
+        let addr = next_addr();
+
+        let token = warp::put()
+            .and(warp::path!("latest" / "api" / "token"))
+            .map(|| warp::reply::with_status("", warp::http::StatusCode::FORBIDDEN));
+        let document = warp::get()
+            .and(warp::path!(
+                "latest" / "dynamic" / "instance-identity" / "document"
+            ))
+            .and(warp::header::optional::<String>("X-aws-ec2-metadata-token"))
+            .map(|token: Option<String>| {
+                assert!(token.is_none());
+                r#"{
+                    "accountId": "071959437513",
+                    "architecture": "x86_64",
+                    "imageId": "ami-05f27d4d6770a43d2",
+                    "instanceId": "i-096fba6d03d36d262",
+                    "instanceType": "t2.micro",
+                    "privateIp": "192.1.1.2",
+                    "region": "us-east-1",
+                    "version": "2017-09-30"
+                }"#
+            });
+        let _server = tokio::spawn(warp::serve(token.or(document)).bind(addr));
+
+        let config = Ec2Metadata {
+            endpoint: format!("http://{}", addr),
+            fields: vec![INSTANCE_ID_KEY.into(), REGION_KEY.into()],
+            strategy: MetadataStrategy::Imdsv1Fallback,
+            ..Default::default()
+        };
+
+        let (tx, rx) = mpsc::channel(1);
+        let (topology, mut out) = create_topology(ReceiverStream::new(rx), config).await;
+
+        tx.send(LogEvent::default().into()).await.unwrap();
+
+        let log = out.recv().await.unwrap().into_log();
+        assert_eq!(
+            log.get(event_path!(INSTANCE_ID_KEY)),
+            Some(&"i-096fba6d03d36d262".into())
+        );
+        assert_eq!(log.get(event_path!(REGION_KEY)), Some(&"us-east-1".into()));
+
+        drop(tx);
+        topology.stop().await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn on_refresh_error_policies() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let addr = next_addr();
+
+        let token = warp::put()
+            .and(warp::path!("latest" / "api" / "token"))
+            .map(|| "token");
+        let failing = Arc::new(AtomicBool::new(false));
+        let document = warp::get()
+            .and(warp::path!(
+                "latest" / "dynamic" / "instance-identity" / "document"
+            ))
+            .map({
+                let failing = Arc::clone(&failing);
+                move || {
+                    let status = if failing.load(Ordering::Relaxed) {
+                        warp::http::StatusCode::INTERNAL_SERVER_ERROR
+                    } else {
+                        warp::http::StatusCode::OK
+                    };
+                    warp::reply::with_status(
+                        r#"{
+                            "accountId": "071959437513",
+                            "architecture": "x86_64",
+                            "imageId": "ami-05f27d4d6770a43d2",
+                            "instanceId": "i-096fba6d03d36d262",
+                            "instanceType": "t2.micro",
+                            "privateIp": "192.1.1.2",
+                            "region": "us-east-1",
+                            "version": "2017-09-30"
+                        }"#,
+                        status,
+                    )
+                }
+            });
+        let _server = tokio::spawn(warp::serve(token.or(document)).bind(addr));
+
+        let config = Ec2Metadata {
+            endpoint: format!("http://{}", addr),
+            fields: vec![INSTANCE_ID_KEY.into(), REGION_KEY.into()],
+            refresh_interval_secs: Duration::from_secs(1),
+            on_refresh_error_fields: HashMap::from([(
+                REGION_KEY.to_owned(),
+                RefreshErrorPolicy::Remove,
+            )]),
+            ..Default::default()
+        };
+
+        let (tx, rx) = mpsc::channel(1);
+        let (topology, mut out) = create_topology(ReceiverStream::new(rx), config).await;
+
+        tx.send(LogEvent::default().into()).await.unwrap();
+        let log = out.recv().await.unwrap().into_log();
+        assert_eq!(log.get(event_path!(REGION_KEY)), Some(&"us-east-1".into()));
+
+        failing.store(true, Ordering::Relaxed);
+        sleep(Duration::from_secs(3)).await;
+
+        tx.send(LogEvent::default().into()).await.unwrap();
+        let log = out.recv().await.unwrap().into_log();
+        assert_eq!(
+            log.get(event_path!(INSTANCE_ID_KEY)),
+            Some(&"i-096fba6d03d36d262".into())
+        );
+        assert_eq!(log.get(event_path!(REGION_KEY)), None);
+
+        drop(tx);
+        topology.stop().await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn ttl_refresh_on_reload_request() {
+        use std::sync::atomic::AtomicUsize;
+
+        let addr = next_addr();
+
+        let token = warp::put()
+            .and(warp::path!("latest" / "api" / "token"))
+            .map(|| "token");
+        let requests = Arc::new(AtomicUsize::new(0));
+        let document = warp::get()
+            .and(warp::path!(
+                "latest" / "dynamic" / "instance-identity" / "document"
+            ))
+            .map({
+                let requests = Arc::clone(&requests);
+                move || {
+                    requests.fetch_add(1, Ordering::Relaxed);
+                    r#"{
+                        "accountId": "071959437513",
+                        "architecture": "x86_64",
+                        "imageId": "ami-05f27d4d6770a43d2",
+                        "instanceId": "i-096fba6d03d36d262",
+                        "instanceType": "t2.micro",
+                        "privateIp": "192.1.1.2",
+                        "region": "us-east-1",
+                        "version": "2017-09-30"
+                    }"#
+                }
+            });
+        let _server = tokio::spawn(warp::serve(token.or(document)).bind(addr));
+
+        let config = Ec2Metadata {
+            endpoint: format!("http://{}", addr),
+            fields: vec![INSTANCE_ID_KEY.into()],
+            refresh_interval_secs: Duration::from_secs(3600),
+            refresh_mode: RefreshMode::Ttl,
+            ..Default::default()
+        };
+
+        let (tx, rx) = mpsc::channel(1);
+        let (topology, mut out) = create_topology(ReceiverStream::new(rx), config).await;
+        assert_eq!(requests.load(Ordering::Relaxed), 1);
+
+        // Events don't refresh metadata that hasn't expired.
+        tx.send(LogEvent::default().into()).await.unwrap();
+        let log = out.recv().await.unwrap().into_log();
+        assert_eq!(
+            log.get(event_path!(INSTANCE_ID_KEY)),
+            Some(&"i-096fba6d03d36d262".into())
+        );
+        sleep(Duration::from_millis(500)).await;
+        assert_eq!(requests.load(Ordering::Relaxed), 1);
+
+        signal::notify_reload_requested();
+        sleep(Duration::from_millis(500)).await;
+        assert_eq!(requests.load(Ordering::Relaxed), 2);
+
+        drop(tx);
+        topology.stop().await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn ecs_task_metadata() {
+        let addr = next_addr();
+
+        let task = warp::path!("v4" / "abc" / "task").map(|| {
+            r#"{
+                "TaskARN": "arn:aws:ecs:us-west-2:111122223333:task/default/abc",
+                "AvailabilityZone": "us-west-2a"
+            }"#
+        });
+        let container = warp::path!("v4" / "abc").map(|| {
+            r#"{
+                "DockerId": "abc",
+                "Networks": [{
+                    "NetworkMode": "awsvpc",
+                    "IPv4Addresses": ["10.0.2.106"],
+                    "PrivateDNSName": "ip-10-0-2-106.us-west-2.compute.internal"
+                }]
+            }"#
+        });
+        let _server = tokio::spawn(warp::serve(task.or(container)).bind(addr));
+
+        let mut fields = default_fields();
+        fields.push(ACCOUNT_ID_KEY.into());
+        let config = Ec2Metadata {
+            fields,
+            strategy: MetadataStrategy::Ecs,
+            ..Default::default()
+        };
+        let environment = HashMap::from([(
+            ECS_CONTAINER_METADATA_URI_ENV,
+            format!("http://{}/v4/abc", addr),
+        )]);
+
+        let mut expected = LogEvent::default();
+        expected.insert(event_path!(ACCOUNT_ID_KEY), "111122223333");
+        expected.insert(event_path!(REGION_KEY), "us-west-2");
+        expected.insert(event_path!(AVAILABILITY_ZONE_KEY), "us-west-2a");
+        expected.insert(event_path!(LOCAL_IPV4_KEY), "10.0.2.106");
+        expected.insert(
+            event_path!(LOCAL_HOSTNAME_KEY),
+            "ip-10-0-2-106.us-west-2.compute.internal",
+        );
+        assert_eq!(
+            enrich_with_environment(&config, environment).await,
+            expected
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn eks_fargate_region() {
+        // Nothing listens on the endpoint, as there is no instance metadata service on Fargate.
+        let config = Ec2Metadata {
+            endpoint: format!("http://{}", next_addr()),
+            strategy: MetadataStrategy::Eks,
+            ..Default::default()
+        };
+        let environment = HashMap::from([("AWS_REGION", "us-west-2".to_string())]);
+
+        let mut expected = LogEvent::default();
+        expected.insert(event_path!(REGION_KEY), "us-west-2");
+        assert_eq!(
+            enrich_with_environment(&config, environment).await,
+            expected
+        );
+    }
+
     #[tokio::test]
     async fn enrich_metric() {
         assert_transform_compliance(async {
@@ -881,6 +2010,47 @@ mod integration_tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn metric_tags_naming() {
+        assert_transform_compliance(async {
+            let transform_config = Ec2Metadata {
+                endpoint: ec2_metadata_address(),
+                namespace: Some(OwnedTargetPath::event(owned_value_path!("aws", "ec2")).into()),
+                metric_tags: MetricTagsConfig {
+                    sanitize_names: true,
+                    role_names: RoleNameTags::Joined,
+                },
+                ..Default::default()
+            };
+
+            let (tx, rx) = mpsc::channel(1);
+            let (topology, mut out) =
+                create_topology(ReceiverStream::new(rx), transform_config).await;
+
+            // We need to sleep to let the background task fetch the data.
+            sleep(Duration::from_secs(1)).await;
+
+            tx.send(make_metric().into()).await.unwrap();
+
+            let event = out.recv().await.unwrap();
+            let metric = event.as_metric();
+            assert_eq!(
+                metric.tag_value("aws_ec2_availability_zone"),
+                Some("ww-region-1a".to_string())
+            );
+            assert_eq!(
+                metric.tag_value("aws_ec2_role_name"),
+                Some("mock-user".to_string())
+            );
+            assert_eq!(metric.tag_value("aws_ec2_role_name_0"), None);
+
+            drop(tx);
+            topology.stop().await;
+            assert_eq!(out.recv().await, None);
+        })
+        .await;
+    }
+
     #[tokio::test]
     async fn fields_log() {
         assert_transform_compliance(async {
@@ -947,6 +2117,64 @@ mod integration_tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn metric_placement_metadata() {
+        assert_transform_compliance(async {
+            let transform_config = Ec2Metadata {
+                endpoint: ec2_metadata_address(),
+                namespace: Some(OwnedTargetPath::event(owned_value_path!("aws", "ec2")).into()),
+                fields: vec![
+                    INSTANCE_ID_KEY.into(),
+                    REGION_KEY.into(),
+                    ROLE_NAME_KEY.into(),
+                ],
+                metric_placement: MetricPlacement::Metadata,
+                metric_placement_fields: HashMap::from([(
+                    REGION_KEY.to_string(),
+                    MetricPlacement::Tag,
+                )]),
+                ..Default::default()
+            };
+
+            let (tx, rx) = mpsc::channel(1);
+            let (topology, mut out) =
+                create_topology(ReceiverStream::new(rx), transform_config).await;
+
+            // We need to sleep to let the background task fetch the data.
+            sleep(Duration::from_secs(1)).await;
+
+            tx.send(make_metric().into()).await.unwrap();
+
+            let event = out.recv().await.unwrap();
+            let metric = event.as_metric();
+            assert_eq!(
+                metric.tag_value("aws.ec2.region"),
+                Some("us-east-1".to_string())
+            );
+            assert_eq!(metric.tag_value("aws.ec2.instance-id"), None);
+            assert_eq!(metric.tag_value("aws.ec2.role-name[0]"), None);
+
+            let metadata = metric.metadata().value();
+            assert_eq!(
+                metadata.get(&owned_value_path!("aws", "ec2", "instance-id")),
+                Some(&"i-096fba6d03d36d262".into())
+            );
+            assert_eq!(
+                metadata.get(&owned_value_path!("aws", "ec2", "role-name", 0)),
+                Some(&"mock-user".into())
+            );
+            assert_eq!(
+                metadata.get(&owned_value_path!("aws", "ec2", "region")),
+                None
+            );
+
+            drop(tx);
+            topology.stop().await;
+            assert_eq!(out.recv().await, None);
+        })
+        .await;
+    }
+
     #[tokio::test]
     async fn namespace_log() {
         {
@@ -1089,4 +2317,73 @@ mod integration_tests {
             .await;
         }
     }
+
+    #[tokio::test]
+    async fn cached_and_fallback_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let context = TransformContext {
+            key: Some(ComponentKey::from("metadata")),
+            globals: GlobalOptions {
+                data_dir: Some(dir.path().to_path_buf()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // The first run caches the fetched metadata.
+        let transform_config = Ec2Metadata {
+            endpoint: ec2_metadata_address(),
+            fields: vec![REGION_KEY.into()],
+            cache: true,
+            ..Default::default()
+        };
+        transform_config.build(&context).await.unwrap();
+
+        // The endpoint is unavailable on the next one.
+        let transform_config = Ec2Metadata {
+            endpoint: format!("http://{}", next_addr()),
+            fields: vec![REGION_KEY.into(), INSTANCE_TYPE_KEY.into()],
+            required: false,
+            fallback_fields: HashMap::from([(
+                INSTANCE_TYPE_KEY.to_string(),
+                "m5.large".to_string(),
+            )]),
+            cache: true,
+            ..Default::default()
+        };
+        let transform = transform_config.build(&context).await.unwrap();
+
+        let mut out = transform
+            .into_task()
+            .transform(Box::pin(futures::stream::iter(vec![EventArray::from(
+                LogEvent::default(),
+            )])));
+        let log = match out.next().await.unwrap() {
+            EventArray::Logs(mut logs) => logs.remove(0),
+            _ => panic!("Expected a log."),
+        };
+        assert_eq!(log.get(event_path!(REGION_KEY)), Some(&"us-east-1".into()));
+        assert_eq!(
+            log.get(event_path!(INSTANCE_TYPE_KEY)),
+            Some(&"m5.large".into())
+        );
+
+        // Required metadata can be cached.
+        let transform_config = Ec2Metadata {
+            endpoint: format!("http://{}", next_addr()),
+            fields: vec![REGION_KEY.into()],
+            cache: true,
+            ..Default::default()
+        };
+        assert!(transform_config.required);
+        transform_config.build(&context).await.unwrap();
+
+        // But not without a cache.
+        let transform_config = Ec2Metadata {
+            endpoint: format!("http://{}", next_addr()),
+            fields: vec![REGION_KEY.into()],
+            ..Default::default()
+        };
+        assert!(transform_config.build(&context).await.is_err());
+    }
 }
@@ -1,8 +1,15 @@
-use chrono::Utc;
+use async_stream::stream;
+use chrono::{DateTime, SecondsFormat, Utc};
+use futures::{Stream, StreamExt};
+use indexmap::IndexMap;
 use lookup::lookup_v2::parse_value_path;
 use lookup::{event_path, owned_value_path, path, PathPrefix};
-use serde_json::Value;
-use std::collections::BTreeSet;
+use ordered_float::NotNan;
+use std::{
+    collections::{btree_map::Entry, BTreeSet, HashMap},
+    pin::Pin,
+    time::{Duration, Instant},
+};
 use value::kind::Collection;
 use value::Kind;
 use vector_common::TimeZone;
@@ -15,11 +22,14 @@ use crate::{
     config::{
         log_schema, DataType, GenerateConfig, Input, Output, TransformConfig, TransformContext,
     },
-    event::{self, Event, LogEvent, Metric},
-    internal_events::MetricToLogSerializeError,
+    event::{
+        self,
+        metric::{MetricKind, MetricSeries, MetricSketch, MetricTags, MetricValue, StatisticKind},
+        Event, EventArray, EventContainer, LogEvent, Metric,
+    },
+    internal_events::{MetricToLogSerializeError, MetricToLogWideEventError},
     schema,
-    transforms::{FunctionTransform, OutputBuffer, Transform},
-    types::Conversion,
+    transforms::{SyncTransform, TaskTransform, Transform, TransformOutputsBuf},
 };
 
 /// Configuration for the `metric_to_log` transform.
@@ -39,17 +49,115 @@ pub struct MetricToLogConfig {
     /// The name of the timezone to apply to timestamp conversions that do not contain an explicit
     /// time zone.
     ///
-    /// This overrides the [global `timezone`][global_timezone] option. The time zone name may be
-    /// any name in the [TZ database][tz_database], or `local` to indicate system local time.
-    ///
-    /// [global_timezone]: https://vector.dev/docs/reference/configuration//global-options#timezone
-    /// [tz_database]: https://en.wikipedia.org/wiki/List_of_tz_database_time_zones
+    /// This option is deprecated and has no effect: metric timestamps always have an explicit time
+    /// zone, and are copied to log events as they are.
+    #[configurable(deprecated)]
     pub timezone: Option<TimeZone>,
 
     /// The namespace to use for logs. This overrides the global setting.
     #[serde(default)]
     #[configurable(metadata(docs::hidden))]
     pub log_namespace: Option<bool>,
+
+    /// Merges metrics into wide log events.
+    ///
+    /// When set, metrics sharing the same timestamp, tags, and kind are merged into a single log
+    /// event with `tags` and `kind` fields and one field per metric name, instead of one log event
+    /// per metric. Counter and gauge fields hold the metric value, other metric types hold their
+    /// serialized value. Metrics with a namespace are nested under a field named after the
+    /// namespace.
+    ///
+    /// Metrics whose name, or namespace, is that of the `tags`, `kind`, timestamp, or host fields
+    /// are dropped, as are counters and gauges whose value is NaN.
+    #[configurable(derived)]
+    pub wide_events: Option<WideEventsConfig>,
+
+    /// Lifts metric tags to top-level fields of the log event, instead of nesting them under a
+    /// `tags` field.
+    ///
+    /// Tags never overwrite other fields: a tag whose field name is already taken, such as a tag
+    /// named `name` or `kind`, is left under the `tags` field.
+    #[serde(default)]
+    pub flatten_tags: bool,
+
+    /// A prefix added to the field name of each tag lifted by `flatten_tags`.
+    ///
+    /// For example, with a prefix of `tag_`, the `region` tag is lifted to the `tag_region` field.
+    /// By default, tags are lifted to fields named after them.
+    #[configurable(metadata(docs::examples = "tag_"))]
+    pub tag_prefix: Option<String>,
+
+    /// Converts incremental counters to absolute counters holding their running total.
+    ///
+    /// A running total is kept for each series, so that the emitted log events carry cumulative
+    /// values, as some consumers of log events can only handle those.
+    #[configurable(derived)]
+    pub cumulative_counters: Option<CumulativeCountersConfig>,
+
+    /// Embeds the metadata needed to rebuild the original metric in each log event.
+    ///
+    /// When set, each log event gets a `metric_metadata` field holding the name, namespace, kind,
+    /// tags, timestamp, and interval of the metric as they were before the conversion, along with
+    /// its origin metadata, and a `schema_version` field holding the version of the layout of
+    /// `metric_metadata`. Cannot be used together with `wide_events`.
+    #[serde(default)]
+    pub embed_metadata: bool,
+}
+
+impl MetricToLogConfig {
+    /// Returns the prefix of the fields tags are lifted to, if they are.
+    pub(crate) fn flattened_tag_prefix(&self) -> Option<String> {
+        self.flatten_tags
+            .then(|| self.tag_prefix.clone().unwrap_or_default())
+    }
+}
+
+/// Configuration for merging metrics into wide log events.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct WideEventsConfig {
+    /// The interval between flushes of pending wide events, in milliseconds.
+    ///
+    /// Metrics sharing the same timestamp, tags, and kind that arrive within this interval are
+    /// merged into the same log event.
+    #[serde(default = "default_flush_interval_ms")]
+    pub flush_interval_ms: u64,
+
+    /// The maximum number of metrics merged into a single log event.
+    ///
+    /// Once reached, the log event is emitted right away, and further metrics sharing its
+    /// timestamp, tags, and kind start a new log event.
+    #[serde(default = "default_max_width")]
+    pub max_width: usize,
+}
+
+/// Configuration for converting incremental counters to cumulative ones.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct CumulativeCountersConfig {
+    /// The time, in seconds, after which the running total of a series that stopped being
+    /// updated is forgotten.
+    ///
+    /// A series seen again after its running total was forgotten starts over from zero.
+    #[serde(default = "default_state_ttl_secs")]
+    pub state_ttl_secs: u64,
+}
+
+/// The version of the layout of the `metric_metadata` field, incremented whenever it changes.
+pub const METRIC_METADATA_SCHEMA_VERSION: i64 = 1;
+
+const fn default_state_ttl_secs() -> u64 {
+    300
+}
+
+const fn default_flush_interval_ms() -> u64 {
+    1000
+}
+
+const fn default_max_width() -> usize {
+    100
 }
 
 impl GenerateConfig for MetricToLogConfig {
@@ -58,6 +166,11 @@ impl GenerateConfig for MetricToLogConfig {
             host_tag: Some("host-tag".to_string()),
             timezone: None,
             log_namespace: None,
+            wide_events: None,
+            flatten_tags: false,
+            tag_prefix: None,
+            cumulative_counters: None,
+            embed_metadata: false,
         })
         .unwrap()
     }
@@ -66,12 +179,24 @@ impl GenerateConfig for MetricToLogConfig {
 #[async_trait::async_trait]
 impl TransformConfig for MetricToLogConfig {
     async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        if self.embed_metadata && self.wide_events.is_some() {
+            return Err("`embed_metadata` and `wide_events` cannot both be set.".into());
+        }
+
+        if self.timezone.is_some() {
+            warn!("The `timezone` setting is deprecated and has no effect.");
+        }
+
         let log_namespace = context.log_namespace(self.log_namespace);
-        Ok(Transform::function(MetricToLog::new(
-            self.host_tag.clone(),
-            self.timezone.unwrap_or_else(|| context.globals.timezone()),
-            log_namespace,
-        )))
+        let metric_to_log = MetricToLog::new(self.host_tag.clone(), log_namespace)
+            .with_flattened_tags(self.flattened_tag_prefix())
+            .with_cumulative_counters(self.cumulative_counters.as_ref())
+            .with_embedded_metadata(self.embed_metadata);
+
+        Ok(match &self.wide_events {
+            Some(config) => Transform::event_task(WideMetricToLog::new(metric_to_log, config)),
+            None => Transform::synchronous(metric_to_log),
+        })
     }
 
     fn input(&self) -> Input {
@@ -80,7 +205,16 @@ impl TransformConfig for MetricToLogConfig {
 
     fn outputs(&self, _: &schema::Definition, global_log_namespace: LogNamespace) -> Vec<Output> {
         let log_namespace = global_log_namespace.merge(self.log_namespace);
-        let mut schema_definition =
+        let mut schema_definition = if self.wide_events.is_some() {
+            Definition::default_for_namespace(&BTreeSet::from([log_namespace]))
+                .with_event_field(
+                    &owned_value_path!("tags"),
+                    Kind::object(Collection::empty().with_unknown(Kind::bytes())).or_undefined(),
+                    None,
+                )
+                .with_event_field(&owned_value_path!("kind"), Kind::bytes(), None)
+                .unknown_fields(Kind::any())
+        } else {
             Definition::default_for_namespace(&BTreeSet::from([log_namespace]))
                 .with_event_field(&owned_value_path!("name"), Kind::bytes(), None)
                 .with_event_field(
@@ -178,14 +312,37 @@ impl TransformConfig for MetricToLogConfig {
                     &owned_value_path!("sketch"),
                     Kind::any().or_undefined(),
                     None,
+                )
+        };
+
+        if self.embed_metadata {
+            schema_definition = schema_definition
+                .with_event_field(&owned_value_path!("schema_version"), Kind::integer(), None)
+                .with_event_field(
+                    &owned_value_path!("metric_metadata"),
+                    Kind::object(Collection::any()),
+                    None,
                 );
+        }
+
+        if self.flatten_tags {
+            // Lifted tags can have any name, and those colliding with other fields are still
+            // nested under `tags`.
+            schema_definition = schema_definition.unknown_fields(Kind::bytes());
+        }
 
         match log_namespace {
             LogNamespace::Vector => {
-                // from serializing the Metric (Legacy moves it to another field)
+                // from serializing the Metric (Legacy moves it to another field), while wide
+                // events keep it as a timestamp
+                let timestamp_kind = if self.wide_events.is_some() {
+                    Kind::timestamp()
+                } else {
+                    Kind::bytes()
+                };
                 schema_definition = schema_definition.with_event_field(
                     &owned_value_path!("timestamp"),
-                    Kind::bytes().or_undefined(),
+                    timestamp_kind.or_undefined(),
                     None,
                 );
 
@@ -215,81 +372,609 @@ impl TransformConfig for MetricToLogConfig {
     }
 
     fn enable_concurrency(&self) -> bool {
-        true
+        // Running totals must see every metric of their series.
+        self.wide_events.is_none() && self.cumulative_counters.is_none()
     }
 }
 
 #[derive(Clone, Debug)]
 pub struct MetricToLog {
     host_tag: String,
-    timezone: TimeZone,
     log_namespace: LogNamespace,
+    tag_prefix: Option<String>,
+    cumulative_counters: Option<CumulativeCounters>,
+    embed_metadata: bool,
 }
 
 impl MetricToLog {
-    pub fn new(host_tag: Option<String>, timezone: TimeZone, log_namespace: LogNamespace) -> Self {
+    pub fn new(host_tag: Option<String>, log_namespace: LogNamespace) -> Self {
         Self {
             host_tag: format!(
                 "tags.{}",
                 host_tag.unwrap_or_else(|| log_schema().host_key().to_string())
             ),
-            timezone,
             log_namespace,
+            tag_prefix: None,
+            cumulative_counters: None,
+            embed_metadata: false,
+        }
+    }
+
+    /// Lifts tags to top-level fields named after them, prefixed with `tag_prefix`, if set.
+    pub fn with_flattened_tags(mut self, tag_prefix: Option<String>) -> Self {
+        self.tag_prefix = tag_prefix;
+        self
+    }
+
+    /// Converts incremental counters to cumulative ones, if configured.
+    pub fn with_cumulative_counters(mut self, config: Option<&CumulativeCountersConfig>) -> Self {
+        self.cumulative_counters = config.map(CumulativeCounters::new);
+        self
+    }
+
+    /// Embeds the metadata needed to rebuild the original metric in each log event, if enabled.
+    pub const fn with_embedded_metadata(mut self, embed_metadata: bool) -> Self {
+        self.embed_metadata = embed_metadata;
+        self
+    }
+
+    fn cumulative(&mut self, metric: Metric, now: Instant) -> Metric {
+        match &mut self.cumulative_counters {
+            Some(counters) => counters.convert(metric, now),
+            None => metric,
+        }
+    }
+
+    /// Moves the tags of `log` to top-level fields, except those whose field is already taken.
+    fn flatten_tags(&self, log: &mut LogEvent) {
+        let prefix = match &self.tag_prefix {
+            Some(prefix) => prefix,
+            None => return,
+        };
+        let tags = match log.remove(event_path!("tags")) {
+            Some(event::Value::Object(tags)) => tags,
+            Some(other) => {
+                log.insert(event_path!("tags"), other);
+                return;
+            }
+            None => return,
+        };
+
+        let mut collisions = BTreeMap::new();
+        for (name, value) in tags {
+            let field = format!("{}{}", prefix, name);
+            if log.contains(event_path!(field.as_str())) {
+                collisions.insert(name, value);
+            } else {
+                log.insert(event_path!(field.as_str()), value);
+            }
+        }
+        if !collisions.is_empty() {
+            log.insert(event_path!("tags"), collisions);
         }
     }
 
     pub fn transform_one(&self, metric: Metric) -> Option<LogEvent> {
-        serde_json::to_value(&metric)
+        self.convert(metric, Utc::now())
+    }
+
+    /// Converts `metric` to a log event, timestamped at `now` if it has no timestamp of its own.
+    fn convert(&self, metric: Metric, now: DateTime<Utc>) -> Option<LogEvent> {
+        let metric_metadata = self.embed_metadata.then(|| metric_metadata(&metric));
+        let mut fields = metric_fields(&metric)
             .map_err(|error| emit!(MetricToLogSerializeError { error }))
-            .ok()
-            .and_then(|value| match value {
-                Value::Object(object) => {
-                    let (_, _, metadata) = metric.into_parts();
-                    let mut log = LogEvent::new_with_metadata(metadata);
-
-                    // converting all fields from serde `Value` to Vector `Value`
-                    for (key, value) in object {
-                        log.insert(event_path!(&key), value);
-                    }
+            .ok()?;
+        let timestamp = metric.timestamp();
+        let (_, _, metadata) = metric.into_parts();
 
-                    if self.log_namespace == LogNamespace::Legacy {
-                        // "Vector" namespace just leaves the `timestamp` in place.
-
-                        let timestamp = log
-                            .remove(event_path!("timestamp"))
-                            .and_then(|value| {
-                                Conversion::Timestamp(self.timezone)
-                                    .convert(value.coerce_to_bytes())
-                                    .ok()
-                            })
-                            .unwrap_or_else(|| event::Value::Timestamp(Utc::now()));
-
-                        log.insert(log_schema().timestamp_key(), timestamp);
-                        if let Some(host) = log.remove_prune(self.host_tag.as_str(), true) {
-                            log.insert(log_schema().host_key(), host);
-                        }
-                    }
-                    if self.log_namespace == LogNamespace::Vector {
-                        // Create vector metadata since this is used as a marker to see which namespace is used at runtime.
-                        // This can be removed once metrics support namespacing.
-                        log.insert(
-                            (PathPrefix::Metadata, path!("vector")),
-                            value::Value::Object(BTreeMap::new()),
-                        );
+        match self.log_namespace {
+            LogNamespace::Legacy => {
+                let mut log = LogEvent::from_map(fields, metadata);
+                log.insert(log_schema().timestamp_key(), timestamp.unwrap_or(now));
+                if let Some(host) = log.remove_prune(self.host_tag.as_str(), true) {
+                    log.insert(log_schema().host_key(), host);
+                }
+                Some(self.finish(log, metric_metadata))
+            }
+            LogNamespace::Vector => {
+                if let Some(timestamp) = timestamp {
+                    fields.insert("timestamp".to_string(), timestamp_string(timestamp));
+                }
+                let mut log = LogEvent::from_map(fields, metadata);
+                // Create vector metadata since this is used as a marker to see which namespace is used at runtime.
+                // This can be removed once metrics support namespacing.
+                log.insert(
+                    (PathPrefix::Metadata, path!("vector")),
+                    value::Value::Object(BTreeMap::new()),
+                );
+                Some(self.finish(log, metric_metadata))
+            }
+        }
+    }
+
+    fn finish(&self, mut log: LogEvent, metric_metadata: Option<event::Value>) -> LogEvent {
+        self.flatten_tags(&mut log);
+        if let Some(metric_metadata) = metric_metadata {
+            log.insert(
+                event_path!("schema_version"),
+                METRIC_METADATA_SCHEMA_VERSION,
+            );
+            log.insert(event_path!("metric_metadata"), metric_metadata);
+        }
+        log
+    }
+}
+
+/// Returns the fields of the log event `metric` is converted to, laid out as `metric` is
+/// serialized, except for its timestamp.
+///
+/// The fields are built directly rather than through `serde_json::to_value`, which allocates an
+/// intermediate JSON tree for every metric. Only sketches are still serialized that way.
+fn metric_fields(metric: &Metric) -> Result<BTreeMap<String, event::Value>, serde_json::Error> {
+    let mut fields = BTreeMap::new();
+    fields.insert("name".to_string(), metric.name().into());
+    if let Some(namespace) = metric.namespace() {
+        fields.insert("namespace".to_string(), namespace.into());
+    }
+    if let Some(tags) = metric.tags() {
+        fields.insert("tags".to_string(), tags_value(tags));
+    }
+    if let Some(interval_ms) = metric.interval_ms() {
+        fields.insert(
+            "interval_ms".to_string(),
+            i64::from(interval_ms.get()).into(),
+        );
+    }
+    fields.insert("kind".to_string(), kind_value(metric.kind()));
+    let (name, value) = metric_value(metric.value())?;
+    fields.insert(name.to_string(), value);
+    Ok(fields)
+}
+
+/// Returns the name of the field holding `value`, and its contents.
+fn metric_value(value: &MetricValue) -> Result<(&'static str, event::Value), serde_json::Error> {
+    let object = |fields: Vec<(&str, event::Value)>| {
+        event::Value::Object(
+            fields
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .collect(),
+        )
+    };
+
+    Ok(match value {
+        MetricValue::Counter { value } => ("counter", object(vec![("value", float(*value))])),
+        MetricValue::Gauge { value } => ("gauge", object(vec![("value", float(*value))])),
+        MetricValue::Set { values } => (
+            "set",
+            object(vec![(
+                "values",
+                values
+                    .iter()
+                    .map(|value| event::Value::from(value.as_str()))
+                    .collect::<Vec<_>>()
+                    .into(),
+            )]),
+        ),
+        MetricValue::Distribution { samples, statistic } => (
+            "distribution",
+            object(vec![
+                (
+                    "samples",
+                    samples
+                        .iter()
+                        .map(|sample| {
+                            object(vec![
+                                ("value", float(sample.value)),
+                                ("rate", i64::from(sample.rate).into()),
+                            ])
+                        })
+                        .collect::<Vec<_>>()
+                        .into(),
+                ),
+                (
+                    "statistic",
+                    match statistic {
+                        StatisticKind::Histogram => "histogram",
+                        StatisticKind::Summary => "summary",
                     }
-                    Some(log)
+                    .into(),
+                ),
+            ]),
+        ),
+        MetricValue::AggregatedHistogram {
+            buckets,
+            count,
+            sum,
+        } => (
+            "aggregated_histogram",
+            object(vec![
+                (
+                    "buckets",
+                    buckets
+                        .iter()
+                        .map(|bucket| {
+                            object(vec![
+                                ("upper_limit", float(bucket.upper_limit)),
+                                ("count", unsigned(bucket.count)),
+                            ])
+                        })
+                        .collect::<Vec<_>>()
+                        .into(),
+                ),
+                ("count", unsigned(*count)),
+                ("sum", float(*sum)),
+            ]),
+        ),
+        MetricValue::AggregatedSummary {
+            quantiles,
+            count,
+            sum,
+        } => (
+            "aggregated_summary",
+            object(vec![
+                (
+                    "quantiles",
+                    quantiles
+                        .iter()
+                        .map(|quantile| {
+                            object(vec![
+                                ("quantile", float(quantile.quantile)),
+                                ("value", float(quantile.value)),
+                            ])
+                        })
+                        .collect::<Vec<_>>()
+                        .into(),
+                ),
+                ("count", unsigned(*count)),
+                ("sum", float(*sum)),
+            ]),
+        ),
+        MetricValue::Sketch { sketch } => {
+            ("sketch", object(vec![("sketch", sketch_value(sketch)?)]))
+        }
+    })
+}
+
+fn sketch_value(sketch: &MetricSketch) -> Result<event::Value, serde_json::Error> {
+    serde_json::to_value(sketch).map(Into::into)
+}
+
+/// Returns the value of each tag, or an array of its values if it has several of them.
+fn tags_value(tags: &MetricTags) -> event::Value {
+    let mut object = BTreeMap::new();
+    for (name, value) in tags.iter_all() {
+        let value = value.map_or(event::Value::Null, event::Value::from);
+        match object.entry(name.to_string()) {
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+            }
+            Entry::Occupied(mut entry) => match entry.get_mut() {
+                event::Value::Array(values) => values.push(value),
+                single => {
+                    let first = std::mem::replace(single, event::Value::Null);
+                    *single = event::Value::Array(vec![first, value]);
                 }
-                _ => None,
-            })
+            },
+        }
+    }
+    event::Value::Object(object)
+}
+
+fn kind_value(kind: MetricKind) -> event::Value {
+    match kind {
+        MetricKind::Incremental => "incremental",
+        MetricKind::Absolute => "absolute",
+    }
+    .into()
+}
+
+/// Non-finite values are `null`, as they can't be serialized.
+fn float(value: f64) -> event::Value {
+    NotNan::new(value)
+        .ok()
+        .filter(|value| value.is_finite())
+        .map_or(event::Value::Null, event::Value::Float)
+}
+
+/// Values beyond the range of integers are kept as strings, as they were when serialized.
+fn unsigned(value: u64) -> event::Value {
+    i64::try_from(value).map_or_else(|_| value.to_string().into(), Into::into)
+}
+
+/// Formats `timestamp` as it is serialized.
+fn timestamp_string(timestamp: DateTime<Utc>) -> event::Value {
+    timestamp
+        .to_rfc3339_opts(SecondsFormat::AutoSi, true)
+        .into()
+}
+
+/// Returns the metadata needed to rebuild `metric` as it is.
+fn metric_metadata(metric: &Metric) -> event::Value {
+    let mut metadata = BTreeMap::new();
+    metadata.insert("name".to_string(), metric.name().into());
+    if let Some(namespace) = metric.namespace() {
+        metadata.insert("namespace".to_string(), namespace.into());
+    }
+    metadata.insert("kind".to_string(), kind_value(metric.kind()));
+    if let Some(tags) = metric.tags() {
+        metadata.insert("tags".to_string(), tags_value(tags));
+    }
+    if let Some(timestamp) = metric.timestamp() {
+        metadata.insert("timestamp".to_string(), timestamp.into());
+    }
+    if let Some(interval_ms) = metric.interval_ms() {
+        metadata.insert(
+            "interval_ms".to_string(),
+            i64::from(interval_ms.get()).into(),
+        );
+    }
+    match metric.metadata().value() {
+        event::Value::Object(origin) if origin.is_empty() => {}
+        origin => {
+            metadata.insert("origin".to_string(), origin.clone());
+        }
+    }
+    metadata.into()
+}
+
+impl SyncTransform for MetricToLog {
+    fn transform(&mut self, event: Event, output: &mut TransformOutputsBuf) {
+        let metric = self.cumulative(event.into_metric(), Instant::now());
+        if let Some(log) = self.transform_one(metric) {
+            output.push(log.into());
+        }
+    }
+
+    fn transform_all(&mut self, events: EventArray, output: &mut TransformOutputsBuf) {
+        // The clocks are read once for the whole batch, and the converted events are appended to
+        // the output at once.
+        let now = Instant::now();
+        let timestamp = Utc::now();
+        let mut logs = Vec::with_capacity(events.len());
+        for event in events.into_events() {
+            let metric = self.cumulative(event.into_metric(), now);
+            logs.extend(self.convert(metric, timestamp).map(Event::from));
+        }
+        output.append(&mut logs);
+    }
+}
+
+/// The running total of an incremental counter series.
+#[derive(Clone, Debug)]
+struct RunningTotal {
+    value: f64,
+    last_seen: Instant,
+}
+
+/// Running totals of incremental counters, used to convert them to absolute counters.
+#[derive(Clone, Debug)]
+struct CumulativeCounters {
+    ttl: Duration,
+    totals: HashMap<MetricSeries, RunningTotal>,
+    last_sweep: Instant,
+}
+
+impl CumulativeCounters {
+    fn new(config: &CumulativeCountersConfig) -> Self {
+        Self {
+            ttl: Duration::from_secs(config.state_ttl_secs),
+            totals: HashMap::new(),
+            last_sweep: Instant::now(),
+        }
+    }
+
+    /// Converts `metric`, seen at `now`, to an absolute counter if it's an incremental one.
+    fn convert(&mut self, metric: Metric, now: Instant) -> Metric {
+        let increment = match (metric.kind(), metric.value()) {
+            (MetricKind::Incremental, MetricValue::Counter { value }) => *value,
+            _ => return metric,
+        };
+
+        if now.saturating_duration_since(self.last_sweep) >= self.ttl {
+            let ttl = self.ttl;
+            self.totals
+                .retain(|_, total| now.saturating_duration_since(total.last_seen) < ttl);
+            self.last_sweep = now;
+        }
+
+        let total = self
+            .totals
+            .entry(metric.series().clone())
+            .or_insert(RunningTotal {
+                value: 0.0,
+                last_seen: now,
+            });
+        // The series may have gone stale since the last sweep.
+        if now.saturating_duration_since(total.last_seen) >= self.ttl {
+            total.value = 0.0;
+        }
+        total.value += increment;
+        total.last_seen = now;
+
+        metric
+            .into_absolute()
+            .with_value(MetricValue::Counter { value: total.value })
+    }
+}
+
+type WideEventKey = (Option<DateTime<Utc>>, Option<MetricTags>, MetricKind);
+
+/// A wide log event that is still accepting metrics.
+struct PendingWideEvent {
+    log: LogEvent,
+    width: usize,
+}
+
+/// Merges metrics sharing the same timestamp and tags into wide log events.
+pub struct WideMetricToLog {
+    metric_to_log: MetricToLog,
+    flush_interval: Duration,
+    max_width: usize,
+    pending: IndexMap<WideEventKey, PendingWideEvent>,
+}
+
+impl WideMetricToLog {
+    pub fn new(metric_to_log: MetricToLog, config: &WideEventsConfig) -> Self {
+        Self {
+            metric_to_log,
+            flush_interval: Duration::from_millis(config.flush_interval_ms),
+            max_width: config.max_width.max(1),
+            pending: IndexMap::new(),
+        }
+    }
+
+    fn record(&mut self, metric: Metric, output: &mut Vec<Event>) {
+        let metric = self.metric_to_log.cumulative(metric, Instant::now());
+        if self.is_reserved_field(metric.namespace().unwrap_or_else(|| metric.name())) {
+            emit!(MetricToLogWideEventError {
+                name: metric.name(),
+                reason: "Metric name is a reserved field of wide events.",
+            });
+            return;
+        }
+        let value = match wide_value(&metric) {
+            Some(value) => value,
+            None => return,
+        };
+
+        let (series, data, metadata) = metric.into_parts();
+        let key = (data.timestamp().copied(), series.tags, data.kind);
+
+        let pending = self.pending.entry(key.clone()).or_insert_with(|| {
+            let mut log = LogEvent::default();
+            if let Some(tags) = &key.1 {
+                let tags = tags
+                    .iter_single()
+                    .map(|(name, value)| (name.to_string(), event::Value::from(value)))
+                    .collect::<BTreeMap<_, _>>();
+                log.insert(event_path!("tags"), tags);
+            }
+            log.insert(event_path!("kind"), kind_value(key.2));
+            PendingWideEvent { log, width: 0 }
+        });
+
+        let name = series.name;
+        let previous = match &name.namespace {
+            Some(namespace) => pending
+                .log
+                .insert(event_path!(namespace.as_str(), name.name.as_str()), value),
+            None => pending.log.insert(event_path!(name.name.as_str()), value),
+        };
+        pending.log.metadata_mut().merge(metadata);
+        if previous.is_none() {
+            pending.width += 1;
+        }
+
+        if pending.width >= self.max_width {
+            if let Some(pending) = self.pending.remove(&key) {
+                output.push(self.finish(key.0, pending.log).into());
+            }
+        }
+    }
+
+    fn flush_into(&mut self, output: &mut Vec<Event>) {
+        let pending = std::mem::take(&mut self.pending);
+        for ((timestamp, _, _), pending) in pending {
+            output.push(self.finish(timestamp, pending.log).into());
+        }
+    }
+
+    /// Returns whether `field` is a top-level field of wide events that doesn't hold a metric.
+    fn is_reserved_field(&self, field: &str) -> bool {
+        field == "tags"
+            || field == "kind"
+            || match self.metric_to_log.log_namespace {
+                LogNamespace::Legacy => {
+                    field == log_schema().timestamp_key() || field == log_schema().host_key()
+                }
+                LogNamespace::Vector => field == "timestamp",
+            }
+    }
+
+    fn finish(&self, timestamp: Option<DateTime<Utc>>, mut log: LogEvent) -> LogEvent {
+        match self.metric_to_log.log_namespace {
+            LogNamespace::Legacy => {
+                log.insert(
+                    log_schema().timestamp_key(),
+                    timestamp.unwrap_or_else(Utc::now),
+                );
+                if let Some(host) = log.remove_prune(self.metric_to_log.host_tag.as_str(), true) {
+                    log.insert(log_schema().host_key(), host);
+                }
+            }
+            LogNamespace::Vector => {
+                if let Some(timestamp) = timestamp {
+                    log.insert(event_path!("timestamp"), timestamp);
+                }
+                // Create vector metadata since this is used as a marker to see which namespace is used at runtime.
+                log.insert(
+                    (PathPrefix::Metadata, path!("vector")),
+                    value::Value::Object(BTreeMap::new()),
+                );
+            }
+        }
+        self.metric_to_log.flatten_tags(&mut log);
+        log
     }
 }
 
-impl FunctionTransform for MetricToLog {
-    fn transform(&mut self, output: &mut OutputBuffer, event: Event) {
-        let retval: Option<Event> = self
-            .transform_one(event.into_metric())
-            .map(|log| log.into());
-        output.extend(retval.into_iter())
+/// The value of a metric in a wide event. Counters and gauges are reduced to their value, other
+/// metric types keep their serialized value.
+fn wide_value(metric: &Metric) -> Option<event::Value> {
+    match metric.value() {
+        MetricValue::Counter { value } | MetricValue::Gauge { value } => {
+            match NotNan::new(*value) {
+                Ok(value) => Some(event::Value::Float(value)),
+                Err(_) => {
+                    emit!(MetricToLogWideEventError {
+                        name: metric.name(),
+                        reason: "Metric value is NaN.",
+                    });
+                    None
+                }
+            }
+        }
+        value => metric_value(value)
+            .map_err(|error| emit!(MetricToLogSerializeError { error }))
+            .ok()
+            .map(|(name, value)| event::Value::from(BTreeMap::from([(name.to_string(), value)]))),
+    }
+}
+
+impl TaskTransform<Event> for WideMetricToLog {
+    fn transform(
+        mut self: Box<Self>,
+        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let mut flush_stream = tokio::time::interval(self.flush_interval);
+
+        Box::pin(stream! {
+            let mut output = Vec::new();
+            let mut done = false;
+            while !done {
+                tokio::select! {
+                    _ = flush_stream.tick() => {
+                        self.flush_into(&mut output);
+                    },
+                    maybe_event = input_rx.next() => {
+                        match maybe_event {
+                            None => {
+                                self.flush_into(&mut output);
+                                done = true;
+                            }
+                            Some(event) => self.record(event.into_metric(), &mut output),
+                        }
+                    }
+                };
+                for event in output.drain(..) {
+                    yield event;
+                }
+            }
+        })
     }
 }
 
@@ -303,7 +988,7 @@ mod tests {
 
     use super::*;
     use crate::event::{
-        metric::{MetricKind, MetricTags, MetricValue, StatisticKind},
+        metric::{MetricKind, MetricTags, MetricValue, StatisticKind, TagValue},
         Metric, Value,
     };
     use crate::test_util::components::assert_transform_compliance;
@@ -320,6 +1005,11 @@ mod tests {
                 host_tag: Some("host".into()),
                 timezone: None,
                 log_namespace: Some(false),
+                wide_events: None,
+                flatten_tags: false,
+                tag_prefix: None,
+                cumulative_counters: None,
+                embed_metadata: false,
             };
             let (tx, rx) = mpsc::channel(1);
             let (topology, mut out) = create_topology(ReceiverStream::new(rx), config).await;
@@ -377,6 +1067,120 @@ mod tests {
         assert_eq!(log.metadata(), &metadata);
     }
 
+    #[tokio::test]
+    async fn transform_wide_events() {
+        assert_transform_compliance(async {
+            let config = MetricToLogConfig {
+                host_tag: Some("host".into()),
+                timezone: None,
+                log_namespace: Some(false),
+                wide_events: Some(WideEventsConfig {
+                    flush_interval_ms: 60_000,
+                    max_width: 2,
+                }),
+                flatten_tags: false,
+                tag_prefix: None,
+                cumulative_counters: None,
+                embed_metadata: false,
+            };
+            let (tx, rx) = mpsc::channel(3);
+            let (topology, mut out) = create_topology(ReceiverStream::new(rx), config).await;
+
+            let counter = Metric::new(
+                "requests",
+                MetricKind::Absolute,
+                MetricValue::Counter { value: 1.0 },
+            )
+            .with_tags(Some(tags()))
+            .with_timestamp(Some(ts()));
+            let incremental = Metric::new(
+                "errors",
+                MetricKind::Incremental,
+                MetricValue::Counter { value: 1.0 },
+            )
+            .with_tags(Some(tags()))
+            .with_timestamp(Some(ts()));
+            let reserved = Metric::new(
+                "tags",
+                MetricKind::Absolute,
+                MetricValue::Gauge { value: 1.0 },
+            )
+            .with_timestamp(Some(ts()));
+            let nan = Metric::new(
+                "load",
+                MetricKind::Absolute,
+                MetricValue::Gauge { value: f64::NAN },
+            )
+            .with_timestamp(Some(ts()));
+            let gauge = Metric::new(
+                "cpu",
+                MetricKind::Absolute,
+                MetricValue::Gauge { value: 0.5 },
+            )
+            .with_namespace(Some("system"))
+            .with_tags(Some(tags()))
+            .with_timestamp(Some(ts()));
+            let untagged = Metric::new(
+                "memory",
+                MetricKind::Absolute,
+                MetricValue::Gauge { value: 2.0 },
+            )
+            .with_timestamp(Some(ts()));
+
+            tx.send(untagged.into()).await.unwrap();
+            tx.send(incremental.into()).await.unwrap();
+            tx.send(reserved.into()).await.unwrap();
+            tx.send(nan.into()).await.unwrap();
+            tx.send(counter.into()).await.unwrap();
+            tx.send(gauge.into()).await.unwrap();
+
+            // The tagged absolute metrics reach the maximum width, and are emitted right away.
+            let log = out.recv().await.unwrap().into_log();
+            let collected: Vec<_> = log.all_fields().unwrap().collect();
+            assert_eq!(
+                collected,
+                vec![
+                    (String::from("host"), &Value::from("localhost")),
+                    (String::from("kind"), &Value::from("absolute")),
+                    (String::from("requests"), &Value::from(1.0)),
+                    (String::from("system.cpu"), &Value::from(0.5)),
+                    (String::from("tags.some_tag"), &Value::from("some_value")),
+                    (String::from("timestamp"), &Value::from(ts())),
+                ]
+            );
+
+            // The untagged and incremental metrics are flushed once the input ends, while the
+            // metrics named after a reserved field or with a NaN value are dropped.
+            drop(tx);
+            let log = out.recv().await.unwrap().into_log();
+            let collected: Vec<_> = log.all_fields().unwrap().collect();
+            assert_eq!(
+                collected,
+                vec![
+                    (String::from("kind"), &Value::from("absolute")),
+                    (String::from("memory"), &Value::from(2.0)),
+                    (String::from("timestamp"), &Value::from(ts())),
+                ]
+            );
+            let log = out.recv().await.unwrap().into_log();
+            let collected: Vec<_> = log.all_fields().unwrap().collect();
+            assert_eq!(
+                collected,
+                vec![
+                    (String::from("errors"), &Value::from(1.0)),
+                    (String::from("host"), &Value::from("localhost")),
+                    (String::from("kind"), &Value::from("incremental")),
+                    (String::from("tags.some_tag"), &Value::from("some_value")),
+                    (String::from("timestamp"), &Value::from(ts())),
+                ]
+            );
+
+            topology.stop().await;
+            assert_eq!(out.recv().await, None);
+        })
+        .await;
+    }
+
     #[tokio::test]
     async fn transform_gauge() {
         let gauge = Metric::new(
@@ -568,4 +1372,260 @@ mod tests {
         );
         assert_eq!(log.metadata(), &metadata);
     }
+
+    #[test]
+    fn transform_flattens_tags() {
+        let counter = Metric::new(
+            "counter",
+            MetricKind::Absolute,
+            MetricValue::Counter { value: 1.0 },
+        )
+        .with_tags(Some(metric_tags! {
+            "host" => "localhost",
+            "kind" => "colliding",
+            "region" => "eu",
+        }))
+        .with_timestamp(Some(ts()));
+
+        let metric_to_log = |prefix: &str| {
+            MetricToLog::new(Some("host".into()), LogNamespace::Legacy)
+                .with_flattened_tags(Some(prefix.into()))
+        };
+
+        let log = metric_to_log("").transform_one(counter.clone()).unwrap();
+        let collected: Vec<_> = log.all_fields().unwrap().collect();
+        assert_eq!(
+            collected,
+            vec![
+                (String::from("counter.value"), &Value::from(1.0)),
+                (String::from("host"), &Value::from("localhost")),
+                (String::from("kind"), &Value::from("absolute")),
+                (String::from("name"), &Value::from("counter")),
+                (String::from("region"), &Value::from("eu")),
+                (String::from("tags.kind"), &Value::from("colliding")),
+                (String::from("timestamp"), &Value::from(ts())),
+            ]
+        );
+
+        let log = metric_to_log("tag_").transform_one(counter).unwrap();
+        assert_eq!(log.get("tag_kind"), Some(&Value::from("colliding")));
+        assert_eq!(log.get("tag_region"), Some(&Value::from("eu")));
+        assert_eq!(log.get("tags"), None);
+    }
+
+    #[test]
+    fn transform_embeds_metadata() {
+        let mut counter = Metric::new(
+            "counter",
+            MetricKind::Incremental,
+            MetricValue::Counter { value: 1.0 },
+        )
+        .with_namespace(Some("app"))
+        .with_tags(Some(tags()))
+        .with_timestamp(Some(ts()))
+        .with_interval_ms(std::num::NonZeroU32::new(10_000));
+        counter
+            .metadata_mut()
+            .value_mut()
+            .insert(path!("source"), "statsd");
+
+        let log = MetricToLog::new(Some("host".into()), LogNamespace::Legacy)
+            .with_flattened_tags(Some(String::new()))
+            .with_embedded_metadata(true)
+            .transform_one(counter)
+            .unwrap();
+
+        assert_eq!(
+            log.get("schema_version"),
+            Some(&Value::from(METRIC_METADATA_SCHEMA_VERSION))
+        );
+        let collected: Vec<_> = log
+            .get("metric_metadata")
+            .unwrap()
+            .as_object()
+            .unwrap()
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.clone()))
+            .collect();
+        assert_eq!(
+            collected,
+            vec![
+                ("interval_ms", Value::from(10_000)),
+                ("kind", Value::from("incremental")),
+                ("name", Value::from("counter")),
+                ("namespace", Value::from("app")),
+                (
+                    "origin",
+                    Value::from(BTreeMap::from([(
+                        "source".to_string(),
+                        Value::from("statsd")
+                    )]))
+                ),
+                (
+                    "tags",
+                    Value::from(BTreeMap::from([
+                        ("host".to_string(), Value::from("localhost")),
+                        ("some_tag".to_string(), Value::from("some_value")),
+                    ]))
+                ),
+                ("timestamp", Value::from(ts())),
+            ]
+        );
+    }
+
+    #[test]
+    fn metric_fields_match_serialized_metric() {
+        let values = vec![
+            MetricValue::Counter { value: 1.5 },
+            MetricValue::Gauge { value: f64::NAN },
+            MetricValue::Set {
+                values: vec!["a".into(), "b".into()].into_iter().collect(),
+            },
+            MetricValue::Distribution {
+                samples: vector_core::samples![1.0 => 10, 2.0 => 20],
+                statistic: StatisticKind::Summary,
+            },
+            MetricValue::AggregatedHistogram {
+                buckets: vector_core::buckets![1.0 => 10, 2.0 => 5],
+                count: 15,
+                sum: 20.0,
+            },
+            MetricValue::AggregatedSummary {
+                quantiles: vector_core::quantiles![0.5 => 1.0, 0.99 => 3.0],
+                count: 15,
+                sum: 20.0,
+            },
+        ];
+        let mut tags = tags();
+        tags.set_multi_value(
+            "multi".into(),
+            vec![
+                TagValue::from("one".to_string()),
+                TagValue::Bare,
+                TagValue::from("two".to_string()),
+            ],
+        );
+
+        for value in values {
+            let metric = Metric::new("metric", MetricKind::Incremental, value)
+                .with_namespace(Some("app"))
+                .with_tags(Some(tags.clone()))
+                .with_interval_ms(std::num::NonZeroU32::new(10_000));
+
+            let serialized = match serde_json::to_value(&metric).unwrap() {
+                serde_json::Value::Object(object) => object
+                    .into_iter()
+                    .map(|(key, value)| (key, Value::from(value)))
+                    .collect::<BTreeMap<_, _>>(),
+                _ => unreachable!("metrics are serialized as objects"),
+            };
+            assert_eq!(metric_fields(&metric).unwrap(), serialized);
+        }
+    }
+
+    #[test]
+    fn transform_all_converts_batches() {
+        let metrics = (0..3)
+            .map(|i| {
+                Metric::new(
+                    format!("counter_{}", i),
+                    MetricKind::Incremental,
+                    MetricValue::Counter { value: 1.0 },
+                )
+            })
+            .collect::<Vec<_>>();
+        let mut metric_to_log = MetricToLog::new(Some("host".into()), LogNamespace::Vector);
+        let mut output =
+            TransformOutputsBuf::new_with_capacity(vec![Output::default(DataType::Log)], 3);
+
+        metric_to_log.transform_all(EventArray::from(metrics), &mut output);
+
+        let logs = output
+            .drain()
+            .map(|event| event.into_log())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            logs.iter()
+                .map(|log| log.get("name").unwrap().to_string_lossy())
+                .collect::<Vec<_>>(),
+            vec!["counter_0", "counter_1", "counter_2"]
+        );
+        // Metrics without a timestamp have none in the `Vector` namespace.
+        assert_eq!(logs[0].get("timestamp"), None);
+    }
+
+    #[test]
+    fn cumulative_counters_keep_running_totals() {
+        let mut counters =
+            CumulativeCounters::new(&CumulativeCountersConfig { state_ttl_secs: 60 });
+        let counter = |name: &str, value: f64| {
+            Metric::new(
+                name,
+                MetricKind::Incremental,
+                MetricValue::Counter { value },
+            )
+            .with_tags(Some(tags()))
+        };
+        let start = Instant::now();
+
+        let first = counters.convert(counter("requests", 2.0), start);
+        assert_eq!(first.kind(), MetricKind::Absolute);
+        assert_eq!(first.value(), &MetricValue::Counter { value: 2.0 });
+
+        let second = counters.convert(counter("requests", 3.0), start + Duration::from_secs(30));
+        assert_eq!(second.value(), &MetricValue::Counter { value: 5.0 });
+
+        let other = counters.convert(counter("errors", 1.0), start + Duration::from_secs(30));
+        assert_eq!(other.value(), &MetricValue::Counter { value: 1.0 });
+
+        // Other metrics are passed through as they are.
+        let gauge = Metric::new(
+            "gauge",
+            MetricKind::Incremental,
+            MetricValue::Gauge { value: 1.0 },
+        );
+        assert_eq!(counters.convert(gauge.clone(), start), gauge);
+    }
+
+    #[test]
+    fn cumulative_counters_forget_stale_series() {
+        let mut counters =
+            CumulativeCounters::new(&CumulativeCountersConfig { state_ttl_secs: 60 });
+        let counter = |value: f64| {
+            Metric::new(
+                "requests",
+                MetricKind::Incremental,
+                MetricValue::Counter { value },
+            )
+        };
+        let start = Instant::now();
+
+        counters.convert(counter(2.0), start);
+        let restarted = counters.convert(counter(3.0), start + Duration::from_secs(120));
+        assert_eq!(restarted.value(), &MetricValue::Counter { value: 3.0 });
+        assert_eq!(counters.totals.len(), 1);
+    }
+
+    #[test]
+    fn cumulative_counters_forget_stale_series_between_sweeps() {
+        let mut counters =
+            CumulativeCounters::new(&CumulativeCountersConfig { state_ttl_secs: 60 });
+        let counter = |name: &str, value: f64| {
+            Metric::new(
+                name,
+                MetricKind::Incremental,
+                MetricValue::Counter { value },
+            )
+        };
+        let start = counters.last_sweep;
+
+        counters.convert(counter("requests", 2.0), start + Duration::from_secs(10));
+        // Sweeps, while `requests` isn't stale yet.
+        counters.convert(counter("errors", 1.0), start + Duration::from_secs(60));
+        assert_eq!(counters.totals.len(), 2);
+
+        let restarted =
+            counters.convert(counter("requests", 3.0), start + Duration::from_secs(100));
+        assert_eq!(restarted.value(), &MetricValue::Counter { value: 3.0 });
+    }
 }
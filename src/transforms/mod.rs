@@ -4,6 +4,8 @@ use std::collections::HashSet;
 use enum_dispatch::enum_dispatch;
 use snafu::Snafu;
 
+#[cfg(feature = "transforms-adaptive_sample")]
+pub mod adaptive_sample;
 #[cfg(feature = "transforms-aggregate")]
 pub mod aggregate;
 #[cfg(feature = "transforms-aws_ec2_metadata")]
@@ -74,6 +76,10 @@ pub enum MetricTagsValues {
 #[serde(tag = "type", rename_all = "snake_case")]
 #[enum_dispatch(TransformConfig)]
 pub enum Transforms {
+    /// Adaptive sample.
+    #[cfg(feature = "transforms-adaptive_sample")]
+    AdaptiveSample(#[configurable(derived)] adaptive_sample::AdaptiveSampleConfig),
+
     /// Aggregate.
     #[cfg(feature = "transforms-aggregate")]
     Aggregate(#[configurable(derived)] aggregate::AggregateConfig),
@@ -149,6 +155,8 @@ impl NamedComponent for Transforms {
 
     fn get_component_name(&self) -> &'static str {
         match self {
+            #[cfg(feature = "transforms-adaptive_sample")]
+            Transforms::AdaptiveSample(config) => config.get_component_name(),
             #[cfg(feature = "transforms-aggregate")]
             Transforms::Aggregate(config) => config.get_component_name(),
             #[cfg(feature = "transforms-aws_ec2_metadata")]
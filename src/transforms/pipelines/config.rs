@@ -1,5 +1,6 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, time::Instant};
 
+use vector_common::internal_event::{InternalEventHandle as _, Registered};
 use vector_config::{configurable_component, NamedComponent};
 use vector_core::config::LogNamespace;
 use vector_core::{
@@ -9,13 +10,16 @@ use vector_core::{
     transform::{SyncTransform, Transform, TransformOutputsBuf},
 };
 
+use super::DROPPED;
 use crate::{
     conditions::{AnyCondition, Condition},
     config::{
         ComponentKey, DataType, InnerTopology, InnerTopologyTransform, Inputs, Output,
         TransformConfig, TransformContext,
     },
-    transforms::Transforms,
+    internal_events::{PipelineBatch, PipelineEventsProcessed, PipelineTransformEventsProcessed},
+    register,
+    transforms::{route::UNMATCHED_ROUTE, Transforms},
 };
 
 // 64 is a lowish number and arbitrarily chosen: there is no magic to this magic
@@ -33,9 +37,20 @@ pub struct PipelineConfig {
     filter: Option<AnyCondition>,
 
     /// A list of sequential transforms that will process any event that is passed to the pipeline.
+    ///
+    /// Events a transform sends to its `dropped` output, such as `remap` with `reroute_dropped`
+    /// enabled, are not passed to the following transforms but are sent to the `dropped` output of
+    /// the pipeline instead. Events a transform discards without sending them to any output, such
+    /// as those failing `remap` without `reroute_dropped`, or those rejected by a `filter`
+    /// transform, are not sent to the `dropped` output.
     #[serde(default)]
     #[configurable(metadata(docs::cycle_entrypoint))]
     transforms: Vec<Transforms>,
+
+    /// Whether events not matching `filter` are sent to the `_unmatched` output instead of the
+    /// default one, which is set for the pipelines of an exclusive `pipelines` transform.
+    #[serde(skip)]
+    exclusive: bool,
 }
 
 impl_generate_config_from_default!(PipelineConfig);
@@ -77,14 +92,15 @@ impl TransformConfig for PipelineConfig {
             return Err(format!("empty pipeline: {}", self.name).into());
         }
         // Today we make the assumption that to be a valid pipeline transform
-        // the transform CANNOT have named outputs. This assumption might break
-        // in the future so, to avoid panics, we instead make building a
-        // pipeline with such transforms an error.
-        for transform in &self.transforms {
-            if transform
-                .outputs(&ctx.merged_schema_definition, ctx.schema.log_namespace())
-                .len()
-                > 1
+        // the transform CANNOT have named outputs, other than the `dropped`
+        // output which is collected into the pipeline's own `dropped` output.
+        // This assumption might break in the future so, to avoid panics, we
+        // instead make building a pipeline with such transforms an error.
+        let chain = self.chain(&ctx.merged_schema_definition, ctx.schema.log_namespace());
+        for (transform, (_, outputs)) in self.transforms.iter().zip(&chain) {
+            if outputs
+                .iter()
+                .any(|output| output.port.is_some() && output.port.as_deref() != Some(DROPPED))
             {
                 return Err(format!(
                     "pipeline {} has transform of type {} with a named output, unsupported",
@@ -96,42 +112,90 @@ impl TransformConfig for PipelineConfig {
         }
 
         let mut transforms = Vec::with_capacity(self.transforms.len());
-        for config in &self.transforms {
-            let transform = match config.build(ctx).await? {
+        for (transform_index, (config, (definition, outputs))) in
+            self.transforms.iter().zip(chain).enumerate()
+        {
+            // Each transform is built against the schema of the events output by the one before
+            // it, rather than the schema of the events input to the pipeline.
+            let context = TransformContext {
+                schema_definitions: outputs
+                    .into_iter()
+                    .filter_map(|output| Some((output.port, output.log_schema_definition?)))
+                    .collect(),
+                merged_schema_definition: definition,
+                ..ctx.clone()
+            };
+            let transform = match config.build(&context).await? {
                 Transform::Function(transform) => Box::new(transform),
                 Transform::Synchronous(transform) => transform,
                 _ => return Err(format!("non-sync transform in pipeline: {:?}", config).into()),
             };
-            transforms.push(transform);
+            let events_processed = register!(PipelineTransformEventsProcessed {
+                pipeline: self.name.clone(),
+                transform_index,
+                transform_type: config.get_component_name(),
+            });
+            transforms.push((transform, events_processed));
         }
 
         let buf_in = TransformOutputsBuf::new_with_capacity(
-            vec![Output::default(DataType::all())],
+            vec![
+                Output::default(DataType::all()),
+                Output::default(DataType::all()).with_port(DROPPED),
+            ],
             INTERIOR_BUFFER_SIZE,
         );
         let buf_out = buf_in.clone();
         Ok(Transform::Synchronous(Box::new(Pipeline {
             condition,
+            exclusive: self.exclusive,
             transforms,
+            events_processed: register!(PipelineEventsProcessed {
+                pipeline: self.name.clone(),
+            }),
             buf_in,
             buf_out,
         })))
     }
 
     fn input(&self) -> Input {
-        if let Some(transform) = self.transforms.first() {
-            transform.input()
-        } else {
-            panic!("pipeline {} does not have transforms", self.name)
-        }
+        self.transforms
+            .first()
+            .map_or_else(Input::all, |transform| transform.input())
     }
 
     fn outputs(&self, schema: &schema::Definition, log_namespace: LogNamespace) -> Vec<Output> {
-        if let Some(transform) = self.transforms.last() {
-            transform.outputs(schema, log_namespace)
+        // The events processed by the pipeline are output by its last transform, and without
+        // transforms the events are output as is.
+        let (ty, definition) = self
+            .chain(schema, log_namespace)
+            .pop()
+            .and_then(|(_, outputs)| outputs.into_iter().find(|output| output.port.is_none()))
+            .and_then(|output| Some((output.ty, output.log_schema_definition?)))
+            .unwrap_or_else(|| (self.input().data_type(), schema.clone()));
+
+        // Events not matching the filter of a non-exclusive pipeline are output as is too.
+        let (ty, definition) = if self.filter.is_some() && !self.exclusive {
+            (
+                ty | self.input().data_type(),
+                definition.merge(schema.clone()),
+            )
         } else {
-            panic!("pipeline {} does not have transforms", self.name)
+            (ty, definition)
+        };
+
+        let mut outputs = vec![
+            Output::default(ty).with_schema_definition(definition),
+            Output::default(DataType::all()).with_port(DROPPED),
+        ];
+        if self.exclusive {
+            outputs.push(
+                Output::default(self.input().data_type())
+                    .with_port(UNMATCHED_ROUTE)
+                    .with_schema_definition(schema.clone()),
+            );
         }
+        outputs
     }
 
     fn enable_concurrency(&self) -> bool {
@@ -140,6 +204,41 @@ impl TransformConfig for PipelineConfig {
 }
 
 impl PipelineConfig {
+    /// Returns the outputs of each transform of the pipeline, along with the schema definition of
+    /// the events it receives, which are those output by the transform before it.
+    ///
+    /// Outputs not defining a schema pass their events through, so they are given the schema
+    /// definition of the events received by their transform.
+    fn chain(
+        &self,
+        schema: &schema::Definition,
+        log_namespace: LogNamespace,
+    ) -> Vec<(schema::Definition, Vec<Output>)> {
+        let mut definition = schema.clone();
+        let mut chain = Vec::with_capacity(self.transforms.len());
+        for transform in &self.transforms {
+            let outputs = transform
+                .outputs(&definition, log_namespace)
+                .into_iter()
+                .map(|mut output| {
+                    output
+                        .log_schema_definition
+                        .get_or_insert_with(|| definition.clone());
+                    output
+                })
+                .collect::<Vec<_>>();
+            let next = outputs
+                .iter()
+                .find(|output| output.port.is_none())
+                .and_then(|output| output.log_schema_definition.clone());
+            chain.push((definition.clone(), outputs));
+            if let Some(next) = next {
+                definition = next;
+            }
+        }
+        chain
+    }
+
     pub(super) fn expand(
         &mut self,
         name: &ComponentKey,
@@ -157,6 +256,9 @@ impl PipelineConfig {
         result
             .outputs
             .push((name.clone(), vec![Output::default(DataType::all())]));
+        result
+            .named_outputs
+            .insert(DROPPED.to_string(), vec![name.port(DROPPED)]);
         Ok(Some(result))
     }
 }
@@ -164,7 +266,12 @@ impl PipelineConfig {
 #[derive(Clone)]
 struct Pipeline {
     condition: Option<Condition>,
-    transforms: Vec<Box<dyn SyncTransform>>,
+    exclusive: bool,
+    transforms: Vec<(
+        Box<dyn SyncTransform>,
+        Registered<PipelineTransformEventsProcessed>,
+    )>,
+    events_processed: Registered<PipelineEventsProcessed>,
     buf_in: TransformOutputsBuf,
     buf_out: TransformOutputsBuf,
 }
@@ -181,8 +288,9 @@ impl SyncTransform for Pipeline {
         // A `Pipeline` is a compound of sub-transforms. That is, it's a
         // transform that runs other transforms. To achieve this we gate all
         // incoming Events by whether they match the pipeline condition or not
-        // and, if they do not, immediately output them. If the Event does match
-        // our condition we queue it up for further processing.
+        // and, if they do not, immediately output them, to the `_unmatched`
+        // output if the pipeline is exclusive. If the Event does match our
+        // condition we queue it up for further processing.
         //
         // Here our queue is the TransformOutputsBuf. In the next chunk of code
         // that follows we do the aforementioned filtering and push into
@@ -193,6 +301,8 @@ impl SyncTransform for Pipeline {
                 let (result, event) = condition.check(event);
                 if result {
                     self.buf_out.push(event);
+                } else if self.exclusive {
+                    output.push_named(UNMATCHED_ROUTE, event);
                 } else {
                     output.push(event);
                 }
@@ -200,19 +310,45 @@ impl SyncTransform for Pipeline {
         } else {
             self.buf_out.extend(ev_container);
         }
+        let pipeline_received = self.buf_out.len();
+        let pipeline_started = Instant::now();
 
         // `buf_out` is now primed with Events. Note that the struct also has a
         // `buf_in`. The pipeline now runs each sub-transform in serial,
         // flip-flopping the in and out TransformOutputsBuf so that the input of
         // one transform becomes the output of the next, after it has been
-        // emptied. Once all the transforms are run, the Events in `buf_out` are
+        // emptied. Events a sub-transform sends to its `dropped` output are
+        // taken out of the chain and emitted to our own `dropped` output right
+        // away. Once all the transforms are run, the Events in `buf_out` are
         // emitted to `output`. When this function runs again `buf_out` is
         // empty, `buf_in` is empty and the process is ready to begin again.
-        for transform in &mut self.transforms {
+        //
+        // As interior transforms are hidden from the topology, the events each
+        // of them receives and sends are accounted for here instead.
+        for (transform, events_processed) in &mut self.transforms {
             std::mem::swap(&mut self.buf_out, &mut self.buf_in);
+            let received = self.buf_in.len();
+            let started = Instant::now();
             for event in self.buf_in.drain() {
                 transform.transform(event, &mut self.buf_out);
             }
+            for event in self.buf_out.drain_named(DROPPED) {
+                output.push_named(DROPPED, event);
+            }
+            if received > 0 {
+                events_processed.emit(PipelineBatch {
+                    received,
+                    sent: self.buf_out.len(),
+                    elapsed: started.elapsed(),
+                });
+            }
+        }
+        if pipeline_received > 0 {
+            self.events_processed.emit(PipelineBatch {
+                received: pipeline_received,
+                sent: self.buf_out.len(),
+                elapsed: pipeline_started.elapsed(),
+            });
         }
         output.extend(self.buf_out.drain());
     }
@@ -256,15 +392,22 @@ impl EventTypeConfig {
     /// This function expands the sub-pipelines according to the order passed by
     /// the user, or, absent an explicit order, by the position of the
     /// sub-pipeline in the configuration file.
+    ///
+    /// If `exclusive` is set, only the events not matched by a sub-pipeline are
+    /// passed on to the next one, and the events matched by each sub-pipeline
+    /// are output as soon as it has processed them.
     pub(super) fn expand(
         &mut self,
         name: &ComponentKey,
         inputs: &[String],
+        exclusive: bool,
     ) -> crate::Result<Option<InnerTopology>> {
         let mut result = InnerTopology::default();
         let mut next_inputs = inputs.to_vec();
+        let mut unmatched = None;
         for (pipeline_index, pipeline_config) in self.0.iter_mut().enumerate() {
             let pipeline_name = name.join(pipeline_index);
+            pipeline_config.exclusive = exclusive;
             let topology = pipeline_config
                 .expand(&pipeline_name, &next_inputs)?
                 .ok_or_else(|| {
@@ -274,10 +417,23 @@ impl EventTypeConfig {
                     )
                 })?;
             result.inner.extend(topology.inner.into_iter());
-            result.outputs = topology.outputs;
-            next_inputs = result.outputs();
+            result.merge_named_outputs(topology.named_outputs);
+            if exclusive {
+                result.outputs.extend(topology.outputs);
+                next_inputs = vec![pipeline_name.port(UNMATCHED_ROUTE)];
+                unmatched = Some(pipeline_name);
+            } else {
+                result.outputs = topology.outputs;
+                next_inputs = result.outputs();
+            }
+        }
+        // The events matched by none of the exclusive sub-pipelines are output as is.
+        if let Some(pipeline_name) = unmatched {
+            result.outputs.push((
+                pipeline_name,
+                vec![Output::default(DataType::all()).with_port(UNMATCHED_ROUTE)],
+            ));
         }
-        //
         Ok(Some(result))
     }
 }
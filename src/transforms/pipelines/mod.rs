@@ -47,62 +47,78 @@
 //! ```
 //!
 //! The pipelines transform will expand individually each pipeline and adjust all the inputs accordingly.
-//! Once transpiled, the topology will have the same shape than the following configuration.
+//! Each pipeline is expanded into a single transform, which runs the events matching its filter
+//! through its transforms, and outputs the other events as is. Once transpiled, the topology will
+//! have the same shape than the following configuration.
 //!
 //! ```toml
-//! [transforms.my_pipelines_type_router]
+//! [transforms."my_pipelines.type_router"]
 //! inputs = ["syslog"]
+//! type = "route"
+//! route.logs.type = "is_log"
+//! route.metrics.type = "is_metric"
 //!
-//! [transforms.my_pipelines_logs_0_transform_0]
-//! inputs = ["my_pipelines_type_router.logs"]
-//! # any transform configuration
+//! [transforms."my_pipelines.logs.0"]
+//! inputs = ["my_pipelines.type_router.logs"]
+//! # the "foo pipeline", without filter, and its two transforms
 //!
-//! [transforms.my_pipelines_logs_0_transform_1]
-//! inputs = ["my_pipelines_logs_0_transform_0"]
-//! # any transform configuration
+//! [transforms."my_pipelines.logs.1"]
+//! inputs = ["my_pipelines.logs.0"]
+//! # the "bar pipeline", its filter, and its transform
 //!
-//! [transforms.my_pipelines_logs_1_filter]
-//! inputs = ["my_pipelines_logs_0_transform_1"]
-//! type = "filter"
-//! condition.type = "vrl"
-//! condition.source = """some condition here"""
+//! [transforms."my_pipelines.metrics.0"]
+//! inputs = ["my_pipelines.type_router.metrics"]
+//! # the "hello pipeline", its filter, and its two transforms
 //!
-//! [transforms.my_pipelines_logs_1_transform_0]
-//! inputs = ["my_pipelines_logs_1_filter.success"]
-//! # any transform configuration
+//! [transforms."my_pipelines.metrics.1"]
+//! inputs = ["my_pipelines.metrics.0"]
+//! # the "world pipeline", without filter, and its transform
 //!
-//! [transforms.my_pipelines_metrics_0_filter]
-//! inputs = ["my_pipelines_type_router.metrics"]
-//! type = "filter"
-//! condition.type = "vrl"
-//! condition.source = """some condition here"""
+//! [sinks.output]
+//! inputs = [
+//!     # the events that are neither logs nor metrics
+//!     "my_pipelines.type_router._unmatched",
+//!     # the events from the last logs pipeline, including those not matching its filter
+//!     "my_pipelines.logs.1",
+//!     "my_pipelines.metrics.1",
+//! ]
+//! # any sink configuration
+//! ```
 //!
-//! [transforms.my_pipelines_metrics_0_transform_0]
-//! inputs = ["my_pipelines_metrics_0_filter.success"]
-//! # any transform configuration
+//! With `exclusive = true`, an event is processed by the first pipeline whose filter it matches
+//! only. Each pipeline then sends the events it doesn't match to its `_unmatched` output, which is
+//! the input of the next pipeline, and the events it processed straight to the outputs of the
+//! pipelines transform. The logs pipelines above would then have the same shape as the following.
 //!
-//! [transforms.my_pipelines_metrics_0_transform_1]
-//! inputs = ["my_pipelines_metrics_0_transform_0"]
-//! # any transform configuration
+//! ```toml
+//! [transforms."my_pipelines.logs.0"]
+//! inputs = ["my_pipelines.type_router.logs"]
+//! # the first pipeline, whose filter matches every event
 //!
-//! [transforms.my_pipelines_metrics_1_transform_0]
-//! inputs = [
-//!     # the events filtered from the previous transform are forwarded
-//!     "my_pipelines_metrics_0_filter._dropped",
-//!     "my_pipelines_metrics_0_transform_1",
-//! ]
-//! # any transform configuration
+//! [transforms."my_pipelines.logs.1"]
+//! inputs = ["my_pipelines.logs.0._unmatched"]
+//! # the second pipeline
 //!
 //! [sinks.output]
 //! inputs = [
-//!     "my_pipelines_type_router._dropped",
-//!     # the events from the last logs pipeline are forwarded here
-//!     "my_pipelines_logs_1_filter._dropped",
-//!     "my_pipelines_logs_1_transform_0",
-//!     "my_pipelines_metrics_1_transform_0",
+//!     "my_pipelines.type_router._unmatched",
+//!     "my_pipelines.logs.0",
+//!     "my_pipelines.logs.1",
+//!     "my_pipelines.logs.1._unmatched",
 //! ]
-//! # any sink configuration
 //! ```
+//!
+//! Each pipeline also has a `dropped` output collecting the events its transforms route to their
+//! own `dropped` output. They are all gathered behind the `dropped` output of the pipelines
+//! transform, so `inputs = ["my_pipelines.dropped"]` is expanded to
+//! `["my_pipelines.logs.0.dropped", "my_pipelines.logs.1.dropped", ...]`. Only the events the
+//! transforms route to an output reach it: the events they discard outright, such as those failing
+//! `remap` without `reroute_dropped`, are still lost.
+//!
+//! The transforms of a pipeline don't need to all handle the same type of events: each of them
+//! receives the events, and the schema definition, output by the one before it. A metrics pipeline
+//! can for instance convert its metrics with `metric_to_log` and then process the resulting logs
+//! with `remap`.
 mod config;
 pub use self::config::PipelineConfig;
 
@@ -128,6 +144,9 @@ use crate::{
     transforms::route::{RouteConfig, UNMATCHED_ROUTE},
 };
 
+/// The name of the output events dropped by a pipeline's transforms are sent to.
+pub(crate) const DROPPED: &str = "dropped";
+
 /// Configuration for the `pipelines` transform.
 #[configurable_component(transform("pipelines"))]
 #[derive(Clone, Debug, Default)]
@@ -143,6 +162,15 @@ pub struct PipelinesConfig {
     /// Configuration for the traces-specific side of the pipeline.
     #[serde(default)]
     traces: EventTypeConfig,
+
+    /// Whether or not the pipelines of each event type are exclusive.
+    ///
+    /// When enabled, the pipelines are evaluated in order and an event is only processed by the
+    /// first pipeline whose filter it matches, like in a routing table. Events matching none of the
+    /// pipelines are forwarded as is. By default, an event is processed by every pipeline whose
+    /// filter it matches.
+    #[serde(default)]
+    exclusive: bool,
 }
 
 #[cfg(test)]
@@ -195,6 +223,7 @@ impl TransformConfig for PipelinesConfig {
                 router_name.clone(),
                 vec![Output::default(DataType::all()).with_port(UNMATCHED_ROUTE)],
             )],
+            named_outputs: Default::default(),
         };
         let mut conditions = IndexMap::new();
         if !self.logs.is_empty() {
@@ -206,10 +235,11 @@ impl TransformConfig for PipelinesConfig {
             let logs_inputs = vec![router_name.port("logs")];
             let inner_topology = self
                 .logs
-                .expand(&logs_route, &logs_inputs)?
+                .expand(&logs_route, &logs_inputs, self.exclusive)?
                 .ok_or("Unable to expand pipeline stream")?;
             result.inner.extend(inner_topology.inner.into_iter());
             result.outputs.extend(inner_topology.outputs.into_iter());
+            result.merge_named_outputs(inner_topology.named_outputs);
         }
         if !self.metrics.is_empty() {
             let metrics_route = name.join("metrics");
@@ -220,10 +250,11 @@ impl TransformConfig for PipelinesConfig {
             let metrics_inputs = vec![router_name.port("metrics")];
             let inner_topology = self
                 .metrics
-                .expand(&metrics_route, &metrics_inputs)?
+                .expand(&metrics_route, &metrics_inputs, self.exclusive)?
                 .ok_or("Unable to expand pipeline stream")?;
             result.inner.extend(inner_topology.inner.into_iter());
             result.outputs.extend(inner_topology.outputs.into_iter());
+            result.merge_named_outputs(inner_topology.named_outputs);
         }
         if !self.traces.is_empty() {
             let traces_route = name.join("traces");
@@ -234,10 +265,11 @@ impl TransformConfig for PipelinesConfig {
             let traces_inputs = vec![router_name.port("traces")];
             let inner_topology = self
                 .traces
-                .expand(&traces_route, &traces_inputs)?
+                .expand(&traces_route, &traces_inputs, self.exclusive)?
                 .ok_or("Unable to expand pipeline stream")?;
             result.inner.extend(inner_topology.inner.into_iter());
             result.outputs.extend(inner_topology.outputs.into_iter());
+            result.merge_named_outputs(inner_topology.named_outputs);
         }
         result.inner.insert(
             router_name,
@@ -254,7 +286,10 @@ impl TransformConfig for PipelinesConfig {
     }
 
     fn outputs(&self, _: &schema::Definition, _: LogNamespace) -> Vec<Output> {
-        vec![Output::default(DataType::all())]
+        vec![
+            Output::default(DataType::all()),
+            Output::default(DataType::all()).with_port(DROPPED),
+        ]
     }
 
     fn nestable(&self, parents: &HashSet<&'static str>) -> bool {
@@ -300,7 +335,7 @@ mod tests {
     use tokio::sync::mpsc;
     use tokio_stream::wrappers::ReceiverStream;
 
-    use super::{GenerateConfig, PipelinesConfig};
+    use super::{schema, DataType, GenerateConfig, PipelinesConfig, TransformConfig};
     use crate::config::{ComponentKey, TransformOuter};
     use crate::test_util::components::assert_transform_compliance;
     use crate::transforms::test::create_topology;
@@ -364,6 +399,194 @@ mod tests {
             expansions["foo"],
             vec!["foo.type_router._unmatched", "foo.logs.1"]
         );
+        assert_eq!(
+            expansions["foo.dropped"],
+            vec!["foo.logs.0.dropped", "foo.logs.1.dropped"]
+        );
+    }
+
+    #[test]
+    fn expanding_exclusive() {
+        let mut config: PipelinesConfig = PipelinesConfig::generate_config().try_into().unwrap();
+        config.exclusive = true;
+        let outer = TransformOuter::new(vec!["source".to_string()], config);
+        let mut transforms = IndexMap::new();
+        let mut expansions = IndexMap::new();
+        outer
+            .expand(
+                ComponentKey::from("foo"),
+                &HashSet::new(),
+                &mut transforms,
+                &mut expansions,
+            )
+            .unwrap();
+
+        assert_eq!(
+            transforms[&ComponentKey::from("foo.logs.1")].inputs,
+            vec!["foo.logs.0._unmatched".to_string()]
+        );
+        assert_eq!(
+            expansions[&ComponentKey::from("foo")]
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "foo.type_router._unmatched",
+                "foo.logs.0",
+                "foo.logs.1",
+                "foo.logs.1._unmatched"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn routes_events_to_first_matching_exclusive_pipeline() {
+        use crate::event::LogEvent;
+
+        let config = toml::from_str::<PipelinesConfig>(indoc::indoc! {r#"
+            exclusive = true
+
+            [[logs]]
+            name = "errors"
+            filter.type = "vrl"
+            filter.source = '.level == "error"'
+
+            [[logs.transforms]]
+            type = "remap"
+            source = '.pipeline = "errors"'
+
+            [[logs]]
+            name = "all"
+
+            [[logs.transforms]]
+            type = "remap"
+            source = '.pipeline = "all"'
+        "#})
+        .unwrap();
+
+        let (tx, rx) = mpsc::channel(1);
+        let (topology, mut out) = create_topology(ReceiverStream::new(rx), config).await;
+
+        let mut error = LogEvent::from("error");
+        error.insert("level", "error");
+        tx.send(error.into()).await.unwrap();
+        let output = out.recv().await.unwrap().into_log();
+        assert_eq!(output["pipeline"], "errors".into());
+
+        tx.send(LogEvent::from("info").into()).await.unwrap();
+        let output = out.recv().await.unwrap().into_log();
+        assert_eq!(output["pipeline"], "all".into());
+
+        drop(tx);
+        topology.stop().await;
+        assert_eq!(out.recv().await, None);
+    }
+
+    #[test]
+    #[cfg(feature = "transforms-metric_to_log")]
+    fn propagates_schema_through_pipeline_transforms() {
+        use vector_core::config::LogNamespace;
+
+        use super::PipelineConfig;
+        use crate::transforms::{metric_to_log::MetricToLogConfig, remap::RemapConfig};
+
+        let metric_to_log = MetricToLogConfig::default()
+            .outputs(&schema::Definition::any(), LogNamespace::Legacy)
+            .remove(0)
+            .log_schema_definition
+            .unwrap();
+        let remap = RemapConfig {
+            source: Some(".converted = true".to_string()),
+            ..Default::default()
+        };
+        let expected = remap
+            .outputs(&metric_to_log, LogNamespace::Legacy)
+            .remove(0)
+            .log_schema_definition;
+        let pipeline = toml::from_str::<PipelineConfig>(indoc::indoc! {r#"
+            name = "metrics"
+
+            [[transforms]]
+            type = "metric_to_log"
+
+            [[transforms]]
+            type = "remap"
+            source = ".converted = true"
+        "#})
+        .unwrap();
+
+        assert_eq!(pipeline.input().data_type(), DataType::Metric);
+        let outputs = pipeline.outputs(&schema::Definition::any(), LogNamespace::Legacy);
+        let output = &outputs[0];
+        assert_eq!(output.port, None);
+        assert_eq!(output.ty, DataType::Log);
+        // The schema `remap` is given is the one output by `metric_to_log`.
+        assert_eq!(output.log_schema_definition, expected);
+
+        let filtered = toml::from_str::<PipelineConfig>(indoc::indoc! {r#"
+            name = "metrics"
+            filter.type = "vrl"
+            filter.source = ".name == \"requests\""
+
+            [[transforms]]
+            type = "metric_to_log"
+        "#})
+        .unwrap();
+
+        // Metrics not matching the filter are output as is.
+        let outputs = filtered.outputs(&schema::Definition::any(), LogNamespace::Legacy);
+        assert_eq!(outputs[0].ty, DataType::Log | DataType::Metric);
+    }
+
+    #[test]
+    fn empty_pipeline_passes_events_through() {
+        use vector_core::config::LogNamespace;
+
+        use super::PipelineConfig;
+
+        let pipeline = toml::from_str::<PipelineConfig>(r#"name = "empty""#).unwrap();
+        let definition = schema::Definition::empty_legacy_namespace();
+
+        assert_eq!(pipeline.input().data_type(), DataType::all());
+        let outputs = pipeline.outputs(&definition, LogNamespace::Legacy);
+        assert_eq!(outputs[0].ty, DataType::all());
+        assert_eq!(outputs[0].log_schema_definition, Some(definition));
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "transforms-metric_to_log")]
+    async fn converts_metrics_to_logs_in_pipeline() {
+        use crate::event::{Metric, MetricKind, MetricValue};
+
+        let config = toml::from_str::<PipelinesConfig>(indoc::indoc! {r#"
+            [[metrics]]
+            name = "to logs"
+
+            [[metrics.transforms]]
+            type = "metric_to_log"
+
+            [[metrics.transforms]]
+            type = "remap"
+            source = ".converted = true"
+        "#})
+        .unwrap();
+
+        let (tx, rx) = mpsc::channel(1);
+        let (topology, mut out) = create_topology(ReceiverStream::new(rx), config).await;
+
+        let metric = Metric::new(
+            "requests",
+            MetricKind::Absolute,
+            MetricValue::Counter { value: 1.0 },
+        );
+        tx.send(metric.into()).await.unwrap();
+        let output = out.recv().await.unwrap().into_log();
+        assert_eq!(output["name"], "requests".into());
+        assert_eq!(output["converted"], true.into());
+
+        drop(tx);
+        topology.stop().await;
+        assert_eq!(out.recv().await, None);
     }
 
     #[tokio::test]
@@ -406,4 +629,63 @@ mod tests {
         })
         .await;
     }
+
+    #[tokio::test]
+    async fn emits_pipeline_metrics() {
+        use crate::event::{LogEvent, MetricValue};
+        use crate::metrics::Controller;
+
+        crate::metrics::init_test();
+
+        let config = toml::from_str::<PipelinesConfig>(indoc::indoc! {r#"
+            [[logs]]
+            name = "metrics pipeline"
+
+            [[logs.transforms]]
+            type = "filter"
+            condition = ".keep == true"
+        "#})
+        .unwrap();
+
+        let (tx, rx) = mpsc::channel(1);
+        let (topology, mut out) = create_topology(ReceiverStream::new(rx), config).await;
+
+        let mut kept = LogEvent::from("kept");
+        kept.insert("keep", true);
+        tx.send(kept.into()).await.unwrap();
+        tx.send(LogEvent::from("discarded").into()).await.unwrap();
+        assert_eq!(
+            out.recv().await.unwrap().into_log()["message"],
+            "kept".into()
+        );
+
+        drop(tx);
+        topology.stop().await;
+
+        let metrics = Controller::get().unwrap().capture_metrics();
+        let counter = |name: &str| {
+            metrics
+                .iter()
+                .filter(|metric| {
+                    metric.name() == name && metric.tag_matches("pipeline", "metrics pipeline")
+                })
+                .map(|metric| match metric.value() {
+                    MetricValue::Counter { value } => *value,
+                    value => panic!("{} is not a counter: {:?}", name, value),
+                })
+                .sum::<f64>()
+        };
+
+        assert_eq!(counter("pipeline_received_events_total"), 2.0);
+        assert_eq!(counter("pipeline_sent_events_total"), 1.0);
+        assert_eq!(counter("pipeline_discarded_events_total"), 1.0);
+        assert_eq!(counter("pipeline_transform_received_events_total"), 2.0);
+        assert_eq!(counter("pipeline_transform_sent_events_total"), 1.0);
+        assert_eq!(counter("pipeline_transform_discarded_events_total"), 1.0);
+        assert!(metrics.iter().any(|metric| {
+            metric.name() == "pipeline_transform_processing_seconds"
+                && metric.tag_matches("transform_index", "0")
+                && metric.tag_matches("transform_type", "filter")
+        }));
+    }
 }
@@ -12,3 +12,9 @@ pub fn set_receive_buffer_size(socket: &UdpSocket, size: usize) -> std::io::Resu
 pub fn set_send_buffer_size(socket: &UdpSocket, size: usize) -> std::io::Result<()> {
     SockRef::from(socket).set_send_buffer_size(size)
 }
+
+// This function will be obsolete after tokio/mio internally use `socket2` and expose the methods to
+// apply options to a socket.
+pub fn set_multicast_hops_v6(socket: &UdpSocket, hops: u32) -> std::io::Result<()> {
+    SockRef::from(socket).set_multicast_hops_v6(hops)
+}
@@ -6,6 +6,7 @@ use exitcode::ExitCode;
 
 use crate::{
     config::{self, Config, ConfigDiff},
+    signal,
     topology::{self, builder::Pieces},
 };
 
@@ -137,9 +138,16 @@ pub fn validate_config(opts: &Opts, fmt: &mut Formatter) -> Option<Config> {
     config::init_log_schema(&paths, true)
         .map_err(&mut report_error)
         .ok()?;
-    let (builder, load_warnings) = config::load_builder_from_paths(&paths)
-        .map_err(&mut report_error)
-        .ok()?;
+    // Secrets are resolved as when running, so that components reading files whose paths are
+    // taken from secrets can be checked.
+    let (_, mut signal_rx) = signal::SignalHandler::new();
+    let (builder, _, load_warnings) = config::load_builder_from_paths_resolving_secrets(
+        &paths,
+        &config::ResolvedSecrets::default(),
+        &mut signal_rx,
+    )
+    .map_err(&mut report_error)
+    .ok()?;
 
     // Build
     let (config, build_warnings) = builder
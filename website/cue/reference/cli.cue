@@ -91,6 +91,10 @@ cli: {
 				Reduce detail of internal logging. Repeat to reduce further. Overrides `--verbose`.
 				"""
 		}
+		"refresh-secrets": {
+			description: env_vars.VECTOR_REFRESH_SECRETS.description
+			env_var:     "VECTOR_REFRESH_SECRETS"
+		}
 		"require-healthy": {
 			_short:      "r"
 			description: env_vars.VECTOR_REQUIRE_HEALTHY.description
@@ -579,6 +583,13 @@ cli: {
 				}
 			}
 		}
+		VECTOR_REFRESH_SECRETS: {
+			description: """
+				Retrieve every secret again when reloading the configuration, instead of reusing those of
+				unchanged components.
+				"""
+			type: bool: default: false
+		}
 		VECTOR_REQUIRE_HEALTHY: {
 			description: "Exit on startup if any sinks fail healthchecks."
 			type: bool: default: false
@@ -39,6 +39,26 @@ components: _aws: {
 							examples: ["arn:aws:iam::123456789098:role/my_role"]
 						}
 					}
+					external_id: {
+						category:    "Auth"
+						common:      false
+						description: "The external ID to pass when assuming `assume_role`, as required by roles whose trust policy is shared with a third party."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["randomEXAMPLEidString"]
+						}
+					}
+					session_name: {
+						category:    "Auth"
+						common:      false
+						description: "The name of the session when assuming `assume_role`, which identifies Vector in the CloudTrail logs of the account the role belongs to. If not set, a name is generated."
+						required:    false
+						type: string: {
+							default: null
+							examples: ["vector-indexer-role"]
+						}
+					}
 					region: {
 						category:    "Auth"
 						common:      false
@@ -190,7 +210,8 @@ components: _aws: {
 					body: """
 						Vector can assume an AWS IAM role via the [`assume_role`](#auth.assume_role) option. This is an
 						optional setting that is helpful for a variety of use cases, such as cross
-						account access.
+						account access. Roles in other accounts often require an
+						[`external_id`](#auth.external_id) to be passed when assuming them.
 						"""
 				},
 			]
@@ -50,6 +50,37 @@ components: sinks: amqp: {
 	support: components._amqp.support
 
 	configuration: {
+		channels: {
+			common:      false
+			description: "Configuration for the pool of channels messages are published on. Each message is assigned a channel by hashing its exchange and routing key, so that messages with the same exchange and routing key are always published on the same channel, in order."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: []
+				options: {
+					size: {
+						common:      false
+						description: "The number of channels opened on the connection to publish messages."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: 1
+							unit:    null
+						}
+					}
+					max_in_flight: {
+						common:      false
+						description: "The maximum number of messages awaiting confirmation on each channel. By default, there is no limit."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: null
+							unit:    null
+						}
+					}
+				}
+			}
+		}
 		connection: {
 			description: "Connection options for the AMQP sink."
 			required:    true
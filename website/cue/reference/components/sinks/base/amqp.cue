@@ -27,6 +27,39 @@ base: components: sinks: amqp: configuration: {
 			type: bool: {}
 		}
 	}
+	channels: {
+		description: """
+			Configuration for the pool of channels messages are published on.
+
+			Each message is assigned a channel by hashing its exchange and routing key, so that messages
+			with the same exchange and routing key are always published on the same channel, in order.
+			Confirmations are awaited independently on each channel.
+			"""
+		required: false
+		type: object: {
+			default: {
+				max_in_flight: null
+				size:          1
+			}
+			options: {
+				max_in_flight: {
+					description: """
+						The maximum number of messages awaiting confirmation on each channel.
+
+						Once a channel has this many messages awaiting confirmation, the messages assigned to it
+						wait until some are confirmed. By default, there is no limit.
+						"""
+					required: false
+					type: uint: {}
+				}
+				size: {
+					description: "The number of channels opened on the connection to publish messages."
+					required:    false
+					type: uint: default: 1
+				}
+			}
+		}
+	}
 	connection: {
 		description: "Connection options for the `amqp` sink."
 		required:    true
@@ -127,6 +160,79 @@ base: components: sinks: amqp: configuration: {
 			}
 		}
 	}
+	dead_letter: {
+		description: """
+			Configuration for the dead-lettering annotations of the messages published.
+
+			These let the time messages are retained for, and where they are meant to go once expired or
+			rejected, be set from the producer side.
+
+			RabbitMQ only dead-letters messages according to the queues they are routed to: those queues
+			must have a dead-letter exchange set, by their `x-dead-letter-exchange` argument or by a
+			policy, for expired or rejected messages to be dead-lettered at all. The dead-letter headers of
+			the messages are not acted upon by the broker, and are meant for consumers and tooling.
+			"""
+		required: false
+		type: object: {
+			default: {
+				check_exchange: false
+				exchange:       null
+				message_ttl_ms: null
+				routing_key:    null
+			}
+			options: {
+				check_exchange: {
+					description: """
+						Whether to check that the dead-letter exchange of each message exists, by declaring it
+						passively.
+
+						Messages published with a dead-letter exchange which doesn't exist are counted by the
+						`amqp_dead_letter_exchange_missing_total` internal metric. Each exchange is checked at most
+						once a minute, without holding up publishing: messages published before the first check of
+						an exchange completes aren't counted.
+						"""
+					required: false
+					type: bool: default: false
+				}
+				exchange: {
+					description: """
+						Template used to generate the exchange messages are dead-lettered to.
+
+						It's set as the `x-dead-letter-exchange` header of the message, which the broker doesn't
+						act upon.
+						"""
+					required: false
+					type: string: {
+						examples: ["{{ service }}-dlx"]
+						syntax: "template"
+					}
+				}
+				message_ttl_ms: {
+					description: """
+						Template used to generate the time-to-live of each message, in milliseconds.
+
+						It's set as the `expiration` property of the message. Events for which it doesn't render to
+						a whole number are dropped.
+						"""
+					required: false
+					type: string: {
+						examples: ["60000"]
+						syntax: "template"
+					}
+				}
+				routing_key: {
+					description: """
+						Template used to generate the routing key of the messages once dead-lettered.
+
+						It's set as the `x-dead-letter-routing-key` header of the message, which the broker
+						doesn't act upon.
+						"""
+					required: false
+					type: string: syntax: "template"
+				}
+			}
+		}
+	}
 	encoding: {
 		description: "Encoding configuration."
 		required:    true
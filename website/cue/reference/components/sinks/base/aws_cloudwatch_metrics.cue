@@ -55,6 +55,16 @@ base: components: sinks: aws_cloudwatch_metrics: configuration: {
 				required:    true
 				type: string: syntax: "literal"
 			}
+			external_id: {
+				description: """
+					The external ID to pass when assuming the role.
+
+					Required by roles whose trust policy is shared with a third party, such as a role in
+					another AWS account.
+					"""
+				required: false
+				type: string: syntax: "literal"
+			}
 			load_timeout_secs: {
 				description: "Timeout for successfully loading any credentials, in seconds."
 				required:    false
@@ -80,6 +90,16 @@ base: components: sinks: aws_cloudwatch_metrics: configuration: {
 				required:    true
 				type: string: syntax: "literal"
 			}
+			session_name: {
+				description: """
+					The name of the session, which identifies Vector in the CloudTrail logs of the account
+					the role belongs to.
+
+					If not set, a name is generated.
+					"""
+				required: false
+				type: string: syntax: "literal"
+			}
 		}
 	}
 	batch: {
@@ -27,6 +27,18 @@ base: components: sinks: blackhole: configuration: {
 			type: bool: {}
 		}
 	}
+	latency_key: {
+		description: """
+			Event field holding the monotonic timestamp at which each log event was generated.
+
+			When set, the end-to-end latency of each log event is recorded in the
+			`end_to_end_latency_seconds` histogram, and included in the summary of activity. The
+			timestamp is added by the `generation_timestamp_key` option of the `demo_logs` source, in
+			the same Vector instance. Events without it are not measured.
+			"""
+		required: false
+		type: string: syntax: "literal"
+	}
 	print_interval_secs: {
 		description: """
 			The number of seconds between reporting a summary of activity.
@@ -171,27 +171,130 @@ base: components: sinks: humio_logs: configuration: {
 		required:    false
 		type: string: syntax: "literal"
 	}
+	endpoint_health_check_interval_secs: {
+		description: """
+			The interval, in seconds, on which each of the `endpoints` is health checked.
+
+			An endpoint failing its health check is taken out of rotation, as if it had failed a
+			request, before any events are sent to it, and is put back as soon as it passes one. By
+			default, endpoints are only taken out of rotation when they fail a request.
+
+			Only relevant when `endpoints` is set.
+			"""
+		required: false
+		type: uint: {}
+	}
+	endpoint_retry_after_secs: {
+		description: """
+			The amount of time, in seconds, that an endpoint which failed a request is skipped for
+			before being tried again.
+
+			Only relevant when `endpoints` is set.
+			"""
+		required: false
+		type: uint: default: 30
+	}
+	endpoints: {
+		description: """
+			A set of Humio endpoints to send events to, instead of a single `endpoint`.
+
+			Events are spread over the healthy endpoints with the lowest `priority`, according to
+			their `weight`. When an endpoint fails a request, it is taken out of rotation and traffic
+			fails over to the remaining endpoints, such as a disaster recovery region.
+
+			Cannot be used together with `endpoint`.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: object: options: {
+				endpoint: {
+					description: "The base URL of the Humio instance."
+					required:    true
+					type: string: syntax: "literal"
+				}
+				priority: {
+					description: """
+						The priority of this endpoint.
+
+						Endpoints with a lower value are preferred. Endpoints with a higher value only receive
+						events while every endpoint with a lower value is unhealthy.
+						"""
+					required: false
+					type: uint: default: 0
+				}
+				weight: {
+					description: "The relative share of events sent to this endpoint, among endpoints of the same priority."
+					required:    false
+					type: uint: default: 1
+				}
+			}
+		}
+	}
 	event_type: {
 		description: """
 			The type of events sent to this sink. Humio uses this as the name of the parser to use to ingest the data.
 
-			If unset, Humio will default it to none.
+			If unset, Humio will default it to none. A static value must be a valid parser name, of at
+			most 255 printable characters.
 			"""
 		required: false
 		type: string: syntax: "template"
 	}
+	event_type_field: {
+		description: """
+			Configuration for deriving the type of each event from one of its fields.
+
+			Humio uses the type of an event as the name of the parser to ingest it with, so this lets
+			events of different kinds sent to the same repository each be parsed by the right parser.
+
+			Events whose field is missing, isn't a string, or names a parser that isn't in `allowed` have
+			the field set to `default`, so that they are still ingested with a known parser.
+			"""
+		required: false
+		type: object: options: {
+			allowed: {
+				description: """
+					The names of the parsers events can be assigned.
+
+					By default, any name is allowed.
+					"""
+				required: false
+				type: array: {
+					default: []
+					items: type: string: syntax: "literal"
+				}
+			}
+			default: {
+				description: "The name of the parser used for events whose field is missing or not allowed."
+				required:    true
+				type: string: syntax: "literal"
+			}
+			field: {
+				description: "The event field holding the name of the parser."
+				required:    true
+				type: string: syntax: "literal"
+			}
+		}
+	}
 	host_key: {
 		description: """
 			Overrides the name of the log field used to grab the hostname to send to Humio.
 
+			A list of fields can be given instead, in which case the first one present in each event
+			is used.
+
 			By default, the [global `log_schema.host_key` option][global_host_key] is used.
 
 			[global_host_key]: https://vector.dev/docs/reference/configuration/global-options/#log_schema.host_key
 			"""
 		required: false
-		type: string: {
-			default: "host"
-			syntax:  "literal"
+		type: {
+			array: items: type: string: syntax: "literal"
+			string: {
+				default: "host"
+				syntax:  "literal"
+			}
 		}
 	}
 	index: {
@@ -202,6 +305,9 @@ base: components: sinks: humio_logs: configuration: {
 
 			In private cluster setups, Humio can be configured to allow these to be different.
 
+			A static value must be a valid repository name, of at most 100 letters, digits, `-`, and
+			`_`.
+
 			For more information, see [Humio’s Format of Data][humio_data_format].
 
 			[humio_data_format]: https://docs.humio.com/integrations/data-shippers/hec/#format-of-data
@@ -225,6 +331,49 @@ base: components: sinks: humio_logs: configuration: {
 			items: type: string: syntax: "literal"
 		}
 	}
+	max_tag_values: {
+		description: """
+			The maximum number of distinct values of each tag in `tags`.
+
+			Humio tags should have few distinct values. Once a tag has had this many distinct values,
+			events with a new value are sent without the tag.
+			"""
+		required: false
+		type: uint: default: 100
+	}
+	normalization: {
+		description: """
+			Configuration for normalizing events to Humio conventions.
+
+			When set, the timestamp field, if it holds an RFC 3339 string or an integer number of
+			milliseconds since the Unix epoch, is converted so that it is sent as Humio's `@timestamp`,
+			and each field listed in `tags` is sent as a Humio tag.
+			"""
+		required: false
+		type: object: options: {
+			max_tag_values: {
+				description: "The maximum number of distinct values of each field listed in `tags`."
+				required:    false
+				type: uint: default: 100
+			}
+			tags: {
+				description: """
+					Event fields to send as Humio tags.
+
+					Each field is moved to a field named after it, prefixed with `#`, which is sent as an
+					indexed field. For example, `env` is sent as the `#env` tag.
+
+					Tags should have few distinct values, so only string values are tagged, and only until a
+					field has had `max_tag_values` distinct values. Other values are left in the event as is.
+					"""
+				required: false
+				type: array: {
+					default: []
+					items: type: string: syntax: "literal"
+				}
+			}
+		}
+	}
 	request: {
 		description: """
 			Middleware settings for outbound requests.
@@ -351,22 +500,59 @@ base: components: sinks: humio_logs: configuration: {
 			The source of events sent to this sink.
 
 			Typically the filename the logs originated from. Maps to `@source` in Humio.
+
+			When schema validation is enabled, the fields referenced by this template, `event_type`,
+			and `index` must be able to exist in the events received.
 			"""
 		required: false
 		type: string: syntax: "template"
 	}
+	tags: {
+		description: """
+			Humio tags rendered from each event.
+
+			Each key is the name of a tag, which is sent as an indexed field prefixed with `#`, and
+			each value is a template rendered with the event. For example, `env = "{{ environment }}"`
+			sends the `environment` field of each event as the `#env` tag.
+
+			Events for which a template can't be rendered are sent without the tag.
+			"""
+		required: false
+		type: object: {
+			default: {}
+			options: "*": {
+				description: """
+					Humio tags rendered from each event.
+
+					Each key is the name of a tag, which is sent as an indexed field prefixed with `#`, and
+					each value is a template rendered with the event. For example, `env = "{{ environment }}"`
+					sends the `environment` field of each event as the `#env` tag.
+
+					Events for which a template can't be rendered are sent without the tag.
+					"""
+				required: true
+				type: string: syntax: "template"
+			}
+		}
+	}
 	timestamp_key: {
 		description: """
 			Overrides the name of the log field used to grab the timestamp to send to Humio.
 
+			A list of fields can be given instead, in which case the first one present in each event
+			is used.
+
 			By default, the [global `log_schema.timestamp_key` option][global_timestamp_key] is used.
 
 			[global_timestamp_key]: https://vector.dev/docs/reference/configuration/global-options/#log_schema.timestamp_key
 			"""
 		required: false
-		type: string: {
-			default: "timestamp"
-			syntax:  "literal"
+		type: {
+			array: items: type: string: syntax: "literal"
+			string: {
+				default: "timestamp"
+				syntax:  "literal"
+			}
 		}
 	}
 	timestamp_nanos_key: {
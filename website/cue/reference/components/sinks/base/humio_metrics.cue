@@ -77,11 +77,102 @@ base: components: sinks: humio_metrics: configuration: {
 			}
 		}
 	}
+	cumulative_counters: {
+		description: """
+			Converts incremental counters to absolute counters holding their running total.
+
+			A running total is kept for each series, so that the emitted log events carry cumulative
+			values, as some consumers of log events can only handle those.
+			"""
+		required: false
+		type: object: options: state_ttl_secs: {
+			description: """
+				The time, in seconds, after which the running total of a series that stopped being
+				updated is forgotten.
+
+				A series seen again after its running total was forgotten starts over from zero.
+				"""
+			required: false
+			type: uint: default: 300
+		}
+	}
+	embed_metadata: {
+		description: """
+			Embeds the metadata needed to rebuild the original metric in each log event.
+
+			When set, each log event gets a `metric_metadata` field holding the name, namespace, kind,
+			tags, timestamp, and interval of the metric as they were before the conversion, along with
+			its origin metadata, and a `schema_version` field holding the version of the layout of
+			`metric_metadata`. Cannot be used together with `wide_events`.
+			"""
+		required: false
+		type: bool: default: false
+	}
 	endpoint: {
 		description: "The base URL of the Humio instance."
 		required:    false
 		type: string: syntax: "literal"
 	}
+	endpoint_health_check_interval_secs: {
+		description: """
+			The interval, in seconds, on which each of the `endpoints` is health checked.
+
+			An endpoint failing its health check is taken out of rotation, as if it had failed a
+			request, before any metrics are sent to it, and is put back as soon as it passes one. By
+			default, endpoints are only taken out of rotation when they fail a request.
+
+			Only relevant when `endpoints` is set.
+			"""
+		required: false
+		type: uint: {}
+	}
+	endpoint_retry_after_secs: {
+		description: """
+			The amount of time, in seconds, that an endpoint which failed a request is skipped for
+			before being tried again.
+
+			Only relevant when `endpoints` is set.
+			"""
+		required: false
+		type: uint: default: 30
+	}
+	endpoints: {
+		description: """
+			A set of Humio endpoints to send metrics to, instead of a single `endpoint`.
+
+			Metrics are spread over the healthy endpoints with the lowest `priority`, according to
+			their `weight`. When an endpoint fails a request, it is taken out of rotation and traffic
+			fails over to the remaining endpoints.
+
+			Cannot be used together with `endpoint`.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: object: options: {
+				endpoint: {
+					description: "The base URL of the Humio instance."
+					required:    true
+					type: string: syntax: "literal"
+				}
+				priority: {
+					description: """
+						The priority of this endpoint.
+
+						Endpoints with a lower value are preferred. Endpoints with a higher value only receive
+						events while every endpoint with a lower value is unhealthy.
+						"""
+					required: false
+					type: uint: default: 0
+				}
+				weight: {
+					description: "The relative share of events sent to this endpoint, among endpoints of the same priority."
+					required:    false
+					type: uint: default: 1
+				}
+			}
+		}
+	}
 	event_type: {
 		description: """
 			The type of events sent to this sink. Humio uses this as the name of the parser to use to ingest the data.
@@ -91,6 +182,17 @@ base: components: sinks: humio_metrics: configuration: {
 		required: false
 		type: string: syntax: "template"
 	}
+	flatten_tags: {
+		description: """
+			Lifts metric tags to top-level fields of the log event, instead of nesting them under a
+			`tags` field.
+
+			Tags never overwrite other fields: a tag whose field name is already taken, such as a tag
+			named `name` or `kind`, is left under the `tags` field.
+			"""
+		required: false
+		type: bool: default: false
+	}
 	host_key: {
 		description: """
 			Overrides the name of the log field used to grab the hostname to send to Humio.
@@ -281,16 +383,26 @@ base: components: sinks: humio_metrics: configuration: {
 		required: false
 		type: string: syntax: "template"
 	}
+	tag_prefix: {
+		description: """
+			A prefix added to the field name of each tag lifted by `flatten_tags`.
+
+			For example, with a prefix of `tag_`, the `region` tag is lifted to the `tag_region` field.
+			By default, tags are lifted to fields named after them.
+			"""
+		required: false
+		type: string: {
+			examples: ["tag_"]
+			syntax: "literal"
+		}
+	}
 	timezone: {
 		description: """
 			The name of the timezone to apply to timestamp conversions that do not contain an explicit
 			time zone.
 
-			This overrides the [global `timezone`][global_timezone] option. The time zone name may be
-			any name in the [TZ database][tz_database], or `local` to indicate system local time.
-
-			[global_timezone]: https://vector.dev/docs/reference/configuration//global-options#timezone
-			[tz_database]: https://en.wikipedia.org/wiki/List_of_tz_database_time_zones
+			This option is deprecated and has no effect: metric timestamps always have an explicit time
+			zone, and are copied to log events as they are.
 			"""
 		required: false
 		type: string: examples: ["local", "America/New_York", "EST5EDT"]
@@ -385,4 +497,41 @@ base: components: sinks: humio_metrics: configuration: {
 		required:    true
 		type: string: syntax: "literal"
 	}
+	wide_events: {
+		description: """
+			Merges metrics into wide log events.
+
+			When set, metrics sharing the same timestamp, tags, and kind are merged into a single log
+			event with `tags` and `kind` fields and one field per metric name, instead of one log event
+			per metric. Counter and gauge fields hold the metric value, other metric types hold their
+			serialized value. Metrics with a namespace are nested under a field named after the
+			namespace.
+
+			Metrics whose name, or namespace, is that of the `tags`, `kind`, timestamp, or host fields
+			are dropped, as are counters and gauges whose value is NaN.
+			"""
+		required: false
+		type: object: options: {
+			flush_interval_ms: {
+				description: """
+					The interval between flushes of pending wide events, in milliseconds.
+
+					Metrics sharing the same timestamp, tags, and kind that arrive within this interval are
+					merged into the same log event.
+					"""
+				required: false
+				type: uint: default: 1000
+			}
+			max_width: {
+				description: """
+					The maximum number of metrics merged into a single log event.
+
+					Once reached, the log event is emitted right away, and further metrics sharing its
+					timestamp, tags, and kind start a new log event.
+					"""
+				required: false
+				type: uint: default: 100
+			}
+		}
+	}
 }
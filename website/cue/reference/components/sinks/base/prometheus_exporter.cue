@@ -41,10 +41,9 @@ base: components: sinks: prometheus_exporter: configuration: {
 	}
 	auth: {
 		description: """
-			Configuration of the authentication strategy for HTTP requests.
+			Authentication required from clients scraping the exposed metrics.
 
-			HTTP authentication should almost always be used with HTTPS only, as the authentication credentials are passed as an
-			HTTP header without any additional encryption beyond what is provided by the transport itself.
+			Scrapes without matching credentials are rejected with a `401 Unauthorized` response.
 			"""
 		required: false
 		type: object: options: {
@@ -141,6 +140,44 @@ base: components: sinks: prometheus_exporter: configuration: {
 			unit:    "seconds"
 		}
 	}
+	pushgateway: {
+		description: """
+			Configuration for accepting metrics pushed with the Pushgateway API.
+
+			When enabled, short-lived jobs can push metrics in the Prometheus text format to
+			`/metrics/job/<job>{/<label>/<value>}`, as they would to a [Pushgateway][pushgateway_docs].
+			The `job` and any other labels of the path form the grouping key of the pushed metrics, and are
+			added to them as tags.
+
+			A `PUT` replaces all the metrics of the group, a `POST` only replaces the metrics with the same
+			name as the pushed ones, and a `DELETE` removes the group. Pushes larger than 10 MiB are
+			rejected.
+
+			[pushgateway_docs]: https://github.com/prometheus/pushgateway#api
+			"""
+		required: false
+		type: object: {
+			default: {
+				enabled:  false
+				ttl_secs: 300
+			}
+			options: {
+				enabled: {
+					description: "Whether or not to accept pushed metrics."
+					required:    false
+					type: bool: default: false
+				}
+				ttl_secs: {
+					description: "The amount of time, in seconds, pushed metrics are exposed after they were last pushed."
+					required:    false
+					type: uint: {
+						default: 300
+						unit:    "seconds"
+					}
+				}
+			}
+		}
+	}
 	quantiles: {
 		description: """
 			Quantiles to use for aggregating [distribution][dist_metric_docs] metrics into a summary.
@@ -153,6 +190,30 @@ base: components: sinks: prometheus_exporter: configuration: {
 			items: type: number: {}
 		}
 	}
+	shard: {
+		description: """
+			Configuration for exposing only a shard of the metric series.
+
+			Each series is assigned to one of `count` shards by a hash of its name and tags, and only the
+			series of shard `index` are exposed. Instances receiving the same metrics, such as a pair of
+			Vector instances deployed for high availability, can be given the same `count` and distinct
+			`index`es so that each exposes a disjoint share of the series, and scraping all of them does not
+			count any series twice.
+			"""
+		required: false
+		type: object: options: {
+			count: {
+				description: "The number of shards the series are split into."
+				required:    true
+				type: uint: {}
+			}
+			index: {
+				description: "The shard to expose, from `0` to `count - 1`."
+				required:    true
+				type: uint: {}
+			}
+		}
+	}
 	suppress_timestamp: {
 		description: """
 			Suppresses timestamps on the Prometheus output.
@@ -165,8 +226,13 @@ base: components: sinks: prometheus_exporter: configuration: {
 		type: bool: default: false
 	}
 	tls: {
-		description: "Configures the TLS options for incoming/outgoing connections."
-		required:    false
+		description: """
+			TLS configuration of the exposed endpoint.
+
+			When `tls.verify_certificate` is enabled, clients must present a certificate issued by one
+			of the authorities in `tls.ca_file`, and connections without one are rejected.
+			"""
+		required: false
 		type: object: options: {
 			alpn_protocols: {
 				description: """
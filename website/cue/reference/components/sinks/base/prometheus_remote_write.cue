@@ -27,6 +27,21 @@ base: components: sinks: prometheus_remote_write: configuration: {
 			type: bool: {}
 		}
 	}
+	aggregation: {
+		description: """
+			Client-side aggregation of metrics before they are written.
+
+			Within each window, the increments of incremental metrics are summed per series, and only the
+			last value of absolute metrics, such as gauges, is kept. This reduces the number of samples
+			written for high-frequency sources, at the cost of delaying them by up to one window.
+			"""
+		required: false
+		type: object: options: window_secs: {
+			description: "The length of the aggregation window, in seconds."
+			required:    false
+			type: uint: default: 15
+		}
+	}
 	auth: {
 		description: "Authentication strategies."
 		required:    false
@@ -49,6 +64,16 @@ base: components: sinks: prometheus_remote_write: configuration: {
 				required:      true
 				type: string: syntax: "literal"
 			}
+			external_id: {
+				description: """
+					The external ID to pass when assuming the role.
+
+					Required by roles whose trust policy is shared with a third party, such as a role in
+					another AWS account.
+					"""
+				required: false
+				type: string: syntax: "literal"
+			}
 			load_timeout_secs: {
 				description:   "Timeout for successfully loading any credentials, in seconds."
 				relevant_when: "strategy = \"aws\""
@@ -84,6 +109,16 @@ base: components: sinks: prometheus_remote_write: configuration: {
 				required:      true
 				type: string: syntax: "literal"
 			}
+			session_name: {
+				description: """
+					The name of the session, which identifies Vector in the CloudTrail logs of the account
+					the role belongs to.
+
+					If not set, a name is generated.
+					"""
+				required: false
+				type: string: syntax: "literal"
+			}
 			strategy: {
 				required: true
 				type: string: enum: {
@@ -183,6 +218,20 @@ base: components: sinks: prometheus_remote_write: configuration: {
 		required:    true
 		type: string: syntax: "literal"
 	}
+	out_of_order_handling: {
+		description: """
+			Whether or not to guard against out-of-order samples.
+
+			When enabled, the samples of each series are sorted by timestamp before being sent. In
+			addition, as receivers such as Cortex or Mimir reject a whole request if any of its series
+			contains an out-of-order sample, a request rejected with `400 Bad Request` is split and
+			each of its series is sent on its own, so that only the offending series are dropped. If any
+			of those series fail with a retriable error, the whole request is retried. Samples that were
+			already accepted are sent again, which receivers accept as duplicates.
+			"""
+		required: false
+		type: bool: default: false
+	}
 	quantiles: {
 		description: """
 			Quantiles to use for aggregating [distribution][dist_metric_docs] metrics into a summary.
@@ -316,6 +365,28 @@ base: components: sinks: prometheus_remote_write: configuration: {
 			}
 		}
 	}
+	send_exemplars: {
+		description: """
+			Whether or not to send the exemplars of histograms.
+
+			Exemplars are read from the `exemplars` array of the metadata of absolute histogram metrics,
+			each being an object with a numeric `value`, and optionally a `timestamp` and an object of
+			`labels`, such as a `trace_id`. Each exemplar is attached to the series of the bucket its
+			value falls in.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	send_metadata: {
+		description: """
+			Whether or not to send the metadata of metrics.
+
+			When enabled, each request includes a `TYPE` and `HELP` record for each metric family in
+			it. Receivers that don't support metadata can reject such requests.
+			"""
+		required: false
+		type: bool: default: true
+	}
 	tenant_id: {
 		description: """
 			The tenant ID to send.
@@ -50,6 +50,19 @@ components: sinks: blackhole: {
 				unit: null
 			}
 		}
+		latency_key: {
+			common: false
+			description: """
+				Event field holding the monotonic timestamp at which each log event was generated, as
+				added by the `generation_timestamp_key` option of the `demo_logs` source. When set,
+				the end-to-end latency of each log event is recorded and reported.
+				"""
+			required: false
+			type: string: {
+				default: null
+				examples: ["generated_at"]
+			}
+		}
 	}
 
 	input: {
@@ -66,6 +79,7 @@ components: sinks: blackhole: {
 	}
 
 	telemetry: metrics: {
+		end_to_end_latency_seconds: components.sources.internal_metrics.output.metrics.end_to_end_latency_seconds
 		processed_bytes_total:  components.sources.internal_metrics.output.metrics.processed_bytes_total
 		processed_events_total: components.sources.internal_metrics.output.metrics.processed_events_total
 	}
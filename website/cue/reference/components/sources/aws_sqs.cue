@@ -96,8 +96,8 @@ components: sources: aws_sqs: components._aws & {
 			}
 		}
 		queue_url: {
-			description: "The URL of the SQS queue to receive events from."
-			required:    true
+			description: "The URL of the SQS queue to receive events from. Can be omitted if `queues` is set."
+			required:    false
 			warnings: []
 			type: string: {
 				examples: ["https://sqs.us-east-2.amazonaws.com/123456789012/MyQueue"]
@@ -106,9 +106,37 @@ components: sources: aws_sqs: components._aws & {
 		}
 	}
 
+	outputs: [
+		{
+			name: components._default_output.name
+			description: """
+				Default output stream of the component. Use this component's ID as an input to downstream transforms and sinks.
+				"""
+		},
+		{
+			name: "raw"
+			description: """
+				If [raw_output](#raw_output) is set, the messages received are also sent as is to this output stream, each as a single event holding the body of the message before decompression and decoding. Use `<component_id>.raw` as an input to downstream transforms and sinks.
+				"""
+		},
+		{
+			name: "errors"
+			description: """
+				If [poison_messages](#poison_messages) is set with the `route` action, messages that keep failing to be processed are sent as is to this output stream before being deleted, each as a single event holding the body of the message as received. Use `<component_id>.errors` as an input to downstream transforms and sinks.
+				"""
+		},
+	]
+
 	output: logs: record: {
 		description: "An individual SQS record"
 		fields: {
+			attributes: {
+				description: "The message attributes of the SQS record. Not set when S3 event notifications are unwrapped, nor when the event decoded from the message already has an `attributes` field."
+				required:    false
+				type: object: {
+					examples: [{"key": "value"}]
+				}
+			}
 			message: {
 				description: "The raw message from the SQS record."
 				required:    true
@@ -117,6 +145,14 @@ components: sources: aws_sqs: components._aws & {
 					syntax: "literal"
 				}
 			}
+			message_id: {
+				description: "The ID of the SQS record. Not set when S3 event notifications are unwrapped, nor when the event decoded from the message already has a `message_id` field."
+				required:    false
+				type: string: {
+					examples: ["5fea7756-0ea4-451a-a703-a558b933e274"]
+					syntax: "literal"
+				}
+			}
 			source_type: {
 				description: "The name of the source type."
 				required:    true
@@ -135,6 +171,7 @@ components: sources: aws_sqs: components._aws & {
 		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
 		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
 		sqs_message_delete_failed_total:      components.sources.internal_metrics.output.metrics.sqs_message_delete_failed_total
+		sqs_poison_messages_total:            components.sources.internal_metrics.output.metrics.sqs_poison_messages_total
 	}
 
 	how_it_works: {
@@ -38,6 +38,16 @@ base: components: sources: aws_sqs: configuration: {
 				required:    true
 				type: string: syntax: "literal"
 			}
+			external_id: {
+				description: """
+					The external ID to pass when assuming the role.
+
+					Required by roles whose trust policy is shared with a third party, such as a role in
+					another AWS account.
+					"""
+				required: false
+				type: string: syntax: "literal"
+			}
 			load_timeout_secs: {
 				description: "Timeout for successfully loading any credentials, in seconds."
 				required:    false
@@ -63,11 +73,21 @@ base: components: sources: aws_sqs: configuration: {
 				required:    true
 				type: string: syntax: "literal"
 			}
+			session_name: {
+				description: """
+					The name of the session, which identifies Vector in the CloudTrail logs of the account
+					the role belongs to.
+
+					If not set, a name is generated.
+					"""
+				required: false
+				type: string: syntax: "literal"
+			}
 		}
 	}
 	client_concurrency: {
 		description: """
-			Number of concurrent tasks to create for polling the queue for messages.
+			Number of concurrent tasks to create for polling each queue for messages.
 
 			Defaults to the number of available CPUs on the system.
 
@@ -79,6 +99,35 @@ base: components: sources: aws_sqs: configuration: {
 		required: false
 		type: uint: {}
 	}
+	compression: {
+		description: """
+			The compression scheme of the message bodies.
+
+			SQS message bodies must be text, so compressed bodies are expected to be base64-encoded.
+			Messages that fail to be decompressed are left in the queue, to be redelivered after the
+			visibility timeout or moved to a dead-letter queue by its redrive policy.
+
+			When `s3_notification` is set, this is the compression scheme of the fetched objects
+			instead, which is determined from their metadata and key when set to `auto`. Notifications
+			themselves are only decompressed according to their `content-encoding` attribute.
+			"""
+		required: false
+		type: string: {
+			default: "auto"
+			enum: {
+				auto: """
+					Automatically determine the compression scheme.
+
+					Vector will determine the compression scheme of each message from its `content-encoding`
+					message attribute, which can be either `gzip` or `zstd`. Messages without that attribute are
+					not decompressed.
+					"""
+				gzip: "GZIP."
+				none: "Uncompressed."
+				zstd: "ZSTD."
+			}
+		}
+	}
 	decoding: {
 		description: "Configures how events are decoded from raw bytes."
 		required:    false
@@ -211,6 +260,59 @@ base: components: sources: aws_sqs: configuration: {
 			}
 		}
 	}
+	max_decompressed_bytes: {
+		description: """
+			The maximum size, in bytes, of a decompressed message body, or of a fetched object when
+			`s3_notification` is set.
+
+			Messages whose body, or one of whose objects, exceeds this size once decompressed are
+			treated as failing to be decompressed.
+			"""
+		required: false
+		type: uint: default: 10485760
+	}
+	poison_messages: {
+		description: """
+			Handling of poison messages, those that keep failing to be processed.
+
+			Messages whose body fails to be decompressed, or whose S3 event notification fails to be
+			processed, are left in the queue to be retried. Without a redrive policy on the queue, such
+			messages are retried forever. Once a message that fails has been received `max_receive_attempts`
+			times, it is instead handled according to `action`, and removed from the queue.
+			"""
+		required: false
+		type: object: options: {
+			action: {
+				description: "What to do with poison messages."
+				required:    false
+				type: string: {
+					default: "route"
+					enum: {
+						delete: "Delete the message."
+						route: """
+							Send the message, as is, to the `errors` output, and then delete it.
+
+							Each message is sent as a single event, whose message is the body of the message as
+							received, along with the same metadata as the decoded events. The message is only deleted
+							once the event has been delivered, when acknowledgements are enabled.
+							"""
+					}
+				}
+			}
+			max_receive_attempts: {
+				description: """
+					The number of times a message can be received before it is handled as a poison message
+					when it fails to be processed.
+
+					This is the approximate receive count of the message, as counted by SQS. To have messages
+					handled by Vector rather than by the redrive policy of the queue, if any, this must be lower
+					than the `maxReceiveCount` of that policy.
+					"""
+				required: true
+				type: uint: {}
+			}
+		}
+	}
 	poll_secs: {
 		description: """
 			How long to wait while polling the queue for new messages, in seconds.
@@ -222,15 +324,195 @@ base: components: sources: aws_sqs: configuration: {
 		type: uint: default: 15
 	}
 	queue_url: {
-		description: "The URL of the SQS queue to poll for messages."
-		required:    true
-		type: string: syntax: "literal"
+		description: """
+			The URL of the SQS queue to poll for messages.
+
+			Can be omitted if `queues` is set.
+			"""
+		required: false
+		type: string: {
+			default: ""
+			syntax:  "literal"
+		}
+	}
+	queues: {
+		description: """
+			Additional SQS queues to poll for messages.
+
+			Each queue can override the region, endpoint, and authentication used to access it, in
+			which case it is polled with a separate client. This allows consuming queues from several
+			regions or accounts with a single source.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: object: options: {
+				auth: {
+					description: """
+						The authentication to use for the queue, such as a role to assume in the account owning it.
+
+						If not set, the authentication of the source is used.
+						"""
+					required: false
+					type: object: options: {
+						access_key_id: {
+							description: "The AWS access key ID."
+							required:    true
+							type: string: syntax: "literal"
+						}
+						assume_role: {
+							description: "The ARN of the role to assume."
+							required:    true
+							type: string: syntax: "literal"
+						}
+						credentials_file: {
+							description: "Path to the credentials file."
+							required:    true
+							type: string: syntax: "literal"
+						}
+						external_id: {
+							description: """
+								The external ID to pass when assuming the role.
+
+								Required by roles whose trust policy is shared with a third party, such as a role in
+								another AWS account.
+								"""
+							required: false
+							type: string: syntax: "literal"
+						}
+						load_timeout_secs: {
+							description: "Timeout for successfully loading any credentials, in seconds."
+							required:    false
+							type: uint: {}
+						}
+						profile: {
+							description: "The credentials profile to use."
+							required:    false
+							type: string: syntax: "literal"
+						}
+						region: {
+							description: """
+								The AWS region to send STS requests to.
+
+								If not set, this will default to the configured region
+								for the service itself.
+								"""
+							required: false
+							type: string: syntax: "literal"
+						}
+						secret_access_key: {
+							description: "The AWS secret access key."
+							required:    true
+							type: string: syntax: "literal"
+						}
+						session_name: {
+							description: """
+								The name of the session, which identifies Vector in the CloudTrail logs of the account
+								the role belongs to.
+
+								If not set, a name is generated.
+								"""
+							required: false
+							type: string: syntax: "literal"
+						}
+					}
+				}
+				endpoint: {
+					description: """
+						The API endpoint of the service for the queue.
+
+						If neither `region` nor `endpoint` is set, the region and endpoint of the source are used.
+						"""
+					required: false
+					type: string: syntax: "literal"
+				}
+				queue_url: {
+					description: "The URL of the SQS queue."
+					required:    true
+					type: string: syntax: "literal"
+				}
+				region: {
+					description: """
+						The AWS region of the queue.
+
+						If neither `region` nor `endpoint` is set, the region and endpoint of the source are used.
+						"""
+					required: false
+					type: string: syntax: "literal"
+				}
+			}
+		}
+	}
+	raw_output: {
+		description: """
+			Also sends messages received, as is, to the `raw` output.
+
+			Each message is sent as a single event, whose message is the body of the message as received,
+			before any decompression or decoding, along with the same metadata as the decoded events. This
+			lets pipelines archive the original messages while others process the decoded events, without
+			consuming the queue twice.
+
+			Messages are only sent to the `raw` output once their body has been decoded, as those that
+			can't be are left in the queue to be redelivered.
+			"""
+		required: false
+		type: object: options: {
+			condition: {
+				description: """
+					A condition the events of the `raw` output must match to be sent.
+
+					By default, every message is sent.
+					"""
+				required: false
+				type: condition: {}
+			}
+			sample_rate: {
+				description: "Send 1 in every `sample_rate` of the messages matching `condition`."
+				required:    false
+				type: uint: default: 1
+			}
+		}
 	}
 	region: {
 		description: "The AWS region to use."
 		required:    false
 		type: string: syntax: "literal"
 	}
+	s3_notification: {
+		description: """
+			Configuration for unwrapping S3 event notifications.
+
+			When set, each message body is parsed as an [S3 event notification][notification], and each
+			record for a created object is emitted as configured here. Records for other kinds of events,
+			and test notifications, are ignored.
+
+			[notification]: https://docs.aws.amazon.com/AmazonS3/latest/userguide/notification-content-structure.html
+			"""
+		required: false
+		type: object: options: {
+			fetch_concurrency: {
+				description: "The maximum number of objects referenced by a single notification to fetch at once."
+				required:    false
+				type: uint: default: 4
+			}
+			fetch_objects: {
+				description: """
+					Whether or not to fetch the created objects.
+
+					When enabled, the contents of each object are decoded as configured by `framing` and
+					`decoding`. Otherwise, the JSON of each notification record is decoded instead.
+
+					Objects are decompressed according to `compression`, and are subject to
+					`max_decompressed_bytes` once decompressed.
+
+					Either way, the SQS message is only deleted once all the events decoded from it have been
+					delivered, and is left in the queue if any of its objects can't be fetched.
+					"""
+				required: false
+				type: bool: default: true
+			}
+		}
+	}
 	tls: {
 		description: "TLS configuration."
 		required:    false
@@ -1,10 +1,23 @@
 package metadata
 
 base: components: sources: demo_logs: configuration: {
+	cardinality: {
+		description: """
+			The number of distinct series to output for each kind of metric.
+
+			Series are told apart by their `series` tag.
+			"""
+		relevant_when: "format = \"metrics\""
+		required:      false
+		type: uint: default: 10
+	}
 	count: {
 		description: """
 			The total number of lines to output.
 
+			With the `metrics` format, this is the total number of batches of metrics to output, and with
+			the `traces` format, the total number of traces.
+
 			By default, the source continuously prints logs (infinitely).
 			"""
 		required: false
@@ -63,8 +76,21 @@ base: components: sources: demo_logs: configuration: {
 				apache_error:  "Randomly generated logs in [Apache error](\\(urls.apache_error)) format."
 				bsd_syslog:    "Randomly generated logs in Syslog format ([RFC 3164](\\(urls.syslog_3164)))."
 				json:          "Randomly generated HTTP server logs in [JSON](\\(urls.json)) format."
-				shuffle:       "Lines are chosen at random from the list specified using `lines`."
-				syslog:        "Randomly generated logs in Syslog format ([RFC 5424](\\(urls.syslog_5424)))."
+				metrics: """
+					Randomly generated metrics, instead of logs.
+
+					Each batch holds a counter, a gauge, a histogram, and a sketch for every series. The
+					`framing` and `decoding` options do not apply.
+					"""
+				shuffle: "Lines are chosen at random from the list specified using `lines`."
+				syslog:  "Randomly generated logs in Syslog format ([RFC 5424](\\(urls.syslog_5424)))."
+				traces: """
+					Randomly generated traces, instead of logs.
+
+					Each line is replaced by a trace, shaped as those received by the `datadog_agent` source,
+					made of a root span and the spans of the operations it calls, with their services,
+					resources, durations, and errors. The `framing` and `decoding` options do not apply.
+					"""
 			}
 		}
 	}
@@ -142,6 +168,19 @@ base: components: sources: demo_logs: configuration: {
 			}
 		}
 	}
+	generation_timestamp_key: {
+		description: """
+			Event field for the monotonic timestamp at which each log event was generated.
+
+			The value is a number of nanoseconds, only meaningful within the same Vector instance. Paired
+			with the `latency_key` option of the `blackhole` sink, it measures the end-to-end latency of
+			events through the topology.
+
+			By default, no timestamp is added.
+			"""
+		required: false
+		type: string: syntax: "literal"
+	}
 	interval: {
 		description: """
 			The amount of time, in seconds, to pause between each batch of output lines.
@@ -158,10 +197,115 @@ base: components: sources: demo_logs: configuration: {
 		required:      true
 		type: array: items: type: string: syntax: "literal"
 	}
+	pacing: {
+		description: """
+			Variations of the pauses between batches set by `interval`, to resemble production traffic.
+
+			They have no effect when `interval` is `0.0`.
+			"""
+		required: false
+		type: object: {
+			default: {
+				arrival: "fixed"
+				burst:   null
+				jitter:  0.0
+			}
+			options: {
+				arrival: {
+					description: "How the pauses between batches are distributed."
+					required:    false
+					type: string: {
+						default: "fixed"
+						enum: {
+							fixed: "Each pause lasts `interval`."
+							poisson: """
+								The pauses follow an exponential distribution with a mean of `interval`, so batches arrive
+								as a Poisson process.
+								"""
+						}
+					}
+				}
+				burst: {
+					description: """
+						Periodic bursts of output.
+
+						Output alternates between bursts, during which `size` batches are output after each pause, and
+						quiet periods, during which a single batch is.
+						"""
+					required: false
+					type: object: options: {
+						duty_cycle: {
+							description: "The fraction of each cycle spent bursting, between `0.0` and `1.0`."
+							required:    false
+							type: float: default: 0.1
+						}
+						period_secs: {
+							description: "The length, in seconds, of a cycle made of a burst followed by a quiet period."
+							required:    false
+							type: float: default: 60.0
+						}
+						size: {
+							description: "The number of batches output after each pause during a burst."
+							required:    false
+							type: uint: default: 10
+						}
+					}
+				}
+				jitter: {
+					description: """
+						The maximum deviation of each pause from its length, as a fraction of that length.
+
+						For example, with a `jitter` of `0.2`, each pause lasts between 80% and 120% of its length,
+						chosen at random. Must be between `0.0` and `1.0`.
+						"""
+					required: false
+					type: float: default: 0.0
+				}
+			}
+		}
+	}
+	seed: {
+		description: """
+			The seed used to generate the output.
+
+			When set, the lines chosen by the `shuffle` format and the values of the `metrics` and
+			`traces` formats are the same on every run, and on every instance sharing the seed.
+			Timestamps, and the contents of the other formats, are still random.
+			"""
+		required: false
+		type: uint: {}
+	}
 	sequence: {
 		description:   "If `true`, each output line starts with an increasing sequence number, beginning with 0."
 		relevant_when: "format = \"shuffle\""
 		required:      false
 		type: bool: default: false
 	}
+	shard_count: {
+		description: """
+			The number of shards the output is split into.
+
+			`count` is the total across all shards.
+			"""
+		required: false
+		type: uint: default: 1
+	}
+	shard_index: {
+		description: """
+			The index of the shard of the output generated by this instance, starting at 0.
+
+			Combined with `shard_count`, this allows multiple instances to each generate a disjoint
+			portion of the same output: instance `i` only outputs the lines whose sequence number `n`
+			satisfies `n % shard_count == i`, or with the `metrics` format, only the series whose
+			number satisfies the same.
+			"""
+		required: false
+		type: uint: default: 0
+	}
+	spans_per_trace: {
+		description:   "The number of spans of each trace, including its root span."
+		relevant_when: "format = \"traces\""
+		required:      false
+		type: uint: default: 5
+	}
 }
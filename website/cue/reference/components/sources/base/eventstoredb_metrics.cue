@@ -1,6 +1,36 @@
 package metadata
 
 base: components: sources: eventstoredb_metrics: configuration: {
+	absolute_counters: {
+		description: """
+			Whether or not to emit counters as absolute values.
+
+			By default, counters such as `disk_read_bytes_total` are emitted as incremental metrics,
+			holding the increase since the previous scrape, so nothing is emitted for them on the first
+			scrape. When enabled, they are instead emitted as absolute metrics, holding the totals
+			reported by EventStoreDB, as in previous versions.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	collect_projections: {
+		description: """
+			Whether or not to also collect the status and progress of projections.
+
+			These are read from the `/projections/any` endpoint of the host `endpoint` points to.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	collect_subscriptions: {
+		description: """
+			Whether or not to also collect statistics of persistent subscriptions, such as their lag.
+
+			These are read from the `/subscriptions` endpoint of the host `endpoint` points to.
+			"""
+		required: false
+		type: bool: default: false
+	}
 	default_namespace: {
 		description: """
 			Overrides the default namespace for the metrics emitted by the source.
@@ -18,6 +48,65 @@ base: components: sources: eventstoredb_metrics: configuration: {
 			syntax:  "literal"
 		}
 	}
+	relabel_rules: {
+		description: """
+			Rules relabeling the metrics scraped, applied in order before they are emitted.
+
+			This allows normalizing the names and values of tags at the source, close to the rest of
+			the scrape configuration, rather than in a downstream transform.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: object: options: {
+				add_tags: {
+					description: "Tags to add, replacing the value of those already present."
+					required:    false
+					type: object: {
+						default: {}
+						options: "*": {
+							description: "The value of the tag."
+							required:    true
+							type: string: syntax: "literal"
+						}
+					}
+				}
+				name_regex: {
+					description: """
+						A regular expression the name of a metric must match for the rule to apply to it.
+
+						The expression is anchored, so that it must match the whole name, excluding the namespace.
+						The rule applies to all metrics if unset.
+						"""
+					required: false
+					type: string: {
+						examples: ["projection_.*"]
+						syntax: "literal"
+					}
+				}
+				remove_tags: {
+					description: "Tags to remove."
+					required:    false
+					type: array: {
+						default: []
+						items: type: string: syntax: "literal"
+					}
+				}
+				rename_tags: {
+					description: "Tags to rename, from their current name to their new one."
+					required:    false
+					type: object: {
+						default: {}
+						options: "*": {
+							description: "The new name of the tag."
+							required:    true
+							type: string: syntax: "literal"
+						}
+					}
+				}
+			}
+		}
+	}
 	scrape_interval_secs: {
 		description: "The interval between scrapes, in seconds."
 		required:    false
@@ -1,6 +1,24 @@
 package metadata
 
 base: components: sources: file_descriptor: configuration: {
+	acknowledgements: {
+		description: """
+			Controls how acknowledgements are handled by this source.
+
+			This setting is **deprecated** in favor of enabling `acknowledgements` at the [global][global_acks] or sink level. Enabling or disabling acknowledgements at the source level has **no effect** on acknowledgement behavior.
+
+			See [End-to-end Acknowledgements][e2e_acks] for more information on how Vector handles event acknowledgement.
+
+			[global_acks]: https://vector.dev/docs/reference/configuration/global-options/#acknowledgements
+			[e2e_acks]: https://vector.dev/docs/about/under-the-hood/architecture/end-to-end-acknowledgements/
+			"""
+		required: false
+		type: object: options: enabled: {
+			description: "Whether or not end-to-end acknowledgements are enabled for this source."
+			required:    false
+			type: bool: {}
+		}
+	}
 	decoding: {
 		description: "Configures how events are decoded from raw bytes."
 		required:    false
@@ -46,8 +64,12 @@ base: components: sources: file_descriptor: configuration: {
 		}
 	}
 	fd: {
-		description: "The file descriptor number to read from."
-		required:    true
+		description: """
+			The file descriptor number to read from.
+
+			Exactly one of `fd`, `pipes`, and `listen_fds` must be set.
+			"""
+		required: false
 		type: uint: {}
 	}
 	framing: {
@@ -132,6 +154,26 @@ base: components: sources: file_descriptor: configuration: {
 		required: false
 		type: string: syntax: "literal"
 	}
+	listen_fds: {
+		description: """
+			The file descriptors passed by systemd socket activation to read from.
+
+			Each file descriptor is selected either by its index among those passed, starting at 0, or
+			by the name set with `FileDescriptorName=` in the socket unit. This allows reading from
+			pipes set up by systemd, such as those of `ListenFIFO=`, without redirecting them to a
+			fixed file descriptor number.
+
+			Exactly one of `fd`, `pipes`, and `listen_fds` must be set.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: {
+				string: syntax: "literal"
+				uint: {}
+			}
+		}
+	}
 	max_length: {
 		description: """
 			The maximum buffer size, in bytes, of incoming messages.
@@ -141,4 +183,84 @@ base: components: sources: file_descriptor: configuration: {
 		required: false
 		type: uint: default: 102400
 	}
+	pipes: {
+		description: """
+			The named pipes (FIFOs) to read from.
+
+			Each pipe is read independently, and is reopened whenever its writer closes it, so writers
+			can restart or recreate the pipe without the source stopping.
+
+			Exactly one of `fd`, `pipes`, and `listen_fds` must be set.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: object: options: {
+				host_key: {
+					description: """
+						Overrides the name of the log field used to add the current hostname to events read from
+						this pipe.
+
+						By default, the `host_key` option of the source is used.
+						"""
+					required: false
+					type: string: syntax: "literal"
+				}
+				path: {
+					description: "The path of the named pipe."
+					required:    true
+					type: string: syntax: "literal"
+				}
+				tags: {
+					description: "Fields added to each event read from this pipe."
+					required:    false
+					type: object: {
+						default: {}
+						options: "*": {
+							description: "Fields added to each event read from this pipe."
+							required:    true
+							type: string: syntax: "literal"
+						}
+					}
+				}
+			}
+		}
+	}
+	validation: {
+		description: """
+			Configuration for validating checksummed, sequenced frames.
+
+			When enabled, input is expected to be a stream of frames, each made of a 16 byte header and a
+			payload. The header holds the length of the payload as a 32-bit integer, the sequence number
+			of the frame as a 64-bit integer, and the CRC-32 (IEEE) checksum of the payload as a 32-bit
+			integer, all big-endian. The payload of each valid frame is decoded as configured by
+			`decoding`.
+
+			Frames whose checksum does not match are dropped, as are frames whose sequence number is not
+			higher than the last one seen. Skipped sequence numbers are reported as gaps.
+			"""
+		required: false
+		type: object: options: {
+			insert_gap_markers: {
+				description: """
+					Whether or not to insert an event marking each detected gap in the sequence of frames.
+
+					Gap markers are log events with `gap_start` and `gap_end` fields holding the first and
+					last missing sequence numbers.
+					"""
+				required: false
+				type: bool: default: false
+			}
+			max_frame_length: {
+				description: """
+					The maximum length, in bytes, of a frame payload.
+
+					A frame with a longer payload means the stream can no longer be trusted, and stops the
+					source from reading any further.
+					"""
+				required: false
+				type: uint: default: 8388608
+			}
+		}
+	}
 }
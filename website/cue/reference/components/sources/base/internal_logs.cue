@@ -1,6 +1,96 @@
 package metadata
 
 base: components: sources: internal_logs: configuration: {
+	component_quota: {
+		description: """
+			The quota of internal logs forwarded for each component.
+
+			When a component emits more internal logs in a second than its quota, the logs over the quota
+			are suppressed, and a single summary log is forwarded for the component at the end of the
+			second instead. The summary has the fields identifying the component under `vector`, the
+			number of logs `suppressed`, and the most frequent `top_messages` suppressed, each with its
+			`count`. This is applied after the other filters, and does not apply to drop reports.
+
+			Summaries can be told apart by their `metadata.target` field, which is always
+			`vector::internal_logs::quota`.
+			"""
+		required: false
+		type: object: options: {
+			max_events_per_sec: {
+				description: "The maximum number of internal logs forwarded for each component per second."
+				required:    true
+				type: uint: {}
+			}
+			top_messages: {
+				description: "The maximum number of distinct messages listed in each summary."
+				required:    false
+				type: uint: default: 5
+			}
+		}
+	}
+	drop_reports: {
+		description: """
+			Whether or not to include drop reports.
+
+			A drop report is emitted every time a component drops events, and consists of the
+			`count` of dropped events, the `reason` they were dropped, whether dropping them was
+			`intentional`, and the fields identifying the component under `vector`. Unlike other
+			internal logs, drop reports are never rate limited nor subject to the log level, which
+			makes them suitable for alerting on data loss.
+
+			Drop reports can be told apart by their `metadata.target` field, which is always
+			`vector::drop_report`.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	guard: {
+		description: """
+			Configuration for guarding the data plane against the cost of self-telemetry.
+
+			The self-telemetry pipeline is made of the `internal_logs` and `internal_metrics` sources, and
+			every component whose inputs all come from that pipeline. At the end of each window, the utilization of the pipeline
+			is computed as the share of the window the source spent producing telemetry, plus the
+			`utilization` of each of the downstream components. When it exceeds `max_utilization`, the
+			source is throttled for the following windows, until the utilization falls back under half of
+			`max_utilization`: `internal_logs` only forwards drop reports, and `internal_metrics` skips its
+			scrapes.
+
+			Utilization is the share of time components spend busy rather than waiting for events, so it
+			includes time spent waiting on I/O, and doesn't account for the CPU used by other threads on
+			their behalf. It is a proxy for the CPU used by the pipeline, not a measure of it.
+
+			Warnings about the guard can be told apart by their `metadata.target` field, which is always
+			`vector::self_telemetry_guard`, and are never throttled nor dropped.
+			"""
+		required: false
+		type: object: options: {
+			drop_feedback: {
+				description: """
+					Whether or not to drop the telemetry about the components of the self-telemetry pipeline.
+
+					Such telemetry describes the work done to handle telemetry, which in turn produces more of
+					it. Drop reports are never dropped, so that data loss is always accounted for.
+					"""
+				required: false
+				type: bool: default: true
+			}
+			max_utilization: {
+				description: """
+					The highest utilization the self-telemetry pipeline may have.
+
+					A utilization of `1.0` is that of a single component busy all the time.
+					"""
+				required: false
+				type: float: default: 0.05
+			}
+			window_secs: {
+				description: "The window, in seconds, the utilization of the self-telemetry pipeline is computed over."
+				required:    false
+				type: uint: default: 10
+			}
+		}
+	}
 	host_key: {
 		description: """
 			Overrides the name of the log field used to add the current hostname to each event.
@@ -14,6 +104,53 @@ base: components: sources: internal_logs: configuration: {
 		required: false
 		type: string: syntax: "literal"
 	}
+	include_span_hierarchy: {
+		description: """
+			Whether or not to include the spans each internal log was emitted in, with all their fields.
+
+			When enabled, internal logs emitted within spans carry a `span_hierarchy` array, ordered
+			from the outermost span to the innermost one. Each entry has the `name` and `target` of the
+			span, and a `fields` object with all of its fields, which allows reconstructing the context
+			a log was emitted in. Unlike the fields identifying components, which are always added under
+			`vector`, these may hold any data the span was created with.
+
+			Only spans created after this source is started have all their fields.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	include_span_timings: {
+		description: """
+			Whether or not to include the timings of the spans each internal log was emitted in.
+
+			When enabled, internal logs emitted within spans carry a `spans` array, ordered from the
+			outermost span to the innermost one. Each entry has the `name` of the span, and the time in
+			nanoseconds it had spent executing (`busy_ns`) and waiting (`idle_ns`) when the log was
+			emitted, which allows breaking down where time is spent within a component.
+
+			Only spans created after this source is started are timed.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	level: {
+		description: """
+			The minimum severity of internal logs to forward.
+
+			Internal logs less severe than this are dropped before entering the topology. Drop reports
+			are always forwarded, regardless of their severity.
+
+			By default, all internal logs emitted at the global log level are forwarded.
+			"""
+		required: false
+		type: string: enum: {
+			debug: "Forward debug, info, warning and error internal logs."
+			error: "Forward error internal logs only."
+			info:  "Forward info, warning and error internal logs."
+			trace: "Forward all internal logs."
+			warn:  "Forward warning and error internal logs."
+		}
+	}
 	pid_key: {
 		description: """
 			Overrides the name of the log field used to add the current process ID to each event.
@@ -25,4 +162,17 @@ base: components: sources: internal_logs: configuration: {
 		required: false
 		type: string: syntax: "literal"
 	}
+	rate_limit_secs: {
+		description: """
+			The window, in seconds, during which repeated internal logs are forwarded only once.
+
+			Internal logs are considered repeated if they have the same message and were emitted by
+			the same component. This is applied on top of the rate limiting done when the logs are
+			emitted, and does not apply to drop reports.
+
+			By default, repeated internal logs are not deduplicated.
+			"""
+		required: false
+		type: uint: {}
+	}
 }
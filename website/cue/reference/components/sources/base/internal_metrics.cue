@@ -1,6 +1,78 @@
 package metadata
 
 base: components: sources: internal_metrics: configuration: {
+	adaptive_interval: {
+		description: """
+			Configuration for lowering the scrape frequency when scrapes get expensive.
+
+			After each scrape, if capturing the metrics took longer than `max_capture_ratio` of the current
+			interval, the interval is lengthened so that it no longer does, up to `max_interval_secs`. Once
+			scrapes get cheap again, the interval is shortened back towards `scrape_interval_secs`.
+
+			This keeps the internal metrics of very large topologies from taking up a material share of
+			CPU. The interval in effect is emitted as the `internal_metrics_scrape_interval_seconds` gauge.
+			"""
+		required: false
+		type: object: options: {
+			max_capture_ratio: {
+				description: "The largest share of the interval capturing metrics may take, between 0 and 1."
+				required:    false
+				type: float: default: 0.1
+			}
+			max_interval_secs: {
+				description: "The longest interval, in seconds, the scrape interval is lengthened to."
+				required:    false
+				type: float: default: 60.0
+			}
+		}
+	}
+	guard: {
+		description: """
+			Configuration for guarding the data plane against the cost of self-telemetry.
+
+			The self-telemetry pipeline is made of the `internal_logs` and `internal_metrics` sources, and
+			every component whose inputs all come from that pipeline. At the end of each window, the utilization of the pipeline
+			is computed as the share of the window the source spent producing telemetry, plus the
+			`utilization` of each of the downstream components. When it exceeds `max_utilization`, the
+			source is throttled for the following windows, until the utilization falls back under half of
+			`max_utilization`: `internal_logs` only forwards drop reports, and `internal_metrics` skips its
+			scrapes.
+
+			Utilization is the share of time components spend busy rather than waiting for events, so it
+			includes time spent waiting on I/O, and doesn't account for the CPU used by other threads on
+			their behalf. It is a proxy for the CPU used by the pipeline, not a measure of it.
+
+			Warnings about the guard can be told apart by their `metadata.target` field, which is always
+			`vector::self_telemetry_guard`, and are never throttled nor dropped.
+			"""
+		required: false
+		type: object: options: {
+			drop_feedback: {
+				description: """
+					Whether or not to drop the telemetry about the components of the self-telemetry pipeline.
+
+					Such telemetry describes the work done to handle telemetry, which in turn produces more of
+					it. Drop reports are never dropped, so that data loss is always accounted for.
+					"""
+				required: false
+				type: bool: default: true
+			}
+			max_utilization: {
+				description: """
+					The highest utilization the self-telemetry pipeline may have.
+
+					A utilization of `1.0` is that of a single component busy all the time.
+					"""
+				required: false
+				type: float: default: 0.05
+			}
+			window_secs: {
+				description: "The window, in seconds, the utilization of the self-telemetry pipeline is computed over."
+				required:    false
+				type: uint: default: 10
+			}
+		}
+	}
 	namespace: {
 		description: """
 			Overrides the default namespace for the metrics emitted by the source.
@@ -10,6 +82,25 @@ base: components: sources: internal_metrics: configuration: {
 		required: false
 		type: string: syntax: "literal"
 	}
+	process_metrics: {
+		description: """
+			Configuration for emitting metrics about the Vector process itself.
+
+			On each scrape, the CPU time, the resident and virtual memory, and, on Linux, the number of
+			open file descriptors of the process are emitted alongside the component metrics.
+			"""
+		required: false
+		type: object: options: tokio_runtime: {
+			description: """
+				Whether to also emit statistics of the Tokio runtime Vector runs on.
+
+				Tokio only exposes these statistics when Vector is built with `--cfg tokio_unstable`, so
+				this has no effect otherwise.
+				"""
+			required: false
+			type: bool: default: true
+		}
+	}
 	scrape_interval_secs: {
 		description: "The interval between metric gathering, in seconds."
 		required:    false
@@ -49,4 +140,38 @@ base: components: sources: internal_metrics: configuration: {
 			}
 		}
 	}
+	top_components: {
+		description: """
+			Configuration for only emitting the metrics of the most relevant components individually.
+
+			On each scrape, components are ranked by the value of `metric`, and only the metrics of the
+			top `k` components are emitted as is. The metrics of all other components are summed up into
+			a single series per metric, with a `component_id` tag of `other`. As the components summed up
+			change from one scrape to the next, counters summed up this way are emitted as gauges. Metrics
+			that are not specific to a component are always emitted.
+
+			This bounds the volume of internal metrics in topologies with many components.
+			"""
+		required: false
+		type: object: options: {
+			k: {
+				description: "The number of components whose metrics are emitted individually."
+				required:    true
+				type: uint: {}
+			}
+			metric: {
+				description: """
+					The name of the metric to rank components by.
+
+					Components are ranked by how much a counter increased since the previous scrape, or by the
+					current value of a gauge, summed over all of their series of that metric.
+					"""
+				required: false
+				type: string: {
+					default: "component_sent_events_total"
+					syntax:  "literal"
+				}
+			}
+		}
+	}
 }
@@ -73,6 +73,109 @@ base: components: sources: journald: configuration: {
 			items: type: string: syntax: "literal"
 		}
 	}
+	gateway: {
+		description: """
+			Configuration for reading from a remote `systemd-journal-gatewayd` endpoint.
+
+			When set, journal entries are streamed from the gateway over HTTP instead of being read by
+			running `journalctl`, and `journalctl_path` and `journal_directory` are ignored. The cursor of
+			the last entry read is checkpointed as it is when reading the local journal, so that reading
+			resumes right after it on restart.
+			"""
+		required: false
+		type: object: options: {
+			endpoint: {
+				description: "The base URL of the gateway, such as `http://localhost:19531`."
+				required:    true
+				type: string: syntax: "literal"
+			}
+			tls: {
+				description: "TLS configuration."
+				required:    false
+				type: object: options: {
+					alpn_protocols: {
+						description: """
+							Sets the list of supported ALPN protocols.
+
+							Declare the supported ALPN protocols, which are used during negotiation with peer. Prioritized in the order
+							they are defined.
+							"""
+						required: false
+						type: array: items: type: string: syntax: "literal"
+					}
+					ca_file: {
+						description: """
+							Absolute path to an additional CA certificate file.
+
+							The certificate must be in the DER or PEM (X.509) format. Additionally, the certificate can be provided as an inline string in PEM format.
+							"""
+						required: false
+						type: string: syntax: "literal"
+					}
+					crt_file: {
+						description: """
+							Absolute path to a certificate file used to identify this server.
+
+							The certificate must be in DER, PEM (X.509), or PKCS#12 format. Additionally, the certificate can be provided as
+							an inline string in PEM format.
+
+							If this is set, and is not a PKCS#12 archive, `key_file` must also be set.
+							"""
+						required: false
+						type: string: syntax: "literal"
+					}
+					key_file: {
+						description: """
+							Absolute path to a private key file used to identify this server.
+
+							The key must be in DER or PEM (PKCS#8) format. Additionally, the key can be provided as an inline string in PEM format.
+							"""
+						required: false
+						type: string: syntax: "literal"
+					}
+					key_pass: {
+						description: """
+							Passphrase used to unlock the encrypted key file.
+
+							This has no effect unless `key_file` is set.
+							"""
+						required: false
+						type: string: syntax: "literal"
+					}
+					verify_certificate: {
+						description: """
+							Enables certificate verification.
+
+							If enabled, certificates must be valid in terms of not being expired, as well as being issued by a trusted
+							issuer. This verification operates in a hierarchical manner, checking that not only the leaf certificate (the
+							certificate presented by the client/server) is valid, but also that the issuer of that certificate is valid, and
+							so on until reaching a root certificate.
+
+							Relevant for both incoming and outgoing connections.
+
+							Do NOT set this to `false` unless you understand the risks of not verifying the validity of certificates.
+							"""
+						required: false
+						type: bool: {}
+					}
+					verify_hostname: {
+						description: """
+							Enables hostname verification.
+
+							If enabled, the hostname used to connect to the remote host must be present in the TLS certificate presented by
+							the remote host, either as the Common Name or as an entry in the Subject Alternative Name extension.
+
+							Only relevant for outgoing connections.
+
+							Do NOT set this to `false` unless you understand the risks of not verifying the remote hostname.
+							"""
+						required: false
+						type: bool: {}
+					}
+				}
+			}
+		}
+	}
 	include_matches: {
 		description: """
 			A list of sets of field/value pairs to monitor.
@@ -1,6 +1,76 @@
 package metadata
 
 base: components: sources: socket: configuration: {
+	ack_format: {
+		description: """
+			The format of the responses sent back to clients for each message received.
+
+			When set, a response is written back on the connection once the events decoded from each
+			message have been accepted, or, if `acknowledgements` are enabled, once they have been
+			delivered. If delivery fails, an error response is written and the connection is closed.
+
+			By default, nothing is ever written back to clients.
+			"""
+		relevant_when: "mode = \"tcp\""
+		required:      false
+		type: string: enum: {
+			length_prefixed: """
+				Each response is the same text as for `newline`, without the trailing newline, prefixed by
+				its length as a 32-bit big-endian integer.
+				"""
+			newline: """
+				Each response is a line of text terminated by a newline.
+
+				The line is `OK` if the events were delivered, or `ERR` followed by a space and the reason
+				(`errored` or `rejected`) otherwise.
+				"""
+		}
+	}
+	acknowledgements: {
+		description: """
+			Controls how acknowledgements are handled by this source.
+
+			This setting is **deprecated** in favor of enabling `acknowledgements` at the [global][global_acks] or sink level. Enabling or disabling acknowledgements at the source level has **no effect** on acknowledgement behavior.
+
+			See [End-to-end Acknowledgements][e2e_acks] for more information on how Vector handles event acknowledgement.
+
+			[global_acks]: https://vector.dev/docs/reference/configuration/global-options/#acknowledgements
+			[e2e_acks]: https://vector.dev/docs/about/under-the-hood/architecture/end-to-end-acknowledgements/
+			"""
+		relevant_when: "mode = \"tcp\""
+		required:      false
+		type: object: options: enabled: {
+			description: "Whether or not end-to-end acknowledgements are enabled for this source."
+			required:    false
+			type: bool: {}
+		}
+	}
+	adaptive_read_buffer: {
+		description: """
+			Sizes the buffer each connection is read into based on the traffic observed on it.
+
+			By default, a fixed size buffer is used for every connection.
+			"""
+		relevant_when: "mode = \"tcp\""
+		required:      false
+		type: object: options: {
+			max_bytes: {
+				description: """
+					The largest size, in bytes, the read buffer of a connection is grown to.
+
+					Frames larger than this are still read in full, the buffer is only sized for them while
+					they are being decoded.
+					"""
+				required: false
+				type: uint: default: 1048576
+			}
+			min_bytes: {
+				description: "The smallest size, in bytes, the read buffer of a connection is shrunk to."
+				required:    false
+				type: uint: default: 1024
+			}
+		}
+	}
 	address: {
 		description:   "The address to listen for connections on."
 		relevant_when: "mode = \"tcp\" or mode = \"udp\""
@@ -16,6 +86,53 @@ base: components: sources: socket: configuration: {
 		required:      false
 		type: uint: {}
 	}
+	connection_metadata: {
+		description: """
+			Connection-level metadata added to each event.
+
+			Each field is only added when its key is set. For connections accepted without TLS, the TLS
+			fields are not added.
+
+			For the `Vector` log namespace, the fields are inserted as the `local_addr`, `tls_version`,
+			`tls_cipher` and `tls_server_name` source metadata fields, and the keys only enable them.
+			"""
+		relevant_when: "mode = \"tcp\""
+		required:      false
+		type: object: options: {
+			local_addr_key: {
+				description: """
+					Event field for the local address the connection was accepted on.
+
+					The value is the address of the listener, including the port i.e. `10.0.0.1:9000`.
+					"""
+				required: false
+				type: string: syntax: "literal"
+			}
+			tls_cipher_key: {
+				description: """
+					Event field for the TLS cipher suite negotiated for the connection i.e.
+					`TLS_AES_256_GCM_SHA384`.
+					"""
+				required: false
+				type: string: syntax: "literal"
+			}
+			tls_server_name_key: {
+				description: """
+					Event field for the server name requested by the client through SNI i.e.
+					`logs.example.com`.
+
+					Not added for clients not using SNI.
+					"""
+				required: false
+				type: string: syntax: "literal"
+			}
+			tls_version_key: {
+				description: "Event field for the TLS protocol version negotiated for the connection i.e. `TLSv1.3`."
+				required:    false
+				type: string: syntax: "literal"
+			}
+		}
+	}
 	decoding: {
 		description: "Configures how events are decoded from raw bytes."
 		required:    false
@@ -60,6 +177,33 @@ base: components: sources: socket: configuration: {
 			}
 		}
 	}
+	decoding_concurrency: {
+		description: """
+			Deserializes the frames of each connection on several tasks.
+
+			This lets a single busy connection use more than one core. By default, the frames of each
+			connection are deserialized on a single task.
+			"""
+		relevant_when: "mode = \"tcp\""
+		required:      false
+		type: object: options: {
+			ordered: {
+				description: """
+					Whether the events of a connection are forwarded in the order they were received in.
+
+					When disabled, the events of each chunk are forwarded as soon as they are deserialized,
+					which keeps a slow chunk from holding back the ones after it.
+					"""
+				required: false
+				type: bool: default: true
+			}
+			workers: {
+				description: "The number of chunks of frames of a connection deserialized at the same time."
+				required:    true
+				type: uint: {}
+			}
+		}
+	}
 	framing: {
 		description: """
 			Framing configuration.
@@ -131,6 +275,24 @@ base: components: sources: socket: configuration: {
 			}
 		}
 	}
+	handoff_socket: {
+		description: """
+			The path of a Unix domain socket over which established connections can be handed off to
+			this source.
+
+			Other processes, such as load balancers or connection redirectors, can pass connected TCP
+			sockets as file descriptors in `SCM_RIGHTS` control messages. These connections are then
+			handled as if they had been accepted on `address`, without the source having to rebind it.
+
+			The socket is only accessible to the user Vector runs as. A socket file left behind by a
+			previous run is replaced, as long as nothing listens on it anymore.
+
+			Only supported on Unix.
+			"""
+		relevant_when: "mode = \"tcp\""
+		required:      false
+		type: string: syntax: "literal"
+	}
 	host_key: {
 		description: """
 			Overrides the name of the log field used to add the peer host to each event.
@@ -144,6 +306,17 @@ base: components: sources: socket: configuration: {
 		required: false
 		type: string: syntax: "literal"
 	}
+	idle_timeout_secs: {
+		description: """
+			The amount of time, in seconds, a connection can stay open without sending any data before it
+			is closed.
+
+			By default, connections are never closed for being idle.
+			"""
+		relevant_when: "mode = \"tcp\""
+		required:      false
+		type: uint: {}
+	}
 	keepalive: {
 		description:   "TCP keepalive settings for socket-based components."
 		relevant_when: "mode = \"tcp\""
@@ -177,6 +350,65 @@ base: components: sources: socket: configuration: {
 			unix_stream:   "Listen on UDS, in stream mode. (Unix domain socket)"
 		}
 	}
+	multicast_groups: {
+		description: """
+			The multicast groups to join.
+
+			The listening address should be a wildcard address, such as `0.0.0.0:9000`, or the address
+			of the groups themselves, for messages sent to the groups to be received.
+			"""
+		relevant_when: "mode = \"udp\""
+		required:      false
+		type: array: {
+			default: []
+			items: type: object: options: {
+				group: {
+					description: "The address of the multicast group."
+					required:    true
+					type: string: syntax: "literal"
+				}
+				interface: {
+					description: """
+						The address of the local interface to join an IPv4 group on.
+
+						By default, the operating system chooses the interface.
+						"""
+					required: false
+					type: string: syntax: "literal"
+				}
+				interface_index: {
+					description: """
+						The index of the local interface to join an IPv6 group on.
+
+						By default, the operating system chooses the interface.
+						"""
+					required: false
+					type: uint: {}
+				}
+			}
+		}
+	}
+	multicast_loop: {
+		description: """
+			Whether multicast messages sent from the listening socket are looped back to the host.
+
+			By default, the operating system's default is used.
+			"""
+		relevant_when: "mode = \"udp\""
+		required:      false
+		type: bool: {}
+	}
+	multicast_ttl: {
+		description: """
+			The time-to-live, or hop limit for IPv6, of multicast messages sent from the listening
+			socket.
+
+			By default, the operating system's default is used.
+			"""
+		relevant_when: "mode = \"udp\""
+		required:      false
+		type: uint: {}
+	}
 	path: {
 		description: """
 			The Unix socket path.
@@ -226,6 +458,24 @@ base: components: sources: socket: configuration: {
 		required:      false
 		type: uint: {}
 	}
+	sockets: {
+		description: """
+			The number of sockets to receive messages on.
+
+			When more than one, the sockets are all bound to the listening address with `SO_REUSEPORT`
+			and each is read by its own task, so that the kernel spreads the load across them by
+			hashing the flows of the messages. Messages of a single flow are all received on the same
+			socket.
+
+			Only supported on Unix, and can't be used with a systemd socket or combined with
+			`multicast_groups`, as each socket would receive every multicast message.
+
+			By default, a single socket is used.
+			"""
+		relevant_when: "mode = \"udp\""
+		required:      false
+		type: uint: {}
+	}
 	tls: {
 		description:   "TlsEnableableConfig for `sources`, adding metadata from the client certificate"
 		relevant_when: "mode = \"tcp\""
@@ -250,6 +500,45 @@ base: components: sources: socket: configuration: {
 				required: false
 				type: string: syntax: "literal"
 			}
+			client_allowlist: {
+				description: """
+					Restricts which clients can connect, based on the certificate they present.
+
+					TLS must be enabled, and clients must still present a certificate valid for the configured CA.
+					Their certificate must then match at least one of the listed SPKI fingerprints or Subject
+					Alternative Name patterns, or the connection is closed. When `client_metadata_key` is set, the
+					matched identity is added to the client certificate metadata as `matched_identity`.
+					"""
+				required: false
+				type: object: options: {
+					san_patterns: {
+						description: """
+							Patterns matched against the DNS name, URI, and email Subject Alternative Names of client
+							certificates.
+
+							`*` matches a single label, that is any sequence of characters other than `.` and `/`. For
+							example, `*.example.com` matches `logs.example.com` but not `eu.logs.example.com`.
+							"""
+						required: false
+						type: array: {
+							default: []
+							items: type: string: syntax: "literal"
+						}
+					}
+					spki_sha256: {
+						description: """
+							SHA-256 fingerprints of the `SubjectPublicKeyInfo` of allowed client certificates.
+
+							Fingerprints are hex encoded, and may contain colons between bytes.
+							"""
+						required: false
+						type: array: {
+							default: []
+							items: type: string: syntax: "literal"
+						}
+					}
+				}
+			}
 			client_metadata_key: {
 				description: "Event field for client certificate metadata."
 				required:    false
@@ -295,6 +584,106 @@ base: components: sources: socket: configuration: {
 				required: false
 				type: string: syntax: "literal"
 			}
+			policy: {
+				description: """
+					Policy enforced on the TLS connections accepted by a source.
+
+					All options are validated when the source is built, and the option breaking the policy is
+					named in the resulting error.
+					"""
+				required: false
+				type: object: options: {
+					cipher_suites: {
+						description: """
+							The cipher suites accepted.
+
+							TLS 1.3 cipher suites use their IANA names, such as `TLS_AES_256_GCM_SHA384`, and earlier
+							ones their OpenSSL names, such as `ECDHE-RSA-AES256-GCM-SHA384`. Each entry must name a
+							single cipher suite: OpenSSL cipher strings such as `HIGH` are rejected.
+
+							Cipher suites not listed are never accepted, whatever the protocol version. If only TLS 1.3
+							cipher suites are listed, connections are restricted to TLS 1.3, and if none are listed,
+							connections are restricted to TLS 1.2 and earlier.
+							"""
+						required: false
+						type: array: items: type: string: syntax: "literal"
+					}
+					curves: {
+						description: "The elliptic curves accepted for key exchange, such as `P-256` or `X25519`."
+						required:    false
+						type: array: items: type: string: syntax: "literal"
+					}
+					fips: {
+						description: """
+							Restricts connections to FIPS 140 approved protocol versions, cipher suites, and curves.
+
+							Unless set explicitly, `min_version`, `cipher_suites`, and `curves` default to the approved
+							values, and setting them to anything else is an error.
+							"""
+						required: false
+						type: bool: default: false
+					}
+					min_version: {
+						description: "The minimum TLS version accepted."
+						required:    false
+						type: string: enum: {
+							"tls1.0": "TLS 1.0."
+							"tls1.1": "TLS 1.1."
+							"tls1.2": "TLS 1.2."
+							"tls1.3": "TLS 1.3."
+						}
+					}
+					require_client_certificate: {
+						description: "Requires clients to present a certificate valid for the configured CA."
+						required:    false
+						type: bool: default: false
+					}
+				}
+			}
+			sni_certificates: {
+				description: """
+					Additional certificates, each presented to the clients requesting one of its server names
+					through SNI.
+
+					Clients not using SNI, or requesting another server name, are presented the certificate set
+					by `crt_file`.
+					"""
+				required: false
+				type: array: {
+					default: []
+					items: type: object: options: {
+						crt_file: {
+							description: "Absolute path to the certificate file, in the same formats as `crt_file`."
+							required:    true
+							type: string: syntax: "literal"
+						}
+						key_file: {
+							description: """
+								Absolute path to the private key file of the certificate, in the same formats as
+								`key_file`.
+								"""
+							required: false
+							type: string: syntax: "literal"
+						}
+						key_pass: {
+							description: "Passphrase used to unlock the encrypted key file."
+							required:    false
+							type: string: syntax: "literal"
+						}
+						server_names: {
+							description: """
+								The server names this certificate is presented for.
+
+								A name starting with `*.` matches any single label in its place, i.e. `*.example.com`
+								matches `logs.example.com` but not `example.com`. Names are matched case-insensitively, in
+								the order the certificates are listed.
+								"""
+							required: true
+							type: array: items: type: string: syntax: "literal"
+						}
+					}
+				}
+			}
 			verify_certificate: {
 				description: """
 					Enables certificate verification.
@@ -327,4 +716,41 @@ base: components: sources: socket: configuration: {
 			}
 		}
 	}
+	wire_capture: {
+		description: """
+			Captures the bytes received on connections to files, to reproduce decoding issues offline.
+
+			The first `max_bytes` bytes received on each captured connection are written, as received
+			after any TLS decryption, to a file in the `wire_capture` subdirectory of the source's data
+			directory. Files are named after the time the connection was accepted, its number since the
+			source started, and the address of the peer. They can be replayed by sending them to a source
+			as is, for example with `nc <address> <port> < <file>`.
+
+			Captures can contain sensitive data, so this should only be enabled while debugging.
+			"""
+		relevant_when: "mode = \"tcp\""
+		required:      false
+		type: object: options: {
+			max_bytes: {
+				description: "The maximum number of bytes captured from each connection."
+				required:    false
+				type: uint: default: 65536
+			}
+			max_connections: {
+				description: """
+					The maximum number of connections captured.
+
+					Once this many connections have been captured, no others are until the source is
+					restarted.
+					"""
+				required: false
+				type: uint: default: 10
+			}
+			sample_rate: {
+				description: "Capture 1 in every `sample_rate` connections."
+				required:    false
+				type: uint: default: 1
+			}
+		}
+	}
 }
@@ -16,6 +16,17 @@ base: components: sources: statsd: configuration: {
 		required:      false
 		type: uint: {}
 	}
+	idle_timeout_secs: {
+		description: """
+			The amount of time, in seconds, a connection can stay open without sending any data before it
+			is closed.
+
+			By default, connections are never closed for being idle.
+			"""
+		relevant_when: "mode = \"tcp\""
+		required:      false
+		type: uint: {}
+	}
 	keepalive: {
 		description:   "TCP keepalive settings for socket-based components."
 		relevant_when: "mode = \"tcp\""
@@ -84,6 +95,45 @@ base: components: sources: statsd: configuration: {
 				required: false
 				type: string: syntax: "literal"
 			}
+			client_allowlist: {
+				description: """
+					Restricts which clients can connect, based on the certificate they present.
+
+					TLS must be enabled, and clients must still present a certificate valid for the configured CA.
+					Their certificate must then match at least one of the listed SPKI fingerprints or Subject
+					Alternative Name patterns, or the connection is closed. When `client_metadata_key` is set, the
+					matched identity is added to the client certificate metadata as `matched_identity`.
+					"""
+				required: false
+				type: object: options: {
+					san_patterns: {
+						description: """
+							Patterns matched against the DNS name, URI, and email Subject Alternative Names of client
+							certificates.
+
+							`*` matches a single label, that is any sequence of characters other than `.` and `/`. For
+							example, `*.example.com` matches `logs.example.com` but not `eu.logs.example.com`.
+							"""
+						required: false
+						type: array: {
+							default: []
+							items: type: string: syntax: "literal"
+						}
+					}
+					spki_sha256: {
+						description: """
+							SHA-256 fingerprints of the `SubjectPublicKeyInfo` of allowed client certificates.
+
+							Fingerprints are hex encoded, and may contain colons between bytes.
+							"""
+						required: false
+						type: array: {
+							default: []
+							items: type: string: syntax: "literal"
+						}
+					}
+				}
+			}
 			client_metadata_key: {
 				description: "Event field for client certificate metadata."
 				required:    false
@@ -129,6 +179,106 @@ base: components: sources: statsd: configuration: {
 				required: false
 				type: string: syntax: "literal"
 			}
+			policy: {
+				description: """
+					Policy enforced on the TLS connections accepted by a source.
+
+					All options are validated when the source is built, and the option breaking the policy is
+					named in the resulting error.
+					"""
+				required: false
+				type: object: options: {
+					cipher_suites: {
+						description: """
+							The cipher suites accepted.
+
+							TLS 1.3 cipher suites use their IANA names, such as `TLS_AES_256_GCM_SHA384`, and earlier
+							ones their OpenSSL names, such as `ECDHE-RSA-AES256-GCM-SHA384`. Each entry must name a
+							single cipher suite: OpenSSL cipher strings such as `HIGH` are rejected.
+
+							Cipher suites not listed are never accepted, whatever the protocol version. If only TLS 1.3
+							cipher suites are listed, connections are restricted to TLS 1.3, and if none are listed,
+							connections are restricted to TLS 1.2 and earlier.
+							"""
+						required: false
+						type: array: items: type: string: syntax: "literal"
+					}
+					curves: {
+						description: "The elliptic curves accepted for key exchange, such as `P-256` or `X25519`."
+						required:    false
+						type: array: items: type: string: syntax: "literal"
+					}
+					fips: {
+						description: """
+							Restricts connections to FIPS 140 approved protocol versions, cipher suites, and curves.
+
+							Unless set explicitly, `min_version`, `cipher_suites`, and `curves` default to the approved
+							values, and setting them to anything else is an error.
+							"""
+						required: false
+						type: bool: default: false
+					}
+					min_version: {
+						description: "The minimum TLS version accepted."
+						required:    false
+						type: string: enum: {
+							"tls1.0": "TLS 1.0."
+							"tls1.1": "TLS 1.1."
+							"tls1.2": "TLS 1.2."
+							"tls1.3": "TLS 1.3."
+						}
+					}
+					require_client_certificate: {
+						description: "Requires clients to present a certificate valid for the configured CA."
+						required:    false
+						type: bool: default: false
+					}
+				}
+			}
+			sni_certificates: {
+				description: """
+					Additional certificates, each presented to the clients requesting one of its server names
+					through SNI.
+
+					Clients not using SNI, or requesting another server name, are presented the certificate set
+					by `crt_file`.
+					"""
+				required: false
+				type: array: {
+					default: []
+					items: type: object: options: {
+						crt_file: {
+							description: "Absolute path to the certificate file, in the same formats as `crt_file`."
+							required:    true
+							type: string: syntax: "literal"
+						}
+						key_file: {
+							description: """
+								Absolute path to the private key file of the certificate, in the same formats as
+								`key_file`.
+								"""
+							required: false
+							type: string: syntax: "literal"
+						}
+						key_pass: {
+							description: "Passphrase used to unlock the encrypted key file."
+							required:    false
+							type: string: syntax: "literal"
+						}
+						server_names: {
+							description: """
+								The server names this certificate is presented for.
+
+								A name starting with `*.` matches any single label in its place, i.e. `*.example.com`
+								matches `logs.example.com` but not `example.com`. Names are matched case-insensitively, in
+								the order the certificates are listed.
+								"""
+							required: true
+							type: array: items: type: string: syntax: "literal"
+						}
+					}
+				}
+			}
 			verify_certificate: {
 				description: """
 					Enables certificate verification.
@@ -94,6 +94,19 @@ components: sources: demo_logs: {
 			required:      false
 			type: bool: default: false
 		}
+		generation_timestamp_key: {
+			common: false
+			description: """
+				Event field for the monotonic timestamp, in nanoseconds, at which each log event was
+				generated. Paired with the `latency_key` option of the `blackhole` sink, it measures
+				the end-to-end latency of events through the topology.
+				"""
+			required: false
+			type: string: {
+				default: null
+				examples: ["generated_at"]
+			}
+		}
 	}
 
 	output: {
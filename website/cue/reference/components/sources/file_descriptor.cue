@@ -13,7 +13,7 @@ components: sources: file_descriptor: {
 	}
 
 	features: {
-		acknowledgements: false
+		acknowledgements: true
 		multiline: enabled: false
 		codecs: {
 			enabled:         true
@@ -40,6 +40,7 @@ components: sources: file_descriptor: {
 	}
 
 	configuration: {
+		acknowledgements: configuration._source_acknowledgements
 		fd: {
 			description: "The file descriptor to read events from."
 			required:    true
@@ -211,6 +211,79 @@ components: sources: internal_metrics: {
 			tags:              _internal_metrics_tags
 		}
 
+		// Process metrics, only emitted when `process_metrics` is set
+		process_cpu_seconds_total: {
+			description:       "The CPU time spent by the Vector process, in seconds."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags & {
+				mode: {
+					description: "Whether the time was spent in `user` or `system` mode."
+					required:    true
+				}
+			}
+		}
+		process_open_fds: {
+			description:       "The number of file descriptors opened by the Vector process. Only available on Linux."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags
+		}
+		process_resident_memory_bytes: {
+			description:       "The resident memory size of the Vector process, in bytes."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags
+		}
+		process_virtual_memory_bytes: {
+			description:       "The virtual memory size of the Vector process, in bytes."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags
+		}
+		tokio_global_queue_depth: {
+			description:       "The number of tasks in the global queue of the Tokio runtime. Only available when built with `--cfg tokio_unstable`."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags
+		}
+		tokio_worker_local_queue_depth: {
+			description:       "The number of tasks in the local queue of a Tokio worker. Only available when built with `--cfg tokio_unstable`."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags & {
+				worker: {
+					description: "The index of the Tokio worker."
+					required:    true
+				}
+			}
+		}
+		tokio_worker_steals_total: {
+			description:       "The number of tasks a Tokio worker stole from other workers. Only available when built with `--cfg tokio_unstable`."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags & {
+				worker: {
+					description: "The index of the Tokio worker."
+					required:    true
+				}
+			}
+		}
+		tokio_workers: {
+			description:       "The number of workers of the Tokio runtime. Only available when built with `--cfg tokio_unstable`."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags
+		}
+
+		// Only emitted when `adaptive_interval` is set
+		internal_metrics_scrape_interval_seconds: {
+			description:       "The scrape interval in effect, in seconds, after adjusting it to how long capturing metrics takes."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _internal_metrics_tags
+		}
+
 		// Metrics emitted by one or more components
 		// Reusable metric definitions
 		adaptive_concurrency_averaged_rtt: {
@@ -420,6 +493,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _internal_metrics_tags
 		}
+		end_to_end_latency_seconds: {
+			description:       "The time elapsed between the generation of an event and its arrival at the sink, measured with the `latency_key` option of the `blackhole` sink."
+			type:              "histogram"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		events_discarded_total: {
 			description:       "The total number of events discarded by this component."
 			type:              "counter"
@@ -972,6 +1051,36 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		secret_cache_hits_total: {
+			description:       "The total number of secrets reused from a previous configuration load instead of being retrieved again."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _secret_backend_tags
+		}
+		secret_retrieval_duration_seconds: {
+			description:       "The time taken to retrieve secrets from a secret backend."
+			type:              "histogram"
+			default_namespace: "vector"
+			tags:              _secret_backend_tags
+		}
+		secret_retrieval_errors_total: {
+			description:       "The total number of failed retrievals of secrets from a secret backend."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _secret_backend_tags
+		}
+		secret_retrievals_total: {
+			description:       "The total number of retrievals of secrets from a secret backend."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _secret_backend_tags
+		}
+		secrets_retrieved_total: {
+			description:       "The total number of secrets retrieved from a secret backend."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _secret_backend_tags
+		}
 		send_errors_total: {
 			description:       "The total number of errors sending messages."
 			type:              "counter"
@@ -1008,6 +1117,21 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		sqs_poison_messages_total: {
+			description:       "The total number of poison messages removed from SQS queues."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				action: {
+					description: "The action taken for the poison messages."
+					required:    true
+					enum: {
+						route:  "Sent to the `errors` output, then deleted."
+						delete: "Deleted."
+					}
+				}
+			}
+		}
 		sqs_message_processing_failed_total: {
 			description:       "The total number of failures to process SQS messages."
 			type:              "counter"
@@ -1195,6 +1319,16 @@ components: sources: internal_metrics: {
 		}
 
 		// Helpful tag groupings
+		_secret_backend_tags: _internal_metrics_tags & {
+			backend: {
+				description: "The ID of the secret backend."
+				required:    true
+			}
+			backend_type: {
+				description: "The type of the secret backend."
+				required:    true
+			}
+		}
 		_component_tags: _internal_metrics_tags & {
 			component_kind: _component_kind
 			component_id:   _component_id
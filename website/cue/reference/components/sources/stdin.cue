@@ -13,7 +13,7 @@ components: sources: stdin: {
 	}
 
 	features: {
-		acknowledgements: false
+		acknowledgements: true
 		multiline: enabled: false
 		codecs: {
 			enabled:         true
@@ -40,6 +40,7 @@ components: sources: stdin: {
 	}
 
 	configuration: {
+		acknowledgements: configuration._source_acknowledgements
 		host_key: {
 			category:    "Context"
 			common:      false
@@ -0,0 +1,55 @@
+package metadata
+
+components: transforms: adaptive_sample: {
+	title: "Adaptive Sample"
+
+	description: """
+		Samples events so that each key forwards roughly a target number of events per time window.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		filter: {}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: base.components.transforms.adaptive_sample.configuration
+
+	input: {
+		logs:    true
+		metrics: null
+		traces:  true
+	}
+
+	telemetry: metrics: {
+		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
+	}
+
+	how_it_works: {
+		sampling: {
+			title: "Sampling"
+			body:  """
+				The rate of events of each key, as rendered by `key_field`, is tracked over a sliding window
+				of `window_secs`. As long as a key stays under `target_rate` events per window, all of its
+				events are forwarded. Once it goes over, its events are forwarded with a probability of
+				`target_rate` divided by its rate, so that roughly `target_rate` events per window are
+				forwarded, and the rest are discarded and tracked by the `events_discarded_total` metric.
+
+				Each forwarded event gets the effective sample rate it was forwarded with in the
+				`sample_rate_field` field, expressed as `N` for a probability of `1/N`, so that counts can be
+				reweighted downstream.
+				"""
+		}
+	}
+}
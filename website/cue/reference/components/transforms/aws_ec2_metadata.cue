@@ -63,7 +63,7 @@ components: transforms: aws_ec2_metadata: {
 			set:          true
 			summary:      true
 		}
-		traces: false
+		traces: true
 	}
 
 	output: logs: log: {
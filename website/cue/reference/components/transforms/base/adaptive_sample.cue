@@ -0,0 +1,48 @@
+package metadata
+
+base: components: transforms: adaptive_sample: configuration: {
+	exclude: {
+		description: "A logical condition used to exclude events from sampling."
+		required:    false
+		type: condition: {}
+	}
+	key_field: {
+		description: """
+			The key used to group events, whose rates are tracked separately.
+
+			If left unspecified, or if the template fails to render, events are tracked under a single
+			shared key.
+			"""
+		required: false
+		type: string: {
+			examples: ["{{ service }}", "{{ hostname }}"]
+			syntax: "template"
+		}
+	}
+	sample_rate_field: {
+		description: """
+			The name of the field the effective sample rate of forwarded events is written to.
+
+			The rate is expressed as `N`, meaning the event was forwarded with a probability of `1/N`,
+			so downstream components can reweight counts by multiplying by it.
+			"""
+		required: false
+		type: string: {
+			default: "sample_rate"
+			syntax:  "literal"
+		}
+	}
+	target_rate: {
+		description: "The number of events of each key forwarded per `window_secs` before sampling starts."
+		required:    true
+		type: uint: {}
+	}
+	window_secs: {
+		description: "The length of the sliding window the rate of each key is tracked over, in seconds."
+		required:    false
+		type: float: {
+			default: 60.0
+			unit:    "seconds"
+		}
+	}
+}
@@ -1,6 +1,17 @@
 package metadata
 
 base: components: transforms: aws_ec2_metadata: configuration: {
+	cache: {
+		description: """
+			Whether to cache the last fetched metadata in the data directory.
+
+			The cached metadata is added to events from startup until it is fetched again, so that
+			events are enriched consistently after a restart even when the metadata endpoint is briefly
+			unavailable. The cached metadata also satisfies `required`.
+			"""
+		required: false
+		type: bool: default: false
+	}
 	endpoint: {
 		description: "Overrides the default EC2 metadata endpoint."
 		required:    false
@@ -9,6 +20,24 @@ base: components: transforms: aws_ec2_metadata: configuration: {
 			syntax:  "literal"
 		}
 	}
+	fallback_fields: {
+		description: """
+			Static values of metadata fields, added to events when no value has been fetched for them.
+
+			This is the case before the first successful refresh, when `required` is `false`, and for
+			fields that aren't available on the instance. A `role-name` value is added as a single role
+			name. Only fields listed in `fields` can be given a fallback value.
+			"""
+		required: false
+		type: object: {
+			default: {}
+			options: "*": {
+				description: "A static metadata value."
+				required:    true
+				type: string: syntax: "literal"
+			}
+		}
+	}
 	fields: {
 		description: "A list of metadata fields to include in each transformed event."
 		required:    false
@@ -20,6 +49,89 @@ base: components: transforms: aws_ec2_metadata: configuration: {
 			}
 		}
 	}
+	metric_placement: {
+		description: "Where metadata fields are added to metrics."
+		required:    false
+		type: string: {
+			default: "tag"
+			enum: {
+				metadata: """
+					Add the field to the event metadata of the metric, at the same path as on logs.
+
+					Unlike tags, metadata doesn't create a new series in downstream time series databases, while
+					it remains available to transforms and sinks, for example as `%aws.ec2.instance-id` in VRL.
+					"""
+				tag: "Add the field as a tag, named as configured by `metric_tags`."
+			}
+		}
+	}
+	metric_placement_fields: {
+		description: """
+			Overrides `metric_placement` for specific metadata fields.
+
+			This allows, for example, keeping `region` as a tag while adding the high cardinality
+			`instance-id` to metric metadata only.
+			"""
+		required: false
+		type: object: {
+			default: {}
+			options: "*": {
+				description: """
+					Overrides `metric_placement` for specific metadata fields.
+
+					This allows, for example, keeping `region` as a tag while adding the high cardinality
+					`instance-id` to metric metadata only.
+					"""
+				required: true
+				type: string: enum: {
+					metadata: """
+						Add the field to the event metadata of the metric, at the same path as on logs.
+
+						Unlike tags, metadata doesn't create a new series in downstream time series databases, while
+						it remains available to transforms and sinks, for example as `%aws.ec2.instance-id` in VRL.
+						"""
+					tag: "Add the field as a tag, named as configured by `metric_tags`."
+				}
+			}
+		}
+	}
+	metric_tags: {
+		description: """
+			Options for naming the tags added to metrics.
+
+			Logs and traces are unaffected.
+			"""
+		required: false
+		type: object: {
+			default: {
+				role_names:     "indexed"
+				sanitize_names: false
+			}
+			options: {
+				role_names: {
+					description: "How the IAM role names of the instance are tagged on metrics."
+					required:    false
+					type: string: {
+						default: "indexed"
+						enum: {
+							indexed: "Each role name is a separate tag, suffixed with its index, such as `role-name[0]`."
+							joined:  "The role names are joined with `,` into a single `role-name` tag."
+						}
+					}
+				}
+				sanitize_names: {
+					description: """
+						Whether to make tag names valid Prometheus label names.
+
+						Characters other than ASCII letters, digits, and `_` are replaced with `_`, so that, for
+						example, `role-name` is tagged as `role_name`, and indexed role names as `role_name_0`.
+						"""
+					required: false
+					type: bool: default: false
+				}
+			}
+		}
+	}
 	namespace: {
 		description: "Sets a prefix for all event fields added by the transform."
 		required:    false
@@ -28,6 +140,48 @@ base: components: transforms: aws_ec2_metadata: configuration: {
 			syntax: "literal"
 		}
 	}
+	on_refresh_error: {
+		description: "What to do with previously fetched metadata when refreshing it fails."
+		required:    false
+		type: string: {
+			default: "retain"
+			enum: {
+				remove: """
+					Stop adding the previously fetched values to events until a refresh succeeds again (fail
+					closed).
+					"""
+				retain: "Keep adding the last successfully fetched values to events (fail open)."
+			}
+		}
+	}
+	on_refresh_error_fields: {
+		description: """
+			Overrides `on_refresh_error` for specific metadata fields.
+
+			This allows, for example, dropping a stale `role-name` while retaining the last known
+			values of all other fields.
+			"""
+		required: false
+		type: object: {
+			default: {}
+			options: "*": {
+				description: """
+					Overrides `on_refresh_error` for specific metadata fields.
+
+					This allows, for example, dropping a stale `role-name` while retaining the last known
+					values of all other fields.
+					"""
+				required: true
+				type: string: enum: {
+					remove: """
+						Stop adding the previously fetched values to events until a refresh succeeds again (fail
+						closed).
+						"""
+					retain: "Keep adding the last successfully fetched values to events (fail open)."
+				}
+			}
+		}
+	}
 	proxy: {
 		description: """
 			Proxy configuration.
@@ -99,6 +253,28 @@ base: components: transforms: aws_ec2_metadata: configuration: {
 			unit:    "seconds"
 		}
 	}
+	refresh_mode: {
+		description: """
+			When the metadata is refreshed.
+
+			In either mode, the metadata is also refreshed right away when the configuration is reloaded
+			from disk, such as on `SIGHUP`, and when events are enriched while no metadata is available.
+			"""
+		required: false
+		type: string: {
+			default: "interval"
+			enum: {
+				interval: "Refresh the metadata every `refresh_interval_secs`."
+				ttl: """
+					Refresh the metadata only once it is older than `refresh_interval_secs`, when the next
+					event is enriched.
+
+					No requests are made while no events are flowing, which allows for long refresh intervals
+					for metadata that rarely changes.
+					"""
+			}
+		}
+	}
 	refresh_timeout_secs: {
 		description: "The timeout for querying the EC2 metadata endpoint, in seconds."
 		required:    false
@@ -108,8 +284,42 @@ base: components: transforms: aws_ec2_metadata: configuration: {
 		}
 	}
 	required: {
-		description: "Requires the transform to be able to successfully query the EC2 metadata before Vector can start."
-		required:    false
+		description: """
+			Requires the transform to be able to successfully query the EC2 metadata before Vector can start.
+
+			With `cache` enabled, Vector also starts when the metadata was cached by a previous run.
+			"""
+		required: false
 		type: bool: default: true
 	}
+	strategy: {
+		description: "The strategy used to query metadata."
+		required:    false
+		type: string: {
+			default: "imdsv2"
+			enum: {
+				ecs: """
+					Query the ECS task metadata endpoint, as found in the `ECS_CONTAINER_METADATA_URI_V4`
+					environment variable.
+
+					Only the `account-id`, `availability-zone`, `local-hostname`, `local-ipv4`, and `region`
+					fields are available from this endpoint. The `endpoint` option is ignored.
+					"""
+				eks: """
+					Query the EC2 instance metadata service of the node an EKS pod runs on, as
+					`imdsv1_fallback` does, since the IMDSv2 token response doesn't reach pods when the hop limit
+					of the node is 1.
+
+					On EKS Fargate, where there is no instance metadata service, only the `region` field is
+					available, from the `AWS_REGION` or `AWS_DEFAULT_REGION` environment variable, as set for
+					IAM roles for service accounts.
+					"""
+				imdsv1_fallback: """
+					Query the EC2 instance metadata service with a session token (IMDSv2), falling back to
+					requests without a token (IMDSv1) when a token can't be fetched.
+					"""
+				imdsv2: "Query the EC2 instance metadata service with a session token (IMDSv2)."
+			}
+		}
+	}
 }
@@ -12,8 +12,16 @@ base: components: transforms: pipeline: configuration: {
 		type: string: syntax: "literal"
 	}
 	transforms: {
-		description: "A list of sequential transforms that will process any event that is passed to the pipeline."
-		required:    false
-		type:        "blank"
+		description: """
+			A list of sequential transforms that will process any event that is passed to the pipeline.
+
+			Events a transform sends to its `dropped` output, such as `remap` with `reroute_dropped`
+			enabled, are not passed to the following transforms but are sent to the `dropped` output of
+			the pipeline instead. Events a transform discards without sending them to any output, such
+			as those failing `remap` without `reroute_dropped`, or those rejected by a `filter`
+			transform, are not sent to the `dropped` output.
+			"""
+		required: false
+		type:     "blank"
 	}
 }
@@ -1,6 +1,18 @@
 package metadata
 
 base: components: transforms: pipelines: configuration: {
+	exclusive: {
+		description: """
+			Whether or not the pipelines of each event type are exclusive.
+
+			When enabled, the pipelines are evaluated in order and an event is only processed by the
+			first pipeline whose filter it matches, like in a routing table. Events matching none of the
+			pipelines are forwarded as is. By default, an event is processed by every pipeline whose
+			filter it matches.
+			"""
+		required: false
+		type: bool: default: false
+	}
 	logs: {
 		description: "Configuration for the logs-specific side of the pipeline."
 		required:    false
@@ -18,9 +30,17 @@ base: components: transforms: pipelines: configuration: {
 					type: string: syntax: "literal"
 				}
 				transforms: {
-					description: "A list of sequential transforms that will process any event that is passed to the pipeline."
-					required:    false
-					type:        "blank"
+					description: """
+						A list of sequential transforms that will process any event that is passed to the pipeline.
+
+						Events a transform sends to its `dropped` output, such as `remap` with `reroute_dropped`
+						enabled, are not passed to the following transforms but are sent to the `dropped` output of
+						the pipeline instead. Events a transform discards without sending them to any output, such
+						as those failing `remap` without `reroute_dropped`, or those rejected by a `filter`
+						transform, are not sent to the `dropped` output.
+						"""
+					required: false
+					type:     "blank"
 				}
 			}
 		}
@@ -42,9 +62,17 @@ base: components: transforms: pipelines: configuration: {
 					type: string: syntax: "literal"
 				}
 				transforms: {
-					description: "A list of sequential transforms that will process any event that is passed to the pipeline."
-					required:    false
-					type:        "blank"
+					description: """
+						A list of sequential transforms that will process any event that is passed to the pipeline.
+
+						Events a transform sends to its `dropped` output, such as `remap` with `reroute_dropped`
+						enabled, are not passed to the following transforms but are sent to the `dropped` output of
+						the pipeline instead. Events a transform discards without sending them to any output, such
+						as those failing `remap` without `reroute_dropped`, or those rejected by a `filter`
+						transform, are not sent to the `dropped` output.
+						"""
+					required: false
+					type:     "blank"
 				}
 			}
 		}
@@ -66,9 +94,17 @@ base: components: transforms: pipelines: configuration: {
 					type: string: syntax: "literal"
 				}
 				transforms: {
-					description: "A list of sequential transforms that will process any event that is passed to the pipeline."
-					required:    false
-					type:        "blank"
+					description: """
+						A list of sequential transforms that will process any event that is passed to the pipeline.
+
+						Events a transform sends to its `dropped` output, such as `remap` with `reroute_dropped`
+						enabled, are not passed to the following transforms but are sent to the `dropped` output of
+						the pipeline instead. Events a transform discards without sending them to any output, such
+						as those failing `remap` without `reroute_dropped`, or those rejected by a `filter`
+						transform, are not sent to the `dropped` output.
+						"""
+					required: false
+					type:     "blank"
 				}
 			}
 		}
@@ -78,6 +78,17 @@ components: transforms: pipelines: {
 
 		logs:    _pipeline_group
 		metrics: _pipeline_group
+
+		exclusive: {
+			description: """
+				Whether or not the pipelines of each event type are exclusive. When enabled, the pipelines are
+				evaluated in order and an event is only processed by the first pipeline whose filter it matches,
+				like in a routing table. Events matching none of the pipelines are forwarded as is.
+				"""
+			required: false
+			common:   false
+			type: bool: default: false
+		}
 	}
 
 	input: {
@@ -249,6 +249,20 @@ configuration: {
 								examples: ["de", "en", "es", "fr", "ja", "pt-BR", "ru", "zh-CN"]
 							}
 						}
+						reload_interval_secs: {
+							description: """
+								The interval, in seconds, between checks for changes to the database file. When the
+								file has changed, the database is reloaded without reloading the configuration. If the
+								new file can't be read, lookups keep using the previous database. Set to `0` to only
+								reload the database when the configuration is reloaded.
+								"""
+							required: false
+							common:   false
+							type: uint: {
+								default: 60
+								unit:    "seconds"
+							}
+						}
 					}
 				}
 			}
@@ -579,6 +593,18 @@ configuration: {
 				```
 
 				In that example Vector will retrieve the `dd_api_key` from `backen_1` and `splunk_token` from `backend_2`.
+
+				Like environment variables, secrets can be used in any option, including the paths of files read by
+				components such as enrichment table files, TLS certificates and keys, and VRL programs loaded with
+				the `file` option of the `remap` transform. Placeholders are replaced before any component is built,
+				including by `vector validate`, so environment-specific paths and credentials can come from the same
+				backend.
+
+				Placeholders outside of quoted strings, such as `port = SECRET[backend_1.port]` or a component name, are
+				replaced in the raw configuration, and the secret must be valid in the configuration format there.
+				Placeholders within quoted strings are replaced once the configuration is parsed, so their secrets can
+				hold quotes or newlines without escaping them. The values of secrets are redacted from the errors
+				reported when loading the configuration.
 				"""
 
 			sub_sections: [